@@ -17,6 +17,18 @@ async fn main() {
         .set_color(BLUE)
         .set_lines_width(5.0, false)
         .set_lines_color(Some(MAGENTA));
+    scene
+        .add_rounded_rectangle(150.0, 80.0, 20.0)
+        .translate(Vec2::new(-200.0, 0.0))
+        .set_color(ORANGE);
+    scene
+        .add_annulus(30.0, 50.0)
+        .translate(Vec2::new(0.0, 200.0))
+        .set_color(YELLOW);
+    scene
+        .add_arc(50.0, -0.5, 3.6, 12.0)
+        .translate(Vec2::new(0.0, -200.0))
+        .set_color(RED);
 
     let rot_rect = 0.014;
     let rot_circ = -0.014;
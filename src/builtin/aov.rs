@@ -9,6 +9,9 @@
 //! - [`AovKind::Normals`] — world- or camera-space surface normals, into
 //!   `Rgba32Float` (encoded from `[-1, 1]` to `[0, 1]`).
 //! - [`AovKind::Segmentation`] — the per-object integer id, into `R32Uint`.
+//! - [`AovKind::Velocity`] — the screen-space motion since the previous
+//!   velocity render, from per-object previous-frame transforms, into
+//!   `Rg32Float`.
 //!
 //! All targets are single-sampled (`sample_count = 1`) so the GPU→CPU read-back
 //! is exact, with no MSAA resolve in the way.
@@ -17,9 +20,10 @@ use crate::camera::Camera3d;
 use crate::context::Context;
 use crate::resource::vertex_index::VERTEX_INDEX_FORMAT;
 use crate::resource::DynamicUniformBuffer;
-use crate::scene::SceneNode3d;
+use crate::scene::{SceneNode3d, SceneNodeId};
 use bytemuck::{Pod, Zeroable};
-use glamx::Mat3;
+use glamx::{Mat3, Mat4};
+use std::collections::HashMap;
 
 /// The texture format of the linear-depth auxiliary output.
 pub const DEPTH_AOV_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Float;
@@ -27,6 +31,8 @@ pub const DEPTH_AOV_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Float;
 pub const NORMALS_AOV_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba32Float;
 /// The texture format of the segmentation (object-id) auxiliary output.
 pub const SEGMENTATION_AOV_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Uint;
+/// The texture format of the screen-space velocity auxiliary output.
+pub const VELOCITY_AOV_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rg32Float;
 
 /// Which auxiliary output a render pass produces.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -39,6 +45,12 @@ pub enum AovKind {
     CameraNormals,
     /// Per-object integer id into [`SEGMENTATION_AOV_FORMAT`].
     Segmentation,
+    /// Screen-space motion into [`VELOCITY_AOV_FORMAT`]: how far each surface
+    /// point moved since the previous velocity render, in texture coordinates
+    /// (a fraction of the image size, top-left origin), accounting for both the
+    /// camera and per-object motion. Surfaces seen for the first time, and the
+    /// background, have no velocity.
+    Velocity,
 }
 
 impl AovKind {
@@ -48,6 +60,7 @@ impl AovKind {
             AovKind::Depth => DEPTH_AOV_FORMAT,
             AovKind::Normals | AovKind::CameraNormals => NORMALS_AOV_FORMAT,
             AovKind::Segmentation => SEGMENTATION_AOV_FORMAT,
+            AovKind::Velocity => VELOCITY_AOV_FORMAT,
         }
     }
 }
@@ -60,6 +73,8 @@ struct FrameUniforms {
     proj: [[f32; 4]; 4],
     /// `flags.x = 1.0` selects camera-space normals; otherwise world-space.
     flags: [f32; 4],
+    /// The view-projection of the previous velocity render.
+    prev_view_proj: [[f32; 4]; 4],
 }
 
 /// Object-level uniforms shared by all AOV passes.
@@ -70,18 +85,27 @@ struct ObjectUniforms {
     scale: [[f32; 4]; 3], // mat3x3 padded to mat3x4 for alignment
     /// `extra[0]` holds the segmentation id; the rest is padding.
     extra: [u32; 4],
+    /// The scaled world transform of the previous velocity render.
+    prev_model: [[f32; 4]; 4],
 }
 
-/// Renders the scene graph into auxiliary outputs (depth, normals, segmentation).
+/// Renders the scene graph into auxiliary outputs (depth, normals, segmentation,
+/// velocity).
 ///
-/// One `AovRenderer` owns the three pipelines and the shared uniform buffers;
+/// One `AovRenderer` owns the pipelines and the shared uniform buffers;
 /// it is created on first use and re-used across frames. It mirrors the
 /// dynamic-uniform batching used by the regular materials: per-object uniforms
 /// are accumulated into a single dynamic buffer addressed with dynamic offsets.
 pub struct AovRenderer {
-    pipeline_depth: wgpu::RenderPipeline,
-    pipeline_normals: wgpu::RenderPipeline,
-    pipeline_segmentation: wgpu::RenderPipeline,
+    pipeline_depth: AovPipelines,
+    pipeline_normals: AovPipelines,
+    pipeline_segmentation: AovPipelines,
+    pipeline_velocity: AovPipelines,
+
+    /// The view-projection and the scaled world transform of each object (by
+    /// the id of its node) at the previous velocity render.
+    prev_view_proj: Option<Mat4>,
+    prev_models: HashMap<SceneNodeId, Mat4>,
 
     frame_uniform_buffer: wgpu::Buffer,
     frame_bind_group: wgpu::BindGroup,
@@ -95,8 +119,24 @@ pub struct AovRenderer {
     visualize: Option<AovVisualize>,
 }
 
+/// The pipelines of one AOV pass, for counter-clockwise and clockwise front
+/// faces (see [`Object3d::set_front_face`](crate::scene::Object3d::set_front_face)).
+struct AovPipelines {
+    ccw: wgpu::RenderPipeline,
+    cw: wgpu::RenderPipeline,
+}
+
+impl AovPipelines {
+    fn get(&self, front_face: wgpu::FrontFace) -> &wgpu::RenderPipeline {
+        match front_face {
+            wgpu::FrontFace::Ccw => &self.ccw,
+            wgpu::FrontFace::Cw => &self.cw,
+        }
+    }
+}
+
 impl AovRenderer {
-    /// Creates the AOV renderer and its three pipelines.
+    /// Creates the AOV renderer and its pipelines.
     pub fn new() -> AovRenderer {
         let ctxt = Context::get();
 
@@ -163,8 +203,11 @@ impl AovRenderer {
         ];
 
         // All AOV passes share the same vertex stage and depth state; only the
-        // fragment entry point and color-target format differ.
-        let make_pipeline = |fs_entry: &str, format: wgpu::TextureFormat, label: &str| {
+        // fragment entry point, color-target format and front-face winding differ.
+        let make_pipeline = |fs_entry: &str,
+                             format: wgpu::TextureFormat,
+                             front_face: wgpu::FrontFace,
+                             label: &str| {
             ctxt.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
                 label: Some(label),
                 layout: Some(&pipeline_layout),
@@ -187,7 +230,7 @@ impl AovRenderer {
                 primitive: wgpu::PrimitiveState {
                     topology: wgpu::PrimitiveTopology::TriangleList,
                     strip_index_format: None,
-                    front_face: wgpu::FrontFace::Ccw,
+                    front_face,
                     // Cull back faces: AOVs describe the closest visible surface.
                     cull_mode: Some(wgpu::Face::Back),
                     polygon_mode: wgpu::PolygonMode::Fill,
@@ -211,14 +254,21 @@ impl AovRenderer {
             })
         };
 
-        let pipeline_depth = make_pipeline("fs_depth", DEPTH_AOV_FORMAT, "aov_depth_pipeline");
+        let make_pipelines =
+            |fs_entry: &str, format: wgpu::TextureFormat, label: &str| AovPipelines {
+                ccw: make_pipeline(fs_entry, format, wgpu::FrontFace::Ccw, label),
+                cw: make_pipeline(fs_entry, format, wgpu::FrontFace::Cw, label),
+            };
+        let pipeline_depth = make_pipelines("fs_depth", DEPTH_AOV_FORMAT, "aov_depth_pipeline");
         let pipeline_normals =
-            make_pipeline("fs_normals", NORMALS_AOV_FORMAT, "aov_normals_pipeline");
-        let pipeline_segmentation = make_pipeline(
+            make_pipelines("fs_normals", NORMALS_AOV_FORMAT, "aov_normals_pipeline");
+        let pipeline_segmentation = make_pipelines(
             "fs_segmentation",
             SEGMENTATION_AOV_FORMAT,
             "aov_segmentation_pipeline",
         );
+        let pipeline_velocity =
+            make_pipelines("fs_velocity", VELOCITY_AOV_FORMAT, "aov_velocity_pipeline");
 
         let frame_uniform_buffer = ctxt.create_buffer(&wgpu::BufferDescriptor {
             label: Some("aov_frame_uniform_buffer"),
@@ -245,6 +295,9 @@ impl AovRenderer {
             pipeline_depth,
             pipeline_normals,
             pipeline_segmentation,
+            pipeline_velocity,
+            prev_view_proj: None,
+            prev_models: HashMap::new(),
             frame_uniform_buffer,
             frame_bind_group,
             object_bind_group_layout,
@@ -258,7 +311,8 @@ impl AovRenderer {
     /// display-ready image into `target_view` (of format `target_format`):
     /// depth as fixed-range grayscale over `[0, depth_range]` world units
     /// (near = bright, background = black), normals as RGB, segmentation ids
-    /// as distinct golden-ratio colors. Entirely on the GPU — no read-back.
+    /// as distinct golden-ratio colors, velocities as red/green offsets from
+    /// mid-gray. Entirely on the GPU — no read-back.
     pub fn visualize_into(
         &mut self,
         kind: AovKind,
@@ -304,6 +358,9 @@ impl AovRenderer {
     /// `color_view` must use the format reported by [`AovKind::format`] and a
     /// sample count of 1; `depth_view` must use [`Context::depth_format`]. Both
     /// are cleared at the start of the pass.
+    ///
+    /// Rendering [`AovKind::Velocity`] records the current camera and object
+    /// transforms as the previous ones of the next velocity render.
     pub fn render(
         &mut self,
         kind: AovKind,
@@ -319,10 +376,12 @@ impl AovRenderer {
         } else {
             [0.0, 0.0, 0.0, 0.0]
         };
+        let view_proj = proj * view.to_mat4();
         let frame_uniforms = FrameUniforms {
             view: view.to_mat4().to_cols_array_2d(),
             proj: proj.to_cols_array_2d(),
             flags,
+            prev_view_proj: self.prev_view_proj.unwrap_or(view_proj).to_cols_array_2d(),
         };
         let ctxt = Context::get();
         ctxt.write_buffer(
@@ -334,7 +393,18 @@ impl AovRenderer {
         // Collect per-object uniforms and the matching draw list.
         self.object_uniform_buffer.clear();
         let mut draws: Vec<DrawItem> = Vec::new();
-        Self::gather(scene, &mut self.object_uniform_buffer, &mut draws);
+        let mut models = HashMap::new();
+        Self::gather(
+            scene,
+            &self.prev_models,
+            &mut models,
+            &mut self.object_uniform_buffer,
+            &mut draws,
+        );
+        if kind == AovKind::Velocity {
+            self.prev_view_proj = Some(view_proj);
+            self.prev_models = models;
+        }
 
         if self.object_uniform_buffer.flush() {
             self.object_bind_group = Self::make_object_bind_group(
@@ -356,10 +426,11 @@ impl AovRenderer {
             },
         };
 
-        let pipeline = match kind {
+        let pipelines = match kind {
             AovKind::Depth => &self.pipeline_depth,
             AovKind::Normals | AovKind::CameraNormals => &self.pipeline_normals,
             AovKind::Segmentation => &self.pipeline_segmentation,
+            AovKind::Velocity => &self.pipeline_velocity,
         };
 
         let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
@@ -386,10 +457,14 @@ impl AovRenderer {
             multiview_mask: None,
         });
 
-        pass.set_pipeline(pipeline);
         pass.set_bind_group(0, &self.frame_bind_group, &[]);
 
+        let mut current_front_face = None;
         for item in &draws {
+            if current_front_face != Some(item.front_face) {
+                pass.set_pipeline(pipelines.get(item.front_face));
+                current_front_face = Some(item.front_face);
+            }
             pass.set_bind_group(1, &self.object_bind_group, &[item.object_offset]);
             pass.set_vertex_buffer(0, item.coords.slice(..));
             pass.set_vertex_buffer(1, item.normals.slice(..));
@@ -400,60 +475,75 @@ impl AovRenderer {
 
     /// Walks the scene graph, pushing per-object uniforms and collecting the
     /// GPU buffers needed to draw each visible, surface-rendered object.
+    ///
+    /// The scaled world transform of each object is recorded into `models`, and
+    /// looked up in `prev_models` for its previous one.
     fn gather(
         scene: &mut SceneNode3d,
+        prev_models: &HashMap<SceneNodeId, Mat4>,
+        models: &mut HashMap<SceneNodeId, Mat4>,
         objects: &mut DynamicUniformBuffer<ObjectUniforms>,
         draws: &mut Vec<DrawItem>,
     ) {
-        scene.apply_to_objects_with_world_mut_recursive(&mut |transform, scale, obj| {
-            if !obj.data().surface_rendering_active() {
-                return;
-            }
-
-            let scale_mat = Mat3::from_diagonal(scale);
-            let scale_cols = scale_mat.to_cols_array_2d();
-            let scale_padded = [
-                [scale_cols[0][0], scale_cols[0][1], scale_cols[0][2], 0.0],
-                [scale_cols[1][0], scale_cols[1][1], scale_cols[1][2], 0.0],
-                [scale_cols[2][0], scale_cols[2][1], scale_cols[2][2], 0.0],
-            ];
-
-            let uniforms = ObjectUniforms {
-                transform: transform.to_mat4().to_cols_array_2d(),
-                scale: scale_padded,
-                extra: [obj.segmentation_id(), 0, 0, 0],
-            };
-            let object_offset = objects.push(&uniforms);
-
-            // Ensure mesh buffers are resident, then snapshot the buffers.
-            let mesh = obj.mesh();
-            let mesh = mesh.borrow();
-            mesh.coords().write().unwrap().load_to_gpu();
-            mesh.normals().write().unwrap().load_to_gpu();
-            mesh.faces().write().unwrap().load_to_gpu();
-
-            let num_indices = mesh.num_indices();
-            let coords = match mesh.coords().read().unwrap().buffer() {
-                Some(b) => b.clone(),
-                None => return,
-            };
-            let normals = match mesh.normals().read().unwrap().buffer() {
-                Some(b) => b.clone(),
-                None => return,
-            };
-            let faces = match mesh.faces().read().unwrap().buffer() {
-                Some(b) => b.clone(),
-                None => return,
-            };
-
-            draws.push(DrawItem {
-                object_offset,
-                coords,
-                normals,
-                faces,
-                num_indices,
-            });
-        });
+        scene.apply_to_identified_objects_with_world_mut_recursive(
+            &mut |id, transform, scale, obj| {
+                if !obj.data().surface_rendering_active() {
+                    return;
+                }
+
+                let scale_mat = Mat3::from_diagonal(scale);
+                let scale_cols = scale_mat.to_cols_array_2d();
+                let scale_padded = [
+                    [scale_cols[0][0], scale_cols[0][1], scale_cols[0][2], 0.0],
+                    [scale_cols[1][0], scale_cols[1][1], scale_cols[1][2], 0.0],
+                    [scale_cols[2][0], scale_cols[2][1], scale_cols[2][2], 0.0],
+                ];
+
+                // Node ids are never reused, so a new object doesn't inherit the
+                // previous transform of a dropped one.
+                let model = transform.to_mat4() * Mat4::from_scale(scale);
+                let prev_model = prev_models.get(&id).copied().unwrap_or(model);
+                let _ = models.insert(id, model);
+
+                let uniforms = ObjectUniforms {
+                    transform: transform.to_mat4().to_cols_array_2d(),
+                    scale: scale_padded,
+                    extra: [obj.segmentation_id(), 0, 0, 0],
+                    prev_model: prev_model.to_cols_array_2d(),
+                };
+                let object_offset = objects.push(&uniforms);
+
+                // Ensure mesh buffers are resident, then snapshot the buffers.
+                let mesh = obj.mesh();
+                let mesh = mesh.borrow();
+                mesh.coords().write().unwrap().load_to_gpu();
+                mesh.normals().write().unwrap().load_to_gpu();
+                mesh.faces().write().unwrap().load_to_gpu();
+
+                let num_indices = mesh.num_indices();
+                let coords = match mesh.coords().read().unwrap().buffer() {
+                    Some(b) => b.clone(),
+                    None => return,
+                };
+                let normals = match mesh.normals().read().unwrap().buffer() {
+                    Some(b) => b.clone(),
+                    None => return,
+                };
+                let faces = match mesh.faces().read().unwrap().buffer() {
+                    Some(b) => b.clone(),
+                    None => return,
+                };
+
+                draws.push(DrawItem {
+                    object_offset,
+                    front_face: obj.data().effective_front_face(),
+                    coords,
+                    normals,
+                    faces,
+                    num_indices,
+                });
+            },
+        );
     }
 }
 
@@ -466,6 +556,7 @@ impl Default for AovRenderer {
 /// A single queued draw for an AOV pass.
 struct DrawItem {
     object_offset: u32,
+    front_face: wgpu::FrontFace,
     coords: wgpu::Buffer,
     normals: wgpu::Buffer,
     faces: wgpu::Buffer,
@@ -476,14 +567,14 @@ struct DrawItem {
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
 struct VisUniforms {
-    /// `x`: float mode (0 = depth, 1 = normals); `y`: depth range;
+    /// `x`: float mode (0 = depth, 1 = normals, 2 = velocity); `y`: depth range;
     /// `z`: 1.0 when the target format is sRGB.
     params: [f32; 4],
 }
 
 /// Fullscreen pass turning a raw AOV texture into a display-ready image.
 ///
-/// Two pipelines: one sampling the float AOV texture (depth/normals) and one
+/// Two pipelines: one sampling the float AOV texture (depth/normals/velocity) and one
 /// sampling the integer segmentation texture. Owned by [`AovRenderer`].
 struct AovVisualize {
     target_format: wgpu::TextureFormat,
@@ -617,6 +708,7 @@ impl AovVisualize {
         let ctxt = Context::get();
         let float_mode = match kind {
             AovKind::Depth => 0.0,
+            AovKind::Velocity => 2.0,
             _ => 1.0,
         };
         let is_srgb = if self.target_format.is_srgb() {
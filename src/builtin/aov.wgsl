@@ -1,6 +1,6 @@
 // Auxiliary-output (AOV) shaders for kiss3d's rasterizer.
 //
-// A single WGSL module with three fragment entry points, one per auxiliary
+// A single WGSL module with four fragment entry points, one per auxiliary
 // render output. They share the vertex stage and uniform layout so the host
 // can drive all three passes from the same scene-graph traversal.
 //
@@ -13,6 +13,8 @@ struct FrameUniforms {
     // x = 1.0 to emit camera-space normals, 0.0 for world-space normals.
     // Remaining components are reserved/padding.
     flags: vec4<f32>,
+    // View-projection of the previous velocity render.
+    prev_view_proj: mat4x4<f32>,
 }
 
 @group(0) @binding(0)
@@ -24,6 +26,8 @@ struct ObjectUniforms {
     scale: mat3x3<f32>,
     // x = segmentation id (bit-cast from u32). y/z/w reserved/padding.
     extra: vec4<u32>,
+    // Scaled world transform of the previous velocity render.
+    prev_model: mat4x4<f32>,
 }
 
 @group(1) @binding(0)
@@ -40,6 +44,9 @@ struct VertexOutput {
     @location(0) ws_normal: vec3<f32>,
     // Eye-space (camera-space) position; -z is the positive linear depth.
     @location(1) eye_pos: vec3<f32>,
+    // Clip-space positions this frame and at the previous velocity render.
+    @location(2) curr_clip: vec4<f32>,
+    @location(3) prev_clip: vec4<f32>,
 }
 
 @vertex
@@ -52,6 +59,8 @@ fn vs_main(vertex: VertexInput) -> VertexOutput {
 
     out.clip_position = frame.proj * eye_pos;
     out.eye_pos = eye_pos.xyz;
+    out.curr_clip = out.clip_position;
+    out.prev_clip = frame.prev_view_proj * object.prev_model * vec4<f32>(vertex.position, 1.0);
     // Transform the normal by the object's rotation (the transform's upper-left
     // 3x3). Uniform meshes use this directly; this matches NormalsMaterial which
     // outputs object-local normals, but here we want world space for AOVs.
@@ -94,3 +103,12 @@ fn fs_normals(in: VertexOutput) -> @location(0) vec4<f32> {
 fn fs_segmentation(in: VertexOutput) -> @location(0) u32 {
     return object.extra.x;
 }
+
+// Velocity: screen-space motion since the previous velocity render, in texture
+// coordinates (top-left origin, +y down).
+@fragment
+fn fs_velocity(in: VertexOutput) -> @location(0) vec2<f32> {
+    let curr = in.curr_clip.xy / in.curr_clip.w;
+    let prev = in.prev_clip.xy / in.prev_clip.w;
+    return (curr - prev) * vec2<f32>(0.5, -0.5);
+}
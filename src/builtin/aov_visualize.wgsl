@@ -2,12 +2,12 @@
 // as display-ready colors, with a fullscreen triangle.
 //
 // Two fragment entry points share the vertex stage: `fs_float` reads the float
-// AOV texture (depth or normals, selected by `params.x`), `fs_seg` reads the
+// AOV texture (depth, normals or velocity, selected by `params.x`), `fs_seg` reads the
 // integer segmentation texture. Each pipeline's bind group layout only covers
 // the bindings its entry point uses.
 //
 // params:
-//   x: float mode (0 = depth, 1 = normals)
+//   x: float mode (0 = depth, 1 = normals, 2 = velocity)
 //   y: depth range in world units (depth mode only)
 //   z: 1.0 when the target is an sRGB format. The visualization is computed in
 //      display space (matching the CPU `snap_*` images); linearizing it first
@@ -54,6 +54,11 @@ fn fs_float(in: VsOut) -> @location(0) vec4<f32> {
         }
         return to_target(vec3<f32>(v));
     }
+    if uni.params.x > 1.5 {
+        // Velocity: magnified red/green offsets from mid-gray.
+        let v = clamp(vec2<f32>(0.5) + texel.rg * 10.0, vec2<f32>(0.0), vec2<f32>(1.0));
+        return to_target(vec3<f32>(v, 0.5));
+    }
     // Normals: already encoded to [0, 1].
     return to_target(texel.rgb);
 }
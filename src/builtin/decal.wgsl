@@ -0,0 +1,80 @@
+// Screen-space projected decals.
+//
+// Each decal is drawn as its oriented unit box (back faces only, so the pass also
+// works with the camera inside the box). Every covered pixel reads the opaque
+// scene depth, reconstructs the world position there, and moves it into the
+// decal's local space: points outside the [-0.5, 0.5]^3 box are discarded, the
+// rest are textured with the local XZ coordinates, i.e. the texture is projected
+// along the decal's local -Y axis. Surfaces nearly parallel to that axis fade out
+// to hide the stretched texels.
+
+struct FrameUniforms {
+    view_proj: mat4x4<f32>,
+    inv_view_proj: mat4x4<f32>,
+    // (width, height, unused, unused) of the scene attachment.
+    viewport: vec4<f32>,
+};
+
+struct DecalUniforms {
+    // Unit box -> world (pose with the decal size baked in).
+    local_to_world: mat4x4<f32>,
+    // World -> unit box.
+    world_to_local: mat4x4<f32>,
+    color: vec4<f32>,
+};
+
+@group(0) @binding(0) var<uniform> frame: FrameUniforms;
+// Bound as a float texture: the GL backend can't `textureLoad` depth textures.
+@if(msaa) @group(0) @binding(1) var scene_depth: texture_multisampled_2d<f32>;
+@if(!msaa) @group(0) @binding(1) var scene_depth: texture_2d<f32>;
+@group(1) @binding(0) var<uniform> decal: DecalUniforms;
+@group(2) @binding(0) var decal_texture: texture_2d<f32>;
+@group(2) @binding(1) var decal_sampler: sampler;
+
+@vertex
+fn vs_main(@builtin(vertex_index) vid: u32) -> @builtin(position) vec4<f32> {
+    // 12 triangles of the unit cube, generated from the vertex index.
+    var faces = array<vec3<u32>, 12>(
+        vec3<u32>(0u, 2u, 1u), vec3<u32>(1u, 2u, 3u),
+        vec3<u32>(4u, 5u, 6u), vec3<u32>(5u, 7u, 6u),
+        vec3<u32>(0u, 1u, 4u), vec3<u32>(1u, 5u, 4u),
+        vec3<u32>(2u, 6u, 3u), vec3<u32>(3u, 6u, 7u),
+        vec3<u32>(0u, 4u, 2u), vec3<u32>(2u, 4u, 6u),
+        vec3<u32>(1u, 3u, 5u), vec3<u32>(3u, 7u, 5u),
+    );
+    let corner = faces[vid / 3u][vid % 3u];
+    let local = vec3<f32>(
+        f32(corner & 1u),
+        f32((corner >> 1u) & 1u),
+        f32((corner >> 2u) & 1u),
+    ) - vec3<f32>(0.5);
+    return frame.view_proj * decal.local_to_world * vec4<f32>(local, 1.0);
+}
+
+@fragment
+fn fs_main(@builtin(position) frag: vec4<f32>) -> @location(0) vec4<f32> {
+    let texel = vec2<i32>(frag.xy);
+    let depth = textureLoad(scene_depth, texel, 0).x;
+
+    let ndc = vec2<f32>(
+        frag.x / frame.viewport.x * 2.0 - 1.0,
+        1.0 - frag.y / frame.viewport.y * 2.0,
+    );
+    let world4 = frame.inv_view_proj * vec4<f32>(ndc, depth, 1.0);
+    let world = world4.xyz / world4.w;
+    let local = (decal.world_to_local * vec4<f32>(world, 1.0)).xyz;
+
+    // Sample and take derivatives before any discard so they stay well-defined.
+    let uv = vec2<f32>(local.x + 0.5, 0.5 - local.z);
+    let texel_color = textureSample(decal_texture, decal_sampler, uv) * decal.color;
+    let normal = normalize(cross(dpdy(world), dpdx(world)));
+    let axis = normalize((decal.local_to_world * vec4<f32>(0.0, 1.0, 0.0, 0.0)).xyz);
+    let facing = smoothstep(0.1, 0.3, abs(dot(normal, axis)));
+
+    let outside = any(abs(local) > vec3<f32>(0.5));
+    let alpha = texel_color.a * facing;
+    if outside || depth >= 1.0 || alpha <= 0.001 {
+        discard;
+    }
+    return vec4<f32>(texel_color.rgb, alpha);
+}
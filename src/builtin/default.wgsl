@@ -55,6 +55,10 @@ struct FrameUniforms {
     ambient_intensity: f32,
     _padding: vec2<f32>,
     ambient_color: vec4<f32>,
+    // Hemisphere ambient ground color (rgb); a = 1 when the hemisphere term is on.
+    ambient_ground: vec4<f32>,
+    // World up axis (xyz) the hemisphere ambient blends along; w unused.
+    world_up: vec4<f32>,
     fog_color: vec4<f32>,
     // (mode, param_a, param_b, height_falloff): mode 0 off / 1 linear / 2 exp / 3 exp2.
     fog_params: vec4<f32>,
@@ -74,13 +78,16 @@ struct FrameUniforms {
     // dot(xyz, world_pos) + w < 0 are discarded. Used by reflector capture to clip
     // geometry behind the mirror. All-zero = inactive.
     clip_plane: vec4<f32>,
+    // (seconds since the window was created, seconds since the previous frame,
+    // unused, unused), for animated shading.
+    time: vec4<f32>,
     // Fixed-size reflection-probe array (first `probe_count.x` are live).
     probes: array<Probe, MAX_PROBES>,
 }
 
 // Blends `color` toward the fog color by an amount derived from the fragment's
-// view-space distance and (optionally) its world height.
-fn apply_fog(color: vec3<f32>, view_dist: f32, world_y: f32) -> vec3<f32> {
+// view-space distance and (optionally) its height along the world up axis.
+fn apply_fog(color: vec3<f32>, view_dist: f32, height: f32) -> vec3<f32> {
     let mode = frame.fog_params.x;
     if mode < 0.5 {
         return color;
@@ -102,7 +109,7 @@ fn apply_fog(color: vec3<f32>, view_dist: f32, world_y: f32) -> vec3<f32> {
     // Optional height thinning: less fog higher up.
     let hf = frame.fog_params.w;
     if hf > 0.0 {
-        f *= exp(-max(world_y, 0.0) * hf);
+        f *= exp(-max(height, 0.0) * hf);
     }
     return mix(color, frame.fog_color.rgb, clamp(f, 0.0, 1.0) * frame.fog_color.a);
 }
@@ -271,8 +278,10 @@ var<uniform> object: ObjectUniforms;
 // Bind group 2: material textures — albedo plus the PBR maps. Albedo and the PBR
 // maps are merged into a single group so the pipeline uses only 4 bind groups,
 // staying within WebGPU's `maxBindGroups` limit of 4 (browsers expose exactly 4).
-@group(2) @binding(0)
-var t_diffuse: texture_2d<f32>;
+// Albedo, or in the `texture_array` variant the texture array indexed by the
+// per-instance texture layer.
+@if(!texture_array) @group(2) @binding(0) var t_diffuse: texture_2d<f32>;
+@if(texture_array) @group(2) @binding(0) var t_diffuse: texture_2d_array<f32>;
 @group(2) @binding(1)
 var s_diffuse: sampler;
 @group(2) @binding(2)
@@ -622,11 +631,13 @@ fn compute_shadow(
 // Vertex input. The attribute layout is identical for the plain and deformed
 // (skinning + morph) variants — deform data is read from the group-4 storage
 // buffers by vertex index, not as vertex attributes. The group-4 bindings and the
-// deformed `vs_main` are gated `@if(deform)`.
+// deformed `vs_main` are gated `@if(deform)`. Only the `vertex_color` variant
+// reads the extra per-vertex color stream (vertex buffer 6).
 struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) tex_coord: vec2<f32>,
     @location(2) normal: vec3<f32>,
+    @if(vertex_color) @location(8) color: vec4<f32>,
 }
 
 // === GPU vertex deformation: skinning + morph targets (deform variant only) ===
@@ -651,6 +662,7 @@ struct InstanceInput {
     @location(5) inst_def_0: vec3<f32>,
     @location(6) inst_def_1: vec3<f32>,
     @location(7) inst_def_2: vec3<f32>,
+    @if(texture_array) @location(9) inst_layer: f32,
 }
 
 // Vertex output / Fragment input
@@ -661,6 +673,7 @@ struct VertexOutput {
     @location(2) world_pos: vec3<f32>,
     @location(3) vert_color: vec4<f32>,
     @location(4) view_pos: vec3<f32>,
+    @if(texture_array) @location(5) @interpolate(flat) tex_layer: f32,
 }
 
 // === PBR BRDF Functions ===
@@ -809,6 +822,8 @@ fn vs_main(vertex: VertexInput, instance: InstanceInput) -> VertexOutput {
 
     out.tex_coord = vertex.tex_coord;
     out.vert_color = instance.inst_color;
+    @if(vertex_color) out.vert_color = out.vert_color * vertex.color;
+    @if(texture_array) out.tex_layer = instance.inst_layer;
 
     return out;
 }
@@ -874,6 +889,8 @@ fn vs_main(vertex: VertexInput, instance: InstanceInput, @builtin(vertex_index)
 
     out.tex_coord = vertex.tex_coord;
     out.vert_color = instance.inst_color;
+    @if(vertex_color) out.vert_color = out.vert_color * vertex.color;
+    @if(texture_array) out.tex_layer = instance.inst_layer;
 
     return out;
 }
@@ -1125,7 +1142,7 @@ fn shade(in: VertexOutput) -> vec4<f32> {
     }
 
     // Sample albedo texture and combine with vertex/object color
-    let albedo_tex = textureSample(t_diffuse, s_diffuse, uv);
+    let albedo_tex = sample_albedo(in, uv);
     let base_color = in.vert_color * object.color;
     let albedo = (albedo_tex * base_color).rgb;
 
@@ -1307,7 +1324,13 @@ fn shade(in: VertexOutput) -> vec4<f32> {
     // set, else the flat colored ambient term. The IBL and probe paths are present
     // only in their respective variants (`ibl`, `probes`); with both off only the
     // flat term remains and the env/probe sampling helpers + bindings strip away.
-    var ambient = frame.ambient_color.rgb * frame.ambient_intensity * albedo * ao;
+    // With a ground color set, the flat term becomes a hemisphere light that blends
+    // from the ground color (facing down) to the ambient/sky color (facing up).
+    var ambient_rgb = frame.ambient_color.rgb;
+    if frame.ambient_ground.a > 0.5 {
+        ambient_rgb = mix(frame.ambient_ground.rgb, ambient_rgb, dot(N, frame.world_up.xyz) * 0.5 + 0.5);
+    }
+    var ambient = ambient_rgb * frame.ambient_intensity * albedo * ao;
     // The environment specular reflection (the skybox mirrored on the surface),
     // kept separate so refractive glass can layer it ON TOP of the refracted
     // background instead of mixing it away — this is what makes `reflectance` grow
@@ -1481,11 +1504,37 @@ fn shade(in: VertexOutput) -> vec4<f32> {
 
     // Distance fog (applied to the lit color; uses view distance + world height).
     // Present only in the `fog` variant; `apply_fog` strips away when off.
-    @if(fog) color = apply_fog(color, length(in.view_pos), in.world_pos.y);
+    @if(fog) color = apply_fog(color, length(in.view_pos), dot(in.world_pos, frame.world_up.xyz));
 
     return vec4<f32>(color, albedo_tex.a * base_color.a);
 }
 
+// The albedo texture at `uv`: the instance's layer of the texture array in the
+// `texture_array` variant, else the object's texture.
+fn sample_albedo(in: VertexOutput, uv: vec2<f32>) -> vec4<f32> {
+    var albedo: vec4<f32>;
+    @if(texture_array) {
+        albedo = textureSample(t_diffuse, s_diffuse, uv, i32(in.tex_layer + 0.5));
+    }
+    @if(!texture_array) {
+        albedo = textureSample(t_diffuse, s_diffuse, uv);
+    }
+    return albedo;
+}
+
+// Unlit shading (`ShadingMode::Unlit`/`VertexColor`/`TexturedUnlit`): the base
+// color — instance × object color, times the vertex colors in the `vertex_color`
+// variant and the albedo texture unless `untextured` — with no lighting at all.
+// Distance fog still applies, since it is a property of the view.
+fn shade_unlit(in: VertexOutput) -> vec4<f32> {
+    @if(untextured)  let albedo_tex = vec4<f32>(1.0);
+    @if(!untextured) let albedo_tex = sample_albedo(in, in.tex_coord);
+    let c = albedo_tex * in.vert_color * object.color;
+    var color = c.rgb;
+    @if(fog) color = apply_fog(color, length(in.view_pos), dot(in.world_pos, frame.world_up.xyz));
+    return vec4<f32>(color, c.a);
+}
+
 // Depth/view-position + lightweight G-buffer prepass. Writes the data the
 // screen-space effects need: view-space position (for SSAO + SSR ray marching),
 // world-space geometric normal + linear roughness, and F0 + metallic (for the SSR
@@ -1527,7 +1576,8 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
         && dot(frame.clip_plane.xyz, in.world_pos) + frame.clip_plane.w < 0.0 {
         discard;
     }
-    let c = shade(in);
+    @if(unlit)  let c = shade_unlit(in);
+    @if(!unlit) let c = shade(in);
     let mode = u32(object.alpha_mode + 0.5);
     // Mask: discard fragments below the cutoff.
     if mode == 1u && c.a < object.alpha_cutoff {
@@ -1557,7 +1607,8 @@ fn fs_oit(in: VertexOutput) -> OitOutput {
         && dot(frame.clip_plane.xyz, in.world_pos) + frame.clip_plane.w < 0.0 {
         discard;
     }
-    let c = shade(in);
+    @if(unlit)  let c = shade_unlit(in);
+    @if(!unlit) let c = shade(in);
     let a = c.a;
     // Depth-based weight: nearer fragments dominate (McGuire eq. 9). `view_pos.z`
     // is negative in front of the camera, so use its magnitude.
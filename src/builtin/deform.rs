@@ -164,6 +164,12 @@ fn with_globals<R>(f: impl FnOnce(&DeformGlobals) -> R) -> R {
     })
 }
 
+/// Drops the shared deform resources, created again on first use. Called when
+/// the GPU context they belong to is released.
+pub(crate) fn reset_globals() {
+    GLOBALS.with(|cell| *cell.borrow_mut() = None);
+}
+
 /// The shared deform bind-group layout, used as group 4 of the color/prepass
 /// pipelines and group 2 of the shadow pipelines. All these pipelines reference the
 /// *same* layout object, so one per-object bind group works in every pass.
@@ -0,0 +1,205 @@
+//! Read-back of the rasterizer's depth buffer as linear eye-space depth.
+//!
+//! Unlike the [depth AOV](crate::builtin::AovKind::Depth), which re-renders the
+//! scene, this converts the depth buffer the last frame actually produced, so it
+//! matches the displayed image exactly (including everything drawn by custom
+//! renderers). Under MSAA the first sample of each pixel is used.
+
+use crate::context::Context;
+use bytemuck::{Pod, Zeroable};
+use glamx::Mat4;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct Params {
+    inv_proj: [[f32; 4]; 4],
+    // (width, height, unused, unused) of the frame.
+    size: [u32; 4],
+    // (x, y, width, height) of the region to convert.
+    region: [u32; 4],
+}
+
+struct Variant {
+    layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::ComputePipeline,
+}
+
+/// Converts a depth texture to linear depth on the GPU and reads it back.
+pub(crate) struct DepthLinearizer {
+    single: Variant,
+    msaa: Variant,
+    params: wgpu::Buffer,
+}
+
+impl DepthLinearizer {
+    pub fn new() -> Self {
+        let ctxt = Context::get();
+        let params = ctxt.create_buffer_simple(
+            Some("depth_linearize_params"),
+            std::mem::size_of::<Params>() as u64,
+            wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        );
+
+        DepthLinearizer {
+            single: Self::variant(&ctxt, false),
+            msaa: Self::variant(&ctxt, true),
+            params,
+        }
+    }
+
+    fn variant(ctxt: &Context, multisampled: bool) -> Variant {
+        let layout = ctxt.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("depth_linearize_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Texture {
+                        // Not `Depth`: the GL backend can't `textureLoad` depth textures.
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let shader = ctxt.create_shader_module(
+            Some("depth_linearize_shader"),
+            &crate::builtin::compile_wesl(
+                &[(
+                    "package::depth_linearize",
+                    include_str!("depth_linearize.wgsl"),
+                )],
+                "package::depth_linearize",
+                &[("msaa", multisampled)],
+            ),
+        );
+        let pipeline_layout = ctxt.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("depth_linearize_pipeline_layout"),
+            bind_group_layouts: &[Some(&layout)],
+            immediate_size: 0,
+        });
+        let pipeline = ctxt
+            .device
+            .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some("depth_linearize_pipeline"),
+                layout: Some(&pipeline_layout),
+                module: &shader,
+                entry_point: Some("main"),
+                compilation_options: Default::default(),
+                cache: None,
+            });
+
+        Variant { layout, pipeline }
+    }
+
+    /// Linearizes the `region` (`[x, y, width, height]`) of `depth_view` (a
+    /// `width × height` depth texture with `sample_count` samples, rendered with
+    /// `projection`) and returns the row-major, top-left-origin result. Blocks
+    /// until the GPU is done, so this is unavailable on the web.
+    pub fn read(
+        &self,
+        depth_view: &wgpu::TextureView,
+        sample_count: u32,
+        (width, height): (u32, u32),
+        region: [u32; 4],
+        projection: Mat4,
+    ) -> Vec<f32> {
+        let ctxt = Context::get();
+        let variant = if sample_count > 1 {
+            &self.msaa
+        } else {
+            &self.single
+        };
+
+        ctxt.write_buffer(
+            &self.params,
+            0,
+            bytemuck::bytes_of(&Params {
+                inv_proj: projection.inverse().to_cols_array_2d(),
+                size: [width, height, 0, 0],
+                region,
+            }),
+        );
+
+        let [_, _, region_width, region_height] = region;
+        let size = (region_width as u64 * region_height as u64 * 4).max(4);
+        let output = ctxt.create_buffer_simple(
+            Some("depth_linearize_output"),
+            size,
+            wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        );
+        let staging = ctxt.create_buffer_simple(
+            Some("depth_linearize_staging"),
+            size,
+            wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        );
+
+        let group = ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("depth_linearize_bind_group"),
+            layout: &variant.layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.params.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(depth_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: output.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = ctxt.create_command_encoder(Some("depth_linearize_encoder"));
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("depth_linearize_pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&variant.pipeline);
+            pass.set_bind_group(0, &group, &[]);
+            pass.dispatch_workgroups(region_width.div_ceil(8), region_height.div_ceil(8), 1);
+        }
+        encoder.copy_buffer_to_buffer(&output, 0, &staging, 0, size);
+        ctxt.submit(std::iter::once(encoder.finish()));
+
+        let slice = staging.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |r| tx.send(r).unwrap());
+        let _ = ctxt.device.poll(wgpu::PollType::wait_indefinitely());
+        rx.recv().unwrap().unwrap();
+
+        let mapped = slice.get_mapped_range();
+        let texels = (region_width * region_height) as usize;
+        let data = bytemuck::cast_slice::<u8, f32>(&mapped)[..texels].to_vec();
+        drop(mapped);
+        staging.unmap();
+        data
+    }
+}
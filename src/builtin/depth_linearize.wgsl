@@ -0,0 +1,43 @@
+// Converts the hardware depth buffer into linear eye-space depth.
+//
+// One invocation per pixel of a region of the frame: loads the stored depth
+// (sample 0 under MSAA), unprojects it with the inverse projection matrix, and
+// writes the positive view-space distance along the camera axis into a tightly
+// packed, row-major (top-left origin) buffer. Background pixels (depth 1, the
+// clear value) read as 0.
+
+struct Params {
+    inv_proj: mat4x4<f32>,
+    // (width, height, unused, unused) of the frame.
+    size: vec4<u32>,
+    // (x, y, width, height) of the region to convert.
+    region: vec4<u32>,
+};
+
+@group(0) @binding(0) var<uniform> params: Params;
+// Bound as a float texture: the GL backend can't `textureLoad` depth textures.
+@if(msaa) @group(0) @binding(1) var depth_tex: texture_multisampled_2d<f32>;
+@if(!msaa) @group(0) @binding(1) var depth_tex: texture_2d<f32>;
+@group(0) @binding(2) var<storage, read_write> out_depth: array<f32>;
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let w = params.size.x;
+    let h = params.size.y;
+    if id.x >= params.region.z || id.y >= params.region.w {
+        return;
+    }
+    let px = params.region.xy + id.xy;
+
+    let d = textureLoad(depth_tex, vec2<i32>(px), 0).x;
+    var linear = 0.0;
+    if d < 1.0 {
+        let ndc = vec2<f32>(
+            (f32(px.x) + 0.5) / f32(w) * 2.0 - 1.0,
+            1.0 - (f32(px.y) + 0.5) / f32(h) * 2.0,
+        );
+        let view = params.inv_proj * vec4<f32>(ndc, d, 1.0);
+        linear = -view.z / view.w;
+    }
+    out_depth[id.y * params.region.z + id.x] = linear;
+}
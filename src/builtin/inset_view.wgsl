@@ -0,0 +1,51 @@
+// Inset view composite pass.
+//
+// Draws a secondary camera's linear HDR capture into its screen rectangle (the
+// render pass viewport) of the final LDR frame, with the same exposure and
+// tonemap operator as the main image, imported from the `tonemap_ops` WESL
+// module (which also declares the Tony McMapface LUT at group(0) bindings 6 & 7).
+// Pixels within `border` of the rectangle's edge get the border color instead.
+
+import package::tonemap_ops::apply_tonemap;
+import package::common::fullscreen_uv_from_clip;
+
+struct InsetUniforms {
+    // (width, height) of the inset in pixels, border width in pixels, exposure.
+    size_border_exposure: vec4<f32>,
+    border_color: vec4<f32>,
+    // Operator code, matching `post_processing::Tonemap::as_u32`.
+    tonemap_op: u32,
+};
+
+@group(0) @binding(0) var t_inset: texture_2d<f32>;
+@group(0) @binding(1) var s_inset: sampler;
+@group(0) @binding(2) var<uniform> inset: InsetUniforms;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coord: vec2<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) vid: u32) -> VertexOutput {
+    // Full-viewport quad as a 4-vertex triangle strip.
+    let p = vec2<f32>(f32(vid & 1u) * 2.0 - 1.0, f32(vid >> 1u) * 2.0 - 1.0);
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(p, 0.0, 1.0);
+    out.tex_coord = fullscreen_uv_from_clip(p);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let size = inset.size_border_exposure.xy;
+    let border = inset.size_border_exposure.z;
+    let px = in.tex_coord * size;
+    if any(px < vec2<f32>(border)) || any(px > size - vec2<f32>(border)) {
+        return inset.border_color;
+    }
+
+    let hdr = textureSample(t_inset, s_inset, in.tex_coord).rgb;
+    let ldr = apply_tonemap(hdr * inset.size_border_exposure.w, inset.tonemap_op);
+    return vec4<f32>(ldr, 1.0);
+}
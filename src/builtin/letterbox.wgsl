@@ -0,0 +1,31 @@
+import package::common::fullscreen_uv_from_clip;
+// Letterbox post-processing effect shader: copies the whole input image into the
+// render pass viewport (the centered fixed-aspect rectangle); the bars around it
+// are the pass clear color.
+
+@group(0) @binding(0)
+var t_fbo: texture_2d<f32>;
+@group(0) @binding(1)
+var s_fbo: sampler;
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coord: vec2<f32>,
+}
+
+@vertex
+fn vs_main(vertex: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(vertex.position, 0.0, 1.0);
+    out.tex_coord = fullscreen_uv_from_clip(vertex.position);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(t_fbo, s_fbo, in.tex_coord);
+}
@@ -0,0 +1,70 @@
+// Matcap shading for kiss3d
+// Looks up the color of each point in a "material capture" image, indexed by
+// the direction of its normal in view space.
+
+// Bind group 0: Frame uniforms
+struct FrameUniforms {
+    view: mat4x4<f32>,
+    proj: mat4x4<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> frame: FrameUniforms;
+
+// Bind group 1: Object uniforms
+struct ObjectUniforms {
+    transform: mat4x4<f32>,
+    scale: mat3x3<f32>,
+    color: vec4<f32>,
+}
+
+@group(1) @binding(0)
+var<uniform> object: ObjectUniforms;
+
+// Bind group 2: The matcap
+@group(2) @binding(0)
+var t_matcap: texture_2d<f32>;
+@group(2) @binding(1)
+var s_matcap: sampler;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) view_position: vec3<f32>,
+    @location(1) view_normal: vec3<f32>,
+}
+
+@vertex
+fn vs_main(vertex: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+
+    let scale = vec3<f32>(object.scale[0][0], object.scale[1][1], object.scale[2][2]);
+    let view_pos = frame.view * object.transform * vec4<f32>(object.scale * vertex.position, 1.0);
+    out.clip_position = frame.proj * view_pos;
+    out.view_position = view_pos.xyz;
+    out.view_normal = (frame.view * object.transform * vec4<f32>(vertex.normal / scale, 0.0)).xyz;
+
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let e = normalize(in.view_position);
+    var n = normalize(in.view_normal);
+    // Show the inside of open meshes as if it faced the camera.
+    if dot(n, e) > 0.0 {
+        n = -n;
+    }
+    // Index by the reflected view direction rather than the normal alone, so
+    // the matcap doesn't slide over surfaces away from the center of the view.
+    let r = reflect(e, n);
+    let m = max(2.0 * sqrt(r.x * r.x + r.y * r.y + (r.z + 1.0) * (r.z + 1.0)), 1.0e-5);
+    let uv = vec2<f32>(r.x / m + 0.5, 0.5 - r.y / m);
+
+    let matcap = textureSample(t_matcap, s_matcap, uv);
+    return vec4<f32>(object.color.rgb * matcap.rgb, object.color.a);
+}
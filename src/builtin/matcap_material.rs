@@ -0,0 +1,535 @@
+use crate::camera::Camera3d;
+use crate::context::Context;
+use crate::light::LightCollection;
+use crate::resource::vertex_index::VERTEX_INDEX_FORMAT;
+use crate::resource::{
+    multisample_state, DynamicUniformBuffer, GpuData, GpuMesh3d, Material3d, PipelineCache,
+    RenderContext, RenderPhase, Texture,
+};
+use crate::scene::{InstancesBuffer3d, ObjectData3d};
+use bytemuck::{Pod, Zeroable};
+use glamx::{Mat3, Pose3, Vec3};
+use std::any::Any;
+use std::cell::Cell;
+use std::sync::Arc;
+
+/// Frame-level uniforms (view, projection).
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct FrameUniforms {
+    view: [[f32; 4]; 4],
+    proj: [[f32; 4]; 4],
+}
+
+/// Object-level uniforms (transform, scale, color).
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct ObjectUniforms {
+    transform: [[f32; 4]; 4],
+    scale: [[f32; 4]; 3], // mat3x3 padded to mat3x4 for alignment
+    color: [f32; 4],
+}
+
+/// Per-object GPU data for MatcapMaterial.
+pub struct MatcapMaterialGpuData {
+    /// Offset into the shared dynamic object uniform buffer.
+    object_uniform_offset: Option<u32>,
+    /// Bind group of the matcap, rebuilt when the object's texture changes.
+    texture_bind_group: Option<wgpu::BindGroup>,
+    cached_texture_ptr: usize,
+}
+
+impl MatcapMaterialGpuData {
+    pub fn new() -> Self {
+        Self {
+            object_uniform_offset: None,
+            texture_bind_group: None,
+            cached_texture_ptr: 0,
+        }
+    }
+}
+
+impl Default for MatcapMaterialGpuData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GpuData for MatcapMaterialGpuData {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// A material shading objects from a matcap ("material capture") texture
+/// alone: the picture of a lit sphere, looked up with the direction of the
+/// surface normal in view space.
+///
+/// It ignores the lights of the scene, so it is a cheap way to give meshes a
+/// convincing look (clay, metal, glossy plastic) in a mesh viewer. The matcap
+/// is the texture of the object, multiplied by its color; use
+/// [`SceneNode3d::set_matcap`](crate::scene::SceneNode3d::set_matcap) to set
+/// both the texture and the `matcap` material registered by default.
+///
+/// The material is only drawn in the opaque pass: it is ignored by the
+/// G-buffer prepass (SSAO, SSR) and by order-independent transparency.
+///
+/// ## Performance Optimization
+///
+/// This material uses dynamic uniform buffers to batch uniform data writes:
+/// - Frame uniforms (view, projection) are written once per frame
+/// - Object uniforms are accumulated in a dynamic buffer and flushed once
+pub struct MatcapMaterial {
+    /// Pipeline with backface culling enabled (lazily built per MSAA sample count)
+    pipeline_cull: PipelineCache,
+    /// `pipeline_cull` for clockwise front faces
+    pipeline_cull_cw: PipelineCache,
+    /// Pipeline with backface culling disabled (lazily built per MSAA sample count)
+    pipeline_no_cull: PipelineCache,
+    object_bind_group_layout: wgpu::BindGroupLayout,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
+
+    // === Dynamic uniform buffer system ===
+    /// Shared frame uniform buffer
+    frame_uniform_buffer: wgpu::Buffer,
+    /// Shared frame bind group
+    frame_bind_group: wgpu::BindGroup,
+    /// Dynamic buffer for object uniforms
+    object_uniform_buffer: DynamicUniformBuffer<ObjectUniforms>,
+    /// Bind group for object uniforms (recreated when buffer grows)
+    object_bind_group: Option<wgpu::BindGroup>,
+    /// Frame counter for detecting new frames
+    frame_counter: Cell<u64>,
+    /// Last frame we processed
+    last_frame: Cell<u64>,
+}
+
+impl Default for MatcapMaterial {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MatcapMaterial {
+    /// Creates a new MatcapMaterial.
+    pub fn new() -> MatcapMaterial {
+        let ctxt = Context::get();
+
+        let frame_bind_group_layout =
+            ctxt.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("matcap_material_frame_bind_group_layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+
+        let object_bind_group_layout =
+            ctxt.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("matcap_material_object_bind_group_layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+
+        let texture_bind_group_layout =
+            ctxt.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("matcap_material_texture_bind_group_layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                ],
+            });
+
+        let pipeline_layout = ctxt.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("matcap_material_pipeline_layout"),
+            bind_group_layouts: &[
+                Some(&frame_bind_group_layout),
+                Some(&object_bind_group_layout),
+                Some(&texture_bind_group_layout),
+            ],
+            immediate_size: 0,
+        });
+
+        let shader =
+            ctxt.create_shader_module(Some("matcap_material_shader"), include_str!("matcap.wgsl"));
+
+        // Shared pipeline builder, parameterized by cull mode, front-face winding and
+        // MSAA sample count.
+        let build = std::rc::Rc::new(
+            move |cull_mode: Option<wgpu::Face>,
+                  front_face: wgpu::FrontFace,
+                  label: &'static str,
+                  sample_count: u32| {
+                let ctxt = Context::get();
+                let vertex_buffer_layouts = [
+                    // Vertex positions
+                    wgpu::VertexBufferLayout {
+                        array_stride: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                        step_mode: wgpu::VertexStepMode::Vertex,
+                        attributes: &[wgpu::VertexAttribute {
+                            offset: 0,
+                            shader_location: 0,
+                            format: wgpu::VertexFormat::Float32x3,
+                        }],
+                    },
+                    // Normals
+                    wgpu::VertexBufferLayout {
+                        array_stride: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                        step_mode: wgpu::VertexStepMode::Vertex,
+                        attributes: &[wgpu::VertexAttribute {
+                            offset: 0,
+                            shader_location: 1,
+                            format: wgpu::VertexFormat::Float32x3,
+                        }],
+                    },
+                ];
+
+                ctxt.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some(label),
+                    layout: Some(&pipeline_layout),
+                    vertex: wgpu::VertexState {
+                        module: &shader,
+                        entry_point: Some("vs_main"),
+                        buffers: &vertex_buffer_layouts,
+                        compilation_options: Default::default(),
+                    },
+                    fragment: Some(wgpu::FragmentState {
+                        module: &shader,
+                        entry_point: Some("fs_main"),
+                        targets: &[Some(wgpu::ColorTargetState {
+                            format: Context::render_format(),
+                            blend: Some(wgpu::BlendState::REPLACE),
+                            write_mask: wgpu::ColorWrites::ALL,
+                        })],
+                        compilation_options: Default::default(),
+                    }),
+                    primitive: wgpu::PrimitiveState {
+                        topology: wgpu::PrimitiveTopology::TriangleList,
+                        strip_index_format: None,
+                        front_face,
+                        cull_mode,
+                        polygon_mode: wgpu::PolygonMode::Fill,
+                        unclipped_depth: false,
+                        conservative: false,
+                    },
+                    depth_stencil: Some(wgpu::DepthStencilState {
+                        format: Context::depth_format(),
+                        depth_write_enabled: Some(true),
+                        depth_compare: Some(wgpu::CompareFunction::Less),
+                        stencil: wgpu::StencilState::default(),
+                        bias: wgpu::DepthBiasState::default(),
+                    }),
+                    multisample: multisample_state(sample_count),
+                    multiview_mask: None,
+                    cache: None,
+                })
+            },
+        );
+
+        let pipeline_cull = PipelineCache::new({
+            let build = build.clone();
+            move |sc| {
+                build(
+                    Some(wgpu::Face::Back),
+                    wgpu::FrontFace::Ccw,
+                    "matcap_material_pipeline_cull",
+                    sc,
+                )
+            }
+        });
+        let pipeline_cull_cw = PipelineCache::new({
+            let build = build.clone();
+            move |sc| {
+                build(
+                    Some(wgpu::Face::Back),
+                    wgpu::FrontFace::Cw,
+                    "matcap_material_pipeline_cull_cw",
+                    sc,
+                )
+            }
+        });
+        let pipeline_no_cull = PipelineCache::new({
+            let build = build.clone();
+            move |sc| {
+                build(
+                    None,
+                    wgpu::FrontFace::Ccw,
+                    "matcap_material_pipeline_no_cull",
+                    sc,
+                )
+            }
+        });
+
+        // === Create shared dynamic buffer resources ===
+
+        let frame_uniform_buffer = ctxt.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("matcap_shared_frame_uniform_buffer"),
+            size: std::mem::size_of::<FrameUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let frame_bind_group = ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("matcap_shared_frame_bind_group"),
+            layout: &frame_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: frame_uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        let object_uniform_buffer =
+            DynamicUniformBuffer::<ObjectUniforms>::new("matcap_dynamic_object_uniform_buffer");
+
+        let object_bind_group = ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("matcap_dynamic_object_bind_group"),
+            layout: &object_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer: object_uniform_buffer.buffer(),
+                    offset: 0,
+                    size: std::num::NonZeroU64::new(object_uniform_buffer.aligned_size()),
+                }),
+            }],
+        });
+
+        MatcapMaterial {
+            pipeline_cull,
+            pipeline_cull_cw,
+            pipeline_no_cull,
+            object_bind_group_layout,
+            texture_bind_group_layout,
+            frame_uniform_buffer,
+            frame_bind_group,
+            object_uniform_buffer,
+            object_bind_group: Some(object_bind_group),
+            frame_counter: Cell::new(0),
+            last_frame: Cell::new(u64::MAX),
+        }
+    }
+
+    fn create_texture_bind_group(&self, matcap: &Texture) -> wgpu::BindGroup {
+        Context::get().create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("matcap_material_texture_bind_group"),
+            layout: &self.texture_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&matcap.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&matcap.sampler),
+                },
+            ],
+        })
+    }
+}
+
+impl Material3d for MatcapMaterial {
+    fn create_gpu_data(&self) -> Box<dyn GpuData> {
+        Box::new(MatcapMaterialGpuData::new())
+    }
+
+    fn begin_frame(&mut self) {
+        self.frame_counter
+            .set(self.frame_counter.get().wrapping_add(1));
+        self.object_uniform_buffer.clear();
+    }
+
+    fn flush(&mut self) {
+        let ctxt = Context::get();
+
+        // Flush returns true if buffer was reallocated
+        let buffer_reallocated = self.object_uniform_buffer.flush();
+
+        // Recreate bind group if buffer was reallocated
+        if buffer_reallocated {
+            self.object_bind_group = Some(ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("matcap_dynamic_object_bind_group"),
+                layout: &self.object_bind_group_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                        buffer: self.object_uniform_buffer.buffer(),
+                        offset: 0,
+                        size: std::num::NonZeroU64::new(self.object_uniform_buffer.aligned_size()),
+                    }),
+                }],
+            }));
+        }
+    }
+
+    fn prepare(
+        &mut self,
+        pass: usize,
+        transform: Pose3,
+        scale: Vec3,
+        camera: &mut dyn Camera3d,
+        _lights: &LightCollection,
+        data: &ObjectData3d,
+        gpu_data: &mut dyn GpuData,
+        _viewport_width: u32,
+        _viewport_height: u32,
+    ) {
+        let ctxt = Context::get();
+
+        let gpu_data = gpu_data
+            .as_any_mut()
+            .downcast_mut::<MatcapMaterialGpuData>()
+            .expect("MatcapMaterial requires MatcapMaterialGpuData");
+
+        // Check if this is a new frame (first object being prepared)
+        let current_frame = self.frame_counter.get();
+        if current_frame != self.last_frame.get() {
+            self.last_frame.set(current_frame);
+
+            let (view, proj) = camera.view_transform_pair(pass);
+            let frame_uniforms = FrameUniforms {
+                view: view.to_mat4().to_cols_array_2d(),
+                proj: proj.to_cols_array_2d(),
+            };
+            ctxt.write_buffer(
+                &self.frame_uniform_buffer,
+                0,
+                bytemuck::bytes_of(&frame_uniforms),
+            );
+        }
+
+        // Pad mat3x3 to mat3x4 for proper alignment
+        let scale_cols = Mat3::from_diagonal(scale).to_cols_array_2d();
+        let scale_padded: [[f32; 4]; 3] = [
+            [scale_cols[0][0], scale_cols[0][1], scale_cols[0][2], 0.0],
+            [scale_cols[1][0], scale_cols[1][1], scale_cols[1][2], 0.0],
+            [scale_cols[2][0], scale_cols[2][1], scale_cols[2][2], 0.0],
+        ];
+
+        let color = data.color();
+        let object_uniforms = ObjectUniforms {
+            transform: transform.to_mat4().to_cols_array_2d(),
+            scale: scale_padded,
+            color: [color.r, color.g, color.b, color.a],
+        };
+
+        let object_offset = self.object_uniform_buffer.push(&object_uniforms);
+        gpu_data.object_uniform_offset = Some(object_offset);
+
+        // Rebuild the matcap bind group when the object's texture changed.
+        let texture: &Arc<Texture> = data.texture();
+        let texture_ptr = Arc::as_ptr(texture) as usize;
+        if gpu_data.texture_bind_group.is_none() || gpu_data.cached_texture_ptr != texture_ptr {
+            gpu_data.texture_bind_group = Some(self.create_texture_bind_group(texture));
+            gpu_data.cached_texture_ptr = texture_ptr;
+        }
+    }
+
+    fn render(
+        &mut self,
+        _pass: usize,
+        _transform: Pose3,
+        _scale: Vec3,
+        _camera: &mut dyn Camera3d,
+        _lights: &LightCollection,
+        data: &ObjectData3d,
+        mesh: &mut GpuMesh3d,
+        _instances: &mut InstancesBuffer3d,
+        gpu_data: &mut dyn GpuData,
+        render_pass: &mut wgpu::RenderPass<'_>,
+        context: &RenderContext,
+    ) {
+        // The prepass and transmission passes have other targets.
+        if !data.surface_rendering_active() || context.phase != RenderPhase::Opaque {
+            return;
+        }
+
+        let gpu_data = gpu_data
+            .as_any_mut()
+            .downcast_mut::<MatcapMaterialGpuData>()
+            .expect("MatcapMaterial requires MatcapMaterialGpuData");
+
+        let object_offset = gpu_data
+            .object_uniform_offset
+            .expect("prepare() must be called before render()");
+        let texture_bind_group = gpu_data
+            .texture_bind_group
+            .as_ref()
+            .expect("prepare() must be called before render()");
+
+        // Ensure mesh buffers are on GPU
+        mesh.coords().write().unwrap().load_to_gpu();
+        mesh.normals().write().unwrap().load_to_gpu();
+        mesh.faces().write().unwrap().load_to_gpu();
+
+        let coords_buffer = mesh.coords().read().unwrap();
+        let normals_buffer = mesh.normals().read().unwrap();
+        let faces_buffer = mesh.faces().read().unwrap();
+
+        let coords_buf = match coords_buffer.buffer() {
+            Some(b) => b,
+            None => return,
+        };
+        let normals_buf = match normals_buffer.buffer() {
+            Some(b) => b,
+            None => return,
+        };
+        let faces_buf = match faces_buffer.buffer() {
+            Some(b) => b,
+            None => return,
+        };
+
+        let object_bind_group = self.object_bind_group.as_ref().unwrap();
+
+        let pipeline = if data.backface_culling_enabled() {
+            match data.effective_front_face() {
+                wgpu::FrontFace::Ccw => self.pipeline_cull.get(context.sample_count),
+                wgpu::FrontFace::Cw => self.pipeline_cull_cw.get(context.sample_count),
+            }
+        } else {
+            self.pipeline_no_cull.get(context.sample_count)
+        };
+        render_pass.set_pipeline(&pipeline);
+        render_pass.set_bind_group(0, &self.frame_bind_group, &[]);
+        render_pass.set_bind_group(1, object_bind_group, &[object_offset]);
+        render_pass.set_bind_group(2, texture_bind_group, &[]);
+
+        render_pass.set_vertex_buffer(0, coords_buf.slice(..));
+        render_pass.set_vertex_buffer(1, normals_buf.slice(..));
+        render_pass.set_index_buffer(faces_buf.slice(..), VERTEX_INDEX_FORMAT);
+
+        render_pass.draw_indexed(0..mesh.num_indices(), 0, 0..1);
+        crate::renderer::timings::record_draw(mesh.num_indices() as u64 / 3);
+    }
+}
@@ -69,9 +69,15 @@ pub(crate) fn compile_wesl(
 
 pub use self::aov::{
     AovKind, AovRenderer, DEPTH_AOV_FORMAT, NORMALS_AOV_FORMAT, SEGMENTATION_AOV_FORMAT,
+    VELOCITY_AOV_FORMAT,
 };
+pub(crate) use self::depth_linearize::DepthLinearizer;
+pub use self::matcap_material::{MatcapMaterial, MatcapMaterialGpuData};
 pub use self::normals_material::{NormalsMaterial, NORMAL_FRAGMENT_SRC, NORMAL_VERTEX_SRC};
+pub(crate) use self::object_material::PipelineVariantKey;
 pub use self::object_material::{ObjectMaterial, OBJECT_FRAGMENT_SRC, OBJECT_VERTEX_SRC};
+pub use self::shader_material::{ShaderMaterial, ShaderMaterialGpuData, SHADER_MATERIAL_SRC};
+pub use self::toon_material::{ToonMaterial, ToonMaterialGpuData, ToonOutline};
 pub use self::uvs_material::{UvsMaterial, UVS_FRAGMENT_SRC, UVS_VERTEX_SRC};
 
 pub use self::lit_material2d::{LitMaterial2d, LitMaterial2dGpuData, LitParams};
@@ -82,9 +88,13 @@ pub use self::skinned_material2d::{Bone2d, SkinVertex2d, SkinnedMesh2d, MAX_JOIN
 mod aov;
 pub(crate) mod clustered;
 pub mod deform;
+mod depth_linearize;
+mod matcap_material;
 mod normals_material;
 mod object_material;
+mod shader_material;
 mod shadow;
+mod toon_material;
 mod uvs_material;
 
 mod lit_material2d;
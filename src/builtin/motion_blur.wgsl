@@ -0,0 +1,67 @@
+import package::common::{fullscreen_triangle_xy, fullscreen_uv_from_clip};
+// Motion blur — two fragment entry points sharing one full-screen vertex stage.
+//
+// `fs_blur` averages the resolved HDR scene along each pixel's screen-space
+// velocity (read from the velocity AOV), centered on the pixel, over the
+// fraction of the frame the virtual shutter stays open. The blur length is
+// capped to `max_blur` pixels. `fs_copy` writes the blurred image back over
+// the scene (the blur can't read and write the scene texture at once).
+
+struct MotionBlurUniforms {
+    // (shutter, num_samples, max_blur_pixels, unused)
+    params0: vec4<f32>,
+    // (width, height, inv_w, inv_h)
+    params1: vec4<f32>,
+}
+
+@group(0) @binding(0) var t_color: texture_2d<f32>;
+// The velocity AOV in the blur pass, unused in the copy pass.
+@group(0) @binding(1) var t_velocity: texture_2d<f32>;
+@group(0) @binding(2) var samp: sampler;
+@group(0) @binding(3) var<uniform> u: MotionBlurUniforms;
+
+struct VsOut {
+    @builtin(position) pos: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vid: u32) -> VsOut {
+    let xy = fullscreen_triangle_xy(vid);
+    var o: VsOut;
+    o.pos = vec4<f32>(xy, 0.0, 1.0);
+    o.uv = fullscreen_uv_from_clip(xy);
+    return o;
+}
+
+@fragment
+fn fs_blur(in: VsOut) -> @location(0) vec4<f32> {
+    let center = textureSampleLevel(t_color, samp, in.uv, 0.0);
+    let coord = vec2<i32>(in.pos.xy);
+    var velocity = textureLoad(t_velocity, coord, 0).xy * u.params0.x;
+
+    // Cap the blur length, in pixels.
+    let length_px = length(velocity * u.params1.xy);
+    if length_px < 0.5 {
+        return center;
+    }
+    let max_blur = u.params0.z;
+    if length_px > max_blur {
+        velocity *= max_blur / length_px;
+    }
+
+    // Velocities point from the previous position to the current one: gather
+    // along the path the surface swept during the shutter interval.
+    let n = max(i32(u.params0.y), 2);
+    var sum = vec4<f32>(0.0);
+    for (var i = 0; i < n; i++) {
+        let t = f32(i) / f32(n - 1) - 0.5;
+        sum += textureSampleLevel(t_color, samp, in.uv - velocity * t, 0.0);
+    }
+    return sum / f32(n);
+}
+
+@fragment
+fn fs_copy(in: VsOut) -> @location(0) vec4<f32> {
+    return textureSampleLevel(t_color, samp, in.uv, 0.0);
+}
@@ -2,12 +2,12 @@ use crate::camera::Camera3d;
 use crate::context::Context;
 use crate::light::{LightCollection, LightType, MAX_LIGHTS};
 use crate::post_processing::{OIT_ACCUM_FORMAT, OIT_REVEAL_FORMAT};
-use crate::resource::vertex_index::VERTEX_INDEX_FORMAT;
+use crate::resource::vertex_index::{VertexIndex, VERTEX_INDEX_FORMAT};
 use crate::resource::{
     multisample_state, DynamicUniformBuffer, GpuData, GpuMesh3d, Material3d, PipelineCache,
     RenderContext, Texture,
 };
-use crate::scene::{InstancesBuffer3d, ObjectData3d};
+use crate::scene::{InstancesBuffer3d, ObjectData3d, ShadingMode};
 use bytemuck::{Pod, Zeroable};
 use glamx::{Mat3, Pose3, Vec3};
 use std::any::Any;
@@ -131,6 +131,11 @@ struct FrameUniforms {
     _padding: [f32; 2],
     // Global ambient light color (rgb); a is unused.
     ambient_color: [f32; 4],
+    // Hemisphere ambient ground color (rgb); a = 1 when the hemisphere term is on.
+    ambient_ground: [f32; 4],
+    // World up axis (xyz) the hemisphere ambient blends along; w is unused. See
+    // `Context::world_up`.
+    world_up: [f32; 4],
     // Distance fog color (rgb) + max fog opacity (a).
     fog_color: [f32; 4],
     // Fog params: (mode, param_a, param_b, height_falloff). See `Fog::params`.
@@ -152,6 +157,9 @@ struct FrameUniforms {
     // dot(xyz, world_pos) + w < 0 are discarded (reflector capture clips geometry
     // behind the mirror). All-zero = inactive.
     clip_plane: [f32; 4],
+    // Frame timing: (seconds since the window was created, seconds since the
+    // previous frame, unused, unused). See `Context::frame_time`.
+    time: [f32; 4],
     // Fixed-size reflection-probe array (only the first `probe_count.x` are live).
     probes: [GpuProbe; MAX_PROBES],
 }
@@ -239,6 +247,27 @@ struct WireframeModelUniforms {
     _padding: [f32; 2],       // 8 bytes at offset 104 to align to 16-byte boundary
 }
 
+/// Depth state of a wireframe pipeline variant, from the object's lines depth
+/// bias and "on top" flag.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct WireframeDepth {
+    constant: i32,
+    /// Bits of the `f32` slope-scale bias (so the key is hashable).
+    slope_scale: u32,
+    on_top: bool,
+}
+
+impl WireframeDepth {
+    fn of(data: &ObjectData3d) -> Self {
+        let (constant, slope_scale) = data.lines_depth_bias();
+        WireframeDepth {
+            constant,
+            slope_scale: slope_scale.to_bits(),
+            on_top: data.lines_on_top(),
+        }
+    }
+}
+
 /// Edge data in GPU format (matches shader struct).
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
@@ -287,6 +316,8 @@ pub struct ObjectMaterialGpuData {
     cached_ao_map_ptr: usize,
     cached_emissive_map_ptr: usize,
     cached_height_map_ptr: usize,
+    /// Whether the albedo bound last is the object's texture array.
+    cached_albedo_is_array: bool,
     /// Reflection texture view bound last (the reflector target, or fallback during
     /// capture / when not a reflector). Detects when the bind group must rebuild.
     cached_reflection_ptr: usize,
@@ -295,24 +326,35 @@ pub struct ObjectMaterialGpuData {
     /// isn't caught by `cached_reflection_ptr` alone — the generation catches it.
     cached_reflection_gen: u64,
     // Wireframe rendering data (model uniforms are per-object)
-    wireframe_model_uniform_buffer: wgpu::Buffer,
+    /// Offset of this object's wireframe model uniforms in the material's dynamic
+    /// buffer (written during prepare).
+    wireframe_model_offset: u32,
     wireframe_edge_buffer: wgpu::Buffer,
     wireframe_edge_capacity: usize,
     wireframe_model_bind_group: Option<wgpu::BindGroup>,
+    /// Generation of the material's model uniform buffers the wireframe model bind
+    /// group was created against.
+    wireframe_model_bind_group_gen: u64,
     /// Cached wireframe edges in local coordinates (built lazily from mesh).
     wireframe_edges: Option<Vec<(Vec3, Vec3)>>,
-    /// Hash of mesh faces to detect when edges need rebuilding.
+    /// Hash of the mesh coords and faces versions (and of the feature-edge angle)
+    /// to detect when edges need rebuilding.
     wireframe_edges_mesh_hash: u64,
     /// Cached wireframe model uniforms (written during prepare).
     wireframe_model_uniforms: WireframeModelUniforms,
     // Point rendering data (model uniforms are per-object)
-    points_model_uniform_buffer: wgpu::Buffer,
+    /// Offset of this object's points model uniforms in the material's dynamic
+    /// buffer (written during prepare).
+    points_model_offset: u32,
     points_vertex_buffer: wgpu::Buffer,
     points_vertex_capacity: usize,
     points_model_bind_group: Option<wgpu::BindGroup>,
+    /// Generation of the material's model uniform buffers the points model bind
+    /// group was created against.
+    points_model_bind_group_gen: u64,
     /// Cached vertices for point rendering (built lazily from mesh).
     points_vertices: Option<Vec<Vec3>>,
-    /// Hash of mesh coords to detect when vertices need rebuilding.
+    /// Version of the mesh coords to detect when vertices need rebuilding.
     points_vertices_mesh_hash: u64,
     /// Cached points model uniforms (written during prepare).
     points_model_uniforms: PointsModelUniforms,
@@ -323,14 +365,6 @@ impl ObjectMaterialGpuData {
     pub fn new() -> Self {
         let ctxt = Context::get();
 
-        // Wireframe model uniform buffer (per-object)
-        let wireframe_model_uniform_buffer = ctxt.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("wireframe_model_uniform_buffer"),
-            size: std::mem::size_of::<WireframeModelUniforms>() as u64,
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-
         // Initial edge storage buffer (will grow as needed)
         let wireframe_edge_capacity = 1024;
         let wireframe_edge_buffer = ctxt.create_buffer(&wgpu::BufferDescriptor {
@@ -340,14 +374,6 @@ impl ObjectMaterialGpuData {
             mapped_at_creation: false,
         });
 
-        // Point model uniform buffer (per-object)
-        let points_model_uniform_buffer = ctxt.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("points_model_uniform_buffer"),
-            size: std::mem::size_of::<PointsModelUniforms>() as u64,
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-
         // Initial vertex storage buffer for points (will grow as needed)
         let points_vertex_capacity = 1024;
         let points_vertex_buffer = ctxt.create_buffer(&wgpu::BufferDescriptor {
@@ -367,13 +393,15 @@ impl ObjectMaterialGpuData {
             cached_ao_map_ptr: 0,
             cached_emissive_map_ptr: 0,
             cached_height_map_ptr: 0,
+            cached_albedo_is_array: false,
             cached_reflection_ptr: 0,
             cached_reflection_gen: 0,
             // Wireframe rendering
-            wireframe_model_uniform_buffer,
+            wireframe_model_offset: 0,
             wireframe_edge_buffer,
             wireframe_edge_capacity,
             wireframe_model_bind_group: None,
+            wireframe_model_bind_group_gen: 0,
             wireframe_edges: None,
             wireframe_edges_mesh_hash: 0,
             wireframe_model_uniforms: WireframeModelUniforms {
@@ -385,10 +413,11 @@ impl ObjectMaterialGpuData {
                 use_perspective: 0,
                 _padding: [0.0; 2],
             },
-            points_model_uniform_buffer,
+            points_model_offset: 0,
             points_vertex_buffer,
             points_vertex_capacity,
             points_model_bind_group: None,
+            points_model_bind_group_gen: 0,
             points_vertices: None,
             points_vertices_mesh_hash: 0,
             points_model_uniforms: PointsModelUniforms {
@@ -465,6 +494,7 @@ impl GpuData for ObjectMaterialGpuData {
 /// This material uses dynamic uniform buffers to batch uniform data writes:
 /// - Frame uniforms (view, projection, light) are written once per frame
 /// - Object uniforms are accumulated in a dynamic buffer and flushed once
+/// - Wireframe/points model uniforms are accumulated in dynamic buffers and flushed once
 /// - Wireframe/points view uniforms (view, proj, viewport) are shared and written once per frame
 /// - This significantly reduces the number of `write_buffer` calls per frame
 pub struct ObjectMaterial {
@@ -475,11 +505,15 @@ pub struct ObjectMaterial {
     /// backend has no spare bind group (web / WebGL2) — deform then falls back to the
     /// plain path.
     deform_pipeline_layout: Option<wgpu::PipelineLayout>,
+    /// The layouts of the `texture_array` variants, which bind the albedo texture
+    /// array through `texture_array_bind_group_layout` (plain, then deform).
+    array_pipeline_layout: wgpu::PipelineLayout,
+    array_deform_pipeline_layout: Option<wgpu::PipelineLayout>,
     /// Opaque-surface pipeline builder: `(layout, module, _skinned, cull, label, samples)`.
     build_opaque: SurfacePipelineBuilder,
     /// Weighted-blended OIT pipeline builder (same signature as `build_opaque`).
     build_oit: SurfacePipelineBuilder,
-    /// Depth + view-position prepass pipeline builder: `(layout, module, _skinned, samples)`.
+    /// Depth + view-position prepass pipeline builder: `(layout, module, _skinned, cull, samples)`.
     build_prepass: PrepassPipelineBuilder,
     /// WESL-compiled shader modules, keyed by feature mask (lazily compiled, cached).
     shader_modules: RefCell<HashMap<ShaderFeatures, Rc<wgpu::ShaderModule>>>,
@@ -489,6 +523,9 @@ pub struct ObjectMaterial {
     object_bind_group_layout: wgpu::BindGroupLayout,
     /// Combined material-texture bind group layout (albedo + PBR maps, group 2).
     texture_bind_group_layout: wgpu::BindGroupLayout,
+    /// Same as `texture_bind_group_layout`, with the albedo at bindings 0/1 being a
+    /// texture array (the `texture_array` variants).
+    texture_array_bind_group_layout: wgpu::BindGroupLayout,
     /// Default PBR textures for when user hasn't set any
     default_normal_map: std::sync::Arc<crate::resource::Texture>,
     default_metallic_roughness_map: std::sync::Arc<crate::resource::Texture>,
@@ -498,7 +535,12 @@ pub struct ObjectMaterial {
     /// Clamp+linear sampler for the per-object planar-reflection texture (binding 13).
     reflection_sampler: wgpu::Sampler,
     // Wireframe rendering resources
-    wireframe_pipeline: PipelineCache,
+    /// Wireframe pipeline builder: `(samples, depth state)`.
+    build_wireframe: Box<dyn Fn(u32, WireframeDepth) -> wgpu::RenderPipeline>,
+    /// Wireframe pipelines, keyed by `(sample_count, depth state)` (lazily built,
+    /// cached). Objects with a custom lines depth bias or drawn on top get their
+    /// own variant.
+    wireframe_pipelines: RefCell<HashMap<(u32, WireframeDepth), Rc<wgpu::RenderPipeline>>>,
     wireframe_model_bind_group_layout: wgpu::BindGroupLayout,
     // Point rendering resources
     points_pipeline: PipelineCache,
@@ -557,6 +599,9 @@ pub struct ObjectMaterial {
     /// reflection probes so captured surfaces don't sample the probe being
     /// captured (which would create a hall-of-mirrors feedback loop).
     capture_mode: Cell<bool>,
+    /// Whether translucent surfaces draw in the OIT transparent phase (the
+    /// default) or alpha-blended in the opaque phase.
+    oit_enabled: bool,
     // === Reflection probes (group 0 binding 7; data in the frame uniform). ===
     /// 1x1x1 black fallback probe array, bound when no probes are set.
     _probe_fallback_texture: wgpu::Texture,
@@ -583,6 +628,13 @@ pub struct ObjectMaterial {
     object_bind_group: Option<wgpu::BindGroup>,
     /// Capacity when bind group was last created (to detect regrowth)
     object_bind_group_capacity: u64,
+    /// Dynamic buffer for per-object wireframe model uniforms
+    wireframe_model_buffer: DynamicUniformBuffer<WireframeModelUniforms>,
+    /// Dynamic buffer for per-object points model uniforms
+    points_model_buffer: DynamicUniformBuffer<PointsModelUniforms>,
+    /// Bumped whenever one of the model uniform buffers is reallocated, which
+    /// invalidates the per-object wireframe/points model bind groups.
+    model_buffers_gen: u64,
     /// Frame counter for detecting new frames
     frame_counter: Cell<u64>,
     /// Last frame we processed (to detect new frame)
@@ -607,13 +659,13 @@ pub struct ObjectMaterial {
 }
 
 /// Builds an opaque-surface or OIT pipeline from a compiled module:
-/// `(pipeline_layout, shader_module, _skinned, cull_mode, label, sample_count)`.
+/// `(pipeline_layout, shader_module, features, cull_mode, label, sample_count)`.
 /// Captures nothing; the deform variant differs only in the module + layout passed.
 type SurfacePipelineBuilder = Rc<
     dyn Fn(
         &wgpu::PipelineLayout,
         &wgpu::ShaderModule,
-        bool,
+        ShaderFeatures,
         Option<wgpu::Face>,
         &'static str,
         u32,
@@ -621,9 +673,16 @@ type SurfacePipelineBuilder = Rc<
 >;
 
 /// Builds the depth + view-position prepass pipeline:
-/// `(pipeline_layout, shader_module, _skinned, sample_count)`.
-type PrepassPipelineBuilder =
-    Rc<dyn Fn(&wgpu::PipelineLayout, &wgpu::ShaderModule, bool, u32) -> wgpu::RenderPipeline>;
+/// `(pipeline_layout, shader_module, features, sample_count)`.
+type PrepassPipelineBuilder = Rc<
+    dyn Fn(
+        &wgpu::PipelineLayout,
+        &wgpu::ShaderModule,
+        ShaderFeatures,
+        wgpu::Face,
+        u32,
+    ) -> wgpu::RenderPipeline,
+>;
 
 /// Owns the GPU resources backing [`ObjectMaterial`]'s neutral shadow bind group.
 struct DefaultShadowResources {
@@ -641,7 +700,11 @@ struct DefaultShadowResources {
 /// specialized shader variant so the features an object/frame doesn't use — and the
 /// registers and bindings they need — are stripped out entirely. The vertex/binding
 /// layout is identical across variants (unused bindings simply strip away), so all
-/// variants share the same pipeline layout and bind groups. See `compile_object_wgsl`.
+/// variants share the same pipeline layout and bind groups. The only exceptions are
+/// `vertex_color`, which adds a per-vertex color stream, and `texture_array`, which
+/// adds a per-instance texture-layer stream (see `surface_vertex_buffers`) and binds
+/// a texture array as the albedo (see `texture_array_bind_group_layout`). See
+/// `compile_object_wgsl`.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
 pub(crate) struct ShaderFeatures(u32);
 
@@ -665,10 +728,17 @@ impl ShaderFeatures {
     const ANISOTROPY: u32 = 1 << 13;
     const TRANSMISSION: u32 = 1 << 14;
     const REFLECTOR: u32 = 1 << 15;
+    // Per-object shading mode (see `ShadingMode`). `untextured` only matters
+    // together with `unlit`.
+    const UNLIT: u32 = 1 << 16;
+    const UNTEXTURED: u32 = 1 << 17;
+    const VERTEX_COLOR: u32 = 1 << 18;
+    // Albedo sampled from the object's texture array, at each instance's layer.
+    const TEXTURE_ARRAY: u32 = 1 << 19;
 
     /// `(WESL feature name, bit)` — names MUST match the `@if(...)` flags in
     /// `default.wgsl`.
-    const TABLE: [(&'static str, u32); 16] = [
+    const TABLE: [(&'static str, u32); 20] = [
         ("deform", Self::DEFORM),
         ("clustered", Self::CLUSTERED),
         ("shadows", Self::SHADOWS),
@@ -685,6 +755,10 @@ impl ShaderFeatures {
         ("anisotropy", Self::ANISOTROPY),
         ("transmission", Self::TRANSMISSION),
         ("reflector", Self::REFLECTOR),
+        ("unlit", Self::UNLIT),
+        ("untextured", Self::UNTEXTURED),
+        ("vertex_color", Self::VERTEX_COLOR),
+        ("texture_array", Self::TEXTURE_ARRAY),
     ];
 
     #[inline]
@@ -702,13 +776,140 @@ impl ShaderFeatures {
         self
     }
 
+    /// The features selected by the object itself (its maps, material parameters,
+    /// shading mode and texture array), as opposed to the frame-wide and
+    /// capability ones.
+    fn of_object(data: &ObjectData3d, has_vertex_colors: bool) -> Self {
+        let (unlit, untextured, vertex_color) = match data.shading_mode() {
+            ShadingMode::Lit => (false, false, false),
+            ShadingMode::Unlit => (true, true, false),
+            ShadingMode::VertexColor => (true, true, has_vertex_colors),
+            ShadingMode::TexturedUnlit => (true, false, false),
+        };
+
+        ShaderFeatures::default()
+            .with(Self::NORMAL_MAP, data.normal_map().is_some())
+            .with(Self::MR_MAP, data.metallic_roughness_map().is_some())
+            .with(Self::AO_MAP, data.ao_map().is_some())
+            .with(Self::EMISSIVE_MAP, data.emissive_map().is_some())
+            .with(Self::PARALLAX, data.height_map().is_some())
+            .with(Self::CLEARCOAT, data.clearcoat() > 0.0)
+            .with(Self::ANISOTROPY, data.anisotropy() != 0.0)
+            .with(Self::TRANSMISSION, data.transmission() > 0.0)
+            .with(Self::REFLECTOR, data.reflector().is_some())
+            .with(Self::UNLIT, unlit)
+            .with(Self::UNTEXTURED, untextured)
+            .with(Self::VERTEX_COLOR, vertex_color)
+            .with(Self::TEXTURE_ARRAY, data.texture_array().is_some())
+    }
+
     /// The feature subset that affects the prepass: only the vertex stage (`deform`)
-    /// matters — `fs_prepass` ignores every shading feature — so collapsing to this
-    /// keeps the prepass to a single module per deform-ness.
+    /// matters — `fs_prepass` ignores every shading feature (and the vertex colors)
+    /// — so collapsing to this keeps the prepass to a single module per deform-ness.
+    /// `texture_array` is kept as it selects the layout of the texture bind group.
     #[inline]
     fn prepass_key(self) -> Self {
-        ShaderFeatures(self.0 & Self::DEFORM)
+        ShaderFeatures(self.0 & (Self::DEFORM | Self::TEXTURE_ARRAY))
+    }
+}
+
+/// The per-object state selecting the [`ObjectMaterial`] pipelines an object is
+/// drawn with: its shader features, culling mode, front face, wireframe depth
+/// state and deformation. The frame-wide features (shadows, IBL, fog, …) are the
+/// same for every object of a frame and left out.
+///
+/// Objects with equal keys are drawn with the same pipelines, which is what
+/// [`Object3d::draw_sort_key`](crate::scene::Object3d::draw_sort_key) groups the
+/// draws of a scene by.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct PipelineVariantKey {
+    features: u32,
+    cull: bool,
+    clockwise: bool,
+    deform: bool,
+    wireframe: WireframeDepth,
+}
+
+impl PipelineVariantKey {
+    pub(crate) fn of(data: &ObjectData3d, has_vertex_colors: bool) -> Self {
+        PipelineVariantKey {
+            features: ShaderFeatures::of_object(data, has_vertex_colors).0,
+            cull: data.backface_culling_enabled(),
+            clockwise: data.effective_front_face() == wgpu::FrontFace::Cw,
+            deform: data.deform_bind_group().is_some(),
+            wireframe: WireframeDepth::of(data),
+        }
+    }
+}
+
+/// Extracts the segments drawn by the wireframe of a mesh.
+///
+/// Each edge shared by several faces is emitted once. Vertices are matched by
+/// position, so meshes that duplicate vertices along hard edges (e.g. for flat
+/// normals) are deduplicated as well. With `feature_angle` (radians), only the
+/// boundary and non-manifold edges, and the edges whose two adjacent faces'
+/// normals differ by more than that angle, are kept.
+fn wireframe_edges(
+    coords: &[Vec3],
+    faces: &[[VertexIndex; 3]],
+    feature_angle: Option<f32>,
+) -> Vec<(Vec3, Vec3)> {
+    let mut canonical: HashMap<[u32; 3], u32> = HashMap::new();
+    let weld: Vec<u32> = coords
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            *canonical
+                .entry(p.to_array().map(f32::to_bits))
+                .or_insert(i as u32)
+        })
+        .collect();
+
+    // (edge, first adjacent face normal, second adjacent face normal, face count)
+    let mut index: HashMap<[u32; 2], usize> = HashMap::new();
+    let mut found: Vec<([u32; 2], Vec3, Vec3, u32)> = Vec::new();
+
+    for face in faces {
+        if face.iter().any(|i| *i as usize >= coords.len()) {
+            continue;
+        }
+
+        let [a, b, c] = face.map(|i| coords[i as usize]);
+        let normal = (b - a).cross(c - a).normalize_or_zero();
+
+        for e in 0..3 {
+            let i = weld[face[e] as usize];
+            let j = weld[face[(e + 1) % 3] as usize];
+            if i == j {
+                continue;
+            }
+
+            let key = if i < j { [i, j] } else { [j, i] };
+            match index.get(&key) {
+                Some(&k) => {
+                    let entry = &mut found[k];
+                    if entry.3 == 1 {
+                        entry.2 = normal;
+                    }
+                    entry.3 += 1;
+                }
+                None => {
+                    let _ = index.insert(key, found.len());
+                    found.push((key, normal, normal, 1));
+                }
+            }
+        }
     }
+
+    let min_cos = feature_angle.map(f32::cos);
+    found
+        .into_iter()
+        .filter(|(_, n1, n2, count)| match min_cos {
+            Some(min_cos) => *count != 2 || n1.dot(*n2) < min_cos,
+            None => true,
+        })
+        .map(|(e, ..)| (coords[e[0] as usize], coords[e[1] as usize]))
+        .collect()
 }
 
 /// Compiles `default.wgsl` to specialized WGSL for `features`, via WESL conditional
@@ -732,14 +933,18 @@ fn compile_object_wgsl(features: ShaderFeatures) -> String {
 }
 
 /// Which surface pipeline to build from a compiled module (the opaque/OIT cull and
-/// no-cull variants, plus the shared depth/G-buffer prepass).
+/// no-cull variants, plus the shared depth/G-buffer prepass). The `Cw` variants
+/// cull the faces of objects whose front faces wind clockwise.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 enum PipelineKind {
     OpaqueCull,
+    OpaqueCullCw,
     OpaqueNoCull,
     OitCull,
+    OitCullCw,
     OitNoCull,
     Prepass,
+    PrepassCw,
 }
 
 /// The vertex buffer layouts shared by the opaque and OIT surface pipelines.
@@ -750,6 +955,9 @@ enum PipelineKind {
 ///
 /// We use separate buffers for instance data (positions, colors, deformations)
 /// instead of interleaving them, to avoid per-frame data conversion overhead.
+///
+/// The `vertex_color` and `texture_array` variants append more buffers, see
+/// [`surface_vertex_buffers`].
 fn surface_vertex_buffer_layouts() -> [wgpu::VertexBufferLayout<'static>; 6] {
     // Buffer 0: Vertex positions
     const POSITIONS: [wgpu::VertexAttribute; 1] = [wgpu::VertexAttribute {
@@ -835,6 +1043,54 @@ fn surface_vertex_buffer_layouts() -> [wgpu::VertexBufferLayout<'static>; 6] {
     ]
 }
 
+/// Buffer 6 of the `vertex_color` variants: per-vertex colors (`[f32; 4]`).
+const VERTEX_COLORS_LAYOUT: wgpu::VertexBufferLayout<'static> = wgpu::VertexBufferLayout {
+    array_stride: std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+    step_mode: wgpu::VertexStepMode::Vertex,
+    attributes: &[wgpu::VertexAttribute {
+        offset: 0,
+        shader_location: 8,
+        format: wgpu::VertexFormat::Float32x4,
+    }],
+};
+
+/// Buffer of the `texture_array` variants after the vertex colors (if any):
+/// per-instance texture-array layers (`f32`).
+const INSTANCE_LAYERS_LAYOUT: wgpu::VertexBufferLayout<'static> = wgpu::VertexBufferLayout {
+    array_stride: std::mem::size_of::<f32>() as wgpu::BufferAddress,
+    step_mode: wgpu::VertexStepMode::Instance,
+    attributes: &[wgpu::VertexAttribute {
+        offset: 0,
+        shader_location: 9,
+        format: wgpu::VertexFormat::Float32,
+    }],
+};
+
+/// The vertex buffer layouts of a surface pipeline for `features`: the shared
+/// ones, then the per-vertex color stream of the `vertex_color` variant (buffer 6),
+/// then the per-instance layer stream of the `texture_array` variant (buffer 6 or
+/// 7, see `instance_layers_slot`).
+fn surface_vertex_buffers(features: ShaderFeatures) -> Vec<wgpu::VertexBufferLayout<'static>> {
+    let mut buffers = surface_vertex_buffer_layouts().to_vec();
+    if features.has(ShaderFeatures::VERTEX_COLOR) {
+        buffers.push(VERTEX_COLORS_LAYOUT);
+    }
+    if features.has(ShaderFeatures::TEXTURE_ARRAY) {
+        buffers.push(INSTANCE_LAYERS_LAYOUT);
+    }
+    buffers
+}
+
+/// The vertex buffer slot of the per-instance layers in the `texture_array`
+/// variant `features`.
+fn instance_layers_slot(features: ShaderFeatures) -> u32 {
+    if features.has(ShaderFeatures::VERTEX_COLOR) {
+        7
+    } else {
+        6
+    }
+}
+
 impl Default for ObjectMaterial {
     fn default() -> Self {
         Self::new()
@@ -1171,33 +1427,46 @@ impl ObjectMaterial {
         // 0/1 albedo, 2/3 normal, 4/5 metallic-roughness, 6/7 ao, 8/9 emissive.
         // 7 texture+sampler pairs (bindings 0..13): albedo(0/1), normal(2/3),
         // metallic-roughness(4/5), ao(6/7), emissive(8/9), height(10/11), and the
-        // per-object planar-reflection texture(12/13).
-        let texture_entries: Vec<wgpu::BindGroupLayoutEntry> = (0..7u32)
-            .flat_map(|i| {
-                [
-                    wgpu::BindGroupLayoutEntry {
-                        binding: i * 2,
-                        visibility: wgpu::ShaderStages::FRAGMENT,
-                        ty: wgpu::BindingType::Texture {
-                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                            view_dimension: wgpu::TextureViewDimension::D2,
-                            multisampled: false,
+        // per-object planar-reflection texture(12/13). The `texture_array` variants
+        // bind the per-instance albedo texture array at 0/1 instead of the albedo,
+        // so they don't need more textures than WebGL2 allows per stage.
+        let texture_entries = |albedo_dimension| -> Vec<wgpu::BindGroupLayoutEntry> {
+            (0..7u32)
+                .flat_map(|i| {
+                    [
+                        wgpu::BindGroupLayoutEntry {
+                            binding: i * 2,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Texture {
+                                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                                view_dimension: if i == 0 {
+                                    albedo_dimension
+                                } else {
+                                    wgpu::TextureViewDimension::D2
+                                },
+                                multisampled: false,
+                            },
+                            count: None,
                         },
-                        count: None,
-                    },
-                    wgpu::BindGroupLayoutEntry {
-                        binding: i * 2 + 1,
-                        visibility: wgpu::ShaderStages::FRAGMENT,
-                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
-                        count: None,
-                    },
-                ]
-            })
-            .collect();
+                        wgpu::BindGroupLayoutEntry {
+                            binding: i * 2 + 1,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                            count: None,
+                        },
+                    ]
+                })
+                .collect()
+        };
         let texture_bind_group_layout =
             ctxt.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 label: Some("object_material_texture_bind_group_layout"),
-                entries: &texture_entries,
+                entries: &texture_entries(wgpu::TextureViewDimension::D2),
+            });
+        let texture_array_bind_group_layout =
+            ctxt.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("object_material_texture_array_bind_group_layout"),
+                entries: &texture_entries(wgpu::TextureViewDimension::D2Array),
             });
 
         // Create default PBR textures
@@ -1232,27 +1501,35 @@ impl ObjectMaterial {
             ],
             immediate_size: 0,
         });
+        let array_pipeline_layout = ctxt.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("object_material_array_pipeline_layout"),
+            bind_group_layouts: &[
+                Some(&frame_bind_group_layout),
+                Some(&object_bind_group_layout),
+                Some(&texture_array_bind_group_layout),
+            ],
+            immediate_size: 0,
+        });
 
         // Shared opaque-surface pipeline builder, parameterized by the pipeline
-        // layout and the (WESL-specialized) shader module. The `skinned` flag is
-        // vestigial — deform data is read from group-4 storage by index, so the
-        // vertex layout is identical; the deform variant differs only in the module +
-        // layout passed. Stored on the material and invoked lazily per
+        // layout and the (WESL-specialized) shader module. Deform data is read from
+        // group-4 storage by index, so the vertex layout is identical for the deform
+        // variant; only the `vertex_color` and `texture_array` variants add vertex
+        // buffers. Stored on the material and invoked lazily per
         // `(features, sample_count)` by `surface_pipeline`.
         let build_opaque = std::rc::Rc::new(
             |layout: &wgpu::PipelineLayout,
              shader: &wgpu::ShaderModule,
-             skinned: bool,
+             features: ShaderFeatures,
              cull_mode: Option<wgpu::Face>,
              label: &'static str,
              sample_count: u32| {
                 let ctxt = Context::get();
                 // The deformed pipelines share the plain vertex layout: skin
                 // joints/weights and morph deltas come from group-4 storage buffers,
-                // not vertex attributes. `skinned` only selects the shader + layout.
-                let _ = skinned;
-                let plain_layouts = surface_vertex_buffer_layouts();
-                let buffers: &[wgpu::VertexBufferLayout] = &plain_layouts;
+                // not vertex attributes.
+                let layouts = surface_vertex_buffers(features);
+                let buffers: &[wgpu::VertexBufferLayout] = &layouts;
                 ctxt.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
                     label: Some(label),
                     layout: Some(layout),
@@ -1304,17 +1581,16 @@ impl ObjectMaterial {
         let build_oit = std::rc::Rc::new(
             |layout: &wgpu::PipelineLayout,
              shader: &wgpu::ShaderModule,
-             skinned: bool,
+             features: ShaderFeatures,
              cull_mode: Option<wgpu::Face>,
              label: &'static str,
              sample_count: u32| {
                 let ctxt = Context::get();
                 // The deformed pipelines share the plain vertex layout: skin
                 // joints/weights and morph deltas come from group-4 storage buffers,
-                // not vertex attributes. `skinned` only selects the shader + layout.
-                let _ = skinned;
-                let plain_layouts = surface_vertex_buffer_layouts();
-                let buffers: &[wgpu::VertexBufferLayout] = &plain_layouts;
+                // not vertex attributes.
+                let layouts = surface_vertex_buffers(features);
+                let buffers: &[wgpu::VertexBufferLayout] = &layouts;
                 ctxt.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
                     label: Some(label),
                     layout: Some(layout),
@@ -1393,15 +1669,15 @@ impl ObjectMaterial {
         let build_prepass = std::rc::Rc::new(
             |layout: &wgpu::PipelineLayout,
              shader: &wgpu::ShaderModule,
-             skinned: bool,
+             features: ShaderFeatures,
+             cull_mode: wgpu::Face,
              sample_count: u32| {
                 let ctxt = Context::get();
                 // The deformed pipelines share the plain vertex layout: skin
                 // joints/weights and morph deltas come from group-4 storage buffers,
-                // not vertex attributes. `skinned` only selects the shader + layout.
-                let _ = skinned;
-                let plain_layouts = surface_vertex_buffer_layouts();
-                let buffers: &[wgpu::VertexBufferLayout] = &plain_layouts;
+                // not vertex attributes.
+                let layouts = surface_vertex_buffers(features);
+                let buffers: &[wgpu::VertexBufferLayout] = &layouts;
                 ctxt.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
                     label: Some("object_material_prepass_pipeline"),
                     layout: Some(layout),
@@ -1445,7 +1721,7 @@ impl ObjectMaterial {
                         topology: wgpu::PrimitiveTopology::TriangleList,
                         strip_index_format: None,
                         front_face: wgpu::FrontFace::Ccw,
-                        cull_mode: Some(wgpu::Face::Back),
+                        cull_mode: Some(cull_mode),
                         polygon_mode: wgpu::PolygonMode::Fill,
                         unclipped_depth: false,
                         conservative: false,
@@ -1471,21 +1747,31 @@ impl ObjectMaterial {
         // `deform`-featured module. The deform bind-group layout is the shared one
         // from `builtin::deform`, so the per-object bind group also works in the
         // shadow pipelines (which place it at their own group index).
-        let deform_pipeline_layout = {
-            let deform_bind_group_layout = crate::builtin::deform::deform_bind_group_layout();
-            Some(
-                ctxt.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                    label: Some("object_material_deform_pipeline_layout"),
-                    bind_group_layouts: &[
-                        Some(&frame_bind_group_layout),
-                        Some(&object_bind_group_layout),
-                        Some(&texture_bind_group_layout),
-                        Some(&deform_bind_group_layout),
-                    ],
-                    immediate_size: 0,
-                }),
-            )
-        };
+        let deform_bind_group_layout = crate::builtin::deform::deform_bind_group_layout();
+        let deform_pipeline_layout = Some(ctxt.create_pipeline_layout(
+            &wgpu::PipelineLayoutDescriptor {
+                label: Some("object_material_deform_pipeline_layout"),
+                bind_group_layouts: &[
+                    Some(&frame_bind_group_layout),
+                    Some(&object_bind_group_layout),
+                    Some(&texture_bind_group_layout),
+                    Some(&deform_bind_group_layout),
+                ],
+                immediate_size: 0,
+            },
+        ));
+        let array_deform_pipeline_layout = Some(ctxt.create_pipeline_layout(
+            &wgpu::PipelineLayoutDescriptor {
+                label: Some("object_material_array_deform_pipeline_layout"),
+                bind_group_layouts: &[
+                    Some(&frame_bind_group_layout),
+                    Some(&object_bind_group_layout),
+                    Some(&texture_array_bind_group_layout),
+                    Some(&deform_bind_group_layout),
+                ],
+                immediate_size: 0,
+            },
+        ));
 
         // Create wireframe shader and pipelines for lines/points
         // Note: _wireframe_shader, _wireframe_pipeline_layout, and _wireframe_vertex_buffer_layouts
@@ -1584,14 +1870,18 @@ impl ObjectMaterial {
             ctxt.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 label: Some("wireframe_model_bind_group_layout"),
                 entries: &[
-                    // Model uniforms
+                    // Model uniforms (dynamic offset into the shared buffer)
                     wgpu::BindGroupLayoutEntry {
                         binding: 0,
                         visibility: wgpu::ShaderStages::VERTEX,
                         ty: wgpu::BindingType::Buffer {
                             ty: wgpu::BufferBindingType::Uniform,
-                            has_dynamic_offset: false,
-                            min_binding_size: None,
+                            has_dynamic_offset: true,
+                            min_binding_size: std::num::NonZeroU64::new(std::mem::size_of::<
+                                WireframeModelUniforms,
+                            >(
+                            )
+                                as u64),
                         },
                         count: None,
                     },
@@ -1626,8 +1916,8 @@ impl ObjectMaterial {
         );
 
         // Wireframe pipeline, built lazily per MSAA sample count (lines render into
-        // the optionally-multisampled HDR film alongside surfaces).
-        let wireframe_pipeline = PipelineCache::new(move |sample_count| {
+        // the optionally-multisampled HDR film alongside surfaces) and depth state.
+        let build_wireframe = Box::new(move |sample_count: u32, depth: WireframeDepth| {
             let ctxt = Context::get();
             // Instance vertex buffer layouts for wireframe (matching InstancesBuffer)
             let wireframe_instance_buffer_layouts = [
@@ -1727,12 +2017,21 @@ impl ObjectMaterial {
                     unclipped_depth: false,
                     conservative: false,
                 },
+                // Lines drawn on top ignore (and leave untouched) the depth buffer.
                 depth_stencil: Some(wgpu::DepthStencilState {
                     format: Context::depth_format(),
-                    depth_write_enabled: Some(true),
-                    depth_compare: Some(wgpu::CompareFunction::LessEqual),
+                    depth_write_enabled: Some(!depth.on_top),
+                    depth_compare: Some(if depth.on_top {
+                        wgpu::CompareFunction::Always
+                    } else {
+                        wgpu::CompareFunction::LessEqual
+                    }),
                     stencil: wgpu::StencilState::default(),
-                    bias: wgpu::DepthBiasState::default(),
+                    bias: wgpu::DepthBiasState {
+                        constant: depth.constant,
+                        slope_scale: f32::from_bits(depth.slope_scale),
+                        clamp: 0.0,
+                    },
                 }),
                 multisample: multisample_state(sample_count),
                 multiview_mask: None,
@@ -1760,14 +2059,18 @@ impl ObjectMaterial {
             ctxt.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 label: Some("points_model_bind_group_layout"),
                 entries: &[
-                    // Model uniforms
+                    // Model uniforms (dynamic offset into the shared buffer)
                     wgpu::BindGroupLayoutEntry {
                         binding: 0,
                         visibility: wgpu::ShaderStages::VERTEX,
                         ty: wgpu::BindingType::Buffer {
                             ty: wgpu::BufferBindingType::Uniform,
-                            has_dynamic_offset: false,
-                            min_binding_size: None,
+                            has_dynamic_offset: true,
+                            min_binding_size: std::num::NonZeroU64::new(std::mem::size_of::<
+                                PointsModelUniforms,
+                            >(
+                            )
+                                as u64),
                         },
                         count: None,
                     },
@@ -2064,6 +2367,8 @@ impl ObjectMaterial {
         ObjectMaterial {
             pipeline_layout,
             deform_pipeline_layout,
+            array_pipeline_layout,
+            array_deform_pipeline_layout,
             build_opaque,
             build_oit,
             build_prepass,
@@ -2071,13 +2376,15 @@ impl ObjectMaterial {
             surface_pipelines: RefCell::new(HashMap::new()),
             object_bind_group_layout,
             texture_bind_group_layout,
+            texture_array_bind_group_layout,
             default_normal_map,
             default_metallic_roughness_map,
             default_ao_map,
             default_emissive_map,
             default_height_map,
             reflection_sampler,
-            wireframe_pipeline,
+            build_wireframe,
+            wireframe_pipelines: RefCell::new(HashMap::new()),
             wireframe_model_bind_group_layout,
             points_pipeline,
             points_model_bind_group_layout,
@@ -2109,6 +2416,7 @@ impl ObjectMaterial {
             ssao_has: Cell::new(false),
             fog_has: Cell::new(false),
             capture_mode: Cell::new(false),
+            oit_enabled: true,
             cur_probe_view: probe_fallback_view.clone(),
             _probe_fallback_texture: probe_fallback_texture,
             probe_fallback_view,
@@ -2119,6 +2427,9 @@ impl ObjectMaterial {
             object_uniform_buffer,
             object_bind_group: Some(object_bind_group),
             object_bind_group_capacity,
+            wireframe_model_buffer: DynamicUniformBuffer::new("dynamic_wireframe_model_buffer"),
+            points_model_buffer: DynamicUniformBuffer::new("dynamic_points_model_buffer"),
+            model_buffers_gen: 0,
             frame_counter: Cell::new(0),
             last_frame: Cell::new(u64::MAX),
             wireframe_view_uniform_buffer,
@@ -2161,10 +2472,17 @@ impl ObjectMaterial {
             return p.clone();
         }
         let module = self.shader_module(features);
+        let array = features.has(ShaderFeatures::TEXTURE_ARRAY);
         let layout = if features.has(ShaderFeatures::DEFORM) {
-            self.deform_pipeline_layout
-                .as_ref()
-                .expect("deform pipeline layout (native only) must exist for a deform variant")
+            if array {
+                &self.array_deform_pipeline_layout
+            } else {
+                &self.deform_pipeline_layout
+            }
+            .as_ref()
+            .expect("deform pipeline layout (native only) must exist for a deform variant")
+        } else if array {
+            &self.array_pipeline_layout
         } else {
             &self.pipeline_layout
         };
@@ -2172,15 +2490,24 @@ impl ObjectMaterial {
             PipelineKind::OpaqueCull => (self.build_opaque)(
                 layout,
                 &module,
-                false,
+                features,
                 Some(wgpu::Face::Back),
                 "object_material_pipeline_cull",
                 sample_count,
             ),
+            // Culling the front (counter-clockwise) faces keeps the clockwise ones.
+            PipelineKind::OpaqueCullCw => (self.build_opaque)(
+                layout,
+                &module,
+                features,
+                Some(wgpu::Face::Front),
+                "object_material_pipeline_cull_cw",
+                sample_count,
+            ),
             PipelineKind::OpaqueNoCull => (self.build_opaque)(
                 layout,
                 &module,
-                false,
+                features,
                 None,
                 "object_material_pipeline_no_cull",
                 sample_count,
@@ -2188,20 +2515,33 @@ impl ObjectMaterial {
             PipelineKind::OitCull => (self.build_oit)(
                 layout,
                 &module,
-                false,
+                features,
                 Some(wgpu::Face::Back),
                 "object_material_oit_pipeline_cull",
                 sample_count,
             ),
+            PipelineKind::OitCullCw => (self.build_oit)(
+                layout,
+                &module,
+                features,
+                Some(wgpu::Face::Front),
+                "object_material_oit_pipeline_cull_cw",
+                sample_count,
+            ),
             PipelineKind::OitNoCull => (self.build_oit)(
                 layout,
                 &module,
-                false,
+                features,
                 None,
                 "object_material_oit_pipeline_no_cull",
                 sample_count,
             ),
-            PipelineKind::Prepass => (self.build_prepass)(layout, &module, false, sample_count),
+            PipelineKind::Prepass => {
+                (self.build_prepass)(layout, &module, features, wgpu::Face::Back, sample_count)
+            }
+            PipelineKind::PrepassCw => {
+                (self.build_prepass)(layout, &module, features, wgpu::Face::Front, sample_count)
+            }
         };
         let pipeline = Rc::new(pipeline);
         self.surface_pipelines
@@ -2243,36 +2583,26 @@ impl ObjectMaterial {
     /// runtime test, so a stripped feature can never skip work the object needs.
     ///
     /// `use_deform` and `shadows_active` are passed from `render()` (they depend on the
-    /// object's deform bind group and the bound shadow group, respectively).
+    /// object's deform bind group and the bound shadow group, respectively), and
+    /// `has_vertex_colors` on whether the mesh carries per-vertex colors.
     fn object_features(
         &self,
         data: &ObjectData3d,
         use_deform: bool,
         shadows_active: bool,
+        has_vertex_colors: bool,
     ) -> ShaderFeatures {
-        let f = ShaderFeatures::default()
+        let probes = self.probe_count.get() > 0 && !self.capture_mode.get();
+        ShaderFeatures::of_object(data, has_vertex_colors)
             // Structural / capability.
             .with(ShaderFeatures::DEFORM, use_deform)
-            .with(ShaderFeatures::CLUSTERED, self.clustered);
-
-        let probes = self.probe_count.get() > 0 && !self.capture_mode.get();
-        f.with(ShaderFeatures::SHADOWS, shadows_active)
+            .with(ShaderFeatures::CLUSTERED, self.clustered)
+            // Global / per-frame.
+            .with(ShaderFeatures::SHADOWS, shadows_active)
             .with(ShaderFeatures::IBL, self.ibl_has.get())
             .with(ShaderFeatures::PROBES, probes)
             .with(ShaderFeatures::FOG, self.fog_has.get())
             .with(ShaderFeatures::SSAO, self.ssao_has.get())
-            .with(ShaderFeatures::NORMAL_MAP, data.normal_map().is_some())
-            .with(
-                ShaderFeatures::MR_MAP,
-                data.metallic_roughness_map().is_some(),
-            )
-            .with(ShaderFeatures::AO_MAP, data.ao_map().is_some())
-            .with(ShaderFeatures::EMISSIVE_MAP, data.emissive_map().is_some())
-            .with(ShaderFeatures::PARALLAX, data.height_map().is_some())
-            .with(ShaderFeatures::CLEARCOAT, data.clearcoat() > 0.0)
-            .with(ShaderFeatures::ANISOTROPY, data.anisotropy() != 0.0)
-            .with(ShaderFeatures::TRANSMISSION, data.transmission() > 0.0)
-            .with(ShaderFeatures::REFLECTOR, data.reflector().is_some())
     }
 
     /// Builds the combined material-texture bind group (group 2): albedo at
@@ -2394,9 +2724,12 @@ impl ObjectMaterial {
         self.frame_shadow_group = Some(group);
     }
 
+    /// The material-texture bind group, for the `texture_array` variants if
+    /// `albedo` is the object's texture array.
     fn create_texture_bind_group(
         &self,
         albedo: &Texture,
+        albedo_is_array: bool,
         normal_map: &Texture,
         metallic_roughness_map: &Texture,
         ao_map: &Texture,
@@ -2443,14 +2776,31 @@ impl ObjectMaterial {
         });
         ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("object_material_texture_bind_group"),
-            layout: &self.texture_bind_group_layout,
+            layout: if albedo_is_array {
+                &self.texture_array_bind_group_layout
+            } else {
+                &self.texture_bind_group_layout
+            },
             entries: &entries,
         })
     }
 
+    /// The wireframe pipeline for `sample_count` and `depth`, built on first use.
+    fn wireframe_pipeline(
+        &self,
+        sample_count: u32,
+        depth: WireframeDepth,
+    ) -> Rc<wgpu::RenderPipeline> {
+        let key = (sample_count.max(1), depth);
+        self.wireframe_pipelines
+            .borrow_mut()
+            .entry(key)
+            .or_insert_with(|| Rc::new((self.build_wireframe)(key.0, depth)))
+            .clone()
+    }
+
     fn create_wireframe_model_bind_group(
         &self,
-        model_buffer: &wgpu::Buffer,
         edge_buffer: &wgpu::Buffer,
         edge_size: u64,
     ) -> wgpu::BindGroup {
@@ -2461,7 +2811,13 @@ impl ObjectMaterial {
             entries: &[
                 wgpu::BindGroupEntry {
                     binding: 0,
-                    resource: model_buffer.as_entire_binding(),
+                    resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                        buffer: self.wireframe_model_buffer.buffer(),
+                        offset: 0,
+                        size: std::num::NonZeroU64::new(
+                            std::mem::size_of::<WireframeModelUniforms>() as u64,
+                        ),
+                    }),
                 },
                 wgpu::BindGroupEntry {
                     binding: 1,
@@ -2477,7 +2833,6 @@ impl ObjectMaterial {
 
     fn create_points_model_bind_group(
         &self,
-        model_buffer: &wgpu::Buffer,
         vertex_buffer: &wgpu::Buffer,
         vertex_size: u64,
     ) -> wgpu::BindGroup {
@@ -2488,7 +2843,13 @@ impl ObjectMaterial {
             entries: &[
                 wgpu::BindGroupEntry {
                     binding: 0,
-                    resource: model_buffer.as_entire_binding(),
+                    resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                        buffer: self.points_model_buffer.buffer(),
+                        offset: 0,
+                        size: std::num::NonZeroU64::new(
+                            std::mem::size_of::<PointsModelUniforms>() as u64
+                        ),
+                    }),
                 },
                 wgpu::BindGroupEntry {
                     binding: 1,
@@ -2510,19 +2871,30 @@ impl ObjectMaterial {
         self.frame_counter
             .set(self.frame_counter.get().wrapping_add(1));
         self.object_uniform_buffer.clear();
+        self.wireframe_model_buffer.clear();
+        self.points_model_buffer.clear();
         // The group-0 (view+shadow) group is rebuilt with this pass's shadow.
         self.frame_shadow_group = None;
     }
 
     /// Flushes the accumulated object uniforms to the GPU.
     ///
-    /// This performs a single `write_buffer` call with all accumulated object data.
+    /// This performs a single `write_buffer` call per dynamic buffer with all
+    /// accumulated object data.
     /// Should be called after all objects have been processed for the frame.
     pub fn flush(&mut self) {
         let ctxt = Context::get();
 
         self.object_uniform_buffer.flush();
 
+        // The per-object wireframe/points bind groups reference the model buffers:
+        // bump the generation so they get recreated if one of them was reallocated.
+        let wireframe_realloc = self.wireframe_model_buffer.flush();
+        let points_realloc = self.points_model_buffer.flush();
+        if wireframe_realloc || points_realloc {
+            self.model_buffers_gen += 1;
+        }
+
         // Recreate bind group if buffer grew
         if self.object_uniform_buffer.capacity() != self.object_bind_group_capacity {
             self.object_bind_group = Some(ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
@@ -2551,6 +2923,8 @@ impl Material3d for ObjectMaterial {
         self.frame_counter
             .set(self.frame_counter.get().wrapping_add(1));
         self.object_uniform_buffer.clear();
+        self.wireframe_model_buffer.clear();
+        self.points_model_buffer.clear();
         // The group-0 (view+shadow) group is rebuilt with this pass's shadow.
         self.frame_shadow_group = None;
     }
@@ -2614,6 +2988,11 @@ impl Material3d for ObjectMaterial {
                     lights.ambient_color.b,
                     1.0,
                 ],
+                ambient_ground: match lights.ambient_ground_color {
+                    Some(c) => [c.r, c.g, c.b, 1.0],
+                    None => [0.0; 4],
+                },
+                world_up: Context::world_up().extend(0.0).to_array(),
                 fog_color: [
                     lights.fog.color.r,
                     lights.fog.color.g,
@@ -2667,6 +3046,10 @@ impl Material3d for ObjectMaterial {
                     0,
                 ],
                 clip_plane: self.clip_plane.get(),
+                time: {
+                    let time = Context::frame_time();
+                    [time.time, time.delta, 0.0, 0.0]
+                },
                 probes: self.probe_records.get(),
             };
 
@@ -2845,12 +3228,11 @@ impl Material3d for ObjectMaterial {
                 _padding: [0.0; 2],
             };
 
-            // Write model uniforms to GPU (view uniforms are shared and written once per frame)
-            ctxt.write_buffer(
-                &gpu_data.wireframe_model_uniform_buffer,
-                0,
-                bytemuck::bytes_of(&gpu_data.wireframe_model_uniforms),
-            );
+            // Push model uniforms to the dynamic buffer (view uniforms are shared and
+            // written once per frame)
+            gpu_data.wireframe_model_offset = self
+                .wireframe_model_buffer
+                .push(&gpu_data.wireframe_model_uniforms);
         }
 
         // Prepare points model uniforms if needed (view uniforms are shared)
@@ -2878,12 +3260,11 @@ impl Material3d for ObjectMaterial {
                 _padding: [0.0; 2],
             };
 
-            // Write model uniforms to GPU (view uniforms are shared and written once per frame)
-            ctxt.write_buffer(
-                &gpu_data.points_model_uniform_buffer,
-                0,
-                bytemuck::bytes_of(&gpu_data.points_model_uniforms),
-            );
+            // Push model uniforms to the dynamic buffer (view uniforms are shared and
+            // written once per frame)
+            gpu_data.points_model_offset = self
+                .points_model_buffer
+                .push(&gpu_data.points_model_uniforms);
         }
     }
 
@@ -2893,6 +3274,10 @@ impl Material3d for ObjectMaterial {
         true
     }
 
+    fn supports_auto_instancing(&self) -> bool {
+        true
+    }
+
     fn set_environment_lighting(&mut self, env: Option<crate::resource::EnvLight<'_>>) {
         match env {
             Some(e) => {
@@ -2957,6 +3342,10 @@ impl Material3d for ObjectMaterial {
         }
     }
 
+    fn set_oit_enabled(&mut self, enabled: bool) {
+        self.oit_enabled = enabled;
+    }
+
     fn set_capture_mode(&mut self, on: bool) {
         self.capture_mode.set(on);
     }
@@ -3042,6 +3431,14 @@ impl Material3d for ObjectMaterial {
 
         self.object_uniform_buffer.flush();
 
+        // The per-object wireframe/points bind groups reference the model buffers:
+        // bump the generation so they get recreated if one of them was reallocated.
+        let wireframe_realloc = self.wireframe_model_buffer.flush();
+        let points_realloc = self.points_model_buffer.flush();
+        if wireframe_realloc || points_realloc {
+            self.model_buffers_gen += 1;
+        }
+
         // Recreate bind group if buffer grew
         if self.object_uniform_buffer.capacity() != self.object_bind_group_capacity {
             self.object_bind_group = Some(ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
@@ -3088,8 +3485,10 @@ impl Material3d for ObjectMaterial {
         // wireframe/point overlays) draw in the opaque phase; surfaces whose color
         // is translucent draw in the OIT transparent phase. Transparency is keyed
         // off the object color's alpha (per-instance alpha uses this classification
-        // too).
-        let transparent = data.alpha_mode().is_transparent(data.color().a);
+        // too). Without OIT, translucent surfaces are alpha-blended in the opaque
+        // phase instead.
+        let translucent = data.alpha_mode().is_transparent(data.color().a);
+        let transparent = translucent && self.oit_enabled;
         // Refractive glass draws in its own post-resolve pass (so it can sample the
         // scene behind it), not the opaque/prepass passes — otherwise it would be
         // drawn opaque and double-rendered.
@@ -3098,7 +3497,7 @@ impl Material3d for ObjectMaterial {
             && match context.phase {
                 // The prepass rasterizes opaque surfaces only (for SSAO geometry +
                 // the depth the glass pass tests against — glass stays out of it).
-                crate::resource::RenderPhase::Prepass => !transparent && !glass,
+                crate::resource::RenderPhase::Prepass => !translucent && !glass,
                 crate::resource::RenderPhase::Opaque => !transparent && !glass,
                 crate::resource::RenderPhase::Transparent => transparent,
                 crate::resource::RenderPhase::Transmission => glass,
@@ -3128,6 +3527,7 @@ impl Material3d for ObjectMaterial {
         instances.positions.load_to_gpu();
         instances.colors.load_to_gpu();
         instances.deformations.load_to_gpu();
+        instances.texture_layers.load_to_gpu();
 
         // Ensure mesh buffers are on GPU
         mesh.coords().write().unwrap().load_to_gpu();
@@ -3139,6 +3539,16 @@ impl Material3d for ObjectMaterial {
         let uvs_buffer = mesh.uvs().read().unwrap();
         let normals_buffer = mesh.normals().read().unwrap();
         let faces_buffer = mesh.faces().read().unwrap();
+        // Per-vertex colors, bound only by the `vertex_color` variant. Ignored unless
+        // there is exactly one per vertex.
+        if let Some(colors) = mesh.colors() {
+            colors.write().unwrap().load_to_gpu();
+        }
+        let colors_buffer = mesh.colors().map(|c| c.read().unwrap());
+        let colors_buf = colors_buffer
+            .as_ref()
+            .filter(|b| b.len() == coords_buffer.len())
+            .and_then(|b| b.buffer());
 
         let coords_buf = match coords_buffer.buffer() {
             Some(b) => b,
@@ -3170,10 +3580,17 @@ impl Material3d for ObjectMaterial {
             Some(b) => b,
             None => return,
         };
+        let inst_layers_buf = match instances.texture_layers.buffer() {
+            Some(b) => b,
+            None => return,
+        };
 
         // Cache the combined material-texture bind group (albedo + PBR maps),
         // rebuilding it whenever any of the source textures change.
-        let texture_ptr = std::sync::Arc::as_ptr(data.texture()) as usize;
+        // The `texture_array` variants sample the object's texture array instead of
+        // its texture.
+        let albedo = data.texture_array().unwrap_or(data.texture());
+        let texture_ptr = std::sync::Arc::as_ptr(albedo) as usize;
         let normal_map = data.normal_map().unwrap_or(&self.default_normal_map);
         let metallic_roughness_map = data
             .metallic_roughness_map()
@@ -3211,12 +3628,14 @@ impl Material3d for ObjectMaterial {
             || gpu_data.cached_ao_map_ptr != ao_ptr
             || gpu_data.cached_emissive_map_ptr != emissive_ptr
             || gpu_data.cached_height_map_ptr != height_ptr
+            || gpu_data.cached_albedo_is_array != data.texture_array().is_some()
             || gpu_data.cached_reflection_ptr != reflection_ptr
             || gpu_data.cached_reflection_gen != reflection_gen;
 
         if textures_changed {
             gpu_data.texture_bind_group = Some(self.create_texture_bind_group(
-                data.texture(),
+                albedo,
+                data.texture_array().is_some(),
                 normal_map,
                 metallic_roughness_map,
                 ao_map,
@@ -3230,6 +3649,7 @@ impl Material3d for ObjectMaterial {
             gpu_data.cached_ao_map_ptr = ao_ptr;
             gpu_data.cached_emissive_map_ptr = emissive_ptr;
             gpu_data.cached_height_map_ptr = height_ptr;
+            gpu_data.cached_albedo_is_array = data.texture_array().is_some();
             gpu_data.cached_reflection_ptr = reflection_ptr;
             gpu_data.cached_reflection_gen = reflection_gen;
         }
@@ -3254,21 +3674,32 @@ impl Material3d for ObjectMaterial {
 
             // Select the specialized pipeline: which entry/targets (opaque vs. OIT vs.
             // prepass, cull vs. no-cull) and which WESL feature variant.
+            let cw = data.effective_front_face() == wgpu::FrontFace::Cw;
             let kind = match (context.phase, cull) {
+                // The prepass always culls the back faces.
+                (crate::resource::RenderPhase::Prepass, _) if cw => PipelineKind::PrepassCw,
                 (crate::resource::RenderPhase::Prepass, _) => PipelineKind::Prepass,
+                (crate::resource::RenderPhase::Transparent, true) if cw => PipelineKind::OitCullCw,
                 (crate::resource::RenderPhase::Transparent, true) => PipelineKind::OitCull,
                 (crate::resource::RenderPhase::Transparent, false) => PipelineKind::OitNoCull,
                 // Glass reuses the opaque pipeline (it writes opaque color/depth);
                 // the refraction is computed in-shader by sampling the background.
                 (crate::resource::RenderPhase::Transmission, true)
+                | (crate::resource::RenderPhase::Opaque, true)
+                    if cw =>
+                {
+                    PipelineKind::OpaqueCullCw
+                }
+                (crate::resource::RenderPhase::Transmission, true)
                 | (crate::resource::RenderPhase::Opaque, true) => PipelineKind::OpaqueCull,
                 (crate::resource::RenderPhase::Transmission, false)
                 | (crate::resource::RenderPhase::Opaque, false) => PipelineKind::OpaqueNoCull,
             };
-            let mut features = self.object_features(data, use_deform, shadows_active);
+            let mut features =
+                self.object_features(data, use_deform, shadows_active, colors_buf.is_some());
             // The prepass ignores all shading features; collapse to the structural key
             // so it stays a single module per deform-ness.
-            if kind == PipelineKind::Prepass {
+            if matches!(kind, PipelineKind::Prepass | PipelineKind::PrepassCw) {
                 features = features.prepass_key();
             }
             let pipeline = self.surface_pipeline(features, context.sample_count, kind);
@@ -3300,18 +3731,40 @@ impl Material3d for ObjectMaterial {
             render_pass.set_vertex_buffer(3, inst_positions_buf.slice(..));
             render_pass.set_vertex_buffer(4, inst_colors_buf.slice(..));
             render_pass.set_vertex_buffer(5, inst_deformations_buf.slice(..));
+            if features.has(ShaderFeatures::VERTEX_COLOR) {
+                if let Some(colors_buf) = colors_buf {
+                    render_pass.set_vertex_buffer(6, colors_buf.slice(..));
+                }
+            }
+            if features.has(ShaderFeatures::TEXTURE_ARRAY) {
+                render_pass
+                    .set_vertex_buffer(instance_layers_slot(features), inst_layers_buf.slice(..));
+            }
 
             render_pass.set_index_buffer(faces_buf.slice(..), VERTEX_INDEX_FORMAT);
 
             render_pass.draw_indexed(0..mesh.num_indices(), 0, 0..num_instances as u32);
+            crate::renderer::timings::record_draw(
+                mesh.num_indices() as u64 / 3 * num_instances as u64,
+            );
         }
 
         // Render wireframe (thick lines using polyline technique)
         if render_wireframe {
-            // Build wireframe edges from mesh if needed
-            // Use a simple hash of the faces buffer length as a cache key
-            let faces_len = mesh.faces().read().unwrap().len();
-            let faces_hash = faces_len as u64;
+            // Build wireframe edges from mesh if needed, keyed on the content
+            // versions of the coords and faces (and on the feature-edge angle,
+            // which changes the extracted edges)
+            let faces_hash = {
+                use std::collections::hash_map::DefaultHasher;
+                use std::hash::{Hash, Hasher};
+                let mut hasher = DefaultHasher::new();
+                mesh.coords().read().unwrap().version().hash(&mut hasher);
+                mesh.faces().read().unwrap().version().hash(&mut hasher);
+                data.lines_feature_angle()
+                    .map(f32::to_bits)
+                    .hash(&mut hasher);
+                hasher.finish()
+            };
 
             if gpu_data.wireframe_edges.is_none()
                 || gpu_data.wireframe_edges_mesh_hash != faces_hash
@@ -3320,18 +3773,7 @@ impl Material3d for ObjectMaterial {
                 let faces_guard = mesh.faces().read().unwrap();
 
                 if let (Some(coords), Some(faces)) = (coords_guard.data(), faces_guard.data()) {
-                    let mut edges = Vec::with_capacity(faces.len() * 3);
-                    for face in faces.iter() {
-                        let idx_a = face[0] as usize;
-                        let idx_b = face[1] as usize;
-                        let idx_c = face[2] as usize;
-
-                        if idx_a < coords.len() && idx_b < coords.len() && idx_c < coords.len() {
-                            edges.push((coords[idx_a], coords[idx_b]));
-                            edges.push((coords[idx_b], coords[idx_c]));
-                            edges.push((coords[idx_c], coords[idx_a]));
-                        }
-                    }
+                    let edges = wireframe_edges(coords, faces, data.lines_feature_angle());
                     gpu_data.wireframe_edges = Some(edges);
                     gpu_data.wireframe_edges_mesh_hash = faces_hash;
                     // Invalidate model bind group since edges changed
@@ -3339,81 +3781,88 @@ impl Material3d for ObjectMaterial {
                 }
             }
 
-            // Get edges info and convert to GPU format first
-            let (num_edges, gpu_edges) = {
-                let edges = match &gpu_data.wireframe_edges {
-                    Some(e) => e,
-                    None => return,
+            // Only the wireframe draw is skipped when there is nothing to draw (e.g. a
+            // smooth mesh has no feature edges): the points below are still drawn.
+            'wireframe: {
+                let num_edges = match &gpu_data.wireframe_edges {
+                    Some(e) if !e.is_empty() => e.len(),
+                    _ => break 'wireframe,
                 };
-                let num = edges.len();
-                if num == 0 {
-                    return;
-                }
-                let gpu_e: Vec<GpuEdge> = edges
-                    .iter()
-                    .map(|(a, b)| GpuEdge {
-                        point_a: (*a).into(),
-                        _pad_a: 0.0,
-                        point_b: (*b).into(),
-                        _pad_b: 0.0,
-                    })
-                    .collect();
-                (num, gpu_e)
-            };
 
-            // Now we can safely mutate gpu_data since edges borrow is done
-            {
                 // Load wireframe instance buffers to GPU
                 instances.lines_colors.load_to_gpu();
                 instances.lines_widths.load_to_gpu();
 
                 let inst_lines_colors_buf = match instances.lines_colors.buffer() {
                     Some(b) => b,
-                    None => return,
+                    None => break 'wireframe,
                 };
                 let inst_lines_widths_buf = match instances.lines_widths.buffer() {
                     Some(b) => b,
-                    None => return,
+                    None => break 'wireframe,
                 };
 
-                // Ensure edge buffer capacity and upload edges (geometry data)
+                // Ensure edge buffer capacity
                 gpu_data.ensure_edge_buffer_capacity(num_edges);
 
-                ctxt.write_buffer(
-                    &gpu_data.wireframe_edge_buffer,
-                    0,
-                    bytemuck::cast_slice(&gpu_edges),
-                );
-
                 // Update num_edges in model uniforms if it changed from prepare()
                 if gpu_data.wireframe_model_uniforms.num_edges != num_edges as u32 {
                     gpu_data.wireframe_model_uniforms.num_edges = num_edges as u32;
                     ctxt.write_buffer(
-                        &gpu_data.wireframe_model_uniform_buffer,
-                        0,
+                        self.wireframe_model_buffer.buffer(),
+                        gpu_data.wireframe_model_offset as u64,
                         bytemuck::bytes_of(&gpu_data.wireframe_model_uniforms),
                     );
                 }
 
-                // Get or create wireframe model bind group (view bind group is shared)
+                if gpu_data.wireframe_model_bind_group_gen != self.model_buffers_gen {
+                    gpu_data.wireframe_model_bind_group = None;
+                }
+
+                // Upload the edges (geometry data) and create the wireframe model bind
+                // group (view bind group is shared). Rebuilding the edges or growing
+                // their buffer drops the bind group, so this only runs when the
+                // geometry actually changed instead of once per frame.
                 if gpu_data.wireframe_model_bind_group.is_none() {
+                    let gpu_edges: Vec<GpuEdge> = gpu_data
+                        .wireframe_edges
+                        .iter()
+                        .flatten()
+                        .map(|(a, b)| GpuEdge {
+                            point_a: (*a).into(),
+                            _pad_a: 0.0,
+                            point_b: (*b).into(),
+                            _pad_b: 0.0,
+                        })
+                        .collect();
+                    ctxt.write_buffer(
+                        &gpu_data.wireframe_edge_buffer,
+                        0,
+                        bytemuck::cast_slice(&gpu_edges),
+                    );
+
                     let edge_size = (num_edges * std::mem::size_of::<GpuEdge>()) as u64;
                     gpu_data.wireframe_model_bind_group =
                         Some(self.create_wireframe_model_bind_group(
-                            &gpu_data.wireframe_model_uniform_buffer,
                             &gpu_data.wireframe_edge_buffer,
                             edge_size,
                         ));
+                    gpu_data.wireframe_model_bind_group_gen = self.model_buffers_gen;
                 }
 
                 let wireframe_model_bind_group =
                     gpu_data.wireframe_model_bind_group.as_ref().unwrap();
 
-                let wireframe_pipeline = self.wireframe_pipeline.get(context.sample_count);
+                let wireframe_pipeline =
+                    self.wireframe_pipeline(context.sample_count, WireframeDepth::of(data));
                 render_pass.set_pipeline(&wireframe_pipeline);
                 // Use shared view bind group (written once per frame)
                 render_pass.set_bind_group(0, &self.wireframe_view_bind_group, &[]);
-                render_pass.set_bind_group(1, wireframe_model_bind_group, &[]);
+                render_pass.set_bind_group(
+                    1,
+                    wireframe_model_bind_group,
+                    &[gpu_data.wireframe_model_offset],
+                );
 
                 // Set instance vertex buffers (5 total: positions, colors, deformations, lines_colors, lines_widths)
                 render_pass.set_vertex_buffer(0, inst_positions_buf.slice(..));
@@ -3425,21 +3874,16 @@ impl Material3d for ObjectMaterial {
                 // Draw: 6 vertices per edge (computed from vertex_index), num_instances instances
                 let num_vertices = (num_edges * 6) as u32;
                 render_pass.draw(0..num_vertices, 0..num_instances as u32);
+                crate::renderer::timings::record_draw(
+                    num_vertices as u64 / 3 * num_instances as u64,
+                );
             }
         }
 
         // Render points
         if render_points {
-            // Build vertex cache if needed (using mesh coords hash)
-            let coords_hash = {
-                use std::collections::hash_map::DefaultHasher;
-                use std::hash::{Hash, Hasher};
-                let mut hasher = DefaultHasher::new();
-                let coords = mesh.coords().read().unwrap();
-                coords.len().hash(&mut hasher);
-                // Simple hash based on length - vertices rarely change
-                hasher.finish()
-            };
+            // Build vertex cache if needed (using mesh coords version)
+            let coords_hash = mesh.coords().read().unwrap().version();
 
             if gpu_data.points_vertices.is_none()
                 || gpu_data.points_vertices_mesh_hash != coords_hash
@@ -3454,27 +3898,11 @@ impl Material3d for ObjectMaterial {
                 }
             }
 
-            // Get vertices info and convert to GPU format
-            let (num_vertices, gpu_vertices) = {
-                let vertices = match &gpu_data.points_vertices {
-                    Some(v) => v,
-                    None => return,
-                };
-                let num = vertices.len();
-                if num == 0 {
-                    return;
-                }
-                let gpu_v: Vec<GpuVertex> = vertices
-                    .iter()
-                    .map(|p| GpuVertex {
-                        position: (*p).into(),
-                        _pad: 0.0,
-                    })
-                    .collect();
-                (num, gpu_v)
+            let num_vertices = match &gpu_data.points_vertices {
+                Some(v) if !v.is_empty() => v.len(),
+                _ => return,
             };
 
-            // Now we can safely mutate gpu_data since vertices borrow is done
             {
                 // Load point instance buffers to GPU
                 instances.points_colors.load_to_gpu();
@@ -3489,33 +3917,48 @@ impl Material3d for ObjectMaterial {
                     None => return,
                 };
 
-                // Ensure vertex buffer capacity and upload vertices (geometry data)
+                // Ensure vertex buffer capacity
                 gpu_data.ensure_vertex_buffer_capacity(num_vertices);
 
-                ctxt.write_buffer(
-                    &gpu_data.points_vertex_buffer,
-                    0,
-                    bytemuck::cast_slice(&gpu_vertices),
-                );
-
                 // Update num_vertices in model uniforms if it changed from prepare()
                 if gpu_data.points_model_uniforms.num_vertices != num_vertices as u32 {
                     gpu_data.points_model_uniforms.num_vertices = num_vertices as u32;
                     ctxt.write_buffer(
-                        &gpu_data.points_model_uniform_buffer,
-                        0,
+                        self.points_model_buffer.buffer(),
+                        gpu_data.points_model_offset as u64,
                         bytemuck::bytes_of(&gpu_data.points_model_uniforms),
                     );
                 }
 
-                // Get or create points model bind group (view bind group is shared)
+                if gpu_data.points_model_bind_group_gen != self.model_buffers_gen {
+                    gpu_data.points_model_bind_group = None;
+                }
+
+                // Upload the vertices (geometry data) and create the points model bind
+                // group (view bind group is shared), only when they changed (see the
+                // wireframe above).
                 if gpu_data.points_model_bind_group.is_none() {
+                    let gpu_vertices: Vec<GpuVertex> = gpu_data
+                        .points_vertices
+                        .iter()
+                        .flatten()
+                        .map(|p| GpuVertex {
+                            position: (*p).into(),
+                            _pad: 0.0,
+                        })
+                        .collect();
+                    ctxt.write_buffer(
+                        &gpu_data.points_vertex_buffer,
+                        0,
+                        bytemuck::cast_slice(&gpu_vertices),
+                    );
+
                     let vertex_size = (num_vertices * std::mem::size_of::<GpuVertex>()) as u64;
                     gpu_data.points_model_bind_group = Some(self.create_points_model_bind_group(
-                        &gpu_data.points_model_uniform_buffer,
                         &gpu_data.points_vertex_buffer,
                         vertex_size,
                     ));
+                    gpu_data.points_model_bind_group_gen = self.model_buffers_gen;
                 }
 
                 let points_model_bind_group = gpu_data.points_model_bind_group.as_ref().unwrap();
@@ -3524,7 +3967,11 @@ impl Material3d for ObjectMaterial {
                 render_pass.set_pipeline(&points_pipeline);
                 // Use shared view bind group (written once per frame)
                 render_pass.set_bind_group(0, &self.points_view_bind_group, &[]);
-                render_pass.set_bind_group(1, points_model_bind_group, &[]);
+                render_pass.set_bind_group(
+                    1,
+                    points_model_bind_group,
+                    &[gpu_data.points_model_offset],
+                );
 
                 // Set instance vertex buffers (5 total: positions, colors, deformations, points_colors, points_sizes)
                 render_pass.set_vertex_buffer(0, inst_positions_buf.slice(..));
@@ -3536,6 +3983,9 @@ impl Material3d for ObjectMaterial {
                 // Draw: 6 vertices per point (computed from vertex_index), num_instances instances
                 let num_draw_vertices = (num_vertices * 6) as u32;
                 render_pass.draw(0..num_draw_vertices, 0..num_instances as u32);
+                crate::renderer::timings::record_draw(
+                    num_draw_vertices as u64 / 3 * num_instances as u64,
+                );
             }
         }
     }
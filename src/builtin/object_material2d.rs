@@ -1,6 +1,6 @@
 use crate::camera::Camera2d;
 use crate::context::Context;
-use crate::resource::vertex_index::VERTEX_INDEX_FORMAT;
+use crate::resource::vertex_index::{VertexIndex, VERTEX_INDEX_FORMAT};
 use crate::resource::{
     multisample_state, DynamicUniformBuffer, GpuData, GpuMesh2d, Material2d, PipelineCache,
     RenderContext2d, Texture, TextureManager,
@@ -10,7 +10,7 @@ use bytemuck::{Pod, Zeroable};
 use glamx::{Mat2, Mat3, Pose2, Vec2};
 use std::any::Any;
 use std::cell::{Cell, RefCell};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use std::sync::Arc;
 
@@ -154,7 +154,8 @@ pub struct ObjectMaterial2dGpuData {
     wireframe_model_bind_group: Option<wgpu::BindGroup>,
     /// Cached wireframe edges in local coordinates (built lazily from mesh).
     wireframe_edges: Option<Vec<(Vec2, Vec2)>>,
-    /// Hash of mesh faces to detect when edges need rebuilding.
+    /// Hash of the mesh coords and faces versions to detect when edges need
+    /// rebuilding.
     wireframe_edges_mesh_hash: u64,
     /// Cached wireframe view uniforms.
     wireframe_view_uniforms: WireframeViewUniforms,
@@ -173,7 +174,7 @@ pub struct ObjectMaterial2dGpuData {
     points_model_bind_group: Option<wgpu::BindGroup>,
     /// Cached vertices for point rendering (built lazily from mesh).
     points_vertices: Option<Vec<Vec2>>,
-    /// Hash of mesh coords to detect when vertices need rebuilding.
+    /// Version of the mesh coords to detect when vertices need rebuilding.
     points_vertices_mesh_hash: u64,
     /// Cached points view uniforms.
     points_view_uniforms: WireframeViewUniforms,
@@ -392,6 +393,42 @@ impl Default for ObjectMaterial2d {
     }
 }
 
+/// Extracts the segments drawn by the wireframe of a mesh, emitting each edge
+/// shared by several faces once. Vertices are matched by position, so meshes that
+/// duplicate vertices are deduplicated as well.
+fn wireframe_edges(coords: &[Vec2], faces: &[[VertexIndex; 3]]) -> Vec<(Vec2, Vec2)> {
+    let mut canonical: HashMap<[u32; 2], u32> = HashMap::new();
+    let weld: Vec<u32> = coords
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            *canonical
+                .entry(p.to_array().map(f32::to_bits))
+                .or_insert(i as u32)
+        })
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut edges = Vec::new();
+
+    for face in faces {
+        if face.iter().any(|i| *i as usize >= coords.len()) {
+            continue;
+        }
+
+        for e in 0..3 {
+            let i = weld[face[e] as usize];
+            let j = weld[face[(e + 1) % 3] as usize];
+            let key = if i < j { [i, j] } else { [j, i] };
+            if i != j && seen.insert(key) {
+                edges.push((coords[i as usize], coords[j as usize]));
+            }
+        }
+    }
+
+    edges
+}
+
 impl ObjectMaterial2d {
     /// Creates a new `ObjectMaterial2d`.
     pub fn new() -> ObjectMaterial2d {
@@ -1212,9 +1249,16 @@ impl Material2d for ObjectMaterial2d {
         // Wireframe rendering uniforms
         gpu_data.wireframe_prepared = false;
         if data.lines_width() > 0.0 {
-            // Build edges from mesh if needed
-            let faces_len = mesh.faces().read().unwrap().len();
-            let faces_hash = faces_len as u64;
+            // Build edges from mesh if needed, keyed on the content versions of
+            // the coords and faces
+            let faces_hash = {
+                use std::collections::hash_map::DefaultHasher;
+                use std::hash::{Hash, Hasher};
+                let mut hasher = DefaultHasher::new();
+                mesh.coords().read().unwrap().version().hash(&mut hasher);
+                mesh.faces().read().unwrap().version().hash(&mut hasher);
+                hasher.finish()
+            };
 
             if gpu_data.wireframe_edges.is_none()
                 || gpu_data.wireframe_edges_mesh_hash != faces_hash
@@ -1223,17 +1267,7 @@ impl Material2d for ObjectMaterial2d {
                 let faces_guard = mesh.faces().read().unwrap();
 
                 if let (Some(coords), Some(faces)) = (coords_guard.data(), faces_guard.data()) {
-                    let mut edges = Vec::new();
-                    for face in faces.iter() {
-                        let idx_a = face[0] as usize;
-                        let idx_b = face[1] as usize;
-                        let idx_c = face[2] as usize;
-                        if idx_a < coords.len() && idx_b < coords.len() && idx_c < coords.len() {
-                            edges.push((coords[idx_a], coords[idx_b]));
-                            edges.push((coords[idx_b], coords[idx_c]));
-                            edges.push((coords[idx_c], coords[idx_a]));
-                        }
-                    }
+                    let edges = wireframe_edges(coords, faces);
                     gpu_data.wireframe_edges = Some(edges);
                     gpu_data.wireframe_edges_mesh_hash = faces_hash;
                     // Invalidate model bind group since edges changed
@@ -1334,8 +1368,7 @@ impl Material2d for ObjectMaterial2d {
         gpu_data.points_prepared = false;
         if data.points_size() > 0.0 {
             // Build vertex list from mesh if needed
-            let coords_len = mesh.coords().read().unwrap().len();
-            let coords_hash = coords_len as u64;
+            let coords_hash = mesh.coords().read().unwrap().version();
 
             if gpu_data.points_vertices.is_none()
                 || gpu_data.points_vertices_mesh_hash != coords_hash
@@ -1553,6 +1586,9 @@ impl Material2d for ObjectMaterial2d {
             render_pass.set_index_buffer(faces_buf.slice(..), VERTEX_INDEX_FORMAT);
 
             render_pass.draw_indexed(0..mesh.num_indices(), 0, 0..num_instances as u32);
+            crate::renderer::timings::record_draw(
+                mesh.num_indices() as u64 / 3 * num_instances as u64,
+            );
         }
 
         // Wireframe rendering
@@ -1581,6 +1617,7 @@ impl Material2d for ObjectMaterial2d {
             // Draw: 6 vertices per edge, num_instances instances
             let num_vertices = (gpu_data.wireframe_num_edges * 6) as u32;
             render_pass.draw(0..num_vertices, 0..num_instances as u32);
+            crate::renderer::timings::record_draw(num_vertices as u64 / 3 * num_instances as u64);
         }
 
         // Point rendering
@@ -1609,6 +1646,9 @@ impl Material2d for ObjectMaterial2d {
             // Draw: 6 vertices per point, num_instances instances
             let num_draw_vertices = (gpu_data.points_num_vertices * 6) as u32;
             render_pass.draw(0..num_draw_vertices, 0..num_instances as u32);
+            crate::renderer::timings::record_draw(
+                num_draw_vertices as u64 / 3 * num_instances as u64,
+            );
         }
     }
 }
@@ -4,6 +4,11 @@ import package::common::unpack_mat3;
 //
 // Uses instanced rendering where each instance is a line segment.
 // Material data (color, width) is passed per-instance via vertex attributes.
+// Color and width are given at both ends (gradients, tapered arrowheads), and
+// dashes are cut in the fragment shader from the distance along the polyline.
+// Segments are drawn as capsules (round caps and joins) with antialiased edges
+// computed from the pixel distance to the segment, so they look smooth without
+// MSAA.
 
 // View uniforms
 // Note: mat3x3 is stored as array<vec4<f32>, 3> for proper alignment
@@ -26,12 +31,24 @@ struct VertexInput {
     @location(1) width: f32,
     @location(2) point_b: vec2<f32>,
     @location(3) color: vec4<f32>,
+    @location(4) width_b: f32,
+    // Distance along the polyline at point_a and point_b.
+    @location(5) distance: vec2<f32>,
+    @location(6) color_b: vec4<f32>,
+    // (dash length, gap length); solid when either is zero.
+    @location(7) dash: vec2<f32>,
     @builtin(vertex_index) index: u32,
 }
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) color: vec4<f32>,
+    @location(1) distance: f32,
+    @location(2) @interpolate(flat) dash: vec2<f32>,
+    // Pixel offset from the projected point_a, along and across the segment.
+    @location(3) local: vec2<f32>,
+    // (projected length, radius at point_a, radius at point_b), in pixels.
+    @location(4) @interpolate(flat) shape: vec3<f32>,
 }
 
 // Reconstruct mat3x3 from padded vec4 columns
@@ -80,22 +97,47 @@ fn vs_main(vertex: VertexInput) -> VertexOutput {
         y_basis = vec2(0.0, 1.0);
     }
 
-    let line_width = vertex.width;
-
-    // Calculate offset from line center
-    let pt_offset = line_width * (position.x * x_basis + position.y * y_basis);
-    let pt0 = screen0 + pt_offset;
-    let pt1 = screen1 + pt_offset;
-    let pt = mix(pt0, pt1, position.z);
+    // The quad covers the segment's capsule (round caps, which also round the
+    // joins between consecutive segments) plus a one-pixel feather on every side.
+    let radius_a = 0.5 * vertex.width;
+    let radius_b = 0.5 * vertex.width_b;
+    let feather = 1.0;
+    var along: f32;
+    if position.z > 0.5 {
+        along = line_length + radius_b + feather;
+    } else {
+        along = -radius_a - feather;
+    }
+    let across = 2.0 * position.y * (max(radius_a, radius_b) + feather);
+    let pt = screen0 + along * x_basis + across * y_basis;
 
     var out: VertexOutput;
     // Convert back from screen space to clip space
     out.clip_position = vec4((2.0 * pt) / resolution - 1.0, 0.0, 1.0);
-    out.color = vertex.color;
+    out.color = mix(vertex.color, vertex.color_b, position.z);
+    out.distance = mix(vertex.distance.x, vertex.distance.y, along / max(line_length, 0.001));
+    out.dash = vertex.dash;
+    out.local = vec2(along, across);
+    out.shape = vec3(line_length, radius_a, radius_b);
     return out;
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return vec4<f32>(in.color.rgb, in.color.a);
+    if in.dash.x > 0.0 && in.dash.y > 0.0 {
+        let period = in.dash.x + in.dash.y;
+        if in.distance - floor(in.distance / period) * period > in.dash.x {
+            discard;
+        }
+    }
+
+    // Antialiased edges: coverage from the signed pixel distance to the capsule.
+    let t = clamp(in.local.x / max(in.shape.x, 0.001), 0.0, 1.0);
+    let radius = mix(in.shape.y, in.shape.z, t);
+    let dist = length(vec2(in.local.x - t * in.shape.x, in.local.y)) - radius;
+    let coverage = clamp(0.5 - dist, 0.0, 1.0);
+    if coverage <= 0.0 {
+        discard;
+    }
+    return vec4<f32>(in.color.rgb, in.color.a * coverage);
 }
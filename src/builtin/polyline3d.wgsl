@@ -3,6 +3,14 @@
 //
 // Uses instanced rendering where each instance is a line segment.
 // Material data (color, width, depth_bias) is passed per-instance via vertex attributes.
+// Color and width are given at both ends (gradients, tapered arrowheads), and
+// dashes are cut in the fragment shader from the distance along the polyline.
+// Segments are drawn as capsules (round caps and joins) with antialiased edges
+// computed from the pixel distance to the segment, so they look smooth without
+// MSAA. Two consecutive segments of a polyline split the round join between
+// them along the bisector of their angle, so no pixel is drawn twice.
+// The fully covered core of the lines (`fs_core`) writes depth; their
+// antialiased fringe (`fs_fringe`) is drawn afterwards without writing depth.
 // Lines are drawn in world space (no model transform).
 
 // View uniforms (bind group 0)
@@ -23,12 +31,31 @@ struct VertexInput {
     @location(3) depth_bias: f32,
     @location(4) color: vec4<f32>,
     @location(5) perspective: u32,
+    @location(6) width_b: f32,
+    // (dash length, gap length); solid when either is zero.
+    @location(7) dash: vec2<f32>,
+    @location(8) color_b: vec4<f32>,
+    // Distance along the polyline at point_a and point_b.
+    @location(9) distance: vec2<f32>,
+    // The polyline vertices before point_a and after point_b (w = 1), or zero.
+    @location(10) prev: vec4<f32>,
+    @location(11) next: vec4<f32>,
     @builtin(vertex_index) index: u32,
 }
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) color: vec4<f32>,
+    @location(1) distance: f32,
+    @location(2) @interpolate(flat) dash: vec2<f32>,
+    // Pixel offset from the projected point_a, along and across the segment.
+    @location(3) @interpolate(linear) local: vec2<f32>,
+    // (projected length, radius at point_a, radius at point_b), in pixels.
+    @location(4) @interpolate(flat) shape: vec3<f32>,
+    // Normals, in the (along, across) frame, of the bisectors splitting the joins
+    // at point_a (xy) and point_b (zw) with the neighboring segments, pointing
+    // into this segment's side; zero without a neighbor.
+    @location(5) @interpolate(flat) joins: vec4<f32>,
 }
 
 // Clip a point against the near plane
@@ -44,6 +71,36 @@ fn clip_near_plane(a: vec4<f32>, b: vec4<f32>) -> vec4<f32> {
     return a;
 }
 
+// Screen-space position of the world-space point `p`.
+fn to_screen(p: vec3<f32>, resolution: vec2<f32>) -> vec2<f32> {
+    let clip = view.proj * view.view * vec4(p, 1.0);
+    return resolution * (0.5 * clip.xy / clip.w + 0.5);
+}
+
+// Normal of the bisector of the angle between the directions from `joint` to
+// `own` (this segment's other end) and to the neighboring vertex, in the
+// (along, across) frame, pointing into this segment's side. Zero if there is no
+// neighbor, or if it's behind the camera.
+fn join_normal(
+    neighbor: vec4<f32>,
+    joint: vec2<f32>,
+    own: vec2<f32>,
+    x_basis: vec2<f32>,
+    y_basis: vec2<f32>,
+    resolution: vec2<f32>,
+) -> vec2<f32> {
+    if neighbor.w == 0.0 || (view.proj * view.view * vec4(neighbor.xyz, 1.0)).w <= 0.0 {
+        return vec2(0.0);
+    }
+    let other = to_screen(neighbor.xyz, resolution) - joint;
+    let mine = own - joint;
+    if dot(other, other) < 1.0e-6 || dot(mine, mine) < 1.0e-6 {
+        return vec2(0.0);
+    }
+    let n = normalize(mine) - normalize(other);
+    return vec2(dot(n, x_basis), dot(n, y_basis));
+}
+
 @vertex
 fn vs_main(vertex: VertexInput) -> VertexOutput {
     // 6 vertices per line segment forming 2 triangles
@@ -94,24 +151,36 @@ fn vs_main(vertex: VertexInput) -> VertexOutput {
         y_basis = vec2(0.0, 1.0);
     }
 
-    var line_width = vertex.width;
-    var color = vertex.color;
+    var width_a = vertex.width;
+    var width_b = vertex.width_b;
+    var color = mix(vertex.color, vertex.color_b, position.z);
 
     // Perspective mode: width varies with distance (thinner when further away)
     if vertex.perspective != 0u {
-        line_width = line_width / clip.w;
+        width_a = width_a / clip0.w;
+        width_b = width_b / clip1.w;
         // Line thinness fade for anti-aliasing when line becomes sub-pixel
-        if line_width > 0.0 && line_width < 1.0 {
-            color.a = color.a * line_width;
-            line_width = 1.0;
+        let max_width = max(width_a, width_b);
+        if max_width > 0.0 && max_width < 1.0 {
+            color.a = color.a * max_width;
+            width_a = width_a / max_width;
+            width_b = width_b / max_width;
         }
     }
 
-    // Calculate offset from line center
-    let pt_offset = line_width * (position.x * x_basis + position.y * y_basis);
-    let pt0 = screen0 + pt_offset;
-    let pt1 = screen1 + pt_offset;
-    let pt = mix(pt0, pt1, position.z);
+    // The quad covers the segment's capsule (round caps, which also round the
+    // joins between consecutive segments) plus a one-pixel feather on every side.
+    let radius_a = 0.5 * width_a;
+    let radius_b = 0.5 * width_b;
+    let feather = 1.0;
+    var along: f32;
+    if position.z > 0.5 {
+        along = line_length + radius_b + feather;
+    } else {
+        along = -radius_a - feather;
+    }
+    let across = 2.0 * position.y * (max(radius_a, radius_b) + feather);
+    let pt = screen0 + along * x_basis + across * y_basis;
 
     // Apply depth bias
     var depth = clip.z;
@@ -127,10 +196,62 @@ fn vs_main(vertex: VertexInput) -> VertexOutput {
     var out: VertexOutput;
     out.clip_position = vec4(clip.w * ((2.0 * pt) / resolution - 1.0), depth, clip.w);
     out.color = color;
+    out.distance = mix(vertex.distance.x, vertex.distance.y, along / max(line_length, 0.001));
+    out.dash = vertex.dash;
+    out.local = vec2(along, across);
+    out.shape = vec3(line_length, radius_a, radius_b);
+    out.joins = vec4(
+        join_normal(vertex.prev, screen0, screen1, x_basis, y_basis, resolution),
+        join_normal(vertex.next, screen1, screen0, x_basis, y_basis, resolution),
+    );
     return out;
 }
 
+// Coverage of the pixel by the segment, in [0, 1], or a negative value if the
+// pixel is outside of it (in a gap between dashes, or on the neighbor's side of a
+// join).
+fn coverage(in: VertexOutput) -> f32 {
+    if in.dash.x > 0.0 && in.dash.y > 0.0 {
+        let period = in.dash.x + in.dash.y;
+        if in.distance - floor(in.distance / period) * period > in.dash.x {
+            return -1.0;
+        }
+    }
+
+    // The join at point_a goes to this segment on its side of the bisector (ties
+    // included), the one at point_b only strictly, so the neighbors, which test
+    // the same bisector the other way around, share every pixel exactly once.
+    if dot(in.local, in.joins.xy) < 0.0 {
+        return -1.0;
+    }
+    let to_b = in.local - vec2(in.shape.x, 0.0);
+    if any(in.joins.zw != vec2(0.0)) && dot(to_b, in.joins.zw) <= 0.0 {
+        return -1.0;
+    }
+
+    // Antialiased edges: coverage from the signed pixel distance to the capsule.
+    let t = clamp(in.local.x / max(in.shape.x, 0.001), 0.0, 1.0);
+    let radius = mix(in.shape.y, in.shape.z, t);
+    let dist = length(vec2(in.local.x - t * in.shape.x, in.local.y)) - radius;
+    return min(0.5 - dist, 1.0);
+}
+
+// The fully covered pixels, which write depth.
 @fragment
-fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+fn fs_core(in: VertexOutput) -> @location(0) vec4<f32> {
+    if coverage(in) < 1.0 {
+        discard;
+    }
     return in.color;
 }
+
+// The partially covered pixels of the antialiased edges, blended without
+// writing depth.
+@fragment
+fn fs_fringe(in: VertexOutput) -> @location(0) vec4<f32> {
+    let coverage = coverage(in);
+    if coverage <= 0.0 || coverage >= 1.0 {
+        discard;
+    }
+    return vec4(in.color.rgb, in.color.a * coverage);
+}
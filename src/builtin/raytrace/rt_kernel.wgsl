@@ -321,8 +321,8 @@ fn env_pdf(rd: vec3<f32>) -> f32 {
 
 // Distance fog, mirroring the rasterizer's `apply_fog`: blends `color` toward the
 // fog color by an amount derived from the camera distance and (optionally) the
-// world height. `fog_params = (mode, param_a, param_b, height_falloff)`.
-fn apply_fog(color: vec3<f32>, view_dist: f32, world_y: f32) -> vec3<f32> {
+// height along the world up axis. `fog_params = (mode, param_a, param_b, height_falloff)`.
+fn apply_fog(color: vec3<f32>, view_dist: f32, height: f32) -> vec3<f32> {
     let mode = frame.fog_params.x;
     if (mode < 0.5) {
         return color;
@@ -341,7 +341,7 @@ fn apply_fog(color: vec3<f32>, view_dist: f32, world_y: f32) -> vec3<f32> {
     }
     let hf = frame.fog_params.w;
     if (hf > 0.0) {
-        f = f * exp(-max(world_y, 0.0) * hf);
+        f = f * exp(-max(height, 0.0) * hf);
     }
     return mix(color, frame.fog_color.rgb, clamp(f, 0.0, 1.0) * frame.fog_color.a);
 }
@@ -719,7 +719,7 @@ struct PathResult {
     // Distance from the camera to the first surface hit, and that hit's world
     // height — used to apply distance fog after the path is traced.
     first_dist: f32,
-    first_world_y: f32,
+    first_height: f32,
     has_first: bool,
 };
 
@@ -839,12 +839,12 @@ fn sample_pixel(gid: vec3<u32>, rng: ptr<function, u32>) -> PathResult {
         }
 
         // Record first-hit guides (albedo + world normal) for the denoiser, plus
-        // the camera distance + world height used for distance fog below.
+        // the camera distance + height along the world up axis used for distance fog below.
         if (!res.has_first) {
             res.first_albedo = s.albedo;
             res.first_normal = n;
             res.first_dist = hit.t;
-            res.first_world_y = p.y;
+            res.first_height = dot(p, frame.world_up.xyz);
             res.has_first = true;
         }
 
@@ -887,7 +887,7 @@ fn sample_pixel(gid: vec3<u32>, rng: ptr<function, u32>) -> PathResult {
     // the rasterizer. Rays that escape to the sky/background are left unfogged, just
     // as the skybox pass draws without fog.
     if (res.has_first) {
-        res.radiance = apply_fog(res.radiance, res.first_dist, res.first_world_y);
+        res.radiance = apply_fog(res.radiance, res.first_dist, res.first_height);
     }
 
     return res;
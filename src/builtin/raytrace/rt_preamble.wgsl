@@ -112,6 +112,8 @@ struct FrameUniforms {
     fog_params: vec4<f32>,
     // Misc flags: x = 1 when some object opts out of casting shadows.
     flags: vec4<u32>,
+    // The world up axis (xyz; w unused), along which fog thins with height.
+    world_up: vec4<f32>,
 };
 
 // Result of a closest-hit query. `valid == false` means the ray escaped.
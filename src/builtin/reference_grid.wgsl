@@ -0,0 +1,117 @@
+// Infinite reference grid.
+//
+// Draws a full-screen triangle after the opaque pass. Each pixel reconstructs its
+// world-space view ray, intersects it with the horizontal plane `y = height`, and
+// shades minor/major grid lines there with screen-space derivative antialiasing.
+// Lines fade out with distance from the camera and the X/Z axes get their own
+// colors. The hit point's depth is written to `frag_depth` so scene geometry
+// occludes the grid (depth test only; the grid never writes depth).
+
+import package::common::fullscreen_triangle_xy;
+
+struct GridUniforms {
+    view_proj: mat4x4<f32>,
+    inv_view_proj: mat4x4<f32>,
+    // (eye.x, eye.y, eye.z, plane height)
+    eye_height: vec4<f32>,
+    // (cell size, major line every N cells, fade distance, line width in pixels)
+    params: vec4<f32>,
+    minor_color: vec4<f32>,
+    major_color: vec4<f32>,
+    x_axis_color: vec4<f32>,
+    z_axis_color: vec4<f32>,
+};
+
+@group(0) @binding(0) var<uniform> u: GridUniforms;
+
+struct VsOut {
+    @builtin(position) pos: vec4<f32>,
+    @location(0) ndc: vec2<f32>,
+};
+
+struct FsOut {
+    @location(0) color: vec4<f32>,
+    @builtin(frag_depth) depth: f32,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vid: u32) -> VsOut {
+    let xy = fullscreen_triangle_xy(vid);
+    var out: VsOut;
+    out.pos = vec4<f32>(xy, 0.0, 1.0);
+    out.ndc = xy;
+    return out;
+}
+
+// Coverage of lines repeating every `spacing` world units along x and z, widened
+// to `width` pixels using the screen-space footprint of the coordinate.
+fn line_coverage(coord: vec2<f32>, spacing: f32, width: f32) -> f32 {
+    let c = coord / spacing;
+    let deriv = max(fwidth(c), vec2<f32>(1.0e-6));
+    let dist = abs(fract(c - 0.5) - 0.5) / deriv;
+    let line = min(dist.x, dist.y);
+    // Fade lines whose cells shrink below a couple of pixels to avoid moiré.
+    let density = clamp(1.0 - max(deriv.x, deriv.y) * 2.0, 0.0, 1.0);
+    return (1.0 - clamp(line / width, 0.0, 1.0)) * density;
+}
+
+// Coverage of the single line at `coord == 0`.
+fn axis_coverage(coord: f32, width: f32) -> f32 {
+    let deriv = max(fwidth(coord), 1.0e-6);
+    return 1.0 - clamp(abs(coord) / deriv / width, 0.0, 1.0);
+}
+
+// Composites `src` (straight alpha, scaled by `coverage`) over `dst`.
+fn over(dst: vec4<f32>, src: vec4<f32>, coverage: f32) -> vec4<f32> {
+    let a = src.a * coverage;
+    let out_a = a + dst.a * (1.0 - a);
+    let rgb = (src.rgb * a + dst.rgb * dst.a * (1.0 - a)) / max(out_a, 1.0e-6);
+    return vec4<f32>(rgb, out_a);
+}
+
+@fragment
+fn fs_main(in: VsOut) -> FsOut {
+    // The clip-space z range of kiss3d's perspective cameras is GL-style [-1, 1];
+    // any two points along the pixel's ray work for the reconstruction.
+    let near4 = u.inv_view_proj * vec4<f32>(in.ndc, -1.0, 1.0);
+    let far4 = u.inv_view_proj * vec4<f32>(in.ndc, 1.0, 1.0);
+    let near = near4.xyz / near4.w;
+    let far = far4.xyz / far4.w;
+    let dir = far - near;
+
+    // Keep derivatives well-defined in the whole quad: compute everything, then
+    // discard at the end.
+    let height = u.eye_height.w;
+    let t = (height - near.y) / select(dir.y, 1.0e-6, abs(dir.y) < 1.0e-6);
+    let hit = near + dir * t;
+
+    let clip = u.view_proj * vec4<f32>(hit, 1.0);
+    let depth = clip.z / clip.w;
+
+    let cell = u.params.x;
+    let major = cell * max(u.params.y, 1.0);
+    let width = u.params.w;
+
+    let minor_cov = line_coverage(hit.xz, cell, width);
+    let major_cov = line_coverage(hit.xz, major, width * 1.5);
+
+    var color = vec4<f32>(0.0);
+    color = over(color, u.minor_color, minor_cov);
+    color = over(color, u.major_color, major_cov);
+    color = over(color, u.x_axis_color, axis_coverage(hit.z, width * 2.0));
+    color = over(color, u.z_axis_color, axis_coverage(hit.x, width * 2.0));
+
+    let fade_dist = u.params.z;
+    let planar = length(hit.xz - u.eye_height.xz);
+    let fade = 1.0 - smoothstep(fade_dist * 0.5, fade_dist, planar);
+    let alpha = color.a * fade;
+
+    if t <= 0.0 || depth < 0.0 || depth > 1.0 || alpha <= 0.001 {
+        discard;
+    }
+
+    var out: FsOut;
+    out.color = vec4<f32>(color.rgb, alpha);
+    out.depth = depth;
+    return out;
+}
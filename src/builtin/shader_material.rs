@@ -0,0 +1,666 @@
+use crate::camera::Camera3d;
+use crate::context::Context;
+use crate::light::LightCollection;
+use crate::resource::vertex_index::VERTEX_INDEX_FORMAT;
+use crate::resource::{
+    multisample_state, DynamicUniformBuffer, GpuData, GpuMesh3d, Material3d, PipelineCache,
+    RenderContext,
+};
+use crate::scene::{InstancesBuffer3d, ObjectData3d};
+use bytemuck::{Pod, Zeroable};
+use glamx::{Pose3, Vec3};
+use std::any::Any;
+use std::cell::Cell;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Frame-level uniforms (view, projection, eye and time).
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct FrameUniforms {
+    view: [[f32; 4]; 4],
+    proj: [[f32; 4]; 4],
+    eye_time: [f32; 4],
+}
+
+/// Object-level uniforms (transform, scale, color).
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct ObjectUniforms {
+    transform: [[f32; 4]; 4],
+    scale: [[f32; 4]; 3], // mat3x3 padded to mat3x4 for alignment
+    color: [f32; 4],
+}
+
+/// Per-object GPU data for ShaderMaterial.
+pub struct ShaderMaterialGpuData {
+    /// Offset into the shared dynamic object uniform buffer.
+    object_uniform_offset: Option<u32>,
+}
+
+impl GpuData for ShaderMaterialGpuData {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// The culling (for both front-face windings) and non-culling pipelines built
+/// from one shader source.
+struct ShaderPipelines {
+    cull: PipelineCache,
+    cull_cw: PipelineCache,
+    no_cull: PipelineCache,
+}
+
+/// The shader file a [`ShaderMaterial`] reloads from.
+#[cfg(not(target_arch = "wasm32"))]
+struct WatchedFile {
+    path: PathBuf,
+    /// Modification time of the file when it was last loaded.
+    modified: Option<std::time::SystemTime>,
+    loaded_once: bool,
+    last_check: web_time::Instant,
+}
+
+/// How often a watched shader file is checked for modifications.
+#[cfg(not(target_arch = "wasm32"))]
+const WATCH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// A material running a user-provided WGSL shader.
+///
+/// The shader must follow the interface of [`SHADER_MATERIAL_SRC`] (the default
+/// shader, a good starting point): frame uniforms at group 0 (view, projection,
+/// eye position and time), object uniforms at group 1 (transform, scale, color),
+/// positions, normals and texture coordinates at vertex locations 0, 1 and 2,
+/// the instance position, color and deformation columns at locations 3 to 7,
+/// and `vs_main` / `fs_main` entry points writing the linear HDR scene color.
+///
+/// The material must be registered with the
+/// [`MaterialManager3d`](crate::resource::MaterialManager3d) and assigned by
+/// name: its object uniforms are uploaded, and its shader file reloaded, by the
+/// manager at each frame.
+///
+/// On native platforms, [`watch`](Self::watch) loads the shader from a file and
+/// reloads it whenever the file changes, so shaders can be tweaked while the
+/// application runs.
+///
+/// # Example
+/// ```no_run
+/// # use kiss3d::prelude::*;
+/// # use kiss3d::builtin::ShaderMaterial;
+/// # use kiss3d::resource::MaterialManager3d;
+/// # #[kiss3d::main]
+/// # async fn main() {
+/// # let mut window = Window::new("Example").await;
+/// # let mut camera = OrbitCamera3d::default();
+/// # let mut scene = SceneNode3d::empty();
+/// let _ = MaterialManager3d::get_global_manager(|mm| {
+///     mm.register("my_material", ShaderMaterial::watch("shaders/my_material.wgsl"))
+/// });
+/// scene.add_cube(1.0, 1.0, 1.0).set_material_with_name("my_material");
+///
+/// // Edit and save `my_material.wgsl`: the cube picks up the changes.
+/// while window.render_3d(&mut scene, &mut camera).await {}
+/// # }
+/// ```
+pub struct ShaderMaterial {
+    source: String,
+    pipelines: ShaderPipelines,
+    pipeline_layout: Rc<wgpu::PipelineLayout>,
+    object_bind_group_layout: wgpu::BindGroupLayout,
+    #[cfg(not(target_arch = "wasm32"))]
+    watched: Option<WatchedFile>,
+
+    // === Dynamic uniform buffer system ===
+    /// Shared frame uniform buffer
+    frame_uniform_buffer: wgpu::Buffer,
+    /// Shared frame bind group
+    frame_bind_group: wgpu::BindGroup,
+    /// Dynamic buffer for object uniforms
+    object_uniform_buffer: DynamicUniformBuffer<ObjectUniforms>,
+    /// Bind group for object uniforms (recreated when buffer grows)
+    object_bind_group: Option<wgpu::BindGroup>,
+    /// Frame counter for detecting new frames
+    frame_counter: Cell<u64>,
+    /// Last frame we processed
+    last_frame: Cell<u64>,
+    /// Whether the missing registration was already reported.
+    warned_unregistered: bool,
+}
+
+impl Default for ShaderMaterial {
+    fn default() -> Self {
+        Self::new(SHADER_MATERIAL_SRC)
+    }
+}
+
+impl ShaderMaterial {
+    /// Creates a material running the WGSL shader `source`.
+    ///
+    /// # Panics
+    /// If the shader does not compile or does not match the material's interface.
+    pub fn new(source: &str) -> ShaderMaterial {
+        Self::try_new(source).unwrap_or_else(|e| panic!("invalid ShaderMaterial shader: {}", e))
+    }
+
+    /// Creates a material running the WGSL shader stored at `path`, and reloads it
+    /// whenever the file is modified.
+    ///
+    /// When the file can't be read or its shader doesn't compile, the error is
+    /// logged with `log::error!` and the previous shader (or [`SHADER_MATERIAL_SRC`]
+    /// initially) keeps being used until the file is fixed.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn watch<P: AsRef<Path>>(path: P) -> ShaderMaterial {
+        let path = path.as_ref().to_path_buf();
+        let mut material = Self::new(SHADER_MATERIAL_SRC);
+        material.watched = Some(WatchedFile {
+            path,
+            modified: None,
+            loaded_once: false,
+            last_check: web_time::Instant::now(),
+        });
+        material.reload_if_modified();
+        material
+    }
+
+    /// The WGSL source of the shader currently in use.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Replaces the shader with `source`. On error, the current shader is kept and
+    /// the compilation error is returned.
+    pub fn set_source(&mut self, source: &str) -> Result<(), String> {
+        self.pipelines = build_pipelines(&self.pipeline_layout, source)?;
+        self.source = source.to_string();
+        Ok(())
+    }
+
+    /// Creates a material running the WGSL shader `source`, or returns the
+    /// compilation error if the shader is invalid.
+    ///
+    /// Errors are only detected on native platforms: on the web they are reported
+    /// by the browser console.
+    pub fn try_new(source: &str) -> Result<ShaderMaterial, String> {
+        let ctxt = Context::get();
+
+        let uniform_entry = |has_dynamic_offset| wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let frame_bind_group_layout =
+            ctxt.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("shader_material_frame_bind_group_layout"),
+                entries: &[uniform_entry(false)],
+            });
+        let object_bind_group_layout =
+            ctxt.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("shader_material_object_bind_group_layout"),
+                entries: &[uniform_entry(true)],
+            });
+
+        let pipeline_layout = Rc::new(ctxt.create_pipeline_layout(
+            &wgpu::PipelineLayoutDescriptor {
+                label: Some("shader_material_pipeline_layout"),
+                bind_group_layouts: &[
+                    Some(&frame_bind_group_layout),
+                    Some(&object_bind_group_layout),
+                ],
+                immediate_size: 0,
+            },
+        ));
+        let pipelines = build_pipelines(&pipeline_layout, source)?;
+
+        let frame_uniform_buffer = ctxt.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("shader_material_frame_uniform_buffer"),
+            size: std::mem::size_of::<FrameUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let frame_bind_group = ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("shader_material_frame_bind_group"),
+            layout: &frame_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: frame_uniform_buffer.as_entire_binding(),
+            }],
+        });
+        let object_uniform_buffer = DynamicUniformBuffer::<ObjectUniforms>::new(
+            "shader_material_dynamic_object_uniform_buffer",
+        );
+
+        let mut material = ShaderMaterial {
+            source: source.to_string(),
+            pipelines,
+            pipeline_layout,
+            object_bind_group_layout,
+            #[cfg(not(target_arch = "wasm32"))]
+            watched: None,
+            frame_uniform_buffer,
+            frame_bind_group,
+            object_uniform_buffer,
+            object_bind_group: None,
+            frame_counter: Cell::new(0),
+            last_frame: Cell::new(u64::MAX),
+            warned_unregistered: false,
+        };
+        material.object_bind_group = Some(material.create_object_bind_group());
+        Ok(material)
+    }
+
+    fn create_object_bind_group(&self) -> wgpu::BindGroup {
+        Context::get().create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("shader_material_dynamic_object_bind_group"),
+            layout: &self.object_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer: self.object_uniform_buffer.buffer(),
+                    offset: 0,
+                    size: std::num::NonZeroU64::new(self.object_uniform_buffer.aligned_size()),
+                }),
+            }],
+        })
+    }
+
+    /// Recompiles the watched shader file if it changed since it was last loaded.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn reload_if_modified(&mut self) {
+        let watched = match self.watched.as_mut() {
+            Some(watched) => watched,
+            None => return,
+        };
+        watched.last_check = web_time::Instant::now();
+        let modified = std::fs::metadata(&watched.path)
+            .and_then(|m| m.modified())
+            .ok();
+        if watched.loaded_once && modified == watched.modified {
+            return;
+        }
+        let first_attempt = !watched.loaded_once;
+        watched.loaded_once = true;
+        watched.modified = modified;
+        let path = watched.path.clone();
+
+        match std::fs::read_to_string(&path) {
+            Ok(source) => match self.set_source(&source) {
+                Ok(()) if !first_attempt => log::info!("Reloaded shader {}", path.display()),
+                Ok(()) => {}
+                Err(e) => log::error!(
+                    "Failed to compile shader {}, keeping the previous one:\n{}",
+                    path.display(),
+                    e
+                ),
+            },
+            Err(e) => log::error!("Failed to read shader {}: {}", path.display(), e),
+        }
+    }
+}
+
+/// Compiles `source` and builds its pipelines, returning the validation error
+/// instead of panicking when the shader is invalid (on native platforms).
+fn build_pipelines(
+    pipeline_layout: &Rc<wgpu::PipelineLayout>,
+    source: &str,
+) -> Result<ShaderPipelines, String> {
+    let ctxt = Context::get();
+    #[cfg(not(target_arch = "wasm32"))]
+    let scope = ctxt.device.push_error_scope(wgpu::ErrorFilter::Validation);
+
+    let shader = ctxt.create_shader_module(Some("shader_material_shader"), source);
+    let pipeline_layout = pipeline_layout.clone();
+    let build = Rc::new(
+        move |cull_mode: Option<wgpu::Face>,
+              front_face: wgpu::FrontFace,
+              label: &'static str,
+              sample_count: u32| {
+            let ctxt = Context::get();
+            let vertex_buffer_layouts = [
+                // Vertex positions
+                wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &[wgpu::VertexAttribute {
+                        offset: 0,
+                        shader_location: 0,
+                        format: wgpu::VertexFormat::Float32x3,
+                    }],
+                },
+                // Normals
+                wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &[wgpu::VertexAttribute {
+                        offset: 0,
+                        shader_location: 1,
+                        format: wgpu::VertexFormat::Float32x3,
+                    }],
+                },
+                // Texture coordinates
+                wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &[wgpu::VertexAttribute {
+                        offset: 0,
+                        shader_location: 2,
+                        format: wgpu::VertexFormat::Float32x2,
+                    }],
+                },
+                // Instance positions
+                wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &[wgpu::VertexAttribute {
+                        offset: 0,
+                        shader_location: 3,
+                        format: wgpu::VertexFormat::Float32x3,
+                    }],
+                },
+                // Instance colors
+                wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &[wgpu::VertexAttribute {
+                        offset: 0,
+                        shader_location: 4,
+                        format: wgpu::VertexFormat::Float32x4,
+                    }],
+                },
+                // Instance deformations (the 3 columns of a 3x3 matrix)
+                wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<[f32; 9]>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &[
+                        wgpu::VertexAttribute {
+                            offset: 0,
+                            shader_location: 5,
+                            format: wgpu::VertexFormat::Float32x3,
+                        },
+                        wgpu::VertexAttribute {
+                            offset: 12,
+                            shader_location: 6,
+                            format: wgpu::VertexFormat::Float32x3,
+                        },
+                        wgpu::VertexAttribute {
+                            offset: 24,
+                            shader_location: 7,
+                            format: wgpu::VertexFormat::Float32x3,
+                        },
+                    ],
+                },
+            ];
+
+            ctxt.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_main"),
+                    buffers: &vertex_buffer_layouts,
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some("fs_main"),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: Context::render_format(),
+                        blend: Some(wgpu::BlendState::REPLACE),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face,
+                    cull_mode,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: Context::depth_format(),
+                    depth_write_enabled: Some(true),
+                    depth_compare: Some(wgpu::CompareFunction::Less),
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: multisample_state(sample_count),
+                multiview_mask: None,
+                cache: None,
+            })
+        },
+    );
+
+    let pipelines = ShaderPipelines {
+        cull: PipelineCache::new({
+            let build = build.clone();
+            move |sc| {
+                build(
+                    Some(wgpu::Face::Back),
+                    wgpu::FrontFace::Ccw,
+                    "shader_material_pipeline_cull",
+                    sc,
+                )
+            }
+        }),
+        cull_cw: PipelineCache::new({
+            let build = build.clone();
+            move |sc| {
+                build(
+                    Some(wgpu::Face::Back),
+                    wgpu::FrontFace::Cw,
+                    "shader_material_pipeline_cull_cw",
+                    sc,
+                )
+            }
+        }),
+        no_cull: PipelineCache::new({
+            let build = build.clone();
+            move |sc| {
+                build(
+                    None,
+                    wgpu::FrontFace::Ccw,
+                    "shader_material_pipeline_no_cull",
+                    sc,
+                )
+            }
+        }),
+    };
+    // Build one pipeline right away so interface mismatches between the shader and
+    // the material are reported here rather than at draw time.
+    let _ = pipelines.cull.get(1);
+
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(err) = crate::pollster::block_on(scope.pop()) {
+        return Err(err.to_string());
+    }
+    Ok(pipelines)
+}
+
+impl Material3d for ShaderMaterial {
+    fn create_gpu_data(&self) -> Box<dyn GpuData> {
+        Box::new(ShaderMaterialGpuData {
+            object_uniform_offset: None,
+        })
+    }
+
+    fn begin_frame(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if matches!(&self.watched, Some(w) if w.last_check.elapsed() >= WATCH_INTERVAL) {
+            self.reload_if_modified();
+        }
+
+        self.frame_counter
+            .set(self.frame_counter.get().wrapping_add(1));
+        self.object_uniform_buffer.clear();
+    }
+
+    fn flush(&mut self) {
+        // Flush returns true if buffer was reallocated
+        if self.object_uniform_buffer.flush() {
+            self.object_bind_group = Some(self.create_object_bind_group());
+        }
+    }
+
+    fn prepare(
+        &mut self,
+        pass: usize,
+        transform: Pose3,
+        scale: Vec3,
+        camera: &mut dyn Camera3d,
+        _lights: &LightCollection,
+        data: &ObjectData3d,
+        gpu_data: &mut dyn GpuData,
+        _viewport_width: u32,
+        _viewport_height: u32,
+    ) {
+        let gpu_data = gpu_data
+            .as_any_mut()
+            .downcast_mut::<ShaderMaterialGpuData>()
+            .expect("ShaderMaterial requires ShaderMaterialGpuData");
+
+        if self.frame_counter.get() == 0 && !self.warned_unregistered {
+            self.warned_unregistered = true;
+            log::warn!(
+                "ShaderMaterial used without being registered with the MaterialManager3d: \
+                 its objects are not drawn"
+            );
+        }
+
+        // Frame uniforms are written once, by the first object of the frame.
+        let current_frame = self.frame_counter.get();
+        if current_frame != self.last_frame.get() {
+            self.last_frame.set(current_frame);
+
+            let (view, proj) = camera.view_transform_pair(pass);
+            let eye = camera.eye();
+            let frame_uniforms = FrameUniforms {
+                view: view.to_mat4().to_cols_array_2d(),
+                proj: proj.to_cols_array_2d(),
+                eye_time: [eye.x, eye.y, eye.z, Context::frame_time().time],
+            };
+            Context::get().write_buffer(
+                &self.frame_uniform_buffer,
+                0,
+                bytemuck::bytes_of(&frame_uniforms),
+            );
+        }
+
+        let color = data.color();
+        let object_uniforms = ObjectUniforms {
+            transform: transform.to_mat4().to_cols_array_2d(),
+            scale: [
+                [scale.x, 0.0, 0.0, 0.0],
+                [0.0, scale.y, 0.0, 0.0],
+                [0.0, 0.0, scale.z, 0.0],
+            ],
+            color: [color.r, color.g, color.b, color.a],
+        };
+        gpu_data.object_uniform_offset = Some(self.object_uniform_buffer.push(&object_uniforms));
+    }
+
+    fn render(
+        &mut self,
+        _pass: usize,
+        _transform: Pose3,
+        _scale: Vec3,
+        _camera: &mut dyn Camera3d,
+        _lights: &LightCollection,
+        data: &ObjectData3d,
+        mesh: &mut GpuMesh3d,
+        instances: &mut InstancesBuffer3d,
+        gpu_data: &mut dyn GpuData,
+        render_pass: &mut wgpu::RenderPass<'_>,
+        context: &RenderContext,
+    ) {
+        // Unregistered materials never upload their object uniforms.
+        if !data.surface_rendering_active() || self.frame_counter.get() == 0 {
+            return;
+        }
+
+        let gpu_data = gpu_data
+            .as_any_mut()
+            .downcast_mut::<ShaderMaterialGpuData>()
+            .expect("ShaderMaterial requires ShaderMaterialGpuData");
+        let object_offset = gpu_data
+            .object_uniform_offset
+            .expect("prepare() must be called before render()");
+
+        // Ensure mesh buffers are on GPU
+        mesh.coords().write().unwrap().load_to_gpu();
+        mesh.normals().write().unwrap().load_to_gpu();
+        mesh.uvs().write().unwrap().load_to_gpu();
+        mesh.faces().write().unwrap().load_to_gpu();
+
+        let num_instances = instances.len();
+        instances.positions.load_to_gpu();
+        instances.colors.load_to_gpu();
+        instances.deformations.load_to_gpu();
+        let (inst_positions_buf, inst_colors_buf, inst_deformations_buf) = match (
+            instances.positions.buffer(),
+            instances.colors.buffer(),
+            instances.deformations.buffer(),
+        ) {
+            (Some(p), Some(c), Some(d)) => (p, c, d),
+            _ => return,
+        };
+
+        let coords_buffer = mesh.coords().read().unwrap();
+        let normals_buffer = mesh.normals().read().unwrap();
+        let uvs_buffer = mesh.uvs().read().unwrap();
+        let faces_buffer = mesh.faces().read().unwrap();
+
+        let (coords_buf, normals_buf, uvs_buf, faces_buf) = match (
+            coords_buffer.buffer(),
+            normals_buffer.buffer(),
+            uvs_buffer.buffer(),
+            faces_buffer.buffer(),
+        ) {
+            (Some(c), Some(n), Some(u), Some(f)) => (c, n, u, f),
+            _ => return,
+        };
+
+        let pipeline = if data.backface_culling_enabled() && !context.force_no_cull {
+            match data.effective_front_face() {
+                wgpu::FrontFace::Ccw => self.pipelines.cull.get(context.sample_count),
+                wgpu::FrontFace::Cw => self.pipelines.cull_cw.get(context.sample_count),
+            }
+        } else {
+            self.pipelines.no_cull.get(context.sample_count)
+        };
+        render_pass.set_pipeline(&pipeline);
+        render_pass.set_bind_group(0, &self.frame_bind_group, &[]);
+        render_pass.set_bind_group(
+            1,
+            self.object_bind_group.as_ref().unwrap(),
+            &[object_offset],
+        );
+
+        render_pass.set_vertex_buffer(0, coords_buf.slice(..));
+        render_pass.set_vertex_buffer(1, normals_buf.slice(..));
+        render_pass.set_vertex_buffer(2, uvs_buf.slice(..));
+        render_pass.set_vertex_buffer(3, inst_positions_buf.slice(..));
+        render_pass.set_vertex_buffer(4, inst_colors_buf.slice(..));
+        render_pass.set_vertex_buffer(5, inst_deformations_buf.slice(..));
+        render_pass.set_index_buffer(faces_buf.slice(..), VERTEX_INDEX_FORMAT);
+
+        render_pass.draw_indexed(0..mesh.num_indices(), 0, 0..num_instances as u32);
+        crate::renderer::timings::record_draw(mesh.num_indices() as u64 / 3 * num_instances as u64);
+    }
+}
+
+/// The default [`ShaderMaterial`] shader, documenting the interface user shaders
+/// must follow.
+pub static SHADER_MATERIAL_SRC: &str = include_str!("shader_material.wgsl");
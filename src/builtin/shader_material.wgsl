@@ -0,0 +1,85 @@
+// Default `ShaderMaterial` shader, and the template for user shaders.
+//
+// A `ShaderMaterial` shader must declare the two uniform blocks and the vertex
+// inputs below (unused ones may be left out) and export `vs_main` / `fs_main`.
+// Its fragment output is the linear HDR scene color.
+
+// Bind group 0: Frame uniforms
+struct FrameUniforms {
+    view: mat4x4<f32>,
+    proj: mat4x4<f32>,
+    // World-space camera position (xyz) and seconds since the window was created (w).
+    eye_time: vec4<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> frame: FrameUniforms;
+
+// Bind group 1: Object uniforms
+struct ObjectUniforms {
+    transform: mat4x4<f32>,
+    scale: mat3x3<f32>,
+    color: vec4<f32>,
+}
+
+@group(1) @binding(0)
+var<uniform> object: ObjectUniforms;
+
+// Vertex input
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+}
+
+// Per-instance input (a single identity instance for non-instanced objects)
+struct InstanceInput {
+    @location(3) position: vec3<f32>,
+    @location(4) color: vec4<f32>,
+    @location(5) deformation_0: vec3<f32>,
+    @location(6) deformation_1: vec3<f32>,
+    @location(7) deformation_2: vec3<f32>,
+}
+
+// Vertex output / Fragment input
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_position: vec3<f32>,
+    @location(1) world_normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+    @location(3) color: vec4<f32>,
+}
+
+@vertex
+fn vs_main(vertex: VertexInput, instance: InstanceInput) -> VertexOutput {
+    var out: VertexOutput;
+
+    let deformation = mat3x3<f32>(
+        instance.deformation_0,
+        instance.deformation_1,
+        instance.deformation_2,
+    );
+    let model = object.transform * vec4<f32>(deformation * (object.scale * vertex.position), 1.0);
+    let world = vec4<f32>(instance.position, 0.0) + model;
+    let inv_scale = vec3<f32>(
+        1.0 / object.scale[0][0],
+        1.0 / object.scale[1][1],
+        1.0 / object.scale[2][2],
+    );
+    out.clip_position = frame.proj * frame.view * world;
+    out.world_position = world.xyz;
+    out.world_normal = deformation * (object.transform * vec4<f32>(vertex.normal * inv_scale, 0.0)).xyz;
+    out.uv = vertex.uv;
+    out.color = object.color * instance.color;
+
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Headlight shading of the object color.
+    let n = normalize(in.world_normal);
+    let v = normalize(frame.eye_time.xyz - in.world_position);
+    let diffuse = 0.2 + 0.8 * abs(dot(n, v));
+    return vec4<f32>(in.color.rgb * diffuse, 1.0);
+}
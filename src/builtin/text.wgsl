@@ -4,7 +4,8 @@
 // Bind group 0: Uniforms
 struct TextUniforms {
     inv_size: vec2<f32>,
-    _padding: vec2<f32>,
+    // Translation of the vertices, in pixels.
+    offset: vec2<f32>,
 }
 
 @group(0) @binding(0)
@@ -34,8 +35,8 @@ struct VertexOutput {
 fn vs_main(vertex: VertexInput) -> VertexOutput {
     var out: VertexOutput;
     out.clip_position = vec4<f32>(
-        vertex.position.x * uniforms.inv_size.x - 1.0,
-        vertex.position.y * uniforms.inv_size.y + 1.0,
+        (vertex.position.x + uniforms.offset.x) * uniforms.inv_size.x - 1.0,
+        (vertex.position.y + uniforms.offset.y) * uniforms.inv_size.y + 1.0,
         0.0,  // z=0 is valid in wgpu's [0,1] depth range
         1.0
     );
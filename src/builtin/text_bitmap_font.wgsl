@@ -0,0 +1,66 @@
+// Bitmap font text shader for kiss3d
+// Draws glyphs from the pre-rendered atlas pages of a `BitmapFont`: either plain
+// images, tinted by the text color, or single/multi-channel distance fields.
+
+// Bind group 0: Uniforms
+struct TextUniforms {
+    inv_size: vec2<f32>,
+    // Translation of the vertices, in pixels.
+    offset: vec2<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: TextUniforms;
+
+// Bind group 1: Atlas page and sampler
+@group(1) @binding(0)
+var t_page: texture_2d<f32>;
+@group(1) @binding(1)
+var s_page: sampler;
+
+// Vertex input - interleaved position, UV, and color
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) tex_coord: vec2<f32>,
+    @location(2) color: vec4<f32>,
+}
+
+// Vertex output / Fragment input
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coord: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+@vertex
+fn vs_main(vertex: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(
+        (vertex.position.x + uniforms.offset.x) * uniforms.inv_size.x - 1.0,
+        (vertex.position.y + uniforms.offset.y) * uniforms.inv_size.y + 1.0,
+        0.0,
+        1.0
+    );
+    out.tex_coord = vertex.tex_coord;
+    out.color = vertex.color;
+    return out;
+}
+
+// Plain atlas: the page colors multiplied by the text color.
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(t_page, s_page, in.tex_coord) * in.color;
+}
+
+// Distance-field atlas, with the edge at 0.5. The median of the channels
+// reconstructs sharp corners from multi-channel fields, and is the distance
+// itself for single-channel ones.
+@fragment
+fn fs_distance_field(in: VertexOutput) -> @location(0) vec4<f32> {
+    let texel = textureSample(t_page, s_page, in.tex_coord);
+    let dist = max(min(texel.r, texel.g), min(max(texel.r, texel.g), texel.b));
+    // Half a pixel worth of distance, whatever the on-screen glyph size.
+    let aa = max(fwidth(dist) * 0.5, 1.0e-4);
+    let alpha = smoothstep(0.5 - aa, 0.5 + aa, dist);
+    return vec4<f32>(in.color.rgb, in.color.a * alpha);
+}
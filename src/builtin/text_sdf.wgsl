@@ -0,0 +1,57 @@
+// Signed-distance-field text shader for kiss3d
+// The atlas stores, per texel, the distance to the nearest glyph edge remapped
+// so that the edge sits at 0.5 (inside > 0.5). The edge is reconstructed here
+// with a screen-space antialiasing width, so glyphs stay sharp at any scale.
+
+// Bind group 0: Uniforms
+struct TextUniforms {
+    inv_size: vec2<f32>,
+    // Translation of the vertices, in pixels.
+    offset: vec2<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: TextUniforms;
+
+// Bind group 1: Distance-field atlas and sampler
+@group(1) @binding(0)
+var t_sdf: texture_2d<f32>;
+@group(1) @binding(1)
+var s_sdf: sampler;
+
+// Vertex input - interleaved position, UV, and color
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) tex_coord: vec2<f32>,
+    @location(2) color: vec4<f32>,
+}
+
+// Vertex output / Fragment input
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coord: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+@vertex
+fn vs_main(vertex: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(
+        (vertex.position.x + uniforms.offset.x) * uniforms.inv_size.x - 1.0,
+        (vertex.position.y + uniforms.offset.y) * uniforms.inv_size.y + 1.0,
+        0.0,
+        1.0
+    );
+    out.tex_coord = vertex.tex_coord;
+    out.color = vertex.color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let dist = textureSample(t_sdf, s_sdf, in.tex_coord).r;
+    // Half a pixel worth of distance, whatever the on-screen glyph size.
+    let aa = max(fwidth(dist) * 0.5, 1.0e-4);
+    let alpha = smoothstep(0.5 - aa, 0.5 + aa, dist);
+    return vec4<f32>(in.color.rgb, in.color.a * alpha);
+}
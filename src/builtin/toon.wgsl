@@ -0,0 +1,117 @@
+// Toon shading for kiss3d
+// Quantizes the diffuse lighting into bands, with an optional outline pass.
+
+const MAX_TOON_LIGHTS: u32 = 4u;
+
+// Bind group 0: Frame uniforms
+struct FrameUniforms {
+    view: mat4x4<f32>,
+    proj: mat4x4<f32>,
+    eye: vec4<f32>,
+    // xyz: world position (w = 1) or direction the light travels (w = 0).
+    light_pos: array<vec4<f32>, 4>,
+    light_color: array<vec4<f32>, 4>,
+    // x: number of lights, y: ambient, z: number of bands, w: outline mode
+    // (0: none, 1: inverted hull, 2: screen space).
+    params: vec4<f32>,
+    outline_color: vec4<f32>,
+    // x: outline width, yz: viewport size in pixels.
+    outline: vec4<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> frame: FrameUniforms;
+
+// Bind group 1: Object uniforms
+struct ObjectUniforms {
+    transform: mat4x4<f32>,
+    scale: mat3x3<f32>,
+    color: vec4<f32>,
+}
+
+@group(1) @binding(0)
+var<uniform> object: ObjectUniforms;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_position: vec3<f32>,
+    @location(1) world_normal: vec3<f32>,
+}
+
+fn world_normal(normal: vec3<f32>) -> vec3<f32> {
+    let scale = vec3<f32>(object.scale[0][0], object.scale[1][1], object.scale[2][2]);
+    let n = (object.transform * vec4<f32>(normal / scale, 0.0)).xyz;
+    return normalize(n);
+}
+
+@vertex
+fn vs_main(vertex: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+
+    let world_pos = object.transform * vec4<f32>(object.scale * vertex.position, 1.0);
+    out.clip_position = frame.proj * frame.view * world_pos;
+    out.world_position = world_pos.xyz;
+    out.world_normal = world_normal(vertex.normal);
+
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let bands = max(frame.params.z, 1.0);
+    var n = normalize(in.world_normal);
+    let to_eye = frame.eye.xyz - in.world_position;
+    // Light both sides of the surface when backface culling is disabled.
+    if dot(n, to_eye) < 0.0 {
+        n = -n;
+    }
+
+    var light = vec3<f32>(frame.params.y);
+    let num_lights = min(u32(frame.params.x), MAX_TOON_LIGHTS);
+    for (var i = 0u; i < num_lights; i++) {
+        let pos = frame.light_pos[i];
+        var l: vec3<f32>;
+        if pos.w == 0.0 {
+            l = -pos.xyz;
+        } else {
+            l = pos.xyz - in.world_position;
+        }
+        let n_dot_l = max(dot(n, normalize(l)), 0.0);
+        // Round up so only the surfaces facing away from the light are unlit.
+        let band = ceil(n_dot_l * bands) / bands;
+        light += frame.light_color[i].rgb * band;
+    }
+
+    return vec4<f32>(object.color.rgb * light, object.color.a);
+}
+
+@vertex
+fn vs_outline(vertex: VertexInput) -> @builtin(position) vec4<f32> {
+    let n = world_normal(vertex.normal);
+    var world_pos = object.transform * vec4<f32>(object.scale * vertex.position, 1.0);
+
+    if frame.params.w == 1.0 {
+        // Inverted hull: the width is in world units.
+        world_pos = vec4<f32>(world_pos.xyz + n * frame.outline.x, 1.0);
+        return frame.proj * frame.view * world_pos;
+    }
+
+    // Screen space: the width is in pixels, whatever the distance.
+    var clip = frame.proj * frame.view * world_pos;
+    let clip_normal = (frame.proj * frame.view * vec4<f32>(n, 0.0)).xy;
+    if dot(clip_normal, clip_normal) > 0.0 {
+        let offset = normalize(clip_normal) * frame.outline.x * 2.0 / frame.outline.yz;
+        clip = vec4<f32>(clip.xy + offset * clip.w, clip.zw);
+    }
+    return clip;
+}
+
+@fragment
+fn fs_outline() -> @location(0) vec4<f32> {
+    return frame.outline_color;
+}
@@ -0,0 +1,697 @@
+use crate::camera::Camera3d;
+use crate::color::{Color, BLACK};
+use crate::context::Context;
+use crate::light::{LightCollection, LightType};
+use crate::resource::vertex_index::VERTEX_INDEX_FORMAT;
+use crate::resource::{
+    multisample_state, DynamicUniformBuffer, GpuData, GpuMesh3d, Material3d, PipelineCache,
+    RenderContext, RenderPhase,
+};
+use crate::scene::{InstancesBuffer3d, ObjectData3d};
+use bytemuck::{Pod, Zeroable};
+use glamx::{Mat3, Pose3, Vec3};
+use std::any::Any;
+use std::cell::Cell;
+
+/// The maximum number of lights shading a [`ToonMaterial`].
+const MAX_TOON_LIGHTS: usize = 4;
+
+/// Frame-level uniforms (view, projection, lights and toon settings).
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct FrameUniforms {
+    view: [[f32; 4]; 4],
+    proj: [[f32; 4]; 4],
+    eye: [f32; 4],
+    /// World position (w = 1) or direction the light travels (w = 0).
+    light_pos: [[f32; 4]; MAX_TOON_LIGHTS],
+    light_color: [[f32; 4]; MAX_TOON_LIGHTS],
+    /// Number of lights, ambient, number of bands, outline mode.
+    params: [f32; 4],
+    outline_color: [f32; 4],
+    /// Outline width, viewport width and height.
+    outline: [f32; 4],
+}
+
+/// Object-level uniforms (transform, scale, color).
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct ObjectUniforms {
+    transform: [[f32; 4]; 4],
+    scale: [[f32; 4]; 3], // mat3x3 padded to mat3x4 for alignment
+    color: [f32; 4],
+}
+
+/// The silhouette drawn around objects by a [`ToonMaterial`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ToonOutline {
+    /// No outline.
+    None,
+    /// The back faces of the mesh, pushed out along the normals by `width`
+    /// world units: the outline thins out with the distance, like ink on the
+    /// object. Meshes with split normals at hard edges get gaps there.
+    InvertedHull {
+        /// The width of the outline, in world units.
+        width: f32,
+    },
+    /// The back faces of the mesh, pushed out in screen space so the outline
+    /// keeps the same width in pixels at any distance.
+    ScreenSpace {
+        /// The width of the outline, in pixels.
+        width: f32,
+    },
+}
+
+/// Per-object GPU data for ToonMaterial.
+pub struct ToonMaterialGpuData {
+    /// Offset into the shared dynamic object uniform buffer.
+    object_uniform_offset: Option<u32>,
+}
+
+impl ToonMaterialGpuData {
+    pub fn new() -> Self {
+        Self {
+            object_uniform_offset: None,
+        }
+    }
+}
+
+impl Default for ToonMaterialGpuData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GpuData for ToonMaterialGpuData {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// A material shading objects in a cartoon style: the diffuse lighting is
+/// quantized into a few flat bands, and a silhouette is drawn around them.
+///
+/// Objects keep their color; the first four lights of the scene (point and
+/// spot lights are treated as point lights, and their intensity is ignored so
+/// the bands stay crisp) plus the ambient light shade them. Without lights, a
+/// headlight at the camera is used.
+///
+/// The settings apply to every object using the material: register one
+/// instance per look and pick it per node by name. The `toon` material
+/// registered by default has three bands and a thin black inverted-hull
+/// outline. For outlines following every depth and normal discontinuity of
+/// the image rather than the silhouettes of the objects, see the
+/// [`SobelEdgeHighlight`](crate::post_processing::SobelEdgeHighlight) effect.
+///
+/// The material is only drawn in the opaque pass: it is ignored by the
+/// G-buffer prepass (SSAO, SSR) and by order-independent transparency.
+///
+/// # Example
+/// ```no_run
+/// # use kiss3d::prelude::*;
+/// # use kiss3d::builtin::{ToonMaterial, ToonOutline};
+/// # use kiss3d::resource::MaterialManager3d;
+/// # #[kiss3d::main]
+/// # async fn main() {
+/// # let _window = Window::new("Example").await;
+/// let _ = MaterialManager3d::get_global_manager(|mm| {
+///     let toon = ToonMaterial::new()
+///         .with_bands(2)
+///         .with_outline(ToonOutline::ScreenSpace { width: 3.0 });
+///     mm.register("toon_bold", toon)
+/// });
+///
+/// let mut scene = SceneNode3d::empty();
+/// let ball = scene
+///     .build_sphere(0.5)
+///     .color(ORANGE)
+///     .material("toon_bold")
+///     .spawn();
+/// # }
+/// ```
+pub struct ToonMaterial {
+    bands: u32,
+    outline: ToonOutline,
+    outline_color: Color,
+
+    /// Pipeline with backface culling enabled (lazily built per MSAA sample count)
+    pipeline_cull: PipelineCache,
+    /// `pipeline_cull` for clockwise front faces
+    pipeline_cull_cw: PipelineCache,
+    /// Pipeline with backface culling disabled (lazily built per MSAA sample count)
+    pipeline_no_cull: PipelineCache,
+    /// Pipeline drawing the back faces of the extruded mesh as the outline
+    pipeline_outline: PipelineCache,
+    /// `pipeline_outline` for clockwise front faces
+    pipeline_outline_cw: PipelineCache,
+    object_bind_group_layout: wgpu::BindGroupLayout,
+
+    // === Dynamic uniform buffer system ===
+    /// Shared frame uniform buffer
+    frame_uniform_buffer: wgpu::Buffer,
+    /// Shared frame bind group
+    frame_bind_group: wgpu::BindGroup,
+    /// Dynamic buffer for object uniforms
+    object_uniform_buffer: DynamicUniformBuffer<ObjectUniforms>,
+    /// Bind group for object uniforms (recreated when buffer grows)
+    object_bind_group: Option<wgpu::BindGroup>,
+    /// Frame counter for detecting new frames
+    frame_counter: Cell<u64>,
+    /// Last frame we processed
+    last_frame: Cell<u64>,
+}
+
+impl Default for ToonMaterial {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ToonMaterial {
+    /// Creates a new ToonMaterial with three bands and a thin black
+    /// inverted-hull outline.
+    pub fn new() -> ToonMaterial {
+        let ctxt = Context::get();
+
+        let frame_bind_group_layout =
+            ctxt.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("toon_material_frame_bind_group_layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+
+        let object_bind_group_layout =
+            ctxt.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("toon_material_object_bind_group_layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+
+        let pipeline_layout = ctxt.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("toon_material_pipeline_layout"),
+            bind_group_layouts: &[
+                Some(&frame_bind_group_layout),
+                Some(&object_bind_group_layout),
+            ],
+            immediate_size: 0,
+        });
+
+        let shader =
+            ctxt.create_shader_module(Some("toon_material_shader"), include_str!("toon.wgsl"));
+
+        // Shared pipeline builder, parameterized by entry points, cull mode, front-face
+        // winding and MSAA sample count. The outline uses the same layout with its own
+        // entry points.
+        let build = std::rc::Rc::new(
+            move |vs: &'static str,
+                  fs: &'static str,
+                  cull_mode: Option<wgpu::Face>,
+                  front_face: wgpu::FrontFace,
+                  label: &'static str,
+                  sample_count: u32| {
+                let ctxt = Context::get();
+                let vertex_buffer_layouts = [
+                    // Vertex positions
+                    wgpu::VertexBufferLayout {
+                        array_stride: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                        step_mode: wgpu::VertexStepMode::Vertex,
+                        attributes: &[wgpu::VertexAttribute {
+                            offset: 0,
+                            shader_location: 0,
+                            format: wgpu::VertexFormat::Float32x3,
+                        }],
+                    },
+                    // Normals
+                    wgpu::VertexBufferLayout {
+                        array_stride: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                        step_mode: wgpu::VertexStepMode::Vertex,
+                        attributes: &[wgpu::VertexAttribute {
+                            offset: 0,
+                            shader_location: 1,
+                            format: wgpu::VertexFormat::Float32x3,
+                        }],
+                    },
+                ];
+
+                ctxt.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some(label),
+                    layout: Some(&pipeline_layout),
+                    vertex: wgpu::VertexState {
+                        module: &shader,
+                        entry_point: Some(vs),
+                        buffers: &vertex_buffer_layouts,
+                        compilation_options: Default::default(),
+                    },
+                    fragment: Some(wgpu::FragmentState {
+                        module: &shader,
+                        entry_point: Some(fs),
+                        targets: &[Some(wgpu::ColorTargetState {
+                            format: Context::render_format(),
+                            blend: Some(wgpu::BlendState::REPLACE),
+                            write_mask: wgpu::ColorWrites::ALL,
+                        })],
+                        compilation_options: Default::default(),
+                    }),
+                    primitive: wgpu::PrimitiveState {
+                        topology: wgpu::PrimitiveTopology::TriangleList,
+                        strip_index_format: None,
+                        front_face,
+                        cull_mode,
+                        polygon_mode: wgpu::PolygonMode::Fill,
+                        unclipped_depth: false,
+                        conservative: false,
+                    },
+                    depth_stencil: Some(wgpu::DepthStencilState {
+                        format: Context::depth_format(),
+                        depth_write_enabled: Some(true),
+                        depth_compare: Some(wgpu::CompareFunction::Less),
+                        stencil: wgpu::StencilState::default(),
+                        bias: wgpu::DepthBiasState::default(),
+                    }),
+                    multisample: multisample_state(sample_count),
+                    multiview_mask: None,
+                    cache: None,
+                })
+            },
+        );
+
+        let pipeline_cull = PipelineCache::new({
+            let build = build.clone();
+            move |sc| {
+                build(
+                    "vs_main",
+                    "fs_main",
+                    Some(wgpu::Face::Back),
+                    wgpu::FrontFace::Ccw,
+                    "toon_material_pipeline_cull",
+                    sc,
+                )
+            }
+        });
+        let pipeline_cull_cw = PipelineCache::new({
+            let build = build.clone();
+            move |sc| {
+                build(
+                    "vs_main",
+                    "fs_main",
+                    Some(wgpu::Face::Back),
+                    wgpu::FrontFace::Cw,
+                    "toon_material_pipeline_cull_cw",
+                    sc,
+                )
+            }
+        });
+        let pipeline_no_cull = PipelineCache::new({
+            let build = build.clone();
+            move |sc| {
+                build(
+                    "vs_main",
+                    "fs_main",
+                    None,
+                    wgpu::FrontFace::Ccw,
+                    "toon_material_pipeline_no_cull",
+                    sc,
+                )
+            }
+        });
+        // Only the back faces of the extruded mesh are drawn, so the outline
+        // shows around the object and never over it.
+        let pipeline_outline = PipelineCache::new({
+            let build = build.clone();
+            move |sc| {
+                build(
+                    "vs_outline",
+                    "fs_outline",
+                    Some(wgpu::Face::Front),
+                    wgpu::FrontFace::Ccw,
+                    "toon_material_pipeline_outline",
+                    sc,
+                )
+            }
+        });
+        let pipeline_outline_cw = PipelineCache::new({
+            let build = build.clone();
+            move |sc| {
+                build(
+                    "vs_outline",
+                    "fs_outline",
+                    Some(wgpu::Face::Front),
+                    wgpu::FrontFace::Cw,
+                    "toon_material_pipeline_outline_cw",
+                    sc,
+                )
+            }
+        });
+
+        // === Create shared dynamic buffer resources ===
+
+        let frame_uniform_buffer = ctxt.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("toon_shared_frame_uniform_buffer"),
+            size: std::mem::size_of::<FrameUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let frame_bind_group = ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("toon_shared_frame_bind_group"),
+            layout: &frame_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: frame_uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        let object_uniform_buffer =
+            DynamicUniformBuffer::<ObjectUniforms>::new("toon_dynamic_object_uniform_buffer");
+
+        let object_bind_group = ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("toon_dynamic_object_bind_group"),
+            layout: &object_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer: object_uniform_buffer.buffer(),
+                    offset: 0,
+                    size: std::num::NonZeroU64::new(object_uniform_buffer.aligned_size()),
+                }),
+            }],
+        });
+
+        ToonMaterial {
+            bands: 3,
+            outline: ToonOutline::InvertedHull { width: 0.02 },
+            outline_color: BLACK,
+            pipeline_cull,
+            pipeline_cull_cw,
+            pipeline_no_cull,
+            pipeline_outline,
+            pipeline_outline_cw,
+            object_bind_group_layout,
+            frame_uniform_buffer,
+            frame_bind_group,
+            object_uniform_buffer,
+            object_bind_group: Some(object_bind_group),
+            frame_counter: Cell::new(0),
+            last_frame: Cell::new(u64::MAX),
+        }
+    }
+
+    /// Sets the number of lighting bands (at least 1).
+    pub fn with_bands(mut self, bands: u32) -> Self {
+        self.set_bands(bands);
+        self
+    }
+
+    /// Sets the outline drawn around the objects.
+    pub fn with_outline(mut self, outline: ToonOutline) -> Self {
+        self.outline = outline;
+        self
+    }
+
+    /// Sets the color of the outline (default: black).
+    pub fn with_outline_color(mut self, color: Color) -> Self {
+        self.outline_color = color;
+        self
+    }
+
+    /// The number of lighting bands.
+    pub fn bands(&self) -> u32 {
+        self.bands
+    }
+
+    /// Sets the number of lighting bands (at least 1).
+    pub fn set_bands(&mut self, bands: u32) {
+        self.bands = bands.max(1);
+    }
+
+    /// The outline drawn around the objects.
+    pub fn outline(&self) -> ToonOutline {
+        self.outline
+    }
+
+    /// Sets the outline drawn around the objects.
+    pub fn set_outline(&mut self, outline: ToonOutline) {
+        self.outline = outline;
+    }
+
+    /// The color of the outline.
+    pub fn outline_color(&self) -> Color {
+        self.outline_color
+    }
+
+    /// Sets the color of the outline.
+    pub fn set_outline_color(&mut self, color: Color) {
+        self.outline_color = color;
+    }
+
+    fn frame_uniforms(
+        &self,
+        pass: usize,
+        camera: &mut dyn Camera3d,
+        lights: &LightCollection,
+        viewport_width: u32,
+        viewport_height: u32,
+    ) -> FrameUniforms {
+        let (view, proj) = camera.view_transform_pair(pass);
+        let eye = camera.eye();
+
+        let mut light_pos = [[0.0; 4]; MAX_TOON_LIGHTS];
+        let mut light_color = [[0.0; 4]; MAX_TOON_LIGHTS];
+        let mut num_lights = 0;
+        for light in lights.lights.iter().take(MAX_TOON_LIGHTS) {
+            let pos = &mut light_pos[num_lights];
+            *pos = match light.light_type {
+                LightType::Directional(_) => {
+                    let d = light.world_direction;
+                    [d.x, d.y, d.z, 0.0]
+                }
+                _ => {
+                    let p = light.world_position;
+                    [p.x, p.y, p.z, 1.0]
+                }
+            };
+            let c = light.color;
+            light_color[num_lights] = [c.x, c.y, c.z, 1.0];
+            num_lights += 1;
+        }
+        if num_lights == 0 {
+            light_pos[0] = [eye.x, eye.y, eye.z, 1.0];
+            light_color[0] = [1.0; 4];
+            num_lights = 1;
+        }
+
+        let (mode, width) = match self.outline {
+            ToonOutline::None => (0.0, 0.0),
+            ToonOutline::InvertedHull { width } => (1.0, width),
+            ToonOutline::ScreenSpace { width } => (2.0, width),
+        };
+        let c = self.outline_color;
+
+        FrameUniforms {
+            view: view.to_mat4().to_cols_array_2d(),
+            proj: proj.to_cols_array_2d(),
+            eye: [eye.x, eye.y, eye.z, 1.0],
+            light_pos,
+            light_color,
+            params: [num_lights as f32, lights.ambient, self.bands as f32, mode],
+            outline_color: [c.r, c.g, c.b, c.a],
+            outline: [
+                width,
+                viewport_width.max(1) as f32,
+                viewport_height.max(1) as f32,
+                0.0,
+            ],
+        }
+    }
+}
+
+impl Material3d for ToonMaterial {
+    fn create_gpu_data(&self) -> Box<dyn GpuData> {
+        Box::new(ToonMaterialGpuData::new())
+    }
+
+    fn begin_frame(&mut self) {
+        self.frame_counter
+            .set(self.frame_counter.get().wrapping_add(1));
+        self.object_uniform_buffer.clear();
+    }
+
+    fn flush(&mut self) {
+        let ctxt = Context::get();
+
+        // Flush returns true if buffer was reallocated
+        let buffer_reallocated = self.object_uniform_buffer.flush();
+
+        // Recreate bind group if buffer was reallocated
+        if buffer_reallocated {
+            self.object_bind_group = Some(ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("toon_dynamic_object_bind_group"),
+                layout: &self.object_bind_group_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                        buffer: self.object_uniform_buffer.buffer(),
+                        offset: 0,
+                        size: std::num::NonZeroU64::new(self.object_uniform_buffer.aligned_size()),
+                    }),
+                }],
+            }));
+        }
+    }
+
+    fn prepare(
+        &mut self,
+        pass: usize,
+        transform: Pose3,
+        scale: Vec3,
+        camera: &mut dyn Camera3d,
+        lights: &LightCollection,
+        data: &ObjectData3d,
+        gpu_data: &mut dyn GpuData,
+        viewport_width: u32,
+        viewport_height: u32,
+    ) {
+        let ctxt = Context::get();
+
+        let gpu_data = gpu_data
+            .as_any_mut()
+            .downcast_mut::<ToonMaterialGpuData>()
+            .expect("ToonMaterial requires ToonMaterialGpuData");
+
+        // Check if this is a new frame (first object being prepared)
+        let current_frame = self.frame_counter.get();
+        if current_frame != self.last_frame.get() {
+            self.last_frame.set(current_frame);
+            let frame_uniforms =
+                self.frame_uniforms(pass, camera, lights, viewport_width, viewport_height);
+            ctxt.write_buffer(
+                &self.frame_uniform_buffer,
+                0,
+                bytemuck::bytes_of(&frame_uniforms),
+            );
+        }
+
+        // Pad mat3x3 to mat3x4 for proper alignment
+        let scale_cols = Mat3::from_diagonal(scale).to_cols_array_2d();
+        let scale_padded: [[f32; 4]; 3] = [
+            [scale_cols[0][0], scale_cols[0][1], scale_cols[0][2], 0.0],
+            [scale_cols[1][0], scale_cols[1][1], scale_cols[1][2], 0.0],
+            [scale_cols[2][0], scale_cols[2][1], scale_cols[2][2], 0.0],
+        ];
+
+        let color = data.color();
+        let object_uniforms = ObjectUniforms {
+            transform: transform.to_mat4().to_cols_array_2d(),
+            scale: scale_padded,
+            color: [color.r, color.g, color.b, color.a],
+        };
+
+        let object_offset = self.object_uniform_buffer.push(&object_uniforms);
+        gpu_data.object_uniform_offset = Some(object_offset);
+    }
+
+    fn render(
+        &mut self,
+        _pass: usize,
+        _transform: Pose3,
+        _scale: Vec3,
+        _camera: &mut dyn Camera3d,
+        _lights: &LightCollection,
+        data: &ObjectData3d,
+        mesh: &mut GpuMesh3d,
+        _instances: &mut InstancesBuffer3d,
+        gpu_data: &mut dyn GpuData,
+        render_pass: &mut wgpu::RenderPass<'_>,
+        context: &RenderContext,
+    ) {
+        // The prepass and transmission passes have other targets.
+        if !data.surface_rendering_active() || context.phase != RenderPhase::Opaque {
+            return;
+        }
+
+        let gpu_data = gpu_data
+            .as_any_mut()
+            .downcast_mut::<ToonMaterialGpuData>()
+            .expect("ToonMaterial requires ToonMaterialGpuData");
+
+        let object_offset = gpu_data
+            .object_uniform_offset
+            .expect("prepare() must be called before render()");
+
+        // Ensure mesh buffers are on GPU
+        mesh.coords().write().unwrap().load_to_gpu();
+        mesh.normals().write().unwrap().load_to_gpu();
+        mesh.faces().write().unwrap().load_to_gpu();
+
+        let coords_buffer = mesh.coords().read().unwrap();
+        let normals_buffer = mesh.normals().read().unwrap();
+        let faces_buffer = mesh.faces().read().unwrap();
+
+        let coords_buf = match coords_buffer.buffer() {
+            Some(b) => b,
+            None => return,
+        };
+        let normals_buf = match normals_buffer.buffer() {
+            Some(b) => b,
+            None => return,
+        };
+        let faces_buf = match faces_buffer.buffer() {
+            Some(b) => b,
+            None => return,
+        };
+
+        let object_bind_group = self.object_bind_group.as_ref().unwrap();
+
+        let cw = data.effective_front_face() == wgpu::FrontFace::Cw;
+        let pipeline = if data.backface_culling_enabled() {
+            if cw {
+                self.pipeline_cull_cw.get(context.sample_count)
+            } else {
+                self.pipeline_cull.get(context.sample_count)
+            }
+        } else {
+            self.pipeline_no_cull.get(context.sample_count)
+        };
+        render_pass.set_pipeline(&pipeline);
+        render_pass.set_bind_group(0, &self.frame_bind_group, &[]);
+        render_pass.set_bind_group(1, object_bind_group, &[object_offset]);
+
+        render_pass.set_vertex_buffer(0, coords_buf.slice(..));
+        render_pass.set_vertex_buffer(1, normals_buf.slice(..));
+        render_pass.set_index_buffer(faces_buf.slice(..), VERTEX_INDEX_FORMAT);
+
+        render_pass.draw_indexed(0..mesh.num_indices(), 0, 0..1);
+        crate::renderer::timings::record_draw(mesh.num_indices() as u64 / 3);
+
+        if self.outline != ToonOutline::None {
+            let outline = if cw {
+                self.pipeline_outline_cw.get(context.sample_count)
+            } else {
+                self.pipeline_outline.get(context.sample_count)
+            };
+            render_pass.set_pipeline(&outline);
+            render_pass.draw_indexed(0..mesh.num_indices(), 0, 0..1);
+            crate::renderer::timings::record_draw(mesh.num_indices() as u64 / 3);
+        }
+    }
+}
@@ -48,4 +48,12 @@ pub trait Camera2d {
     /// # Returns
     /// The corresponding point in 2D world space
     fn unproject(&self, window_coord: Vec2, window_size: Vec2) -> Vec2;
+
+    /// The corners, in window pixels, of the zoom rectangle being dragged, if
+    /// any. The window draws it as a rubber band over the frame. The default
+    /// implementation returns `None`.
+    #[inline]
+    fn box_zoom_rect(&self) -> Option<(Vec2, Vec2)> {
+        None
+    }
 }
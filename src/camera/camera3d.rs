@@ -1,4 +1,7 @@
+use crate::camera::{CameraState, Projection};
+use crate::context::Context;
 use crate::event::WindowEvent;
+use crate::scene::SceneNode3d;
 use crate::window::Canvas;
 use glamx::{Mat4, Pose3, Vec2, Vec3, Vec4, Vec4Swizzles};
 
@@ -31,6 +34,31 @@ pub trait Camera3d {
     /// * `event` - The window event to handle
     fn handle_event(&mut self, canvas: &Canvas, event: &WindowEvent);
 
+    /// The window pixel (physical pixels from the top-left corner) under which
+    /// the camera wants the point of the scene picked, e.g. to orbit around it.
+    ///
+    /// The window calls this after handling each batch of events and answers a
+    /// request with [`set_picked_point`](Self::set_picked_point). The default
+    /// implementation never requests a pick.
+    #[inline]
+    fn take_pick_request(&mut self) -> Option<Vec2> {
+        None
+    }
+
+    /// Receives the world-space point of the last rendered frame's geometry
+    /// under the pixel requested by [`take_pick_request`](Self::take_pick_request),
+    /// or `None` if there is only background there.
+    #[inline]
+    fn set_picked_point(&mut self, _point: Option<Vec3>) {}
+
+    /// The corners, in window pixels, of the zoom rectangle being dragged, if
+    /// any. The window draws it as a rubber band over the frame. The default
+    /// implementation returns `None`.
+    #[inline]
+    fn box_zoom_rect(&self) -> Option<(Vec2, Vec2)> {
+        None
+    }
+
     // ==================
     // Transformation-related methods
     // ==================
@@ -147,6 +175,85 @@ pub trait Camera3d {
     #[inline]
     fn render_complete(&self, _canvas: &Canvas) {}
 
+    // ==================
+    // Framing
+    // ==================
+
+    /// Moves the camera so that the axis-aligned box `[min, max]` fills the view.
+    ///
+    /// `margin` is the fraction of the box size kept as empty space around it
+    /// (e.g. `0.1` for 10%). The viewing direction is preserved. The default
+    /// implementation does nothing, for cameras that cannot be moved freely.
+    #[inline]
+    fn fit_aabb(&mut self, _min: Vec3, _max: Vec3, _margin: f32) {}
+
+    /// Moves the camera so that the world-space bounding box of `node` (see
+    /// [`SceneNode3d::world_aabb`]) fills the view, i.e. "zoom to fit".
+    ///
+    /// Does nothing if the subtree has no visible geometry. See
+    /// [`fit_aabb`](Self::fit_aabb) for `margin`.
+    fn fit_scene(&mut self, node: &SceneNode3d, margin: f32) {
+        if let Some((min, max)) = node.world_aabb() {
+            self.fit_aabb(min, max, margin);
+        }
+    }
+
+    /// The viewpoint of the camera, to restore it later with
+    /// [`set_state`](Self::set_state), e.g. on the next launch.
+    ///
+    /// The default implementation derives it from the view and projection of
+    /// the first pass, looking one unit ahead with the
+    /// [world up axis](crate::context::Context::world_up) up.
+    fn state(&self) -> CameraState {
+        let (view, proj) = self.view_transform_pair(0);
+        let eye = self.eye();
+        let (znear, zfar) = self.clip_planes();
+        // The last row of an orthographic projection is `(0, 0, 0, 1)`.
+        let orthographic = proj.w_axis.w == 1.0;
+        CameraState {
+            eye,
+            at: eye + view.rotation.inverse() * -Vec3::Z,
+            up: Context::world_up(),
+            fov: if orthographic {
+                std::f32::consts::FRAC_PI_4
+            } else {
+                2.0 * (1.0 / proj.y_axis.y).atan()
+            },
+            znear,
+            zfar,
+            projection: if orthographic {
+                Projection::Orthographic
+            } else {
+                Projection::Perspective
+            },
+        }
+    }
+
+    /// Rotates the camera by `angle` radians around the up axis through the
+    /// point it looks at, e.g. for the turntable mode of
+    /// [`Window::set_turntable`](crate::window::Window::set_turntable).
+    ///
+    /// The default implementation does nothing, for cameras that don't orbit.
+    #[inline]
+    fn orbit_around_up(&mut self, _angle: f32) {}
+
+    /// Moves the camera to a viewpoint saved with [`state`](Self::state).
+    ///
+    /// The default implementation does nothing, for cameras that cannot be
+    /// moved freely.
+    fn set_state(&mut self, _state: CameraState) {}
+
+    /// Moves the camera by `-offset`, following the world when its origin moves
+    /// to `offset` (see
+    /// [`Window::set_camera_relative`](crate::window::Window::set_camera_relative)).
+    ///
+    /// Returns whether the camera was moved. The default implementation does
+    /// nothing and returns `false`, for cameras that cannot be moved freely.
+    #[inline]
+    fn shift_origin(&mut self, _offset: Vec3) -> bool {
+        false
+    }
+
     /// Projects a 3D point in world coordinates to 2D screen coordinates.
     ///
     /// # Arguments
@@ -214,3 +321,11 @@ pub trait Camera3d {
         )
     }
 }
+
+/// Distance from the center of a bounding sphere of radius `radius` at which a
+/// perspective camera with the vertical field of view `fov` and the given
+/// aspect ratio sees the whole sphere.
+pub(crate) fn fit_distance(radius: f32, fov: f32, aspect: f32) -> f32 {
+    let tan_half = (fov * 0.5).tan() * aspect.min(1.0);
+    radius / tan_half.atan().sin().max(1.0e-6)
+}
@@ -0,0 +1,473 @@
+use crate::camera::{Camera3d, CameraState};
+use crate::event::WindowEvent;
+use crate::window::Canvas;
+use glamx::{Mat4, Pose3, Quat, Vec2, Vec3};
+
+/// A camera wrapper adding procedural shake, smoothing and look-ahead on top of
+/// any other camera.
+///
+/// All inputs are forwarded to the inner camera, which keeps controlling the
+/// view; the modifier only offsets the resulting viewpoint:
+///
+/// * **Shake** — call [`add_trauma`](Self::add_trauma) on impacts or explosions.
+///   The view then jitters with smooth noise whose strength is the square of the
+///   current trauma, which decays over time.
+/// * **Smoothing** — the viewpoint follows the inner camera with an exponential
+///   lag, hiding jerky movements (e.g. a camera attached to a physics body).
+/// * **Look-ahead** — the viewpoint leads the inner camera in its direction of
+///   motion.
+///
+/// # Example
+/// ```no_run
+/// # use kiss3d::prelude::*;
+/// # use kiss3d::camera::CameraModifier;
+/// let mut camera = CameraModifier::new(OrbitCamera3d::new(Vec3::new(0.0, 2.0, 5.0), Vec3::ZERO));
+/// camera.set_smoothing(0.1);
+/// // On impact:
+/// camera.add_trauma(0.6);
+/// ```
+#[derive(Clone, Debug)]
+pub struct CameraModifier<C: Camera3d> {
+    inner: C,
+
+    trauma: f32,
+    trauma_decay: f32,
+    max_shake_offset: f32,
+    max_shake_angle: f32,
+    shake_frequency: f32,
+    shake_time: f32,
+
+    smoothing: f32,
+    smoothed: Option<Pose3>,
+
+    look_ahead: f32,
+    look_ahead_offset: Vec3,
+    last_inner_eye: Option<Vec3>,
+
+    last_update: Option<web_time::Instant>,
+    view: Pose3,
+    proj: Mat4,
+    proj_view: Mat4,
+    inverse_proj_view: Mat4,
+}
+
+impl<C: Camera3d> CameraModifier<C> {
+    /// Wraps `inner` without any effect enabled.
+    pub fn new(inner: C) -> Self {
+        let view = inner.view_transform();
+        let (_, proj) = inner.view_transform_pair(0);
+        let proj_view = proj * view.to_mat4();
+        CameraModifier {
+            inner,
+            trauma: 0.0,
+            trauma_decay: 1.0,
+            max_shake_offset: 0.1,
+            max_shake_angle: 0.05,
+            shake_frequency: 15.0,
+            shake_time: 0.0,
+            smoothing: 0.0,
+            smoothed: None,
+            look_ahead: 0.0,
+            look_ahead_offset: Vec3::ZERO,
+            last_inner_eye: None,
+            last_update: None,
+            view,
+            proj,
+            proj_view,
+            inverse_proj_view: proj_view.inverse(),
+        }
+    }
+
+    /// The wrapped camera.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// The wrapped camera, mutably.
+    pub fn inner_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+
+    /// Unwraps the inner camera.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+
+    /// Adds `amount` of trauma (clamped to `[0, 1]` in total), making the view
+    /// shake.
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    /// The current trauma, in `[0, 1]`.
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    /// Sets how much trauma is lost per second. Defaults to `1.0`.
+    pub fn set_trauma_decay(&mut self, per_second: f32) {
+        self.trauma_decay = per_second.max(0.0);
+    }
+
+    /// Sets the shake amplitude at full trauma: the maximum displacement of the
+    /// eye (in world units) and the maximum rotation (in radians). Defaults to
+    /// `0.1` and `0.05`.
+    pub fn set_shake_amplitude(&mut self, max_offset: f32, max_angle: f32) {
+        self.max_shake_offset = max_offset;
+        self.max_shake_angle = max_angle;
+    }
+
+    /// Sets the typical frequency of the shake, in Hz. Defaults to `15.0`.
+    pub fn set_shake_frequency(&mut self, frequency: f32) {
+        self.shake_frequency = frequency;
+    }
+
+    /// Sets the time (in seconds) the viewpoint takes to catch up with the inner
+    /// camera, roughly. `0.0` (the default) disables smoothing.
+    pub fn set_smoothing(&mut self, time: f32) {
+        self.smoothing = time.max(0.0);
+        if self.smoothing == 0.0 {
+            self.smoothed = None;
+        }
+    }
+
+    /// Sets how far ahead the viewpoint leads the inner camera: its offset is the
+    /// inner camera's velocity times `seconds`. `0.0` (the default) disables it.
+    pub fn set_look_ahead(&mut self, seconds: f32) {
+        self.look_ahead = seconds;
+    }
+
+    fn update_view(&mut self, dt: f32) {
+        let inner_view = self.inner.view_transform();
+        let target = inner_view.inverse();
+
+        // Smoothing of the whole viewpoint.
+        let mut pose = match (self.smoothing > 0.0, self.smoothed) {
+            (true, Some(prev)) => {
+                let t = 1.0 - (-dt / self.smoothing).exp();
+                Pose3::from_parts(
+                    prev.translation.lerp(target.translation, t),
+                    prev.rotation.slerp(target.rotation, t),
+                )
+            }
+            _ => target,
+        };
+        if self.smoothing > 0.0 {
+            self.smoothed = Some(pose);
+        }
+
+        // Look-ahead along the (filtered) velocity of the inner camera.
+        let inner_eye = target.translation;
+        if self.look_ahead != 0.0 && dt > 0.0 {
+            let velocity = self
+                .last_inner_eye
+                .map(|prev| (inner_eye - prev) / dt)
+                .unwrap_or(Vec3::ZERO);
+            let t = 1.0 - (-dt * 4.0).exp();
+            self.look_ahead_offset = self.look_ahead_offset.lerp(velocity * self.look_ahead, t);
+        } else if self.look_ahead == 0.0 {
+            self.look_ahead_offset = Vec3::ZERO;
+        }
+        self.last_inner_eye = Some(inner_eye);
+        pose.translation += self.look_ahead_offset;
+
+        // Trauma-driven shake, in the camera's local frame.
+        self.shake_time += dt;
+        self.trauma = (self.trauma - self.trauma_decay * dt).max(0.0);
+        let shake = self.trauma * self.trauma;
+        if shake > 0.0 {
+            let t = self.shake_time * self.shake_frequency;
+            let offset = Vec3::new(noise(t, 0.0), noise(t, 1.0), noise(t, 2.0))
+                * (shake * self.max_shake_offset);
+            let angles = Vec3::new(noise(t, 3.0), noise(t, 4.0), noise(t, 5.0))
+                * (shake * self.max_shake_angle);
+            let rotation = Quat::from_rotation_y(angles.x)
+                * Quat::from_rotation_x(angles.y)
+                * Quat::from_rotation_z(angles.z);
+            pose *= Pose3::from_parts(offset, rotation);
+        }
+
+        self.view = pose.inverse();
+        self.proj = self.inner.view_transform_pair(0).1;
+        self.proj_view = self.proj * self.view.to_mat4();
+        self.inverse_proj_view = self.proj_view.inverse();
+    }
+}
+
+/// Smooth pseudo-random signal in `[-1, 1]`, decorrelated by `seed`.
+fn noise(t: f32, seed: f32) -> f32 {
+    0.5 * (t + seed * 12.9898).sin()
+        + 0.3 * (t * 1.73 + seed * 78.233).sin()
+        + 0.2 * (t * 3.11 + seed * 37.719).sin()
+}
+
+impl<C: Camera3d> Camera3d for CameraModifier<C> {
+    fn handle_event(&mut self, canvas: &Canvas, event: &WindowEvent) {
+        self.inner.handle_event(canvas, event)
+    }
+
+    fn take_pick_request(&mut self) -> Option<Vec2> {
+        self.inner.take_pick_request()
+    }
+
+    fn set_picked_point(&mut self, point: Option<Vec3>) {
+        self.inner.set_picked_point(point)
+    }
+
+    fn box_zoom_rect(&self) -> Option<(Vec2, Vec2)> {
+        self.inner.box_zoom_rect()
+    }
+
+    fn shift_origin(&mut self, offset: Vec3) -> bool {
+        if !self.inner.shift_origin(offset) {
+            return false;
+        }
+        if let Some(smoothed) = &mut self.smoothed {
+            smoothed.translation -= offset;
+        }
+        self.last_inner_eye = self.last_inner_eye.map(|eye| eye - offset);
+        let mut pose = self.view.inverse();
+        pose.translation -= offset;
+        self.view = pose.inverse();
+        self.proj_view = self.proj * self.view.to_mat4();
+        self.inverse_proj_view = self.proj_view.inverse();
+        true
+    }
+
+    fn eye(&self) -> Vec3 {
+        self.view.inverse().translation
+    }
+
+    fn view_transform(&self) -> Pose3 {
+        self.view
+    }
+
+    fn transformation(&self) -> Mat4 {
+        self.proj_view
+    }
+
+    fn inverse_transformation(&self) -> Mat4 {
+        self.inverse_proj_view
+    }
+
+    fn clip_planes(&self) -> (f32, f32) {
+        self.inner.clip_planes()
+    }
+
+    fn update(&mut self, canvas: &Canvas) {
+        self.inner.update(canvas);
+
+        let now = web_time::Instant::now();
+        let dt = self
+            .last_update
+            .map(|prev| now.duration_since(prev).as_secs_f32())
+            .unwrap_or(0.0);
+        self.last_update = Some(now);
+        self.update_view(dt);
+    }
+
+    fn view_transform_pair(&self, pass: usize) -> (Pose3, Mat4) {
+        // Apply the same viewpoint offset to every pass (e.g. both stereo eyes).
+        let (view, proj) = self.inner.view_transform_pair(pass);
+        let correction = self.inner.view_transform().inverse() * self.view;
+        (view * correction, proj)
+    }
+
+    fn num_passes(&self) -> usize {
+        self.inner.num_passes()
+    }
+
+    fn render_layers(&self) -> u32 {
+        self.inner.render_layers()
+    }
+
+    fn start_pass(&self, pass: usize, canvas: &Canvas) {
+        self.inner.start_pass(pass, canvas)
+    }
+
+    fn render_complete(&self, canvas: &Canvas) {
+        self.inner.render_complete(canvas)
+    }
+
+    fn fit_aabb(&mut self, min: Vec3, max: Vec3, margin: f32) {
+        self.inner.fit_aabb(min, max, margin);
+        self.smoothed = None;
+    }
+
+    fn orbit_around_up(&mut self, angle: f32) {
+        self.inner.orbit_around_up(angle)
+    }
+
+    fn state(&self) -> CameraState {
+        // The viewpoint of the inner camera, without the shake and smoothing.
+        self.inner.state()
+    }
+
+    fn set_state(&mut self, state: CameraState) {
+        self.inner.set_state(state);
+        // A restored viewpoint is a jump, not a motion to smooth or lead.
+        self.smoothed = None;
+        self.last_inner_eye = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::Projection;
+    use crate::window::OffscreenSurface;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// A camera recording which of its hooks were called.
+    struct Probe {
+        calls: Rc<RefCell<Vec<&'static str>>>,
+        state: CameraState,
+    }
+
+    impl Probe {
+        fn log(&self, hook: &'static str) {
+            self.calls.borrow_mut().push(hook);
+        }
+    }
+
+    impl Camera3d for Probe {
+        fn handle_event(&mut self, _: &Canvas, _: &WindowEvent) {
+            self.log("handle_event")
+        }
+        fn take_pick_request(&mut self) -> Option<Vec2> {
+            self.log("take_pick_request");
+            Some(Vec2::new(3.0, 4.0))
+        }
+        fn set_picked_point(&mut self, _: Option<Vec3>) {
+            self.log("set_picked_point")
+        }
+        fn box_zoom_rect(&self) -> Option<(Vec2, Vec2)> {
+            self.log("box_zoom_rect");
+            Some((Vec2::ZERO, Vec2::ONE))
+        }
+        fn eye(&self) -> Vec3 {
+            self.state.eye
+        }
+        fn view_transform(&self) -> Pose3 {
+            Pose3::IDENTITY
+        }
+        fn transformation(&self) -> Mat4 {
+            Mat4::IDENTITY
+        }
+        fn inverse_transformation(&self) -> Mat4 {
+            Mat4::IDENTITY
+        }
+        fn clip_planes(&self) -> (f32, f32) {
+            self.log("clip_planes");
+            (0.5, 50.0)
+        }
+        fn update(&mut self, _: &Canvas) {
+            self.log("update")
+        }
+        fn view_transform_pair(&self, _: usize) -> (Pose3, Mat4) {
+            (Pose3::IDENTITY, Mat4::IDENTITY)
+        }
+        fn num_passes(&self) -> usize {
+            self.log("num_passes");
+            2
+        }
+        fn render_layers(&self) -> u32 {
+            self.log("render_layers");
+            0b101
+        }
+        fn start_pass(&self, _: usize, _: &Canvas) {
+            self.log("start_pass")
+        }
+        fn render_complete(&self, _: &Canvas) {
+            self.log("render_complete")
+        }
+        fn fit_aabb(&mut self, _: Vec3, _: Vec3, _: f32) {
+            self.log("fit_aabb")
+        }
+        fn state(&self) -> CameraState {
+            self.log("state");
+            self.state
+        }
+        fn orbit_around_up(&mut self, _: f32) {
+            self.log("orbit_around_up")
+        }
+        fn set_state(&mut self, state: CameraState) {
+            self.log("set_state");
+            self.state = state;
+        }
+        fn shift_origin(&mut self, _: Vec3) -> bool {
+            self.log("shift_origin");
+            true
+        }
+    }
+
+    #[test]
+    fn forwards_every_hook_to_the_inner_camera() {
+        crate::pollster::block_on(async {
+            if !crate::shader_validity::tests::adapter_available().await {
+                eprintln!("camera modifier test: no GPU adapter found, skipping");
+                return;
+            }
+            let surface = OffscreenSurface::new(8, 8).await;
+            let canvas = surface.window().canvas();
+
+            let calls = Rc::new(RefCell::new(Vec::new()));
+            let state = CameraState {
+                eye: Vec3::new(1.0, 2.0, 3.0),
+                at: Vec3::ZERO,
+                up: Vec3::Y,
+                fov: 1.0,
+                znear: 0.5,
+                zfar: 50.0,
+                projection: Projection::Perspective,
+            };
+            let mut camera = CameraModifier::new(Probe {
+                calls: calls.clone(),
+                state,
+            });
+
+            camera.handle_event(canvas, &WindowEvent::FramebufferSize(8, 8));
+            assert_eq!(camera.take_pick_request(), Some(Vec2::new(3.0, 4.0)));
+            camera.set_picked_point(Some(Vec3::X));
+            assert_eq!(camera.box_zoom_rect(), Some((Vec2::ZERO, Vec2::ONE)));
+            assert_eq!(camera.clip_planes(), (0.5, 50.0));
+            camera.update(canvas);
+            assert_eq!(camera.num_passes(), 2);
+            assert_eq!(camera.render_layers(), 0b101);
+            camera.start_pass(1, canvas);
+            camera.render_complete(canvas);
+            camera.fit_aabb(-Vec3::ONE, Vec3::ONE, 0.1);
+            camera.orbit_around_up(0.5);
+            let moved = CameraState {
+                eye: Vec3::new(-4.0, 0.0, 0.0),
+                ..state
+            };
+            camera.set_state(moved);
+            assert_eq!(camera.state(), moved);
+            assert!(camera.shift_origin(Vec3::X));
+
+            let expected = [
+                "handle_event",
+                "take_pick_request",
+                "set_picked_point",
+                "box_zoom_rect",
+                "clip_planes",
+                "update",
+                "num_passes",
+                "render_layers",
+                "start_pass",
+                "render_complete",
+                "fit_aabb",
+                "orbit_around_up",
+                "set_state",
+                "state",
+                "shift_origin",
+            ];
+            let calls = calls.borrow();
+            for hook in expected {
+                assert!(calls.contains(&hook), "`{}` was not forwarded", hook);
+            }
+        })
+    }
+}
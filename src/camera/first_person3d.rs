@@ -1,4 +1,5 @@
-use crate::camera::Camera3d;
+use crate::camera::{Camera3d, CameraState, Projection};
+use crate::context::Context;
 use crate::event::{Action, Key, MouseButton, WindowEvent};
 use crate::window::Canvas;
 use glamx::glam::camera::rh::proj::opengl;
@@ -16,6 +17,10 @@ use std::f32;
 /// - **Arrow keys**: Move forward/backward/left/right
 /// - **Mouse wheel**: Move forward/backward
 ///
+/// With [`set_raw_mouse_motion`](Self::set_raw_mouse_motion), looking around
+/// follows the raw mouse motion instead of the cursor, for smooth mouse-look
+/// with a grabbed or hidden cursor.
+///
 /// All controls can be customized using the rebind methods.
 ///
 /// # Example
@@ -43,6 +48,8 @@ pub struct FirstPersonCamera3d {
     move_step: f32,
     rotate_button: Option<MouseButton>,
     drag_button: Option<MouseButton>,
+    /// Whether rotations follow `MouseMotionDelta` instead of `CursorPos` events.
+    raw_mouse_motion: bool,
     up_key: Option<Key>,
     down_key: Option<Key>,
     left_key: Option<Key>,
@@ -51,6 +58,7 @@ pub struct FirstPersonCamera3d {
     fov: f32,
     znear: f32,
     zfar: f32,
+    render_layers: u32,
     proj: Mat4,
     view: Mat4,
     proj_view: Mat4,
@@ -111,6 +119,7 @@ impl FirstPersonCamera3d {
             move_step: 0.5,
             rotate_button: Some(MouseButton::Button1),
             drag_button: Some(MouseButton::Button2),
+            raw_mouse_motion: false,
             up_key: Some(Key::Up),
             down_key: Some(Key::Down),
             left_key: Some(Key::Left),
@@ -124,7 +133,8 @@ impl FirstPersonCamera3d {
             inverse_proj_view: Mat4::IDENTITY,
             last_cursor_pos: Vec2::ZERO,
             last_framebuffer_size: Vec2::new(800.0, 600.0),
-            coord_system: CoordSystemRh::from_up_axis(Vec3::Y),
+            coord_system: CoordSystemRh::from_up_axis(Context::world_up()),
+            render_layers: u32::MAX,
         };
 
         res.look_at(eye, at);
@@ -189,6 +199,18 @@ impl FirstPersonCamera3d {
         self.update_projviews();
     }
 
+    /// Moves the camera back along its viewing direction until the axis-aligned
+    /// box `[min, max]` fills the view, looking at the box center.
+    ///
+    /// `margin` is the fraction of the box size kept as empty space around it.
+    pub fn look_at_aabb(&mut self, min: Vec3, max: Vec3, margin: f32) {
+        let radius = ((max - min).length() * 0.5 * (1.0 + margin)).max(1.0e-4);
+        let aspect = self.last_framebuffer_size.x / self.last_framebuffer_size.y;
+        let dist = super::camera3d::fit_distance(radius, self.fov, aspect);
+        let center = (min + max) * 0.5;
+        self.look_at(center - self.eye_dir() * dist, center);
+    }
+
     /// The point the camera is looking at.
     pub fn at(&self) -> Vec3 {
         let view_eye = self.coord_system.rotation_to_y_up * self.eye;
@@ -220,6 +242,23 @@ impl FirstPersonCamera3d {
         self.rotate_button = new_button;
     }
 
+    /// Whether looking around follows the raw mouse motion (see
+    /// [`set_raw_mouse_motion`](Self::set_raw_mouse_motion)).
+    pub fn raw_mouse_motion(&self) -> bool {
+        self.raw_mouse_motion
+    }
+
+    /// Makes looking around follow [`WindowEvent::MouseMotionDelta`] events
+    /// instead of cursor motion: rotations are then smooth and unbounded while
+    /// the cursor is grabbed or hidden (see
+    /// [`Window::set_cursor_grab`](crate::window::Window::set_cursor_grab)). The
+    /// rotate button must still be pressed, unless it is unbound with
+    /// [`rebind_rotate_button(None)`](Self::rebind_rotate_button), in which case
+    /// the mouse always looks around.
+    pub fn set_raw_mouse_motion(&mut self, enabled: bool) {
+        self.raw_mouse_motion = enabled;
+    }
+
     /// The button used to drag the FirstPersonCamera3d camera.
     pub fn drag_button(&self) -> Option<MouseButton> {
         self.drag_button
@@ -382,6 +421,13 @@ impl FirstPersonCamera3d {
         self.update_projviews();
     }
 
+    /// Sets the render-layer bitmask this camera draws (see
+    /// [`Camera3d::render_layers`](super::Camera3d::render_layers)).
+    #[inline]
+    pub fn set_render_layers(&mut self, layers: u32) {
+        self.render_layers = layers;
+    }
+
     /// Sets the up vector of this camera. Prefer using [`set_up_axis_dir`](#method.set_up_axis_dir)
     /// if your up vector is already normalized.
     #[inline]
@@ -410,6 +456,38 @@ impl FirstPersonCamera3d {
 }
 
 impl Camera3d for FirstPersonCamera3d {
+    fn state(&self) -> CameraState {
+        CameraState {
+            eye: self.eye,
+            at: self.at(),
+            up: self.coord_system.up_axis,
+            fov: self.fov,
+            znear: self.znear,
+            zfar: self.zfar,
+            projection: Projection::Perspective,
+        }
+    }
+
+    fn set_state(&mut self, state: CameraState) {
+        self.fov = state.fov;
+        self.znear = state.znear;
+        self.zfar = state.zfar;
+        if state.up != Vec3::ZERO {
+            self.set_up_axis(state.up);
+        }
+        self.look_at(state.eye, state.at);
+    }
+
+    fn fit_aabb(&mut self, min: Vec3, max: Vec3, margin: f32) {
+        self.look_at_aabb(min, max, margin)
+    }
+
+    fn shift_origin(&mut self, offset: Vec3) -> bool {
+        self.eye -= offset;
+        self.update_projviews();
+        true
+    }
+
     fn clip_planes(&self) -> (f32, f32) {
         (self.znear, self.zfar)
     }
@@ -424,7 +502,7 @@ impl Camera3d for FirstPersonCamera3d {
             WindowEvent::CursorPos(x, y, _) => {
                 let curr_pos = Vec2::new(x as f32, y as f32);
 
-                if let Some(rotate_button) = self.rotate_button {
+                if let Some(rotate_button) = self.rotate_button.filter(|_| !self.raw_mouse_motion) {
                     if canvas.get_mouse_button(rotate_button) == Action::Press {
                         let dpos = curr_pos - self.last_cursor_pos;
                         self.handle_left_button_displacement(dpos)
@@ -440,6 +518,14 @@ impl Camera3d for FirstPersonCamera3d {
 
                 self.last_cursor_pos = curr_pos;
             }
+            WindowEvent::MouseMotionDelta(dx, dy) if self.raw_mouse_motion => {
+                let rotating = self
+                    .rotate_button
+                    .is_none_or(|b| canvas.get_mouse_button(b) == Action::Press);
+                if rotating {
+                    self.handle_left_button_displacement(Vec2::new(dx as f32, dy as f32))
+                }
+            }
             WindowEvent::Scroll(_, off, _) => self.handle_scroll(off as f32),
             WindowEvent::FramebufferSize(w, h) => {
                 self.last_framebuffer_size = Vec2::new(w as f32, h as f32);
@@ -453,6 +539,10 @@ impl Camera3d for FirstPersonCamera3d {
         self.eye
     }
 
+    fn render_layers(&self) -> u32 {
+        self.render_layers
+    }
+
     fn transformation(&self) -> Mat4 {
         self.proj_view
     }
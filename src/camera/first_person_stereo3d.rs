@@ -3,7 +3,9 @@ use std::f32;
 use glamx::glam::camera::rh::proj::opengl;
 use glamx::{Mat4, Pose3, Vec2, Vec3};
 
-use crate::camera::Camera3d;
+use crate::camera::first_person3d::CoordSystemRh;
+use crate::camera::{Camera3d, CameraState, Projection};
+use crate::context::Context;
 use crate::event::{Action, Key, MouseButton, WindowEvent};
 use crate::window::Canvas;
 
@@ -24,9 +26,9 @@ pub struct FirstPersonCamera3dStereo {
     /// Inter Pupilary Distance
     ipd: f32,
 
-    /// Yaw of the camera (rotation along the y axis).
+    /// Yaw of the camera (rotation around the up axis).
     yaw: f32,
-    /// Pitch of the camera (rotation along the x axis).
+    /// Pitch of the camera (angle from the up axis).
     pitch: f32,
 
     /// Increment of the yaw per unit mouse movement. The default value is 0.005.
@@ -47,6 +49,8 @@ pub struct FirstPersonCamera3dStereo {
     inverse_proj_view: Mat4,
     last_cursor_pos: Vec2,
     last_framebuffer_size: Vec2,
+    render_layers: u32,
+    coord_system: CoordSystemRh,
 }
 
 impl FirstPersonCamera3dStereo {
@@ -89,9 +93,11 @@ impl FirstPersonCamera3dStereo {
             inverse_proj_view: Mat4::IDENTITY,
             last_cursor_pos: Vec2::ZERO,
             last_framebuffer_size: Vec2::new(800.0, 600.0),
+            render_layers: u32::MAX,
             proj: Mat4::IDENTITY,
             view_left: Mat4::IDENTITY,
             view_right: Mat4::IDENTITY,
+            coord_system: CoordSystemRh::from_up_axis(Context::world_up()),
         };
 
         res.look_at(eye, at);
@@ -103,8 +109,10 @@ impl FirstPersonCamera3dStereo {
     pub fn look_at(&mut self, eye: Vec3, at: Vec3) {
         let dist = (eye - at).length();
 
-        let pitch = ((at.y - eye.y) / dist).acos();
-        let yaw = (at.z - eye.z).atan2(at.x - eye.x);
+        let view_eye = self.coord_system.rotation_to_y_up * eye;
+        let view_at = self.coord_system.rotation_to_y_up * at;
+        let pitch = ((view_at.y - view_eye.y) / dist).acos();
+        let yaw = (view_at.z - view_eye.z).atan2(view_at.x - view_eye.x);
 
         self.eye = eye;
         self.yaw = yaw;
@@ -115,11 +123,11 @@ impl FirstPersonCamera3dStereo {
 
     /// The point the camera is looking at.
     pub fn at(&self) -> Vec3 {
-        let ax = self.eye.x + self.yaw.cos() * self.pitch.sin();
-        let ay = self.eye.y + self.pitch.cos();
-        let az = self.eye.z + self.yaw.sin() * self.pitch.sin();
-
-        Vec3::new(ax, ay, az)
+        let view_eye = self.coord_system.rotation_to_y_up * self.eye;
+        let ax = view_eye.x + self.yaw.cos() * self.pitch.sin();
+        let ay = view_eye.y + self.pitch.cos();
+        let az = view_eye.z + self.yaw.sin() * self.pitch.sin();
+        self.coord_system.rotation_to_y_up.conjugate() * Vec3::new(ax, ay, az)
     }
 
     fn update_restrictions(&mut self) {
@@ -144,9 +152,8 @@ impl FirstPersonCamera3dStereo {
 
     fn update_eyes_location(&mut self) {
         // left and right are on a line perpendicular to both up and the target
-        // up is always y
         let dir = (self.at() - self.eye).normalize();
-        let tangent = Vec3::Y.cross(dir).normalize();
+        let tangent = self.coord_system.up_axis.cross(dir).normalize();
         self.eye_left = self.eye - tangent * (self.ipd / 2.0);
         self.eye_right = self.eye + tangent * (self.ipd / 2.0);
         //println(fmt!("eye_left = %f,%f,%f", self.eye_left.x as float, self.eye_left.y as float, self.eye_left.z as float));
@@ -158,7 +165,7 @@ impl FirstPersonCamera3dStereo {
     pub fn handle_right_button_displacement(&mut self, dpos: Vec2) {
         let at = self.at();
         let dir = (at - self.eye).normalize();
-        let tangent = Vec3::Y.cross(dir).normalize();
+        let tangent = self.coord_system.up_axis.cross(dir).normalize();
         let bitangent = dir.cross(tangent);
 
         self.eye = self.eye + tangent * (0.01 * dpos.x / 10.0) + bitangent * (0.01 * dpos.y / 10.0);
@@ -199,12 +206,12 @@ impl FirstPersonCamera3dStereo {
 
     /// The left eye camera view transformation
     fn view_transform_left(&self) -> Pose3 {
-        Pose3::look_at_rh(self.eye_left, self.at(), Vec3::Y)
+        Pose3::look_at_rh(self.eye_left, self.at(), self.coord_system.up_axis)
     }
 
     /// The right eye camera view transformation
     fn view_transform_right(&self) -> Pose3 {
-        Pose3::look_at_rh(self.eye_right, self.at(), Vec3::Y)
+        Pose3::look_at_rh(self.eye_right, self.at(), self.coord_system.up_axis)
     }
 
     /// return Inter Pupilary Distance
@@ -220,16 +227,49 @@ impl FirstPersonCamera3dStereo {
         self.update_restrictions();
         self.update_projviews();
     }
+
+    /// Sets the render-layer bitmask this camera draws (see
+    /// [`Camera3d::render_layers`](super::Camera3d::render_layers)).
+    #[inline]
+    pub fn set_render_layers(&mut self, layers: u32) {
+        self.render_layers = layers;
+    }
 }
 
 impl Camera3d for FirstPersonCamera3dStereo {
+    fn state(&self) -> CameraState {
+        CameraState {
+            eye: self.eye,
+            at: self.at(),
+            up: self.coord_system.up_axis,
+            fov: self.fov,
+            znear: self.znear,
+            zfar: self.zfar,
+            projection: Projection::Perspective,
+        }
+    }
+
+    fn set_state(&mut self, state: CameraState) {
+        self.fov = state.fov;
+        self.znear = state.znear;
+        self.zfar = state.zfar;
+        self.look_at(state.eye, state.at);
+    }
+
     fn clip_planes(&self) -> (f32, f32) {
         (self.znear, self.zfar)
     }
 
+    fn shift_origin(&mut self, offset: Vec3) -> bool {
+        self.eye -= offset;
+        self.update_eyes_location();
+        self.update_projviews();
+        true
+    }
+
     /// The imaginary middle eye camera view transformation (i-e transformation without projection).
     fn view_transform(&self) -> Pose3 {
-        Pose3::look_at_rh(self.eye, self.at(), Vec3::Y)
+        Pose3::look_at_rh(self.eye, self.at(), self.coord_system.up_axis)
     }
 
     fn handle_event(&mut self, canvas: &Canvas, event: &WindowEvent) {
@@ -262,6 +302,10 @@ impl Camera3d for FirstPersonCamera3dStereo {
         self.eye
     }
 
+    fn render_layers(&self) -> u32 {
+        self.render_layers
+    }
+
     fn transformation(&self) -> Mat4 {
         self.proj_view
     }
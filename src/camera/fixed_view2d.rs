@@ -33,6 +33,15 @@ pub enum CoordinateSystem2d {
 /// so that coordinates are in logical pixels. When `false`, coordinates map directly to
 /// physical pixels — useful for pixel-perfect rendering on high-DPI displays.
 ///
+/// # Pixel-perfect mode
+///
+/// [`FixedView2d::pixel_perfect`] maps one unit to an *integer* number of physical
+/// pixels (the display scale factor rounded, so `2` on a Retina display, or a
+/// fixed [`integer_scale`](Self::set_integer_scale)) and aligns integer
+/// coordinates with pixel corners, so pixel-art sprites and HUDs stay crisp.
+/// Combine it with nearest-neighbor textures
+/// ([`TextureManager::add_pixelated`](crate::resource::TextureManager::add_pixelated)).
+///
 /// # Example
 ///
 /// ```rust
@@ -50,6 +59,8 @@ pub struct FixedView2d {
     inv_proj: Mat3,
     coord_system: CoordinateSystem2d,
     apply_hidpi: bool,
+    pixel_perfect: bool,
+    integer_scale: Option<u32>,
 }
 
 impl Default for FixedView2d {
@@ -66,27 +77,78 @@ impl FixedView2d {
             inv_proj: Mat3::IDENTITY,
             coord_system,
             apply_hidpi,
+            pixel_perfect: false,
+            integer_scale: None,
         }
     }
+
+    /// Create a pixel-perfect camera: one unit spans an integer number of physical
+    /// pixels (the display scale factor, rounded to the nearest integer ≥ 1) and
+    /// integer coordinates fall on pixel corners.
+    pub fn pixel_perfect(coord_system: CoordinateSystem2d) -> FixedView2d {
+        FixedView2d {
+            pixel_perfect: true,
+            ..Self::new(coord_system, true)
+        }
+    }
+
+    /// Forces a pixel-perfect camera to draw each unit as `scale × scale`
+    /// physical pixels, or restores the automatic DPI-based factor with `None`.
+    /// Enables pixel-perfect mode. Takes effect on the next resize event (every
+    /// frame, in practice).
+    pub fn set_integer_scale(&mut self, scale: Option<u32>) {
+        self.pixel_perfect = true;
+        self.integer_scale = scale.map(|s| s.max(1));
+    }
+
+    /// The fixed integer scale of a pixel-perfect camera, if any.
+    pub fn integer_scale(&self) -> Option<u32> {
+        self.integer_scale
+    }
+
+    /// Whether this camera is in pixel-perfect mode.
+    pub fn is_pixel_perfect(&self) -> bool {
+        self.pixel_perfect
+    }
 }
 
 impl Camera2d for FixedView2d {
     fn handle_event(&mut self, canvas: &Canvas, event: &WindowEvent) {
         if let WindowEvent::FramebufferSize(w, h) = *event {
-            let scale = if self.apply_hidpi {
+            let scale = if self.pixel_perfect {
+                let auto = if self.apply_hidpi {
+                    (canvas.scale_factor().round() as u32).max(1)
+                } else {
+                    1
+                };
+                self.integer_scale.unwrap_or(auto) as f32
+            } else if self.apply_hidpi {
                 canvas.scale_factor() as f32
             } else {
                 1.0
             };
 
+            // With an odd framebuffer size the centered origin falls in the middle
+            // of a pixel; a pixel-perfect camera shifts it by half a pixel onto a
+            // pixel corner.
+            let snap = |extent: u32| {
+                if self.pixel_perfect && extent % 2 == 1 {
+                    -1.0 / extent as f32
+                } else {
+                    0.0
+                }
+            };
+            let (snap_x, snap_y) = (snap(w), snap(h));
+
             let w = w as f32;
             let h = h as f32;
 
             let proj = match self.coord_system {
-                CoordinateSystem2d::CenterUp => {
-                    let diag = Vec3::new(2.0 * scale / w, 2.0 * scale / h, 1.0);
-                    Mat3::from_diagonal(diag)
-                }
+                CoordinateSystem2d::CenterUp => Mat3::from_cols(
+                    Vec3::new(2.0 * scale / w, 0.0, 0.0),
+                    Vec3::new(0.0, 2.0 * scale / h, 0.0),
+                    Vec3::new(snap_x, snap_y, 1.0),
+                ),
                 CoordinateSystem2d::TopLeftDown => Mat3::from_cols(
                     Vec3::new(2.0 * scale / w, 0.0, 0.0),
                     Vec3::new(0.0, -2.0 * scale / h, 0.0),
@@ -1,4 +1,4 @@
-use crate::camera::Camera3d;
+use crate::camera::{Camera3d, CameraState, Projection};
 use crate::event::WindowEvent;
 use crate::window::Canvas;
 use glamx::glam::camera::rh::proj::opengl;
@@ -15,6 +15,7 @@ pub struct FixedView3d {
     proj: Mat4,
     inv_proj: Mat4,
     last_framebuffer_size: (f32, f32),
+    render_layers: u32,
 }
 
 impl Default for FixedView3d {
@@ -38,11 +39,19 @@ impl FixedView3d {
             proj: Mat4::IDENTITY,
             inv_proj: Mat4::IDENTITY,
             last_framebuffer_size: (800.0, 600.0),
+            render_layers: u32::MAX,
         };
         res.update_projviews();
         res
     }
 
+    /// Sets the render-layer bitmask this camera draws (see
+    /// [`Camera3d::render_layers`](super::Camera3d::render_layers)).
+    #[inline]
+    pub fn set_render_layers(&mut self, layers: u32) {
+        self.render_layers = layers;
+    }
+
     fn update_projviews(&mut self) {
         let aspect = self.last_framebuffer_size.0 / self.last_framebuffer_size.1;
         self.proj = opengl::perspective(self.fov, aspect, self.znear, self.zfar);
@@ -51,6 +60,27 @@ impl FixedView3d {
 }
 
 impl Camera3d for FixedView3d {
+    fn state(&self) -> CameraState {
+        CameraState {
+            eye: Vec3::ZERO,
+            at: -Vec3::Z,
+            up: Vec3::Y,
+            fov: self.fov,
+            znear: self.znear,
+            zfar: self.zfar,
+            projection: Projection::Perspective,
+        }
+    }
+
+    /// Only restores the field of view and the clip planes: the camera doesn't
+    /// move.
+    fn set_state(&mut self, state: CameraState) {
+        self.fov = state.fov;
+        self.znear = state.znear;
+        self.zfar = state.zfar;
+        self.update_projviews();
+    }
+
     fn clip_planes(&self) -> (f32, f32) {
         (self.znear, self.zfar)
     }
@@ -75,6 +105,10 @@ impl Camera3d for FixedView3d {
         (Pose3::IDENTITY, self.proj)
     }
 
+    fn render_layers(&self) -> u32 {
+        self.render_layers
+    }
+
     fn transformation(&self) -> Mat4 {
         self.proj
     }
@@ -0,0 +1,356 @@
+use crate::camera::{Camera3d, CameraState, OrbitCamera3d};
+use crate::context::Context;
+use crate::event::{Action, Key, WindowEvent};
+use crate::window::Canvas;
+use glamx::{Mat4, Pose3, Vec2, Vec3};
+
+/// The keys driving a [`KeyboardOrbitCamera3d`].
+///
+/// Each action is triggered by any of its keys; an empty list disables it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyboardOrbitBindings {
+    /// Orbits to the left around the focus point.
+    pub orbit_left: Vec<Key>,
+    /// Orbits to the right around the focus point.
+    pub orbit_right: Vec<Key>,
+    /// Orbits upward, looking down on the focus point.
+    pub orbit_up: Vec<Key>,
+    /// Orbits downward.
+    pub orbit_down: Vec<Key>,
+    /// Moves closer to the focus point.
+    pub zoom_in: Vec<Key>,
+    /// Moves away from the focus point.
+    pub zoom_out: Vec<Key>,
+    /// Moves the focus point forward, parallel to the ground.
+    pub pan_forward: Vec<Key>,
+    /// Moves the focus point backward, parallel to the ground.
+    pub pan_backward: Vec<Key>,
+    /// Moves the focus point to the left.
+    pub pan_left: Vec<Key>,
+    /// Moves the focus point to the right.
+    pub pan_right: Vec<Key>,
+}
+
+impl Default for KeyboardOrbitBindings {
+    fn default() -> Self {
+        KeyboardOrbitBindings {
+            orbit_left: vec![Key::Left],
+            orbit_right: vec![Key::Right],
+            orbit_up: vec![Key::Up],
+            orbit_down: vec![Key::Down],
+            zoom_in: vec![Key::Equals, Key::Add],
+            zoom_out: vec![Key::Minus, Key::Subtract],
+            pan_forward: vec![Key::W],
+            pan_backward: vec![Key::S],
+            pan_left: vec![Key::A],
+            pan_right: vec![Key::D],
+        }
+    }
+}
+
+/// An orbit camera fully controllable from the keyboard, for applications that
+/// must be usable without a mouse (kiosks, accessibility requirements).
+///
+/// The following inputs are handled (see [`KeyboardOrbitBindings`] to rebind
+/// them):
+///
+/// * Arrow keys - orbit around the focus point
+/// * `+` / `-` (main keyboard or numpad) - zoom in/out
+/// * `W` `A` `S` `D` - pan the focus point forward, left, backward and right
+///
+/// Held keys move the camera at constant rates, in units per second, independent
+/// of the frame rate. The mouse controls of the wrapped [`OrbitCamera3d`] keep
+/// working.
+///
+/// # Example
+/// ```no_run
+/// # use kiss3d::prelude::*;
+/// # #[kiss3d::main]
+/// # async fn main() {
+/// # let mut window = Window::new("Example").await;
+/// # let mut scene = SceneNode3d::empty();
+/// let mut camera = KeyboardOrbitCamera3d::new(Vec3::new(0.0, 2.0, 5.0), Vec3::ZERO);
+/// camera.set_orbit_speed(2.0);
+///
+/// while window.render_3d(&mut scene, &mut camera).await {}
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct KeyboardOrbitCamera3d {
+    orbit: OrbitCamera3d,
+    bindings: KeyboardOrbitBindings,
+    /// Orbit angular speed, in radians per second.
+    orbit_speed: f32,
+    /// Zoom rate: the distance is scaled by `exp(±zoom_speed)` per second.
+    zoom_speed: f32,
+    /// Pan speed, in multiples of the distance to the focus point per second.
+    pan_speed: f32,
+}
+
+impl Default for KeyboardOrbitCamera3d {
+    fn default() -> Self {
+        Self::from_orbit(OrbitCamera3d::default())
+    }
+}
+
+impl KeyboardOrbitCamera3d {
+    /// Creates a keyboard orbit camera at `eye`, looking at and orbiting around
+    /// `at`, with the default key bindings and rates.
+    pub fn new(eye: Vec3, at: Vec3) -> Self {
+        Self::from_orbit(OrbitCamera3d::new(eye, at))
+    }
+
+    /// Adds the keyboard controls to an existing orbit camera.
+    pub fn from_orbit(orbit: OrbitCamera3d) -> Self {
+        KeyboardOrbitCamera3d {
+            orbit,
+            bindings: KeyboardOrbitBindings::default(),
+            orbit_speed: 1.5,
+            zoom_speed: 1.5,
+            pan_speed: 0.75,
+        }
+    }
+
+    /// The wrapped orbit camera.
+    pub fn orbit(&self) -> &OrbitCamera3d {
+        &self.orbit
+    }
+
+    /// Mutable access to the wrapped orbit camera, e.g. to set its focus point,
+    /// distance limits or projection.
+    pub fn orbit_mut(&mut self) -> &mut OrbitCamera3d {
+        &mut self.orbit
+    }
+
+    /// The key bindings.
+    pub fn bindings(&self) -> &KeyboardOrbitBindings {
+        &self.bindings
+    }
+
+    /// Sets the key bindings.
+    pub fn set_bindings(&mut self, bindings: KeyboardOrbitBindings) {
+        self.bindings = bindings;
+    }
+
+    /// The orbit angular speed, in radians per second (default: 1.5).
+    pub fn orbit_speed(&self) -> f32 {
+        self.orbit_speed
+    }
+
+    /// Sets the orbit angular speed, in radians per second.
+    pub fn set_orbit_speed(&mut self, speed: f32) {
+        self.orbit_speed = speed;
+    }
+
+    /// The zoom rate (default: 1.5): holding a zoom key scales the distance to the
+    /// focus point by `exp(rate)` per second.
+    pub fn zoom_speed(&self) -> f32 {
+        self.zoom_speed
+    }
+
+    /// Sets the zoom rate.
+    pub fn set_zoom_speed(&mut self, speed: f32) {
+        self.zoom_speed = speed;
+    }
+
+    /// The pan speed, in multiples of the distance to the focus point per second
+    /// (default: 0.75), so panning feels the same at any zoom level.
+    pub fn pan_speed(&self) -> f32 {
+        self.pan_speed
+    }
+
+    /// Sets the pan speed, in multiples of the distance to the focus point per
+    /// second.
+    pub fn set_pan_speed(&mut self, speed: f32) {
+        self.pan_speed = speed;
+    }
+}
+
+/// `1.0` if any of `positive` is pressed, `-1.0` if any of `negative` is, `0.0` if
+/// none or both are.
+fn key_axis(canvas: &Canvas, positive: &[Key], negative: &[Key]) -> f32 {
+    let pressed = |keys: &[Key]| keys.iter().any(|k| canvas.get_key(*k) == Action::Press);
+    match (pressed(positive), pressed(negative)) {
+        (true, false) => 1.0,
+        (false, true) => -1.0,
+        _ => 0.0,
+    }
+}
+
+impl Camera3d for KeyboardOrbitCamera3d {
+    fn state(&self) -> CameraState {
+        self.orbit.state()
+    }
+
+    fn set_state(&mut self, state: CameraState) {
+        self.orbit.set_state(state)
+    }
+
+    fn orbit_around_up(&mut self, angle: f32) {
+        self.orbit.orbit_around_up(angle)
+    }
+
+    fn clip_planes(&self) -> (f32, f32) {
+        self.orbit.clip_planes()
+    }
+
+    fn view_transform(&self) -> Pose3 {
+        self.orbit.view_transform()
+    }
+
+    fn eye(&self) -> Vec3 {
+        self.orbit.eye()
+    }
+
+    fn handle_event(&mut self, canvas: &Canvas, event: &WindowEvent) {
+        self.orbit.handle_event(canvas, event)
+    }
+
+    #[inline]
+    fn view_transform_pair(&self, pass: usize) -> (Pose3, Mat4) {
+        self.orbit.view_transform_pair(pass)
+    }
+
+    fn num_passes(&self) -> usize {
+        self.orbit.num_passes()
+    }
+
+    fn render_layers(&self) -> u32 {
+        self.orbit.render_layers()
+    }
+
+    fn start_pass(&self, pass: usize, canvas: &Canvas) {
+        self.orbit.start_pass(pass, canvas)
+    }
+
+    fn render_complete(&self, canvas: &Canvas) {
+        self.orbit.render_complete(canvas)
+    }
+
+    fn transformation(&self) -> Mat4 {
+        self.orbit.transformation()
+    }
+
+    fn inverse_transformation(&self) -> Mat4 {
+        self.orbit.inverse_transformation()
+    }
+
+    fn take_pick_request(&mut self) -> Option<Vec2> {
+        self.orbit.take_pick_request()
+    }
+
+    fn set_picked_point(&mut self, point: Option<Vec3>) {
+        self.orbit.set_picked_point(point)
+    }
+
+    fn box_zoom_rect(&self) -> Option<(Vec2, Vec2)> {
+        self.orbit.box_zoom_rect()
+    }
+
+    fn shift_origin(&mut self, offset: Vec3) -> bool {
+        self.orbit.shift_origin(offset)
+    }
+
+    fn update(&mut self, canvas: &Canvas) {
+        self.orbit.update(canvas);
+
+        let dt = Context::frame_time().delta;
+        let b = &self.bindings;
+        let yaw = key_axis(canvas, &b.orbit_left, &b.orbit_right);
+        let pitch = key_axis(canvas, &b.orbit_up, &b.orbit_down);
+        let zoom = key_axis(canvas, &b.zoom_out, &b.zoom_in);
+        let forward = key_axis(canvas, &b.pan_forward, &b.pan_backward);
+        let right = key_axis(canvas, &b.pan_right, &b.pan_left);
+
+        if yaw != 0.0 {
+            let new_yaw = self.orbit.yaw() + yaw * self.orbit_speed * dt;
+            self.orbit.set_yaw(new_yaw);
+        }
+        if pitch != 0.0 {
+            let new_pitch = self.orbit.pitch() - pitch * self.orbit_speed * dt;
+            self.orbit.set_pitch(new_pitch);
+        }
+        if zoom != 0.0 {
+            let new_dist = self.orbit.dist() * (zoom * self.zoom_speed * dt).exp();
+            self.orbit.set_dist(new_dist);
+        }
+        if forward != 0.0 || right != 0.0 {
+            // Pan parallel to the ground: the view direction without its vertical
+            // component (or the camera's up direction when looking straight down).
+            let up = self.orbit.up_axis();
+            let view_dir = self.orbit.at() - self.orbit.eye();
+            let mut ahead = view_dir - up * view_dir.dot(up);
+            if ahead.length_squared() < 1.0e-8 {
+                ahead = self.orbit.view_transform().rotation.inverse() * Vec3::Y;
+            }
+            let ahead = ahead.normalize();
+            let side = ahead.cross(up).normalize();
+            let step = self.pan_speed * self.orbit.dist() * dt;
+            let at = self.orbit.at() + (ahead * forward + side * right) * step;
+            self.orbit.set_at(at);
+        }
+    }
+
+    fn fit_aabb(&mut self, min: Vec3, max: Vec3, margin: f32) {
+        self.orbit.fit_aabb(min, max, margin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::window::OffscreenSurface;
+
+    /// Every hook of `Camera3d` must reach the inner orbit camera: the wrapper
+    /// behaves as a plain `OrbitCamera3d` when no key is pressed.
+    #[test]
+    fn forwards_every_hook_to_the_orbit_camera() {
+        crate::pollster::block_on(async {
+            if !crate::shader_validity::tests::adapter_available().await {
+                eprintln!("keyboard orbit camera test: no GPU adapter found, skipping");
+                return;
+            }
+            let surface = OffscreenSurface::new(64, 32).await;
+            let canvas = surface.window().canvas();
+
+            let eye = Vec3::new(1.0, 2.0, 5.0);
+            let mut expected = OrbitCamera3d::new(eye, Vec3::ZERO);
+            let mut camera = KeyboardOrbitCamera3d::new(eye, Vec3::ZERO);
+            let cameras: [&mut dyn Camera3d; 2] = [&mut expected, &mut camera];
+            for c in cameras {
+                c.handle_event(canvas, &WindowEvent::FramebufferSize(64, 32));
+                c.fit_aabb(Vec3::splat(-2.0), Vec3::new(1.0, 3.0, 2.0), 0.1);
+                c.orbit_around_up(0.3);
+                c.shift_origin(Vec3::new(0.5, 0.0, -1.0));
+                c.set_picked_point(Some(Vec3::X));
+                c.update(canvas);
+                c.start_pass(0, canvas);
+                c.render_complete(canvas);
+            }
+
+            assert_eq!(camera.state(), expected.state());
+            assert_eq!(camera.eye(), expected.eye());
+            assert_eq!(camera.view_transform(), expected.view_transform());
+            assert_eq!(camera.transformation(), expected.transformation());
+            assert_eq!(
+                camera.inverse_transformation(),
+                expected.inverse_transformation()
+            );
+            assert_eq!(
+                camera.view_transform_pair(0),
+                expected.view_transform_pair(0)
+            );
+            assert_eq!(camera.clip_planes(), expected.clip_planes());
+            assert_eq!(camera.num_passes(), expected.num_passes());
+            assert_eq!(camera.render_layers(), expected.render_layers());
+            assert_eq!(camera.box_zoom_rect(), expected.box_zoom_rect());
+            assert_eq!(camera.take_pick_request(), expected.take_pick_request());
+
+            let mut state = expected.state();
+            state.eye = Vec3::new(-3.0, 1.0, 2.0);
+            camera.set_state(state);
+            expected.set_state(state);
+            assert_eq!(camera.state(), expected.state());
+        })
+    }
+}
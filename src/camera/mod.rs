@@ -2,13 +2,18 @@
 
 pub use self::camera2d::Camera2d;
 pub use self::camera3d::Camera3d;
+pub use self::camera_modifier::CameraModifier;
 pub use self::first_person3d::FirstPersonCamera3d;
 pub use self::first_person_stereo3d::FirstPersonCamera3dStereo;
 pub use self::fixed_view2d::{CoordinateSystem2d, FixedView2d};
 pub use self::fixed_view3d::FixedView3d;
+pub use self::keyboard_orbit3d::{KeyboardOrbitBindings, KeyboardOrbitCamera3d};
 pub use self::orbit3d::OrbitCamera3d;
+pub use self::ray::{DragConstraint, DragPlane, Ray};
 pub use self::sidescroll2d::PanZoomCamera2d;
 
+use glamx::Vec3;
+
 /// The projection a 3D camera uses to map view space to clip space.
 ///
 /// Provides the two standard projections: a
@@ -26,6 +31,51 @@ pub enum Projection {
     Orthographic,
 }
 
+/// The viewpoint of a 3D camera, to save it (e.g. to disk, with the `serde`
+/// feature) and restore it later with
+/// [`Camera3d::set_state`].
+///
+/// Cameras restore what they support: e.g. [`FixedView3d`] only keeps the
+/// field of view and clip planes, and first-person cameras always use a
+/// perspective projection and only keep the direction towards `at`.
+///
+/// # Example
+/// ```no_run
+/// # use kiss3d::prelude::*;
+/// # fn load() -> Option<CameraState> { None }
+/// # fn save(_: &CameraState) {}
+/// # #[kiss3d::main]
+/// # async fn main() {
+/// # let mut window = Window::new("Example").await;
+/// # let mut scene = SceneNode3d::empty();
+/// let mut camera = OrbitCamera3d::default();
+/// if let Some(state) = load() {
+///     camera.set_state(state);
+/// }
+///
+/// while window.render_3d(&mut scene, &mut camera).await {}
+/// save(&camera.state());
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CameraState {
+    /// The position of the camera.
+    pub eye: Vec3,
+    /// The point the camera looks at, which orbit cameras rotate around.
+    pub at: Vec3,
+    /// The world up axis of the camera.
+    pub up: Vec3,
+    /// The vertical field of view, in radians.
+    pub fov: f32,
+    /// The distance to the near clip plane.
+    pub znear: f32,
+    /// The distance to the far clip plane.
+    pub zfar: f32,
+    /// The projection of the camera.
+    pub projection: Projection,
+}
+
 /// Physically-based camera exposure, expressed as an EV100 value.
 ///
 /// The scene's linear HDR radiance is scaled by
@@ -90,9 +140,110 @@ impl Exposure {
 
 mod camera2d;
 mod camera3d;
+mod camera_modifier;
 mod first_person3d;
 mod first_person_stereo3d;
 mod fixed_view2d;
 mod fixed_view3d;
+mod keyboard_orbit3d;
 mod orbit3d;
+mod ray;
 mod sidescroll2d;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// First-person cameras only keep the direction they look at, not the
+    /// distance to `at`.
+    fn assert_same_state(actual: CameraState, expected: CameraState, keeps_target: bool) {
+        let close = |a: Vec3, b: Vec3| a.abs_diff_eq(b, 1.0e-4);
+        let same_target = if keeps_target {
+            close(actual.at, expected.at)
+        } else {
+            close(
+                (actual.at - actual.eye).normalize(),
+                (expected.at - expected.eye).normalize(),
+            )
+        };
+        assert!(
+            close(actual.eye, expected.eye)
+                && same_target
+                && close(actual.up, expected.up)
+                && actual.fov == expected.fov
+                && actual.znear == expected.znear
+                && actual.zfar == expected.zfar
+                && actual.projection == expected.projection,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    /// Checks that `camera` moves to `state`, and that its own state restores it
+    /// on a fresh camera.
+    fn assert_round_trip<C: Camera3d>(
+        make: impl Fn() -> C,
+        state: CameraState,
+        keeps_target: bool,
+    ) {
+        let mut camera = make();
+        camera.set_state(state);
+        assert_same_state(camera.state(), state, keeps_target);
+
+        let mut restored = make();
+        restored.set_state(camera.state());
+        assert_same_state(restored.state(), camera.state(), true);
+    }
+
+    fn state(up: Vec3, projection: Projection) -> CameraState {
+        CameraState {
+            eye: Vec3::new(1.0, 2.0, 5.0),
+            at: Vec3::new(0.5, -0.5, 1.0),
+            up,
+            fov: 1.0,
+            znear: 0.2,
+            zfar: 500.0,
+            projection,
+        }
+    }
+
+    #[test]
+    fn orbit_cameras_round_trip_their_state() {
+        let make = || OrbitCamera3d::new(Vec3::new(0.0, 0.0, 10.0), Vec3::ZERO);
+        let make_keyboard = || KeyboardOrbitCamera3d::new(Vec3::new(0.0, 0.0, 10.0), Vec3::ZERO);
+        for projection in [Projection::Perspective, Projection::Orthographic] {
+            for up in [Vec3::Y, Vec3::Z] {
+                assert_round_trip(make, state(up, projection), true);
+                assert_round_trip(make_keyboard, state(up, projection), true);
+            }
+        }
+    }
+
+    #[test]
+    fn first_person_cameras_round_trip_their_state() {
+        let make = || FirstPersonCamera3d::new(Vec3::new(0.0, 0.0, 10.0), Vec3::ZERO);
+        for up in [Vec3::Y, Vec3::Z] {
+            assert_round_trip(make, state(up, Projection::Perspective), false);
+        }
+
+        let make = || FirstPersonCamera3dStereo::new(Vec3::new(0.0, 0.0, 10.0), Vec3::ZERO, 0.1);
+        assert_round_trip(make, state(Vec3::Y, Projection::Perspective), false);
+    }
+
+    #[test]
+    fn fixed_view_only_restores_its_frustum() {
+        let mut camera = FixedView3d::new();
+        camera.set_state(state(Vec3::Y, Projection::Perspective));
+        let restored = camera.state();
+        assert_eq!(
+            (restored.fov, restored.znear, restored.zfar),
+            (1.0, 0.2, 500.0)
+        );
+        assert_eq!((restored.eye, restored.at), (Vec3::ZERO, -Vec3::Z));
+
+        let mut other = FixedView3d::new();
+        other.set_state(restored);
+        assert_eq!(other.state(), restored);
+    }
+}
@@ -1,9 +1,10 @@
 use crate::camera::first_person3d::CoordSystemRh;
-use crate::camera::Camera3d;
+use crate::camera::{Camera3d, CameraState};
+use crate::context::Context;
 use crate::event::{Action, Key, Modifiers, MouseButton, WindowEvent};
 use crate::window::Canvas;
 use glamx::glam::camera::rh::proj::{directx, opengl};
-use glamx::{Mat4, Pose3, Vec2, Vec3};
+use glamx::{Mat4, Pose3, Quat, Vec2, Vec3};
 use std::f32;
 
 /// Arc-ball camera mode.
@@ -15,6 +16,7 @@ use std::f32;
 /// * Right button press + drag - translates the focus point on the plane orthogonal to the view
 ///   direction
 /// * Scroll in/out - zoom in/out
+/// * Shift + left button drag - zooms to fit the dragged rectangle
 /// * Enter key - set the focus point to the origin
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -42,6 +44,20 @@ pub struct OrbitCamera3d {
     max_pitch: f32,
     /// Distance change factor per unit scrolling. The default value is 1.01.
     dist_step: f32,
+    /// Multiplier of the scroll amount when zooming. The default value is 1.0.
+    zoom_sensitivity: f32,
+    /// Multiplier of the cursor motion when panning. The default value is 1.0.
+    pan_sensitivity: f32,
+    /// Whether rotations orbit around the point of the scene under the cursor.
+    rotate_around_cursor: bool,
+    /// The cursor position at which the point under the cursor must be picked.
+    pick_request: Option<Vec2>,
+    /// The point the current rotation drag orbits around, if not the focus point.
+    rotation_pivot: Option<Vec3>,
+    /// Modifiers turning a rotation drag into a box zoom.
+    box_zoom_modifiers: Option<Modifiers>,
+    /// The start and current cursor positions of the box zoom being dragged.
+    box_zoom: Option<(Vec2, Vec2)>,
     rotate_button: Option<MouseButton>,
     rotate_modifiers: Option<Modifiers>,
     drag_button: Option<MouseButton>,
@@ -64,7 +80,9 @@ pub struct OrbitCamera3d {
 
 impl Default for OrbitCamera3d {
     fn default() -> Self {
-        Self::new(Vec3::new(0.0f32, 0.0, -2.0), Vec3::ZERO)
+        // Looking horizontally at the origin, whatever the world up axis.
+        let eye = Quat::from_rotation_arc(Vec3::Y, Context::world_up()) * Vec3::new(0.0, 0.0, -2.0);
+        Self::new(eye, Vec3::ZERO)
     }
 }
 
@@ -118,6 +136,13 @@ impl OrbitCamera3d {
             dist_step: 1.0001,
             #[cfg(not(target_os = "macos"))]
             dist_step: 1.01,
+            zoom_sensitivity: 1.0,
+            pan_sensitivity: 1.0,
+            rotate_around_cursor: false,
+            pick_request: None,
+            rotation_pivot: None,
+            box_zoom_modifiers: Some(Modifiers::Shift),
+            box_zoom: None,
             rotate_button: Some(MouseButton::Button1),
             rotate_modifiers: None,
             drag_button: Some(MouseButton::Button2),
@@ -134,7 +159,7 @@ impl OrbitCamera3d {
             inverse_proj_view: Mat4::IDENTITY,
             last_framebuffer_size: Vec2::new(800.0, 600.0),
             last_cursor_pos: Vec2::ZERO,
-            coord_system: CoordSystemRh::from_up_axis(Vec3::Y),
+            coord_system: CoordSystemRh::from_up_axis(Context::world_up()),
         };
 
         res.look_at(eye, at);
@@ -311,6 +336,111 @@ impl OrbitCamera3d {
         self.dist_step = dist_step;
     }
 
+    /// Moves the focus point, which the camera orbits around, to `pivot` while
+    /// keeping the camera at the same position (it turns to look at the pivot).
+    pub fn set_pivot(&mut self, pivot: Vec3) {
+        let eye = self.eye();
+        self.look_at(eye, pivot);
+    }
+
+    /// The rotation angle, in radians, per pixel of cursor motion (default:
+    /// 0.005).
+    pub fn rotation_sensitivity(&self) -> f32 {
+        self.yaw_step
+    }
+
+    /// Sets the rotation angle, in radians, per pixel of cursor motion, both
+    /// horizontally and vertically.
+    pub fn set_rotation_sensitivity(&mut self, sensitivity: f32) {
+        self.yaw_step = sensitivity;
+        self.pitch_step = sensitivity;
+    }
+
+    /// The multiplier of the scroll amount when zooming (default: 1.0).
+    pub fn zoom_sensitivity(&self) -> f32 {
+        self.zoom_sensitivity
+    }
+
+    /// Sets the multiplier of the scroll amount when zooming: values above 1.0
+    /// zoom faster, values below 1.0 slower. Use
+    /// [`set_dist_step`](Self::set_dist_step) to change the zoom factor itself.
+    pub fn set_zoom_sensitivity(&mut self, sensitivity: f32) {
+        self.zoom_sensitivity = sensitivity;
+    }
+
+    /// The multiplier of the cursor motion when panning (default: 1.0).
+    pub fn pan_sensitivity(&self) -> f32 {
+        self.pan_sensitivity
+    }
+
+    /// Sets the multiplier of the cursor motion when panning. At 1.0, dragging
+    /// across the whole window pans by the distance to the focus point.
+    pub fn set_pan_sensitivity(&mut self, sensitivity: f32) {
+        self.pan_sensitivity = sensitivity;
+    }
+
+    /// Whether rotations orbit around the point of the scene under the cursor
+    /// (see [`set_rotate_around_cursor`](Self::set_rotate_around_cursor)).
+    pub fn rotate_around_cursor(&self) -> bool {
+        self.rotate_around_cursor
+    }
+
+    /// Makes each rotation drag orbit around the point of the scene under the
+    /// cursor when the drag starts, like CAD packages, instead of around the
+    /// focus point. The view does not jump: the focus point moves along with the
+    /// camera. Dragging from the background orbits around the focus point.
+    ///
+    /// The point is read from the depth buffer of the last rendered frame (see
+    /// [`Window::scene_point_at`](crate::window::Window::scene_point_at)).
+    pub fn set_rotate_around_cursor(&mut self, enabled: bool) {
+        self.rotate_around_cursor = enabled;
+    }
+
+    /// Modifiers that turn a drag of the rotate button into a box zoom (see
+    /// [`set_box_zoom_modifiers`](Self::set_box_zoom_modifiers)).
+    pub fn box_zoom_modifiers(&self) -> Option<Modifiers> {
+        self.box_zoom_modifiers
+    }
+
+    /// Sets the modifiers that turn a drag of the rotate button into a box zoom
+    /// (default: `Shift`). Use `None` to disable box zooming.
+    ///
+    /// While the modifiers are pressed, dragging draws a rectangle instead of
+    /// rotating; releasing the button zooms to fit it with
+    /// [`zoom_to_rect`](Self::zoom_to_rect).
+    pub fn set_box_zoom_modifiers(&mut self, modifiers: Option<Modifiers>) {
+        self.box_zoom_modifiers = modifiers;
+        self.box_zoom = None;
+    }
+
+    /// Pans and zooms so that the window rectangle with corners `a` and `b`, in
+    /// pixels, fills the view.
+    ///
+    /// This is exact with an [`Orthographic`](super::Projection::Orthographic)
+    /// projection. With a perspective projection, the rectangle is fitted on the
+    /// plane through the focus point. Rectangles smaller than a few pixels are
+    /// ignored.
+    pub fn zoom_to_rect(&mut self, a: Vec2, b: Vec2) {
+        let size = self.last_framebuffer_size;
+        let (min, max) = (a.min(b), a.max(b));
+        let extent = max - min;
+        if extent.x < 4.0 || extent.y < 4.0 {
+            return;
+        }
+
+        // Half extents of the view on the focus plane, where both projections agree.
+        let half_h = self.dist * (self.fov * 0.5).tan();
+        let half_w = half_h * size.x / size.y;
+        let center = (min + max) * 0.5;
+        let ndc = Vec2::new(center.x / size.x * 2.0 - 1.0, 1.0 - center.y / size.y * 2.0);
+        let rotation = self.view_transform().rotation.inverse();
+        self.at += rotation * Vec3::new(ndc.x * half_w, ndc.y * half_h, 0.0);
+        self.dist *= (extent.x / size.x).max(extent.y / size.y);
+
+        self.update_restrictions();
+        self.update_projviews();
+    }
+
     /// Positions and orients the camera to look at a specific point from a specific position.
     ///
     /// This is similar to gluLookAt. The camera will be positioned at `eye`,
@@ -336,6 +466,28 @@ impl OrbitCamera3d {
         self.update_projviews();
     }
 
+    /// Centers the camera on the axis-aligned box `[min, max]` and moves it
+    /// away just enough for the box to fill the view, keeping the current yaw
+    /// and pitch.
+    ///
+    /// `margin` is the fraction of the box size kept as empty space around it.
+    pub fn look_at_aabb(&mut self, min: Vec3, max: Vec3, margin: f32) {
+        let radius = ((max - min).length() * 0.5 * (1.0 + margin)).max(1.0e-4);
+        let aspect = self.last_framebuffer_size.x / self.last_framebuffer_size.y;
+
+        self.at = (min + max) * 0.5;
+        self.dist = match self.projection {
+            super::Projection::Perspective => {
+                super::camera3d::fit_distance(radius, self.fov, aspect)
+            }
+            // The orthographic half-height is `dist * tan(fov / 2)`.
+            super::Projection::Orthographic => radius / ((self.fov * 0.5).tan() * aspect.min(1.0)),
+        };
+
+        self.update_restrictions();
+        self.update_projviews();
+    }
+
     /// Transformation applied by the camera without perspective.
     fn update_restrictions(&mut self) {
         if self.dist < self.min_dist {
@@ -417,10 +569,18 @@ impl OrbitCamera3d {
     }
 
     fn handle_left_button_displacement(&mut self, dpos: Vec2) {
+        let old_rotation = self.view_transform().rotation.inverse();
+
         self.yaw += dpos.x * self.yaw_step;
         self.pitch -= dpos.y * self.pitch_step;
-
         self.update_restrictions();
+
+        if let Some(pivot) = self.rotation_pivot {
+            // Apply the same rotation to the focus point, around the pivot.
+            let new_rotation = self.view_transform().rotation.inverse();
+            let rotation = new_rotation * old_rotation.inverse();
+            self.at = pivot + rotation * (self.at - pivot);
+        }
         self.update_projviews();
     }
 
@@ -446,7 +606,7 @@ impl OrbitCamera3d {
         );
         self.handle_right_button_displacement(dpos);
 
-        self.dist *= self.dist_step.powf(off);
+        self.dist *= self.dist_step.powf(off * self.zoom_sensitivity);
         self.update_restrictions();
         self.update_projviews();
 
@@ -505,6 +665,12 @@ impl OrbitCamera3d {
         self.update_projviews();
     }
 
+    /// The up-axis direction of this camera.
+    #[inline]
+    pub fn up_axis(&self) -> Vec3 {
+        self.coord_system.up_axis
+    }
+
     /// Sets the up vector of this camera. Prefer using [`set_up_axis_dir`](#method.set_up_axis_dir)
     /// if your up vector is already normalized.
     #[inline]
@@ -538,6 +704,34 @@ impl OrbitCamera3d {
 }
 
 impl Camera3d for OrbitCamera3d {
+    fn state(&self) -> CameraState {
+        CameraState {
+            eye: self.eye(),
+            at: self.at,
+            up: self.coord_system.up_axis,
+            fov: self.fov,
+            znear: self.znear,
+            zfar: self.zfar,
+            projection: self.projection,
+        }
+    }
+
+    fn orbit_around_up(&mut self, angle: f32) {
+        self.yaw += angle;
+        self.update_projviews();
+    }
+
+    fn set_state(&mut self, state: CameraState) {
+        self.fov = state.fov;
+        self.znear = state.znear;
+        self.zfar = state.zfar;
+        self.projection = state.projection;
+        if state.up != Vec3::ZERO {
+            self.set_up_axis(state.up);
+        }
+        self.look_at(state.eye, state.at);
+    }
+
     fn clip_planes(&self) -> (f32, f32) {
         (self.znear, self.zfar)
     }
@@ -559,6 +753,12 @@ impl Camera3d for OrbitCamera3d {
             WindowEvent::CursorPos(x, y, modifiers) => {
                 let curr_pos = Vec2::new(x as f32, y as f32);
 
+                if let Some((_, end)) = &mut self.box_zoom {
+                    *end = curr_pos;
+                    self.last_cursor_pos = curr_pos;
+                    return;
+                }
+
                 if let Some(rotate_button) = self.rotate_button {
                     if canvas.get_mouse_button(rotate_button) == Action::Press
                         && self
@@ -576,13 +776,25 @@ impl Camera3d for OrbitCamera3d {
                         && self.drag_modifiers.map(|m| m == modifiers).unwrap_or(true)
                     {
                         let dpos = curr_pos - self.last_cursor_pos;
-                        let dpos_norm = dpos / self.last_framebuffer_size;
+                        let dpos_norm = dpos / self.last_framebuffer_size * self.pan_sensitivity;
                         self.handle_right_button_displacement(dpos_norm)
                     }
                 }
 
                 self.last_cursor_pos = curr_pos;
             }
+            WindowEvent::MouseButton(button, action, modifiers)
+                if Some(button) == self.rotate_button =>
+            {
+                self.rotation_pivot = None;
+                if let Some((start, end)) = self.box_zoom.take() {
+                    self.zoom_to_rect(start, end);
+                } else if action == Action::Press && self.box_zoom_modifiers == Some(modifiers) {
+                    self.box_zoom = Some((self.last_cursor_pos, self.last_cursor_pos));
+                } else if action == Action::Press && self.rotate_around_cursor {
+                    self.pick_request = Some(self.last_cursor_pos);
+                }
+            }
             WindowEvent::Key(key, Action::Press, _) if Some(key) == self.reset_key => {
                 self.at = Vec3::ZERO;
                 self.update_projviews();
@@ -614,4 +826,27 @@ impl Camera3d for OrbitCamera3d {
     }
 
     fn update(&mut self, _: &Canvas) {}
+
+    fn take_pick_request(&mut self) -> Option<Vec2> {
+        self.pick_request.take()
+    }
+
+    fn set_picked_point(&mut self, point: Option<Vec3>) {
+        self.rotation_pivot = point;
+    }
+
+    fn box_zoom_rect(&self) -> Option<(Vec2, Vec2)> {
+        self.box_zoom
+    }
+
+    fn shift_origin(&mut self, offset: Vec3) -> bool {
+        self.at -= offset;
+        self.rotation_pivot = self.rotation_pivot.map(|p| p - offset);
+        self.update_projviews();
+        true
+    }
+
+    fn fit_aabb(&mut self, min: Vec3, max: Vec3, margin: f32) {
+        self.look_at_aabb(min, max, margin)
+    }
 }
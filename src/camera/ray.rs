@@ -0,0 +1,196 @@
+use crate::camera::Camera3d;
+use crate::context::Context;
+use glamx::Vec3;
+
+/// A half-line in world space, e.g. the ray through a pixel returned by
+/// [`Window::cursor_ray`](crate::window::Window::cursor_ray).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ray {
+    /// The start point of the ray.
+    pub origin: Vec3,
+    /// The normalized direction of the ray.
+    pub dir: Vec3,
+}
+
+impl Ray {
+    /// Creates a ray from `origin` along `dir` (normalized by this constructor).
+    pub fn new(origin: Vec3, dir: Vec3) -> Self {
+        Ray {
+            origin,
+            dir: dir.normalize_or_zero(),
+        }
+    }
+
+    /// The point at distance `t` along the ray.
+    #[inline]
+    pub fn point_at(&self, t: f32) -> Vec3 {
+        self.origin + self.dir * t
+    }
+
+    /// The distance along the ray at which it crosses the plane through `point`
+    /// with the given `normal`, if it does (in front of its origin).
+    pub fn intersect_plane(&self, point: Vec3, normal: Vec3) -> Option<f32> {
+        let denom = self.dir.dot(normal);
+        if denom.abs() <= 1.0e-6 {
+            return None;
+        }
+        let t = (point - self.origin).dot(normal) / denom;
+        (t >= 0.0).then_some(t)
+    }
+
+    /// The parameter along the infinite line through `point` along `direction`
+    /// (normalized) of the point of that line closest to this ray, if the ray is
+    /// not parallel to it.
+    pub fn closest_on_line(&self, point: Vec3, direction: Vec3) -> Option<f32> {
+        // Closest points between two lines, see e.g. Ericson, "Real-Time Collision
+        // Detection", 5.1.8.
+        let b = self.dir.dot(direction);
+        let denom = 1.0 - b * b;
+        if denom <= 1.0e-6 {
+            return None;
+        }
+        let r = self.origin - point;
+        let c = self.dir.dot(r);
+        let f = direction.dot(r);
+        Some((f - b * c) / denom)
+    }
+}
+
+/// The set of points a dragged object is constrained to.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DragConstraint {
+    /// The plane through the drag origin with the given normal.
+    Plane(Vec3),
+    /// The line through the drag origin along the given direction.
+    Axis(Vec3),
+}
+
+/// Converts mouse motion into world-space translations constrained to a plane
+/// or an axis, for dragging objects with the mouse.
+///
+/// Each cursor position is turned into a ray with
+/// [`Window::cursor_ray`](crate::window::Window::cursor_ray) (which takes care of
+/// the HiDPI scale factor and letterboxed viewports); [`DragPlane::translation`]
+/// then gives how far the grabbed point moved along the constraint since
+/// [`DragPlane::begin`].
+///
+/// # Example
+/// ```no_run
+/// # use kiss3d::prelude::*;
+/// # #[kiss3d::main]
+/// # async fn main() {
+/// # let mut window = Window::new("Example").await;
+/// # let mut camera = OrbitCamera3d::default();
+/// # let mut scene = SceneNode3d::empty();
+/// let mut cube = scene.add_cube(1.0, 1.0, 1.0);
+/// let mut drag: Option<(DragPlane, Vec3)> = None;
+///
+/// while window.render_3d(&mut scene, &mut camera).await {
+///     let Some(ray) = window.cursor_ray(&camera) else { continue };
+///     match window.get_mouse_button(MouseButton::Button3) {
+///         Action::Press => {
+///             let start = cube.position();
+///             let (plane, grabbed) = drag.get_or_insert_with(|| {
+///                 // Drag on the ground plane through the cube.
+///                 let mut plane = DragPlane::plane(start, Vec3::Y);
+///                 plane.begin(&ray);
+///                 (plane, start)
+///             });
+///             if let Some(delta) = plane.translation(&ray) {
+///                 cube.set_position(*grabbed + delta);
+///             }
+///         }
+///         Action::Release => drag = None,
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DragPlane {
+    /// A point of the plane or axis, typically the position of the dragged
+    /// object.
+    pub origin: Vec3,
+    /// The plane or axis the drag is constrained to.
+    pub constraint: DragConstraint,
+    /// The point of the constraint grabbed by [`DragPlane::begin`].
+    grab: Option<Vec3>,
+}
+
+impl DragPlane {
+    /// A drag constrained to the plane through `origin` with the given `normal`.
+    pub fn plane(origin: Vec3, normal: Vec3) -> Self {
+        DragPlane {
+            origin,
+            constraint: DragConstraint::Plane(normal.normalize_or_zero()),
+            grab: None,
+        }
+    }
+
+    /// A drag constrained to the horizontal plane through `origin`, e.g. to move
+    /// objects on the ground, perpendicular to the world up axis (see
+    /// [`Window::set_world_up`](crate::window::Window::set_world_up)).
+    pub fn ground(origin: Vec3) -> Self {
+        Self::plane(origin, Context::world_up())
+    }
+
+    /// A drag constrained to the plane through `origin` facing `camera`, which
+    /// follows the cursor exactly.
+    pub fn camera_facing(origin: Vec3, camera: &dyn Camera3d) -> Self {
+        let normal = camera.eye() - origin;
+        Self::plane(origin, normal)
+    }
+
+    /// A drag constrained to the line through `origin` along `direction`, e.g.
+    /// one axis of a translation gizmo.
+    pub fn axis(origin: Vec3, direction: Vec3) -> Self {
+        DragPlane {
+            origin,
+            constraint: DragConstraint::Axis(direction.normalize_or_zero()),
+            grab: None,
+        }
+    }
+
+    /// The point of the plane or axis under `ray`, if any.
+    ///
+    /// For an axis, this is the point of the axis closest to the ray. `None` if
+    /// the ray is parallel to the constraint or, for a plane, points away from it.
+    pub fn project(&self, ray: &Ray) -> Option<Vec3> {
+        match self.constraint {
+            DragConstraint::Plane(normal) => ray
+                .intersect_plane(self.origin, normal)
+                .map(|t| ray.point_at(t)),
+            DragConstraint::Axis(direction) => ray
+                .closest_on_line(self.origin, direction)
+                .map(|s| self.origin + direction * s),
+        }
+    }
+
+    /// Starts a drag, grabbing the point of the constraint under `ray`.
+    ///
+    /// Returns `false` (and does not start the drag) if `ray` misses the
+    /// constraint.
+    pub fn begin(&mut self, ray: &Ray) -> bool {
+        self.grab = self.project(ray);
+        self.grab.is_some()
+    }
+
+    /// Ends the drag started by [`DragPlane::begin`].
+    pub fn end(&mut self) {
+        self.grab = None;
+    }
+
+    /// Whether a drag is in progress.
+    pub fn is_dragging(&self) -> bool {
+        self.grab.is_some()
+    }
+
+    /// The translation, along the constraint, from the point grabbed by
+    /// [`DragPlane::begin`] to the point under `ray`.
+    ///
+    /// `None` if no drag is in progress, or if `ray` misses the constraint (e.g.
+    /// the cursor went above the horizon of a ground plane).
+    pub fn translation(&self, ray: &Ray) -> Option<Vec3> {
+        let grab = self.grab?;
+        self.project(ray).map(|p| p - grab)
+    }
+}
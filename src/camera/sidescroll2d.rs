@@ -5,6 +5,12 @@ use glamx::{Mat3, Vec2, Vec3, Vec3Swizzles};
 use num::Pow;
 
 /// A 2D camera that can be zoomed and panned.
+///
+/// The following inputs are handled:
+///
+/// * Right button press + drag - pans the view
+/// * Scroll in/out - zoom in/out
+/// * Shift + left button drag - zooms to fit the dragged rectangle
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PanZoomCamera2d {
@@ -17,12 +23,17 @@ pub struct PanZoomCamera2d {
     zoom_modifier: Option<Modifiers>,
     drag_button: Option<MouseButton>,
     drag_modifier: Option<Modifiers>,
+    box_zoom_button: Option<MouseButton>,
+    box_zoom_modifier: Option<Modifiers>,
+    /// The start and current cursor positions of the box zoom being dragged.
+    box_zoom: Option<(Vec2, Vec2)>,
 
     view: Mat3,
     proj: Mat3,
     scaled_proj: Mat3,
     inv_scaled_proj: Mat3,
     last_cursor_pos: Vec2,
+    last_framebuffer_size: Vec2,
 }
 
 impl Default for PanZoomCamera2d {
@@ -41,11 +52,15 @@ impl PanZoomCamera2d {
             zoom_modifier: None,
             drag_button: Some(MouseButton::Button2),
             drag_modifier: None,
+            box_zoom_button: Some(MouseButton::Button1),
+            box_zoom_modifier: Some(Modifiers::Shift),
+            box_zoom: None,
             view: Mat3::IDENTITY,
             proj: Mat3::IDENTITY,
             scaled_proj: Mat3::IDENTITY,
             inv_scaled_proj: Mat3::IDENTITY,
             last_cursor_pos: Vec2::ZERO,
+            last_framebuffer_size: Vec2::new(800.0, 600.0),
         };
 
         res.update_projviews();
@@ -122,6 +137,46 @@ impl PanZoomCamera2d {
         self.drag_modifier = new_modifier;
     }
 
+    /// The button used to drag a zoom rectangle.
+    pub fn box_zoom_button(&self) -> Option<MouseButton> {
+        self.box_zoom_button
+    }
+
+    /// Set the button used to drag a zoom rectangle.
+    /// Use None to disable box zooming.
+    pub fn rebind_box_zoom_button(&mut self, new_button: Option<MouseButton>) {
+        self.box_zoom_button = new_button;
+        self.box_zoom = None;
+    }
+
+    /// The modifier that must be pressed to start dragging a zoom rectangle.
+    pub fn box_zoom_modifier(&self) -> Option<Modifiers> {
+        self.box_zoom_modifier
+    }
+
+    /// Set the modifier that must be pressed to start dragging a zoom rectangle
+    /// (default: `Shift`). Use None to start it without modifier.
+    pub fn rebind_box_zoom_modifier(&mut self, new_modifier: Option<Modifiers>) {
+        self.box_zoom_modifier = new_modifier;
+    }
+
+    /// Pans and zooms so that the window rectangle with corners `a` and `b`, in
+    /// pixels, fills the view. Rectangles smaller than a few pixels are ignored.
+    pub fn zoom_to_rect(&mut self, a: Vec2, b: Vec2) {
+        let size = self.last_framebuffer_size;
+        let (min, max) = (a.min(b), a.max(b));
+        let extent = max - min;
+        if extent.x < 4.0 || extent.y < 4.0 {
+            return;
+        }
+
+        let center = (min + max) * 0.5 - size * 0.5;
+        self.at += Vec2::new(center.x, -center.y) / self.zoom;
+        self.zoom *= (size.x / extent.x).min(size.y / extent.y);
+        self.update_restrictions();
+        self.update_projviews();
+    }
+
     /// Move the camera based on drag from right mouse button
     /// `dpos` is assumed to be in window space so the y-axis is flipped
     fn handle_right_button_displacement(&mut self, dpos: Vec2) {
@@ -174,6 +229,12 @@ impl Camera2d for PanZoomCamera2d {
             WindowEvent::CursorPos(x, y, modifiers) => {
                 let curr_pos = Vec2::new(x as f32, y as f32);
 
+                if let Some((_, end)) = &mut self.box_zoom {
+                    *end = curr_pos;
+                    self.last_cursor_pos = curr_pos;
+                    return;
+                }
+
                 if let Some(drag_button) = self.drag_button {
                     if (self.drag_modifier.is_none() || self.drag_modifier == Some(modifiers))
                         && canvas.get_mouse_button(drag_button) == Action::Press
@@ -185,12 +246,25 @@ impl Camera2d for PanZoomCamera2d {
 
                 self.last_cursor_pos = curr_pos;
             }
+            WindowEvent::MouseButton(button, action, modifiers)
+                if Some(button) == self.box_zoom_button =>
+            {
+                if let Some((start, end)) = self.box_zoom.take() {
+                    self.zoom_to_rect(start, end);
+                } else if action == Action::Press
+                    && (self.box_zoom_modifier.is_none()
+                        || self.box_zoom_modifier == Some(modifiers))
+                {
+                    self.box_zoom = Some((self.last_cursor_pos, self.last_cursor_pos));
+                }
+            }
             WindowEvent::Scroll(_, off, modifiers)
                 if (self.zoom_modifier.is_none() || self.zoom_modifier == Some(modifiers)) =>
             {
                 self.handle_scroll(off as f32)
             }
             WindowEvent::FramebufferSize(w, h) => {
+                self.last_framebuffer_size = Vec2::new(w as f32, h as f32);
                 self.proj = Mat3::from_cols(
                     Vec3::new(2.0 * (scale as f32) / (w as f32), 0.0, 0.0),
                     Vec3::new(0.0, 2.0 * (scale as f32) / (h as f32), 0.0),
@@ -226,4 +300,8 @@ impl Camera2d for PanZoomCamera2d {
         let screen_pos = unprojected_homogeneous.xy() / unprojected_homogeneous.z;
         screen_pos + self.at
     }
+
+    fn box_zoom_rect(&self) -> Option<(Vec2, Vec2)> {
+        self.box_zoom
+    }
 }
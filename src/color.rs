@@ -517,3 +517,159 @@ pub const MISTY_ROSE: Color = Color::new(1.0, 0.89411765, 0.88235295, 1.0);
 
 /// Transparent color (0, 0, 0, 0). Useful for clearing or as a default.
 pub const TRANSPARENT: Color = Color::new(0.0, 0.0, 0.0, 0.0);
+
+// ============================================================================
+// Colormaps
+// ============================================================================
+
+/// A color ramp mapping scalar values in `[0, 1]` to colors, e.g. to display
+/// simulation results with
+/// [`SceneNode3d::set_scalar_field`](crate::scene::SceneNode3d::set_scalar_field).
+///
+/// The built-in maps interpolate linearly between evenly-spaced samples of the
+/// well-known matplotlib (and Google Turbo) maps.
+///
+/// # Example
+/// ```
+/// # use kiss3d::color::Colormap;
+/// let low = Colormap::Viridis.sample(0.0);
+/// let high = Colormap::Viridis.sample(1.0);
+/// assert!(high.g > low.g);
+/// ```
+#[derive(Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Colormap {
+    /// Perceptually uniform dark purple → green → yellow (the default).
+    #[default]
+    Viridis,
+    /// Perceptually uniform dark blue → magenta → yellow.
+    Plasma,
+    /// Perceptually uniform black → red → pale yellow.
+    Inferno,
+    /// Perceptually uniform black → pink → pale yellow.
+    Magma,
+    /// High-contrast rainbow, dark blue → green → dark red.
+    Turbo,
+    /// Diverging blue → light gray → red, for values around a midpoint.
+    Coolwarm,
+    /// Black → white.
+    Grayscale,
+    /// Evenly-spaced colors, from the color of `0.0` to the color of `1.0`.
+    Custom(Vec<Color>),
+}
+
+impl Colormap {
+    /// The color of `t`, clamped to `[0, 1]`. A NaN `t` maps to `0.0`.
+    pub fn sample(&self, t: f32) -> Color {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        match self {
+            Colormap::Custom(colors) => match colors.len() {
+                0 => BLACK,
+                1 => colors[0],
+                n => {
+                    let x = t * (n - 1) as f32;
+                    let i = (x as usize).min(n - 2);
+                    let f = x - i as f32;
+                    let (a, b) = (colors[i], colors[i + 1]);
+                    Color::new(
+                        a.r + (b.r - a.r) * f,
+                        a.g + (b.g - a.g) * f,
+                        a.b + (b.b - a.b) * f,
+                        a.a + (b.a - a.a) * f,
+                    )
+                }
+            },
+            _ => {
+                let samples = self.samples();
+                let x = t * (samples.len() - 1) as f32;
+                let i = (x as usize).min(samples.len() - 2);
+                let f = x - i as f32;
+                let (a, b) = (samples[i], samples[i + 1]);
+                let channel = |k: usize| (a[k] as f32 + (b[k] as f32 - a[k] as f32) * f) / 255.0;
+                Color::new(channel(0), channel(1), channel(2), 1.0)
+            }
+        }
+    }
+
+    /// The color of `value` with `range` mapped to `[0, 1]`.
+    ///
+    /// An empty range maps every value to the middle of the map.
+    pub fn map(&self, value: f32, range: (f32, f32)) -> Color {
+        let extent = range.1 - range.0;
+        if extent.abs() <= f32::EPSILON {
+            self.sample(0.5)
+        } else {
+            self.sample((value - range.0) / extent)
+        }
+    }
+
+    /// Evenly-spaced sRGB samples of the built-in maps.
+    fn samples(&self) -> &'static [[u8; 3]] {
+        match self {
+            Colormap::Viridis => &[
+                [0x44, 0x01, 0x54],
+                [0x47, 0x2d, 0x7b],
+                [0x3b, 0x52, 0x8b],
+                [0x2c, 0x72, 0x8e],
+                [0x21, 0x91, 0x8c],
+                [0x28, 0xae, 0x80],
+                [0x5e, 0xc9, 0x62],
+                [0xad, 0xdc, 0x30],
+                [0xfd, 0xe7, 0x25],
+            ],
+            Colormap::Plasma => &[
+                [0x0d, 0x08, 0x87],
+                [0x4c, 0x02, 0xa1],
+                [0x7e, 0x03, 0xa8],
+                [0xa9, 0x23, 0x95],
+                [0xcc, 0x47, 0x78],
+                [0xe6, 0x6c, 0x5c],
+                [0xf8, 0x95, 0x40],
+                [0xfd, 0xc3, 0x28],
+                [0xf0, 0xf9, 0x21],
+            ],
+            Colormap::Inferno => &[
+                [0x00, 0x00, 0x04],
+                [0x1f, 0x0c, 0x48],
+                [0x55, 0x0f, 0x6d],
+                [0x88, 0x22, 0x6a],
+                [0xba, 0x36, 0x55],
+                [0xe3, 0x59, 0x33],
+                [0xf9, 0x8e, 0x09],
+                [0xf8, 0xc9, 0x32],
+                [0xfc, 0xff, 0xa4],
+            ],
+            Colormap::Magma => &[
+                [0x00, 0x00, 0x04],
+                [0x1c, 0x10, 0x44],
+                [0x4f, 0x12, 0x7b],
+                [0x81, 0x25, 0x81],
+                [0xb5, 0x36, 0x7a],
+                [0xe5, 0x50, 0x64],
+                [0xfb, 0x87, 0x61],
+                [0xfe, 0xc2, 0x87],
+                [0xfc, 0xfd, 0xbf],
+            ],
+            Colormap::Turbo => &[
+                [0x30, 0x12, 0x3b],
+                [0x46, 0x62, 0xd7],
+                [0x36, 0xaa, 0xf9],
+                [0x1a, 0xe4, 0xb6],
+                [0x72, 0xfe, 0x5e],
+                [0xc7, 0xef, 0x34],
+                [0xfa, 0xba, 0x39],
+                [0xf6, 0x6b, 0x19],
+                [0xcb, 0x2a, 0x04],
+                [0x7a, 0x04, 0x03],
+            ],
+            Colormap::Coolwarm => &[
+                [0x3b, 0x4c, 0xc0],
+                [0x8d, 0xb0, 0xfe],
+                [0xdd, 0xdd, 0xdd],
+                [0xf4, 0x9a, 0x7b],
+                [0xb4, 0x04, 0x26],
+            ],
+            Colormap::Grayscale | Colormap::Custom(_) => &[[0, 0, 0], [0xff, 0xff, 0xff]],
+        }
+    }
+}
@@ -4,8 +4,11 @@
 //! across window recreations.
 
 use std::cell::{Cell, RefCell};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
+use glamx::Vec3;
+
 // The global wgpu context singleton.
 // We use RefCell<Option<>> instead of OnceLock to allow resetting the context
 // when creating new windows (required for multi-window support).
@@ -13,6 +16,37 @@ thread_local! {
     static CONTEXT_SINGLETON: RefCell<Option<Context>> = const { RefCell::new(None) };
     // Track number of active windows to know when to reset the context
     static WINDOW_COUNT: Cell<usize> = const { Cell::new(0) };
+    // The up axis of the world, set with `Window::set_world_up`.
+    static WORLD_UP: Cell<Vec3> = const { Cell::new(Vec3::Y) };
+    // The format of the depth buffers, chosen when the context is created.
+    static DEPTH_FORMAT: Cell<wgpu::TextureFormat> = const { Cell::new(wgpu::TextureFormat::Depth32Float) };
+    // The winding of front faces, set with `Window::set_default_winding`.
+    static DEFAULT_WINDING: Cell<wgpu::FrontFace> = const { Cell::new(wgpu::FrontFace::Ccw) };
+    // Timing of the frame being rendered, set by the window rendering it.
+    static FRAME_TIME: Cell<FrameTime> = const {
+        Cell::new(FrameTime {
+            time: 0.0,
+            delta: 0.0,
+            frame: 0,
+        })
+    };
+}
+
+/// Timing of the frame being rendered, for animating materials, shaders and
+/// post-processing effects.
+///
+/// Set by the window at the start of each frame and read with
+/// [`Context::frame_time`]. The built-in object material also exposes
+/// `time` and `delta` to its shader as `frame.time.xy`.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FrameTime {
+    /// Seconds elapsed since the window rendering the frame was created.
+    pub time: f32,
+    /// Seconds elapsed since the previous frame of that window (`0` on its first
+    /// frame).
+    pub delta: f32,
+    /// Number of frames rendered by that window, including this one.
+    pub frame: u64,
 }
 
 /// The wgpu rendering context containing all GPU resources needed for rendering.
@@ -31,6 +65,8 @@ pub struct Context {
     pub adapter: Arc<wgpu::Adapter>,
     /// The preferred texture format for the surface.
     pub surface_format: wgpu::TextureFormat,
+    /// Set when the device is lost, by wgpu's device-lost callback.
+    device_lost: Arc<AtomicBool>,
 }
 
 impl Context {
@@ -52,6 +88,16 @@ impl Context {
         adapter: wgpu::Adapter,
         surface_format: wgpu::TextureFormat,
     ) {
+        let device_lost = Arc::new(AtomicBool::new(false));
+        let lost = device_lost.clone();
+        device.set_device_lost_callback(move |reason, message| {
+            // `Destroyed` is the context being reset on purpose.
+            if !matches!(reason, wgpu::DeviceLostReason::Destroyed) {
+                log::error!("the GPU device was lost: {}", message);
+                lost.store(true, Ordering::Relaxed);
+            }
+        });
+
         CONTEXT_SINGLETON.with(|cell| {
             *cell.borrow_mut() = Some(Context {
                 instance: Arc::new(instance),
@@ -59,10 +105,31 @@ impl Context {
                 queue: Arc::new(queue),
                 adapter: Arc::new(adapter),
                 surface_format,
+                device_lost,
             });
         });
     }
 
+    /// Whether the GPU device was lost, e.g. after a driver reset or a switch
+    /// of GPU on a laptop.
+    ///
+    /// A lost device can't be used anymore: every GPU resource created from it
+    /// must be re-created with a new context, which is done by dropping all the
+    /// windows and creating a new one (see
+    /// [`Window::is_device_lost`](crate::window::Window::is_device_lost)).
+    pub fn is_device_lost(&self) -> bool {
+        self.device_lost.load(Ordering::Relaxed)
+    }
+
+    /// Information about the GPU adapter the context was created on: its name,
+    /// vendor, device type and graphics backend.
+    ///
+    /// Useful to log which GPU and backend were selected (see
+    /// [`CanvasSetup`](crate::window::CanvasSetup) for choosing them).
+    pub fn adapter_info(&self) -> wgpu::AdapterInfo {
+        self.adapter.get_info()
+    }
+
     /// Gets a clone of the global wgpu context.
     ///
     /// # Panics
@@ -76,6 +143,40 @@ impl Context {
         })
     }
 
+    /// Timing of the frame currently (or last) rendered.
+    pub fn frame_time() -> FrameTime {
+        FRAME_TIME.with(Cell::get)
+    }
+
+    /// Sets the timing returned by [`Context::frame_time`]. Called by the window
+    /// at the start of each frame.
+    pub(crate) fn set_frame_time(time: FrameTime) {
+        FRAME_TIME.with(|cell| cell.set(time));
+    }
+
+    /// The up axis of the world: `Y` unless changed with
+    /// [`Window::set_world_up`](crate::window::Window::set_world_up).
+    pub fn world_up() -> Vec3 {
+        WORLD_UP.with(Cell::get)
+    }
+
+    /// Sets the axis returned by [`Context::world_up`].
+    pub(crate) fn set_world_up(up: Vec3) {
+        WORLD_UP.with(|cell| cell.set(up));
+    }
+
+    /// The winding of the front faces of objects that don't set their own:
+    /// counter-clockwise unless changed with
+    /// [`Window::set_default_winding`](crate::window::Window::set_default_winding).
+    pub fn default_winding() -> wgpu::FrontFace {
+        DEFAULT_WINDING.with(Cell::get)
+    }
+
+    /// Sets the winding returned by [`Context::default_winding`].
+    pub(crate) fn set_default_winding(winding: wgpu::FrontFace) {
+        DEFAULT_WINDING.with(|cell| cell.set(winding));
+    }
+
     /// Checks if the context has been initialized.
     pub fn is_initialized() -> bool {
         CONTEXT_SINGLETON.with(|cell| cell.borrow().is_some())
@@ -261,6 +362,35 @@ impl Context {
             })
     }
 
+    /// Creates a compute pipeline from WGSL source.
+    ///
+    /// The pipeline layout is derived from the shader, so the bind group layouts
+    /// to create bind groups with are available through
+    /// `wgpu::ComputePipeline::get_bind_group_layout`. Dispatch the pipeline with
+    /// `Window::dispatch_compute` to run it as part of the next frame.
+    ///
+    /// # Arguments
+    /// * `label` - Debug label for the shader and pipeline
+    /// * `source` - WGSL shader source code
+    /// * `entry_point` - Name of the `@compute` entry point
+    pub fn create_compute_pipeline(
+        &self,
+        label: Option<&str>,
+        source: &str,
+        entry_point: &str,
+    ) -> wgpu::ComputePipeline {
+        let module = self.create_shader_module(label, source);
+        self.device
+            .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label,
+                layout: None,
+                module: &module,
+                entry_point: Some(entry_point),
+                compilation_options: Default::default(),
+                cache: None,
+            })
+    }
+
     /// Creates a new command encoder.
     ///
     /// # Arguments
@@ -272,10 +402,14 @@ impl Context {
 
     /// Submits command buffers to the GPU queue.
     ///
+    /// The uploads staged by the [`GPUVec`](crate::resource::GPUVec)s in
+    /// [`UploadMode::StagingBelt`](crate::resource::UploadMode::StagingBelt) are
+    /// submitted first.
+    ///
     /// # Arguments
     /// * `command_buffers` - Iterator of command buffers to submit
     pub fn submit<I: IntoIterator<Item = wgpu::CommandBuffer>>(&self, command_buffers: I) {
-        self.queue.submit(command_buffers);
+        self.submit_indexed(command_buffers);
     }
 
     /// Submits command buffers to the GPU queue, returning the submission
@@ -285,7 +419,12 @@ impl Context {
         &self,
         command_buffers: I,
     ) -> wgpu::SubmissionIndex {
-        self.queue.submit(command_buffers)
+        let uploads = crate::resource::gpu_vector::take_staged_uploads();
+        let index = self
+            .queue
+            .submit(uploads.into_iter().chain(command_buffers));
+        crate::resource::gpu_vector::recall_staged_uploads();
+        index
     }
 
     /// Writes texture data to the GPU.
@@ -306,8 +445,48 @@ impl Context {
     }
 
     /// Gets the depth texture format used for depth attachments.
+    ///
+    /// `Depth32Float` unless another format was chosen with
+    /// [`CanvasSetup::depth_format`](crate::window::CanvasSetup::depth_format).
     pub fn depth_format() -> wgpu::TextureFormat {
-        wgpu::TextureFormat::Depth32Float
+        DEPTH_FORMAT.with(Cell::get)
+    }
+
+    /// Sets the depth texture format used for depth attachments, e.g.
+    /// `Depth24PlusStencil8` for custom pipelines using the stencil buffer.
+    ///
+    /// The depth format also sets the precision of the depth read back by
+    /// [`Window::snap_depth_image`](crate::window::Window::snap_depth_image):
+    /// `Depth32Float` keeps full floating-point precision while 24-bit formats
+    /// quantize it. Shadow maps always use `Depth32Float`.
+    ///
+    /// Called by the first window with its
+    /// [`CanvasSetup::depth_format`](crate::window::CanvasSetup::depth_format),
+    /// before any pipeline or depth texture is created: changing it afterwards
+    /// makes them incompatible.
+    ///
+    /// # Panics
+    /// Panics if `format` has no depth aspect.
+    pub fn set_depth_format(format: wgpu::TextureFormat) {
+        assert!(
+            format.has_depth_aspect(),
+            "{:?} is not a depth texture format",
+            format
+        );
+        DEPTH_FORMAT.with(|cell| cell.set(format));
+    }
+
+    /// Creates a view of the depth aspect of `texture`, a depth texture with
+    /// the [`depth_format`](Self::depth_format), to sample it in a shader.
+    ///
+    /// Views of all the aspects, used as render attachments, can't be sampled
+    /// when the format has a stencil aspect too.
+    pub fn depth_sample_view(texture: &wgpu::Texture) -> wgpu::TextureView {
+        texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("depth_sample_view"),
+            aspect: wgpu::TextureAspect::DepthOnly,
+            ..Default::default()
+        })
     }
 
     /// Whether this device can run clustered (forward+) lighting.
@@ -322,11 +501,22 @@ impl Context {
     /// compile-time `cfg(target_arch = "wasm32")` gate would wrongly disable clustering on
     /// WebGPU.
     pub fn supports_clustered_lighting(&self) -> bool {
+        self.supports_compute() && self.device.limits().max_storage_buffers_per_shader_stage >= 3
+    }
+
+    /// Whether this device can run compute shaders writing to storage buffers.
+    ///
+    /// Native and WebGPU browsers can; WebGL2 cannot, so the features relying on
+    /// compute passes (e.g. [`Window::snap_depth_image`](crate::window::Window::snap_depth_image))
+    /// are unavailable there. Only one storage buffer per shader stage is
+    /// required, the most those features bind; clustered lighting binds three
+    /// and checks for them in [`supports_clustered_lighting`](Self::supports_clustered_lighting).
+    pub fn supports_compute(&self) -> bool {
         self.adapter
             .get_downlevel_capabilities()
             .flags
             .contains(wgpu::DownlevelFlags::COMPUTE_SHADERS)
-            && self.device.limits().max_storage_buffers_per_shader_stage >= 3
+            && self.device.limits().max_storage_buffers_per_shader_stage >= 1
     }
 
     /// The internal floating-point color format the rasterizer renders into.
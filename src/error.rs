@@ -1,37 +1,81 @@
-#[macro_export]
-macro_rules! verify(
-    ($e: expr) => {
-        {
-            let res = $e;
-            #[cfg(not(target_arch = "wasm32"))]
-            { assert_eq!($crate::context::Context::get().get_error(), 0); }
-            res
-        }
-    }
-);
+//! Errors reported by the fallible window and resource constructors.
+
+use std::fmt;
+use std::path::PathBuf;
+
+/// An error raised while creating a window, its GPU context, or a resource.
+///
+/// The infallible constructors ([`Window::new`](crate::window::Window::new),
+/// [`TextureManager::add`](crate::resource::TextureManager::add), …) panic with
+/// this error's message; their `try_` counterparts
+/// ([`Window::try_new`](crate::window::Window::try_new),
+/// [`TextureManager::try_add`](crate::resource::TextureManager::try_add), …)
+/// return it instead, so an application can report it or retry with different
+/// settings (e.g. a fallback adapter).
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The OS window (or, on the web, the canvas) could not be created.
+    WindowCreation(String),
+    /// The wgpu surface could not be created for the window.
+    SurfaceCreation(wgpu::CreateSurfaceError),
+    /// No GPU adapter matching the requested options was found.
+    AdapterRequest(wgpu::RequestAdapterError),
+    /// The GPU adapter refused to create a device.
+    DeviceRequest(wgpu::RequestDeviceError),
+    /// An image file could not be opened or decoded.
+    TextureLoad {
+        /// The file being loaded, if the image came from a file.
+        path: Option<PathBuf>,
+        /// The underlying decoding or I/O error.
+        source: image::ImageError,
+    },
+    /// A font file could not be read or parsed.
+    FontLoad {
+        /// The file being loaded, if the font came from a file.
+        path: Option<PathBuf>,
+        /// What went wrong.
+        message: String,
+    },
+}
 
-#[macro_export]
-macro_rules! ignore(
-    ($e: expr) => {
-        {
-            let res = $e;
-            #[cfg(not(target_arch = "wasm32"))]
-            { let _ = $crate::context::Context::get().get_error(); }
-            res
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::WindowCreation(msg) => write!(f, "failed to create the window: {}", msg),
+            Error::SurfaceCreation(e) => write!(f, "failed to create the surface: {}", e),
+            Error::AdapterRequest(e) => {
+                write!(f, "failed to find an appropriate GPU adapter: {}", e)
+            }
+            Error::DeviceRequest(e) => write!(f, "failed to create the GPU device: {}", e),
+            Error::TextureLoad {
+                path: Some(path),
+                source,
+            } => write!(f, "unable to load texture from file {:?}: {}", path, source),
+            Error::TextureLoad { path: None, source } => {
+                write!(f, "unable to decode texture: {}", source)
+            }
+            Error::FontLoad {
+                path: Some(path),
+                message,
+            } => write!(f, "unable to load font from file {:?}: {}", path, message),
+            Error::FontLoad {
+                path: None,
+                message,
+            } => write!(f, "unable to parse font: {}", message),
         }
     }
-);
+}
 
-#[macro_export]
-macro_rules! checked(
-    ($e: expr) => {
-        {
-            let res = $e;
-            if cfg!(not(any(target_arch = "wasm32", target_arch = "asmjs"))) && $crate::context::Context::get().get_error() != 0 {
-                None
-            } else {
-                Some(res)
-            }
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::WindowCreation(_) => None,
+            Error::SurfaceCreation(e) => Some(e),
+            Error::AdapterRequest(e) => Some(e),
+            Error::DeviceRequest(e) => Some(e),
+            Error::TextureLoad { source, .. } => Some(source),
+            Error::FontLoad { .. } => None,
         }
     }
-);
+}
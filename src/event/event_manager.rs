@@ -8,27 +8,41 @@ use crate::event::WindowEvent;
 pub struct Event<'a> {
     /// The exact glfw event value. This can be modified to fool the other event handlers.
     pub value: WindowEvent,
+    /// When the event was received, in seconds since the window was created, on
+    /// the same clock as [`FrameTime::time`](crate::context::FrameTime::time).
+    ///
+    /// Events are stamped when the window polls them from the platform, so
+    /// events of the same poll may share close timestamps; their order is the
+    /// order they occurred in.
+    pub timestamp: f64,
     // /// The platform-specific event.
     // pub platform_value: PlatformEvent,
     /// Set this to `true` to prevent the window or the camera from handling the event.
     pub inhibited: bool,
-    inhibitor: &'a RefCell<Vec<WindowEvent>>,
+    inhibitor: &'a RefCell<Vec<(f64, WindowEvent)>>,
 }
 
 impl<'a> Drop for Event<'a> {
     #[inline]
     fn drop(&mut self) {
         if !self.inhibited {
-            self.inhibitor.borrow_mut().push(self.value)
+            self.inhibitor
+                .borrow_mut()
+                .push((self.timestamp, self.value))
         }
     }
 }
 
 impl<'a> Event<'a> {
     #[inline]
-    fn new(value: WindowEvent, inhibitor: &RefCell<Vec<WindowEvent>>) -> Event<'_> {
+    fn new(
+        value: WindowEvent,
+        timestamp: f64,
+        inhibitor: &RefCell<Vec<(f64, WindowEvent)>>,
+    ) -> Event<'_> {
         Event {
             value,
+            timestamp,
             inhibited: false,
             inhibitor,
         }
@@ -37,15 +51,15 @@ impl<'a> Event<'a> {
 
 /// An iterator through events.
 pub struct Events<'a> {
-    stream: TryIter<'a, WindowEvent>,
-    inhibitor: &'a RefCell<Vec<WindowEvent>>,
+    stream: TryIter<'a, (f64, WindowEvent)>,
+    inhibitor: &'a RefCell<Vec<(f64, WindowEvent)>>,
 }
 
 impl<'a> Events<'a> {
     #[inline]
     fn new(
-        stream: TryIter<'a, WindowEvent>,
-        inhibitor: &'a RefCell<Vec<WindowEvent>>,
+        stream: TryIter<'a, (f64, WindowEvent)>,
+        inhibitor: &'a RefCell<Vec<(f64, WindowEvent)>>,
     ) -> Events<'a> {
         Events { stream, inhibitor }
     }
@@ -58,7 +72,7 @@ impl<'a> Iterator for Events<'a> {
     fn next(&mut self) -> Option<Event<'a>> {
         match self.stream.next() {
             None => None,
-            Some(e) => Some(Event::new(e, self.inhibitor)),
+            Some((timestamp, e)) => Some(Event::new(e, timestamp, self.inhibitor)),
         }
     }
 }
@@ -67,16 +81,16 @@ impl<'a> Iterator for Events<'a> {
 ///
 /// It is not lifetime-bound to the main window.
 pub struct EventManager {
-    events: Rc<Receiver<WindowEvent>>,
-    inhibitor: Rc<RefCell<Vec<WindowEvent>>>,
+    events: Rc<Receiver<(f64, WindowEvent)>>,
+    inhibitor: Rc<RefCell<Vec<(f64, WindowEvent)>>>,
 }
 
 impl EventManager {
     /// Creates a new event manager.
     #[inline]
     pub fn new(
-        events: Rc<Receiver<WindowEvent>>,
-        inhibitor: Rc<RefCell<Vec<WindowEvent>>>,
+        events: Rc<Receiver<(f64, WindowEvent)>>,
+        inhibitor: Rc<RefCell<Vec<(f64, WindowEvent)>>>,
     ) -> EventManager {
         EventManager { events, inhibitor }
     }
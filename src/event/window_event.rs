@@ -17,12 +17,33 @@ pub enum WindowEvent {
     Focus(bool),
     /// The window was iconified (minimized) or restored.
     Iconify(bool),
+    /// The window became visible or hidden: fully covered by other windows,
+    /// in a hidden browser tab, or with the application suspended by the OS
+    /// (e.g. sent to the background on mobile). Rendering pauses while it is
+    /// hidden, so simulations may want to pause too.
+    Visible(bool),
     /// The framebuffer size changed (width, height in pixels).
     FramebufferSize(u32, u32),
+    /// The DPI scale factor changed, e.g. because the window moved to another
+    /// monitor. Followed by a `FramebufferSize` event if the size in pixels
+    /// changed too.
+    ScaleFactorChanged(f64),
     /// A mouse button was pressed or released (button, action, modifiers).
     MouseButton(MouseButton, Action, Modifiers),
+    /// A mouse button was pressed twice in a row, quickly and at about the same
+    /// place (button, modifiers). Sent right after the second `MouseButton`
+    /// press; see [`Window::set_double_click_threshold`](crate::window::Window::set_double_click_threshold).
+    DoubleClick(MouseButton, Modifiers),
     /// The cursor position changed (x, y in pixels, modifiers).
     CursorPos(f64, f64, Modifiers),
+    /// The mouse moved (dx, dy in device units, usually about a pixel each).
+    ///
+    /// Unlike `CursorPos`, this is the raw motion reported by the mouse: it is
+    /// not affected by pointer acceleration, and keeps coming while the cursor is
+    /// grabbed, hidden, or at the edge of the screen, which makes it suited to
+    /// mouse-look controls. Only sent while the window has focus (on the web,
+    /// with the pointer over or locked to the canvas).
+    MouseMotionDelta(f64, f64),
     /// The cursor entered or left the window.
     CursorEnter(bool),
     /// The mouse wheel was scrolled (x_offset, y_offset, modifiers).
@@ -50,11 +71,17 @@ impl WindowEvent {
     /// Checks if this event is mouse-related.
     ///
     /// # Returns
-    /// `true` for `MouseButton`, `CursorPos`, `CursorEnter`, or `Scroll` events
+    /// `true` for `MouseButton`, `DoubleClick`, `CursorPos`, `MouseMotionDelta`,
+    /// `CursorEnter`, or `Scroll` events
     pub fn is_mouse_event(&self) -> bool {
         matches!(
             self,
-            MouseButton(..) | CursorPos(..) | CursorEnter(..) | Scroll(..)
+            MouseButton(..)
+                | DoubleClick(..)
+                | CursorPos(..)
+                | MouseMotionDelta(..)
+                | CursorEnter(..)
+                | Scroll(..)
         )
     }
 
@@ -118,6 +118,8 @@ pub use pollster;
 #[doc(hidden)]
 pub use wasm_bindgen_futures;
 
+pub use crate::error::Error;
+
 #[deprecated(note = "Use the `renderer` module instead.")]
 pub use crate::renderer::point_renderer3d;
 
@@ -125,16 +127,24 @@ pub mod builtin;
 pub mod camera;
 pub mod color;
 pub mod context;
+pub mod error;
 pub mod event;
 pub mod light;
 pub mod light2d;
 pub mod loader;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod offscreen;
+pub mod plot2d;
+pub mod plot3d;
 pub mod post_processing;
 pub mod procedural;
 pub mod renderer;
 pub mod resource;
 pub mod scene;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod testing;
 pub mod text;
+pub mod viz;
 pub mod window;
 
 #[cfg(test)]
@@ -274,8 +274,8 @@ pub struct Fog {
     pub color: Color,
     /// The falloff curve (and whether fog is active at all).
     pub mode: FogMode,
-    /// Optional exponential thinning of fog with world-space height `y`
-    /// (`0` disables it). Larger values clear the fog faster as you go up.
+    /// Optional exponential thinning of fog with the height along the
+    /// [world up axis](crate::context::Context::world_up) (`0` disables it). Larger values clear the fog faster as you go up.
     pub height_falloff: f32,
 }
 
@@ -345,6 +345,10 @@ pub struct LightCollection {
     pub ambient: f32,
     /// Global ambient light color (multiplied by [`ambient`](Self::ambient)).
     pub ambient_color: Color,
+    /// Ground color of the hemisphere ambient light. When set, the ambient term
+    /// blends from this color (downward-facing surfaces) to
+    /// [`ambient_color`](Self::ambient_color) (upward-facing surfaces).
+    pub ambient_ground_color: Option<Color>,
     /// Distance fog applied to the scene during shading.
     pub fog: Fog,
 }
@@ -362,6 +366,7 @@ impl LightCollection {
             lights: Vec::with_capacity(MAX_LIGHTS),
             ambient: 0.2,
             ambient_color: crate::color::WHITE,
+            ambient_ground_color: None,
             fog: Fog::default(),
         }
     }
@@ -372,6 +377,7 @@ impl LightCollection {
             lights: Vec::with_capacity(MAX_LIGHTS),
             ambient,
             ambient_color: crate::color::WHITE,
+            ambient_ground_color: None,
             fog: Fog::default(),
         }
     }
@@ -65,16 +65,29 @@ pub fn parse(string: &str) -> Vec<MtlMaterial> {
             "Ks" => curr_material.specular = parse_color(l, words),
             // shininess
             "Ns" => curr_material.shininess = parse_scalar(l, words),
+            // emissive color
+            "Ke" => curr_material.emissive = parse_color(l, words),
             // alpha
             "d" => curr_material.alpha = parse_scalar(l, words),
+            // transparency (inverse of alpha)
+            "Tr" => curr_material.alpha = 1.0 - parse_scalar(l, words),
             // ambient map
-            "map_Ka" => curr_material.ambient_texture = Some(parse_name(l, words)),
+            "map_Ka" => curr_material.ambient_texture = Some(parse_map(l, words)),
             // diffuse texture map
-            "map_Kd" => curr_material.diffuse_texture = Some(parse_name(l, words)),
+            "map_Kd" => curr_material.diffuse_texture = Some(parse_map(l, words)),
             // specular texture map
-            "map_Ks" => curr_material.specular_texture = Some(parse_name(l, words)),
-            // specular texture map
-            "map_d" | "map_opacity" => curr_material.opacity_map = Some(parse_name(l, words)),
+            "map_Ks" => curr_material.specular_texture = Some(parse_map(l, words)),
+            // emissive texture map
+            "map_Ke" => curr_material.emissive_texture = Some(parse_map(l, words)),
+            // opacity map
+            "map_d" | "map_opacity" => curr_material.opacity_map = Some(parse_map(l, words)),
+            // bump/normal map (exporters such as Blender write tangent-space
+            // normal maps as `map_Bump`)
+            "bump" | "map_bump" | "map_Bump" | "norm" | "map_Norm" => {
+                curr_material.normal_map = Some(parse_map(l, words))
+            }
+            // illumination model, optical density: no rasterizer equivalent
+            "illum" | "Ni" => {}
             _ => {
                 log::warn!("unknown line {} ignored: `{}'", l, line);
             }
@@ -93,6 +106,31 @@ fn parse_name<'a>(_: usize, ws: Words<'a>) -> String {
     res.join(" ")
 }
 
+/// Parses a texture map statement, skipping the leading options (`-bm 1.0`,
+/// `-s 1 1 1`, `-clamp on`, …) so only the file name remains.
+fn parse_map<'a>(_: usize, ws: Words<'a>) -> String {
+    let words: Vec<&'a str> = ws.collect();
+    let mut i = 0;
+
+    while i + 1 < words.len() && words[i].starts_with('-') {
+        let option = words[i];
+        i += 1;
+
+        match option {
+            // Options taking a single non-numeric argument.
+            "-blendu" | "-blendv" | "-cc" | "-clamp" | "-imfchan" | "-type" => i += 1,
+            // Options taking up to three numeric arguments.
+            _ => {
+                while i + 1 < words.len() && words[i].parse::<f32>().is_ok() {
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    words[i.min(words.len())..].join(" ")
+}
+
 fn parse_color(l: usize, mut ws: Words) -> [f32; 3] {
     let sx = ws
         .next()
@@ -138,12 +176,18 @@ pub struct MtlMaterial {
     pub specular_texture: Option<String>,
     /// Path to the opacity map.
     pub opacity_map: Option<String>,
+    /// Path to the tangent-space normal map (`norm`, `bump` or `map_Bump`).
+    pub normal_map: Option<String>,
+    /// Path to the emissive texture.
+    pub emissive_texture: Option<String>,
     /// The ambient color.
     pub ambient: [f32; 3],
     /// The diffuse color.
     pub diffuse: [f32; 3],
     /// The specular color.
     pub specular: [f32; 3],
+    /// The emissive color.
+    pub emissive: [f32; 3],
     /// The shininess.
     pub shininess: f32,
     /// Alpha blending.
@@ -161,9 +205,12 @@ impl MtlMaterial {
             diffuse_texture: None,
             specular_texture: None,
             opacity_map: None,
+            normal_map: None,
+            emissive_texture: None,
             ambient: [1.0, 1.0, 1.0],
             diffuse: [1.0, 1.0, 1.0],
             specular: [1.0, 1.0, 1.0],
+            emissive: [0.0, 0.0, 0.0],
         }
     }
 
@@ -189,8 +236,39 @@ impl MtlMaterial {
             diffuse_texture,
             specular_texture,
             opacity_map,
+            normal_map: None,
+            emissive_texture: None,
+            emissive: [0.0, 0.0, 0.0],
             shininess,
             alpha,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(line: &str) -> String {
+        parse_map(0, obj::split_words(line))
+    }
+
+    #[test]
+    fn parse_map_skips_options() {
+        assert_eq!(map("diffuse.png"), "diffuse.png");
+        assert_eq!(map("-bm 0.5 normal.png"), "normal.png");
+        assert_eq!(map("-s 2 2 1 -o 0.5 0.5 0 tiled.png"), "tiled.png");
+        assert_eq!(map("-blendu off -clamp on -imfchan r mask.png"), "mask.png");
+        // Options with fewer than three numeric arguments.
+        assert_eq!(map("-s 2 -mm 0 1 scaled.png"), "scaled.png");
+    }
+
+    #[test]
+    fn parse_map_keeps_the_path_intact() {
+        assert_eq!(map("textures/my diffuse.png"), "textures/my diffuse.png");
+        // A path starting with a dash, with no option before it.
+        assert_eq!(map("-dashed.png"), "-dashed.png");
+        // A file named like a number.
+        assert_eq!(map("-bm 1 2"), "2");
+    }
+}
@@ -54,7 +54,8 @@ fn warn(line: usize, err: &str) {
 /// Parses an OBJ file and returns the meshes it contains.
 ///
 /// Loads a Wavefront OBJ file from disk and parses it into GPU meshes.
-/// Each object group in the OBJ file becomes a separate mesh.
+/// Each object (`o`), group (`g`) and material switch (`usemtl`) in the OBJ file
+/// becomes a separate mesh, in file order.
 ///
 /// # Arguments
 /// * `path` - Path to the .obj file
@@ -171,7 +172,8 @@ pub fn parse(
                     uvs.push(parse_vt(l, words))
                 }
             }
-            "g" => {
+            // Objects (`o`) are split the same way as groups (`g`).
+            "g" | "o" => {
                 curr_group = parse_g(l, words, basename, &mut groups, &mut groups_ids);
                 let _ = curr_mtl
                     .as_ref()
@@ -190,6 +192,8 @@ pub fn parse(
                     &mut curr_mtl,
                 )
             }
+            // Smoothing groups: normals come from the file or are computed smooth.
+            "s" => {}
             _ => {
                 log::warn!("unknown line {} ignored: `{}'", l, line);
             }
@@ -407,10 +411,9 @@ fn parse_f<'a>(
 fn parse_vt(l: usize, mut ws: Words) -> UV {
     let sx = ws
         .next()
-        .unwrap_or_else(|| error(l, "at least 2 components were expected, found 0."));
-    let sy = ws
-        .next()
-        .unwrap_or_else(|| error(l, "at least 2 components were expected, found 1."));
+        .unwrap_or_else(|| error(l, "at least 1 component was expected, found 0."));
+    // The `v` component is optional (1D textures).
+    let sy = ws.next().unwrap_or("0");
 
     let x: Result<f32, _> = FromStr::from_str(sx);
     let y: Result<f32, _> = FromStr::from_str(sy);
@@ -467,6 +470,11 @@ fn reformat(
     let mut names: Vec<String> = Vec::new();
     let mut mtls: Vec<Option<MtlMaterial>> = Vec::new();
 
+    // Emit the groups in file order so the resulting meshes (and the scene nodes
+    // built from them) are deterministic.
+    let mut groups: Vec<(String, usize)> = groups.into_iter().collect();
+    groups.sort_by_key(|(_, i)| *i);
+
     for (name, i) in groups.into_iter() {
         names.push(name);
         mtls.push(group2mtl.get(&i).cloned());
@@ -0,0 +1,333 @@
+//! Scripted off-screen rendering of image sequences and videos.
+//!
+//! [`render_script`] renders a scene along a [`CameraPath`] to a PNG sequence
+//! or, with the `recording` feature, to an MP4 video, without any window nor
+//! user interaction. It is meant to be driven by other programs, e.g. to
+//! generate the figures of a paper from a batch script.
+//!
+//! # Example
+//! ```no_run
+//! use kiss3d::offscreen::{render_script, CameraPath, OutputSpec, ScriptScene};
+//! use kiss3d::prelude::*;
+//! use std::path::Path;
+//!
+//! kiss3d::pollster::block_on(async {
+//!     let path = CameraPath::new()
+//!         .with_keyframe(0.0, Vec3::new(0.0, 1.0, 5.0), Vec3::ZERO)
+//!         .with_keyframe(2.0, Vec3::new(5.0, 2.0, 0.0), Vec3::ZERO);
+//!     let output = OutputSpec::png_sequence("frames", 1280, 720).with_fps(24);
+//!     let scene = ScriptScene::File(Path::new("assets/robot.glb").to_path_buf());
+//!
+//!     let num_frames = render_script(scene, path, output).await.unwrap();
+//!     println!("rendered {} frames", num_frames);
+//! });
+//! ```
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use glamx::Vec3;
+
+use crate::camera::OrbitCamera3d;
+use crate::scene::SceneNode3d;
+
+pub use crate::window::OffscreenSurface;
+
+/// The scene rendered by [`render_script`].
+pub enum ScriptScene {
+    /// A scene built in code by the closure, called by [`render_script`] once
+    /// its GPU context exists (objects cannot be created before).
+    Build(Box<dyn FnOnce() -> SceneNode3d>),
+    /// A glTF (`.gltf`, `.glb`) or Wavefront OBJ (`.obj`) file, the materials of
+    /// OBJ files being looked up next to it.
+    File(PathBuf),
+}
+
+impl ScriptScene {
+    /// A scene built in code by `build`, see [`ScriptScene::Build`].
+    pub fn build(build: impl FnOnce() -> SceneNode3d + 'static) -> Self {
+        ScriptScene::Build(Box::new(build))
+    }
+
+    /// Loads the scene. Must run once the GPU context exists, as loading
+    /// creates the default materials and textures of the objects.
+    fn into_node(self) -> io::Result<SceneNode3d> {
+        let path = match self {
+            ScriptScene::Build(build) => return Ok(build()),
+            ScriptScene::File(path) => path,
+        };
+
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        let mut scene = SceneNode3d::empty();
+        match extension.as_deref() {
+            Some("gltf") | Some("glb") => {
+                let model = crate::loader::gltf::load(&path).map_err(other_error)?;
+                scene.add_child(model.root);
+            }
+            Some("obj") => {
+                let dir = path.parent().unwrap_or_else(|| Path::new(""));
+                let _ = scene.try_add_obj(&path, dir, Vec3::ONE)?;
+            }
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unsupported scene file: {}", path.display()),
+                ))
+            }
+        }
+        Ok(scene)
+    }
+}
+
+/// A camera pose of a [`CameraPath`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CameraKeyframe {
+    /// The time of the keyframe, in seconds from the start of the path.
+    pub time: f32,
+    /// The position of the camera.
+    pub eye: Vec3,
+    /// The point the camera looks at.
+    pub at: Vec3,
+}
+
+/// The motion of the camera rendering a [`render_script`]: keyframes whose
+/// positions and targets are linearly interpolated.
+///
+/// The path lasts until its last keyframe; a path with a single keyframe (see
+/// [`fixed`](Self::fixed)) renders a single frame.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CameraPath {
+    keyframes: Vec<CameraKeyframe>,
+}
+
+impl CameraPath {
+    /// An empty path, rendered from the default camera pose until keyframes are
+    /// added.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A still camera at `eye` looking at `at`.
+    pub fn fixed(eye: Vec3, at: Vec3) -> Self {
+        Self::new().with_keyframe(0.0, eye, at)
+    }
+
+    /// Adds a keyframe at `time` seconds, keeping the keyframes sorted by time.
+    pub fn with_keyframe(mut self, time: f32, eye: Vec3, at: Vec3) -> Self {
+        self.add_keyframe(time, eye, at);
+        self
+    }
+
+    /// Adds a keyframe at `time` seconds, keeping the keyframes sorted by time.
+    pub fn add_keyframe(&mut self, time: f32, eye: Vec3, at: Vec3) {
+        let i = self.keyframes.partition_point(|k| k.time <= time);
+        self.keyframes.insert(i, CameraKeyframe { time, eye, at });
+    }
+
+    /// The keyframes, sorted by time.
+    pub fn keyframes(&self) -> &[CameraKeyframe] {
+        &self.keyframes
+    }
+
+    /// The time of the last keyframe, in seconds.
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |k| k.time.max(0.0))
+    }
+
+    /// The camera position and target at `time` seconds, or `None` for a path
+    /// without keyframes.
+    pub fn sample(&self, time: f32) -> Option<(Vec3, Vec3)> {
+        let next = self.keyframes.partition_point(|k| k.time <= time);
+        let (a, b) = match (next.checked_sub(1), self.keyframes.get(next)) {
+            (Some(prev), Some(b)) => (&self.keyframes[prev], b),
+            (Some(prev), None) => (&self.keyframes[prev], &self.keyframes[prev]),
+            (None, Some(b)) => (b, b),
+            (None, None) => return None,
+        };
+        let span = b.time - a.time;
+        let s = if span > 0.0 {
+            (time - a.time) / span
+        } else {
+            0.0
+        };
+        Some((a.eye.lerp(b.eye, s), a.at.lerp(b.at, s)))
+    }
+}
+
+/// Where the frames of a [`render_script`] go.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OutputTarget {
+    /// PNG files named `<prefix><frame number>.png`, the frame number being
+    /// padded with zeros to five digits, in the directory `dir` (created if
+    /// needed).
+    Images {
+        /// The directory of the images.
+        dir: PathBuf,
+        /// The beginning of the file names.
+        prefix: String,
+    },
+    /// An MP4 video.
+    #[cfg(feature = "recording")]
+    Video(PathBuf),
+}
+
+/// The size, frame rate and destination of the frames of a [`render_script`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutputSpec {
+    /// The width of the frames, in pixels.
+    pub width: u32,
+    /// The height of the frames, in pixels.
+    pub height: u32,
+    /// The number of frames per second of the camera path (default: `30`).
+    pub fps: u32,
+    /// Where the frames go.
+    pub target: OutputTarget,
+}
+
+impl OutputSpec {
+    /// Frames saved as `frame_00000.png`, `frame_00001.png`, … in `dir`.
+    pub fn png_sequence(dir: impl AsRef<Path>, width: u32, height: u32) -> Self {
+        OutputSpec {
+            width,
+            height,
+            fps: 30,
+            target: OutputTarget::Images {
+                dir: dir.as_ref().to_path_buf(),
+                prefix: "frame_".to_string(),
+            },
+        }
+    }
+
+    /// Frames encoded into the MP4 video `path`.
+    #[cfg(feature = "recording")]
+    pub fn mp4(path: impl AsRef<Path>, width: u32, height: u32) -> Self {
+        OutputSpec {
+            width,
+            height,
+            fps: 30,
+            target: OutputTarget::Video(path.as_ref().to_path_buf()),
+        }
+    }
+
+    /// Sets the number of frames per second (at least 1).
+    pub fn with_fps(mut self, fps: u32) -> Self {
+        self.fps = fps.max(1);
+        self
+    }
+
+    /// The number of frames rendered for `path`: one per frame period over its
+    /// duration, both ends included.
+    pub fn num_frames(&self, path: &CameraPath) -> usize {
+        (path.duration() * self.fps.max(1) as f32).floor() as usize + 1
+    }
+}
+
+/// Renders `scene` seen along `camera_path` to the frames described by
+/// `output`, off-screen, and returns the number of frames written.
+///
+/// The scene is rendered with the default settings of an
+/// [`OffscreenSurface`]; use one directly for more control.
+///
+/// # Errors
+/// Fails if the scene file cannot be loaded, or if the frames cannot be
+/// written or encoded.
+///
+/// # Panics
+/// Panics if no GPU adapter is available.
+pub async fn render_script(
+    scene: ScriptScene,
+    camera_path: CameraPath,
+    output: OutputSpec,
+) -> io::Result<usize> {
+    let mut surface = OffscreenSurface::new(output.width, output.height).await;
+    let mut scene = scene.into_node()?;
+    let mut camera = OrbitCamera3d::default();
+    let num_frames = output.num_frames(&camera_path);
+    let fps = output.fps.max(1);
+
+    match &output.target {
+        OutputTarget::Images { dir, .. } => std::fs::create_dir_all(dir)?,
+        #[cfg(feature = "recording")]
+        OutputTarget::Video(_) => surface.window_mut().begin_recording(),
+    }
+
+    for frame in 0..num_frames {
+        if let Some((eye, at)) = camera_path.sample(frame as f32 / fps as f32) {
+            camera.look_at(eye, at);
+        }
+
+        match &output.target {
+            OutputTarget::Images { dir, prefix } => {
+                let image = surface.render_image_3d(&mut scene, &mut camera).await;
+                let file = dir.join(format!("{}{:05}.png", prefix, frame));
+                image.save(file).map_err(other_error)?;
+            }
+            #[cfg(feature = "recording")]
+            OutputTarget::Video(_) => surface.render_3d(&mut scene, &mut camera).await,
+        }
+    }
+
+    #[cfg(feature = "recording")]
+    if let OutputTarget::Video(path) = &output.target {
+        surface
+            .window_mut()
+            .end_recording(path, fps)
+            .map_err(other_error)?;
+    }
+
+    Ok(num_frames)
+}
+
+fn other_error(e: impl ToString) -> io::Error {
+    io::Error::other(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn camera_path_sample() {
+        assert_eq!(CameraPath::new().sample(0.0), None);
+
+        let eye = Vec3::new(1.0, 2.0, 3.0);
+        let still = CameraPath::fixed(eye, Vec3::ZERO);
+        assert_eq!(still.sample(-1.0), Some((eye, Vec3::ZERO)));
+        assert_eq!(still.sample(10.0), Some((eye, Vec3::ZERO)));
+
+        // Keyframes added out of order are sorted.
+        let path = CameraPath::new()
+            .with_keyframe(2.0, Vec3::new(4.0, 0.0, 0.0), Vec3::Y)
+            .with_keyframe(0.0, Vec3::ZERO, Vec3::ZERO);
+        assert_eq!(path.keyframes()[0].time, 0.0);
+        assert_eq!(path.duration(), 2.0);
+        assert_eq!(path.sample(-1.0), Some((Vec3::ZERO, Vec3::ZERO)));
+        assert_eq!(
+            path.sample(0.5),
+            Some((Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 0.25, 0.0)))
+        );
+        assert_eq!(path.sample(2.0), Some((Vec3::new(4.0, 0.0, 0.0), Vec3::Y)));
+        assert_eq!(path.sample(3.0), Some((Vec3::new(4.0, 0.0, 0.0), Vec3::Y)));
+    }
+
+    #[test]
+    fn output_spec_num_frames() {
+        let output = OutputSpec::png_sequence("frames", 64, 64).with_fps(10);
+        assert_eq!(output.num_frames(&CameraPath::new()), 1);
+        assert_eq!(
+            output.num_frames(&CameraPath::fixed(Vec3::Z, Vec3::ZERO)),
+            1
+        );
+
+        let path = CameraPath::new()
+            .with_keyframe(0.0, Vec3::Z, Vec3::ZERO)
+            .with_keyframe(2.0, Vec3::X, Vec3::ZERO);
+        // Both ends included.
+        assert_eq!(output.num_frames(&path), 21);
+        assert_eq!(output.clone().with_fps(0).fps, 1);
+        assert_eq!(output.with_fps(0).num_frames(&path), 3);
+    }
+}
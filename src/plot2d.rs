@@ -0,0 +1,438 @@
+//! 2D line charts drawn on top of the scene, e.g. for live telemetry.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use glamx::Vec2;
+
+use crate::camera::Camera2d;
+use crate::color::Color;
+use crate::plot3d::{nice_ticks, tick_decimals};
+use crate::renderer::Polyline2d;
+use crate::text::Font;
+use crate::window::Window;
+
+/// The range of values covered by an axis of a [`LineChart`].
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub enum AxisRange {
+    /// The range fits the data of every series. The Y range is widened to round
+    /// tick values.
+    #[default]
+    Auto,
+    /// A fixed `(min, max)` range. Data outside of it is clipped.
+    Fixed(f32, f32),
+}
+
+/// A data series of a [`LineChart`], drawn as one polyline.
+#[derive(Clone, Debug)]
+pub struct LineSeries {
+    name: String,
+    points: VecDeque<Vec2>,
+    max_points: Option<usize>,
+    /// Bounds of `points`, or `None` if they must be recomputed.
+    bounds: Option<(Vec2, Vec2)>,
+    /// The polyline drawn each frame, kept to reuse its vertex buffer.
+    polyline: Polyline2d,
+}
+
+impl LineSeries {
+    /// An empty series named `name` (shown in the chart's legend if not empty),
+    /// drawn with `color`.
+    pub fn new(name: &str, color: Color) -> Self {
+        LineSeries {
+            name: name.to_string(),
+            points: VecDeque::new(),
+            max_points: None,
+            bounds: None,
+            polyline: Polyline2d::default().with_color(color).with_width(2.0),
+        }
+    }
+
+    /// Keeps only the last `max_points` points of the series, dropping the
+    /// oldest ones as new points are pushed (a scrolling chart).
+    pub fn with_max_points(mut self, max_points: usize) -> Self {
+        self.set_max_points(Some(max_points));
+        self
+    }
+
+    /// Sets the width of the series' line, in pixels.
+    pub fn with_width(mut self, width: f32) -> Self {
+        self.polyline.width = width;
+        self
+    }
+
+    /// The name of the series.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The color of the series.
+    pub fn color(&self) -> Color {
+        self.polyline.color
+    }
+
+    /// Sets the color of the series.
+    pub fn set_color(&mut self, color: Color) {
+        self.polyline.color = color;
+    }
+
+    /// Sets the maximum number of points kept by the series (`None` for no limit).
+    pub fn set_max_points(&mut self, max_points: Option<usize>) {
+        self.max_points = max_points;
+        self.evict();
+    }
+
+    /// Appends a point to the series.
+    ///
+    /// This is cheap enough to be called every frame: the chart's bounds are
+    /// updated incrementally.
+    pub fn push(&mut self, point: Vec2) {
+        self.points.push_back(point);
+        if let Some((min, max)) = &mut self.bounds {
+            *min = min.min(point);
+            *max = max.max(point);
+        } else if self.points.len() == 1 {
+            self.bounds = Some((point, point));
+        }
+        self.evict();
+    }
+
+    /// Appends several points to the series.
+    pub fn extend<I: IntoIterator<Item = Vec2>>(&mut self, points: I) {
+        for point in points {
+            self.push(point);
+        }
+    }
+
+    /// Replaces all the points of the series.
+    pub fn set_data(&mut self, points: &[Vec2]) {
+        self.clear();
+        self.extend(points.iter().copied());
+    }
+
+    /// Removes all the points of the series.
+    pub fn clear(&mut self) {
+        self.points.clear();
+        self.bounds = None;
+    }
+
+    /// The points of the series, oldest first.
+    pub fn points(&self) -> &VecDeque<Vec2> {
+        &self.points
+    }
+
+    /// The bounding box `(min, max)` of the points, or `None` if there are none.
+    pub fn bounds(&mut self) -> Option<(Vec2, Vec2)> {
+        if self.bounds.is_none() && !self.points.is_empty() {
+            self.bounds = Some(self.points.iter().fold(
+                (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+                |(min, max), p| (min.min(*p), max.max(*p)),
+            ));
+        }
+        self.bounds
+    }
+
+    fn evict(&mut self) {
+        if let Some(max_points) = self.max_points {
+            while self.points.len() > max_points {
+                let evicted = self.points.pop_front();
+                // The bounds only change if an extreme point was dropped.
+                if let (Some(p), Some((min, max))) = (evicted, self.bounds) {
+                    if p.x <= min.x || p.y <= min.y || p.x >= max.x || p.y >= max.y {
+                        self.bounds = None;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A 2D line chart with axes, ticks, gridlines, labels and a legend, drawn on top
+/// of the scene with the planar polyline and text renderers.
+///
+/// The chart occupies a rectangle of the window given in pixels from its
+/// top-left corner (like [`Window::draw_text`]). Feed its [`LineSeries`] as new
+/// data arrives and call [`draw`](Self::draw) every frame.
+///
+/// # Example
+/// ```no_run
+/// # use kiss3d::prelude::*;
+/// # use kiss3d::plot2d::{LineChart, LineSeries};
+/// # #[kiss3d::main]
+/// # async fn main() {
+/// # let mut window = Window::new("Example").await;
+/// # let mut camera = OrbitCamera3d::default();
+/// # let mut scene = SceneNode3d::empty();
+/// let mut chart = LineChart::new(Vec2::new(20.0, 20.0), Vec2::new(400.0, 200.0));
+/// let fps = chart.add_series(LineSeries::new("fps", GREEN).with_max_points(300));
+///
+/// while window.render_3d(&mut scene, &mut camera).await {
+///     let t = Context::frame_time();
+///     chart.series_mut(fps).push(Vec2::new(t.time, 1.0 / t.delta.max(1.0e-3)));
+///     chart.draw(&mut window);
+/// }
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct LineChart {
+    /// Position of the top-left corner of the chart, in pixels.
+    pub position: Vec2,
+    /// Size of the chart (including its labels), in pixels.
+    pub size: Vec2,
+    /// The range of the X axis.
+    pub x_range: AxisRange,
+    /// The range of the Y axis.
+    pub y_range: AxisRange,
+    /// Approximate number of ticks along each axis.
+    pub tick_count: u32,
+    /// Color of the axes and tick marks.
+    pub axis_color: Color,
+    /// Color of the gridlines. Set its alpha to zero to hide them.
+    pub grid_color: Color,
+    /// Color of the tick labels.
+    pub label_color: Color,
+    /// Height of the labels, in pixels.
+    pub label_size: f32,
+    /// The font of the labels.
+    pub font: Arc<Font>,
+    series: Vec<LineSeries>,
+}
+
+impl LineChart {
+    /// An empty chart occupying the rectangle of `size` pixels whose top-left
+    /// corner is at `position`.
+    pub fn new(position: Vec2, size: Vec2) -> Self {
+        LineChart {
+            position,
+            size,
+            x_range: AxisRange::Auto,
+            y_range: AxisRange::Auto,
+            tick_count: 5,
+            axis_color: Color::new(0.8, 0.8, 0.8, 1.0),
+            grid_color: Color::new(0.5, 0.5, 0.5, 0.3),
+            label_color: Color::new(0.9, 0.9, 0.9, 1.0),
+            label_size: 16.0,
+            font: Font::default(),
+            series: Vec::new(),
+        }
+    }
+
+    /// Adds a series to the chart and returns its index.
+    pub fn add_series(&mut self, series: LineSeries) -> usize {
+        self.series.push(series);
+        self.series.len() - 1
+    }
+
+    /// The `i`-th series of the chart.
+    pub fn series(&self, i: usize) -> &LineSeries {
+        &self.series[i]
+    }
+
+    /// The `i`-th series of the chart, e.g. to push new data.
+    pub fn series_mut(&mut self, i: usize) -> &mut LineSeries {
+        &mut self.series[i]
+    }
+
+    /// All the series of the chart.
+    pub fn all_series(&self) -> &[LineSeries] {
+        &self.series
+    }
+
+    /// Removes all the series of the chart.
+    pub fn clear_series(&mut self) {
+        self.series.clear();
+    }
+
+    /// The `(min, max)` values covered by the X and Y axes for the current data.
+    pub fn ranges(&mut self) -> ((f32, f32), (f32, f32)) {
+        let bounds = self
+            .series
+            .iter_mut()
+            .filter_map(|s| s.bounds())
+            .reduce(|(min1, max1), (min2, max2)| (min1.min(min2), max1.max(max2)));
+        let (min, max) = bounds.unwrap_or((Vec2::ZERO, Vec2::ONE));
+
+        let resolve = |range: AxisRange, min: f32, max: f32, round: bool| match range {
+            AxisRange::Fixed(a, b) => (a.min(b), a.max(b)),
+            AxisRange::Auto => {
+                let (min, max) = if max - min <= f32::EPSILON {
+                    (min - 0.5, max + 0.5)
+                } else {
+                    (min, max)
+                };
+                match nice_ticks(min, max, self.tick_count).as_slice() {
+                    [a, b, ..] if round => {
+                        let step = b - a;
+                        ((min / step).floor() * step, (max / step).ceil() * step)
+                    }
+                    _ => (min, max),
+                }
+            }
+        };
+        (
+            resolve(self.x_range, min.x, max.x, false),
+            resolve(self.y_range, min.y, max.y, true),
+        )
+    }
+
+    /// Draws the chart for the current frame, with the coordinates of the
+    /// default 2D camera (a [`FixedView2d::default`](crate::camera::FixedView2d)),
+    /// which is the one used by [`Window::render_3d`].
+    pub fn draw(&mut self, window: &mut Window) {
+        let size = Vec2::new(window.width() as f32, window.height() as f32);
+        let scale = window.scale_factor() as f32;
+        self.draw_with(window, |p| {
+            Vec2::new(p.x - size.x * 0.5, size.y * 0.5 - p.y) / scale
+        });
+    }
+
+    /// Draws the chart for the current frame, for the 2D camera `camera_2d`
+    /// passed to the render call.
+    pub fn draw_with_camera(&mut self, window: &mut Window, camera_2d: &dyn Camera2d) {
+        let size = Vec2::new(window.width() as f32, window.height() as f32);
+        self.draw_with(window, |p| camera_2d.unproject(p, size));
+    }
+
+    /// Draws the chart, with `to_planar` converting window pixels to the planar
+    /// scene's coordinates.
+    fn draw_with(&mut self, window: &mut Window, to_planar: impl Fn(Vec2) -> Vec2) {
+        let ((x_min, x_max), (y_min, y_max)) = self.ranges();
+        let x_ticks = nice_ticks(x_min, x_max, self.tick_count);
+        let y_ticks = nice_ticks(y_min, y_max, self.tick_count);
+        let (x_decimals, y_decimals) = (tick_decimals(&x_ticks), tick_decimals(&y_ticks));
+        let y_labels: Vec<_> = y_ticks
+            .iter()
+            .map(|t| format!("{:.*}", y_decimals, t))
+            .collect();
+
+        // The plot area, in pixels, leaves room for the labels on the left and
+        // bottom.
+        let label_width = y_labels
+            .iter()
+            .map(|l| window.measure_text(l, self.label_size, &self.font).x)
+            .fold(0.0, f32::max);
+        let tick_length = self.label_size * 0.3;
+        let left = self.position.x + label_width + tick_length * 2.0;
+        let right = self.position.x + self.size.x;
+        let top = self.position.y;
+        let bottom = self.position.y + self.size.y - self.label_size - tick_length * 2.0;
+        if right <= left || bottom <= top {
+            return;
+        }
+
+        let x_span = (x_max - x_min).max(f32::EPSILON);
+        let y_span = (y_max - y_min).max(f32::EPSILON);
+        let to_px_x = |x: f32| left + (x - x_min) / x_span * (right - left);
+        let to_px_y = |y: f32| bottom - (y - y_min) / y_span * (bottom - top);
+        let line = |window: &mut Window, a: Vec2, b: Vec2, color: Color, width: f32| {
+            window.draw_line_2d(to_planar(a), to_planar(b), color, width)
+        };
+
+        // Gridlines, tick marks and labels.
+        for (t, label) in y_ticks.iter().zip(&y_labels) {
+            let y = to_px_y(*t);
+            if self.grid_color.a > 0.0 {
+                line(
+                    window,
+                    Vec2::new(left, y),
+                    Vec2::new(right, y),
+                    self.grid_color,
+                    1.0,
+                );
+            }
+            line(
+                window,
+                Vec2::new(left - tick_length, y),
+                Vec2::new(left, y),
+                self.axis_color,
+                1.0,
+            );
+            let label_size = window.measure_text(label, self.label_size, &self.font);
+            window.draw_text(
+                label,
+                Vec2::new(
+                    left - tick_length * 2.0 - label_size.x,
+                    y - label_size.y * 0.5,
+                ),
+                self.label_size,
+                &self.font,
+                self.label_color,
+            );
+        }
+        for t in &x_ticks {
+            let x = to_px_x(*t);
+            if self.grid_color.a > 0.0 {
+                line(
+                    window,
+                    Vec2::new(x, top),
+                    Vec2::new(x, bottom),
+                    self.grid_color,
+                    1.0,
+                );
+            }
+            line(
+                window,
+                Vec2::new(x, bottom),
+                Vec2::new(x, bottom + tick_length),
+                self.axis_color,
+                1.0,
+            );
+            let label = format!("{:.*}", x_decimals, t);
+            let label_size = window.measure_text(&label, self.label_size, &self.font);
+            window.draw_text(
+                &label,
+                Vec2::new(x - label_size.x * 0.5, bottom + tick_length * 2.0),
+                self.label_size,
+                &self.font,
+                self.label_color,
+            );
+        }
+
+        // Axes.
+        line(
+            window,
+            Vec2::new(left, top),
+            Vec2::new(left, bottom),
+            self.axis_color,
+            1.5,
+        );
+        line(
+            window,
+            Vec2::new(left, bottom),
+            Vec2::new(right, bottom),
+            self.axis_color,
+            1.5,
+        );
+
+        // Series, with the points outside of the X range skipped and the Y values
+        // clamped to the plot area.
+        let mut legend_y = top;
+        for series in &mut self.series {
+            let LineSeries {
+                points, polyline, ..
+            } = &mut *series;
+            polyline.vertices.clear();
+            polyline.vertices.extend(
+                points
+                    .iter()
+                    .filter(|p| p.x >= x_min && p.x <= x_max)
+                    .map(|p| to_planar(Vec2::new(to_px_x(p.x), to_px_y(p.y.clamp(y_min, y_max))))),
+            );
+            if polyline.vertices.len() > 1 {
+                window.draw_polyline_2d(polyline);
+            }
+
+            if !series.name.is_empty() {
+                let name_size = window.measure_text(&series.name, self.label_size, &self.font);
+                window.draw_text(
+                    &series.name,
+                    Vec2::new(right - name_size.x - tick_length, legend_y),
+                    self.label_size,
+                    &self.font,
+                    series.polyline.color,
+                );
+                legend_y += name_size.y;
+            }
+        }
+    }
+}
@@ -0,0 +1,297 @@
+//! 3D chart axes: an axis box with ticks, numeric labels and grid planes.
+
+use std::sync::Arc;
+
+use glamx::Vec3;
+
+use crate::camera::Camera3d;
+use crate::color::Color;
+use crate::text::Font;
+use crate::window::Window;
+
+/// An axis box fitted to the bounds of 3D data, drawn with [`Axes3d::draw`].
+///
+/// Each frame, the box edges, the grid planes behind the data (the three faces of
+/// the box farthest from the camera) and the tick marks with their numeric labels
+/// (along the edges closest to the camera) are drawn with the immediate-mode line
+/// and 3D text renderers, so the axes stay readable while orbiting around the
+/// data. Tick values are "nice" round numbers (see [`nice_ticks`]).
+///
+/// # Example
+/// ```no_run
+/// # use kiss3d::prelude::*;
+/// # use kiss3d::plot3d::Axes3d;
+/// # #[kiss3d::main]
+/// # async fn main() {
+/// # let mut window = Window::new("Example").await;
+/// # let mut camera = OrbitCamera3d::default();
+/// # let mut scene = SceneNode3d::empty();
+/// let points: Vec<Vec3> = (0..100)
+///     .map(|i| {
+///         let t = i as f32 * 0.1;
+///         Vec3::new(t.cos() * t, t, t.sin() * t)
+///     })
+///     .collect();
+/// let axes = Axes3d::fit(&points).titles(["x", "time", "z"]);
+///
+/// while window.render_3d(&mut scene, &mut camera).await {
+///     for p in &points {
+///         window.draw_point(*p, RED, 5.0);
+///     }
+///     axes.draw(&mut window, &camera);
+/// }
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Axes3d {
+    /// The minimum corner of the axis box.
+    pub min: Vec3,
+    /// The maximum corner of the axis box.
+    pub max: Vec3,
+    /// Approximate number of ticks along each axis.
+    pub tick_count: u32,
+    /// Color of the box edges and tick marks.
+    pub color: Color,
+    /// Color of the grid lines. Set its alpha to zero to hide the grid planes.
+    pub grid_color: Color,
+    /// Color of the tick labels and axis titles.
+    pub label_color: Color,
+    /// Width of the box edges and tick marks, in pixels. Grid lines are half as
+    /// wide.
+    pub line_width: f32,
+    /// Line height of the tick labels, in world units. `None` picks 4% of the
+    /// largest box extent.
+    pub label_height: Option<f32>,
+    /// The titles of the X, Y and Z axes, drawn next to their tick labels. Empty
+    /// titles are not drawn.
+    pub titles: [String; 3],
+    /// The font of the labels.
+    pub font: Arc<Font>,
+}
+
+impl Axes3d {
+    /// Axes spanning the box from `min` to `max`.
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Axes3d {
+            min: min.min(max),
+            max: min.max(max),
+            tick_count: 5,
+            color: Color::new(0.8, 0.8, 0.8, 1.0),
+            grid_color: Color::new(0.5, 0.5, 0.5, 0.4),
+            label_color: Color::new(0.9, 0.9, 0.9, 1.0),
+            line_width: 1.5,
+            label_height: None,
+            titles: [String::new(), String::new(), String::new()],
+            font: Font::default(),
+        }
+    }
+
+    /// Axes fitted to the bounding box of `points`, widened to the nearest ticks
+    /// so the box edges fall on round values.
+    ///
+    /// Flat dimensions are given a unit extent. Empty `points` give the unit box
+    /// around the origin.
+    pub fn fit(points: &[Vec3]) -> Self {
+        let (mut min, mut max) = points.iter().fold(
+            (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+            |(lo, hi), p| (lo.min(*p), hi.max(*p)),
+        );
+        if points.is_empty() {
+            min = Vec3::splat(-0.5);
+            max = Vec3::splat(0.5);
+        }
+
+        let mut axes = Axes3d::new(min, max);
+        for k in 0..3 {
+            if max[k] - min[k] <= f32::EPSILON {
+                min[k] -= 0.5;
+                max[k] += 0.5;
+            }
+            let step = nice_step(max[k] - min[k], axes.tick_count);
+            min[k] = (min[k] / step).floor() * step;
+            max[k] = (max[k] / step).ceil() * step;
+        }
+        axes.min = min;
+        axes.max = max;
+        axes
+    }
+
+    /// Sets the titles of the X, Y and Z axes.
+    pub fn titles(mut self, titles: [&str; 3]) -> Self {
+        self.titles = titles.map(String::from);
+        self
+    }
+
+    /// Draws the axes for the current frame, choosing which faces and edges to
+    /// use from the position of `camera`.
+    ///
+    /// Call this every frame from within your render loop.
+    pub fn draw(&self, window: &mut Window, camera: &dyn Camera3d) {
+        let (min, max) = (self.min, self.max);
+        let extent = max - min;
+        let center = (min + max) * 0.5;
+        let eye = camera.eye();
+        let label_height = self.label_height.unwrap_or(extent.max_element() * 0.04);
+        let tick_length = label_height * 0.5;
+
+        // For each axis, the coordinate of the box face farthest from (`back`)
+        // and closest to (`front`) the camera.
+        let mut back = Vec3::ZERO;
+        let mut front = Vec3::ZERO;
+        for k in 0..3 {
+            let eye_above = eye[k] > center[k];
+            back[k] = if eye_above { min[k] } else { max[k] };
+            front[k] = if eye_above { max[k] } else { min[k] };
+        }
+
+        let ticks = [
+            nice_ticks(min.x, max.x, self.tick_count),
+            nice_ticks(min.y, max.y, self.tick_count),
+            nice_ticks(min.z, max.z, self.tick_count),
+        ];
+
+        // Grid lines on the three back faces.
+        if self.grid_color.a > 0.0 {
+            for normal in 0..3 {
+                for (along, across) in [
+                    ((normal + 1) % 3, (normal + 2) % 3),
+                    ((normal + 2) % 3, (normal + 1) % 3),
+                ] {
+                    for &t in &ticks[along] {
+                        let mut a = Vec3::ZERO;
+                        a[normal] = back[normal];
+                        a[along] = t;
+                        a[across] = min[across];
+                        let mut b = a;
+                        b[across] = max[across];
+                        window.draw_line(a, b, self.grid_color, self.line_width * 0.5, false);
+                    }
+                }
+            }
+        }
+
+        // The twelve edges of the box.
+        for k in 0..3 {
+            let (i, j) = ((k + 1) % 3, (k + 2) % 3);
+            for (ci, cj) in [
+                (min[i], min[j]),
+                (max[i], min[j]),
+                (min[i], max[j]),
+                (max[i], max[j]),
+            ] {
+                let mut a = Vec3::ZERO;
+                a[i] = ci;
+                a[j] = cj;
+                a[k] = min[k];
+                let mut b = a;
+                b[k] = max[k];
+                window.draw_line(a, b, self.color, self.line_width, false);
+            }
+        }
+
+        // Ticks and labels: X and Z along the bottom front edges, Y along the
+        // vertical edge on the front of X and the back of Z.
+        let edges = [
+            (0, Vec3::new(0.0, min.y, front.z), 2),
+            (1, Vec3::new(front.x, 0.0, back.z), 0),
+            (2, Vec3::new(front.x, min.y, 0.0), 0),
+        ];
+        for (k, anchor, outward_axis) in edges {
+            let mut outward = Vec3::ZERO;
+            outward[outward_axis] = if anchor[outward_axis] >= center[outward_axis] {
+                1.0
+            } else {
+                -1.0
+            };
+            if k != 1 {
+                // Also push the labels of the horizontal axes below the box.
+                outward.y = -0.5;
+            }
+            let outward = outward.normalize();
+            let decimals = tick_decimals(&ticks[k]);
+
+            for &t in &ticks[k] {
+                let mut p = anchor;
+                p[k] = t;
+                window.draw_line(
+                    p,
+                    p + outward * tick_length,
+                    self.color,
+                    self.line_width,
+                    false,
+                );
+                let label = format!("{:.*}", decimals, t);
+                window.draw_text_3d(
+                    &label,
+                    p + outward * (tick_length * 3.0),
+                    label_height,
+                    &self.font,
+                    self.label_color,
+                );
+            }
+
+            if !self.titles[k].is_empty() {
+                let mut p = anchor;
+                p[k] = center[k];
+                window.draw_text_3d(
+                    &self.titles[k],
+                    p + outward * (tick_length * 7.0),
+                    label_height * 1.2,
+                    &self.font,
+                    self.label_color,
+                );
+            }
+        }
+    }
+}
+
+/// "Nice" tick values covering `[min, max]`: multiples of 1, 2 or 5 times a power
+/// of ten, about `target_count` of them.
+///
+/// # Example
+/// ```
+/// # use kiss3d::plot3d::nice_ticks;
+/// assert_eq!(nice_ticks(0.0, 10.0, 5), vec![0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+/// assert_eq!(nice_ticks(-1.3, 2.9, 4), vec![-1.0, 0.0, 1.0, 2.0]);
+/// ```
+pub fn nice_ticks(min: f32, max: f32, target_count: u32) -> Vec<f32> {
+    let (min, max) = (min.min(max), min.max(max));
+    if !(max - min).is_finite() || max - min <= 0.0 {
+        return if min.is_finite() {
+            vec![min]
+        } else {
+            Vec::new()
+        };
+    }
+
+    let step = nice_step(max - min, target_count);
+    let first = (min / step).ceil() as i64;
+    let last = (max / step + 1.0e-4).floor() as i64;
+    (first..=last).map(|i| i as f32 * step).collect()
+}
+
+/// The number of decimals needed to tell the `ticks` of [`nice_ticks`] apart.
+pub fn tick_decimals(ticks: &[f32]) -> usize {
+    match ticks {
+        [a, b, ..] => (-(b - a).abs().log10().floor()).max(0.0) as usize,
+        _ => 1,
+    }
+}
+
+/// A step of 1, 2 or 5 times a power of ten dividing `range` in about
+/// `target_count` intervals.
+fn nice_step(range: f32, target_count: u32) -> f32 {
+    let raw = range / target_count.max(1) as f32;
+    let magnitude = 10.0f32.powf(raw.log10().floor());
+    let normalized = raw / magnitude;
+    let nice = if normalized < 1.5 {
+        1.0
+    } else if normalized < 3.0 {
+        2.0
+    } else if normalized < 7.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * magnitude
+}
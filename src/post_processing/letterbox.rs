@@ -0,0 +1,267 @@
+//! Post-processing effect fitting the image into a fixed-aspect viewport with
+//! bars around it.
+
+use crate::color::Color;
+use crate::context::Context;
+use crate::post_processing::post_processing_effect::{PostProcessingContext, PostProcessingEffect};
+use crate::resource::RenderTarget;
+use bytemuck::{Pod, Zeroable};
+
+/// Vertex data for full-screen quad.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct QuadVertex {
+    position: [f32; 2],
+}
+
+/// Post processing effect which letterboxes the image to a fixed aspect ratio.
+///
+/// The whole input image is drawn into the largest centered rectangle of the
+/// output with the requested aspect ratio (see [`Letterbox::viewport`]), and the
+/// remaining bars are filled with a solid color. The scene must have been
+/// rendered with a projection of that aspect ratio for the result to look
+/// undistorted, and at the size of that rectangle for it to be copied without
+/// resampling; this is what
+/// [`Window::set_fixed_aspect`](crate::window::Window::set_fixed_aspect) sets up.
+pub struct Letterbox {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    vertex_buffer: wgpu::Buffer,
+    aspect: f32,
+    color: Color,
+    width: f32,
+    height: f32,
+}
+
+impl Letterbox {
+    /// Creates a letterbox effect for the aspect ratio `aspect` (width / height)
+    /// with bars of the given color.
+    pub fn new(aspect: f32, color: Color) -> Letterbox {
+        let ctxt = Context::get();
+
+        // Create bind group layout for texture + sampler
+        let bind_group_layout = ctxt.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("letterbox_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = ctxt.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("letterbox_pipeline_layout"),
+            bind_group_layouts: &[Some(&bind_group_layout)],
+            immediate_size: 0,
+        });
+
+        // Load shader
+        let shader = ctxt.create_shader_module(
+            Some("letterbox_shader"),
+            &crate::builtin::compile_shader_with_common(
+                "package::letterbox",
+                include_str!("../builtin/letterbox.wgsl"),
+            ),
+        );
+
+        // Vertex buffer layout
+        let vertex_buffer_layout = wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<QuadVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[wgpu::VertexAttribute {
+                offset: 0,
+                shader_location: 0,
+                format: wgpu::VertexFormat::Float32x2,
+            }],
+        };
+
+        let pipeline = ctxt.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("letterbox_pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[vertex_buffer_layout],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: ctxt.surface_format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview_mask: None,
+            cache: None,
+        });
+
+        // Create full-screen quad vertices
+        let vertices = [
+            QuadVertex {
+                position: [-1.0, -1.0],
+            },
+            QuadVertex {
+                position: [1.0, -1.0],
+            },
+            QuadVertex {
+                position: [-1.0, 1.0],
+            },
+            QuadVertex {
+                position: [1.0, 1.0],
+            },
+        ];
+
+        let vertex_buffer = ctxt.create_buffer_init(
+            Some("letterbox_vertex_buffer"),
+            bytemuck::cast_slice(&vertices),
+            wgpu::BufferUsages::VERTEX,
+        );
+
+        Letterbox {
+            pipeline,
+            bind_group_layout,
+            vertex_buffer,
+            aspect: aspect.max(1.0e-6),
+            color,
+            width: 1.0,
+            height: 1.0,
+        }
+    }
+
+    /// The target aspect ratio (width / height).
+    pub fn aspect(&self) -> f32 {
+        self.aspect
+    }
+
+    /// Sets the target aspect ratio (width / height).
+    pub fn set_aspect(&mut self, aspect: f32) {
+        self.aspect = aspect.max(1.0e-6);
+    }
+
+    /// The color of the bars.
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    /// Sets the color of the bars.
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    /// The centered rectangle `(x, y, width, height)`, in pixels, in which an
+    /// output of size `width × height` shows an image of aspect ratio `aspect`.
+    pub fn viewport(aspect: f32, width: f32, height: f32) -> (f32, f32, f32, f32) {
+        let aspect = aspect.max(1.0e-6);
+        let (vw, vh) = if width / height.max(1.0) > aspect {
+            // Output wider than the target: bars on the left and right.
+            ((height * aspect).round().max(1.0), height)
+        } else {
+            // Output taller than the target: bars at the top and bottom.
+            (width, (width / aspect).round().max(1.0))
+        };
+        (
+            ((width - vw) * 0.5).floor(),
+            ((height - vh) * 0.5).floor(),
+            vw,
+            vh,
+        )
+    }
+}
+
+impl PostProcessingEffect for Letterbox {
+    fn update(&mut self, _: f32, w: f32, h: f32, _: f32, _: f32) {
+        self.width = w;
+        self.height = h;
+    }
+
+    fn draw(&mut self, target: &RenderTarget, context: &mut PostProcessingContext) {
+        let ctxt = Context::get();
+
+        // Get the source texture and sampler from the render target
+        let (color_view, sampler) = match target {
+            RenderTarget::Offscreen(o) => (&o.color_view, &o.sampler),
+            RenderTarget::Screen => return, // Can't post-process the screen directly
+        };
+
+        // Create bind group for this frame
+        let bind_group = ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("letterbox_bind_group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(color_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+            ],
+        });
+
+        // Create render pass to the output view
+        {
+            let mut render_pass = context
+                .encoder
+                .begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("letterbox_render_pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: context.output_view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color {
+                                r: self.color.r as f64,
+                                g: self.color.g as f64,
+                                b: self.color.b as f64,
+                                a: self.color.a as f64,
+                            }),
+                            store: wgpu::StoreOp::Store,
+                        },
+                        depth_slice: None,
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                    multiview_mask: None,
+                });
+
+            let (x, y, w, h) = Letterbox::viewport(self.aspect, self.width, self.height);
+            render_pass.set_viewport(x, y, w, h, 0.0, 1.0);
+            render_pass.set_pipeline(&self.pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[]);
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.draw(0..4, 0..1);
+        }
+    }
+}
@@ -363,6 +363,7 @@ impl PostProcessingEffect for Loupe {
                 let mut inner_ctx = PostProcessingContext {
                     encoder: context.encoder,
                     output_view: &mid.view,
+                    time: context.time,
                 };
                 inner.draw(target, &mut inner_ctx);
                 (&mid.view, &mid.sampler)
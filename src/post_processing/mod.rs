@@ -11,6 +11,7 @@ pub use crate::post_processing::hdr::{
     ColorGrading, HdrPipeline, HdrSettings, Tonemap, HDR_FORMAT, OIT_ACCUM_FORMAT,
     OIT_REVEAL_FORMAT,
 };
+pub use crate::post_processing::letterbox::Letterbox;
 pub use crate::post_processing::loupe::{Loupe, LoupeCorner};
 pub use crate::post_processing::oculus_stereo::OculusStereo;
 pub use crate::post_processing::post_processing_effect::{
@@ -26,6 +27,7 @@ mod fxaa;
 mod gi2d;
 mod grayscales;
 mod hdr;
+mod letterbox;
 mod loupe;
 mod oculus_stereo;
 pub mod post_processing_effect;
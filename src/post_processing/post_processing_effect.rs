@@ -1,13 +1,32 @@
 //! Trait implemented by every post-processing effect.
 
+use crate::context::{Context, FrameTime};
 use crate::resource::RenderTarget;
 
 /// Context passed to post-processing effects during draw.
+///
+/// Build one with [`PostProcessingContext::new`], e.g. to draw an effect wrapped
+/// by another one.
+#[non_exhaustive]
 pub struct PostProcessingContext<'a> {
     /// The command encoder for this frame.
     pub encoder: &'a mut wgpu::CommandEncoder,
     /// The output color view to render to.
     pub output_view: &'a wgpu::TextureView,
+    /// Timing of the frame being rendered.
+    pub time: FrameTime,
+}
+
+impl<'a> PostProcessingContext<'a> {
+    /// A context drawing into `output_view` with `encoder`, during the frame
+    /// currently rendered (see [`Context::frame_time`]).
+    pub fn new(encoder: &'a mut wgpu::CommandEncoder, output_view: &'a wgpu::TextureView) -> Self {
+        PostProcessingContext {
+            encoder,
+            output_view,
+            time: Context::frame_time(),
+        }
+    }
 }
 
 /// Trait for implementing custom post-processing effects.
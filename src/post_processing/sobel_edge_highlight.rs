@@ -262,7 +262,11 @@ impl PostProcessingEffect for SobelEdgeHighlight {
 
         // Get the source textures and sampler from the render target
         let (color_view, depth_view, sampler) = match target {
-            RenderTarget::Offscreen(o) => (&o.color_view, &o.depth_view, &o.sampler),
+            RenderTarget::Offscreen(o) => (
+                &o.color_view,
+                Context::depth_sample_view(&o.depth_texture),
+                &o.sampler,
+            ),
             RenderTarget::Screen => return, // Can't post-process the screen directly
         };
 
@@ -300,7 +304,7 @@ impl PostProcessingEffect for SobelEdgeHighlight {
             entries: &[
                 wgpu::BindGroupEntry {
                     binding: 0,
-                    resource: wgpu::BindingResource::TextureView(depth_view),
+                    resource: wgpu::BindingResource::TextureView(&depth_view),
                 },
                 wgpu::BindGroupEntry {
                     binding: 1,
@@ -0,0 +1,80 @@
+use super::{IndexBuffer, RenderMesh};
+use glamx::Vec3;
+
+/// Generates an arrow mesh: a cylindrical shaft capped by a conical head.
+///
+/// The arrow has unit length and points along the positive Y axis, from its
+/// tail at the origin to its tip at `(0, 1, 0)`, which makes it easy to orient
+/// and scale as a glyph. The shaft, head base and tail cap have sharp edges.
+///
+/// # Arguments
+/// * `shaft_radius` - The radius of the shaft
+/// * `head_radius` - The radius of the base of the head
+/// * `head_length` - The length of the head, between 0 and 1
+/// * `nsubdiv` - Number of subdivisions around the circles
+///
+/// # Example
+/// ```no_run
+/// # use kiss3d::procedural::arrow;
+/// let arrow_mesh = arrow(0.03, 0.08, 0.25, 16);
+/// ```
+pub fn arrow(shaft_radius: f32, head_radius: f32, head_length: f32, nsubdiv: u32) -> RenderMesh {
+    let nsubdiv = nsubdiv.max(3);
+    let head_length = head_length.clamp(0.0, 1.0);
+    let head_base = 1.0 - head_length;
+    let dtheta = std::f32::consts::TAU / nsubdiv as f32;
+    let dir = |i: u32| {
+        let theta = i as f32 * dtheta;
+        Vec3::new(theta.cos(), 0.0, theta.sin())
+    };
+    let cone_normal = |d: Vec3| (d * head_length + Vec3::Y * head_radius).normalize_or_zero();
+
+    let mut coords = Vec::new();
+    let mut normals = Vec::new();
+    let mut faces = Vec::new();
+    let mut push = |p: Vec3, n: Vec3, coords: &mut Vec<Vec3>| {
+        coords.push(p);
+        normals.push(n);
+        coords.len() as u32 - 1
+    };
+
+    for i in 0..nsubdiv {
+        let (da, db) = (dir(i), dir(i + 1));
+
+        // Shaft side.
+        let a0 = push(da * shaft_radius, da, &mut coords);
+        let b0 = push(db * shaft_radius, db, &mut coords);
+        let a1 = push(da * shaft_radius + Vec3::Y * head_base, da, &mut coords);
+        let b1 = push(db * shaft_radius + Vec3::Y * head_base, db, &mut coords);
+        faces.push([a0, a1, b0]);
+        faces.push([b0, a1, b1]);
+
+        // Tail cap.
+        let center = push(Vec3::ZERO, -Vec3::Y, &mut coords);
+        let a = push(da * shaft_radius, -Vec3::Y, &mut coords);
+        let b = push(db * shaft_radius, -Vec3::Y, &mut coords);
+        faces.push([center, a, b]);
+
+        // Underside of the head, between the shaft and the head rim.
+        let base = Vec3::Y * head_base;
+        let ia = push(base + da * shaft_radius, -Vec3::Y, &mut coords);
+        let ib = push(base + db * shaft_radius, -Vec3::Y, &mut coords);
+        let oa = push(base + da * head_radius, -Vec3::Y, &mut coords);
+        let ob = push(base + db * head_radius, -Vec3::Y, &mut coords);
+        faces.push([ia, oa, ob]);
+        faces.push([ia, ob, ib]);
+
+        // Head side, with one apex per sector so its normal is not averaged.
+        let ha = push(base + da * head_radius, cone_normal(da), &mut coords);
+        let hb = push(base + db * head_radius, cone_normal(db), &mut coords);
+        let apex = push(Vec3::Y, cone_normal((da + db).normalize()), &mut coords);
+        faces.push([ha, apex, hb]);
+    }
+
+    RenderMesh::new(
+        coords,
+        Some(normals),
+        None,
+        Some(IndexBuffer::Unified(faces)),
+    )
+}
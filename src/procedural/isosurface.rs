@@ -0,0 +1,278 @@
+use super::{IndexBuffer, RenderMesh};
+use glamx::Vec3;
+use std::collections::HashMap;
+
+/// The six tetrahedra each grid cell is split into, as indices of its corners
+/// (corner `i` is offset by `(i & 1, (i >> 1) & 1, (i >> 2) & 1)` cells). They
+/// all share the `0–7` diagonal, so neighboring cells split their common faces
+/// along the same diagonal and the surface has no cracks.
+const CELL_TETRAHEDRA: [[usize; 4]; 6] = [
+    [0, 1, 3, 7],
+    [0, 3, 2, 7],
+    [0, 2, 6, 7],
+    [0, 6, 4, 7],
+    [0, 4, 5, 7],
+    [0, 5, 1, 7],
+];
+
+/// Extracts the isosurface `field = iso` of a scalar field sampled on a regular
+/// grid, e.g. the output of a volumetric simulation or a CT scan.
+///
+/// `samples` holds `dims[0] × dims[1] × dims[2]` values, with `x` varying
+/// fastest then `y` then `z`; sample `(i, j, k)` lies at
+/// `min + (max - min) * (i, j, k) / (dims - 1)`. Values below `iso` are inside
+/// the surface (as with signed distance functions; negate a density field to
+/// extract where it is above `iso`), and the triangles face outward.
+///
+/// Each grid cell is split into six tetrahedra (marching tetrahedra rather than
+/// marching cubes), which avoids the ambiguous configurations of the classic
+/// marching cubes table. Vertices
+/// are shared between adjacent triangles and get smooth normals from the field
+/// gradient.
+///
+/// # Example
+/// ```no_run
+/// # use kiss3d::procedural::isosurface;
+/// # use kiss3d::glamx::Vec3;
+/// # fn load_density() -> Vec<f32> { vec![0.0; 64 * 64 * 64] }
+/// let density = load_density();
+/// let negated: Vec<f32> = density.iter().map(|d| -d).collect();
+/// let mesh = isosurface(&negated, [64, 64, 64], Vec3::splat(-1.0), Vec3::splat(1.0), -0.5);
+/// ```
+///
+/// # Panics
+/// Panics if `samples.len()` is not `dims[0] * dims[1] * dims[2]`.
+pub fn isosurface(samples: &[f32], dims: [usize; 3], min: Vec3, max: Vec3, iso: f32) -> RenderMesh {
+    assert_eq!(
+        samples.len(),
+        dims[0] * dims[1] * dims[2],
+        "isosurface: the number of samples does not match the grid dimensions"
+    );
+
+    let mut coords = Vec::new();
+    let mut normals = Vec::new();
+    let mut faces = Vec::new();
+    if dims.iter().any(|d| *d < 2) {
+        return RenderMesh::new(
+            coords,
+            Some(normals),
+            None,
+            Some(IndexBuffer::Unified(faces)),
+        );
+    }
+
+    let cell = (max - min)
+        / Vec3::new(
+            (dims[0] - 1) as f32,
+            (dims[1] - 1) as f32,
+            (dims[2] - 1) as f32,
+        );
+    let index = |i: usize, j: usize, k: usize| i + dims[0] * (j + dims[1] * k);
+    let position = |id: usize| {
+        let (i, j, k) = (
+            id % dims[0],
+            (id / dims[0]) % dims[1],
+            id / (dims[0] * dims[1]),
+        );
+        min + cell * Vec3::new(i as f32, j as f32, k as f32)
+    };
+    // Central differences (one-sided on the grid boundary).
+    let gradient = |id: usize| {
+        let (i, j, k) = (
+            id % dims[0],
+            (id / dims[0]) % dims[1],
+            id / (dims[0] * dims[1]),
+        );
+        let along = |c: usize, n: usize, step: usize, h: f32| {
+            let lo = if c > 0 { id - step } else { id };
+            let hi = if c + 1 < n { id + step } else { id };
+            let span = (hi - lo) / step;
+            (samples[hi] - samples[lo]) / (span as f32 * h)
+        };
+        Vec3::new(
+            along(i, dims[0], 1, cell.x),
+            along(j, dims[1], dims[0], cell.y),
+            along(k, dims[2], dims[0] * dims[1], cell.z),
+        )
+    };
+
+    // One vertex per crossed grid edge, shared by all the triangles touching it.
+    let mut edge_vertices: HashMap<(usize, usize), u32> = HashMap::new();
+    let vertex_on_edge = |coords: &mut Vec<Vec3>,
+                          normals: &mut Vec<Vec3>,
+                          edge_vertices: &mut HashMap<(usize, usize), u32>,
+                          (a, b): (usize, usize)| {
+        let key = (a.min(b), a.max(b));
+        *edge_vertices.entry(key).or_insert_with(|| {
+            let (fa, fb) = (samples[key.0], samples[key.1]);
+            let t = if fb != fa {
+                (iso - fa) / (fb - fa)
+            } else {
+                0.5
+            };
+            let (pa, pb) = (position(key.0), position(key.1));
+            coords.push(pa + (pb - pa) * t);
+            let (ga, gb) = (gradient(key.0), gradient(key.1));
+            normals.push((ga + (gb - ga) * t).normalize_or_zero());
+            coords.len() as u32 - 1
+        })
+    };
+
+    for k in 0..dims[2] - 1 {
+        for j in 0..dims[1] - 1 {
+            for i in 0..dims[0] - 1 {
+                let corners: [usize; 8] = std::array::from_fn(|c| {
+                    index(i + (c & 1), j + ((c >> 1) & 1), k + ((c >> 2) & 1))
+                });
+
+                for tetrahedron in &CELL_TETRAHEDRA {
+                    // The corners inside the surface first, then those outside.
+                    let mut ids = tetrahedron.map(|c| corners[c]);
+                    ids.sort_by_key(|id| samples[*id] >= iso);
+                    let num_inside = ids.iter().filter(|id| samples[**id] < iso).count();
+                    let (inside, outside) = ids.split_at(num_inside);
+
+                    let (triangles, num_triangles): ([[(usize, usize); 3]; 2], usize) =
+                        match num_inside {
+                            1 => {
+                                let t = [
+                                    (inside[0], outside[0]),
+                                    (inside[0], outside[1]),
+                                    (inside[0], outside[2]),
+                                ];
+                                ([t, t], 1)
+                            }
+                            3 => {
+                                let t = [
+                                    (inside[0], outside[0]),
+                                    (inside[1], outside[0]),
+                                    (inside[2], outside[0]),
+                                ];
+                                ([t, t], 1)
+                            }
+                            2 => {
+                                let (a, b, c, d) = (inside[0], inside[1], outside[0], outside[1]);
+                                ([[(a, c), (a, d), (b, d)], [(a, c), (b, d), (b, c)]], 2)
+                            }
+                            _ => continue,
+                        };
+
+                    let out_point = position(outside[0]);
+                    for triangle in &triangles[..num_triangles] {
+                        let mut face = triangle.map(|edge| {
+                            vertex_on_edge(&mut coords, &mut normals, &mut edge_vertices, edge)
+                        });
+                        let [pa, pb, pc] = face.map(|v| coords[v as usize]);
+                        // Face away from the inside of the surface.
+                        if (pb - pa).cross(pc - pa).dot(out_point - pa) < 0.0 {
+                            face.swap(1, 2);
+                        }
+                        if face[0] != face[1] && face[1] != face[2] && face[0] != face[2] {
+                            faces.push(face);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    RenderMesh::new(
+        coords,
+        Some(normals),
+        None,
+        Some(IndexBuffer::Unified(faces)),
+    )
+}
+
+/// Extracts the isosurface `field(p) = iso` of an implicit function, sampled on
+/// a `dims[0] × dims[1] × dims[2]` grid spanning the box from `min` to `max`.
+///
+/// See [`isosurface`] for the conventions: points where `field` is below
+/// `iso` are inside the surface, so signed distance functions work as is.
+///
+/// # Example
+/// ```no_run
+/// # use kiss3d::procedural::isosurface_fn;
+/// # use kiss3d::glamx::Vec3;
+/// // A torus of radii 1 and 0.3 around the Y axis.
+/// let torus = |p: Vec3| {
+///     let q = (p.x * p.x + p.z * p.z).sqrt() - 1.0;
+///     (q * q + p.y * p.y).sqrt() - 0.3
+/// };
+/// let mesh = isosurface_fn(torus, [64, 32, 64], Vec3::new(-1.5, -0.5, -1.5), Vec3::new(1.5, 0.5, 1.5), 0.0);
+/// ```
+pub fn isosurface_fn(
+    field: impl Fn(Vec3) -> f32,
+    dims: [usize; 3],
+    min: Vec3,
+    max: Vec3,
+    iso: f32,
+) -> RenderMesh {
+    let steps = Vec3::new(
+        dims[0].saturating_sub(1).max(1) as f32,
+        dims[1].saturating_sub(1).max(1) as f32,
+        dims[2].saturating_sub(1).max(1) as f32,
+    );
+    let cell = (max - min) / steps;
+    let mut samples = Vec::with_capacity(dims[0] * dims[1] * dims[2]);
+    for k in 0..dims[2] {
+        for j in 0..dims[1] {
+            for i in 0..dims[0] {
+                samples.push(field(min + cell * Vec3::new(i as f32, j as f32, k as f32)));
+            }
+        }
+    }
+    isosurface(&samples, dims, min, max, iso)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sphere(dims: usize) -> RenderMesh {
+        isosurface_fn(
+            |p| p.length() - 0.8,
+            [dims; 3],
+            Vec3::splat(-1.0),
+            Vec3::splat(1.0),
+            0.0,
+        )
+    }
+
+    #[test]
+    fn sphere_is_closed_and_faces_outward() {
+        let mesh = sphere(12);
+        assert!(mesh.num_triangles() > 0);
+
+        for p in &mesh.coords {
+            assert!((p.length() - 0.8).abs() < 0.05, "{:?} is off the sphere", p);
+        }
+        for t in mesh.indices.as_unified() {
+            let [a, b, c] = t.map(|i| mesh.coords[i as usize]);
+            let n = (b - a).cross(c - a);
+            assert!(n.dot(a + b + c) > 0.0, "triangle {:?} faces inward", t);
+        }
+        // Every edge is shared by two triangles.
+        assert!(mesh
+            .face_adjacency()
+            .iter()
+            .all(|adj| adj.iter().all(Option::is_some)));
+    }
+
+    #[test]
+    fn normals_follow_the_gradient() {
+        let mesh = sphere(12);
+        let normals = mesh.normals.as_ref().unwrap();
+        for (p, n) in mesh.coords.iter().zip(normals) {
+            assert!(n.dot(p.normalize()) > 0.95);
+        }
+    }
+
+    #[test]
+    fn no_crossing_gives_an_empty_mesh() {
+        let outside = isosurface(&[1.0; 27], [3, 3, 3], Vec3::ZERO, Vec3::ONE, 0.0);
+        assert_eq!(outside.num_triangles(), 0);
+        let flat = isosurface(&[-1.0; 4], [2, 2, 1], Vec3::ZERO, Vec3::ONE, 0.0);
+        assert_eq!(flat.num_triangles(), 0);
+    }
+}
@@ -1,6 +1,6 @@
 use super::utils;
 use glamx::{Pose3, Vec2, Vec3};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Different representations of the index buffer.
 #[derive(Clone, Debug, PartialEq)]
@@ -51,6 +51,30 @@ impl IndexBuffer {
     }
 }
 
+/// How the vertex normals of a mesh are generated.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NormalMode {
+    /// Normals are averaged over the faces around each vertex, except across
+    /// edges where adjacent faces meet at an angle (in radians) larger than the
+    /// threshold, which stay sharp. `Smooth(PI)` averages over all the faces.
+    Smooth(f32),
+    /// Each face gets its own normal, for faceted shading.
+    Flat,
+    /// Uses the given normals, one per vertex.
+    Imported(Vec<Vec3>),
+}
+
+impl From<bool> for NormalMode {
+    /// `true` for [`NormalMode::Flat`], `false` for fully smooth normals.
+    fn from(flat_normals: bool) -> Self {
+        if flat_normals {
+            NormalMode::Flat
+        } else {
+            NormalMode::Smooth(std::f32::consts::PI)
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Geometric description of a mesh.
@@ -160,9 +184,7 @@ impl RenderMesh {
 
         res
     }
-}
 
-impl RenderMesh {
     /// Recomputes the mesh normals using its vertex coordinates and adjacency information
     /// inferred from the index buffer.
     #[inline]
@@ -187,6 +209,113 @@ impl RenderMesh {
         self.normals = Some(new_normals);
     }
 
+    /// Sets the normals of this mesh as described by `mode`.
+    ///
+    /// [`NormalMode::Flat`] and angle-limited [`NormalMode::Smooth`] duplicate
+    /// the vertices lying on sharp edges (and unify the index buffer).
+    ///
+    /// # Panics
+    /// With [`NormalMode::Imported`], if the number of normals differs from the
+    /// number of vertices.
+    pub fn apply_normal_mode(&mut self, mode: NormalMode) {
+        match mode {
+            NormalMode::Smooth(angle) if angle >= std::f32::consts::PI => self.recompute_normals(),
+            NormalMode::Smooth(angle) => self.smooth_normals_by_angle(angle),
+            NormalMode::Flat => {
+                self.replicate_vertices();
+                self.recompute_normals();
+            }
+            NormalMode::Imported(normals) => {
+                assert_eq!(
+                    normals.len(),
+                    self.coords.len(),
+                    "Imported normals must match the number of vertices."
+                );
+                self.normals = Some(normals);
+            }
+        }
+    }
+
+    /// Recomputes smooth normals, keeping the edges between faces forming an
+    /// angle larger than `max_angle` (in radians) sharp.
+    ///
+    /// The normal of each face corner is the average of the unit normals of the
+    /// faces around its vertex that are within `max_angle` of that face, the
+    /// same weighting as [`recompute_normals`](Self::recompute_normals). Vertices get duplicated where their corners end up with
+    /// different normals. Faces are only smoothed together if they share vertex
+    /// indices, so call [`weld_vertices`](Self::weld_vertices) first on meshes
+    /// with disconnected triangles.
+    pub fn smooth_normals_by_angle(&mut self, max_angle: f32) {
+        self.unify_index_buffer();
+
+        let faces = self.indices.as_unified().to_vec();
+        let face_normal = |t: &[u32; 3]| {
+            let [a, b, c] = t.map(|i| self.coords[i as usize]);
+            (b - a).cross(c - a).normalize_or_zero()
+        };
+        let vertex_faces = self.vertex_faces();
+        let cos_max = max_angle.cos();
+
+        #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+        let face_normals: Vec<Vec3> = {
+            use rayon::prelude::*;
+            faces.par_iter().map(face_normal).collect()
+        };
+        #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+        let face_normals: Vec<Vec3> = faces.iter().map(face_normal).collect();
+
+        let corner_normals = |(f, t): (usize, &[u32; 3])| {
+            t.map(|v| {
+                let mut normal = Vec3::ZERO;
+                for g in &vertex_faces[v as usize] {
+                    if *g == f || face_normals[f].dot(face_normals[*g]) >= cos_max {
+                        normal += face_normals[*g];
+                    }
+                }
+                normal.normalize_or_zero()
+            })
+        };
+
+        #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+        let corner_normals: Vec<[Vec3; 3]> = {
+            use rayon::prelude::*;
+            faces.par_iter().enumerate().map(corner_normals).collect()
+        };
+        #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+        let corner_normals: Vec<[Vec3; 3]> = faces.iter().enumerate().map(corner_normals).collect();
+
+        let mut vertex_ids: HashMap<(u32, [u32; 3]), u32> = HashMap::new();
+        let mut coords = Vec::new();
+        let mut normals = Vec::new();
+        let mut uvs = self.uvs.as_ref().map(|_| Vec::new());
+        let mut new_faces = Vec::with_capacity(faces.len());
+
+        for (t, corner_normals) in faces.iter().zip(&corner_normals) {
+            let mut new_face = [0; 3];
+
+            for (corner, (v, normal)) in t.iter().zip(corner_normals).enumerate() {
+                // Corners of the same vertex with the same smoothing group get the
+                // same normal bit-for-bit, so they share the output vertex.
+                let key = (*v, normal.to_array().map(f32::to_bits));
+                new_face[corner] = *vertex_ids.entry(key).or_insert_with(|| {
+                    coords.push(self.coords[*v as usize]);
+                    normals.push(*normal);
+                    if let (Some(uvs), Some(src)) = (uvs.as_mut(), self.uvs.as_ref()) {
+                        uvs.push(src[*v as usize]);
+                    }
+                    coords.len() as u32 - 1
+                });
+            }
+
+            new_faces.push(new_face);
+        }
+
+        self.coords = coords;
+        self.normals = Some(normals);
+        self.uvs = uvs;
+        self.indices = IndexBuffer::Unified(new_faces);
+    }
+
     /// Flips all the normals of this mesh.
     #[inline]
     pub fn flip_normals(&mut self) {
@@ -234,9 +363,7 @@ impl RenderMesh {
             }
         }
     }
-}
 
-impl RenderMesh {
     /// Scales each vertex of this mesh.
     #[inline]
     pub fn scale_by_scalar(&mut self, s: f32) {
@@ -244,9 +371,7 @@ impl RenderMesh {
             *c *= s
         }
     }
-}
 
-impl RenderMesh {
     /// Force the mesh to use the same index for vertices, normals and uvs.
     ///
     /// This might cause the duplication of some vertices, normals and uvs.
@@ -367,9 +492,7 @@ impl RenderMesh {
 
         self.indices = IndexBuffer::Unified(batched_indices)
     }
-}
 
-impl RenderMesh {
     /// Forces the mesh to use a different index for the vertices, normals and uvs.
     ///
     /// If `recover_topology` is true, this will merge exactly identical vertices together.
@@ -394,4 +517,333 @@ impl RenderMesh {
 
         let _ = new_indices.map(|nids| self.indices = nids);
     }
+
+    /// Merges the vertices lying within `epsilon` of each other.
+    ///
+    /// With a unified index buffer, each merged vertex keeps the normal and
+    /// texture coordinates of the first vertex of its cluster; call
+    /// [`recompute_normals`](Self::recompute_normals) afterwards to get smooth
+    /// shading across the welded seams. With a split index buffer only the
+    /// positions are merged. Returns the number of vertices removed.
+    pub fn weld_vertices(&mut self, epsilon: f32) -> usize {
+        let (remap, kept) = weld_map(&self.coords, epsilon);
+        let removed = self.coords.len() - kept.len();
+
+        self.coords = kept.iter().map(|i| self.coords[*i]).collect();
+
+        match self.indices {
+            IndexBuffer::Unified(ref mut idx) => {
+                if let Some(normals) = self.normals.as_mut() {
+                    *normals = kept.iter().map(|i| normals[*i]).collect();
+                }
+                if let Some(uvs) = self.uvs.as_mut() {
+                    *uvs = kept.iter().map(|i| uvs[*i]).collect();
+                }
+                for t in idx.iter_mut() {
+                    for v in t.iter_mut() {
+                        *v = remap[*v as usize];
+                    }
+                }
+            }
+            IndexBuffer::Split(ref mut idx) => {
+                for t in idx.iter_mut() {
+                    for v in t.iter_mut() {
+                        v[0] = remap[v[0] as usize];
+                    }
+                }
+            }
+        }
+
+        removed
+    }
+
+    /// Removes the triangles that reference the same vertex twice or have a
+    /// zero area. Vertices are left untouched. Returns the number of triangles
+    /// removed.
+    pub fn remove_degenerate_faces(&mut self) -> usize {
+        let coords = &self.coords;
+        let is_valid = |t: [u32; 3]| {
+            if t[0] == t[1] || t[1] == t[2] || t[2] == t[0] {
+                return false;
+            }
+            let [a, b, c] = t.map(|i| coords[i as usize]);
+            (b - a).cross(c - a).length_squared() > 0.0
+        };
+
+        let before = self.num_triangles();
+        match self.indices {
+            IndexBuffer::Unified(ref mut idx) => idx.retain(|t| is_valid(*t)),
+            IndexBuffer::Split(ref mut idx) => idx.retain(|t| is_valid(t.map(|v| v[0]))),
+        }
+
+        before - self.num_triangles()
+    }
+
+    /// For each vertex, the indices of the triangles using it.
+    pub fn vertex_faces(&self) -> Vec<Vec<usize>> {
+        let mut res = vec![Vec::new(); self.coords.len()];
+
+        for (f, t) in self.coord_triangles().enumerate() {
+            for v in t {
+                let faces: &mut Vec<usize> = &mut res[v as usize];
+                if faces.last() != Some(&f) {
+                    faces.push(f);
+                }
+            }
+        }
+
+        res
+    }
+
+    /// The unique undirected edges of this mesh, as sorted vertex index pairs in
+    /// order of first appearance.
+    ///
+    /// Unlike iterating over the triangles, each edge shared by two faces is
+    /// listed once.
+    pub fn edges(&self) -> Vec<[u32; 2]> {
+        let mut seen = HashSet::new();
+        let mut res = Vec::new();
+
+        for t in self.coord_triangles() {
+            for e in 0..3 {
+                let edge = sorted_edge(t[e], t[(e + 1) % 3]);
+                if edge[0] != edge[1] && seen.insert(edge) {
+                    res.push(edge);
+                }
+            }
+        }
+
+        res
+    }
+
+    /// For each triangle, the index of the triangle sharing each of its edges
+    /// (edge `i` goes from corner `i` to corner `(i + 1) % 3`), or `None` on a
+    /// boundary.
+    ///
+    /// Vertices are matched by index, so call
+    /// [`weld_vertices`](Self::weld_vertices) first on meshes whose faces do not
+    /// share vertices. The faces around a non-manifold edge are linked in pairs,
+    /// in order (the first with the second, the third with the fourth, …), and
+    /// the last one of an odd count has `None` for that edge.
+    pub fn face_adjacency(&self) -> Vec<[Option<usize>; 3]> {
+        let mut open: HashMap<[u32; 2], (usize, usize)> = HashMap::new();
+        let mut res = vec![[None; 3]; self.num_triangles()];
+
+        for (f, t) in self.coord_triangles().enumerate() {
+            for e in 0..3 {
+                let edge = sorted_edge(t[e], t[(e + 1) % 3]);
+                match open.remove(&edge) {
+                    Some((g, ge)) => {
+                        res[g][ge] = Some(f);
+                        res[f][e] = Some(g);
+                    }
+                    None => {
+                        open.insert(edge, (f, e));
+                    }
+                }
+            }
+        }
+
+        res
+    }
+
+    /// Iterates through the vertex (coordinate) indices of each triangle.
+    fn coord_triangles(&self) -> impl Iterator<Item = [u32; 3]> + '_ {
+        (0..self.num_triangles()).map(move |i| match self.indices {
+            IndexBuffer::Unified(ref idx) => idx[i],
+            IndexBuffer::Split(ref idx) => idx[i].map(|v| v[0]),
+        })
+    }
+}
+
+fn sorted_edge(a: u32, b: u32) -> [u32; 2] {
+    if a < b {
+        [a, b]
+    } else {
+        [b, a]
+    }
+}
+
+/// Clusters the points lying within `epsilon` of each other using a uniform
+/// grid. Returns, for each point, the index of its cluster, and for each cluster
+/// the index of its first (representative) point.
+fn weld_map(coords: &[Vec3], epsilon: f32) -> (Vec<u32>, Vec<usize>) {
+    let cell = epsilon.max(f32::EPSILON);
+    let key = |p: Vec3| {
+        [
+            (p.x / cell).floor() as i64,
+            (p.y / cell).floor() as i64,
+            (p.z / cell).floor() as i64,
+        ]
+    };
+
+    let mut grid: HashMap<[i64; 3], Vec<u32>> = HashMap::new();
+    let mut remap = Vec::with_capacity(coords.len());
+    let mut kept: Vec<usize> = Vec::new();
+
+    for (i, p) in coords.iter().enumerate() {
+        let k = key(*p);
+        let mut found = None;
+
+        'search: for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let Some(cluster) = grid.get(&[k[0] + dx, k[1] + dy, k[2] + dz]) else {
+                        continue;
+                    };
+                    for c in cluster {
+                        if coords[kept[*c as usize]].distance(*p) <= epsilon {
+                            found = Some(*c);
+                            break 'search;
+                        }
+                    }
+                }
+            }
+        }
+
+        let id = match found {
+            Some(id) => id,
+            None => {
+                let id = kept.len() as u32;
+                kept.push(i);
+                grid.entry(k).or_default().push(id);
+                id
+            }
+        };
+        remap.push(id);
+    }
+
+    (remap, kept)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two triangles forming a unit quad, each with its own copies of the vertices
+    /// of the shared diagonal.
+    fn unwelded_quad() -> RenderMesh {
+        let coords = vec![
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+        ];
+        RenderMesh::new(coords, None, None, None)
+    }
+
+    #[test]
+    fn weld_map_clusters_nearby_points() {
+        let coords = [
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0005, 0.0, 0.0),
+            // Across a grid cell boundary from the previous point.
+            Vec3::new(0.9995, 0.0, 0.0),
+            Vec3::new(0.0, 0.5, 0.0),
+        ];
+        let (remap, kept) = weld_map(&coords, 1.0e-3);
+        assert_eq!(remap, vec![0, 1, 0, 1, 2]);
+        assert_eq!(kept, vec![0, 1, 4]);
+    }
+
+    #[test]
+    fn weld_vertices_merges_shared_corners() {
+        let mut mesh = unwelded_quad();
+        assert_eq!(mesh.weld_vertices(1.0e-4), 2);
+        assert_eq!(mesh.coords.len(), 4);
+        assert_eq!(mesh.indices.as_unified(), &[[0, 1, 2], [0, 2, 3]]);
+        // Welding again is a no-op.
+        assert_eq!(mesh.weld_vertices(1.0e-4), 0);
+    }
+
+    #[test]
+    fn face_adjacency_links_shared_edges() {
+        let mut mesh = unwelded_quad();
+        // Faces don't share vertex indices before welding.
+        assert_eq!(mesh.face_adjacency(), vec![[None; 3]; 2]);
+
+        mesh.weld_vertices(1.0e-4);
+        // The diagonal is edge 2 (corner 2 to 0) of the first face and edge 0 of
+        // the second one.
+        assert_eq!(
+            mesh.face_adjacency(),
+            vec![[None, None, Some(1)], [Some(0), None, None]]
+        );
+    }
+
+    /// A pyramid with an irregular base, so that its side faces have very
+    /// different areas.
+    fn irregular_pyramid() -> RenderMesh {
+        let coords = vec![
+            Vec3::new(0.0, 0.0, 0.5),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(-1.0, 0.0, 0.0),
+            Vec3::new(0.0, -3.0, 0.0),
+        ];
+        let faces = vec![[0, 1, 2], [0, 2, 3], [0, 3, 4], [0, 4, 1]];
+        RenderMesh::new(coords, None, None, Some(IndexBuffer::Unified(faces)))
+    }
+
+    #[test]
+    fn smooth_normals_do_not_jump_below_the_full_angle() {
+        let mut full = irregular_pyramid();
+        full.apply_normal_mode(NormalMode::Smooth(std::f32::consts::PI));
+        let full_normals = full.normals.as_ref().unwrap();
+
+        // No edge is sharper than the threshold, so every vertex is smoothed over
+        // all its faces, exactly like with `Smooth(PI)`.
+        let mut limited = irregular_pyramid();
+        limited.apply_normal_mode(NormalMode::Smooth(2.0));
+        assert_eq!(limited.coords.len(), full.coords.len());
+
+        for (p, n) in limited.coords.iter().zip(limited.normals.as_ref().unwrap()) {
+            let i = full.coords.iter().position(|q| q == p).unwrap();
+            let expected = full_normals[i].normalize();
+            assert!(
+                (*n - expected).length() < 1.0e-5,
+                "{:?}: {:?} != {:?}",
+                p,
+                n,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn smooth_normals_by_angle_ignore_face_areas() {
+        let mut mesh = irregular_pyramid();
+        mesh.smooth_normals_by_angle(2.0);
+        let apex = mesh.coords.iter().position(|p| p.x == 0.0 && p.y == 0.0);
+        let apex_normal = mesh.normals.as_ref().unwrap()[apex.unwrap()];
+
+        let pyramid = irregular_pyramid();
+        let mut expected = Vec3::ZERO;
+        for t in pyramid.indices.as_unified() {
+            let [a, b, c] = t.map(|i| pyramid.coords[i as usize]);
+            expected += (b - a).cross(c - a).normalize();
+        }
+        assert!((apex_normal - expected.normalize()).length() < 1.0e-5);
+    }
+
+    #[test]
+    fn face_adjacency_pairs_faces_around_non_manifold_edges() {
+        // Three fins sharing the edge 0-1.
+        let coords = vec![
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, -1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+        ];
+        let indices = IndexBuffer::Unified(vec![[0, 1, 2], [1, 0, 3], [0, 1, 4]]);
+        let mesh = RenderMesh::new(coords, None, None, Some(indices));
+        assert_eq!(
+            mesh.face_adjacency(),
+            vec![[Some(1), None, None], [Some(0), None, None], [None; 3]]
+        );
+    }
 }
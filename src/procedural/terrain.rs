@@ -0,0 +1,309 @@
+use super::{IndexBuffer, RenderMesh};
+use crate::color::Color;
+use glamx::{Vec2, Vec3};
+
+/// Parameters of the fractal (FBM) Perlin noise shaping a [`terrain`].
+///
+/// The height at `(x, z)` is the sum of `octaves` layers of Perlin noise, each
+/// with `lacunarity` times the frequency and `persistence` times the amplitude of
+/// the previous one, normalized and scaled so it stays within
+/// `[-amplitude, amplitude]`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NoiseConfig {
+    /// Seed of the noise: different seeds give unrelated terrains.
+    pub seed: u32,
+    /// Frequency of the first octave, in features per world unit.
+    pub frequency: f32,
+    /// Number of noise layers added together. More octaves add finer details.
+    pub octaves: u32,
+    /// Frequency multiplier between successive octaves.
+    pub lacunarity: f32,
+    /// Amplitude multiplier between successive octaves.
+    pub persistence: f32,
+    /// Maximum height of the terrain above (and depth below) `y = 0`.
+    pub amplitude: f32,
+}
+
+impl Default for NoiseConfig {
+    fn default() -> Self {
+        NoiseConfig {
+            seed: 0,
+            frequency: 0.05,
+            octaves: 5,
+            lacunarity: 2.0,
+            persistence: 0.5,
+            amplitude: 5.0,
+        }
+    }
+}
+
+impl NoiseConfig {
+    /// The terrain height at the horizontal position `(x, z)`.
+    pub fn sample(&self, x: f32, z: f32) -> f32 {
+        let mut frequency = self.frequency;
+        let mut amplitude = 1.0;
+        let mut total = 0.0;
+        let mut norm = 0.0;
+
+        for octave in 0..self.octaves.max(1) {
+            let seed = self.seed.wrapping_add(octave.wrapping_mul(0x9e37_79b9));
+            total += perlin(x * frequency, z * frequency, seed) * amplitude;
+            norm += amplitude;
+            frequency *= self.lacunarity;
+            amplitude *= self.persistence;
+        }
+
+        total / norm * self.amplitude
+    }
+}
+
+/// 2D Perlin (gradient) noise at `(x, y)`, roughly in `[-1, 1]`.
+///
+/// The noise is smooth, zero at integer coordinates, and fully determined by the
+/// coordinates and `seed`.
+pub fn perlin(x: f32, y: f32, seed: u32) -> f32 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let (ix, iy) = (x0 as i32, y0 as i32);
+
+    let corner = |cx: i32, cy: i32, dx: f32, dy: f32| {
+        let angle = hash(ix.wrapping_add(cx), iy.wrapping_add(cy), seed) as f32
+            * (std::f32::consts::TAU / u32::MAX as f32);
+        angle.cos() * dx + angle.sin() * dy
+    };
+    let fade = |t: f32| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+
+    let (u, v) = (fade(fx), fade(fy));
+    let bottom = lerp(corner(0, 0, fx, fy), corner(1, 0, fx - 1.0, fy), u);
+    let top = lerp(
+        corner(0, 1, fx, fy - 1.0),
+        corner(1, 1, fx - 1.0, fy - 1.0),
+        u,
+    );
+    // Unit gradients give values within ±√2/2: rescale to about ±1.
+    lerp(bottom, top, v) * std::f32::consts::SQRT_2
+}
+
+fn hash(x: i32, y: i32, seed: u32) -> u32 {
+    let mut h = (x as u32).wrapping_mul(0x8da6_b343)
+        ^ (y as u32).wrapping_mul(0xd816_3841)
+        ^ seed.wrapping_mul(0xcb1a_b31f);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0x5bd1_e995);
+    h ^ (h >> 15)
+}
+
+/// Generates a noise-based terrain heightfield.
+///
+/// The terrain lies on the XZ plane, centered at the origin, with heights along
+/// the Y axis given by `noise` sampled at each vertex's world position (so two
+/// terrains with the same `noise` tile seamlessly when placed side by side).
+/// The grid has `subdivisions × subdivisions` cells and smooth normals. Its
+/// texture coordinates hit the texel centers of a `(subdivisions + 1)²` texture,
+/// such as the one produced by [`terrain_texture`], so each vertex gets exactly
+/// one texel.
+///
+/// # Arguments
+/// * `width` - The terrain extent along the X axis
+/// * `depth` - The terrain extent along the Z axis
+/// * `subdivisions` - Number of grid cells along each axis. Must not be 0.
+/// * `noise` - The noise shaping the heights
+///
+/// # Example
+/// ```no_run
+/// # use kiss3d::procedural::{terrain, NoiseConfig};
+/// let mesh = terrain(100.0, 100.0, 128, &NoiseConfig { seed: 42, ..Default::default() });
+/// ```
+///
+/// # Panics
+/// Panics if `subdivisions` is 0.
+pub fn terrain(width: f32, depth: f32, subdivisions: usize, noise: &NoiseConfig) -> RenderMesh {
+    assert!(
+        subdivisions > 0,
+        "The number of subdivisions cannot be zero"
+    );
+
+    let n = subdivisions;
+    let stride = n + 1;
+    let (dx, dz) = (width / n as f32, depth / n as f32);
+
+    let mut coords = Vec::with_capacity(stride * stride);
+    let mut uvs = Vec::with_capacity(stride * stride);
+    for i in 0..stride {
+        for j in 0..stride {
+            let x = j as f32 * dx - width * 0.5;
+            let z = i as f32 * dz - depth * 0.5;
+            coords.push(Vec3::new(x, noise.sample(x, z), z));
+            uvs.push(Vec2::new(
+                (j as f32 + 0.5) / stride as f32,
+                (i as f32 + 0.5) / stride as f32,
+            ));
+        }
+    }
+
+    // Normals from the height gradient (central differences, one-sided on edges).
+    let height = |i: usize, j: usize| coords[i * stride + j].y;
+    let mut normals = Vec::with_capacity(stride * stride);
+    for i in 0..stride {
+        for j in 0..stride {
+            let (j0, j1) = (j.saturating_sub(1), (j + 1).min(n));
+            let (i0, i1) = (i.saturating_sub(1), (i + 1).min(n));
+            let slope_x = (height(i, j1) - height(i, j0)) / ((j1 - j0) as f32 * dx);
+            let slope_z = (height(i1, j) - height(i0, j)) / ((i1 - i0) as f32 * dz);
+            normals.push(Vec3::new(-slope_x, 1.0, -slope_z).normalize());
+        }
+    }
+
+    // Two counter-clockwise (seen from +Y) triangles per cell.
+    let mut triangles = Vec::with_capacity(n * n * 2);
+    for i in 0..n as u32 {
+        for j in 0..n as u32 {
+            let s = stride as u32;
+            let a = i * s + j;
+            let b = a + 1;
+            let c = a + s;
+            let d = c + 1;
+            triangles.push([a, c, b]);
+            triangles.push([b, c, d]);
+        }
+    }
+
+    RenderMesh::new(
+        coords,
+        Some(normals),
+        Some(uvs),
+        Some(IndexBuffer::Unified(triangles)),
+    )
+}
+
+/// Height- and slope-based colors of a terrain.
+///
+/// Heights are measured relative to the lowest and highest vertices of the mesh:
+/// the lowest areas get `low`, blending into `mid` around `mid_height` and into
+/// `high` around `high_height`. Slopes steeper than `steep_angle` blend into
+/// `steep` regardless of their height.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TerrainColoring {
+    /// Color of the lowest areas (e.g. sand).
+    pub low: Color,
+    /// Color of the mid-height areas (e.g. grass).
+    pub mid: Color,
+    /// Color of the highest areas (e.g. snow).
+    pub high: Color,
+    /// Color of the steep slopes (e.g. rock).
+    pub steep: Color,
+    /// Relative height, in `[0, 1]`, where `low` turns into `mid`.
+    pub mid_height: f32,
+    /// Relative height, in `[0, 1]`, where `mid` turns into `high`.
+    pub high_height: f32,
+    /// Slope angle from the horizontal, in radians, where the `steep` color
+    /// starts blending in.
+    pub steep_angle: f32,
+}
+
+impl Default for TerrainColoring {
+    fn default() -> Self {
+        TerrainColoring {
+            low: Color::new(0.76, 0.7, 0.5, 1.0),
+            mid: Color::new(0.3, 0.55, 0.2, 1.0),
+            high: Color::new(0.95, 0.95, 0.97, 1.0),
+            steep: Color::new(0.45, 0.4, 0.35, 1.0),
+            mid_height: 0.25,
+            high_height: 0.8,
+            steep_angle: 35.0f32.to_radians(),
+        }
+    }
+}
+
+impl TerrainColoring {
+    /// The color of a vertex at relative height `height` (in `[0, 1]`) whose
+    /// normal is `normal`.
+    pub fn color_at(&self, height: f32, normal: Vec3) -> Color {
+        const BLEND: f32 = 0.05;
+        let smoothstep = |e0: f32, e1: f32, x: f32| {
+            let t = ((x - e0) / (e1 - e0)).clamp(0.0, 1.0);
+            t * t * (3.0 - 2.0 * t)
+        };
+        let mix = |a: Color, b: Color, t: f32| {
+            Color::new(
+                a.r + (b.r - a.r) * t,
+                a.g + (b.g - a.g) * t,
+                a.b + (b.b - a.b) * t,
+                a.a + (b.a - a.a) * t,
+            )
+        };
+
+        let by_height = mix(
+            mix(
+                self.low,
+                self.mid,
+                smoothstep(self.mid_height - BLEND, self.mid_height + BLEND, height),
+            ),
+            self.high,
+            smoothstep(self.high_height - BLEND, self.high_height + BLEND, height),
+        );
+        let slope = normal.normalize_or(Vec3::Y).y.clamp(-1.0, 1.0).acos();
+        mix(
+            by_height,
+            self.steep,
+            smoothstep(self.steep_angle, self.steep_angle + 0.15, slope),
+        )
+    }
+}
+
+/// Computes one color per vertex of `mesh` (typically generated by [`terrain`])
+/// from its height and slope.
+///
+/// Vertices are assumed to be Y-up. Without normals, every vertex is considered
+/// flat.
+pub fn terrain_colors(mesh: &RenderMesh, coloring: &TerrainColoring) -> Vec<Color> {
+    let (min, max) = mesh
+        .coords
+        .iter()
+        .fold((f32::MAX, f32::MIN), |(lo, hi), p| {
+            (lo.min(p.y), hi.max(p.y))
+        });
+    let range = (max - min).max(f32::EPSILON);
+
+    mesh.coords
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let normal = mesh
+                .normals
+                .as_ref()
+                .and_then(|n| n.get(i).copied())
+                .unwrap_or(Vec3::Y);
+            coloring.color_at((p.y - min) / range, normal)
+        })
+        .collect()
+}
+
+/// Bakes the [`terrain_colors`] of a mesh generated by [`terrain`] with
+/// `subdivisions` into a `(subdivisions + 1)²` image, one texel per vertex, to be
+/// used as its texture.
+///
+/// # Panics
+/// Panics if `mesh` doesn't have `(subdivisions + 1)²` vertices.
+pub fn terrain_texture(
+    mesh: &RenderMesh,
+    subdivisions: usize,
+    coloring: &TerrainColoring,
+) -> image::RgbaImage {
+    let stride = subdivisions + 1;
+    assert_eq!(
+        mesh.coords.len(),
+        stride * stride,
+        "The mesh is not a terrain with {} subdivisions",
+        subdivisions
+    );
+
+    let to_u8 = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let pixels = terrain_colors(mesh, coloring)
+        .into_iter()
+        .flat_map(|c| [to_u8(c.r), to_u8(c.g), to_u8(c.b), to_u8(c.a)])
+        .collect();
+    image::RgbaImage::from_raw(stride as u32, stride as u32, pixels)
+        .expect("terrain texture size mismatch")
+}
@@ -0,0 +1,111 @@
+use super::{IndexBuffer, RenderMesh};
+use glamx::Vec3;
+
+/// Generates a tube of constant radius following a 3D polyline, with flat caps
+/// at both ends.
+///
+/// The cross-sections are oriented with parallel-transported frames, so the tube
+/// does not twist along curved paths. The vertices are laid out as follows,
+/// which allows giving them per-vertex attributes matching the path points:
+///
+/// * `path.len() * nsubdiv` side vertices, `nsubdiv` per path point, in order;
+/// * `nsubdiv + 1` vertices of the start cap, then `nsubdiv + 1` of the end cap.
+///
+/// Consecutive duplicate points are not removed; the result is empty if `path`
+/// has less than two points.
+///
+/// # Arguments
+/// * `path` - The points the tube passes through
+/// * `radius` - The radius of the tube
+/// * `nsubdiv` - Number of subdivisions around each cross-section
+///
+/// # Example
+/// ```no_run
+/// # use kiss3d::procedural::tube;
+/// # use glamx::Vec3;
+/// let helix: Vec<Vec3> = (0..200)
+///     .map(|i| {
+///         let t = i as f32 * 0.1;
+///         Vec3::new(t.cos(), t * 0.1, t.sin())
+///     })
+///     .collect();
+/// let tube_mesh = tube(&helix, 0.05, 12);
+/// ```
+pub fn tube(path: &[Vec3], radius: f32, nsubdiv: u32) -> RenderMesh {
+    let mut coords = Vec::new();
+    let mut normals = Vec::new();
+    let mut faces = Vec::new();
+    let nsubdiv = nsubdiv.max(3);
+
+    if path.len() >= 2 {
+        let last = path.len() - 1;
+        let tangent = |i: usize| {
+            let t = (path[i.min(last - 1) + 1] - path[i.saturating_sub(1).min(last - 1)])
+                .normalize_or_zero();
+            if t == Vec3::ZERO {
+                Vec3::Y
+            } else {
+                t
+            }
+        };
+        let dtheta = std::f32::consts::TAU / nsubdiv as f32;
+
+        let mut normal = tangent(0).any_orthonormal_vector();
+        let mut frames = Vec::with_capacity(path.len());
+        for (i, point) in path.iter().enumerate() {
+            let t = tangent(i);
+            // Parallel transport: remove the tangent component of the previous normal.
+            let projected = normal - t * normal.dot(t);
+            normal = if projected.length_squared() > 1.0e-12 {
+                projected.normalize()
+            } else {
+                t.any_orthonormal_vector()
+            };
+            let binormal = t.cross(normal);
+            frames.push((t, normal, binormal));
+
+            for j in 0..nsubdiv {
+                let theta = j as f32 * dtheta;
+                let dir = normal * theta.cos() + binormal * theta.sin();
+                coords.push(*point + dir * radius);
+                normals.push(dir);
+            }
+        }
+
+        for i in 0..last as u32 {
+            let (ring, next) = (i * nsubdiv, (i + 1) * nsubdiv);
+            for j in 0..nsubdiv {
+                let k = (j + 1) % nsubdiv;
+                faces.push([ring + j, ring + k, next + j]);
+                faces.push([ring + k, next + k, next + j]);
+            }
+        }
+
+        // Caps, with their own vertices for sharp edges.
+        for (end, facing) in [(0, -1.0), (last, 1.0)] {
+            let (t, _, _) = frames[end];
+            let center = coords.len() as u32;
+            coords.push(path[end]);
+            normals.push(t * facing);
+            for j in 0..nsubdiv as usize {
+                coords.push(coords[end * nsubdiv as usize + j]);
+                normals.push(t * facing);
+            }
+            for j in 0..nsubdiv {
+                let (a, b) = (center + 1 + j, center + 1 + (j + 1) % nsubdiv);
+                if facing < 0.0 {
+                    faces.push([center, b, a]);
+                } else {
+                    faces.push([center, a, b]);
+                }
+            }
+        }
+    }
+
+    RenderMesh::new(
+        coords,
+        Some(normals),
+        None,
+        Some(IndexBuffer::Unified(faces)),
+    )
+}
@@ -1,5 +1,6 @@
 //! Utilities useful for various generations tasks.
 
+use super::RenderMesh;
 use glamx::{Vec2, Vec3};
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
@@ -220,6 +221,9 @@ pub fn split_index_buffer_and_recover_topology(
 
 // TODO: check at compile-time that we are in 3D?
 /// Computes the normals of a set of vertices.
+///
+/// With the `parallel` feature (on non-wasm targets), the face normals are
+/// computed on the rayon thread pool.
 #[inline]
 pub fn compute_normals(coordinates: &[Vec3], faces: &[[u32; 3]], normals: &mut Vec<Vec3>) {
     let mut divisor: Vec<f32> = vec![0.0; coordinates.len()];
@@ -233,18 +237,28 @@ pub fn compute_normals(coordinates: &[Vec3], faces: &[[u32; 3]], normals: &mut V
     normals.clear();
     normals.extend(std::iter::repeat_n(Vec3::ZERO, coordinates.len()));
 
-    // Accumulate normals ...
-    for f in faces.iter() {
+    let face_normal = |f: &[u32; 3]| {
         let edge1 = coordinates[f[1] as usize] - coordinates[f[0] as usize];
         let edge2 = coordinates[f[2] as usize] - coordinates[f[0] as usize];
         let cross = edge1.cross(edge2);
 
-        let normal = if cross.length_squared() > 0.0 {
+        if cross.length_squared() > 0.0 {
             cross.normalize()
         } else {
             cross
-        };
+        }
+    };
 
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    let face_normals: Vec<Vec3> = {
+        use rayon::prelude::*;
+        faces.par_iter().map(face_normal).collect()
+    };
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+    let face_normals = faces.iter().map(face_normal);
+
+    // Accumulate normals ...
+    for (f, normal) in faces.iter().zip(face_normals) {
         normals[f[0] as usize] += normal;
         normals[f[1] as usize] += normal;
         normals[f[2] as usize] += normal;
@@ -259,3 +273,42 @@ pub fn compute_normals(coordinates: &[Vec3], faces: &[[u32; 3]], normals: &mut V
         *n /= *divisor
     }
 }
+
+/// Builds one mesh per input with `build`, on the rayon thread pool when the
+/// `parallel` feature is enabled (on non-wasm targets), sequentially otherwise.
+///
+/// The output keeps the order of `inputs`. Generating meshes only touches CPU
+/// data, so it can run off the render thread; the results are then handed to the
+/// window for GPU upload, e.g. with
+/// [`SceneNode3d::add_render_mesh`](crate::scene::SceneNode3d::add_render_mesh).
+///
+/// # Example
+///
+/// ```no_run
+/// # use kiss3d::prelude::*;
+/// # use kiss3d::procedural;
+/// # fn example(scene: &mut SceneNode3d) {
+/// let meshes = procedural::utils::build_meshes((1..=64).collect(), |i| {
+///     procedural::sphere(0.1 * i as f32, 64, 32, true)
+/// });
+///
+/// for mesh in meshes {
+///     scene.add_render_mesh(mesh, Vec3::ONE);
+/// }
+/// # }
+/// ```
+pub fn build_meshes<T, F>(inputs: Vec<T>, build: F) -> Vec<RenderMesh>
+where
+    T: Send,
+    F: Fn(T) -> RenderMesh + Sync + Send,
+{
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    {
+        use rayon::prelude::*;
+        inputs.into_par_iter().map(build).collect()
+    }
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+    {
+        inputs.into_iter().map(build).collect()
+    }
+}
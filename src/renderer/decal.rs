@@ -0,0 +1,463 @@
+//! Screen-space projected decals.
+//!
+//! A decal is an oriented box that projects a texture onto whatever opaque
+//! geometry lies inside it, without touching that geometry's mesh or UVs. It is
+//! drawn in a deferred pass after the opaque scene: the box's back faces are
+//! rasterized, and each covered pixel reconstructs its world position from the
+//! scene depth buffer, maps it into the box and samples the texture there.
+//! Markers, footprints or annotations can thus be laid over arbitrary terrain.
+//!
+//! Decals are composited unlit (alpha-blended over the shaded scene) and only
+//! affect the opaque surfaces written to the depth buffer; transparent surfaces
+//! drawn afterwards are not decorated.
+
+use crate::camera::Camera3d;
+use crate::color::Color;
+use crate::context::Context;
+use crate::resource::{multisample_state, DynamicUniformBuffer, PipelineCache, Texture};
+use bytemuck::{Pod, Zeroable};
+use glamx::{Mat4, Pose3, Vec3};
+use std::sync::Arc;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct FrameUniforms {
+    view_proj: [[f32; 4]; 4],
+    inv_view_proj: [[f32; 4]; 4],
+    // (width, height, unused, unused) of the scene attachment.
+    viewport: [f32; 4],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct DecalUniforms {
+    local_to_world: [[f32; 4]; 4],
+    world_to_local: [[f32; 4]; 4],
+    color: [f32; 4],
+}
+
+/// A texture projected onto the scene, registered with
+/// [`Window::add_decal`](crate::window::Window::add_decal).
+///
+/// The decal covers the box of dimensions `size` centered on `pose`. The texture
+/// is projected along the box's local `-Y` axis: its U axis follows local `+X`
+/// and its V axis local `-Z`, so an identity pose lays it flat on the ground,
+/// seen upright from above.
+#[derive(Clone)]
+pub struct Decal {
+    /// The projected texture (its alpha is honored).
+    pub texture: Arc<Texture>,
+    /// Position and orientation of the projection box's center.
+    pub pose: Pose3,
+    /// Full extents of the projection box along its local axes. The `y` extent is
+    /// the projection depth: how far above and below the center surfaces receive
+    /// the decal.
+    pub size: Vec3,
+    /// Tint multiplied with the texture color (including alpha).
+    pub color: Color,
+}
+
+impl Decal {
+    /// A white-tinted decal projecting `texture` through the box `size` at `pose`.
+    pub fn new(texture: Arc<Texture>, pose: Pose3, size: Vec3) -> Self {
+        Decal {
+            texture,
+            pose,
+            size,
+            color: Color::new(1.0, 1.0, 1.0, 1.0),
+        }
+    }
+
+    fn local_to_world(&self) -> Mat4 {
+        self.pose.to_mat4() * Mat4::from_scale(self.size.max(Vec3::splat(1.0e-6)))
+    }
+}
+
+struct Slot {
+    decal: Decal,
+    // Texture bind group, tagged with the texture it was built for.
+    texture_group: Option<(Arc<Texture>, wgpu::BindGroup)>,
+}
+
+/// The set of decals of a window and the GPU state used to draw them. Created
+/// lazily by [`Window::add_decal`](crate::window::Window::add_decal).
+pub struct Decals {
+    // Removed decals leave a `None` hole so the other indices stay valid.
+    slots: Vec<Option<Slot>>,
+    frame_layout: [wgpu::BindGroupLayout; 2],
+    decal_layout: wgpu::BindGroupLayout,
+    texture_layout: wgpu::BindGroupLayout,
+    pipeline: PipelineCache,
+    // One entry per stereo pass of the frame.
+    frame_uniforms: DynamicUniformBuffer<FrameUniforms>,
+    decal_uniforms: DynamicUniformBuffer<DecalUniforms>,
+    decal_group: Option<wgpu::BindGroup>,
+    // The offset of each decal in `decal_uniforms`, set by the frame's first pass.
+    decal_offsets: Vec<u32>,
+}
+
+impl Decals {
+    /// Creates an empty decal set.
+    pub fn new() -> Decals {
+        let ctxt = Context::get();
+
+        let frame_layout = [false, true].map(|multisampled| {
+            ctxt.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("decal_frame_bind_group_layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: true,
+                            min_binding_size: wgpu::BufferSize::new(
+                                std::mem::size_of::<FrameUniforms>() as u64,
+                            ),
+                        },
+                        count: None,
+                    },
+                    // Bound as an unfilterable float texture rather than a depth
+                    // texture: the GL backend can't `textureLoad` depth textures.
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled,
+                        },
+                        count: None,
+                    },
+                ],
+            })
+        });
+
+        let decal_layout = ctxt.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("decal_uniform_bind_group_layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: wgpu::BufferSize::new(
+                        std::mem::size_of::<DecalUniforms>() as u64
+                    ),
+                },
+                count: None,
+            }],
+        });
+
+        let texture_layout = ctxt.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("decal_texture_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let layouts = [0, 1].map(|i| {
+            ctxt.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("decal_pipeline_layout"),
+                bind_group_layouts: &[
+                    Some(&frame_layout[i]),
+                    Some(&decal_layout),
+                    Some(&texture_layout),
+                ],
+                immediate_size: 0,
+            })
+        });
+
+        // Built lazily per MSAA sample count; the scene depth binding (and thus the
+        // shader and layout) differs between single- and multi-sampled targets.
+        let pipeline = PipelineCache::new(move |sample_count| {
+            let ctxt = Context::get();
+            let msaa = sample_count > 1;
+            let shader = ctxt.create_shader_module(
+                Some("decal_shader"),
+                &crate::builtin::compile_wesl(
+                    &[("package::decal", include_str!("../builtin/decal.wgsl"))],
+                    "package::decal",
+                    &[("msaa", msaa)],
+                ),
+            );
+            ctxt.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("decal_pipeline"),
+                layout: Some(&layouts[msaa as usize]),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_main"),
+                    buffers: &[],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some("fs_main"),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: Context::render_format(),
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                // Only the back faces are drawn, so each pixel is shaded once and the
+                // box still covers the screen when the camera is inside it.
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: Some(wgpu::Face::Front),
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                // The depth buffer is read as a texture, so it can't also be the
+                // attachment; the box is clipped against it in the shader instead.
+                depth_stencil: None,
+                multisample: multisample_state(sample_count),
+                multiview_mask: None,
+                cache: None,
+            })
+        });
+
+        Decals {
+            slots: Vec::new(),
+            frame_layout,
+            decal_layout,
+            texture_layout,
+            pipeline,
+            frame_uniforms: DynamicUniformBuffer::new("decal_frame_uniforms"),
+            decal_uniforms: DynamicUniformBuffer::new("decal_uniforms"),
+            decal_group: None,
+            decal_offsets: Vec::new(),
+        }
+    }
+
+    /// Registers a decal and returns its index. Indices of removed decals are
+    /// reused.
+    pub fn add(&mut self, decal: Decal) -> usize {
+        let slot = Some(Slot {
+            decal,
+            texture_group: None,
+        });
+        match self.slots.iter().position(Option::is_none) {
+            Some(idx) => {
+                self.slots[idx] = slot;
+                idx
+            }
+            None => {
+                self.slots.push(slot);
+                self.slots.len() - 1
+            }
+        }
+    }
+
+    /// Removes decal `idx`, returning it if it existed.
+    pub fn remove(&mut self, idx: usize) -> Option<Decal> {
+        self.slots
+            .get_mut(idx)
+            .and_then(Option::take)
+            .map(|slot| slot.decal)
+    }
+
+    /// Removes every decal.
+    pub fn clear(&mut self) {
+        self.slots.clear();
+    }
+
+    /// The decal at `idx`, if any.
+    pub fn get(&self, idx: usize) -> Option<&Decal> {
+        self.slots.get(idx)?.as_ref().map(|slot| &slot.decal)
+    }
+
+    /// Mutable access to the decal at `idx` (to move, resize, retexture or tint it).
+    pub fn get_mut(&mut self, idx: usize) -> Option<&mut Decal> {
+        self.slots
+            .get_mut(idx)?
+            .as_mut()
+            .map(|slot| &mut slot.decal)
+    }
+
+    /// Translates every decal by `offset`.
+    pub(crate) fn translate(&mut self, offset: Vec3) {
+        for slot in self.slots.iter_mut().flatten() {
+            slot.decal.pose.translation += offset;
+        }
+    }
+
+    /// Number of registered decals.
+    pub fn len(&self) -> usize {
+        self.slots.iter().flatten().count()
+    }
+
+    /// Whether no decal is registered.
+    pub fn is_empty(&self) -> bool {
+        self.slots.iter().all(Option::is_none)
+    }
+
+    /// Projects every decal onto `color_view` (the HDR scene attachment of size
+    /// `width × height`), using the opaque scene depth in `depth_view` (a view of
+    /// its depth aspect) as seen from `camera`'s pass `pass`.
+    ///
+    /// Called after the opaque geometry of each pass; the decal uniforms are
+    /// gathered by pass `0`.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn render(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        color_view: &wgpu::TextureView,
+        depth_view: &wgpu::TextureView,
+        sample_count: u32,
+        (width, height): (u32, u32),
+        camera: &dyn Camera3d,
+        pass: usize,
+        gpu: Option<&mut crate::renderer::timings::GpuTimer>,
+    ) {
+        if self.is_empty() {
+            return;
+        }
+
+        let ctxt = Context::get();
+        if pass == 0 {
+            self.frame_uniforms.clear();
+            self.gather_decals();
+        }
+
+        let (view, proj) = camera.view_transform_pair(pass);
+        let view_proj = proj * view.to_mat4();
+        let frame_offset = self.frame_uniforms.push(&FrameUniforms {
+            view_proj: view_proj.to_cols_array_2d(),
+            inv_view_proj: view_proj.inverse().to_cols_array_2d(),
+            viewport: [width as f32, height as f32, 0.0, 0.0],
+        });
+        let _ = self.frame_uniforms.flush();
+
+        // Rebuilt every frame: the depth texture is recreated on resize.
+        let frame_group = ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("decal_frame_bind_group"),
+            layout: &self.frame_layout[(sample_count > 1) as usize],
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                        buffer: self.frame_uniforms.buffer(),
+                        offset: 0,
+                        size: wgpu::BufferSize::new(std::mem::size_of::<FrameUniforms>() as u64),
+                    }),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(depth_view),
+                },
+            ],
+        });
+
+        let pipeline = self.pipeline.get(sample_count);
+        let decal_ts = gpu.and_then(|g| g.render_scope("decals"));
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("decal_pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: color_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+                depth_slice: None,
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: decal_ts,
+            occlusion_query_set: None,
+            multiview_mask: None,
+        });
+        render_pass.set_pipeline(&pipeline);
+        render_pass.set_bind_group(0, &frame_group, &[frame_offset]);
+        let decal_group = self.decal_group.as_ref().expect("created by pass 0");
+        for (slot, offset) in self.slots.iter().flatten().zip(&self.decal_offsets) {
+            let (_, texture_group) = slot.texture_group.as_ref().expect("created by pass 0");
+            render_pass.set_bind_group(1, decal_group, &[*offset]);
+            render_pass.set_bind_group(2, texture_group, &[]);
+            render_pass.draw(0..36, 0..1);
+        }
+    }
+
+    /// Uploads the uniforms of every decal and creates their missing texture bind
+    /// groups, once per frame.
+    fn gather_decals(&mut self) {
+        let ctxt = Context::get();
+        self.decal_uniforms.clear();
+        self.decal_offsets.clear();
+        for slot in self.slots.iter_mut().flatten() {
+            let d = &slot.decal;
+            let local_to_world = d.local_to_world();
+            let offset = self.decal_uniforms.push(&DecalUniforms {
+                local_to_world: local_to_world.to_cols_array_2d(),
+                world_to_local: local_to_world.inverse().to_cols_array_2d(),
+                color: [d.color.r, d.color.g, d.color.b, d.color.a],
+            });
+
+            if !slot
+                .texture_group
+                .as_ref()
+                .is_some_and(|(tex, _)| Arc::ptr_eq(tex, &d.texture))
+            {
+                let group = ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("decal_texture_bind_group"),
+                    layout: &self.texture_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::TextureView(&d.texture.view),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::Sampler(&d.texture.sampler),
+                        },
+                    ],
+                });
+                slot.texture_group = Some((d.texture.clone(), group));
+            }
+            self.decal_offsets.push(offset);
+        }
+        if self.decal_uniforms.flush() || self.decal_group.is_none() {
+            self.decal_group =
+                Some(
+                    ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
+                        label: Some("decal_uniform_bind_group"),
+                        layout: &self.decal_layout,
+                        entries: &[wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                                buffer: self.decal_uniforms.buffer(),
+                                offset: 0,
+                                size: wgpu::BufferSize::new(
+                                    std::mem::size_of::<DecalUniforms>() as u64
+                                ),
+                            }),
+                        }],
+                    }),
+                );
+        }
+    }
+}
+
+impl Default for Decals {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,499 @@
+//! Secondary camera views drawn into rectangles of the window (minimaps, debug
+//! views).
+
+use crate::camera::Camera3d;
+use crate::color::Color;
+use crate::context::Context;
+use crate::post_processing::HdrSettings;
+use crate::renderer::timings::GpuTimer;
+use crate::renderer::ReflectorOit;
+use bytemuck::{Pod, Zeroable};
+use std::any::Any;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct InsetUniforms {
+    size_border_exposure: [f32; 4],
+    border_color: [f32; 4],
+    tonemap_op: u32,
+    _pad: [u32; 3],
+}
+
+/// Object-safe camera that can also be downcast back to its concrete type.
+trait InsetCamera: Camera3d {
+    fn as_camera_mut(&mut self) -> &mut dyn Camera3d;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<C: Camera3d + 'static> InsetCamera for C {
+    fn as_camera_mut(&mut self) -> &mut dyn Camera3d {
+        self
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// The offscreen targets an inset view is rendered into.
+pub(crate) struct InsetTargets {
+    _color: wgpu::Texture,
+    pub(crate) color_view: wgpu::TextureView,
+    _depth: wgpu::Texture,
+    pub(crate) depth_view: wgpu::TextureView,
+    /// Weighted-blended OIT targets, created once the scene has transparent
+    /// surfaces.
+    pub(crate) oit: Option<ReflectorOit>,
+    width: u32,
+    height: u32,
+}
+
+impl InsetTargets {
+    fn new(width: u32, height: u32) -> InsetTargets {
+        let ctxt = Context::get();
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let color = ctxt.create_texture(&wgpu::TextureDescriptor {
+            label: Some("inset_view_color"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: crate::post_processing::HDR_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let depth = ctxt.create_texture(&wgpu::TextureDescriptor {
+            label: Some("inset_view_depth"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: Context::depth_format(),
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        InsetTargets {
+            color_view: color.create_view(&wgpu::TextureViewDescriptor::default()),
+            depth_view: depth.create_view(&wgpu::TextureViewDescriptor::default()),
+            _color: color,
+            _depth: depth,
+            oit: None,
+            width,
+            height,
+        }
+    }
+}
+
+/// A secondary camera whose view of the scene is drawn into a rectangle of the
+/// window each frame, on top of the main image.
+///
+/// Created by [`Window::add_inset_view`](crate::window::Window::add_inset_view).
+/// The rectangle is given as fractions of the window size, so it follows resizes.
+/// The inset camera receives no input events: move it from the application, e.g.
+/// through [`camera_mut`](Self::camera_mut).
+///
+/// Insets render the opaque and transparent surfaces plus the skybox; refractive
+/// glass, planar reflectors and screen-space effects (SSAO, SSR, depth of field,
+/// bloom) are only applied to the main view.
+pub struct InsetView {
+    camera: Box<dyn InsetCamera>,
+    rect: (f32, f32, f32, f32),
+    border: Option<(Color, f32)>,
+    targets: Option<InsetTargets>,
+    uniforms: wgpu::Buffer,
+}
+
+impl InsetView {
+    /// Creates an inset showing `camera`'s view in `rect`: `(x, y, width, height)`
+    /// as fractions of the window size, from its top-left corner.
+    pub fn new<C: Camera3d + 'static>(rect: (f32, f32, f32, f32), camera: C) -> InsetView {
+        let ctxt = Context::get();
+        let uniforms = ctxt.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("inset_view_uniforms"),
+            size: std::mem::size_of::<InsetUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        InsetView {
+            camera: Box::new(camera),
+            rect,
+            border: None,
+            targets: None,
+            uniforms,
+        }
+    }
+
+    /// The inset rectangle `(x, y, width, height)`, as fractions of the window
+    /// size.
+    pub fn rect(&self) -> (f32, f32, f32, f32) {
+        self.rect
+    }
+
+    /// Sets the inset rectangle `(x, y, width, height)`, as fractions of the
+    /// window size from its top-left corner.
+    pub fn set_rect(&mut self, rect: (f32, f32, f32, f32)) {
+        self.rect = rect;
+    }
+
+    /// The border color and width (in pixels), if any.
+    pub fn border(&self) -> Option<(Color, f32)> {
+        self.border
+    }
+
+    /// Sets a border of `width` pixels drawn inside the inset rectangle, or
+    /// removes it with `None`.
+    pub fn set_border(&mut self, border: Option<(Color, f32)>) {
+        self.border = border;
+    }
+
+    /// The inset camera, if it is of type `C`.
+    pub fn camera<C: Camera3d + 'static>(&self) -> Option<&C> {
+        self.camera.as_any().downcast_ref()
+    }
+
+    /// The inset camera, mutably, if it is of type `C`.
+    pub fn camera_mut<C: Camera3d + 'static>(&mut self) -> Option<&mut C> {
+        self.camera.as_any_mut().downcast_mut()
+    }
+
+    /// Replaces the inset camera.
+    pub fn set_camera<C: Camera3d + 'static>(&mut self, camera: C) {
+        self.camera = Box::new(camera);
+    }
+
+    /// The inset rectangle in pixels `(x, y, width, height)` for a `width × height`
+    /// window, clamped to the window and at least one pixel wide.
+    pub(crate) fn pixel_rect(&self, width: u32, height: u32) -> (u32, u32, u32, u32) {
+        let (x, y, w, h) = self.rect;
+        let px = ((x * width as f32).round().max(0.0) as u32).min(width.saturating_sub(1));
+        let py = ((y * height as f32).round().max(0.0) as u32).min(height.saturating_sub(1));
+        let pw = ((w * width as f32).round().max(1.0) as u32).min(width - px);
+        let ph = ((h * height as f32).round().max(1.0) as u32).min(height - py);
+        (px, py, pw.max(1), ph.max(1))
+    }
+
+    /// Resizes the offscreen targets to `width × height` if needed and returns the
+    /// camera together with them, for the window to render the scene into.
+    pub(crate) fn render_parts(
+        &mut self,
+        width: u32,
+        height: u32,
+    ) -> (&mut dyn Camera3d, &mut InsetTargets) {
+        let (w, h) = (width.max(1), height.max(1));
+        if !matches!(&self.targets, Some(t) if t.width == w && t.height == h) {
+            let oit = self.targets.take().and_then(|t| t.oit);
+            let mut targets = InsetTargets::new(w, h);
+            targets.oit = oit;
+            self.targets = Some(targets);
+        }
+        let targets = self.targets.as_mut().unwrap();
+        if let Some(oit) = targets.oit.as_mut() {
+            oit.resize(w, h);
+        }
+        (self.camera.as_camera_mut(), targets)
+    }
+}
+
+/// The set of inset views of a window, and the pass compositing them over the
+/// final image.
+pub struct InsetViews {
+    // Removed insets leave a `None` hole so the other indices stay valid.
+    slots: Vec<Option<InsetView>>,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+    sampler: wgpu::Sampler,
+    _tony_lut: wgpu::Texture,
+    tony_lut_view: wgpu::TextureView,
+    tony_sampler: wgpu::Sampler,
+}
+
+impl Default for InsetViews {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InsetViews {
+    /// Creates an empty set of inset views.
+    pub fn new() -> InsetViews {
+        let ctxt = Context::get();
+
+        let texture_entry = |binding, view_dimension| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let sampler_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+            count: None,
+        };
+        let bind_group_layout = ctxt.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("inset_view_bind_group_layout"),
+            entries: &[
+                texture_entry(0, wgpu::TextureViewDimension::D2),
+                sampler_entry(1),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Tony McMapface 3D LUT + its sampler (shared `tonemap_ops.wgsl`
+                // declares these at bindings 6 & 7).
+                texture_entry(6, wgpu::TextureViewDimension::D3),
+                sampler_entry(7),
+            ],
+        });
+
+        let pipeline_layout = ctxt.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("inset_view_pipeline_layout"),
+            bind_group_layouts: &[Some(&bind_group_layout)],
+            immediate_size: 0,
+        });
+
+        let wgsl = crate::builtin::compile_wesl(
+            &[
+                ("package::tonemap_ops", crate::builtin::TONEMAP_OPS_WESL),
+                (
+                    "package::inset_view",
+                    include_str!("../builtin/inset_view.wgsl"),
+                ),
+                ("package::common", crate::builtin::COMMON_WESL),
+            ],
+            "package::inset_view",
+            &[],
+        );
+        let shader = ctxt.create_shader_module(Some("inset_view_shader"), &wgsl);
+
+        let pipeline = ctxt.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("inset_view_pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: ctxt.surface_format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview_mask: None,
+            cache: None,
+        });
+
+        let sampler = ctxt.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("inset_view_sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let tony_lut = crate::post_processing::HdrPipeline::create_tony_lut(&ctxt);
+        let tony_lut_view = tony_lut.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(wgpu::TextureViewDimension::D3),
+            ..Default::default()
+        });
+        let tony_sampler = ctxt.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("inset_view_tony_sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::MipmapFilterMode::Nearest,
+            ..Default::default()
+        });
+
+        InsetViews {
+            slots: Vec::new(),
+            bind_group_layout,
+            pipeline,
+            sampler,
+            _tony_lut: tony_lut,
+            tony_lut_view,
+            tony_sampler,
+        }
+    }
+
+    /// Adds an inset view and returns its index.
+    pub fn add(&mut self, view: InsetView) -> usize {
+        match self.slots.iter().position(Option::is_none) {
+            Some(idx) => {
+                self.slots[idx] = Some(view);
+                idx
+            }
+            None => {
+                self.slots.push(Some(view));
+                self.slots.len() - 1
+            }
+        }
+    }
+
+    /// Removes the inset view at `idx`, returning it if it existed. The indices of
+    /// the other inset views are unaffected.
+    pub fn remove(&mut self, idx: usize) -> Option<InsetView> {
+        self.slots.get_mut(idx).and_then(Option::take)
+    }
+
+    /// The inset view at `idx`, if any.
+    pub fn get(&self, idx: usize) -> Option<&InsetView> {
+        self.slots.get(idx)?.as_ref()
+    }
+
+    /// The inset view at `idx`, mutably, if any.
+    pub fn get_mut(&mut self, idx: usize) -> Option<&mut InsetView> {
+        self.slots.get_mut(idx)?.as_mut()
+    }
+
+    /// Number of inset views.
+    pub fn len(&self) -> usize {
+        self.slots.iter().flatten().count()
+    }
+
+    /// Whether there is no inset view.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = &mut InsetView> {
+        self.slots.iter_mut().flatten()
+    }
+
+    /// Draws every rendered inset into its rectangle of `output_view` (the final
+    /// `width × height` LDR frame), with the exposure and tonemap operator of the
+    /// main image.
+    pub(crate) fn composite(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        output_view: &wgpu::TextureView,
+        width: u32,
+        height: u32,
+        hdr: &HdrSettings,
+        gpu: &mut GpuTimer,
+    ) {
+        let ctxt = Context::get();
+        let mut draws = Vec::new();
+        for view in self.slots.iter().flatten() {
+            let targets = match &view.targets {
+                Some(t) => t,
+                None => continue,
+            };
+            let (x, y, w, h) = view.pixel_rect(width, height);
+            let (border_color, border_width) = view
+                .border
+                .map(|(c, bw)| (c, bw.max(0.0)))
+                .unwrap_or((Color::new(0.0, 0.0, 0.0, 1.0), 0.0));
+            let uniforms = InsetUniforms {
+                size_border_exposure: [w as f32, h as f32, border_width, hdr.exposure],
+                border_color: [
+                    border_color.r,
+                    border_color.g,
+                    border_color.b,
+                    border_color.a,
+                ],
+                tonemap_op: hdr.tonemap.as_u32(),
+                _pad: [0; 3],
+            };
+            ctxt.write_buffer(&view.uniforms, 0, bytemuck::bytes_of(&uniforms));
+            let bind_group = ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("inset_view_bind_group"),
+                layout: &self.bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&targets.color_view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: view.uniforms.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 6,
+                        resource: wgpu::BindingResource::TextureView(&self.tony_lut_view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 7,
+                        resource: wgpu::BindingResource::Sampler(&self.tony_sampler),
+                    },
+                ],
+            });
+            draws.push(((x, y, w, h), bind_group));
+        }
+        if draws.is_empty() {
+            return;
+        }
+
+        let timestamp_writes = gpu.render_scope("inset_views");
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("inset_view_pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: output_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+                depth_slice: None,
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes,
+            occlusion_query_set: None,
+            multiview_mask: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        for ((x, y, w, h), bind_group) in &draws {
+            pass.set_viewport(*x as f32, *y as f32, *w as f32, *h as f32, 0.0, 1.0);
+            pass.set_scissor_rect(*x, *y, *w, *h);
+            pass.set_bind_group(0, bind_group, &[]);
+            pass.draw(0..4, 0..1);
+        }
+    }
+}
@@ -1,14 +1,18 @@
 //! Structures responsible for rendering elements other than kiss3d's meshes.
 
+pub use self::decal::{Decal, Decals};
 pub use self::dof::{DepthOfFieldMode, Dof, DofSettings};
 #[cfg(feature = "egui")]
 pub use self::egui_renderer::EguiRenderer;
 pub use self::ibl::EnvironmentMap;
+pub use self::inset_view::{InsetView, InsetViews};
+pub use self::motion_blur::{MotionBlur, MotionBlurSettings};
 pub use self::point_renderer2d::PointRenderer2d;
 pub use self::point_renderer3d::PointRenderer3d;
 pub use self::polyline_renderer2d::{Polyline2d, PolylineRenderer2d};
 pub use self::polyline_renderer3d::{Polyline3d, PolylineRenderer3d};
 pub use self::raytracer::{RayBackend, RayTracer, RayTracerPreset};
+pub use self::reference_grid::{GridConfig, ReferenceGrid};
 pub use self::reflection_probe::{
     CubeFaceCamera, ProbeCapture, ReflectionProbe, ReflectionProbes, MAX_PROBES,
 };
@@ -21,15 +25,19 @@ pub use self::ssr::{Ssr, SsrMaterial, SsrSettings};
 pub use self::timings::RenderTimings;
 pub use self::transmission::{Transmission, TransmissionBlurQuality, TransmissionSettings};
 
+mod decal;
 mod dof;
 #[cfg(feature = "egui")]
 mod egui_renderer;
 mod ibl;
+mod inset_view;
+mod motion_blur;
 pub mod point_renderer2d;
 pub mod point_renderer3d;
 pub mod polyline_renderer2d;
 pub mod polyline_renderer3d;
 pub mod raytracer;
+mod reference_grid;
 pub mod reflection_probe;
 pub mod reflector;
 mod renderer;
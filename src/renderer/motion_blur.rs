@@ -0,0 +1,371 @@
+//! Motion blur for the rasterizer.
+//!
+//! Runs after the opaque pass + MSAA resolve and before DoF. The scene is
+//! rendered a second time into a velocity G-buffer (the velocity AOV, from each
+//! object's previous-frame transform and the previous camera), then the resolved
+//! HDR scene is averaged along each pixel's velocity and written back before
+//! tonemapping. Meant mostly for recordings, where fast simulations captured at
+//! a low frame rate otherwise stutter.
+
+use crate::builtin::{AovKind, AovRenderer, VELOCITY_AOV_FORMAT};
+use crate::camera::Camera3d;
+use crate::context::Context;
+use crate::scene::SceneNode3d;
+use bytemuck::{Pod, Zeroable};
+
+/// Tunable motion blur parameters.
+#[derive(Copy, Clone, Debug)]
+pub struct MotionBlurSettings {
+    /// Fraction of the frame interval the virtual shutter stays open: `0.5`
+    /// (the default) matches a film camera's 180° shutter, `1.0` blurs over
+    /// the whole motion since the previous frame.
+    pub shutter: f32,
+    /// Number of samples along the motion of each pixel. More samples give
+    /// smoother trails at a higher cost.
+    pub num_samples: u32,
+    /// Maximum blur length, in pixels.
+    pub max_blur: f32,
+}
+
+impl Default for MotionBlurSettings {
+    fn default() -> Self {
+        MotionBlurSettings {
+            shutter: 0.5,
+            num_samples: 16,
+            max_blur: 48.0,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct MotionBlurUniforms {
+    params0: [f32; 4],
+    params1: [f32; 4],
+}
+
+/// Owns the velocity G-buffer, pipelines and uniform of the motion blur of one
+/// window.
+pub struct MotionBlur {
+    settings: MotionBlurSettings,
+    width: u32,
+    height: u32,
+
+    /// Renders the velocity G-buffer, and keeps the previous-frame transforms.
+    velocity_renderer: AovRenderer,
+    _velocity_texture: wgpu::Texture,
+    velocity_view: wgpu::TextureView,
+    _depth_texture: wgpu::Texture,
+    depth_view: wgpu::TextureView,
+    // The blurred scene, copied back over the scene by the copy pass.
+    _blurred_texture: wgpu::Texture,
+    blurred_view: wgpu::TextureView,
+
+    sampler: wgpu::Sampler,
+    layout: wgpu::BindGroupLayout,
+    blur_pipeline: wgpu::RenderPipeline,
+    copy_pipeline: wgpu::RenderPipeline,
+
+    uniform: wgpu::Buffer,
+}
+
+impl MotionBlur {
+    /// Creates the motion blur resources for the given size.
+    pub fn new(width: u32, height: u32) -> MotionBlur {
+        let ctxt = Context::get();
+        let w = width.max(1);
+        let h = height.max(1);
+
+        let sampler = ctxt.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("motion_blur_sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let shader = ctxt.create_shader_module(
+            Some("motion_blur"),
+            &crate::builtin::compile_shader_with_common(
+                "package::motion_blur",
+                include_str!("../builtin/motion_blur.wgsl"),
+            ),
+        );
+
+        let texture_entry = |binding: u32, filterable: bool| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let layout = ctxt.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("motion_blur_layout"),
+            entries: &[
+                texture_entry(0, true),
+                texture_entry(1, false),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let blur_pipeline = make_fullscreen_pipeline("motion_blur", &shader, "fs_blur", &layout);
+        let copy_pipeline =
+            make_fullscreen_pipeline("motion_blur_copy", &shader, "fs_copy", &layout);
+
+        let uniform = ctxt.create_buffer_simple(
+            Some("motion_blur_uniform"),
+            std::mem::size_of::<MotionBlurUniforms>() as u64,
+            wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        );
+
+        let [(velocity_texture, velocity_view), (depth_texture, depth_view), (blurred_texture, blurred_view)] =
+            Self::make_targets(w, h);
+
+        MotionBlur {
+            settings: MotionBlurSettings::default(),
+            width: w,
+            height: h,
+            velocity_renderer: AovRenderer::new(),
+            _velocity_texture: velocity_texture,
+            velocity_view,
+            _depth_texture: depth_texture,
+            depth_view,
+            _blurred_texture: blurred_texture,
+            blurred_view,
+            sampler,
+            layout,
+            blur_pipeline,
+            copy_pipeline,
+            uniform,
+        }
+    }
+
+    /// The velocity, depth and blurred-scene targets.
+    fn make_targets(w: u32, h: u32) -> [(wgpu::Texture, wgpu::TextureView); 3] {
+        let sampled = wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING;
+        [
+            make_target("motion_blur_velocity", w, h, VELOCITY_AOV_FORMAT, sampled),
+            make_target(
+                "motion_blur_depth",
+                w,
+                h,
+                Context::depth_format(),
+                wgpu::TextureUsages::RENDER_ATTACHMENT,
+            ),
+            make_target(
+                "motion_blur_blurred",
+                w,
+                h,
+                crate::post_processing::HDR_FORMAT,
+                sampled,
+            ),
+        ]
+    }
+
+    /// Resizes the motion blur targets if needed.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        let w = width.max(1);
+        let h = height.max(1);
+        if self.width == w && self.height == h {
+            return;
+        }
+        let [velocity, depth, blurred] = Self::make_targets(w, h);
+        (self._velocity_texture, self.velocity_view) = velocity;
+        (self._depth_texture, self.depth_view) = depth;
+        (self._blurred_texture, self.blurred_view) = blurred;
+        self.width = w;
+        self.height = h;
+    }
+
+    /// Mutable access to the motion blur settings.
+    pub fn settings_mut(&mut self) -> &mut MotionBlurSettings {
+        &mut self.settings
+    }
+
+    /// The current motion blur settings.
+    pub fn settings(&self) -> &MotionBlurSettings {
+        &self.settings
+    }
+
+    /// Runs motion blur: renders the velocity of `scene` as seen by `camera`
+    /// (whose transforms must be up to date), then blurs `scene_view` along it.
+    pub(crate) fn compute(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        scene: &mut SceneNode3d,
+        camera: &mut dyn Camera3d,
+        scene_view: &wgpu::TextureView,
+        gpu: &mut crate::renderer::timings::GpuTimer,
+    ) {
+        let ctxt = Context::get();
+        let s = &self.settings;
+
+        ctxt.write_buffer(
+            &self.uniform,
+            0,
+            bytemuck::bytes_of(&MotionBlurUniforms {
+                params0: [s.shutter.max(0.0), s.num_samples as f32, s.max_blur, 0.0],
+                params1: [
+                    self.width as f32,
+                    self.height as f32,
+                    1.0 / self.width as f32,
+                    1.0 / self.height as f32,
+                ],
+            }),
+        );
+
+        // 1. Velocity G-buffer.
+        self.velocity_renderer.render(
+            AovKind::Velocity,
+            scene,
+            camera,
+            encoder,
+            &self.velocity_view,
+            &self.depth_view,
+        );
+
+        // 2. Blur the scene into the intermediate target, then 3. copy it back.
+        let passes = [
+            (scene_view, &self.blurred_view, &self.blur_pipeline),
+            (&self.blurred_view, scene_view, &self.copy_pipeline),
+        ];
+        for (source, target, pipeline) in passes {
+            let bg = ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("motion_blur_bg"),
+                layout: &self.layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(source),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::TextureView(&self.velocity_view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 3,
+                        resource: self.uniform.as_entire_binding(),
+                    },
+                ],
+            });
+            let timestamps = gpu.render_scope("motion_blur");
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("motion_blur_pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                    depth_slice: None,
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: timestamps,
+                occlusion_query_set: None,
+                multiview_mask: None,
+            });
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &bg, &[]);
+            pass.draw(0..3, 0..1);
+        }
+    }
+}
+
+fn make_target(
+    label: &str,
+    width: u32,
+    height: u32,
+    format: wgpu::TextureFormat,
+    usage: wgpu::TextureUsages,
+) -> (wgpu::Texture, wgpu::TextureView) {
+    let texture = Context::get().create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    (texture, view)
+}
+
+fn make_fullscreen_pipeline(
+    label: &str,
+    shader: &wgpu::ShaderModule,
+    fs_entry: &str,
+    layout: &wgpu::BindGroupLayout,
+) -> wgpu::RenderPipeline {
+    let ctxt = Context::get();
+    let pl = ctxt.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some(label),
+        bind_group_layouts: &[Some(layout)],
+        immediate_size: 0,
+    });
+    ctxt.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(label),
+        layout: Some(&pl),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs_main"),
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some(fs_entry),
+            targets: &[Some(wgpu::ColorTargetState {
+                format: crate::post_processing::HDR_FORMAT,
+                blend: None,
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview_mask: None,
+        cache: None,
+    })
+}
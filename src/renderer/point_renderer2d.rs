@@ -37,6 +37,8 @@ pub struct PointRenderer2d {
     point_storage_buffer: wgpu::Buffer,
     point_capacity: usize,
     points: Vec<PointData2D>,
+    /// Multiplier applied to the sizes of drawn items (the window's UI scale).
+    size_scale: f32,
 }
 
 impl Default for PointRenderer2d {
@@ -158,6 +160,7 @@ impl PointRenderer2d {
             point_storage_buffer,
             point_capacity,
             points: Vec::new(),
+            size_scale: 1.0,
         }
     }
 
@@ -166,12 +169,30 @@ impl PointRenderer2d {
         !self.points.is_empty()
     }
 
+    /// Drops the pending draws without rendering them, e.g. when the frame is
+    /// skipped.
+    pub fn discard(&mut self) {
+        self.points.clear();
+    }
+
+    /// Multiplier applied to the point sizes of everything drawn from now on
+    /// (`1.0` by default). The window sets it to its UI scale (see
+    /// [`Window::set_ui_scale`](crate::window::Window::set_ui_scale)).
+    pub fn set_size_scale(&mut self, scale: f32) {
+        self.size_scale = scale;
+    }
+
+    /// The multiplier applied to the point sizes of drawn items.
+    pub fn size_scale(&self) -> f32 {
+        self.size_scale
+    }
+
     /// Adds a 2D point to be drawn during the next frame. Points are not persistent between frames.
     /// This method must be called for each point to draw, and at each update loop iteration.
     pub fn draw_point(&mut self, pt: Vec2, color: Color, size: f32) {
         self.points.push(PointData2D {
             position: pt.into(),
-            size,
+            size: size * self.size_scale,
             _pad: 0.0,
             color: [color.r, color.g, color.b, color.a],
         });
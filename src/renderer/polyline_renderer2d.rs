@@ -5,9 +5,10 @@
 use crate::camera::Camera2d;
 use crate::color::Color;
 use crate::context::Context;
+use crate::renderer::polyline_renderer3d::lerp_color;
 use crate::resource::{multisample_state, PipelineCache, RenderContext2dEncoder};
 use bytemuck::{Pod, Zeroable};
-use glamx::{Mat3, Pose2, Vec2};
+use glamx::{Mat3, Pose2, Vec2, Vec3};
 
 /// A 2D line segment with endpoints and per-segment material properties.
 #[repr(C)]
@@ -15,10 +16,17 @@ use glamx::{Mat3, Pose2, Vec2};
 struct LineSegment2D {
     point_a: [f32; 2],
     width: f32,
-    _pad1: f32,
+    /// Width at `point_b` (`width` is the width at `point_a`).
+    width_b: f32,
     point_b: [f32; 2],
-    _pad2: [f32; 2],
+    /// Distances along the polyline at `point_a` and `point_b`, for the dashes.
+    distance: [f32; 2],
     color: [f32; 4],
+    /// Color at `point_b` (`color` is the color at `point_a`).
+    color_b: [f32; 4],
+    /// Dash and gap lengths (solid when either is zero).
+    dash: [f32; 2],
+    _pad: [f32; 2],
 }
 
 /// View uniforms for 2D polyline rendering.
@@ -38,8 +46,18 @@ pub struct Polyline2d {
     pub vertices: Vec<Vec2>,
     /// The color of the polyline (RGBA, 0-1).
     pub color: Color,
+    /// Per-vertex colors, interpolated along each segment. Vertices without a
+    /// color here use [`color`](Self::color).
+    pub colors: Vec<Color>,
     /// The width of the line in pixels.
     pub width: f32,
+    /// Whether the last vertex is connected back to the first one.
+    pub closed: bool,
+    /// Dash and gap lengths, in the polyline's units, or `None` for a solid line.
+    pub dashes: Option<(f32, f32)>,
+    /// Lengths of the arrowheads at the first and last vertex, in the polyline's
+    /// units (`0.0` for none). Ignored for closed polylines.
+    pub arrows: (f32, f32),
     /// The model transform for this polyline.
     pub transform: Pose2,
 }
@@ -49,7 +67,11 @@ impl Default for Polyline2d {
         Self {
             vertices: Vec::new(),
             color: crate::color::WHITE,
+            colors: Vec::new(),
             width: 2.0,
+            closed: false,
+            dashes: None,
+            arrows: (0.0, 0.0),
             transform: Pose2::IDENTITY,
         }
     }
@@ -70,12 +92,40 @@ impl Polyline2d {
         self
     }
 
+    /// Sets one color per vertex, for a gradient along the polyline.
+    pub fn with_colors(mut self, colors: Vec<Color>) -> Self {
+        self.colors = colors;
+        self
+    }
+
     /// Sets the width of the line in pixels.
     pub fn with_width(mut self, width: f32) -> Self {
         self.width = width;
         self
     }
 
+    /// Connects (or not) the last vertex back to the first one.
+    pub fn with_closed(mut self, closed: bool) -> Self {
+        self.closed = closed;
+        self
+    }
+
+    /// Draws the polyline as dashes of length `dash` separated by gaps of length
+    /// `gap`, in the polyline's units, running continuously across vertices.
+    /// Dashes about as long as the line is wide give a dotted line.
+    pub fn with_dashes(mut self, dash: f32, gap: f32) -> Self {
+        self.dashes = Some((dash, gap));
+        self
+    }
+
+    /// Adds arrowheads of the given lengths (in the polyline's units, `0.0` for
+    /// none) at the first and last vertex. Arrowheads are four times as wide as
+    /// the line at their base.
+    pub fn with_arrows(mut self, start: f32, end: f32) -> Self {
+        self.arrows = (start, end);
+        self
+    }
+
     /// Sets the model transform for this polyline.
     pub fn with_transform(mut self, transform: Pose2) -> Self {
         self.transform = transform;
@@ -92,6 +142,18 @@ pub struct PolylineRenderer2d {
     segment_capacity: usize,
     /// Pre-built segments ready for rendering
     segments: Vec<LineSegment2D>,
+    /// Index of the draw call (polyline or line) each segment comes from.
+    segment_items: Vec<usize>,
+    /// Number of draw calls since the last render.
+    num_items: usize,
+    /// Segments of the last rendered frame, kept for [`Self::pick`].
+    last_segments: Vec<LineSegment2D>,
+    last_segment_items: Vec<usize>,
+    /// Projection-view matrix and viewport size of the last rendered frame.
+    last_view_proj: Mat3,
+    last_viewport: Vec2,
+    /// Multiplier applied to the sizes of drawn items (the window's UI scale).
+    size_scale: f32,
 }
 
 impl Default for PolylineRenderer2d {
@@ -157,18 +219,42 @@ impl PolylineRenderer2d {
                         shader_location: 1,
                         format: wgpu::VertexFormat::Float32,
                     },
-                    // point_b (vec2) - offset 16 (after point_a[2] + width + _pad1)
+                    // point_b (vec2) - offset 16 (after point_a[2] + width + width_b)
                     wgpu::VertexAttribute {
                         offset: 16,
                         shader_location: 2,
                         format: wgpu::VertexFormat::Float32x2,
                     },
-                    // color (vec4) - offset 32 (after point_b[2] + _pad2[2])
+                    // color (vec4) - offset 32 (after point_b[2] + distance[2])
                     wgpu::VertexAttribute {
                         offset: 32,
                         shader_location: 3,
                         format: wgpu::VertexFormat::Float32x4,
                     },
+                    // width_b (f32)
+                    wgpu::VertexAttribute {
+                        offset: 12,
+                        shader_location: 4,
+                        format: wgpu::VertexFormat::Float32,
+                    },
+                    // distance (vec2)
+                    wgpu::VertexAttribute {
+                        offset: 24,
+                        shader_location: 5,
+                        format: wgpu::VertexFormat::Float32x2,
+                    },
+                    // color_b (vec4)
+                    wgpu::VertexAttribute {
+                        offset: 48,
+                        shader_location: 6,
+                        format: wgpu::VertexFormat::Float32x4,
+                    },
+                    // dash (vec2)
+                    wgpu::VertexAttribute {
+                        offset: 64,
+                        shader_location: 7,
+                        format: wgpu::VertexFormat::Float32x2,
+                    },
                 ],
             };
 
@@ -231,6 +317,13 @@ impl PolylineRenderer2d {
             segment_buffer,
             segment_capacity,
             segments: Vec::new(),
+            segment_items: Vec::new(),
+            num_items: 0,
+            last_segments: Vec::new(),
+            last_segment_items: Vec::new(),
+            last_view_proj: Mat3::IDENTITY,
+            last_viewport: Vec2::ONE,
+            size_scale: 1.0,
         }
     }
 
@@ -239,60 +332,190 @@ impl PolylineRenderer2d {
         !self.segments.is_empty()
     }
 
+    /// Drops the pending draws without rendering them, e.g. when the frame is
+    /// skipped.
+    pub fn discard(&mut self) {
+        self.segments.clear();
+    }
+
+    /// Multiplier applied to the line widths of everything drawn from now on
+    /// (`1.0` by default). The window sets it to its UI scale (see
+    /// [`Window::set_ui_scale`](crate::window::Window::set_ui_scale)).
+    pub fn set_size_scale(&mut self, scale: f32) {
+        self.size_scale = scale;
+    }
+
+    /// The multiplier applied to the line widths of drawn items.
+    pub fn size_scale(&self) -> f32 {
+        self.size_scale
+    }
+
     /// Adds a 2D polyline to be drawn during the next frame.
     /// Takes a reference to avoid allocations - segments are built immediately.
     /// Polylines are not persistent between frames.
     pub fn draw_polyline(&mut self, polyline: &Polyline2d) {
+        let item = self.num_items;
+        self.num_items += 1;
         if polyline.vertices.len() < 2 {
             return;
         }
 
-        let transform = polyline.transform;
-        let color = [
-            polyline.color.r,
-            polyline.color.g,
-            polyline.color.b,
-            polyline.color.a,
-        ];
-        let width = polyline.width;
-
-        for pair in polyline.vertices.windows(2) {
-            let a = transform * pair[0];
-            let b = transform * pair[1];
+        let width = polyline.width * self.size_scale;
+        let dash = match polyline.dashes {
+            Some((dash, gap)) => [dash.max(0.0), gap.max(0.0)],
+            None => [0.0; 2],
+        };
+
+        // Vertices with their color and distance along the polyline.
+        let mut path: Vec<(Vec2, [f32; 4], f32)> = Vec::with_capacity(polyline.vertices.len() + 1);
+        for (i, v) in polyline.vertices.iter().enumerate() {
+            let c = polyline.colors.get(i).copied().unwrap_or(polyline.color);
+            let p = polyline.transform * *v;
+            let dist = path
+                .last()
+                .map(|&(prev, _, d)| d + prev.distance(p))
+                .unwrap_or(0.0);
+            path.push((p, [c.r, c.g, c.b, c.a], dist));
+        }
+        if polyline.closed {
+            let (first, color, _) = path[0];
+            let (last, _, dist) = path[path.len() - 1];
+            path.push((first, color, dist + last.distance(first)));
+        }
+
+        // Arrowheads shorten the end segments and are drawn as segments tapering
+        // from four times the line width to a point.
+        let mut arrows = Vec::new();
+        if !polyline.closed {
+            let n = path.len();
+            for (len, tip, base) in [(polyline.arrows.0, 0, 1), (polyline.arrows.1, n - 1, n - 2)] {
+                let (tip_pt, tip_color, tip_dist) = path[tip];
+                let (base_pt, base_color, _) = path[base];
+                let seg_len = tip_pt.distance(base_pt);
+                if len <= 0.0 || seg_len == 0.0 {
+                    continue;
+                }
+                let t = (len / seg_len).min(1.0);
+                let start = tip_pt.lerp(base_pt, t);
+                let start_color = lerp_color(tip_color, base_color, t);
+                let trimmed = if tip == 0 {
+                    tip_dist + len.min(seg_len)
+                } else {
+                    tip_dist - len.min(seg_len)
+                };
+                path[tip] = (start, start_color, trimmed);
+                arrows.push((start, tip_pt, start_color, tip_color));
+            }
+        }
+
+        for pair in path.windows(2) {
+            let (a, color, dist_a) = pair[0];
+            let (b, color_b, dist_b) = pair[1];
             self.segments.push(LineSegment2D {
                 point_a: a.into(),
                 width,
-                _pad1: 0.0,
+                width_b: width,
+                point_b: b.into(),
+                distance: [dist_a, dist_b],
+                color,
+                color_b,
+                dash,
+                _pad: [0.0; 2],
+            });
+            self.segment_items.push(item);
+        }
+        for (a, b, color, color_b) in arrows {
+            self.segments.push(LineSegment2D {
+                point_a: a.into(),
+                width: width * 4.0,
+                width_b: 0.0,
                 point_b: b.into(),
-                _pad2: [0.0; 2],
+                distance: [0.0; 2],
                 color,
+                color_b,
+                dash: [0.0; 2],
+                _pad: [0.0; 2],
             });
+            self.segment_items.push(item);
         }
     }
 
     /// Draws a simple 2D line segment with the given width.
     pub fn draw_line(&mut self, a: Vec2, b: Vec2, color: Color, width: f32) {
+        let width = width * self.size_scale;
+        let color = [color.r, color.g, color.b, color.a];
         self.segments.push(LineSegment2D {
             point_a: a.into(),
             width,
-            _pad1: 0.0,
+            width_b: width,
             point_b: b.into(),
-            _pad2: [0.0; 2],
-            color: [color.r, color.g, color.b, color.a],
+            distance: [0.0; 2],
+            color,
+            color_b: color,
+            dash: [0.0; 2],
+            _pad: [0.0; 2],
         });
+        self.segment_items.push(self.num_items);
+        self.num_items += 1;
+    }
+
+    /// The index of the topmost line or polyline of the last rendered frame
+    /// passing within `tolerance` pixels of `point`, if any.
+    ///
+    /// `point` is in pixels from the top-left corner of the window, and indices
+    /// count the [`Self::draw_polyline`] and [`Self::draw_line`] calls of that
+    /// frame, in call order.
+    pub fn pick(&self, point: Vec2, tolerance: f32) -> Option<usize> {
+        let to_pixels = |p: [f32; 2]| {
+            let ndc = self.last_view_proj * Vec3::new(p[0], p[1], 1.0);
+            let ndc = Vec2::new(ndc.x, ndc.y) / ndc.z;
+            Vec2::new(
+                (ndc.x + 1.0) * 0.5 * self.last_viewport.x,
+                (1.0 - ndc.y) * 0.5 * self.last_viewport.y,
+            )
+        };
+
+        self.last_segments
+            .iter()
+            .zip(self.last_segment_items.iter())
+            .rev()
+            .find(|(segment, _)| {
+                let a = to_pixels(segment.point_a);
+                let b = to_pixels(segment.point_b);
+                let ab = b - a;
+                let t = if ab.length_squared() > 0.0 {
+                    ((point - a).dot(ab) / ab.length_squared()).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                point.distance(a + ab * t) <= segment.width.max(segment.width_b) * 0.5 + tolerance
+            })
+            .map(|(_, item)| *item)
     }
 
     /// Renders all 2D polylines in a single draw call.
     pub fn render(&mut self, camera: &mut dyn Camera2d, context: &mut RenderContext2dEncoder) {
+        // Get camera matrices
+        let (view, proj) = camera.view_transform_pair();
+
+        // Keep what this frame draws for `pick`.
+        self.last_view_proj = proj * view;
+        self.last_viewport = Vec2::new(
+            context.viewport_width as f32,
+            context.viewport_height as f32,
+        );
+        self.last_segments.clear();
+        self.last_segments.extend_from_slice(&self.segments);
+        std::mem::swap(&mut self.segment_items, &mut self.last_segment_items);
+        self.segment_items.clear();
+        self.num_items = 0;
+
         if self.segments.is_empty() {
             return;
         }
 
         let ctxt = Context::get();
 
-        // Get camera matrices
-        let (view, proj) = camera.view_transform_pair();
-
         // Update view uniforms
         let view_uniforms = ViewUniforms2D {
             view: Self::mat3_to_padded(&view),
@@ -22,7 +22,21 @@ struct LineSegment {
     depth_bias: f32,
     color: [f32; 4],
     perspective: u32,
-    _padding: [u32; 3],
+    /// Width at `point_b` (`width` is the width at `point_a`).
+    width_b: f32,
+    /// Dash and gap lengths (solid when either is zero).
+    dash: [f32; 2],
+    /// Color at `point_b` (`color` is the color at `point_a`).
+    color_b: [f32; 4],
+    /// Distances along the polyline at `point_a` and `point_b`, for the dashes.
+    distance: [f32; 2],
+    /// The polyline vertex before `point_a` (`w = 1`), or zero if the segment
+    /// starts the polyline.
+    prev: [f32; 4],
+    /// The polyline vertex after `point_b` (`w = 1`), or zero if the segment ends
+    /// the polyline.
+    next: [f32; 4],
+    _padding: [u32; 2],
 }
 
 /// View uniforms for polyline rendering.
@@ -41,8 +55,18 @@ pub struct Polyline3d {
     pub vertices: Vec<Vec3>,
     /// The color of the polyline (RGBA, 0-1).
     pub color: Color,
+    /// Per-vertex colors, interpolated along each segment. Vertices without a
+    /// color here use [`color`](Self::color).
+    pub colors: Vec<Color>,
     /// The width of the line in pixels.
     pub width: f32,
+    /// Whether the last vertex is connected back to the first one.
+    pub closed: bool,
+    /// Dash and gap lengths, in the polyline's units, or `None` for a solid line.
+    pub dashes: Option<(f32, f32)>,
+    /// Lengths of the arrowheads at the first and last vertex, in the polyline's
+    /// units (`0.0` for none). Ignored for closed polylines.
+    pub arrows: (f32, f32),
     /// Whether to use perspective-correct line width.
     pub perspective: bool,
     /// Depth bias for z-fighting prevention. Range [-1, 1].
@@ -57,7 +81,11 @@ impl Default for Polyline3d {
         Self {
             vertices: Vec::new(),
             color: crate::color::WHITE,
+            colors: Vec::new(),
             width: 2.0,
+            closed: false,
+            dashes: None,
+            arrows: (0.0, 0.0),
             perspective: false,
             depth_bias: 0.0,
             transform: Pose3::IDENTITY,
@@ -80,12 +108,40 @@ impl Polyline3d {
         self
     }
 
+    /// Sets one color per vertex, for a gradient along the polyline.
+    pub fn with_colors(mut self, colors: Vec<Color>) -> Self {
+        self.colors = colors;
+        self
+    }
+
     /// Sets the width of the line in pixels.
     pub fn with_width(mut self, width: f32) -> Self {
         self.width = width;
         self
     }
 
+    /// Connects (or not) the last vertex back to the first one.
+    pub fn with_closed(mut self, closed: bool) -> Self {
+        self.closed = closed;
+        self
+    }
+
+    /// Draws the polyline as dashes of length `dash` separated by gaps of length
+    /// `gap`, in the polyline's units, running continuously across vertices.
+    /// Dashes about as long as the line is wide give a dotted line.
+    pub fn with_dashes(mut self, dash: f32, gap: f32) -> Self {
+        self.dashes = Some((dash, gap));
+        self
+    }
+
+    /// Adds arrowheads of the given lengths (in the polyline's units, `0.0` for
+    /// none) at the first and last vertex. Arrowheads are four times as wide as
+    /// the line at their base.
+    pub fn with_arrows(mut self, start: f32, end: f32) -> Self {
+        self.arrows = (start, end);
+        self
+    }
+
     /// Enables or disables perspective-correct line width.
     pub fn with_perspective(mut self, perspective: bool) -> Self {
         self.perspective = perspective;
@@ -108,12 +164,16 @@ impl Polyline3d {
 /// Structure which manages the display of polylines with configurable width.
 pub struct PolylineRenderer3d {
     pipeline: PipelineCache,
+    /// Draws the antialiased fringe of the lines, after `pipeline` drew their core.
+    fringe_pipeline: PipelineCache,
     view_bind_group_layout: wgpu::BindGroupLayout,
     view_uniform_buffer: wgpu::Buffer,
     segment_buffer: wgpu::Buffer,
     segment_capacity: usize,
     /// Pre-built segments ready for rendering (avoids reallocations)
     segments: Vec<LineSegment>,
+    /// Multiplier applied to the sizes of drawn items (the window's UI scale).
+    size_scale: f32,
 }
 
 impl Default for PolylineRenderer3d {
@@ -155,9 +215,11 @@ impl PolylineRenderer3d {
             include_str!("../builtin/polyline3d.wgsl"),
         );
 
-        // Pipeline is built lazily per MSAA sample count (see `PipelineCache`):
-        // polylines render into the (optionally multisampled) HDR film.
-        let pipeline = PipelineCache::new(move |sample_count| {
+        // Pipelines are built lazily per MSAA sample count (see `PipelineCache`):
+        // polylines render into the (optionally multisampled) HDR film. The fully
+        // covered core of the lines writes depth; their antialiased fringe is
+        // blended over it without writing depth, so it doesn't hide what's behind.
+        let build = std::rc::Rc::new(move |fringe: bool, sample_count: u32| {
             let ctxt = Context::get();
             // Vertex buffer layout - each instance is a line segment with material data
             let vertex_buffer_layout = wgpu::VertexBufferLayout {
@@ -200,11 +262,51 @@ impl PolylineRenderer3d {
                         shader_location: 5,
                         format: wgpu::VertexFormat::Uint32,
                     },
+                    // width_b (f32)
+                    wgpu::VertexAttribute {
+                        offset: 52,
+                        shader_location: 6,
+                        format: wgpu::VertexFormat::Float32,
+                    },
+                    // dash (vec2)
+                    wgpu::VertexAttribute {
+                        offset: 56,
+                        shader_location: 7,
+                        format: wgpu::VertexFormat::Float32x2,
+                    },
+                    // color_b (vec4)
+                    wgpu::VertexAttribute {
+                        offset: 64,
+                        shader_location: 8,
+                        format: wgpu::VertexFormat::Float32x4,
+                    },
+                    // distance (vec2)
+                    wgpu::VertexAttribute {
+                        offset: 80,
+                        shader_location: 9,
+                        format: wgpu::VertexFormat::Float32x2,
+                    },
+                    // prev (vec4)
+                    wgpu::VertexAttribute {
+                        offset: 88,
+                        shader_location: 10,
+                        format: wgpu::VertexFormat::Float32x4,
+                    },
+                    // next (vec4)
+                    wgpu::VertexAttribute {
+                        offset: 104,
+                        shader_location: 11,
+                        format: wgpu::VertexFormat::Float32x4,
+                    },
                 ],
             };
 
             ctxt.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-                label: Some("polyline_pipeline"),
+                label: Some(if fringe {
+                    "polyline_fringe_pipeline"
+                } else {
+                    "polyline_pipeline"
+                }),
                 layout: Some(&pipeline_layout),
                 vertex: wgpu::VertexState {
                     module: &shader,
@@ -214,7 +316,7 @@ impl PolylineRenderer3d {
                 },
                 fragment: Some(wgpu::FragmentState {
                     module: &shader,
-                    entry_point: Some("fs_main"),
+                    entry_point: Some(if fringe { "fs_fringe" } else { "fs_core" }),
                     targets: &[Some(wgpu::ColorTargetState {
                         format: Context::render_format(), // HDR rasterization target (tonemapped to LDR in the resolve pass)
                         blend: Some(wgpu::BlendState::ALPHA_BLENDING),
@@ -233,7 +335,7 @@ impl PolylineRenderer3d {
                 },
                 depth_stencil: Some(wgpu::DepthStencilState {
                     format: Context::depth_format(),
-                    depth_write_enabled: Some(true),
+                    depth_write_enabled: Some(!fringe),
                     depth_compare: Some(wgpu::CompareFunction::LessEqual),
                     stencil: wgpu::StencilState::default(),
                     bias: wgpu::DepthBiasState::default(),
@@ -243,6 +345,11 @@ impl PolylineRenderer3d {
                 cache: None,
             })
         });
+        let pipeline = PipelineCache::new({
+            let build = build.clone();
+            move |sc| build(false, sc)
+        });
+        let fringe_pipeline = PipelineCache::new(move |sc| build(true, sc));
 
         // Create view uniform buffer
         let view_uniform_buffer = ctxt.create_buffer(&wgpu::BufferDescriptor {
@@ -263,11 +370,13 @@ impl PolylineRenderer3d {
 
         PolylineRenderer3d {
             pipeline,
+            fringe_pipeline,
             view_bind_group_layout,
             view_uniform_buffer,
             segment_buffer,
             segment_capacity,
             segments: Vec::new(),
+            size_scale: 1.0,
         }
     }
 
@@ -276,6 +385,26 @@ impl PolylineRenderer3d {
         !self.segments.is_empty()
     }
 
+    /// Drops the pending draws without rendering them, e.g. when the frame is
+    /// skipped.
+    pub fn discard(&mut self) {
+        self.segments.clear();
+    }
+
+    /// Multiplier applied to the pixel line widths (perspective lines are
+    /// unaffected) of everything drawn from now on
+    /// (`1.0` by default). The window sets it to its UI scale (see
+    /// [`Window::set_ui_scale`](crate::window::Window::set_ui_scale)).
+    pub fn set_size_scale(&mut self, scale: f32) {
+        self.size_scale = scale;
+    }
+
+    /// The multiplier applied to the pixel line widths (perspective lines are
+    /// unaffected) of drawn items.
+    pub fn size_scale(&self) -> f32 {
+        self.size_scale
+    }
+
     /// Adds a polyline to be drawn during the next frame.
     /// Takes a reference to avoid allocations - segments are built immediately.
     /// Polylines are not persistent between frames.
@@ -284,20 +413,81 @@ impl PolylineRenderer3d {
             return;
         }
 
-        let transform = polyline.transform;
-        let color = [
-            polyline.color.r,
-            polyline.color.g,
-            polyline.color.b,
-            polyline.color.a,
-        ];
-        let width = polyline.width;
+        let width = if polyline.perspective {
+            polyline.width
+        } else {
+            polyline.width * self.size_scale
+        };
         let depth_bias = polyline.depth_bias;
         let perspective = if polyline.perspective { 1 } else { 0 };
+        let dash = match polyline.dashes {
+            Some((dash, gap)) => [dash.max(0.0), gap.max(0.0)],
+            None => [0.0; 2],
+        };
+
+        // World-space vertices with their color and distance along the polyline.
+        let mut path: Vec<(Vec3, [f32; 4], f32)> = Vec::with_capacity(polyline.vertices.len() + 1);
+        for (i, v) in polyline.vertices.iter().enumerate() {
+            let c = polyline.colors.get(i).copied().unwrap_or(polyline.color);
+            let p = polyline.transform * *v;
+            let dist = path
+                .last()
+                .map(|&(prev, _, d)| d + prev.distance(p))
+                .unwrap_or(0.0);
+            path.push((p, [c.r, c.g, c.b, c.a], dist));
+        }
+        if polyline.closed {
+            let (first, color, _) = path[0];
+            let (last, _, dist) = path[path.len() - 1];
+            path.push((first, color, dist + last.distance(first)));
+        }
 
-        for pair in polyline.vertices.windows(2) {
-            let a = transform * pair[0];
-            let b = transform * pair[1];
+        // Arrowheads shorten the end segments and are drawn as segments tapering
+        // from four times the line width to a point.
+        let mut arrows = Vec::new();
+        if !polyline.closed {
+            let n = path.len();
+            for (len, tip, base) in [(polyline.arrows.0, 0, 1), (polyline.arrows.1, n - 1, n - 2)] {
+                let (tip_pt, tip_color, tip_dist) = path[tip];
+                let (base_pt, base_color, _) = path[base];
+                let seg_len = tip_pt.distance(base_pt);
+                if len <= 0.0 || seg_len == 0.0 {
+                    continue;
+                }
+                let t = (len / seg_len).min(1.0);
+                let start = tip_pt.lerp(base_pt, t);
+                let start_color = lerp_color(tip_color, base_color, t);
+                let trimmed = if tip == 0 {
+                    tip_dist + len.min(seg_len)
+                } else {
+                    tip_dist - len.min(seg_len)
+                };
+                path[tip] = (start, start_color, trimmed);
+                arrows.push((start, tip_pt, start_color, tip_color));
+            }
+        }
+
+        // Consecutive segments share the round join between them (see
+        // `polyline3d.wgsl`), which needs their neighboring vertices. Arrowheads
+        // are not joined.
+        let n = path.len();
+        let neighbor = |i: Option<usize>| match i {
+            Some(i) => path[i].0.extend(1.0).into(),
+            None => [0.0; 4],
+        };
+        for (i, pair) in path.windows(2).enumerate() {
+            let (a, color, dist_a) = pair[0];
+            let (b, color_b, dist_b) = pair[1];
+            let prev = match i {
+                0 if polyline.closed && n > 2 => Some(n - 2),
+                0 => None,
+                _ => Some(i - 1),
+            };
+            let next = match i + 2 {
+                j if j < n => Some(j),
+                _ if polyline.closed && n > 2 => Some(1),
+                _ => None,
+            };
             self.segments.push(LineSegment {
                 point_a: a.into(),
                 width,
@@ -305,13 +495,41 @@ impl PolylineRenderer3d {
                 depth_bias,
                 color,
                 perspective,
-                _padding: [0; 3],
+                width_b: width,
+                dash,
+                color_b,
+                distance: [dist_a, dist_b],
+                prev: neighbor(prev),
+                next: neighbor(next),
+                _padding: [0; 2],
+            });
+        }
+        for (a, b, color, color_b) in arrows {
+            self.segments.push(LineSegment {
+                point_a: a.into(),
+                width: width * 4.0,
+                point_b: b.into(),
+                depth_bias,
+                color,
+                perspective,
+                width_b: 0.0,
+                dash: [0.0; 2],
+                color_b,
+                distance: [0.0; 2],
+                prev: [0.0; 4],
+                next: [0.0; 4],
+                _padding: [0; 2],
             });
         }
     }
 
     /// Draws a simple line segment with the given width.
     pub fn draw_line(&mut self, a: Vec3, b: Vec3, color: Color, width: f32, perspective: bool) {
+        let width = if perspective {
+            width
+        } else {
+            width * self.size_scale
+        };
         self.segments.push(LineSegment {
             point_a: a.into(),
             width,
@@ -319,7 +537,13 @@ impl PolylineRenderer3d {
             depth_bias: 0.0,
             color: [color.r, color.g, color.b, color.a],
             perspective: perspective as u32,
-            _padding: [0; 3],
+            width_b: width,
+            dash: [0.0; 2],
+            color_b: [color.r, color.g, color.b, color.a],
+            distance: [0.0; 2],
+            prev: [0.0; 4],
+            next: [0.0; 4],
+            _padding: [0; 2],
         });
     }
 
@@ -351,7 +575,8 @@ impl PolylineRenderer3d {
 }
 
 impl Renderer3d for PolylineRenderer3d {
-    /// Renders all polylines in a single draw call.
+    /// Renders all polylines in two draw calls: their cores, then their antialiased
+    /// fringes.
     fn render(
         &mut self,
         pass: usize,
@@ -398,19 +623,25 @@ impl Renderer3d for PolylineRenderer3d {
         // Create view bind group
         let view_bind_group = self.create_view_bind_group();
 
-        let pipeline = self.pipeline.get(context.sample_count);
-        render_pass.set_pipeline(&pipeline);
         render_pass.set_bind_group(0, &view_bind_group, &[]);
         render_pass.set_vertex_buffer(0, self.segment_buffer.slice(..));
 
-        // Draw all polylines in a single call
+        // Draw all the polylines' cores, then all their antialiased fringes.
         let num_segments = self.segments.len() as u32;
-        render_pass.draw(0..6, 0..num_segments);
+        for pipeline in [&self.pipeline, &self.fringe_pipeline] {
+            render_pass.set_pipeline(&pipeline.get(context.sample_count));
+            render_pass.draw(0..6, 0..num_segments);
+        }
 
         // Clear segments for next frame
         self.segments.clear();
     }
 }
 
+/// Linear interpolation between two RGBA colors.
+pub(crate) fn lerp_color(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t)
+}
+
 /// Vertex shader source for polylines.
 pub static POLYLINE_SHADER_SRC: &str = include_str!("../builtin/polyline3d.wgsl");
@@ -616,6 +616,7 @@ impl RayTracer {
             ],
             fog_params: lights.fog.params(),
             flags: [gpu_scene.has_non_shadow_caster as u32, 0, 0, 0],
+            world_up: crate::context::Context::world_up().extend(0.0).to_array(),
         };
         self.pipeline.write_uniforms(&uniforms);
 
@@ -70,6 +70,9 @@ pub struct FrameUniforms {
     /// Misc flags. `x = 1` when some object opts out of casting shadows (the kernel
     /// then walks shadow-ray occluders to skip non-casters). The rest are reserved.
     pub flags: [u32; 4],
+    /// The [world up axis](crate::context::Context::world_up) (XYZ; W unused),
+    /// along which fog thins with height.
+    pub world_up: [f32; 4],
 }
 
 const PREAMBLE: &str = include_str!("../../builtin/raytrace/rt_preamble.wgsl");
@@ -0,0 +1,266 @@
+//! Infinite ground-plane reference grid.
+//!
+//! Drawn as a single full-screen pass after the opaque geometry: the fragment
+//! shader intersects each pixel's view ray with a horizontal plane (perpendicular
+//! to [`Context::world_up`]) and shades
+//! analytic, derivative-antialiased grid lines there, so the grid has no extent,
+//! no vertex buffers and no CPU-side line generation. The hit depth is written
+//! to the depth test so scene objects correctly occlude (and stand on) the grid.
+
+use crate::camera::Camera3d;
+use crate::color::Color;
+use crate::context::Context;
+use crate::resource::{multisample_state, PipelineCache};
+use bytemuck::{Pod, Zeroable};
+use glamx::{Mat4, Quat, Vec3};
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct GridUniforms {
+    view_proj: [[f32; 4]; 4],
+    inv_view_proj: [[f32; 4]; 4],
+    // (eye.x, eye.y, eye.z, plane height)
+    eye_height: [f32; 4],
+    // (cell size, major line every N cells, fade distance, line width in pixels)
+    params: [f32; 4],
+    minor_color: [f32; 4],
+    major_color: [f32; 4],
+    x_axis_color: [f32; 4],
+    z_axis_color: [f32; 4],
+}
+
+/// Appearance of the reference grid enabled by
+/// [`Window::enable_reference_grid`](crate::window::Window::enable_reference_grid).
+#[derive(Copy, Clone, Debug)]
+pub struct GridConfig {
+    /// World-space size of one minor grid cell.
+    pub cell_size: f32,
+    /// A major line is drawn every `major_every` minor cells.
+    pub major_every: u32,
+    /// Height of the grid plane along the world up axis (see
+    /// [`Window::set_world_up`](crate::window::Window::set_world_up)).
+    pub height: f32,
+    /// Horizontal distance from the camera at which the grid has fully faded out.
+    /// Fading starts at half this distance.
+    pub fade_distance: f32,
+    /// Width of the minor lines, in pixels. Major lines and axes are drawn
+    /// slightly thicker.
+    pub line_width: f32,
+    /// Color of the minor lines (the alpha is honored).
+    pub minor_color: Color,
+    /// Color of the major lines.
+    pub major_color: Color,
+    /// Color of the line along the world X axis (`z = 0`).
+    pub x_axis_color: Color,
+    /// Color of the line along the world Z axis (`x = 0`), or along the world Y
+    /// axis in a Z-up world.
+    pub z_axis_color: Color,
+}
+
+impl Default for GridConfig {
+    fn default() -> Self {
+        GridConfig {
+            cell_size: 1.0,
+            major_every: 10,
+            height: 0.0,
+            fade_distance: 100.0,
+            line_width: 1.0,
+            minor_color: Color::new(0.5, 0.5, 0.5, 0.35),
+            major_color: Color::new(0.6, 0.6, 0.6, 0.7),
+            x_axis_color: Color::new(0.9, 0.2, 0.2, 1.0),
+            z_axis_color: Color::new(0.2, 0.4, 0.9, 1.0),
+        }
+    }
+}
+
+/// Owns the pipeline and uniform buffer used to draw the reference grid. Created
+/// lazily by [`Window::enable_reference_grid`](crate::window::Window::enable_reference_grid).
+pub struct ReferenceGrid {
+    config: GridConfig,
+    pipeline: PipelineCache,
+    uniform: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+impl ReferenceGrid {
+    /// Creates a reference grid with the given appearance.
+    pub fn new(config: GridConfig) -> ReferenceGrid {
+        let ctxt = Context::get();
+
+        let layout = ctxt.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("reference_grid_bind_group_layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+
+        let pipeline_layout = ctxt.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("reference_grid_pipeline_layout"),
+            bind_group_layouts: &[Some(&layout)],
+            immediate_size: 0,
+        });
+
+        let shader = ctxt.create_shader_module(
+            Some("reference_grid_shader"),
+            &crate::builtin::compile_shader_with_common(
+                "package::reference_grid",
+                include_str!("../builtin/reference_grid.wgsl"),
+            ),
+        );
+
+        // Built lazily per MSAA sample count to match the HDR scene attachment.
+        let pipeline = PipelineCache::new(move |sample_count| {
+            let ctxt = Context::get();
+            ctxt.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("reference_grid_pipeline"),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_main"),
+                    buffers: &[],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some("fs_main"),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: Context::render_format(),
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: None,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                // Depth-tested against the opaque scene (via `frag_depth`) but never
+                // written: the grid is a translucent overlay, so transparent surfaces
+                // drawn afterwards must still see the geometry behind it.
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: Context::depth_format(),
+                    depth_write_enabled: Some(false),
+                    depth_compare: Some(wgpu::CompareFunction::LessEqual),
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: multisample_state(sample_count),
+                multiview_mask: None,
+                cache: None,
+            })
+        });
+
+        let uniform = ctxt.create_buffer_simple(
+            Some("reference_grid_uniform"),
+            std::mem::size_of::<GridUniforms>() as u64,
+            wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        );
+
+        let bind_group = ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("reference_grid_bind_group"),
+            layout: &layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform.as_entire_binding(),
+            }],
+        });
+
+        ReferenceGrid {
+            config,
+            pipeline,
+            uniform,
+            bind_group,
+        }
+    }
+
+    /// The grid appearance.
+    pub fn config(&self) -> &GridConfig {
+        &self.config
+    }
+
+    /// Replaces the grid appearance.
+    pub fn set_config(&mut self, config: GridConfig) {
+        self.config = config;
+    }
+
+    /// Draws the grid into `color_view` (the HDR scene attachment) as seen from
+    /// `camera`'s current pass, depth-tested against `depth_view`.
+    pub(crate) fn render(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        color_view: &wgpu::TextureView,
+        depth_view: &wgpu::TextureView,
+        sample_count: u32,
+        camera: &dyn Camera3d,
+        gpu: Option<&mut crate::renderer::timings::GpuTimer>,
+    ) {
+        let ctxt = Context::get();
+        let c = &self.config;
+        // The shader works in a frame where the world up axis is `Y`.
+        let to_grid = Quat::from_rotation_arc(Context::world_up(), Vec3::Y);
+        let eye = to_grid * camera.eye();
+        let view_proj = camera.transformation() * Mat4::from_quat(to_grid.inverse());
+        let rgba = |c: Color| [c.r, c.g, c.b, c.a];
+
+        ctxt.write_buffer(
+            &self.uniform,
+            0,
+            bytemuck::bytes_of(&GridUniforms {
+                view_proj: view_proj.to_cols_array_2d(),
+                inv_view_proj: view_proj.inverse().to_cols_array_2d(),
+                eye_height: [eye.x, eye.y, eye.z, c.height],
+                params: [
+                    c.cell_size.max(1.0e-6),
+                    c.major_every.max(1) as f32,
+                    c.fade_distance.max(1.0e-6),
+                    c.line_width.max(0.0),
+                ],
+                minor_color: rgba(c.minor_color),
+                major_color: rgba(c.major_color),
+                x_axis_color: rgba(c.x_axis_color),
+                z_axis_color: rgba(c.z_axis_color),
+            }),
+        );
+
+        let pipeline = self.pipeline.get(sample_count);
+        let grid_ts = gpu.and_then(|g| g.render_scope("reference_grid"));
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("reference_grid_pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: color_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+                depth_slice: None,
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: grid_ts,
+            occlusion_query_set: None,
+            multiview_mask: None,
+        });
+        pass.set_pipeline(&pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
@@ -439,10 +439,22 @@ pub struct CubeFaceCamera {
 impl CubeFaceCamera {
     /// Builds the capture camera for `face` (0..6) at `eye`.
     pub fn new(eye: glamx::Vec3, face: usize, znear: f32, zfar: f32) -> CubeFaceCamera {
+        Self::new_oriented(eye, face, glamx::Quat::IDENTITY, znear, zfar)
+    }
+
+    /// Builds the capture camera for `face` (0..6) at `eye`, with the whole cube
+    /// layout rotated by `orientation`.
+    pub fn new_oriented(
+        eye: glamx::Vec3,
+        face: usize,
+        orientation: glamx::Quat,
+        znear: f32,
+        zfar: f32,
+    ) -> CubeFaceCamera {
         let f = FACE_FORWARD[face];
         let u = FACE_UP[face];
-        let fwd = glamx::Vec3::new(f[0], f[1], f[2]);
-        let up = glamx::Vec3::new(u[0], u[1], u[2]);
+        let fwd = orientation * glamx::Vec3::new(f[0], f[1], f[2]);
+        let up = orientation * glamx::Vec3::new(u[0], u[1], u[2]);
         let proj = glamx::glam::camera::rh::proj::opengl::perspective(
             core::f32::consts::FRAC_PI_2,
             1.0,
@@ -460,6 +472,24 @@ impl CubeFaceCamera {
     }
 }
 
+impl CubeFaceCamera {
+    /// Builds the camera rendering `face` (0..6) of a cube-map texture at `eye`.
+    ///
+    /// Cube-map faces use a left-handed layout, so the image is mirrored
+    /// horizontally compared to [`CubeFaceCamera::new`]. This flips the triangle
+    /// winding: render with back-face culling disabled.
+    pub fn new_cubemap_face(
+        eye: glamx::Vec3,
+        face: usize,
+        znear: f32,
+        zfar: f32,
+    ) -> CubeFaceCamera {
+        let mut camera = Self::new(eye, face, znear, zfar);
+        camera.proj = glamx::Mat4::from_scale(glamx::Vec3::new(-1.0, 1.0, 1.0)) * camera.proj;
+        camera
+    }
+}
+
 impl crate::camera::Camera3d for CubeFaceCamera {
     fn handle_event(&mut self, _: &crate::window::Canvas, _: &crate::event::WindowEvent) {}
     fn update(&mut self, _: &crate::window::Canvas) {}
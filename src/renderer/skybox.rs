@@ -185,6 +185,16 @@ impl Skybox {
         self.generation += 1;
     }
 
+    /// Sets the skybox from the six faces of a cubemap, in the usual
+    /// `+X, -X, +Y, -Y, +Z, -Z` order and orientation (each face seen from the
+    /// cube's center, as in OpenGL/DDS cubemaps). The faces are resampled into an
+    /// equirectangular map four faces wide.
+    pub fn set_cubemap(&mut self, faces: &[image::DynamicImage; 6]) {
+        self.set_image(&image::DynamicImage::ImageRgba32F(cubemap_to_equirect(
+            faces,
+        )));
+    }
+
     /// Clears the skybox (subsequent frames render no background or IBL).
     pub fn clear(&mut self) {
         self.environment = Environment::fallback();
@@ -298,3 +308,64 @@ impl Skybox {
         pass.draw(0..3, 0..1);
     }
 }
+
+/// Resamples six cubemap faces (`+X, -X, +Y, -Y, +Z, -Z`) into an equirectangular
+/// image using the renderer's direction->UV convention (see `pbr_env.wgsl`).
+fn cubemap_to_equirect(faces: &[image::DynamicImage; 6]) -> image::Rgba32FImage {
+    use std::f32::consts::PI;
+
+    let faces = faces.each_ref().map(|f| f.to_rgba32f());
+    let face_size = faces.iter().map(|f| f.width()).max().unwrap_or(1).max(1);
+    let (width, height) = (face_size * 4, face_size * 2);
+
+    // Bilinear lookup within one face at normalized coordinates, clamped to its edges.
+    let sample = |face: &image::Rgba32FImage, s: f32, t: f32| -> [f32; 4] {
+        let (w, h) = face.dimensions();
+        let x = (s * w as f32 - 0.5).clamp(0.0, (w - 1) as f32);
+        let y = (t * h as f32 - 0.5).clamp(0.0, (h - 1) as f32);
+        let (x0, y0) = (x.floor() as u32, y.floor() as u32);
+        let (x1, y1) = ((x0 + 1).min(w - 1), (y0 + 1).min(h - 1));
+        let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+        let mut out = [0.0; 4];
+        for (c, o) in out.iter_mut().enumerate() {
+            let top = face.get_pixel(x0, y0)[c] * (1.0 - fx) + face.get_pixel(x1, y0)[c] * fx;
+            let bottom = face.get_pixel(x0, y1)[c] * (1.0 - fx) + face.get_pixel(x1, y1)[c] * fx;
+            *o = top * (1.0 - fy) + bottom * fy;
+        }
+        out
+    };
+
+    image::Rgba32FImage::from_fn(width, height, |px, py| {
+        let phi = ((px as f32 + 0.5) / width as f32 - 0.5) * 2.0 * PI;
+        let theta = (py as f32 + 0.5) / height as f32 * PI;
+        let (x, y, z) = (
+            theta.sin() * phi.cos(),
+            theta.cos(),
+            theta.sin() * phi.sin(),
+        );
+        let (ax, ay, az) = (x.abs(), y.abs(), z.abs());
+
+        // Face index and in-face coordinates (sc, tc) per the cubemap convention.
+        let (face, sc, tc, ma) = if ax >= ay && ax >= az {
+            if x > 0.0 {
+                (0, -z, -y, ax)
+            } else {
+                (1, z, -y, ax)
+            }
+        } else if ay >= az {
+            if y > 0.0 {
+                (2, x, z, ay)
+            } else {
+                (3, x, -z, ay)
+            }
+        } else if z > 0.0 {
+            (4, x, -y, az)
+        } else {
+            (5, -x, -y, az)
+        };
+
+        let s = (sc / ma + 1.0) * 0.5;
+        let t = (tc / ma + 1.0) * 0.5;
+        image::Rgba(sample(&faces[face], s, t))
+    })
+}
@@ -51,7 +51,7 @@ impl Default for SsrSettings {
 /// object receives no SSR (gated off); `Some(SsrMaterial { .. })` makes it receive
 /// SSR with these properties. Combined with the window-global [`SsrSettings`]
 /// (which holds the march-quality knobs shared by all objects).
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct SsrMaterial {
     /// Per-object reflection strength (multiplies the global intensity). `0`
     /// disables SSR on this object, same as `set_ssr(None)`.
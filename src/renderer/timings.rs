@@ -12,10 +12,14 @@
 //!   `present` calls — the parts of the frame that actually run on the CPU and can
 //!   block — plus the `total` time of the whole `render_*` call.
 //!
+//! Each frame also counts the draw calls of the scene objects (shadow, prepass
+//! and main passes included) and the triangles they rasterize.
+//!
 //! Read the latest with [`Window::render_timings`](crate::window::Window::render_timings).
 //! [`RenderTimings`] implements [`Display`](std::fmt::Display); the built-in
 //! inspector shows it.
 
+use std::cell::Cell;
 use std::fmt;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -25,6 +29,26 @@ use web_time::Instant;
 
 use crate::context::Context;
 
+thread_local! {
+    // Draw calls and triangles submitted since the start of the frame.
+    static DRAW_STATS: Cell<(u32, u64)> = const { Cell::new((0, 0)) };
+}
+
+/// Counts one draw call rasterizing `triangles` triangles (all instances
+/// included) for the frame being rendered.
+pub(crate) fn record_draw(triangles: u64) {
+    DRAW_STATS.with(|stats| {
+        let (calls, tris) = stats.get();
+        stats.set((calls + 1, tris + triangles));
+    });
+}
+
+/// The `(draw calls, triangles)` counted since the last call, resetting the
+/// counters.
+pub(crate) fn take_draw_stats() -> (u32, u64) {
+    DRAW_STATS.with(|stats| stats.replace((0, 0)))
+}
+
 /// Wall-clock duration in milliseconds.
 fn ms(d: Duration) -> f64 {
     d.as_secs_f64() * 1000.0
@@ -35,6 +59,7 @@ fn ms(d: Duration) -> f64 {
 /// See the [module docs](self) for what is measured (GPU pass times via timestamp
 /// queries; CPU wall-clock for submit/present and the whole frame).
 #[derive(Clone, Debug, Default)]
+#[non_exhaustive]
 pub struct RenderTimings {
     /// Which renderer produced these timings (`"Rasterizer"` or `"Path tracer"`).
     pub renderer: &'static str,
@@ -56,6 +81,10 @@ pub struct RenderTimings {
     /// `None` when GPU timestamp queries are unsupported on this platform, or
     /// while the first results are still in flight.
     pub gpu_steps: Option<Vec<(&'static str, Duration)>>,
+    /// Number of draw calls issued for the scene objects.
+    pub draw_calls: u32,
+    /// Number of triangles drawn for the scene objects, all instances included.
+    pub triangles: u64,
 }
 
 impl RenderTimings {
@@ -86,6 +115,11 @@ impl fmt::Display for RenderTimings {
         write!(f, "\n  cpu render   {:>8.3} ms", ms(self.total))?;
         write!(f, "\n  cpu submit   {:>8.3} ms", ms(self.cpu_submit))?;
         write!(f, "\n  cpu present  {:>8.3} ms", ms(self.cpu_present))?;
+        write!(
+            f,
+            "\n  draw calls   {:>8} ({} triangles)",
+            self.draw_calls, self.triangles
+        )?;
         match &self.gpu_steps {
             Some(steps) => {
                 for (name, dur) in steps {
@@ -49,6 +49,17 @@ impl RenderTarget {
         }
     }
 
+    /// Creates a view of the depth texture to sample it in a shader (see
+    /// [`Context::depth_sample_view`]).
+    ///
+    /// Returns `None` if this is the screen target.
+    pub fn depth_sample_view(&self) -> Option<wgpu::TextureView> {
+        match self {
+            RenderTarget::Screen => None,
+            RenderTarget::Offscreen(o) => Some(Context::depth_sample_view(&o.depth_texture)),
+        }
+    }
+
     /// Returns the color texture for off-screen rendering.
     ///
     /// Returns `None` if this is the screen target.
@@ -2,13 +2,89 @@
 
 use crate::context::Context;
 use bytemuck::{Pod, Zeroable};
+use std::cell::RefCell;
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Source of the content versions of every `GPUVec`, so that versions are unique
+/// process-wide and never collide between two different vectors.
+static NEXT_VERSION: AtomicU64 = AtomicU64::new(1);
+
+fn next_version() -> u64 {
+    NEXT_VERSION.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Size of the staging buffers of [`UploadMode::StagingBelt`]. Larger uploads get
+/// a dedicated staging buffer.
+const STAGING_BELT_CHUNK_SIZE: u64 = 64 * 1024;
+
+thread_local! {
+    /// The uploads of the vectors in [`UploadMode::StagingBelt`] since the last
+    /// submission, batched into one encoder.
+    static STAGED_UPLOADS: RefCell<Option<StagedUploads>> = const { RefCell::new(None) };
+}
+
+/// The staging belt shared by the vectors in [`UploadMode::StagingBelt`], and the
+/// encoder recording their copies until the next [`Context::submit`].
+struct StagedUploads {
+    belt: wgpu::util::StagingBelt,
+    encoder: Option<wgpu::CommandEncoder>,
+}
+
+/// Finishes the copies staged since the last submission, into a command buffer
+/// to be submitted before any other (see [`Context::submit`]).
+pub(crate) fn take_staged_uploads() -> Option<wgpu::CommandBuffer> {
+    STAGED_UPLOADS.with_borrow_mut(|staged| {
+        let staged = staged.as_mut()?;
+        let encoder = staged.encoder.take()?;
+        staged.belt.finish();
+        Some(encoder.finish())
+    })
+}
+
+/// Recycles the staging buffers whose copies were submitted, once the GPU is done
+/// with them.
+pub(crate) fn recall_staged_uploads() {
+    STAGED_UPLOADS.with_borrow_mut(|staged| {
+        if let Some(staged) = staged.as_mut() {
+            if staged.encoder.is_none() {
+                staged.belt.recall();
+            }
+        }
+    })
+}
+
+/// Drops the staging belt, so that it is re-created with the next context.
+pub(crate) fn reset_staged_uploads() {
+    STAGED_UPLOADS.set(None);
+}
+
+/// How a [`GPUVec`] uploads its modified elements to its GPU buffer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UploadMode {
+    /// Copies the data with `wgpu::Queue::write_buffer`, which allocates a
+    /// temporary staging buffer for every upload.
+    #[default]
+    WriteBuffer,
+    /// Writes the data into mapped staging buffers (a `wgpu::util::StagingBelt`
+    /// shared by every vector in this mode), which are recycled once the GPU has
+    /// copied them. The copies of all the vectors are recorded into a single
+    /// encoder, submitted ahead of the next frame. Suited to vectors rewritten
+    /// every frame.
+    StagingBelt,
+}
 
 /// A vector of elements that can be loaded to the GPU, on the RAM, or both.
 pub struct GPUVec<T: Pod + Zeroable> {
     dirty: bool,
+    /// The elements modified since the last upload when only part of the vector
+    /// changed, or `None` if the whole vector has to be uploaded.
+    dirty_range: Option<Range<usize>>,
+    version: u64,
     len: usize,
     usage: wgpu::BufferUsages,
     buffer: Option<wgpu::Buffer>,
+    upload_mode: UploadMode,
     data: Option<Vec<T>>,
 }
 
@@ -18,9 +94,12 @@ impl<T: Pod + Zeroable> GPUVec<T> {
         let usage = buf_type.to_wgpu();
         GPUVec {
             dirty: true,
+            dirty_range: None,
+            version: next_version(),
             len: data.len(),
             usage,
             buffer: None,
+            upload_mode: UploadMode::WriteBuffer,
             data: Some(data),
         }
     }
@@ -30,9 +109,12 @@ impl<T: Pod + Zeroable> GPUVec<T> {
         let usage = buf_type.to_wgpu();
         GPUVec {
             dirty: false,
+            dirty_range: None,
+            version: next_version(),
             len: 0,
             usage,
             buffer: None,
+            upload_mode: UploadMode::WriteBuffer,
             data: Some(Vec::new()),
         }
     }
@@ -58,13 +140,99 @@ impl<T: Pod + Zeroable> GPUVec<T> {
 
     /// Mutably accesses the vector if it is available on RAM.
     ///
-    /// This method will mark this vector as `dirty`.
+    /// This method will mark this vector as `dirty` and bump its
+    /// [`version`](Self::version).
     #[inline]
     pub fn data_mut(&mut self) -> &mut Option<Vec<T>> {
         self.dirty = true;
+        self.dirty_range = None;
+        self.version = next_version();
         &mut self.data
     }
 
+    /// Overwrites the elements starting at `offset` with `values`.
+    ///
+    /// Unlike modifications through [`data_mut`](Self::data_mut), only the
+    /// modified elements are uploaded by the next
+    /// [`load_to_gpu`](Self::load_to_gpu).
+    ///
+    /// # Panics
+    /// If the vector is not on RAM or if `offset + values.len()` exceeds its length.
+    pub fn update_range(&mut self, offset: usize, values: &[T]) {
+        let data = self
+            .data
+            .as_mut()
+            .expect("GPUVec::update_range: the vector is not on RAM.");
+        data[offset..offset + values.len()].copy_from_slice(values);
+        self.mark_range(offset..offset + values.len());
+    }
+
+    /// Appends `values` at the end of the vector.
+    ///
+    /// Only the new elements are uploaded by the next
+    /// [`load_to_gpu`](Self::load_to_gpu), as long as they fit in the GPU buffer;
+    /// the buffer grows geometrically otherwise, so streaming appends (trails,
+    /// point clouds) are amortized.
+    ///
+    /// # Panics
+    /// If the vector is not on RAM.
+    pub fn extend_from_slice(&mut self, values: &[T]) {
+        let data = self
+            .data
+            .as_mut()
+            .expect("GPUVec::extend_from_slice: the vector is not on RAM.");
+        let start = data.len();
+        data.extend_from_slice(values);
+        self.mark_range(start..start + values.len());
+    }
+
+    /// Appends one element at the end of the vector.
+    ///
+    /// See [`extend_from_slice`](Self::extend_from_slice).
+    pub fn push(&mut self, value: T) {
+        self.extend_from_slice(&[value])
+    }
+
+    /// Shortens the vector to `len` elements, without uploading anything.
+    ///
+    /// Does nothing if the vector is already shorter.
+    ///
+    /// # Panics
+    /// If the vector is not on RAM.
+    pub fn truncate(&mut self, len: usize) {
+        let data = self
+            .data
+            .as_mut()
+            .expect("GPUVec::truncate: the vector is not on RAM.");
+        if len < data.len() {
+            data.truncate(len);
+            self.mark_range(len..len);
+        }
+    }
+
+    fn mark_range(&mut self, range: Range<usize>) {
+        if !self.dirty {
+            self.dirty = true;
+            self.dirty_range = Some(range);
+        } else if let Some(curr) = &mut self.dirty_range {
+            curr.start = curr.start.min(range.start);
+            curr.end = curr.end.max(range.end);
+        }
+        self.version = next_version();
+    }
+
+    /// An identifier of the current contents of this vector.
+    ///
+    /// It changes every time the contents may have been modified (each call to
+    /// [`data_mut`](Self::data_mut) or [`prepare_gpu_writable`](Self::prepare_gpu_writable)),
+    /// and is unique across all `GPUVec`s. Caches derived from the contents (e.g.
+    /// wireframe edges) can store it and rebuild only when it differs. Unlike
+    /// [`dirty`](Self::dirty), it is not reset by uploads to the GPU.
+    #[inline]
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
     /// Immutably accesses the vector if it is available on RAM.
     #[inline]
     pub fn data(&self) -> &Option<Vec<T>> {
@@ -110,6 +278,49 @@ impl<T: Pod + Zeroable> GPUVec<T> {
         self.usage
     }
 
+    /// Adds `usage` to the usages of the GPU buffer, e.g.
+    /// `wgpu::BufferUsages::STORAGE` to bind a vertex buffer in a compute shader.
+    ///
+    /// If the buffer already exists without these usages it is recreated by the
+    /// next [`load_to_gpu`](Self::load_to_gpu), which requires the vector to be
+    /// on RAM; otherwise the existing buffer is kept.
+    #[inline]
+    pub fn add_usage(&mut self, usage: wgpu::BufferUsages) {
+        self.usage |= usage;
+        let outdated = matches!(&self.buffer, Some(b) if !b.usage().contains(self.usage));
+        if outdated && self.data.is_some() {
+            self.buffer = None;
+        }
+    }
+
+    /// Uploads the vector if needed and returns its whole buffer as a binding
+    /// resource, ready to be put in a `wgpu::BindGroupEntry`.
+    ///
+    /// Returns `None` if the vector is empty. The buffer must have been created
+    /// with a usage matching the binding (see [`add_usage`](Self::add_usage)).
+    #[inline]
+    pub fn binding(&mut self) -> Option<wgpu::BindingResource<'_>> {
+        self.load_to_gpu();
+        let size = wgpu::BufferSize::new((self.len * std::mem::size_of::<T>()) as u64)?;
+        let buffer = self.buffer.as_ref()?;
+        Some(wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+            buffer,
+            offset: 0,
+            size: Some(size),
+        }))
+    }
+
+    /// Sets how the modified elements are uploaded by
+    /// [`load_to_gpu`](Self::load_to_gpu) (see [`UploadMode`]).
+    pub fn set_upload_mode(&mut self, mode: UploadMode) {
+        self.upload_mode = mode;
+    }
+
+    /// How the modified elements are uploaded to the GPU.
+    pub fn upload_mode(&self) -> UploadMode {
+        self.upload_mode
+    }
+
     /// Loads the vector from the RAM to the GPU.
     ///
     /// If the vector is not available on RAM or already loaded to the GPU, nothing will happen.
@@ -122,42 +333,52 @@ impl<T: Pod + Zeroable> GPUVec<T> {
                 return;
             }
 
-            let bytes = bytemuck::cast_slice(data);
-
-            if !self.is_on_gpu() {
-                // Create new buffer
-                self.len = data.len();
-                let buffer = ctxt.create_buffer_init(
-                    Some("GPUVec buffer"),
-                    bytes,
-                    self.usage | wgpu::BufferUsages::COPY_DST,
-                );
-                self.buffer = Some(buffer);
-            } else if self.dirty {
-                // Update existing buffer
-                self.len = data.len();
-
-                if let Some(ref buffer) = self.buffer {
-                    let buffer_size = buffer.size() as usize;
-                    let data_size = bytes.len();
-
-                    if data_size <= buffer_size {
-                        // Buffer is big enough, just update
-                        ctxt.write_buffer(buffer, 0, bytes);
-                    } else {
-                        // Need to recreate buffer
-                        let new_buffer = ctxt.create_buffer_init(
-                            Some("GPUVec buffer"),
-                            bytes,
-                            self.usage | wgpu::BufferUsages::COPY_DST,
-                        );
-                        self.buffer = Some(new_buffer);
-                    }
+            let bytes: &[u8] = bytemuck::cast_slice(data);
+            self.len = data.len();
+
+            match &self.buffer {
+                None => {
+                    // Create new buffer, with no spare capacity: most vectors are
+                    // uploaded once.
+                    let buffer = ctxt.create_buffer_init(
+                        Some("GPUVec buffer"),
+                        bytes,
+                        self.usage | wgpu::BufferUsages::COPY_DST,
+                    );
+                    self.buffer = Some(buffer);
+                }
+                Some(buffer) if (buffer.size() as usize) < bytes.len() => {
+                    // The vector outgrew its buffer: reallocate with (at least)
+                    // twice the capacity so repeated appends are amortized.
+                    let capacity = bytes
+                        .len()
+                        .max(buffer.size() as usize * 2)
+                        .div_ceil(wgpu::COPY_BUFFER_ALIGNMENT as usize)
+                        * wgpu::COPY_BUFFER_ALIGNMENT as usize;
+                    let buffer = ctxt.create_buffer(&wgpu::BufferDescriptor {
+                        label: Some("GPUVec buffer"),
+                        size: capacity as u64,
+                        usage: self.usage | wgpu::BufferUsages::COPY_DST,
+                        mapped_at_creation: false,
+                    });
+                    write_bytes(&ctxt, self.upload_mode, &buffer, bytes, 0..bytes.len());
+                    self.buffer = Some(buffer);
+                }
+                Some(buffer) if self.dirty => {
+                    // Buffer is big enough, only upload what changed.
+                    let elt = std::mem::size_of::<T>();
+                    let range = match &self.dirty_range {
+                        Some(r) => r.start * elt..(r.end * elt).min(bytes.len()),
+                        None => 0..bytes.len(),
+                    };
+                    write_bytes(&ctxt, self.upload_mode, buffer, bytes, range);
                 }
+                Some(_) => {}
             }
         }
 
         self.dirty = false;
+        self.dirty_range = None;
     }
 
     /// Ensures the buffer is on the GPU and returns a reference to it.
@@ -206,6 +427,7 @@ impl<T: Pod + Zeroable> GPUVec<T> {
         // (since `dirty` is false) and `load_to_gpu` becomes a no-op.
         self.len = count;
         self.dirty = false;
+        self.version = next_version();
         self.data = None;
         self.buffer.as_ref().unwrap()
     }
@@ -216,6 +438,7 @@ impl<T: Pod + Zeroable> GPUVec<T> {
         self.len = self.len();
         self.buffer = None;
         self.dirty = false;
+        self.dirty_range = None;
     }
 
     /// Removes this resource from the RAM.
@@ -242,6 +465,52 @@ impl<T: Clone + Pod + Zeroable> GPUVec<T> {
     }
 }
 
+/// Writes `bytes[range]` at the same offset of `buffer`, widening the range to
+/// the copy alignment wgpu requires (zero-padding past the end of `bytes`).
+///
+/// Goes through `wgpu::Queue::write_buffer`, or records a copy from the shared
+/// staging belt in [`UploadMode::StagingBelt`].
+fn write_bytes(
+    ctxt: &Context,
+    mode: UploadMode,
+    buffer: &wgpu::Buffer,
+    bytes: &[u8],
+    range: Range<usize>,
+) {
+    let align = wgpu::COPY_BUFFER_ALIGNMENT as usize;
+    let start = range.start / align * align;
+    let end = range.end.div_ceil(align) * align;
+    if start >= end {
+        return;
+    }
+
+    let padded;
+    let data = if end <= bytes.len() {
+        &bytes[start..end]
+    } else {
+        padded = [&bytes[start..], &vec![0; end - bytes.len()][..]].concat();
+        &padded[..]
+    };
+
+    match mode {
+        UploadMode::StagingBelt => STAGED_UPLOADS.with_borrow_mut(|staged| {
+            let staged = staged.get_or_insert_with(|| StagedUploads {
+                belt: wgpu::util::StagingBelt::new((*ctxt.device).clone(), STAGING_BELT_CHUNK_SIZE),
+                encoder: None,
+            });
+            let encoder = staged
+                .encoder
+                .get_or_insert_with(|| ctxt.create_command_encoder(Some("GPUVec staged uploads")));
+            let size = wgpu::BufferSize::new(data.len() as u64).unwrap();
+            staged
+                .belt
+                .write_buffer(encoder, buffer, start as u64, size)
+                .copy_from_slice(data);
+        }),
+        UploadMode::WriteBuffer => ctxt.write_buffer(buffer, start as u64, data),
+    }
+}
+
 /// Type of gpu buffer.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum BufferType {
@@ -278,3 +547,86 @@ pub enum AllocationType {
     /// Data for immediate use (lines, points, text).
     StreamDraw,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::window::OffscreenSurface;
+
+    /// Copies the first `len` elements of `buffer` back to the CPU.
+    fn read_back(buffer: &wgpu::Buffer, len: usize) -> Vec<u32> {
+        let ctxt = Context::get();
+        let size = (len * std::mem::size_of::<u32>()) as u64;
+        let staging = ctxt.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("GPUVec test readback"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = ctxt.create_command_encoder(Some("GPUVec test readback"));
+        encoder.copy_buffer_to_buffer(buffer, 0, &staging, 0, size);
+        ctxt.submit(Some(encoder.finish()));
+
+        let slice = staging.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |r| tx.send(r).unwrap());
+        let _ = ctxt.device.poll(wgpu::PollType::wait_indefinitely());
+        rx.recv().unwrap().unwrap();
+        let data = bytemuck::cast_slice(&slice.get_mapped_range()).to_vec();
+        data
+    }
+
+    fn has_staged_uploads() -> bool {
+        STAGED_UPLOADS.with_borrow(|s| s.as_ref().is_some_and(|s| s.encoder.is_some()))
+    }
+
+    #[test]
+    fn staged_uploads_are_batched_until_the_next_submission() {
+        crate::pollster::block_on(async {
+            if !crate::shader_validity::tests::adapter_available().await {
+                eprintln!("staging belt test: no GPU adapter found, skipping");
+                return;
+            }
+            let _surface = OffscreenSurface::new(8, 8).await;
+
+            let mut a = GPUVec::new(
+                vec![1u32, 2, 3, 4],
+                BufferType::Storage,
+                AllocationType::DynamicDraw,
+            );
+            let mut b = GPUVec::new(
+                vec![10u32; 4],
+                BufferType::Storage,
+                AllocationType::DynamicDraw,
+            );
+            for v in [&mut a, &mut b] {
+                v.set_upload_mode(UploadMode::StagingBelt);
+                v.add_usage(wgpu::BufferUsages::COPY_SRC);
+                v.load_to_gpu();
+            }
+
+            // Partial updates of both vectors, and the reallocation of `b`, are
+            // recorded into the same encoder without being submitted.
+            a.update_range(1, &[20, 30]);
+            a.load_to_gpu();
+            b.update_range(0, &[11]);
+            b.load_to_gpu();
+            b.extend_from_slice(&[12; 4]);
+            b.load_to_gpu();
+            assert!(has_staged_uploads());
+
+            // The next submission (here, the readback) submits them first.
+            assert_eq!(read_back(a.buffer().unwrap(), 4), [1, 20, 30, 4]);
+            assert!(!has_staged_uploads());
+            assert_eq!(
+                read_back(b.buffer().unwrap(), 8),
+                [11, 10, 10, 10, 12, 12, 12, 12]
+            );
+
+            // The recycled staging buffers are reused by the following uploads.
+            a.update_range(0, &[5]);
+            a.load_to_gpu();
+            assert_eq!(read_back(a.buffer().unwrap(), 4), [5, 20, 30, 4]);
+        })
+    }
+}
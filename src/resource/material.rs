@@ -204,6 +204,17 @@ pub trait Material3d {
         false
     }
 
+    /// Whether objects drawn with this material may be merged into a single
+    /// instanced draw by the scene graph's automatic instancing (see
+    /// [`SceneNode3d::set_auto_instancing`](crate::scene::SceneNode3d::set_auto_instancing)).
+    ///
+    /// Defaults to `false`. A material returning `true` must honor the instance
+    /// buffers (per-instance position, deformation and color) the way the default
+    /// material does.
+    fn supports_auto_instancing(&self) -> bool {
+        false
+    }
+
     /// Supplies (or clears) the image-based-lighting environment for this frame.
     ///
     /// Called once per frame by the window with the active skybox environment, or
@@ -223,6 +234,20 @@ pub trait Material3d {
     /// `None` disables it. Default no-op.
     fn set_ssao(&mut self, _ao: Option<&wgpu::TextureView>) {}
 
+    /// Whether translucent surfaces draw in the transparent (OIT) phase this
+    /// frame. When `false`, they are alpha-blended in the opaque phase instead.
+    /// Default no-op.
+    fn set_oit_enabled(&mut self, _enabled: bool) {}
+
+    /// Called once when the GPU device is lost (see
+    /// [`Context::is_device_lost`](crate::context::Context::is_device_lost)).
+    ///
+    /// The material's GPU resources are unusable from then on. Materials holding
+    /// resources they can't rebuild from their own settings, e.g. data streamed
+    /// by the application, can record it here to re-upload them once the
+    /// window, and with it the GPU context, is re-created. Default no-op.
+    fn on_device_lost(&mut self) {}
+
     /// Supplies (or clears) the transmission background — the resolved opaque scene
     /// color (with a blurred mip chain) that refractive (glass) objects sample to
     /// refract the scene behind them. `None` falls back to a placeholder. No-op by
@@ -362,6 +387,15 @@ pub trait Material2d {
     /// in a single batch. The default implementation does nothing.
     fn flush(&mut self) {}
 
+    /// Called once when the GPU device is lost (see
+    /// [`Context::is_device_lost`](crate::context::Context::is_device_lost)).
+    ///
+    /// The material's GPU resources are unusable from then on. Materials holding
+    /// resources they can't rebuild from their own settings, e.g. data streamed
+    /// by the application, can record it here to re-upload them once the
+    /// window, and with it the GPU context, is re-created. Default no-op.
+    fn on_device_lost(&mut self) {}
+
     /// Render the given 2D mesh using this material (phase 3).
     ///
     /// # Arguments
@@ -64,6 +64,36 @@ impl MaterialManager2d {
         let _ = self.materials.insert(name.to_string(), material);
     }
 
+    /// Registers `material` under `name`, replacing any material with that name,
+    /// and returns the shared instance.
+    ///
+    /// Objects then share this instance when their material is set by name with
+    /// [`SceneNode2d::set_material_with_name`](crate::scene::SceneNode2d::set_material_with_name),
+    /// the same way meshes and textures are shared by their managers.
+    /// Registered materials are also told when frames start and end, which
+    /// materials batching their uniforms rely on.
+    pub fn register(
+        &mut self,
+        name: &str,
+        material: impl Material2d + 'static,
+    ) -> Rc<RefCell<Box<dyn Material2d + 'static>>> {
+        let material = Rc::new(RefCell::new(
+            Box::new(material) as Box<dyn Material2d + 'static>
+        ));
+        self.add(material.clone(), name);
+        material
+    }
+
+    /// Whether a material is registered under `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.materials.contains_key(name)
+    }
+
+    /// The names of the registered materials, in no particular order.
+    pub fn names(&self) -> Vec<String> {
+        self.materials.keys().cloned().collect()
+    }
+
     /// Removes a mesh from this cache.
     pub fn remove(&mut self, name: &str) {
         let _ = self.materials.remove(name);
@@ -90,6 +120,14 @@ impl MaterialManager2d {
         }
     }
 
+    /// Notifies all materials that the GPU device was lost (see
+    /// `Material::on_device_lost()`).
+    pub fn device_lost(&mut self) {
+        for material in self.materials.values() {
+            material.borrow_mut().on_device_lost();
+        }
+    }
+
     /// Resets the global material manager, releasing all GPU resources.
     ///
     /// This should be called before thread-local storage destruction begins
@@ -1,6 +1,6 @@
 //! A resource manager to load materials.
 
-use crate::builtin::{NormalsMaterial, ObjectMaterial, UvsMaterial};
+use crate::builtin::{MatcapMaterial, NormalsMaterial, ObjectMaterial, ToonMaterial, UvsMaterial};
 use crate::resource::Material3d;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -11,6 +11,9 @@ use std::rc::Rc;
 /// Upon construction, it contains:
 /// * the `object` material, used as the default to render objects.
 /// * the `normals` material, used do display an object normals.
+/// * the `uvs` material, used to display an object texture coordinates.
+/// * the `toon` material, a [`ToonMaterial`] with its default settings.
+/// * the `matcap` material, shading objects from their texture as a [`MatcapMaterial`].
 ///
 /// It keeps a cache of already-loaded materials. Note that this is only a cache, nothing more.
 /// Thus, its usage is not required to load materials.
@@ -46,6 +49,16 @@ impl MaterialManager3d {
         ));
         let _ = materials.insert("uvs".to_string(), um.clone());
 
+        let tm = Rc::new(RefCell::new(
+            Box::new(ToonMaterial::new()) as Box<dyn Material3d + 'static>
+        ));
+        let _ = materials.insert("toon".to_string(), tm);
+
+        let mm = Rc::new(RefCell::new(
+            Box::new(MatcapMaterial::new()) as Box<dyn Material3d + 'static>
+        ));
+        let _ = materials.insert("matcap".to_string(), mm);
+
         MaterialManager3d {
             default_material: om,
             materials,
@@ -73,6 +86,44 @@ impl MaterialManager3d {
         let _ = self.materials.insert(name.to_string(), material);
     }
 
+    /// Registers `material` under `name`, replacing any material with that name,
+    /// and returns the shared instance.
+    ///
+    /// Objects then share this instance when their material is set by name with
+    /// [`SceneNode3d::set_material_with_name`](crate::scene::SceneNode3d::set_material_with_name),
+    /// the same way meshes and textures are shared by their managers.
+    /// Registered materials are also told when frames start and end, which
+    /// materials batching their uniforms rely on.
+    pub fn register(
+        &mut self,
+        name: &str,
+        material: impl Material3d + 'static,
+    ) -> Rc<RefCell<Box<dyn Material3d + 'static>>> {
+        let material = Rc::new(RefCell::new(
+            Box::new(material) as Box<dyn Material3d + 'static>
+        ));
+        self.add(material.clone(), name);
+        material
+    }
+
+    /// Whether a material is registered under `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.materials.contains_key(name)
+    }
+
+    /// The names of the registered materials, in no particular order.
+    pub fn names(&self) -> Vec<String> {
+        self.materials.keys().cloned().collect()
+    }
+
+    /// The name `material` is registered under, if any.
+    pub fn name_of(&self, material: &Rc<RefCell<Box<dyn Material3d + 'static>>>) -> Option<String> {
+        self.materials
+            .iter()
+            .find(|(_, m)| Rc::ptr_eq(m, material))
+            .map(|(name, _)| name.clone())
+    }
+
     /// Removes a mesh from this cache.
     pub fn remove(&mut self, name: &str) {
         let _ = self.materials.remove(name);
@@ -99,4 +150,12 @@ impl MaterialManager3d {
             material.borrow_mut().flush();
         }
     }
+
+    /// Notifies all materials that the GPU device was lost (see
+    /// `Material::on_device_lost()`).
+    pub fn device_lost(&mut self) {
+        for material in self.materials.values() {
+            material.borrow_mut().on_device_lost();
+        }
+    }
 }
@@ -1,6 +1,7 @@
 //! Data structure of a scene node geometry.
 use std::sync::{Arc, RwLock};
 
+use crate::color::Color;
 use crate::procedural::{IndexBuffer, RenderMesh};
 use crate::resource::gpu_vector::{AllocationType, BufferType, GPUVec};
 use crate::resource::vertex_index::VertexIndex;
@@ -29,6 +30,9 @@ pub struct GpuMesh3d {
     /// Optional morph-target deltas (glTF primitive targets), present only on
     /// meshes with blend shapes. Drives the GPU morph path.
     morph: Option<MorphTargets>,
+    /// Optional per-vertex linear RGBA colors, used by the
+    /// [`ShadingMode::VertexColor`](crate::scene::ShadingMode::VertexColor) shading.
+    colors: Option<Arc<RwLock<GPUVec<[f32; 4]>>>>,
 }
 
 /// Per-vertex skinning attributes for a skinned mesh: four joint indices and four
@@ -270,9 +274,58 @@ impl GpuMesh3d {
             edges: None,
             skin_vertices: None,
             morph: None,
+            colors: None,
         }
     }
 
+    /// Sets the per-vertex colors of this mesh, one per vertex of `coords`.
+    ///
+    /// They are only used by objects whose shading mode is
+    /// [`ShadingMode::VertexColor`](crate::scene::ShadingMode::VertexColor).
+    ///
+    /// When the mesh already has as many colors, they are overwritten in place
+    /// and their GPU buffer is reused, so this can be called every frame.
+    pub fn set_colors(&mut self, colors: Vec<Color>) {
+        let colors: Vec<[f32; 4]> = colors.into_iter().map(|c| [c.r, c.g, c.b, c.a]).collect();
+        if let Some(existing) = &self.colors {
+            let mut existing = existing.write().unwrap();
+            if existing.is_on_ram() && existing.len() == colors.len() {
+                existing.update_range(0, &colors);
+                return;
+            }
+        }
+        self.colors = Some(Arc::new(RwLock::new(GPUVec::new(
+            colors,
+            BufferType::Array,
+            AllocationType::StaticDraw,
+        ))));
+    }
+
+    /// A mesh sharing the geometry buffers (coordinates, faces, normals, UVs,
+    /// skinning and morph targets) of this one, but not its per-vertex colors.
+    pub(crate) fn share_geometry(&self) -> GpuMesh3d {
+        GpuMesh3d {
+            coords: self.coords.clone(),
+            faces: self.faces.clone(),
+            normals: self.normals.clone(),
+            uvs: self.uvs.clone(),
+            edges: self.edges.clone(),
+            skin_vertices: self.skin_vertices.clone(),
+            morph: self.morph.clone(),
+            colors: None,
+        }
+    }
+
+    /// Removes the per-vertex colors of this mesh.
+    pub fn clear_colors(&mut self) {
+        self.colors = None;
+    }
+
+    /// This mesh per-vertex colors, if any.
+    pub fn colors(&self) -> Option<&Arc<RwLock<GPUVec<[f32; 4]>>>> {
+        self.colors.as_ref()
+    }
+
     /// Attaches per-vertex skinning data (joint indices + weights) to this mesh,
     /// marking it as a skinned mesh. Used by the glTF loader.
     pub fn set_skin_vertices(&mut self, skin: SkinVertexData) {
@@ -290,6 +343,79 @@ impl GpuMesh3d {
         self.morph = Some(morph);
     }
 
+    /// Adds a morph target (blend shape) to this mesh and returns its index.
+    ///
+    /// `positions` (and `normals`, if given) are the vertex positions and normals of
+    /// the mesh in the target shape, one per vertex of the mesh: they are stored as
+    /// deltas from the current base mesh and blended in the vertex shader with the
+    /// weights set by [`SceneNode3d::set_morph_weights`](crate::scene::SceneNode3d::set_morph_weights),
+    /// e.g. to interpolate smoothly between two simulation states. Targets added
+    /// without normals leave the base normals untouched.
+    ///
+    /// # Panics
+    /// Panics if the slices don't have one element per vertex, if the mesh
+    /// vertices aren't available on the CPU, or if the mesh already has
+    /// [`MAX_MORPH_TARGETS`](crate::builtin::deform::MAX_MORPH_TARGETS) targets.
+    pub fn add_morph_target(&mut self, positions: &[Vec3], normals: Option<&[Vec3]>) -> usize {
+        let num_targets = self.morph_target_count();
+        assert!(
+            num_targets < crate::builtin::deform::MAX_MORPH_TARGETS,
+            "A mesh cannot have more than {} morph targets",
+            crate::builtin::deform::MAX_MORPH_TARGETS
+        );
+
+        let coords = self.coords.read().unwrap();
+        let base = coords
+            .data()
+            .as_ref()
+            .expect("The mesh vertices must be available on the CPU to add a morph target");
+        let num_vertices = base.len();
+        assert_eq!(
+            positions.len(),
+            num_vertices,
+            "A morph target must have one position per vertex"
+        );
+
+        let delta = |a: Vec3, b: Vec3| {
+            let d = a - b;
+            [d.x, d.y, d.z, 0.0]
+        };
+        let read_deltas = |buf: Option<&Arc<RwLock<GPUVec<[f32; 4]>>>>| {
+            buf.and_then(|b| b.read().unwrap().to_owned())
+        };
+
+        let mut all_positions = read_deltas(self.morph_positions()).unwrap_or_default();
+        all_positions.extend(positions.iter().zip(base).map(|(p, b)| delta(*p, *b)));
+
+        let mut all_normals = read_deltas(self.morph_normals());
+        if let Some(normals) = normals {
+            assert_eq!(
+                normals.len(),
+                num_vertices,
+                "A morph target must have one normal per vertex"
+            );
+            let base_normals = self.normals.read().unwrap().to_owned().unwrap_or_default();
+            let all = all_normals.get_or_insert_with(|| vec![[0.0; 4]; num_targets * num_vertices]);
+            all.extend(
+                normals
+                    .iter()
+                    .enumerate()
+                    .map(|(i, n)| delta(*n, base_normals.get(i).copied().unwrap_or(*n))),
+            );
+        } else if let Some(all) = &mut all_normals {
+            all.extend(std::iter::repeat_n([0.0; 4], num_vertices));
+        }
+        drop(coords);
+
+        self.morph = Some(MorphTargets::new(
+            num_targets + 1,
+            num_vertices,
+            all_positions,
+            all_normals,
+        ));
+        num_targets
+    }
+
     /// Whether this mesh carries morph-target deltas.
     pub fn has_morph(&self) -> bool {
         self.morph.is_some()
@@ -535,36 +661,7 @@ impl GpuMesh3d {
         faces: &[[VertexIndex; 3]],
         normals: &mut Vec<Vec3>,
     ) {
-        let mut divisor: Vec<f32> = vec![0f32; coordinates.len()];
-
-        normals.clear();
-        normals.extend(std::iter::repeat_n(Vec3::ZERO, coordinates.len()));
-
-        // Accumulate normals ...
-        for f in faces.iter() {
-            let edge1 = coordinates[f[1] as usize] - coordinates[f[0] as usize];
-            let edge2 = coordinates[f[2] as usize] - coordinates[f[0] as usize];
-            let cross = edge1.cross(edge2);
-
-            let normal = if cross != Vec3::ZERO {
-                cross.normalize()
-            } else {
-                cross
-            };
-
-            normals[f[0] as usize] += normal;
-            normals[f[1] as usize] += normal;
-            normals[f[2] as usize] += normal;
-
-            divisor[f[0] as usize] += 1.0;
-            divisor[f[1] as usize] += 1.0;
-            divisor[f[2] as usize] += 1.0;
-        }
-
-        // ... and compute the mean
-        for (n, divisor) in normals.iter_mut().zip(divisor.iter()) {
-            *n /= *divisor
-        }
+        crate::procedural::utils::compute_normals(coordinates, faces, normals);
     }
 }
 
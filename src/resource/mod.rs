@@ -4,7 +4,7 @@ pub use crate::resource::dynamic_buffer::DynamicUniformBuffer;
 pub use crate::resource::framebuffer_manager::{
     FramebufferManager, OffscreenBuffers, RenderTarget,
 };
-pub use crate::resource::gpu_vector::{AllocationType, BufferType, GPUVec};
+pub use crate::resource::gpu_vector::{AllocationType, BufferType, GPUVec, UploadMode};
 pub use crate::resource::material::{
     EnvLight, GpuData, Material2d, Material3d, ProbeData, ProbeLighting, RenderContext,
     RenderContext2d, RenderContext2dEncoder, RenderPhase, ShadowResources,
@@ -16,11 +16,12 @@ pub use crate::resource::mesh3d::{GpuMesh3d, MorphTargets, SkinVertexData};
 pub use crate::resource::mesh_manager2d::MeshManager2d;
 pub use crate::resource::mesh_manager3d::MeshManager3d;
 pub use crate::resource::pipeline_cache::{multisample_state, PipelineCache};
+pub use crate::resource::texture_loader::TextureLoad;
 pub use crate::resource::texture_manager::{Texture, TextureManager, TextureWrapping};
 
 mod dynamic_buffer;
 mod framebuffer_manager;
-mod gpu_vector;
+pub(crate) mod gpu_vector;
 pub mod material;
 mod material_manager2d;
 mod material_manager3d;
@@ -29,5 +30,6 @@ mod mesh3d;
 mod mesh_manager2d;
 mod mesh_manager3d;
 mod pipeline_cache;
+mod texture_loader;
 mod texture_manager;
 pub mod vertex_index;
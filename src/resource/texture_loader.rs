@@ -0,0 +1,558 @@
+//! Background loading of large textures.
+
+use std::cell::RefCell;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::sync::Arc;
+
+use image::{GenericImageView, ImageFormat};
+
+use crate::context::Context;
+use crate::error::Error;
+use crate::resource::{Texture, TextureManager};
+
+/// The default number of bytes uploaded to the GPU per frame by the background
+/// texture loads.
+pub(crate) const DEFAULT_UPLOAD_BUDGET: usize = 16 * 1024 * 1024;
+
+/// The maximum number of worker threads decoding the background texture loads.
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_DECODING_THREADS: usize = 4;
+
+/// A texture being loaded in the background, created by
+/// [`TextureManager::add_async`].
+///
+/// The image is decoded, and its mipmaps generated, by a small pool of worker
+/// threads (on the web, where there are no threads, on the main thread during
+/// the first frame after the request). It is then uploaded to the GPU in slices over the next
+/// frames, so even very large textures don't stall rendering. Once uploaded,
+/// the texture is registered by the texture manager under its name and handed
+/// to the [`on_ready`](Self::on_ready) callbacks.
+///
+/// Cloning the handle gives another handle to the same load.
+#[derive(Clone)]
+pub struct TextureLoad {
+    state: Rc<RefCell<LoadState>>,
+}
+
+struct LoadState {
+    name: String,
+    progress: f32,
+    texture: Option<Arc<Texture>>,
+    error: Option<String>,
+    callbacks: Vec<Box<dyn FnOnce(&Arc<Texture>)>>,
+}
+
+impl TextureLoad {
+    fn new(name: &str) -> Self {
+        TextureLoad {
+            state: Rc::new(RefCell::new(LoadState {
+                name: name.to_string(),
+                progress: 0.0,
+                texture: None,
+                error: None,
+                callbacks: Vec::new(),
+            })),
+        }
+    }
+
+    fn ready(name: &str, texture: Arc<Texture>) -> Self {
+        let load = Self::new(name);
+        {
+            let mut state = load.state.borrow_mut();
+            state.progress = 1.0;
+            state.texture = Some(texture);
+        }
+        load
+    }
+
+    /// The name the texture is registered under once loaded.
+    pub fn name(&self) -> String {
+        self.state.borrow().name.clone()
+    }
+
+    /// The fraction of the load done, from `0.0` (decoding) to `1.0` (ready).
+    ///
+    /// The decoding (reading the file, decoding the image and generating its
+    /// mipmaps) counts for the first tenth; the rest follows the upload.
+    pub fn progress(&self) -> f32 {
+        self.state.borrow().progress
+    }
+
+    /// Whether the texture is loaded and uploaded.
+    pub fn is_ready(&self) -> bool {
+        self.state.borrow().texture.is_some()
+    }
+
+    /// The loaded texture, or `None` while it is loading or if it failed.
+    pub fn texture(&self) -> Option<Arc<Texture>> {
+        self.state.borrow().texture.clone()
+    }
+
+    /// The error message if the image could not be opened or decoded.
+    pub fn error(&self) -> Option<String> {
+        self.state.borrow().error.clone()
+    }
+
+    /// Calls `f` with the texture once it is uploaded, at the beginning of a
+    /// frame, or right away if it is already. It is never called if the load
+    /// fails.
+    pub fn on_ready(&self, f: impl FnOnce(&Arc<Texture>) + 'static) {
+        let texture = self.texture();
+        match texture {
+            Some(texture) => f(&texture),
+            None => self.state.borrow_mut().callbacks.push(Box::new(f)),
+        }
+    }
+}
+
+/// An image decoded by a worker: the RGBA data of each mip level.
+struct DecodedImage {
+    width: u32,
+    height: u32,
+    levels: Vec<Vec<u8>>,
+}
+
+/// Where the encoded image of a background load comes from.
+enum ImageSource {
+    File(PathBuf),
+    Memory(Vec<u8>),
+}
+
+impl ImageSource {
+    fn path(&self) -> Option<PathBuf> {
+        match self {
+            ImageSource::File(path) => Some(path.clone()),
+            ImageSource::Memory(_) => None,
+        }
+    }
+}
+
+/// The fraction of a decoding done, written by the worker and read by the
+/// texture manager.
+#[derive(Default)]
+struct DecodeProgress(AtomicU32);
+
+impl DecodeProgress {
+    fn set(&self, fraction: f32) {
+        self.0.store(fraction.to_bits(), Ordering::Relaxed);
+    }
+
+    fn get(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+}
+
+enum UploadStage {
+    /// Waiting for the worker decoding the image.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    Decoding {
+        path: Option<PathBuf>,
+        receiver: Receiver<Result<DecodedImage, Error>>,
+        progress: Arc<DecodeProgress>,
+    },
+    /// Decoding on the main thread, which has no workers.
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    DecodingLater(ImageSource),
+    /// Uploading the rows of the mip levels, level by level.
+    Uploading {
+        image: DecodedImage,
+        texture: wgpu::Texture,
+        level: usize,
+        row: u32,
+    },
+}
+
+/// A background texture load tracked by the texture manager.
+pub(crate) struct PendingUpload {
+    load: TextureLoad,
+    /// The file the image is read from, if any.
+    path: Option<PathBuf>,
+    stage: UploadStage,
+    generate_mipmaps: bool,
+}
+
+impl TextureManager {
+    /// Loads a texture from a file in the background, e.g. a large color map
+    /// that would stall the first frame if loaded with [`add`](Self::add).
+    ///
+    /// Returns a handle to follow the load; the texture is registered as `name`
+    /// once uploaded. Use
+    /// [`SceneNode3d::set_texture_async`](crate::scene::SceneNode3d::set_texture_async)
+    /// to show it on an object as soon as it is ready. Mipmaps are generated if
+    /// enabled by [`set_generate_mipmaps`](Self::set_generate_mipmaps). If a
+    /// texture with same name exists, nothing is loaded and the returned handle
+    /// is already ready.
+    ///
+    /// The loads progress while the window renders frames.
+    ///
+    /// On the web, where files can't be read, the load fails right away: fetch
+    /// the file and use [`add_async_from_memory`](Self::add_async_from_memory)
+    /// instead.
+    pub fn add_async(&mut self, path: &Path, name: &str) -> TextureLoad {
+        if cfg!(target_arch = "wasm32") && self.get(name).is_none() {
+            let load = TextureLoad::new(name);
+            let e = Error::TextureLoad {
+                path: Some(path.to_path_buf()),
+                source: image::ImageError::IoError(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "files can't be read on the web, use `add_async_from_memory`",
+                )),
+            };
+            log::error!("{}", e);
+            load.state.borrow_mut().error = Some(e.to_string());
+            return load;
+        }
+
+        self.add_async_from_source(ImageSource::File(path.to_path_buf()), name)
+    }
+
+    /// Like [`add_async`](Self::add_async), but decodes an encoded image (e.g. the
+    /// content of a PNG or JPEG file) already in memory. This works on the web
+    /// too.
+    pub fn add_async_from_memory(&mut self, image_data: Vec<u8>, name: &str) -> TextureLoad {
+        self.add_async_from_source(ImageSource::Memory(image_data), name)
+    }
+
+    fn add_async_from_source(&mut self, source: ImageSource, name: &str) -> TextureLoad {
+        if let Some(texture) = self.get(name) {
+            return TextureLoad::ready(name, texture);
+        }
+
+        let load = TextureLoad::new(name);
+        let path = source.path();
+        let generate_mipmaps = self.generate_mipmaps;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let stage = {
+            let (sender, receiver) = std::sync::mpsc::channel();
+            let progress = Arc::new(DecodeProgress::default());
+            let worker_progress = progress.clone();
+            spawn_decoding(Box::new(move || {
+                let _ = sender.send(decode_image(&source, generate_mipmaps, &worker_progress));
+            }));
+            UploadStage::Decoding {
+                path: path.clone(),
+                receiver,
+                progress,
+            }
+        };
+        #[cfg(target_arch = "wasm32")]
+        let stage = UploadStage::DecodingLater(source);
+
+        self.pending_uploads.push(PendingUpload {
+            load: load.clone(),
+            path,
+            stage,
+            generate_mipmaps,
+        });
+        load
+    }
+
+    /// The number of bytes the background texture loads upload to the GPU per
+    /// frame (see [`add_async`](Self::add_async)).
+    pub fn upload_budget(&self) -> usize {
+        self.upload_budget
+    }
+
+    /// Sets the number of bytes the background texture loads upload to the GPU
+    /// per frame (default: 16 MiB). Larger budgets load faster but make the
+    /// frames that upload longer. At least one texture row is uploaded per
+    /// frame, even if it is larger than the budget.
+    pub fn set_upload_budget(&mut self, bytes: usize) {
+        self.upload_budget = bytes.max(1);
+    }
+
+    /// Advances the background texture loads by one frame, calling the
+    /// [`TextureLoad::on_ready`] callbacks of the finished ones.
+    pub(crate) fn pump_uploads() {
+        let finished = TextureManager::get_global_manager(|tm| tm.advance_uploads());
+        // Called outside of the manager so callbacks may use it.
+        for (load, texture) in finished {
+            let callbacks = std::mem::take(&mut load.state.borrow_mut().callbacks);
+            for callback in callbacks {
+                callback(&texture);
+            }
+        }
+    }
+
+    fn advance_uploads(&mut self) -> Vec<(TextureLoad, Arc<Texture>)> {
+        let mut budget = self.upload_budget;
+        let mut uploaded = false;
+        let mut finished = Vec::new();
+        let mut i = 0;
+
+        while i < self.pending_uploads.len() {
+            let pending = &mut self.pending_uploads[i];
+            match advance_upload(pending, &mut budget, &mut uploaded) {
+                Ok(None) => i += 1,
+                Ok(Some(texture)) => {
+                    let pending = self.pending_uploads.swap_remove(i);
+                    let name = pending.load.name();
+                    if let (Some(path), false) = (pending.path, self.textures.contains_key(&name)) {
+                        let _ = self.paths.insert(name.clone(), path);
+                    }
+                    let texture = self.textures.entry(name).or_insert(texture).clone();
+                    {
+                        let mut state = pending.load.state.borrow_mut();
+                        state.progress = 1.0;
+                        state.texture = Some(texture.clone());
+                    }
+                    finished.push((pending.load, texture));
+                }
+                Err(e) => {
+                    let pending = self.pending_uploads.swap_remove(i);
+                    log::error!("{}", e);
+                    pending.load.state.borrow_mut().error = Some(e.to_string());
+                }
+            }
+        }
+
+        finished
+    }
+}
+
+/// Advances `pending` within the byte `budget`, returning the texture once
+/// fully uploaded. `uploaded` tracks whether any row was uploaded this frame.
+fn advance_upload(
+    pending: &mut PendingUpload,
+    budget: &mut usize,
+    uploaded: &mut bool,
+) -> Result<Option<Arc<Texture>>, Error> {
+    loop {
+        match &mut pending.stage {
+            UploadStage::Decoding {
+                path,
+                receiver,
+                progress,
+            } => match receiver.try_recv() {
+                Ok(image) => pending.stage = start_upload(image?),
+                Err(TryRecvError::Empty) => {
+                    pending.load.state.borrow_mut().progress = 0.1 * progress.get();
+                    return Ok(None);
+                }
+                Err(TryRecvError::Disconnected) => {
+                    return Err(Error::TextureLoad {
+                        path: path.clone(),
+                        source: image::ImageError::IoError(io::Error::other(
+                            "the decoding thread panicked",
+                        )),
+                    })
+                }
+            },
+            UploadStage::DecodingLater(source) => {
+                let progress = DecodeProgress::default();
+                let image = decode_image(source, pending.generate_mipmaps, &progress)?;
+                pending.stage = start_upload(image);
+            }
+            UploadStage::Uploading {
+                image,
+                texture,
+                level,
+                row,
+            } => {
+                let ctxt = Context::get();
+                let level_width = (image.width >> *level).max(1);
+                let level_height = (image.height >> *level).max(1);
+                let bytes_per_row = level_width as usize * 4;
+
+                let mut rows = (*budget / bytes_per_row) as u32;
+                if rows == 0 {
+                    // Upload at least one row per frame so that a budget smaller
+                    // than a row doesn't stall the load forever.
+                    if *uploaded {
+                        return Ok(None);
+                    }
+                    rows = 1;
+                }
+                let rows = rows.min(level_height - *row);
+                *budget = budget.saturating_sub(rows as usize * bytes_per_row);
+                *uploaded = true;
+
+                let start = *row as usize * bytes_per_row;
+                let end = start + rows as usize * bytes_per_row;
+                ctxt.write_texture(
+                    wgpu::TexelCopyTextureInfo {
+                        texture,
+                        mip_level: *level as u32,
+                        origin: wgpu::Origin3d {
+                            x: 0,
+                            y: *row,
+                            z: 0,
+                        },
+                        aspect: wgpu::TextureAspect::All,
+                    },
+                    &image.levels[*level][start..end],
+                    wgpu::TexelCopyBufferLayout {
+                        offset: 0,
+                        bytes_per_row: Some(bytes_per_row as u32),
+                        rows_per_image: Some(rows),
+                    },
+                    wgpu::Extent3d {
+                        width: level_width,
+                        height: rows,
+                        depth_or_array_layers: 1,
+                    },
+                );
+
+                *row += rows;
+                if *row == level_height {
+                    *row = 0;
+                    *level += 1;
+                }
+
+                let total: usize = image.levels.iter().map(|l| l.len()).sum();
+                let done: usize = image.levels[..*level]
+                    .iter()
+                    .map(|l| l.len())
+                    .sum::<usize>()
+                    + *row as usize * bytes_per_row;
+                pending.load.state.borrow_mut().progress = 0.1 + 0.9 * done as f32 / total as f32;
+
+                if *level == image.levels.len() {
+                    return Ok(Some(Texture::from_uploaded(
+                        texture.clone(),
+                        (image.width, image.height),
+                        image.levels.len() > 1,
+                    )));
+                }
+            }
+        }
+    }
+}
+
+/// Creates the GPU texture of a decoded image, to be uploaded in slices.
+fn start_upload(image: DecodedImage) -> UploadStage {
+    let texture = Context::get().create_texture(&wgpu::TextureDescriptor {
+        label: Some("texture"),
+        size: wgpu::Extent3d {
+            width: image.width,
+            height: image.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: image.levels.len() as u32,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8UnormSrgb,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+
+    UploadStage::Uploading {
+        image,
+        texture,
+        level: 0,
+        row: 0,
+    }
+}
+
+/// Runs `job` on one of the decoding workers, started on first use.
+#[cfg(not(target_arch = "wasm32"))]
+fn spawn_decoding(job: Box<dyn FnOnce() + Send>) {
+    use std::sync::mpsc::Sender;
+    use std::sync::{Mutex, OnceLock};
+
+    static JOBS: OnceLock<Sender<Box<dyn FnOnce() + Send>>> = OnceLock::new();
+    let jobs = JOBS.get_or_init(|| {
+        let (sender, receiver) = std::sync::mpsc::channel::<Box<dyn FnOnce() + Send>>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let threads = std::thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .min(MAX_DECODING_THREADS);
+        for _ in 0..threads {
+            let receiver = receiver.clone();
+            let _ = std::thread::spawn(move || loop {
+                let job = receiver.lock().unwrap().recv();
+                match job {
+                    // A panicking job drops its result sender, which fails its
+                    // load, but must not take the worker down with it.
+                    Ok(job) => {
+                        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(job));
+                    }
+                    Err(_) => break,
+                }
+            });
+        }
+        sender
+    });
+    let _ = jobs.send(job);
+}
+
+/// Reads and decodes the image of `source` to RGBA, with its mip chain if
+/// `generate_mipmaps` is set, reporting its advance to `progress`.
+fn decode_image(
+    source: &ImageSource,
+    generate_mipmaps: bool,
+    progress: &DecodeProgress,
+) -> Result<DecodedImage, Error> {
+    let error = |source| Error::TextureLoad { path: None, source };
+    let image = match source {
+        ImageSource::File(path) => {
+            let file_error = |source| Error::TextureLoad {
+                path: Some(path.clone()),
+                source,
+            };
+            let data =
+                std::fs::read(path).map_err(|e| file_error(image::ImageError::IoError(e)))?;
+            progress.set(0.2);
+            match ImageFormat::from_path(path) {
+                Ok(format) => image::load_from_memory_with_format(&data, format),
+                Err(_) => image::load_from_memory(&data),
+            }
+            .map_err(file_error)?
+        }
+        ImageSource::Memory(data) => image::load_from_memory(data).map_err(error)?,
+    };
+    progress.set(0.6);
+    let (width, height) = image.dimensions();
+    let mut levels = vec![image.to_rgba8().into_raw()];
+    progress.set(0.7);
+
+    if generate_mipmaps {
+        // Each level has a quarter of the texels of the previous one, so the
+        // first one is about three quarters of the work.
+        let (mut w, mut h) = (width, height);
+        let mut done = 0.0;
+        while w > 1 || h > 1 {
+            let next = Texture::downsample_rgba(&levels[levels.len() - 1], w, h, true);
+            levels.push(next);
+            w = (w / 2).max(1);
+            h = (h / 2).max(1);
+            done = 0.75 + done / 4.0;
+            progress.set(0.7 + 0.3 * done);
+        }
+    }
+    progress.set(1.0);
+
+    Ok(DecodedImage {
+        width,
+        height,
+        levels,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decoding_reports_progress_and_builds_the_mip_chain() {
+        let mut png = Vec::new();
+        image::DynamicImage::new_rgba8(8, 4)
+            .write_to(&mut io::Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+
+        let progress = DecodeProgress::default();
+        let image = decode_image(&ImageSource::Memory(png), true, &progress).unwrap();
+        assert_eq!((image.width, image.height), (8, 4));
+        let sizes: Vec<_> = image.levels.iter().map(|l| l.len()).collect();
+        assert_eq!(sizes, [8 * 4 * 4, 4 * 2 * 4, 2 * 4, 4]);
+        assert_eq!(progress.get(), 1.0);
+
+        let error = decode_image(&ImageSource::Memory(vec![1, 2, 3]), true, &progress);
+        assert!(error.is_err());
+    }
+}
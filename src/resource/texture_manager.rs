@@ -3,10 +3,12 @@
 use image::{self, DynamicImage, GenericImageView};
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use crate::context::Context;
+use crate::error::Error;
+use crate::resource::texture_loader::{self, PendingUpload};
 
 /// Wrapping parameters for a texture.
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
@@ -41,6 +43,9 @@ pub struct Texture {
     pub sampler: wgpu::Sampler,
     /// Texture dimensions (width, height).
     pub size: (u32, u32),
+    /// Number of layers: `1` for plain textures, the layer count of a
+    /// [texture array](Self::new_array).
+    pub layers: u32,
 }
 
 impl Texture {
@@ -53,6 +58,106 @@ impl Texture {
         address_mode: wgpu::AddressMode,
         filter: wgpu::FilterMode,
         generate_mipmaps: bool,
+    ) -> Arc<Texture> {
+        Self::with_layers(
+            width,
+            height,
+            &[data],
+            false,
+            format,
+            address_mode,
+            filter,
+            generate_mipmaps,
+        )
+    }
+
+    /// Creates a new 2D array texture with one layer per element of `layers`,
+    /// each holding the RGBA data of a `width × height` image.
+    ///
+    /// Its view is a `texture_2d_array`, sampled by the instanced objects given
+    /// this texture with
+    /// [`SceneNode3d::set_texture_array`](crate::scene::SceneNode3d::set_texture_array).
+    ///
+    /// # Panics
+    /// Panics if `layers` is empty.
+    pub fn new_array(
+        width: u32,
+        height: u32,
+        layers: &[&[u8]],
+        format: wgpu::TextureFormat,
+        address_mode: wgpu::AddressMode,
+        filter: wgpu::FilterMode,
+        generate_mipmaps: bool,
+    ) -> Arc<Texture> {
+        assert!(
+            !layers.is_empty(),
+            "A texture array needs at least one layer"
+        );
+        Self::with_layers(
+            width,
+            height,
+            layers,
+            true,
+            format,
+            address_mode,
+            filter,
+            generate_mipmaps,
+        )
+    }
+
+    /// Creates an empty HDR (`Rgba16Float`) cube-map texture with `size × size`
+    /// faces that can be rendered to, one layer per face.
+    ///
+    /// Its view is a `texture_cube`. This is the texture returned by
+    /// [`Window::capture_cubemap`](crate::window::Window::capture_cubemap).
+    pub fn new_cubemap_target(size: u32) -> Arc<Texture> {
+        let ctxt = Context::get();
+        let texture = ctxt.create_texture(&wgpu::TextureDescriptor {
+            label: Some("cubemap_texture"),
+            size: wgpu::Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: 6,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba16Float,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(wgpu::TextureViewDimension::Cube),
+            ..Default::default()
+        });
+        let sampler = ctxt.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("cubemap_sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Arc::new(Texture {
+            texture,
+            view,
+            sampler,
+            size: (size, size),
+            layers: 6,
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn with_layers(
+        width: u32,
+        height: u32,
+        layers: &[&[u8]],
+        array: bool,
+        format: wgpu::TextureFormat,
+        address_mode: wgpu::AddressMode,
+        filter: wgpu::FilterMode,
+        generate_mipmaps: bool,
     ) -> Arc<Texture> {
         let ctxt = Context::get();
 
@@ -67,7 +172,7 @@ impl Texture {
             size: wgpu::Extent3d {
                 width,
                 height,
-                depth_or_array_layers: 1,
+                depth_or_array_layers: layers.len() as u32,
             },
             mip_level_count,
             sample_count: 1,
@@ -82,70 +187,85 @@ impl Texture {
             _ => 4, // Default to 4
         };
 
-        // Upload mip level 0
-        ctxt.write_texture(
-            wgpu::TexelCopyTextureInfo {
-                texture: &texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            data,
-            wgpu::TexelCopyBufferLayout {
-                offset: 0,
-                bytes_per_row: Some(width * bytes_per_pixel),
-                rows_per_image: Some(height),
-            },
-            wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            },
-        );
+        for (layer, data) in layers.iter().enumerate() {
+            let origin = wgpu::Origin3d {
+                x: 0,
+                y: 0,
+                z: layer as u32,
+            };
 
-        // Generate and upload remaining mip levels
-        if generate_mipmaps && mip_level_count > 1 {
-            // Color (sRGB) textures must be averaged in linear space; data
-            // textures (normal/metallic-roughness/AO) are already linear.
-            let srgb = matches!(format, wgpu::TextureFormat::Rgba8UnormSrgb);
-            let mut current_data = data.to_vec();
-            let mut current_width = width;
-            let mut current_height = height;
-
-            for mip_level in 1..mip_level_count {
-                let new_width = (current_width / 2).max(1);
-                let new_height = (current_height / 2).max(1);
-
-                let new_data =
-                    Self::downsample_rgba(&current_data, current_width, current_height, srgb);
-
-                ctxt.write_texture(
-                    wgpu::TexelCopyTextureInfo {
-                        texture: &texture,
-                        mip_level,
-                        origin: wgpu::Origin3d::ZERO,
-                        aspect: wgpu::TextureAspect::All,
-                    },
-                    &new_data,
-                    wgpu::TexelCopyBufferLayout {
-                        offset: 0,
-                        bytes_per_row: Some(new_width * bytes_per_pixel),
-                        rows_per_image: Some(new_height),
-                    },
-                    wgpu::Extent3d {
-                        width: new_width,
-                        height: new_height,
-                        depth_or_array_layers: 1,
-                    },
-                );
-
-                current_data = new_data;
-                current_width = new_width;
-                current_height = new_height;
+            // Upload mip level 0
+            ctxt.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &texture,
+                    mip_level: 0,
+                    origin,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                data,
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(width * bytes_per_pixel),
+                    rows_per_image: Some(height),
+                },
+                wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+            );
+
+            // Generate and upload remaining mip levels
+            if generate_mipmaps && mip_level_count > 1 {
+                // Color (sRGB) textures must be averaged in linear space; data
+                // textures (normal/metallic-roughness/AO) are already linear.
+                let srgb = matches!(format, wgpu::TextureFormat::Rgba8UnormSrgb);
+                let mut current_data = data.to_vec();
+                let mut current_width = width;
+                let mut current_height = height;
+
+                for mip_level in 1..mip_level_count {
+                    let new_width = (current_width / 2).max(1);
+                    let new_height = (current_height / 2).max(1);
+
+                    let new_data =
+                        Self::downsample_rgba(&current_data, current_width, current_height, srgb);
+
+                    ctxt.write_texture(
+                        wgpu::TexelCopyTextureInfo {
+                            texture: &texture,
+                            mip_level,
+                            origin,
+                            aspect: wgpu::TextureAspect::All,
+                        },
+                        &new_data,
+                        wgpu::TexelCopyBufferLayout {
+                            offset: 0,
+                            bytes_per_row: Some(new_width * bytes_per_pixel),
+                            rows_per_image: Some(new_height),
+                        },
+                        wgpu::Extent3d {
+                            width: new_width,
+                            height: new_height,
+                            depth_or_array_layers: 1,
+                        },
+                    );
+
+                    current_data = new_data;
+                    current_width = new_width;
+                    current_height = new_height;
+                }
             }
         }
 
-        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(if array {
+                wgpu::TextureViewDimension::D2Array
+            } else {
+                wgpu::TextureViewDimension::D2
+            }),
+            ..Default::default()
+        });
 
         let sampler = ctxt.create_sampler(&wgpu::SamplerDescriptor {
             label: Some("texture_sampler"),
@@ -167,6 +287,40 @@ impl Texture {
             view,
             sampler,
             size: (width, height),
+            layers: layers.len() as u32,
+        })
+    }
+
+    /// Wraps an sRGB texture whose levels were all uploaded, sampled with
+    /// bilinear filtering and clamped to its edges like
+    /// [`TextureManager::add_image`] textures.
+    pub(crate) fn from_uploaded(
+        texture: wgpu::Texture,
+        size: (u32, u32),
+        mipmapped: bool,
+    ) -> Arc<Texture> {
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = Context::get().create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("texture_sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: if mipmapped {
+                wgpu::MipmapFilterMode::Linear
+            } else {
+                wgpu::MipmapFilterMode::Nearest
+            },
+            ..Default::default()
+        });
+
+        Arc::new(Texture {
+            texture,
+            view,
+            sampler,
+            size,
+            layers: 1,
         })
     }
 
@@ -176,7 +330,7 @@ impl Texture {
     /// averaging and re-encoded afterward (alpha is always linear), so color
     /// mip chains don't darken — the gamma-correct behavior. Data textures pass
     /// `srgb = false` and are averaged directly.
-    fn downsample_rgba(data: &[u8], width: u32, height: u32, srgb: bool) -> Vec<u8> {
+    pub(crate) fn downsample_rgba(data: &[u8], width: u32, height: u32, srgb: bool) -> Vec<u8> {
         // sRGB transfer-function helpers (IEC 61966-2-1).
         fn srgb_to_linear(u: u8) -> f32 {
             let c = u as f32 / 255.0;
@@ -347,8 +501,14 @@ impl Texture {
 /// It keeps a cache of already-loaded textures, and can load new textures.
 pub struct TextureManager {
     default_texture: Arc<Texture>,
-    textures: HashMap<String, Arc<Texture>>,
-    generate_mipmaps: bool,
+    pub(super) textures: HashMap<String, Arc<Texture>>,
+    /// The files the textures loaded from one were read from, by name.
+    pub(super) paths: HashMap<String, PathBuf>,
+    pub(super) generate_mipmaps: bool,
+    /// The textures loading in the background (see [`Self::add_async`]).
+    pub(super) pending_uploads: Vec<PendingUpload>,
+    /// Bytes uploaded per frame by the background loads.
+    pub(super) upload_budget: usize,
 }
 
 impl Default for TextureManager {
@@ -364,8 +524,11 @@ impl TextureManager {
 
         TextureManager {
             textures: HashMap::new(),
+            paths: HashMap::new(),
             default_texture,
             generate_mipmaps: false,
+            pending_uploads: Vec::new(),
+            upload_budget: texture_loader::DEFAULT_UPLOAD_BUDGET,
         }
     }
 
@@ -385,6 +548,20 @@ impl TextureManager {
         self.textures.get(name).cloned()
     }
 
+    /// The name `texture` is registered under, if any.
+    pub fn name_of(&self, texture: &Arc<Texture>) -> Option<String> {
+        self.textures
+            .iter()
+            .find(|(_, t)| Arc::ptr_eq(t, texture))
+            .map(|(name, _)| name.clone())
+    }
+
+    /// The file `texture` was loaded from, if it was loaded from a file by this
+    /// manager.
+    pub(crate) fn path_of(&self, texture: &Arc<Texture>) -> Option<PathBuf> {
+        self.paths.get(&self.name_of(texture)?).cloned()
+    }
+
     /// Get a texture (and its size) with the specified name. Returns `None` if the texture is not registered.
     pub fn get_with_size(&mut self, name: &str) -> Option<(Arc<Texture>, (u32, u32))> {
         self.textures.get(name).map(|t| (t.clone(), t.size))
@@ -440,6 +617,24 @@ impl TextureManager {
         )
     }
 
+    /// Allocates a new texture decoded from a bytes array, returning an error
+    /// instead of panicking if the data cannot be decoded.
+    ///
+    /// If a texture with same name exists, nothing is decoded and the old texture is returned.
+    pub fn try_add_image_from_memory(
+        &mut self,
+        image_data: &[u8],
+        name: &str,
+    ) -> Result<Arc<Texture>, Error> {
+        if let Some(texture) = self.textures.get(name) {
+            return Ok(texture.clone());
+        }
+
+        let image = image::load_from_memory(image_data)
+            .map_err(|source| Error::TextureLoad { path: None, source })?;
+        Ok(self.add_image(image, name))
+    }
+
     /// Like [`add_image_from_memory`](Self::add_image_from_memory) but with
     /// nearest-neighbor filtering, for pixel-art / sprite-sheet textures (see
     /// [`add_image_pixelated`](Self::add_image_pixelated)).
@@ -491,6 +686,52 @@ impl TextureManager {
             .clone()
     }
 
+    /// Allocates a new texture array with one layer per image, for per-instance
+    /// textures (see
+    /// [`SceneNode3d::set_texture_array`](crate::scene::SceneNode3d::set_texture_array)).
+    ///
+    /// All the layers share the size of the first image: the other images are
+    /// resized to match it. If a texture with same name exists, nothing is created
+    /// and the old texture is returned.
+    ///
+    /// # Panics
+    /// Panics if `images` is empty.
+    pub fn add_image_array(&mut self, images: Vec<DynamicImage>, name: &str) -> Arc<Texture> {
+        assert!(
+            !images.is_empty(),
+            "A texture array needs at least one image"
+        );
+        let generate_mipmaps = self.generate_mipmaps;
+        self.textures
+            .entry(name.to_string())
+            .or_insert_with(|| {
+                let (width, height) = images[0].dimensions();
+                let layers: Vec<_> = images
+                    .iter()
+                    .map(|image| {
+                        if image.dimensions() == (width, height) {
+                            image.to_rgba8()
+                        } else {
+                            image
+                                .resize_exact(width, height, image::imageops::FilterType::Triangle)
+                                .to_rgba8()
+                        }
+                    })
+                    .collect();
+                let layer_data: Vec<&[u8]> = layers.iter().map(|l| l.as_raw().as_slice()).collect();
+                Texture::new_array(
+                    width,
+                    height,
+                    &layer_data,
+                    wgpu::TextureFormat::Rgba8UnormSrgb,
+                    wgpu::AddressMode::Repeat,
+                    wgpu::FilterMode::Linear,
+                    generate_mipmaps,
+                )
+            })
+            .clone()
+    }
+
     /// Loads a texture from a DynamicImage.
     fn load_texture_from_image(
         image: DynamicImage,
@@ -531,6 +772,40 @@ impl TextureManager {
         self.add_filtered(path, name, wgpu::FilterMode::Linear)
     }
 
+    /// Allocates a new texture read from a file, returning an error instead of
+    /// panicking if the file cannot be opened or decoded.
+    ///
+    /// If a texture with same name exists, nothing is loaded and the old texture is returned.
+    pub fn try_add(&mut self, path: &Path, name: &str) -> Result<Arc<Texture>, Error> {
+        if let Some(texture) = self.textures.get(name) {
+            return Ok(texture.clone());
+        }
+
+        let texture = self.try_add_converted(path, name, |image| image)?;
+        let _ = self.paths.insert(name.to_string(), path.to_path_buf());
+        Ok(texture)
+    }
+
+    /// Like [`try_add`](Self::try_add), but registers the image read from `path`
+    /// as transformed by `convert`, e.g. to derive a roughness map from a specular
+    /// map. The file is not recorded as the source of the texture.
+    pub(crate) fn try_add_converted(
+        &mut self,
+        path: &Path,
+        name: &str,
+        convert: impl FnOnce(DynamicImage) -> DynamicImage,
+    ) -> Result<Arc<Texture>, Error> {
+        if let Some(texture) = self.textures.get(name) {
+            return Ok(texture.clone());
+        }
+
+        let image = image::open(path).map_err(|source| Error::TextureLoad {
+            path: Some(path.to_path_buf()),
+            source,
+        })?;
+        Ok(self.add_image(convert(image), name))
+    }
+
     /// Like [`add`](Self::add) but samples with nearest-neighbor filtering, for
     /// pixel-art / sprite-sheet textures (see
     /// [`add_image_pixelated`](Self::add_image_pixelated)).
@@ -540,9 +815,11 @@ impl TextureManager {
 
     fn add_filtered(&mut self, path: &Path, name: &str, filter: wgpu::FilterMode) -> Arc<Texture> {
         let generate_mipmaps = self.generate_mipmaps;
+        let paths = &mut self.paths;
         self.textures
             .entry(name.to_string())
             .or_insert_with(|| {
+                let _ = paths.insert(name.to_string(), path.to_path_buf());
                 TextureManager::load_texture_from_file(path, generate_mipmaps, filter)
             })
             .clone()
@@ -1,4 +1,9 @@
 /// The type used for vertex indices.
+///
+/// Indices are always 32-bit, so a single mesh may reference up to `u32::MAX`
+/// vertices; meshes with more than 65 535 vertices need no special handling.
 pub type VertexIndex = u32;
 /// The wgpu IndexFormat for the vertex index type.
+///
+/// Every pipeline binding a mesh index buffer uses this format.
 pub const VERTEX_INDEX_FORMAT: wgpu::IndexFormat = wgpu::IndexFormat::Uint32;
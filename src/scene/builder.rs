@@ -0,0 +1,184 @@
+//! Fluent construction of scene nodes.
+
+use glamx::{Quat, Vec3};
+
+use crate::color::Color;
+use crate::scene::SceneNode3d;
+
+/// A scene node being set up before being added to its parent, to create and
+/// configure a node in a single expression.
+///
+/// Created by [`SceneNode3d::build`] and the `build_*` methods of
+/// [`SceneNode3d`]. The node is added to the parent by [`spawn`](Self::spawn),
+/// and discarded if the builder is dropped instead.
+///
+/// # Example
+/// ```no_run
+/// # use kiss3d::prelude::*;
+/// # #[kiss3d::main]
+/// # async fn main() {
+/// # let _window = Window::new("Example").await;
+/// let mut scene = SceneNode3d::empty();
+/// let crate_box = scene
+///     .build_cube(1.0, 1.0, 1.0)
+///     .color(RED)
+///     .position(Vec3::new(0.0, 0.5, 0.0))
+///     .lines_width(1.5)
+///     .name("crate")
+///     .spawn();
+/// # }
+/// ```
+#[must_use = "the node is only added to its parent by `spawn`"]
+pub struct SceneNodeBuilder3d<'a> {
+    parent: &'a mut SceneNode3d,
+    node: SceneNode3d,
+}
+
+impl<'a> SceneNodeBuilder3d<'a> {
+    /// Sets the color of the node's object.
+    pub fn color(mut self, color: Color) -> Self {
+        let _ = self.node.set_color(color);
+        self
+    }
+
+    /// Sets the local translation of the node.
+    pub fn position(mut self, position: Vec3) -> Self {
+        let _ = self.node.set_position(position);
+        self
+    }
+
+    /// Sets the local rotation of the node.
+    pub fn rotation(mut self, rotation: Quat) -> Self {
+        let _ = self.node.set_rotation(rotation);
+        self
+    }
+
+    /// Sets the local scale of the node.
+    ///
+    /// The scale of a primitive applies on top of its dimensions, e.g.
+    /// `build_cube(1.0, 2.0, 3.0).scale(Vec3::splat(2.0))` builds a 2 by 4 by 6 box.
+    pub fn scale(mut self, scale: Vec3) -> Self {
+        let extents = self
+            .node
+            .primitive()
+            .map_or(Vec3::ONE, |p| p.extents_scale());
+        let scale = scale * extents;
+        let _ = self.node.set_local_scale(scale.x, scale.y, scale.z);
+        self
+    }
+
+    /// Sets the name of the node.
+    pub fn name(mut self, name: &str) -> Self {
+        let _ = self.node.set_name(name);
+        self
+    }
+
+    /// Sets the width, in pixels, of the object's wireframe lines.
+    pub fn lines_width(mut self, width: f32) -> Self {
+        let _ = self.node.set_lines_width(width, false);
+        self
+    }
+
+    /// Sets the color of the object's wireframe lines.
+    pub fn lines_color(mut self, color: Color) -> Self {
+        let _ = self.node.set_lines_color(Some(color));
+        self
+    }
+
+    /// Sets the size, in pixels, of the object's points.
+    pub fn points_size(mut self, size: f32) -> Self {
+        let _ = self.node.set_points_size(size, false);
+        self
+    }
+
+    /// Enables or disables the rendering of the object's surface, e.g. to only
+    /// show its wireframe.
+    pub fn surface(mut self, active: bool) -> Self {
+        let _ = self.node.set_surface_rendering_activation(active);
+        self
+    }
+
+    /// Sets the texture of the object to the texture registered as `name`.
+    ///
+    /// # Panics
+    /// Panics if no texture is registered as `name` (see
+    /// [`SceneNode3d::set_texture_with_name`]).
+    pub fn texture(mut self, name: &str) -> Self {
+        let _ = self.node.set_texture_with_name(name);
+        self
+    }
+
+    /// Sets the material of the object to the material registered as `name`.
+    ///
+    /// # Panics
+    /// Panics if no material is registered as `name` (see
+    /// [`SceneNode3d::set_material_with_name`]).
+    pub fn material(mut self, name: &str) -> Self {
+        let _ = self.node.set_material_with_name(name);
+        self
+    }
+
+    /// Shows or hides the node.
+    pub fn visible(mut self, visible: bool) -> Self {
+        let _ = self.node.set_visible(visible);
+        self
+    }
+
+    /// Applies `f` to the node, for the settings without a builder method.
+    pub fn with(mut self, f: impl FnOnce(&mut SceneNode3d)) -> Self {
+        f(&mut self.node);
+        self
+    }
+
+    /// Adds the node to its parent and returns it.
+    pub fn spawn(self) -> SceneNode3d {
+        self.parent.add_child(self.node.clone());
+        self.node
+    }
+}
+
+impl SceneNode3d {
+    /// Starts setting up `node` to add it as a child of this node with
+    /// [`SceneNodeBuilder3d::spawn`].
+    ///
+    /// # Panics
+    /// Panics on `spawn` if `node` already has a parent.
+    pub fn build(&mut self, node: SceneNode3d) -> SceneNodeBuilder3d<'_> {
+        SceneNodeBuilder3d { parent: self, node }
+    }
+
+    /// Starts setting up an empty group node to add as a child of this node.
+    pub fn build_group(&mut self) -> SceneNodeBuilder3d<'_> {
+        self.build(SceneNode3d::empty())
+    }
+
+    /// Starts setting up a cube to add as a child of this node (see
+    /// [`add_cube`](Self::add_cube)).
+    pub fn build_cube(&mut self, wx: f32, wy: f32, wz: f32) -> SceneNodeBuilder3d<'_> {
+        self.build(SceneNode3d::cube(wx, wy, wz))
+    }
+
+    /// Starts setting up a sphere to add as a child of this node (see
+    /// [`add_sphere`](Self::add_sphere)).
+    pub fn build_sphere(&mut self, r: f32) -> SceneNodeBuilder3d<'_> {
+        self.build(SceneNode3d::sphere(r))
+    }
+
+    /// Starts setting up a cone to add as a child of this node (see
+    /// [`add_cone`](Self::add_cone)).
+    pub fn build_cone(&mut self, r: f32, h: f32) -> SceneNodeBuilder3d<'_> {
+        self.build(SceneNode3d::cone(r, h))
+    }
+
+    /// Starts setting up a cylinder to add as a child of this node (see
+    /// [`add_cylinder`](Self::add_cylinder)).
+    pub fn build_cylinder(&mut self, r: f32, h: f32) -> SceneNodeBuilder3d<'_> {
+        self.build(SceneNode3d::cylinder(r, h))
+    }
+
+    /// Starts setting up a capsule to add as a child of this node (see
+    /// [`add_capsule`](Self::add_capsule)).
+    pub fn build_capsule(&mut self, r: f32, h: f32) -> SceneNodeBuilder3d<'_> {
+        self.build(SceneNode3d::capsule(r, h))
+    }
+}
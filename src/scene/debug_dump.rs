@@ -0,0 +1,376 @@
+//! Snapshots of scene subtrees for debugging, and the differences between two.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use glamx::{Pose3, Quat, Vec3};
+
+use crate::color::Color;
+use crate::resource::{MaterialManager3d, TextureManager};
+use crate::scene::{Object3d, SceneNode3d};
+
+/// Transforms closer than this are considered equal by [`SceneDescription::diff`].
+const DIFF_EPSILON: f32 = 1.0e-5;
+
+/// A snapshot of a scene node and its descendants, created by
+/// [`SceneNode3d::debug_dump`].
+///
+/// It prints as an indented tree with [`Display`](fmt::Display), and two
+/// snapshots are compared with [`diff`](Self::diff), e.g. to find what a
+/// loader did differently from one run to the next.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SceneDescription {
+    /// The name of the node, if any.
+    pub name: Option<String>,
+    /// Whether the node is visible.
+    pub visible: bool,
+    /// The local translation of the node.
+    pub position: Vec3,
+    /// The local rotation of the node.
+    pub rotation: Quat,
+    /// The local scale of the node.
+    pub scale: Vec3,
+    /// The translation of the node in world space.
+    pub world_position: Vec3,
+    /// The object of the node, if any.
+    pub object: Option<ObjectDescription>,
+    /// The descriptions of the children of the node.
+    pub children: Vec<SceneDescription>,
+}
+
+/// A snapshot of the object of a scene node, part of a [`SceneDescription`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjectDescription {
+    /// The number of vertices of the mesh.
+    pub num_vertices: usize,
+    /// The number of triangles of the mesh.
+    pub num_triangles: usize,
+    /// The number of instances, `1` for a non-instanced object.
+    pub num_instances: usize,
+    /// The name the material is registered under, `None` if it isn't.
+    pub material: Option<String>,
+    /// The name the texture is registered under, `None` if it isn't.
+    pub texture: Option<String>,
+    /// The color of the object.
+    pub color: Color,
+    /// Whether the surface of the object is rendered.
+    pub surface: bool,
+}
+
+/// A difference between two [`SceneDescription`]s, found by
+/// [`SceneDescription::diff`].
+///
+/// Nodes are identified by their path from the root: the names of the nodes
+/// separated by `/`. Unnamed nodes are named after their index among their
+/// unnamed siblings (e.g. `#2`), and repeated names get their occurrence
+/// appended (e.g. `wheel[1]`).
+#[derive(Clone, Debug, PartialEq)]
+pub enum SceneChange {
+    /// A node (with its descendants) only exists in the newer snapshot.
+    Added(String),
+    /// A node (with its descendants) only exists in the older snapshot.
+    Removed(String),
+    /// A property of a node changed.
+    Changed {
+        /// The path of the node.
+        path: String,
+        /// The name of the property.
+        property: &'static str,
+        /// The value in the older snapshot.
+        before: String,
+        /// The value in the newer snapshot.
+        after: String,
+    },
+}
+
+impl fmt::Display for SceneChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneChange::Added(path) => write!(f, "+ {}", path),
+            SceneChange::Removed(path) => write!(f, "- {}", path),
+            SceneChange::Changed {
+                path,
+                property,
+                before,
+                after,
+            } => write!(f, "~ {}: {} {} -> {}", path, property, before, after),
+        }
+    }
+}
+
+impl SceneNode3d {
+    /// Takes a snapshot of this node and its descendants: their names,
+    /// transforms, mesh sizes and materials.
+    ///
+    /// Print it to see the structure of a scene built by complex loading code,
+    /// or compare two snapshots with [`SceneDescription::diff`] to find what
+    /// changed, e.g. a node that silently ended up at the origin.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let _window = Window::new("Example").await;
+    /// let mut scene = SceneNode3d::empty();
+    /// scene.add_cube(1.0, 1.0, 1.0).set_name("crate");
+    /// let before = scene.debug_dump();
+    /// println!("{}", before);
+    ///
+    /// scene.add_sphere(0.5).set_name("ball");
+    /// for change in before.diff(&scene.debug_dump()) {
+    ///     println!("{}", change);
+    /// }
+    /// # }
+    /// ```
+    pub fn debug_dump(&self) -> SceneDescription {
+        describe(self, self.world_pose())
+    }
+}
+
+fn describe(node: &SceneNode3d, world: Pose3) -> SceneDescription {
+    let local = node.local_transformation();
+    let (name, visible, scale) = (node.name(), node.is_visible(), node.local_scale());
+    let data = node.data();
+    let children = data
+        .children()
+        .iter()
+        .map(|child| describe(child, world * child.local_transformation()))
+        .collect();
+
+    SceneDescription {
+        name,
+        visible,
+        position: local.translation,
+        rotation: local.rotation,
+        scale,
+        world_position: world.translation,
+        object: data.object().map(describe_object),
+        children,
+    }
+}
+
+fn describe_object(object: &Object3d) -> ObjectDescription {
+    let (num_vertices, num_triangles) = {
+        let mesh = object.mesh().borrow();
+        let num_vertices = mesh.coords().read().unwrap().len();
+        let num_triangles = mesh.faces().read().unwrap().len();
+        (num_vertices, num_triangles)
+    };
+    let num_instances = object.instances().borrow().len().max(1);
+    let material = object.material();
+    let material = MaterialManager3d::get_global_manager(|mm| mm.name_of(&material));
+    let texture = TextureManager::get_global_manager(|tm| tm.name_of(object.data().texture()));
+
+    ObjectDescription {
+        num_vertices,
+        num_triangles,
+        num_instances,
+        material,
+        texture,
+        color: object.data().color(),
+        surface: object.data().surface_rendering_active(),
+    }
+}
+
+impl SceneDescription {
+    /// The differences from this snapshot to the `newer` one.
+    ///
+    /// Children are matched by name, and unnamed children by their index among
+    /// the unnamed siblings. Transforms are compared with a small tolerance.
+    pub fn diff(&self, newer: &SceneDescription) -> Vec<SceneChange> {
+        let mut changes = Vec::new();
+        let root = self.label(0);
+        diff_nodes(self, newer, &root, &mut changes);
+        changes
+    }
+
+    /// The total number of nodes of the snapshot.
+    pub fn num_nodes(&self) -> usize {
+        1 + self.children.iter().map(|c| c.num_nodes()).sum::<usize>()
+    }
+
+    /// The node's name, or `#index` for an unnamed node.
+    fn label(&self, index: usize) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("#{}", index),
+        }
+    }
+
+    /// The children keyed by label, duplicated names getting their occurrence
+    /// appended (e.g. `wheel[1]`).
+    fn keyed_children(&self) -> Vec<(String, &SceneDescription)> {
+        let mut occurrences: HashMap<String, usize> = HashMap::new();
+        let mut num_unnamed = 0;
+        self.children
+            .iter()
+            .map(|child| {
+                let label = match &child.name {
+                    Some(_) => child.label(0),
+                    None => {
+                        num_unnamed += 1;
+                        child.label(num_unnamed - 1)
+                    }
+                };
+                let count = occurrences.entry(label.clone()).or_insert(0);
+                *count += 1;
+                let key = if *count == 1 {
+                    label
+                } else {
+                    format!("{}[{}]", label, *count - 1)
+                };
+                (key, child)
+            })
+            .collect()
+    }
+
+    fn fmt_tree(&self, f: &mut fmt::Formatter<'_>, label: &str, depth: usize) -> fmt::Result {
+        write!(
+            f,
+            "{:indent$}{} pos {} rot {} scale {} world {}",
+            "",
+            label,
+            fmt_vec(self.position),
+            fmt_quat(self.rotation),
+            fmt_vec(self.scale),
+            fmt_vec(self.world_position),
+            indent = depth * 2
+        )?;
+        if !self.visible {
+            write!(f, " (hidden)")?;
+        }
+        if let Some(object) = &self.object {
+            write!(
+                f,
+                " | {} vertices, {} triangles",
+                object.num_vertices, object.num_triangles
+            )?;
+            if object.num_instances > 1 {
+                write!(f, ", {} instances", object.num_instances)?;
+            }
+            write!(
+                f,
+                ", material {}",
+                object.material.as_deref().unwrap_or("<unnamed>")
+            )?;
+            if let Some(texture) = &object.texture {
+                write!(f, ", texture {}", texture)?;
+            }
+        }
+        writeln!(f)?;
+
+        for (key, child) in self.keyed_children() {
+            child.fmt_tree(f, &key, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SceneDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_tree(f, &self.label(0), 0)
+    }
+}
+
+fn diff_nodes(
+    before: &SceneDescription,
+    after: &SceneDescription,
+    path: &str,
+    changes: &mut Vec<SceneChange>,
+) {
+    let mut change = |property: &'static str, before: String, after: String| {
+        if before != after {
+            changes.push(SceneChange::Changed {
+                path: path.to_string(),
+                property,
+                before,
+                after,
+            });
+        }
+    };
+    change(
+        "visible",
+        before.visible.to_string(),
+        after.visible.to_string(),
+    );
+    if !before.position.abs_diff_eq(after.position, DIFF_EPSILON) {
+        change(
+            "position",
+            fmt_vec(before.position),
+            fmt_vec(after.position),
+        );
+    }
+    if !before.rotation.abs_diff_eq(after.rotation, DIFF_EPSILON) {
+        change(
+            "rotation",
+            fmt_quat(before.rotation),
+            fmt_quat(after.rotation),
+        );
+    }
+    if !before.scale.abs_diff_eq(after.scale, DIFF_EPSILON) {
+        change("scale", fmt_vec(before.scale), fmt_vec(after.scale));
+    }
+    if !before
+        .world_position
+        .abs_diff_eq(after.world_position, DIFF_EPSILON)
+    {
+        change(
+            "world position",
+            fmt_vec(before.world_position),
+            fmt_vec(after.world_position),
+        );
+    }
+
+    match (&before.object, &after.object) {
+        (Some(b), Some(a)) => {
+            change(
+                "vertices",
+                b.num_vertices.to_string(),
+                a.num_vertices.to_string(),
+            );
+            change(
+                "triangles",
+                b.num_triangles.to_string(),
+                a.num_triangles.to_string(),
+            );
+            change(
+                "instances",
+                b.num_instances.to_string(),
+                a.num_instances.to_string(),
+            );
+            change("material", fmt_name(&b.material), fmt_name(&a.material));
+            change("texture", fmt_name(&b.texture), fmt_name(&a.texture));
+            change("color", format!("{:?}", b.color), format!("{:?}", a.color));
+            change("surface", b.surface.to_string(), a.surface.to_string());
+        }
+        (b, a) => change("object", b.is_some().to_string(), a.is_some().to_string()),
+    }
+
+    let before_children = before.keyed_children();
+    let after_children = after.keyed_children();
+    for (key, child) in &before_children {
+        let child_path = format!("{}/{}", path, key);
+        match after_children.iter().find(|(k, _)| k == key) {
+            Some((_, newer)) => diff_nodes(child, newer, &child_path, changes),
+            None => changes.push(SceneChange::Removed(child_path)),
+        }
+    }
+    for (key, _) in &after_children {
+        if !before_children.iter().any(|(k, _)| k == key) {
+            changes.push(SceneChange::Added(format!("{}/{}", path, key)));
+        }
+    }
+}
+
+fn fmt_vec(v: Vec3) -> String {
+    format!("({:.3}, {:.3}, {:.3})", v.x, v.y, v.z)
+}
+
+fn fmt_quat(q: Quat) -> String {
+    format!("({:.3}, {:.3}, {:.3}, {:.3})", q.x, q.y, q.z, q.w)
+}
+
+fn fmt_name(name: &Option<String>) -> String {
+    name.as_deref().unwrap_or("<unnamed>").to_string()
+}
@@ -0,0 +1,295 @@
+//! Export of scene subtrees to the Wavefront OBJ format.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use glamx::{Pose3, Vec3};
+
+use crate::resource::{Texture, TextureManager};
+use crate::scene::{Object3d, SceneNode3d};
+
+impl SceneNode3d {
+    /// Exports the visible meshes of this node and its descendants to a
+    /// Wavefront OBJ file, e.g. to render procedurally generated geometry in
+    /// Blender.
+    ///
+    /// Each object becomes an OBJ object (named after its node, if named) with
+    /// its vertices, normals and texture coordinates in world space, and its
+    /// vertex colors, if any, as the widespread `v x y z r g b` extension. Its
+    /// color, opacity and emissive color are written to a material library next
+    /// to `path`, with the same file name and the `.mtl` extension. Base color
+    /// textures loaded from a file by the
+    /// [`TextureManager`](crate::resource::TextureManager) are copied next to it
+    /// too; the other ones (e.g. created from images in memory) are skipped.
+    ///
+    /// Meshes whose data is no longer on the CPU are skipped, and instances,
+    /// skinning and morph targets are not applied: each object is exported once,
+    /// in its rest shape.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # use std::path::Path;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let _window = Window::new("Example").await;
+    /// let mut scene = SceneNode3d::empty();
+    /// scene.add_terrain(10.0, 10.0, 128, &kiss3d::procedural::NoiseConfig::default());
+    /// scene.export_obj(Path::new("terrain.obj")).unwrap();
+    /// # }
+    /// ```
+    pub fn export_obj(&self, path: &Path) -> io::Result<()> {
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("scene")
+            .to_string();
+        let dir = path.parent().unwrap_or_else(|| Path::new("")).to_path_buf();
+        let mtl_name = format!("{}.mtl", stem);
+
+        let mut exporter = ObjExporter {
+            obj: BufWriter::new(File::create(path)?),
+            mtl: BufWriter::new(File::create(dir.join(&mtl_name))?),
+            dir,
+            stem,
+            num_coords: 0,
+            num_uvs: 0,
+            num_normals: 0,
+            num_objects: 0,
+            textures: HashMap::new(),
+            default_texture: TextureManager::get_global_manager(|tm| tm.get_default()),
+        };
+        writeln!(exporter.obj, "# Exported by kiss3d")?;
+        writeln!(exporter.obj, "mtllib {}", mtl_name)?;
+        writeln!(exporter.mtl, "# Exported by kiss3d")?;
+
+        exporter.export_node(self, self.world_pose(), self.world_scale())?;
+        exporter.obj.flush()?;
+        exporter.mtl.flush()
+    }
+}
+
+/// The state of an OBJ export.
+struct ObjExporter {
+    obj: BufWriter<File>,
+    mtl: BufWriter<File>,
+    /// The directory of the exported files.
+    dir: PathBuf,
+    /// The file name of the OBJ file, without extension.
+    stem: String,
+    /// Number of `v`, `vt` and `vn` entries written so far, as OBJ indices are
+    /// global to the file.
+    num_coords: usize,
+    num_uvs: usize,
+    num_normals: usize,
+    num_objects: usize,
+    /// The file name of each texture already saved, by texture address, or `None`
+    /// if it could not be saved.
+    textures: HashMap<*const Texture, Option<String>>,
+    default_texture: Arc<Texture>,
+}
+
+impl ObjExporter {
+    /// Writes `node` and its descendants, `transform` and `scale` being the world
+    /// transform and scale of `node`.
+    fn export_node(&mut self, node: &SceneNode3d, transform: Pose3, scale: Vec3) -> io::Result<()> {
+        if !node.is_visible() {
+            return Ok(());
+        }
+
+        let data = node.data();
+        if let Some(object) = data.object() {
+            self.export_object(node.name(), object, transform, scale)?;
+        }
+        for child in data.children() {
+            let child_transform = transform * child.local_transformation();
+            let child_scale = scale * child.local_scale();
+            self.export_node(child, child_transform, child_scale)?;
+        }
+        Ok(())
+    }
+
+    fn export_object(
+        &mut self,
+        name: Option<String>,
+        object: &Object3d,
+        transform: Pose3,
+        scale: Vec3,
+    ) -> io::Result<()> {
+        let Some(mut mesh) = object.mesh().borrow().to_render_mesh() else {
+            return Ok(());
+        };
+        if !matches!(&mesh.normals, Some(n) if n.len() == mesh.coords.len()) {
+            mesh.recompute_normals();
+        }
+        let has_uvs = matches!(&mesh.uvs, Some(uvs) if uvs.len() == mesh.coords.len());
+        let colors = object
+            .mesh()
+            .borrow()
+            .colors()
+            .and_then(|colors| colors.read().unwrap().data().clone())
+            .filter(|colors| colors.len() == mesh.coords.len());
+
+        self.num_objects += 1;
+        let material = format!("material_{}", self.num_objects);
+        self.export_material(&material, object)?;
+
+        let obj = &mut self.obj;
+        match name {
+            Some(name) => writeln!(obj, "o {}", name)?,
+            None => writeln!(obj, "o object_{}", self.num_objects)?,
+        }
+        let (rot, tra) = (transform.rotation, transform.translation);
+        for (i, p) in mesh.coords.iter().enumerate() {
+            let p = rot * (*p * scale) + tra;
+            match &colors {
+                Some(colors) => {
+                    let [r, g, b, _] = colors[i];
+                    writeln!(obj, "v {} {} {} {} {} {}", p.x, p.y, p.z, r, g, b)?
+                }
+                None => writeln!(obj, "v {} {} {}", p.x, p.y, p.z)?,
+            }
+        }
+        for n in mesh.normals.iter().flatten() {
+            let n = (rot * (*n / scale)).normalize_or_zero();
+            writeln!(obj, "vn {} {} {}", n.x, n.y, n.z)?;
+        }
+        if has_uvs {
+            for uv in mesh.uvs.iter().flatten() {
+                writeln!(obj, "vt {} {}", uv.x, uv.y)?;
+            }
+        }
+
+        writeln!(obj, "usemtl {}", material)?;
+        let (v0, vt0, vn0) = (self.num_coords + 1, self.num_uvs + 1, self.num_normals + 1);
+        for face in mesh.indices.as_unified() {
+            let corners = face.map(|i| {
+                let i = i as usize;
+                if has_uvs {
+                    format!("{}/{}/{}", v0 + i, vt0 + i, vn0 + i)
+                } else {
+                    format!("{}//{}", v0 + i, vn0 + i)
+                }
+            });
+            writeln!(obj, "f {} {} {}", corners[0], corners[1], corners[2])?;
+        }
+
+        self.num_coords += mesh.coords.len();
+        self.num_normals += mesh.coords.len();
+        if has_uvs {
+            self.num_uvs += mesh.coords.len();
+        }
+        Ok(())
+    }
+
+    fn export_material(&mut self, material: &str, object: &Object3d) -> io::Result<()> {
+        let data = object.data();
+        let (color, emissive) = (data.color(), data.emissive());
+        let texture = self.export_texture(data.texture());
+
+        let mtl = &mut self.mtl;
+        writeln!(mtl, "newmtl {}", material)?;
+        writeln!(mtl, "Kd {} {} {}", color.r, color.g, color.b)?;
+        writeln!(mtl, "d {}", color.a)?;
+        if emissive.r > 0.0 || emissive.g > 0.0 || emissive.b > 0.0 {
+            writeln!(mtl, "Ke {} {} {}", emissive.r, emissive.g, emissive.b)?;
+        }
+        if let Some(file) = texture {
+            writeln!(mtl, "map_Kd {}", file)?;
+        }
+        Ok(())
+    }
+
+    /// Copies the file `texture` was loaded from next to the OBJ file, once,
+    /// returning its file name. `None` for the default (white) texture and for
+    /// textures not loaded from a file.
+    fn export_texture(&mut self, texture: &Arc<Texture>) -> Option<String> {
+        if Arc::ptr_eq(texture, &self.default_texture) {
+            return None;
+        }
+        if let Some(file) = self.textures.get(&Arc::as_ptr(texture)) {
+            return file.clone();
+        }
+
+        let source = TextureManager::get_global_manager(|tm| tm.path_of(texture));
+        let file = source.and_then(|source| {
+            let extension = source.extension()?.to_str()?;
+            let file = format!(
+                "{}_texture_{}.{}",
+                self.stem,
+                self.textures.len() + 1,
+                extension
+            );
+            std::fs::copy(&source, self.dir.join(&file)).ok()?;
+            Some(file)
+        });
+        if file.is_none() {
+            log::warn!("OBJ export: skipping a texture not loaded from a file.");
+        }
+        let _ = self.textures.insert(Arc::as_ptr(texture), file.clone());
+        file
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::window::OffscreenSurface;
+
+    #[test]
+    fn exported_scene_reads_back() {
+        crate::pollster::block_on(async {
+            if !crate::shader_validity::tests::adapter_available().await {
+                eprintln!("OBJ export test: no GPU adapter found, skipping");
+                return;
+            }
+            let _surface = OffscreenSurface::new(8, 8).await;
+
+            let mut scene = SceneNode3d::empty();
+            let mut block = scene.add_cube(2.0, 1.0, 1.0);
+            block.set_name("block");
+            block.set_position(Vec3::new(3.0, 0.0, 0.0));
+            let mut num_vertices = 0;
+            block.read_vertices(&mut |v| num_vertices = v.len());
+            block.set_vertex_colors(&vec![Color::new(1.0, 0.5, 0.0, 1.0); num_vertices]);
+            scene
+                .add_sphere(0.5)
+                .set_texture_from_file(Path::new("examples/media/kitten.png"), "export_kitten");
+
+            let dir = std::env::temp_dir().join(format!("kiss3d_export_{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            let path = dir.join("scene.obj");
+            scene.export_obj(&path).unwrap();
+
+            let meshes = crate::loader::obj::parse_file(&path, &dir, "scene").unwrap();
+            let (_, mesh, _) = meshes
+                .iter()
+                .find(|(name, ..)| name == "scene/block")
+                .unwrap();
+            let coords = mesh.coords().read().unwrap().data().clone().unwrap();
+            let faces = mesh.faces().read().unwrap().data().clone().unwrap();
+            let (min, max) = faces
+                .iter()
+                .flatten()
+                .map(|i| &coords[*i as usize])
+                .fold((Vec3::INFINITY, Vec3::NEG_INFINITY), |(min, max), p| {
+                    (min.min(*p), max.max(*p))
+                });
+            assert!(min.abs_diff_eq(Vec3::new(2.0, -0.5, -0.5), 1.0e-5));
+            assert!(max.abs_diff_eq(Vec3::new(4.0, 0.5, 0.5), 1.0e-5));
+            assert!(meshes.iter().any(|(_, _, material)| material
+                .as_ref()
+                .and_then(|m| m.diffuse_texture.as_ref())
+                .is_some()));
+
+            let obj = std::fs::read_to_string(&path).unwrap();
+            assert!(obj.lines().any(|l| l == "v 2 -0.5 -0.5 1 0.5 0"));
+            assert!(dir.join("scene_texture_1.png").exists());
+            let _ = std::fs::remove_dir_all(&dir);
+        });
+    }
+}
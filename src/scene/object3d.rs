@@ -1,5 +1,6 @@
 //! Data structure of a scene node.
 
+use crate::builtin::PipelineVariantKey;
 use crate::camera::Camera3d;
 use crate::color::Color;
 use crate::context::Context;
@@ -18,6 +19,9 @@ use std::rc::{Rc, Weak};
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 
+/// Key grouping the draws of a scene (see [`Object3d::draw_sort_key`]).
+pub(crate) type DrawSortKey = (usize, PipelineVariantKey, usize);
+
 /// Skeletal skinning binding for a skinned mesh.
 ///
 /// Holds **weak** references to the skeleton's joint nodes (so the skin never
@@ -189,6 +193,27 @@ impl AlphaMode {
     }
 }
 
+/// Which shading the default material applies to a surface.
+///
+/// Each mode selects a specialized variant of the object shader, so the unlit
+/// modes skip the lighting, shadow and environment computations entirely.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShadingMode {
+    /// Full physically-based lighting (the default).
+    #[default]
+    Lit,
+    /// Flat object (and instance) color, without lighting nor texture.
+    Unlit,
+    /// The mesh per-vertex colors (see
+    /// [`GpuMesh3d::set_colors`](crate::resource::GpuMesh3d::set_colors)) times the
+    /// object color, without lighting nor texture. Falls back to
+    /// [`Unlit`](ShadingMode::Unlit) on meshes without vertex colors.
+    VertexColor,
+    /// The texture times the object color, without lighting.
+    TexturedUnlit,
+}
+
 /// How parallax mapping marches the height field.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -233,6 +258,9 @@ pub struct ObjectData3d {
     texture: Arc<Texture>,
     color: Color,
     lines_color: Option<Color>,
+    lines_feature_angle: Option<f32>,
+    lines_depth_bias: (i32, f32),
+    lines_on_top: bool,
     points_color: Option<Color>,
     wlines: f32,
     wpoints: f32,
@@ -240,6 +268,7 @@ pub struct ObjectData3d {
     points_use_perspective: bool,
     draw_surface: bool,
     cull: bool,
+    front_face: Option<wgpu::FrontFace>,
     /// Integer object identifier written to the segmentation auxiliary output.
     /// Auto-assigned to a process-unique value on creation; user-overridable.
     segmentation_id: u32,
@@ -261,6 +290,7 @@ pub struct ObjectData3d {
     roughness: f32,
     emissive: Color,
     alpha_mode: AlphaMode,
+    shading_mode: ShadingMode,
     // Path-tracer BSDF properties (ignored by the rasterizer).
     bsdf: Bsdf,
     ior: f32,
@@ -311,6 +341,9 @@ pub struct ObjectData3d {
     emissive_map: Option<Arc<Texture>>,
     /// Height/displacement map for parallax mapping (grayscale; brighter = higher).
     height_map: Option<Arc<Texture>>,
+    /// Albedo texture array indexed by the instances' `texture_layer`. Replaces
+    /// `texture` when set.
+    texture_array: Option<Arc<Texture>>,
     /// Parallax displacement scale (surface depth in UV units). `0` disables it.
     parallax_scale: f32,
     /// Maximum number of parallax search layers (more = sharper, costlier).
@@ -372,6 +405,33 @@ impl ObjectData3d {
         self.lines_color
     }
 
+    /// Returns the minimum dihedral angle of the edges drawn by the wireframe.
+    ///
+    /// # Returns
+    /// `Some(angle)` (in radians) if only feature edges are drawn, `None` if every edge is drawn
+    #[inline]
+    pub fn lines_feature_angle(&self) -> Option<f32> {
+        self.lines_feature_angle
+    }
+
+    /// Returns the depth bias applied to the wireframe lines.
+    ///
+    /// # Returns
+    /// The `(constant, slope_scale)` bias, `(0, 0.0)` by default
+    #[inline]
+    pub fn lines_depth_bias(&self) -> (i32, f32) {
+        self.lines_depth_bias
+    }
+
+    /// Checks if the wireframe lines are drawn on top of all geometry.
+    ///
+    /// # Returns
+    /// `true` if the lines ignore the depth test, `false` (the default) otherwise
+    #[inline]
+    pub fn lines_on_top(&self) -> bool {
+        self.lines_on_top
+    }
+
     /// Returns the point size used for point cloud rendering.
     ///
     /// # Returns
@@ -426,6 +486,20 @@ impl ObjectData3d {
         self.cull
     }
 
+    /// The winding of this object's front faces, `None` to follow
+    /// [`Context::default_winding`](crate::context::Context::default_winding).
+    #[inline]
+    pub fn front_face(&self) -> Option<wgpu::FrontFace> {
+        self.front_face
+    }
+
+    /// The winding of this object's front faces, resolving the default.
+    #[inline]
+    pub fn effective_front_face(&self) -> wgpu::FrontFace {
+        self.front_face
+            .unwrap_or_else(crate::context::Context::default_winding)
+    }
+
     /// Returns the integer segmentation/object id of this object.
     ///
     /// This id is what the segmentation auxiliary render output writes into the
@@ -623,6 +697,12 @@ impl ObjectData3d {
         self.alpha_mode
     }
 
+    /// Returns this object's shading mode.
+    #[inline]
+    pub fn shading_mode(&self) -> ShadingMode {
+        self.shading_mode
+    }
+
     /// Returns this object's render-layer bitmask.
     #[inline]
     pub fn render_layers(&self) -> u32 {
@@ -788,6 +868,12 @@ impl ObjectData3d {
         self.height_map.as_ref()
     }
 
+    /// Returns a reference to this object's per-instance texture array, if any.
+    #[inline]
+    pub fn texture_array(&self) -> Option<&Arc<Texture>> {
+        self.texture_array.as_ref()
+    }
+
     /// Returns the parallax displacement scale (`0` disables parallax mapping).
     #[inline]
     pub fn parallax_scale(&self) -> f32 {
@@ -812,6 +898,9 @@ impl ObjectData3d {
 /// When rendering multiple copies of the same mesh with different transformations
 /// and colors (instancing), each instance is defined by this data.
 ///
+/// Build it from [`InstanceData3d::default`] with `..Default::default()` so that
+/// the fields added over time keep their default values:
+///
 /// # Example
 /// ```no_run
 /// # use kiss3d::scene::InstanceData3d;
@@ -819,13 +908,14 @@ impl ObjectData3d {
 /// # use glamx::{Vec3, Mat3};
 /// let instance = InstanceData3d {
 ///     position: Vec3::new(1.0, 0.0, 0.0),
-///     deformation: Mat3::IDENTITY,
 ///     color: RED,
 ///     lines_color: Some(LIME),  // Green wireframe
 ///     lines_width: Some(2.0),  // 2px wireframe
 ///     points_color: Some(YELLOW),  // Yellow points
 ///     points_size: Some(5.0),  // 5px points
+///     ..Default::default()
 /// };
+/// let at_origin = InstanceData3d::new(Vec3::ZERO).with_texture_layer(2);
 /// ```
 pub struct InstanceData3d {
     /// The position offset for this instance.
@@ -842,6 +932,9 @@ pub struct InstanceData3d {
     pub points_color: Option<Color>,
     /// The point size in pixels for this instance. None = use object's point size.
     pub points_size: Option<f32>,
+    /// The layer of the object's texture array sampled by this instance (see
+    /// [`Object3d::set_texture_array`]). Ignored if the object has no texture array.
+    pub texture_layer: u32,
 }
 
 impl Default for InstanceData3d {
@@ -854,8 +947,27 @@ impl Default for InstanceData3d {
             lines_width: None,  // Use object's wireframe width
             points_color: None, // Use object's point color
             points_size: None,  // Use object's point size
+            texture_layer: 0,
+        }
+    }
+}
+
+impl InstanceData3d {
+    /// An instance at `position`, with the default values of every other field.
+    #[inline]
+    pub fn new(position: Vec3) -> Self {
+        Self {
+            position,
+            ..Default::default()
         }
     }
+
+    /// Sets the layer of the object's texture array sampled by this instance.
+    #[inline]
+    pub fn with_texture_layer(mut self, texture_layer: u32) -> Self {
+        self.texture_layer = texture_layer;
+        self
+    }
 }
 
 /// Sentinel value for lines_width indicating "use object's value".
@@ -886,6 +998,8 @@ pub struct InstancesBuffer3d {
     pub points_colors: GPUVec<[f32; 4]>,
     /// GPU buffer of instance point sizes. Negative means use object's size.
     pub points_sizes: GPUVec<f32>,
+    /// GPU buffer of instance texture array layers.
+    pub texture_layers: GPUVec<f32>,
 }
 
 /// Raw per-instance GPU buffers prepared for direct compute writes.
@@ -902,6 +1016,9 @@ pub struct InstanceComputeBuffers {
     pub colors: wgpu::Buffer,
     /// Three `Vec3` deformation-matrix columns per instance (9 floats / instance).
     pub deformations: wgpu::Buffer,
+    /// One texture layer (`f32`) per instance, read only by objects with a
+    /// texture array (see [`InstanceData3d::texture_layer`]).
+    pub texture_layers: wgpu::Buffer,
 }
 
 /// Helper function to convert Color to [f32; 4] for GPU buffers.
@@ -948,6 +1065,7 @@ impl Default for InstancesBuffer3d {
                 BufferType::Array,
                 AllocationType::StreamDraw,
             ),
+            texture_layers: GPUVec::new(vec![0.0], BufferType::Array, AllocationType::StreamDraw),
         }
     }
 }
@@ -994,6 +1112,137 @@ impl InstancesBuffer3d {
     }
 }
 
+impl ObjectData3d {
+    /// Copies the appearance of this object (material, textures, color, surface,
+    /// wireframe and point properties) into new object data. The identity
+    /// (segmentation id, user data) and the per-object state (skinning, morphing,
+    /// reflector, GPU caches) are not copied.
+    pub(crate) fn clone_appearance(&self) -> ObjectData3d {
+        ObjectData3d {
+            material: self.material.clone(),
+            texture: self.texture.clone(),
+            color: self.color,
+            lines_color: self.lines_color,
+            lines_feature_angle: self.lines_feature_angle,
+            lines_depth_bias: self.lines_depth_bias,
+            lines_on_top: self.lines_on_top,
+            points_color: self.points_color,
+            wlines: self.wlines,
+            wpoints: self.wpoints,
+            lines_use_perspective: self.lines_use_perspective,
+            points_use_perspective: self.points_use_perspective,
+            draw_surface: self.draw_surface,
+            cull: self.cull,
+            front_face: self.front_face,
+            render_layers: self.render_layers,
+            light_layers: self.light_layers,
+            casts_shadows: self.casts_shadows,
+            metallic: self.metallic,
+            roughness: self.roughness,
+            emissive: self.emissive,
+            alpha_mode: self.alpha_mode,
+            shading_mode: self.shading_mode,
+            bsdf: self.bsdf,
+            ior: self.ior,
+            transmission: self.transmission,
+            specular_tint: self.specular_tint,
+            subsurface: self.subsurface,
+            subsurface_radius: self.subsurface_radius,
+            thickness: self.thickness,
+            attenuation_color: self.attenuation_color,
+            attenuation_distance: self.attenuation_distance,
+            reflectance: self.reflectance,
+            clearcoat: self.clearcoat,
+            clearcoat_roughness: self.clearcoat_roughness,
+            anisotropy: self.anisotropy,
+            anisotropy_rotation: self.anisotropy_rotation,
+            ssr: self.ssr,
+            parallax_scale: self.parallax_scale,
+            parallax_layers: self.parallax_layers,
+            parallax_method: self.parallax_method,
+            normal_map: self.normal_map.clone(),
+            metallic_roughness_map: self.metallic_roughness_map.clone(),
+            ao_map: self.ao_map.clone(),
+            emissive_map: self.emissive_map.clone(),
+            height_map: self.height_map.clone(),
+            texture_array: self.texture_array.clone(),
+            segmentation_id: next_segmentation_id(),
+            user_data: Box::new(()),
+            reflector: None,
+            skin: None,
+            morph_weights: Vec::new(),
+            deform: None,
+            shadow_tex_bind_group: None,
+            cached_shadow_tex_ptr: 0,
+        }
+    }
+
+    /// Whether `self` and `other` are drawn identically up to their geometry, i.e.
+    /// whether their meshes can be merged into a single draw.
+    pub(crate) fn same_appearance(&self, other: &ObjectData3d) -> bool {
+        self.color == other.color && self.same_appearance_but_color(other)
+    }
+
+    /// Whether `self` and `other` are drawn identically up to their geometry and
+    /// color.
+    fn same_appearance_but_color(&self, other: &ObjectData3d) -> bool {
+        fn same_texture(a: &Option<Arc<Texture>>, b: &Option<Arc<Texture>>) -> bool {
+            match (a, b) {
+                (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+        }
+
+        Rc::ptr_eq(&self.material, &other.material)
+            && Arc::ptr_eq(&self.texture, &other.texture)
+            && self.lines_color == other.lines_color
+            && self.lines_feature_angle == other.lines_feature_angle
+            && self.lines_depth_bias == other.lines_depth_bias
+            && self.lines_on_top == other.lines_on_top
+            && self.points_color == other.points_color
+            && self.wlines == other.wlines
+            && self.wpoints == other.wpoints
+            && self.lines_use_perspective == other.lines_use_perspective
+            && self.points_use_perspective == other.points_use_perspective
+            && self.draw_surface == other.draw_surface
+            && self.cull == other.cull
+            && self.front_face == other.front_face
+            && self.render_layers == other.render_layers
+            && self.light_layers == other.light_layers
+            && self.casts_shadows == other.casts_shadows
+            && self.metallic == other.metallic
+            && self.roughness == other.roughness
+            && self.emissive == other.emissive
+            && self.alpha_mode == other.alpha_mode
+            && self.shading_mode == other.shading_mode
+            && self.bsdf == other.bsdf
+            && self.ior == other.ior
+            && self.transmission == other.transmission
+            && self.specular_tint == other.specular_tint
+            && self.subsurface == other.subsurface
+            && self.subsurface_radius == other.subsurface_radius
+            && self.thickness == other.thickness
+            && self.attenuation_color == other.attenuation_color
+            && self.attenuation_distance == other.attenuation_distance
+            && self.reflectance == other.reflectance
+            && self.clearcoat == other.clearcoat
+            && self.clearcoat_roughness == other.clearcoat_roughness
+            && self.anisotropy == other.anisotropy
+            && self.anisotropy_rotation == other.anisotropy_rotation
+            && self.ssr == other.ssr
+            && self.parallax_scale == other.parallax_scale
+            && self.parallax_layers == other.parallax_layers
+            && self.parallax_method == other.parallax_method
+            && same_texture(&self.normal_map, &other.normal_map)
+            && same_texture(&self.metallic_roughness_map, &other.metallic_roughness_map)
+            && same_texture(&self.ao_map, &other.ao_map)
+            && same_texture(&self.emissive_map, &other.emissive_map)
+            && same_texture(&self.height_map, &other.height_map)
+            && same_texture(&self.texture_array, &other.texture_array)
+    }
+}
+
 /// A renderable 3D object in the scene.
 ///
 /// `Object` combines a mesh with rendering properties (material, texture, color).
@@ -1023,6 +1272,9 @@ impl Object3d {
         let data = ObjectData3d {
             color,
             lines_color: None,
+            lines_feature_angle: None,
+            lines_depth_bias: (0, 0.0),
+            lines_on_top: false,
             points_color: None,
             texture,
             wlines: 0.0,
@@ -1031,6 +1283,7 @@ impl Object3d {
             points_use_perspective: true,
             draw_surface: true,
             cull: true,
+            front_face: None,
             segmentation_id: next_segmentation_id(),
             material,
             user_data: Box::new(user_data),
@@ -1043,6 +1296,7 @@ impl Object3d {
             roughness: 0.5,
             emissive: crate::color::BLACK,
             alpha_mode: AlphaMode::default(),
+            shading_mode: ShadingMode::default(),
             // Path-tracer BSDF defaults: opaque dielectric.
             bsdf: Bsdf::Opaque,
             ior: 1.5,
@@ -1065,6 +1319,7 @@ impl Object3d {
             ao_map: None,
             emissive_map: None,
             height_map: None,
+            texture_array: None,
             parallax_scale: 0.1,
             parallax_layers: 16.0,
             parallax_method: ParallaxMethod::Occlusion,
@@ -1147,6 +1402,82 @@ impl Object3d {
         );
     }
 
+    /// Creates an object drawing `mesh` with the given appearance.
+    pub(crate) fn from_data(mesh: Rc<RefCell<GpuMesh3d>>, data: ObjectData3d) -> Object3d {
+        let gpu_data = data.material.borrow().create_gpu_data();
+        let instances = Rc::new(RefCell::new(InstancesBuffer3d::default()));
+
+        Object3d {
+            data,
+            instances,
+            mesh,
+            gpu_data,
+        }
+    }
+
+    /// Whether this object can be merged into a static batch by
+    /// [`SceneNode3d::bake_static`](crate::scene::SceneNode3d::bake_static): its
+    /// geometry is readable on the CPU and not deformed (skinning, morphing), it is
+    /// not a mirror, and it has a single untransformed instance.
+    pub(crate) fn is_static_batchable(&self) -> bool {
+        let mesh = self.mesh.borrow();
+        let instances = self.instances.borrow();
+        self.data.skin.is_none()
+            && self.data.reflector.is_none()
+            && self.data.texture_array.is_none()
+            && !mesh.has_skin_vertices()
+            && !mesh.has_morph()
+            && instances.positions.data().as_deref() == Some(&[Vec3::ZERO][..])
+            && instances.deformations.data().as_deref() == Some(&[Vec3::X, Vec3::Y, Vec3::Z][..])
+            && instances.colors.data().as_deref() == Some(&[[1.0f32; 4]][..])
+    }
+
+    /// Whether this object can be drawn as one instance of a draw merged by the
+    /// automatic instancing (see
+    /// [`SceneNode3d::set_auto_instancing`](crate::scene::SceneNode3d::set_auto_instancing)):
+    /// on top of being [static batchable](Self::is_static_batchable), its material
+    /// honors the instance buffers, and it is neither glass nor drawn with a
+    /// wireframe or points.
+    pub(crate) fn is_auto_instanceable(&self) -> bool {
+        self.is_static_batchable()
+            && self.data.material.borrow().supports_auto_instancing()
+            && self.data.wlines == 0.0
+            && self.data.wpoints == 0.0
+            && self.data.transmission == 0.0
+    }
+
+    /// Whether this object and `other` can be drawn by the same automatically
+    /// instanced draw: they share their mesh and their appearance up to the color
+    /// (which moves to the instances), except for its alpha that decides the
+    /// render phase.
+    pub(crate) fn shares_instanced_draw(&self, other: &Object3d) -> bool {
+        Rc::ptr_eq(&self.mesh, &other.mesh)
+            && self.data.color.a == other.data.color.a
+            && self.data.same_appearance_but_color(&other.data)
+    }
+
+    /// Creates the stand-in object drawing a group of objects sharing an instanced
+    /// draw with this one: its mesh and appearance, with a white color keeping
+    /// only its alpha.
+    pub(crate) fn auto_instance_object(&self) -> Object3d {
+        let mut data = self.data.clone_appearance();
+        data.color = Color::new(1.0, 1.0, 1.0, self.data.color.a);
+        Object3d::from_data(self.mesh.clone(), data)
+    }
+
+    /// Key used to group draws sharing state: the material, the pipeline variant
+    /// the object resolves to (see [`PipelineVariantKey`]) and the albedo texture.
+    /// Objects with equal keys can be drawn back to back without switching
+    /// pipelines or texture bind groups.
+    pub(crate) fn draw_sort_key(&self) -> DrawSortKey {
+        let has_vertex_colors = self.mesh.borrow().colors().is_some();
+        (
+            Rc::as_ptr(&self.data.material) as *const () as usize,
+            PipelineVariantKey::of(&self.data, has_vertex_colors),
+            Arc::as_ptr(&self.data.texture) as usize,
+        )
+    }
+
     /// Whether this object contributes surface geometry to the shadow pre-pass.
     /// True only when surface rendering is active *and* shadow casting is enabled
     /// (see [`set_casts_shadows`](Self::set_casts_shadows)).
@@ -1261,6 +1592,7 @@ impl Object3d {
         render_pass.set_vertex_buffer(2, inst_deformations_buf.slice(..));
         render_pass.set_index_buffer(faces_buf.slice(..), VERTEX_INDEX_FORMAT);
         render_pass.draw_indexed(0..mesh.num_indices(), 0, 0..num_instances as u32);
+        crate::renderer::timings::record_draw(mesh.num_indices() as u64 / 3 * num_instances as u64);
     }
 
     /// Gets the data of this object.
@@ -1343,6 +1675,13 @@ impl Object3d {
             .data_mut()
             .take()
             .unwrap_or_default();
+        let mut layer_data: Vec<_> = self
+            .instances
+            .borrow_mut()
+            .texture_layers
+            .data_mut()
+            .take()
+            .unwrap_or_default();
 
         pos_data.clear();
         col_data.clear();
@@ -1351,6 +1690,7 @@ impl Object3d {
         lines_width_data.clear();
         points_col_data.clear();
         points_size_data.clear();
+        layer_data.clear();
 
         pos_data.extend(instances.iter().map(|i| i.position));
         col_data.extend(instances.iter().map(|i| color_to_array(i.color)));
@@ -1381,6 +1721,7 @@ impl Object3d {
                 .iter()
                 .map(|i| i.points_size.unwrap_or(POINTS_SIZE_USE_OBJECT)),
         );
+        layer_data.extend(instances.iter().map(|i| i.texture_layer as f32));
 
         *self.instances.borrow_mut().positions.data_mut() = Some(pos_data);
         *self.instances.borrow_mut().colors.data_mut() = Some(col_data);
@@ -1389,6 +1730,7 @@ impl Object3d {
         *self.instances.borrow_mut().lines_widths.data_mut() = Some(lines_width_data);
         *self.instances.borrow_mut().points_colors.data_mut() = Some(points_col_data);
         *self.instances.borrow_mut().points_sizes.data_mut() = Some(points_size_data);
+        *self.instances.borrow_mut().texture_layers.data_mut() = Some(layer_data);
     }
 
     /// Prepares this object's per-instance buffers to be written directly by a
@@ -1400,8 +1742,8 @@ impl Object3d {
     /// This is an alternative to [`set_instances`](Self::set_instances), which
     /// uploads the same data from the CPU; use one or the other per frame.
     ///
-    /// Only the surface attributes (position/color/deformation) are managed
-    /// here; the wireframe/point overlay attributes keep their previous
+    /// Only the surface attributes (position/color/deformation/texture layer) are
+    /// managed here; the wireframe/point overlay attributes keep their previous
     /// contents, so this is intended for plain surface-rendered instances.
     pub fn instance_compute_buffers(&mut self, count: usize) -> InstanceComputeBuffers {
         let mut inst = self.instances.borrow_mut();
@@ -1409,10 +1751,12 @@ impl Object3d {
         let colors = inst.colors.prepare_gpu_writable(count).clone();
         // Three Vec3 columns (a Mat3) per instance.
         let deformations = inst.deformations.prepare_gpu_writable(count * 3).clone();
+        let texture_layers = inst.texture_layers.prepare_gpu_writable(count).clone();
         InstanceComputeBuffers {
             positions,
             colors,
             deformations,
+            texture_layers,
         }
     }
 
@@ -1422,6 +1766,15 @@ impl Object3d {
         self.data.cull = active;
     }
 
+    /// Sets the winding of this object's front faces, e.g.
+    /// `Some(wgpu::FrontFace::Cw)` for a mesh exported with clockwise triangles
+    /// that vanishes with backface culling. `None` (the default) follows
+    /// [`Window::set_default_winding`](crate::window::Window::set_default_winding).
+    #[inline]
+    pub fn set_front_face(&mut self, front_face: Option<wgpu::FrontFace>) {
+        self.data.front_face = front_face;
+    }
+
     /// Attaches user-defined data to this object.
     #[inline]
     pub fn set_user_data(&mut self, user_data: Box<dyn Any + 'static>) {
@@ -1487,6 +1840,55 @@ impl Object3d {
         self.data.lines_color
     }
 
+    /// Restricts the wireframe to feature edges.
+    ///
+    /// With `Some(angle)`, only the boundary edges and the edges whose adjacent faces
+    /// meet at an angle (between their normals, in radians) larger than `angle` are
+    /// drawn, which outlines hard edges without the triangulation of flat or smooth
+    /// areas. With `None` (the default), every edge is drawn.
+    #[inline]
+    pub fn set_lines_feature_angle(&mut self, angle: Option<f32>) {
+        self.data.lines_feature_angle = angle
+    }
+
+    /// Returns the minimum dihedral angle of the edges drawn by the wireframe.
+    #[inline]
+    pub fn lines_feature_angle(&self) -> Option<f32> {
+        self.data.lines_feature_angle
+    }
+
+    /// Sets the depth bias (polygon offset) of the wireframe lines.
+    ///
+    /// The `constant` term is in units of the depth buffer's precision and
+    /// `slope_scale` multiplies the line's depth slope; negative values pull the
+    /// lines toward the camera. Use this to get rid of the z-fighting between a
+    /// wireframe and its own surface at grazing angles. Defaults to `(0, 0.0)`.
+    #[inline]
+    pub fn set_lines_depth_bias(&mut self, constant: i32, slope_scale: f32) {
+        self.data.lines_depth_bias = (constant, slope_scale)
+    }
+
+    /// Returns the depth bias of the wireframe lines, as `(constant, slope_scale)`.
+    #[inline]
+    pub fn lines_depth_bias(&self) -> (i32, f32) {
+        self.data.lines_depth_bias
+    }
+
+    /// Sets whether the wireframe lines are drawn on top of all geometry.
+    ///
+    /// When enabled, the lines are neither depth-tested nor written to the depth
+    /// buffer, so hidden edges show through the surfaces. Defaults to `false`.
+    #[inline]
+    pub fn set_lines_on_top(&mut self, on_top: bool) {
+        self.data.lines_on_top = on_top
+    }
+
+    /// Returns whether the wireframe lines are drawn on top of all geometry.
+    #[inline]
+    pub fn lines_on_top(&self) -> bool {
+        self.data.lines_on_top
+    }
+
     /// Sets the size of the points drawn for this object.
     ///
     /// If `use_perspective` is true, the size is in world units and scales with distance.
@@ -1533,6 +1935,27 @@ impl Object3d {
         &self.mesh
     }
 
+    /// Replaces this object's mesh. Other objects sharing the previous mesh are
+    /// not affected.
+    #[inline]
+    pub fn set_mesh(&mut self, mesh: Rc<RefCell<GpuMesh3d>>) {
+        self.mesh = mesh;
+    }
+
+    /// Sets the per-vertex colors of this object's mesh, one per vertex.
+    ///
+    /// If the mesh is shared with other objects (or with the
+    /// [`MeshManager3d`](crate::resource::MeshManager3d)), this object first gets
+    /// its own mesh, which still shares the geometry buffers, so the colors of
+    /// the other objects are not affected.
+    pub fn set_vertex_colors(&mut self, colors: Vec<Color>) {
+        if Rc::strong_count(&self.mesh) > 1 {
+            let mesh = self.mesh.borrow().share_geometry();
+            self.mesh = Rc::new(RefCell::new(mesh));
+        }
+        self.mesh.borrow_mut().set_colors(colors);
+    }
+
     /// Mutably access the object's vertices.
     #[inline(always)]
     pub fn modify_vertices<F: FnMut(&mut Vec<Vec3>)>(&mut self, f: &mut F) {
@@ -1726,6 +2149,22 @@ impl Object3d {
         self.data.roughness = roughness.clamp(0.0, 1.0);
     }
 
+    /// Makes this object reflect its environment, from `0.0` (the default matte
+    /// dielectric) to `1.0` (a polished mirror-like metal).
+    ///
+    /// A shortcut over the PBR parameters: sets the metallic factor to
+    /// `reflectivity` and the roughness to `1.0 - reflectivity`. The reflected
+    /// environment is the skybox / [`EnvironmentMap`](crate::renderer::EnvironmentMap),
+    /// or a reflection probe around the object, refined by screen-space
+    /// reflections where enabled
+    /// ([`Window::set_ssr_enabled`](crate::window::Window::set_ssr_enabled)).
+    #[inline]
+    pub fn set_reflectivity(&mut self, reflectivity: f32) {
+        let reflectivity = reflectivity.clamp(0.0, 1.0);
+        self.set_metallic(reflectivity);
+        self.set_roughness(1.0 - reflectivity);
+    }
+
     /// Sets the emissive color of this object.
     ///
     /// Objects with emissive color appear to glow. Values above 1.0 can be used for HDR.
@@ -1787,6 +2226,13 @@ impl Object3d {
         self.data.alpha_mode = alpha_mode;
     }
 
+    /// Sets which shading the default material applies to this object (see
+    /// [`ShadingMode`]). Ignored by custom materials.
+    #[inline]
+    pub fn set_shading_mode(&mut self, shading_mode: ShadingMode) {
+        self.data.shading_mode = shading_mode;
+    }
+
     // === Path-tracer BSDF Properties ===
 
     /// Selects the path-tracer BSDF model for this object (rasterizer unaffected).
@@ -2011,6 +2457,24 @@ impl Object3d {
         self.data.height_map = None;
     }
 
+    /// Sets the albedo texture array sampled by the instances of this object, each
+    /// instance reading the layer given by its
+    /// [`InstanceData3d::texture_layer`], so instances of one draw can show
+    /// different textures. Replaces the object's texture while set.
+    ///
+    /// Create one with
+    /// [`TextureManager::add_image_array`](crate::resource::TextureManager::add_image_array).
+    #[inline]
+    pub fn set_texture_array(&mut self, texture: Arc<Texture>) {
+        self.data.texture_array = Some(texture);
+    }
+
+    /// Clears the texture array: all instances use the object's texture again.
+    #[inline]
+    pub fn clear_texture_array(&mut self) {
+        self.data.texture_array = None;
+    }
+
     /// Sets the parallax displacement scale (surface depth in UV units). `0`
     /// disables parallax even when a height map is set; typical values are small
     /// (e.g. `0.03`–`0.1`).
@@ -2033,3 +2497,162 @@ impl Object3d {
         self.data.parallax_method = method;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::SceneNode3d;
+
+    #[test]
+    fn same_appearance_compares_every_appearance_field() {
+        crate::pollster::block_on(async {
+            if !crate::shader_validity::tests::adapter_available().await {
+                eprintln!("appearance test: no GPU adapter found, skipping");
+                return;
+            }
+            let _surface = crate::window::OffscreenSurface::new(8, 8).await;
+
+            let mut scene = SceneNode3d::empty();
+            let cube = scene.add_cube(1.0, 1.0, 1.0);
+            let data = cube.data();
+            let a = data.object().unwrap().data();
+            assert!(a.same_appearance(&a.clone_appearance()));
+
+            // Changes one field of a copy of `a` and checks whether the copy
+            // still looks the same.
+            let differs = |change: &dyn Fn(&mut ObjectData3d)| {
+                let mut b = a.clone_appearance();
+                change(&mut b);
+                !a.same_appearance(&b)
+            };
+            let texture = Texture::new_default;
+            let material: Rc<RefCell<Box<dyn Material3d>>> = Rc::new(RefCell::new(Box::new(
+                crate::builtin::NormalsMaterial::new(),
+            )));
+
+            // Every field must be listed here: adding one to `ObjectData3d` fails
+            // to compile until it is either compared by `same_appearance` (and
+            // checked below) or explicitly ignored.
+            let ObjectData3d {
+                material: _,
+                texture: _,
+                color: _,
+                lines_color: _,
+                lines_feature_angle: _,
+                lines_depth_bias: _,
+                lines_on_top: _,
+                points_color: _,
+                wlines: _,
+                wpoints: _,
+                lines_use_perspective: _,
+                points_use_perspective: _,
+                draw_surface: _,
+                cull: _,
+                front_face: _,
+                render_layers: _,
+                light_layers: _,
+                casts_shadows: _,
+                metallic: _,
+                roughness: _,
+                emissive: _,
+                alpha_mode: _,
+                shading_mode: _,
+                bsdf: _,
+                ior: _,
+                transmission: _,
+                specular_tint: _,
+                subsurface: _,
+                subsurface_radius: _,
+                thickness: _,
+                attenuation_color: _,
+                attenuation_distance: _,
+                reflectance: _,
+                clearcoat: _,
+                clearcoat_roughness: _,
+                anisotropy: _,
+                anisotropy_rotation: _,
+                ssr: _,
+                parallax_scale: _,
+                parallax_layers: _,
+                parallax_method: _,
+                normal_map: _,
+                metallic_roughness_map: _,
+                ao_map: _,
+                emissive_map: _,
+                height_map: _,
+                texture_array: _,
+                // Identity and per-object state: not part of the appearance.
+                segmentation_id: _,
+                user_data: _,
+                reflector: _,
+                skin: _,
+                morph_weights: _,
+                deform: _,
+                shadow_tex_bind_group: _,
+                cached_shadow_tex_ptr: _,
+            } = a;
+
+            assert!(differs(&|b| b.material = material.clone()));
+            assert!(differs(&|b| b.texture = texture()));
+            assert!(differs(&|b| b.color = Color::new(0.1, 0.2, 0.3, 1.0)));
+            assert!(differs(
+                &|b| b.lines_color = Some(Color::new(0.1, 0.2, 0.3, 1.0))
+            ));
+            assert!(differs(&|b| b.lines_feature_angle = Some(0.5)));
+            assert!(differs(&|b| b.lines_depth_bias = (3, 0.5)));
+            assert!(differs(&|b| b.lines_on_top = !b.lines_on_top));
+            assert!(differs(
+                &|b| b.points_color = Some(Color::new(0.1, 0.2, 0.3, 1.0))
+            ));
+            assert!(differs(&|b| b.wlines += 1.0));
+            assert!(differs(&|b| b.wpoints += 1.0));
+            assert!(differs(
+                &|b| b.lines_use_perspective = !b.lines_use_perspective
+            ));
+            assert!(differs(
+                &|b| b.points_use_perspective = !b.points_use_perspective
+            ));
+            assert!(differs(&|b| b.draw_surface = !b.draw_surface));
+            assert!(differs(&|b| b.cull = !b.cull));
+            assert!(differs(&|b| b.front_face = Some(wgpu::FrontFace::Cw)));
+            assert!(differs(&|b| b.render_layers ^= 2));
+            assert!(differs(&|b| b.light_layers ^= 2));
+            assert!(differs(&|b| b.casts_shadows = !b.casts_shadows));
+            assert!(differs(&|b| b.metallic += 0.5));
+            assert!(differs(&|b| b.roughness += 0.5));
+            assert!(differs(&|b| b.emissive = Color::new(0.1, 0.2, 0.3, 1.0)));
+            assert!(differs(&|b| b.alpha_mode = AlphaMode::Mask(0.5)));
+            assert!(differs(&|b| b.shading_mode = ShadingMode::Unlit));
+            assert!(differs(&|b| b.bsdf = Bsdf::Glass));
+            assert!(differs(&|b| b.ior += 0.5));
+            assert!(differs(&|b| b.transmission += 0.5));
+            assert!(differs(
+                &|b| b.specular_tint = Color::new(0.1, 0.2, 0.3, 1.0)
+            ));
+            assert!(differs(&|b| b.subsurface += 0.5));
+            assert!(differs(&|b| b.subsurface_radius += 0.5));
+            assert!(differs(&|b| b.thickness += 0.5));
+            assert!(differs(
+                &|b| b.attenuation_color = Color::new(0.1, 0.2, 0.3, 1.0)
+            ));
+            assert!(differs(&|b| b.attenuation_distance = 2.0));
+            assert!(differs(&|b| b.reflectance += 0.5));
+            assert!(differs(&|b| b.clearcoat += 0.5));
+            assert!(differs(&|b| b.clearcoat_roughness += 0.5));
+            assert!(differs(&|b| b.anisotropy += 0.5));
+            assert!(differs(&|b| b.anisotropy_rotation += 0.5));
+            assert!(differs(&|b| b.ssr = None));
+            assert!(differs(&|b| b.parallax_scale += 0.5));
+            assert!(differs(&|b| b.parallax_layers += 1.0));
+            assert!(differs(
+                &|b| b.parallax_method = ParallaxMethod::Relief { max_steps: 3 }
+            ));
+            assert!(differs(&|b| b.normal_map = Some(texture())));
+            assert!(differs(&|b| b.metallic_roughness_map = Some(texture())));
+            assert!(differs(&|b| b.ao_map = Some(texture())));
+            assert!(differs(&|b| b.emissive_map = Some(texture())));
+            assert!(differs(&|b| b.height_map = Some(texture())));
+            assert!(differs(&|b| b.texture_array = Some(texture())));
+        })
+    }
+}
@@ -411,6 +411,75 @@ impl SceneNode2d {
         Self::mesh(Rc::new(RefCell::new(capsule)), Vec2::ONE)
     }
 
+    /// Creates a new scene node with a rounded rectangle mesh.
+    ///
+    /// The rectangle is initially axis-aligned and centered at (0, 0).
+    ///
+    /// # Arguments
+    /// * `wx` - the rectangle extent along the x axis
+    /// * `wy` - the rectangle extent along the y axis
+    /// * `radius` - the corners radius, clamped to half the smallest extent
+    pub fn rounded_rectangle(wx: f32, wy: f32, radius: f32) -> SceneNode2d {
+        let radius = radius.clamp(0.0, wx.min(wy) / 2.0);
+        let half = Vec2::new(wx / 2.0 - radius, wy / 2.0 - radius);
+        let nsubdiv = arc_subdivisions(f32::consts::FRAC_PI_2);
+
+        let mut vtx = vec![Vec2::ZERO];
+        let corners = [
+            Vec2::new(half.x, half.y),
+            Vec2::new(-half.x, half.y),
+            Vec2::new(-half.x, -half.y),
+            Vec2::new(half.x, -half.y),
+        ];
+        for (k, corner) in corners.iter().enumerate() {
+            for i in 0..=nsubdiv {
+                let ang = (k as f32 + i as f32 / nsubdiv as f32) * f32::consts::FRAC_PI_2;
+                vtx.push(*corner + Vec2::new(ang.cos(), ang.sin()) * radius);
+            }
+        }
+
+        let n = vtx.len() as VertexIndex - 1;
+        let ids = (1..=n).map(|i| [0, i, i % n + 1]).collect();
+        let mesh = GpuMesh2d::new(vtx, ids, None, false);
+        Self::mesh(Rc::new(RefCell::new(mesh)), Vec2::ONE)
+    }
+
+    /// Creates a new scene node with an annulus (ring) mesh.
+    ///
+    /// The annulus is initially centered at (0, 0).
+    ///
+    /// # Arguments
+    /// * `r_in` - the radius of the hole
+    /// * `r_out` - the outer radius
+    pub fn annulus(r_in: f32, r_out: f32) -> SceneNode2d {
+        Self::ring_sector(r_in, r_out, 0.0, f32::consts::TAU)
+    }
+
+    /// Creates a new scene node with a thick circular arc mesh, e.g. for gauges.
+    ///
+    /// The arc is centered at (0, 0) and goes counterclockwise from the angle
+    /// `start` to the angle `end` (in radians, measured from the x axis). If `end`
+    /// is smaller than `start`, the arc wraps around through the angle 0, e.g.
+    /// from `3π/2` to `π/2` covers the right half of the circle.
+    ///
+    /// # Arguments
+    /// * `r` - the radius of the arc centerline
+    /// * `start` - the angle where the arc starts
+    /// * `end` - the angle where the arc ends
+    /// * `thickness` - the arc width, centered on its centerline
+    pub fn arc(r: f32, start: f32, end: f32, thickness: f32) -> SceneNode2d {
+        let half = thickness.abs() / 2.0;
+        Self::ring_sector((r - half).max(0.0), r + half, start, end)
+    }
+
+    /// A ring sector between the radii `r_in` and `r_out` and the angles `start`
+    /// and `end`, tessellated as a triangle strip.
+    fn ring_sector(r_in: f32, r_out: f32, start: f32, end: f32) -> SceneNode2d {
+        let (vtx, ids) = ring_sector_geometry(r_in, r_out, start, end);
+        let mesh = GpuMesh2d::new(vtx, ids, None, false);
+        Self::mesh(Rc::new(RefCell::new(mesh)), Vec2::ONE)
+    }
+
     /// Creates a new scene node with a polyline.
     pub fn polyline(
         vertices: Vec<Vec2>,
@@ -671,6 +740,33 @@ impl SceneNode2d {
         node
     }
 
+    /// Adds a rounded rectangle as a child of this node.
+    ///
+    /// See [`Self::rounded_rectangle`].
+    pub fn add_rounded_rectangle(&mut self, wx: f32, wy: f32, radius: f32) -> SceneNode2d {
+        let node = Self::rounded_rectangle(wx, wy, radius);
+        self.add_child(node.clone());
+        node
+    }
+
+    /// Adds an annulus (ring) as a child of this node.
+    ///
+    /// See [`Self::annulus`].
+    pub fn add_annulus(&mut self, r_in: f32, r_out: f32) -> SceneNode2d {
+        let node = Self::annulus(r_in, r_out);
+        self.add_child(node.clone());
+        node
+    }
+
+    /// Adds a thick circular arc as a child of this node.
+    ///
+    /// See [`Self::arc`].
+    pub fn add_arc(&mut self, r: f32, start: f32, end: f32, thickness: f32) -> SceneNode2d {
+        let node = Self::arc(r, start, end, thickness);
+        self.add_child(node.clone());
+        node
+    }
+
     /// Creates and adds a new object using the geometry registered as `geometry_name`.
     pub fn add_geom_with_name(&mut self, geometry_name: &str, scale: Vec2) -> Option<SceneNode2d> {
         Self::geom_with_name(geometry_name, scale).inspect(|node| {
@@ -1570,3 +1666,70 @@ impl SceneNode2d {
             .instance_compute_buffers(count)
     }
 }
+
+/// The vertices and triangles of the ring sector between the radii `r_in` and
+/// `r_out` going counterclockwise from `start` to `end`, one turn later if
+/// `end < start`.
+fn ring_sector_geometry(
+    r_in: f32,
+    r_out: f32,
+    start: f32,
+    end: f32,
+) -> (Vec<Vec2>, Vec<[VertexIndex; 3]>) {
+    let end = if end < start {
+        end + f32::consts::TAU
+    } else {
+        end
+    };
+    let nsubdiv = arc_subdivisions(end - start);
+
+    let mut vtx = Vec::with_capacity(2 * (nsubdiv as usize + 1));
+    let mut ids = Vec::with_capacity(2 * nsubdiv as usize);
+    for i in 0..=nsubdiv {
+        let ang = start + (end - start) * i as f32 / nsubdiv as f32;
+        let dir = Vec2::new(ang.cos(), ang.sin());
+        vtx.push(dir * r_in);
+        vtx.push(dir * r_out);
+
+        if i > 0 {
+            let k = 2 * i as VertexIndex;
+            ids.push([k - 2, k - 1, k + 1]);
+            ids.push([k - 2, k + 1, k]);
+        }
+    }
+
+    (vtx, ids)
+}
+
+/// Number of segments tessellating a circular arc spanning `angle` radians: 64
+/// per full turn, and at least 2.
+fn arc_subdivisions(angle: f32) -> u32 {
+    ((angle.abs() / f32::consts::TAU * 64.0).ceil() as u32).max(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reversed_arc_angles_wrap_around_zero() {
+        let (start, end) = (3.0 * f32::consts::FRAC_PI_2, f32::consts::FRAC_PI_2);
+        let (vtx, ids) = ring_sector_geometry(1.0, 2.0, start, end);
+        // Tessellated as half a turn (give or take the rounding of the span).
+        assert!(ids.len() <= 2 * (arc_subdivisions(f32::consts::PI) as usize + 1));
+
+        // Half a turn through the angle 0: the right half of the ring, not the
+        // left one a swap of the angles would draw.
+        let outer: Vec<_> = vtx.iter().skip(1).step_by(2).collect();
+        assert!(outer.iter().all(|v| v.x >= -1.0e-5));
+        assert!(outer
+            .first()
+            .unwrap()
+            .abs_diff_eq(Vec2::new(0.0, -2.0), 1.0e-5));
+        assert!(outer
+            .last()
+            .unwrap()
+            .abs_diff_eq(Vec2::new(0.0, 2.0), 1.0e-5));
+        assert!(outer.iter().any(|v| v.x > 1.99));
+    }
+}
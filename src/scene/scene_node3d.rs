@@ -1,32 +1,246 @@
 use crate::camera::Camera3d;
-use crate::color::Color;
+use crate::color::{Color, Colormap};
 use crate::light::{CollectedLight, Light, LightCollection, LightType};
+use crate::loader::mtl::MtlMaterial;
 use crate::procedural;
-use crate::procedural::{IndexBuffer, RenderMesh};
+use crate::procedural::{IndexBuffer, NormalMode, RenderMesh};
 use crate::resource::vertex_index::VertexIndex;
 use crate::resource::{
-    GpuMesh3d, Material3d, MaterialManager3d, MeshManager3d, RenderContext, Texture, TextureManager,
+    GpuMesh3d, Material3d, MaterialManager3d, MeshManager3d, RenderContext, Texture, TextureLoad,
+    TextureManager,
 };
-use crate::scene::{AlphaMode, AnimationPlayer, Bsdf, InstanceData3d, Object3d};
+use crate::scene::object3d::DrawSortKey;
+use crate::scene::{
+    AlphaMode, AnimationPlayer, Bsdf, InstanceData3d, Object3d, ObjectData3d, ShadingMode,
+};
+use glamx::glam::DVec3;
 use glamx::{Mat3, Mat4, Pose3, Quat, Vec2, Vec3};
-use std::cell::{Ref, RefCell, RefMut};
-use std::path::{Path, PathBuf};
+use std::any::Any;
+use std::cell::{Cell, Ref, RefCell, RefMut};
+use std::collections::HashMap;
+use std::path::Path;
 use std::rc::Rc;
 use std::rc::Weak;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
+/// A lightweight, copyable identifier of a [`SceneNode3d`].
+///
+/// Unlike the node handle itself, an id holds no reference to the node and
+/// never borrows it, so it can be stored in hash maps, closures or command
+/// queues freely. Resolve it back with [`SceneNode3d::from_id`] (or
+/// [`Window::node`](crate::window::Window::node)), which returns `None` once the
+/// node has been dropped. Ids are never reused.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SceneNodeId(u64);
+
+impl SceneNodeId {
+    /// The raw value of this id.
+    #[inline]
+    pub fn get(self) -> u64 {
+        self.0
+    }
+}
+
+static NEXT_NODE_ID: AtomicU64 = AtomicU64::new(0);
+
+// Bumped whenever a double-precision position is set or cleared, or the
+// transform of a node with one is edited, so the window only rebases the scene
+// when the origin or one of these nodes changed.
+static PRECISE_POSITION_EDITS: AtomicU64 = AtomicU64::new(0);
+
+// Bumped whenever a node is added, removed, shown or hidden, so the automatic
+// instancing only regroups the objects of a scene when its structure changed.
+static SCENE_GRAPH_EDITS: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    // Every live node of this thread, by id. Entries are removed when the node's
+    // data is dropped.
+    static NODE_REGISTRY: RefCell<HashMap<SceneNodeId, Weak<RefCell<SceneNodeData3d>>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// The shape and dimensions of a node created by one of the primitive
+/// constructors of [`SceneNode3d`], e.g. [`SceneNode3d::cube`], changed with
+/// setters like [`SceneNode3d::set_cube_extents`].
+///
+/// `subdivs` is `None` for the primitives sharing the built-in geometry, which
+/// are sized by their node's local scale, and the subdivisions of the node's own
+/// mesh otherwise.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Primitive3d {
+    /// A box with the given extents along each axis.
+    Cube {
+        /// The extents along the `x`, `y` and `z` axes.
+        extents: Vec3,
+    },
+    /// A sphere.
+    Sphere {
+        /// The sphere radius.
+        radius: f32,
+        /// The longitude and latitude subdivisions.
+        subdivs: Option<(u32, u32)>,
+    },
+    /// A cone along the `y` axis.
+    Cone {
+        /// The base radius.
+        radius: f32,
+        /// The height.
+        height: f32,
+        /// The subdivisions of the base circle.
+        subdivs: Option<u32>,
+    },
+    /// A cylinder along the `y` axis.
+    Cylinder {
+        /// The base radius.
+        radius: f32,
+        /// The height.
+        height: f32,
+        /// The subdivisions of the circumference.
+        subdivs: Option<u32>,
+    },
+    /// A capsule along the `y` axis.
+    Capsule {
+        /// The radius of the caps.
+        radius: f32,
+        /// The height of the cylindrical part.
+        height: f32,
+        /// The longitude and (per cap) latitude subdivisions.
+        subdivs: (u32, u32),
+    },
+}
+
+impl Primitive3d {
+    /// The name of the built-in geometry and the node scale of a primitive
+    /// sharing it, or `None` if it has its own mesh.
+    fn shared_geometry(&self) -> Option<(&'static str, Vec3)> {
+        match *self {
+            Primitive3d::Cube { extents } => Some(("cube", extents)),
+            Primitive3d::Sphere {
+                radius,
+                subdivs: None,
+            } => Some(("sphere", Vec3::splat(radius * 2.0))),
+            Primitive3d::Cone {
+                radius,
+                height,
+                subdivs: None,
+            } => Some(("cone", Vec3::new(radius * 2.0, height, radius * 2.0))),
+            Primitive3d::Cylinder {
+                radius,
+                height,
+                subdivs: None,
+            } => Some(("cylinder", Vec3::new(radius * 2.0, height, radius * 2.0))),
+            _ => None,
+        }
+    }
+
+    /// The part of the node scale that comes from the dimensions of the
+    /// primitive: the scale of its shared geometry, or one if it has its own mesh.
+    pub(crate) fn extents_scale(&self) -> Vec3 {
+        self.shared_geometry().map_or(Vec3::ONE, |(_, scale)| scale)
+    }
+
+    /// The mesh of a primitive with its own mesh, or `None` if it shares the
+    /// built-in geometry.
+    fn render_mesh(&self) -> Option<RenderMesh> {
+        match *self {
+            Primitive3d::Sphere {
+                radius,
+                subdivs: Some((ntheta, nphi)),
+            } => Some(procedural::sphere(radius * 2.0, ntheta, nphi, true)),
+            Primitive3d::Cone {
+                radius,
+                height,
+                subdivs: Some(nsubdiv),
+            } => Some(procedural::cone(radius * 2.0, height, nsubdiv)),
+            Primitive3d::Cylinder {
+                radius,
+                height,
+                subdivs: Some(nsubdiv),
+            } => Some(procedural::cylinder(radius * 2.0, height, nsubdiv)),
+            Primitive3d::Capsule {
+                radius,
+                height,
+                subdivs: (ntheta, nphi),
+            } => Some(procedural::capsule(radius * 2.0, height, ntheta, nphi)),
+            _ => None,
+        }
+    }
+}
+
 /// The data contained by a `SceneNode`.
 pub struct SceneNodeData3d {
+    id: SceneNodeId,
+    name: Option<String>,
+    user_data: Option<Box<dyn Any>>,
+    /// Coordinate-frame arrows added by `SceneNode3d::show_axes`. They follow
+    /// this node like a child but aren't one, so the scene graph queries and
+    /// exports don't see them.
+    axes: Option<SceneNode3d>,
     local_scale: Vec3,
     local_transform: Pose3,
     world_scale: Vec3,
     world_transform: Pose3,
     visible: bool,
     up_to_date: bool,
+    /// Whether a world transform of this subtree may be out of date, so that
+    /// the transform propagation skips the subtrees where nothing moved.
+    subtree: Rc<SubtreeFlag>,
     children: Vec<SceneNode3d>,
     object: Option<Object3d>,
+    /// The primitive this node was created as, if any.
+    primitive: Option<Primitive3d>,
+    /// The double-precision world position of this node, if any (see
+    /// [`SceneNode3d::set_position_f64`]).
+    precise_position: Option<DVec3>,
     light: Option<Light>,
     parent: Option<Weak<RefCell<SceneNodeData3d>>>,
+    /// Whether `prepare` merges the objects of this subtree sharing a mesh and an
+    /// appearance into instanced draws (see [`SceneNode3d::set_auto_instancing`]).
+    auto_instancing: bool,
+    /// The instanced draws merged by the last `prepare` of this subtree.
+    auto_instances: Vec<AutoInstanceBatch>,
+    /// The value of `SCENE_GRAPH_EDITS` when `auto_instances` were grouped.
+    auto_instances_edits: Option<u64>,
+    /// The draws of this subtree sorted by the last `prepare`, shared by every
+    /// render phase of the pass.
+    draw_queue: Vec<(DrawSortKey, DrawItem)>,
+    /// Whether this node's object is currently drawn by an ancestor's
+    /// automatically instanced draw instead of on its own.
+    auto_instanced: bool,
+}
+
+/// The "some world transform of this subtree is out of date" flag of a node,
+/// linked to the flag of its parent.
+///
+/// It lives outside of the node data so that moving a node can flag its
+/// ancestors even while they are borrowed (e.g. while iterating over their
+/// children).
+struct SubtreeFlag {
+    dirty: Cell<bool>,
+    parent: RefCell<Option<Rc<SubtreeFlag>>>,
+}
+
+impl SubtreeFlag {
+    fn new() -> Self {
+        SubtreeFlag {
+            dirty: Cell::new(true),
+            parent: RefCell::new(None),
+        }
+    }
+
+    /// Flags this subtree and the subtrees of all the ancestors.
+    fn mark_dirty(&self) {
+        self.dirty.set(true);
+        let mut parent = self.parent.borrow().clone();
+        while let Some(flag) = parent {
+            // The ancestors of a dirty subtree are dirty too.
+            if flag.dirty.replace(true) {
+                break;
+            }
+            parent = flag.parent.borrow().clone();
+        }
+    }
 }
 
 /// A node of the scene graph.
@@ -35,6 +249,8 @@ pub struct SceneNodeData3d {
 #[derive(Clone)]
 pub struct SceneNode3d {
     data: Rc<RefCell<SceneNodeData3d>>,
+    // Copy of `data.id`, readable while the data is borrowed.
+    id: SceneNodeId,
 }
 
 /// A loaded glTF / GLB model: the scene subtree plus its animations.
@@ -49,6 +265,142 @@ pub struct GltfModel {
     pub player: AnimationPlayer,
 }
 
+/// Geometry merged by [`SceneNode3d::bake_static`] for objects sharing one
+/// appearance.
+struct StaticBatch {
+    data: ObjectData3d,
+    coords: Vec<Vec3>,
+    normals: Vec<Vec3>,
+    uvs: Vec<Vec2>,
+    faces: Vec<[u32; 3]>,
+}
+
+/// Objects sharing a mesh and an appearance, drawn as the instances of a single
+/// stand-in object by the automatic instancing.
+struct AutoInstanceBatch {
+    object: Object3d,
+    /// The scale the object is drawn with: one, unless its instances share a
+    /// non-uniform scale (which the instance deformations can't carry, as they
+    /// also transform the normals).
+    scale: Vec3,
+    /// The nodes drawn by this batch, one per instance.
+    members: Vec<SceneNode3d>,
+    /// The `(position, deformation, color)` of the instances last uploaded, to
+    /// only upload the instances that changed.
+    instances: Vec<(Vec3, Mat3, Color)>,
+}
+
+impl AutoInstanceBatch {
+    /// Whether every member can still be drawn by this batch. Moving a node
+    /// doesn't change the scene structure, but may change its world scale.
+    fn is_valid(&self) -> bool {
+        self.members.iter().all(|m| {
+            let m = m.data();
+            m.visible
+                && m.object.as_ref().is_some_and(|o| {
+                    o.is_auto_instanceable() && o.shares_instanced_draw(&self.object)
+                })
+                && auto_instance_scale(m.world_scale) == self.scale
+        })
+    }
+
+    /// The instance standing for `member`.
+    fn instance(&self, member: &SceneNodeData3d) -> (Vec3, Mat3, Color) {
+        let color = member.object.as_ref().unwrap().data().color();
+        let (position, deformation) =
+            auto_instance_transform(member.world_transform, member.world_scale, self.scale);
+        (
+            position,
+            deformation,
+            Color::new(color.r, color.g, color.b, 1.0),
+        )
+    }
+
+    /// Uploads the instances of the members, all of them at once.
+    fn upload_instances(&mut self) {
+        self.instances = self
+            .members
+            .iter()
+            .map(|m| self.instance(&m.data()))
+            .collect();
+        let data: Vec<_> = self
+            .instances
+            .iter()
+            .map(|(position, deformation, color)| InstanceData3d {
+                position: *position,
+                deformation: *deformation,
+                color: *color,
+                ..Default::default()
+            })
+            .collect();
+        self.object.set_instances(&data);
+    }
+
+    /// Uploads the instances of the members that moved or changed color since
+    /// the last upload.
+    fn refresh_instances(&mut self) {
+        let mut buffers = self.object.instances().borrow_mut();
+        for (i, member) in self.members.iter().enumerate() {
+            let instance = self.instance(&member.data());
+            if self.instances[i] == instance {
+                continue;
+            }
+            let (position, deformation, color) = instance;
+            buffers.positions.update_range(i, &[position]);
+            buffers.deformations.update_range(
+                3 * i,
+                &[deformation.x_axis, deformation.y_axis, deformation.z_axis],
+            );
+            buffers
+                .colors
+                .update_range(i, &[[color.r, color.g, color.b, color.a]]);
+            self.instances[i] = instance;
+        }
+    }
+
+    /// Whether this batch draws the shadows of its members, which it does for
+    /// opaque objects only: the colored shadows of transparent ones use the
+    /// color of each object.
+    fn draws_shadows(&self) -> bool {
+        self.object.casts_shadows() && self.object.data().color().a == 1.0
+    }
+}
+
+/// Minimum number of objects sharing a mesh and an appearance for the automatic
+/// instancing to merge them into an instanced draw.
+const MIN_AUTO_INSTANCES: usize = 2;
+
+/// The scale an automatic instancing batch is drawn with, given the world scale
+/// of its members (see [`AutoInstanceBatch::scale`]).
+fn auto_instance_scale(world_scale: Vec3) -> Vec3 {
+    if world_scale.x == world_scale.y && world_scale.y == world_scale.z {
+        Vec3::ONE
+    } else {
+        world_scale
+    }
+}
+
+/// The `(position, deformation)` of the instance standing for an object of
+/// world transform `world` and world scale `world_scale` in a batch drawn with
+/// `batch_scale`, so that the instanced draw places its vertices where the
+/// object's own draw would.
+fn auto_instance_transform(world: Pose3, world_scale: Vec3, batch_scale: Vec3) -> (Vec3, Mat3) {
+    (
+        world.translation,
+        Mat3::from_quat(world.rotation) * Mat3::from_diagonal(world_scale / batch_scale),
+    )
+}
+
+/// An entry of the draw queue of [`SceneNodeData3d::render`].
+enum DrawItem {
+    /// The object of the node the queue is rendered from (already borrowed).
+    Own,
+    /// The object of a descendant.
+    Node(SceneNode3d),
+    /// An automatically instanced draw of the node the queue is rendered from.
+    AutoInstances(usize),
+}
+
 impl SceneNodeData3d {
     // XXX: Because `node.borrow_mut().parent = Some(self.data.downgrade())`
     // causes a weird compiler error:
@@ -59,8 +411,9 @@ impl SceneNodeData3d {
     // `std::option::Option<std::rc::Weak<std::cell::RefCell<scene::scene_node::SceneNodeData>>>`
     // (expe cted &-ptr but found enum std::option::Option)
     // ```
-    fn set_parent(&mut self, parent: Weak<RefCell<SceneNodeData3d>>) {
+    fn set_parent(&mut self, parent: Weak<RefCell<SceneNodeData3d>>, flag: Rc<SubtreeFlag>) {
         self.parent = Some(parent);
+        *self.subtree.parent.borrow_mut() = Some(flag);
     }
 
     // TODO: this exists because of a similar bug as `set_parent`.
@@ -129,8 +482,163 @@ impl SceneNodeData3d {
             // Pass 1: Collect all lights and update transforms
             self.do_collect_lights(lights);
 
+            // Merge the objects sharing a mesh and an appearance into instanced draws.
+            if self.auto_instancing {
+                self.update_auto_instances();
+            } else {
+                self.clear_auto_instances();
+            }
+
             // Pass 2: Prepare all objects with the complete light collection
             self.do_prepare_objects(pass, camera, lights, viewport_width, viewport_height);
+            for batch in &mut self.auto_instances {
+                batch.object.prepare(
+                    Pose3::IDENTITY,
+                    batch.scale,
+                    pass,
+                    camera,
+                    lights,
+                    viewport_width,
+                    viewport_height,
+                );
+            }
+
+            self.update_draw_queue();
+        } else {
+            self.draw_queue.clear();
+        }
+    }
+
+    /// Sorts the visible objects of this subtree into the draw queue used by
+    /// `render`, grouped by material, pipeline variant and texture rather than in
+    /// traversal order, so consecutive draws share pipelines and bind groups. The
+    /// sort is stable: objects with equal keys keep their traversal order.
+    fn update_draw_queue(&mut self) {
+        let mut queue = std::mem::take(&mut self.draw_queue);
+        queue.clear();
+        if let Some(ref o) = self.object {
+            queue.push((o.draw_sort_key(), DrawItem::Own));
+        }
+        self.collect_draw_queue(&mut queue);
+        for (i, batch) in self.auto_instances.iter().enumerate() {
+            queue.push((batch.object.draw_sort_key(), DrawItem::AutoInstances(i)));
+        }
+        queue.sort_by_key(|(key, _)| *key);
+        self.draw_queue = queue;
+    }
+
+    /// Updates the instanced draws of the objects of the visible descendants
+    /// sharing a mesh and an appearance. Their nodes are then skipped by
+    /// `prepare` and `render`.
+    ///
+    /// The objects are only regrouped when the scene structure changed or when a
+    /// batch can't draw one of its members anymore; otherwise, only the instances
+    /// of the members that moved or changed color are uploaded.
+    fn update_auto_instances(&mut self) {
+        let edits = SCENE_GRAPH_EDITS.load(Ordering::Relaxed);
+        if self.auto_instances_edits != Some(edits)
+            || !self.auto_instances.iter().all(AutoInstanceBatch::is_valid)
+        {
+            self.regroup_auto_instances();
+            self.auto_instances_edits = Some(edits);
+        } else {
+            for batch in &mut self.auto_instances {
+                batch.refresh_instances();
+            }
+        }
+    }
+
+    /// Groups the auto-instanceable objects of the visible descendants by mesh and
+    /// appearance, and rebuilds the instanced draws of the groups large enough.
+    fn regroup_auto_instances(&mut self) {
+        for batch in &self.auto_instances {
+            for m in &batch.members {
+                m.data.borrow_mut().auto_instanced = false;
+            }
+        }
+
+        let mut groups: Vec<Vec<SceneNode3d>> = Vec::new();
+        let mut by_key: HashMap<_, Vec<usize>> = HashMap::new();
+        // Walk the subtree with a stack of handles rather than recursively, so no
+        // node is borrowed while the others are compared to the groups.
+        let mut stack: Vec<SceneNode3d> = self.children.iter().rev().cloned().collect();
+        while let Some(node) = stack.pop() {
+            let data = node.data();
+            if !data.visible {
+                continue;
+            }
+            stack.extend(data.children.iter().rev().cloned());
+            let Some(o) = data.object.as_ref().filter(|o| o.is_auto_instanceable()) else {
+                continue;
+            };
+
+            // Objects with a non-uniform scale are only grouped with objects of
+            // the same scale (see `AutoInstanceBatch::scale`).
+            let s = data.world_scale;
+            let scale_key = if s.x == s.y && s.y == s.z {
+                None
+            } else {
+                Some(s.to_array().map(f32::to_bits))
+            };
+            let key = (Rc::as_ptr(o.mesh()) as usize, o.draw_sort_key(), scale_key);
+            let candidates = by_key.entry(key).or_default();
+            let group = candidates.iter().copied().find(|&i| {
+                groups[i][0]
+                    .data()
+                    .object
+                    .as_ref()
+                    .is_some_and(|g| g.shares_instanced_draw(o))
+            });
+            drop(data);
+            match group {
+                Some(i) => groups[i].push(node),
+                None => {
+                    candidates.push(groups.len());
+                    groups.push(vec![node]);
+                }
+            }
+        }
+
+        let mut previous = std::mem::take(&mut self.auto_instances);
+        for members in groups {
+            if members.len() < MIN_AUTO_INSTANCES {
+                continue;
+            }
+
+            for m in &members {
+                m.data.borrow_mut().auto_instanced = true;
+            }
+            let scale = auto_instance_scale(members[0].data().world_scale);
+            let first_data = members[0].data();
+            let first = first_data.object.as_ref().unwrap();
+            // Reuse the stand-in object of a previous batch to keep its buffers.
+            let object = match previous
+                .iter()
+                .position(|b| b.object.shares_instanced_draw(first))
+            {
+                Some(i) => previous.swap_remove(i).object,
+                None => first.auto_instance_object(),
+            };
+            drop(first_data);
+            let mut batch = AutoInstanceBatch {
+                object,
+                scale,
+                members,
+                instances: Vec::new(),
+            };
+            batch.upload_instances();
+            self.auto_instances.push(batch);
+        }
+    }
+
+    /// Drops the instanced draws and draws every object of this subtree on its own
+    /// again.
+    fn clear_auto_instances(&mut self) {
+        self.auto_instances_edits = None;
+        for batch in self.auto_instances.drain(..) {
+            for m in &batch.members {
+                m.data.borrow_mut().auto_instanced = false;
+            }
         }
     }
 
@@ -141,11 +649,17 @@ impl SceneNodeData3d {
             self.world_scale = scale * self.local_scale;
         }
 
+        // Nothing moved below this node since the last propagation.
+        if !self.subtree.dirty.get() {
+            return;
+        }
+
         // Recurse to children
-        for c in self.children.iter_mut() {
+        for c in self.children.iter_mut().chain(self.axes.as_mut()) {
             let mut bc = c.data_mut();
             bc.do_propagate_transforms(self.world_transform, self.world_scale);
         }
+        self.subtree.dirty.set(false);
     }
 
     /// First pass: update transforms and collect all lights from the scene tree.
@@ -189,21 +703,23 @@ impl SceneNodeData3d {
         viewport_width: u32,
         viewport_height: u32,
     ) {
-        // Prepare this node's object
+        // Prepare this node's object, unless it is drawn by an instanced draw.
         if let Some(ref mut o) = self.object {
-            o.prepare(
-                self.world_transform,
-                self.world_scale,
-                pass,
-                camera,
-                lights,
-                viewport_width,
-                viewport_height,
-            );
+            if !self.auto_instanced {
+                o.prepare(
+                    self.world_transform,
+                    self.world_scale,
+                    pass,
+                    camera,
+                    lights,
+                    viewport_width,
+                    viewport_height,
+                );
+            }
         }
 
         // Recurse to children
-        for c in self.children.iter_mut() {
+        for c in self.children.iter_mut().chain(self.axes.as_mut()) {
             let mut bc = c.data_mut();
             if bc.visible {
                 bc.do_prepare_objects(pass, camera, lights, viewport_width, viewport_height);
@@ -211,7 +727,8 @@ impl SceneNodeData3d {
         }
     }
 
-    /// Render the scene graph rooted by this node.
+    /// Render the scene graph rooted by this node, in the draw order sorted by
+    /// the last [`prepare`](Self::prepare).
     pub fn render(
         &mut self,
         pass: usize,
@@ -233,24 +750,134 @@ impl SceneNodeData3d {
         render_pass: &mut wgpu::RenderPass<'_>,
         context: &RenderContext,
     ) {
-        if let Some(ref mut o) = self.object {
-            o.render(
-                self.world_transform,
-                self.world_scale,
-                pass,
-                camera,
-                lights,
-                render_pass,
-                context,
-            )
+        // Draw in the order sorted by `prepare`. The queue is moved out while
+        // drawing so that the own object can be drawn through `self`.
+        let queue = std::mem::take(&mut self.draw_queue);
+        for (_, item) in &queue {
+            match item {
+                DrawItem::Own => {
+                    self.render_object_only(pass, camera, lights, render_pass, context)
+                }
+                DrawItem::Node(node) => node.data.borrow_mut().render_object_only(
+                    pass,
+                    camera,
+                    lights,
+                    render_pass,
+                    context,
+                ),
+                DrawItem::AutoInstances(i) => {
+                    let batch = &mut self.auto_instances[*i];
+                    batch.object.render(
+                        Pose3::IDENTITY,
+                        batch.scale,
+                        pass,
+                        camera,
+                        lights,
+                        render_pass,
+                        context,
+                    )
+                }
+            }
         }
+        self.draw_queue = queue;
+    }
 
-        for c in self.children.iter_mut() {
-            let mut bc = c.data_mut();
+    /// Appends the visible descendants carrying an object drawn on its own to
+    /// `queue`, with their draw sort key.
+    fn collect_draw_queue(&self, queue: &mut Vec<(DrawSortKey, DrawItem)>) {
+        for c in self.children.iter().chain(self.axes.as_ref()) {
+            let bc = c.data();
             if bc.visible {
-                bc.do_render(pass, camera, lights, render_pass, context)
+                if let Some(ref o) = bc.object {
+                    if !bc.auto_instanced {
+                        queue.push((o.draw_sort_key(), DrawItem::Node(c.clone())));
+                    }
+                }
+                bc.collect_draw_queue(queue);
+            }
+        }
+    }
+
+    /// Merges the batchable object of every node of this visible subtree into
+    /// `batches` (see [`SceneNode3d::bake_static`]) and hides it. `transform` and
+    /// `scale` are those of this node's parent relative to the baked frame.
+    fn collect_static_batches(
+        &mut self,
+        transform: Pose3,
+        scale: Vec3,
+        batches: &mut Vec<StaticBatch>,
+    ) {
+        if !self.visible {
+            return;
+        }
+
+        let transform = transform * self.local_transform;
+        let scale = scale * self.local_scale;
+
+        if let Some(ref mut o) = self.object {
+            let part = if o.is_static_batchable() {
+                o.mesh().borrow().to_render_mesh()
+            } else {
+                None
+            };
+
+            if let Some(mut part) = part {
+                if !matches!(&part.normals, Some(n) if n.len() == part.coords.len()) {
+                    part.recompute_normals();
+                }
+
+                let batch = match batches
+                    .iter()
+                    .position(|b| b.data.same_appearance(o.data()))
+                {
+                    Some(i) => &mut batches[i],
+                    None => {
+                        batches.push(StaticBatch {
+                            data: o.data().clone_appearance(),
+                            coords: Vec::new(),
+                            normals: Vec::new(),
+                            uvs: Vec::new(),
+                            faces: Vec::new(),
+                        });
+                        batches.last_mut().unwrap()
+                    }
+                };
+
+                let base = batch.coords.len() as u32;
+                let rot = transform.rotation;
+                let tra = transform.translation;
+                batch
+                    .coords
+                    .extend(part.coords.iter().map(|p| rot * (*p * scale) + tra));
+                batch.normals.extend(
+                    part.normals
+                        .iter()
+                        .flatten()
+                        .map(|n| (rot * (*n / scale)).normalize_or_zero()),
+                );
+                match part.uvs {
+                    Some(uvs) if uvs.len() == part.coords.len() => batch.uvs.extend(uvs),
+                    _ => batch
+                        .uvs
+                        .extend(std::iter::repeat_n(Vec2::ZERO, part.coords.len())),
+                }
+                batch.faces.extend(
+                    part.indices
+                        .unwrap_unified()
+                        .into_iter()
+                        .map(|t| t.map(|i| i + base)),
+                );
+
+                // Keep the original object (and its node) but stop drawing it.
+                o.set_render_layers(0);
+                o.set_casts_shadows(false);
             }
         }
+
+        for c in self.children.iter_mut() {
+            c.data_mut()
+                .collect_static_batches(transform, scale, batches);
+        }
     }
 
     /// Renders only this node's own object (not its children). Used by the
@@ -289,13 +916,16 @@ impl SceneNodeData3d {
     pub fn collect_shadow_models(&self, f: &mut dyn FnMut(Pose3, Vec3, Color)) {
         if self.visible {
             if let Some(ref o) = self.object {
-                if o.casts_shadows() {
+                if o.casts_shadows() && !self.shadow_drawn_by_batch(o) {
                     f(self.world_transform, self.world_scale, o.data().color());
                 }
             }
             for c in self.children.iter() {
                 c.data().collect_shadow_models(f);
             }
+            for batch in self.auto_instances.iter().filter(|b| b.draws_shadows()) {
+                f(Pose3::IDENTITY, batch.scale, batch.object.data().color());
+            }
         }
     }
 
@@ -310,9 +940,22 @@ impl SceneNodeData3d {
     /// shadow-casting vertex count; it runs once per frame, before the pre-pass.
     #[doc(hidden)]
     pub fn shadow_casters_world_aabb(&self) -> Option<(Vec3, Vec3)> {
+        self.aabb(true)
+    }
+
+    /// Computes the world-space axis-aligned bounding box of the visible
+    /// geometry of this subtree, as `(min, max)`, or `None` if there is none.
+    ///
+    /// This relies on the world transforms of the last transform propagation;
+    /// see [`SceneNode3d::world_aabb`] for a version bringing them up to date.
+    pub fn world_aabb(&self) -> Option<(Vec3, Vec3)> {
+        self.aabb(false)
+    }
+
+    fn aabb(&self, casters_only: bool) -> Option<(Vec3, Vec3)> {
         let mut min = Vec3::splat(f32::INFINITY);
         let mut max = Vec3::splat(f32::NEG_INFINITY);
-        self.accumulate_caster_aabb(&mut min, &mut max);
+        self.accumulate_aabb(casters_only, &mut min, &mut max);
         if min.x <= max.x {
             Some((min, max))
         } else {
@@ -320,12 +963,12 @@ impl SceneNodeData3d {
         }
     }
 
-    fn accumulate_caster_aabb(&self, min: &mut Vec3, max: &mut Vec3) {
+    fn accumulate_aabb(&self, casters_only: bool, min: &mut Vec3, max: &mut Vec3) {
         if !self.visible {
             return;
         }
         if let Some(ref o) = self.object {
-            if o.casts_shadows() {
+            if !casters_only || o.casts_shadows() {
                 let mesh = o.mesh().borrow();
                 // A skinned mesh is deformed in world space by its joint palette
                 // (its node + instance transforms are ignored), so bound the actual
@@ -359,7 +1002,7 @@ impl SceneNodeData3d {
                         }
                     }
                     for c in self.children.iter() {
-                        c.data().accumulate_caster_aabb(min, max);
+                        c.data().accumulate_aabb(casters_only, min, max);
                     }
                     return;
                 }
@@ -425,7 +1068,7 @@ impl SceneNodeData3d {
             }
         }
         for c in self.children.iter() {
-            c.data().accumulate_caster_aabb(min, max);
+            c.data().accumulate_aabb(casters_only, min, max);
         }
     }
 
@@ -455,8 +1098,8 @@ impl SceneNodeData3d {
             return;
         }
 
-        if let Some(ref mut o) = self.object {
-            if o.casts_shadows() {
+        let draw =
+            |o: &mut Object3d, render_pass: &mut wgpu::RenderPass<'_>, object_index: &mut u32| {
                 let transparent = o.data().color().a < alpha_threshold;
                 if transparent == only_transparent {
                     let offset = *object_index * model_stride;
@@ -471,6 +1114,15 @@ impl SceneNodeData3d {
                 }
                 // Increment for every caster so slots stay aligned across both passes.
                 *object_index += 1;
+            };
+
+        let drawn_by_batch = self
+            .object
+            .as_ref()
+            .is_some_and(|o| self.shadow_drawn_by_batch(o));
+        if let Some(o) = self.object.as_mut() {
+            if o.casts_shadows() && !drawn_by_batch {
+                draw(o, render_pass, object_index);
             }
         }
 
@@ -488,6 +1140,16 @@ impl SceneNodeData3d {
                 alpha_threshold,
             );
         }
+
+        for batch in self.auto_instances.iter_mut().filter(|b| b.draws_shadows()) {
+            draw(&mut batch.object, render_pass, object_index);
+        }
+    }
+
+    /// Whether the shadow of `object`, the object of this node, is drawn by an
+    /// automatically instanced draw (see [`AutoInstanceBatch::draws_shadows`]).
+    fn shadow_drawn_by_batch(&self, object: &Object3d) -> bool {
+        self.auto_instanced && object.data().color().a == 1.0
     }
 
     /// A reference to the object possibly contained by this node.
@@ -524,14 +1186,28 @@ impl SceneNodeData3d {
             .expect("This scene node does not contain an Object.")
     }
 
+    /// Marks the world transforms of this node and its descendants as out of
+    /// date.
     fn invalidate(&mut self) {
+        if self.precise_position.is_some() {
+            // The local transform of a double-precision node was edited: the
+            // next rebase must restore its translation.
+            PRECISE_POSITION_EDITS.fetch_add(1, Ordering::Relaxed);
+        }
+        self.invalidate_descendants();
+        self.subtree.mark_dirty();
+    }
+
+    fn invalidate_descendants(&mut self) {
         self.up_to_date = false;
+        self.subtree.dirty.set(true);
 
-        for c in self.children.iter_mut() {
+        for c in self.children.iter_mut().chain(self.axes.as_mut()) {
             let mut dm = c.data_mut();
 
+            // The descendants of an out-of-date node are out of date too.
             if dm.up_to_date {
-                dm.invalidate()
+                dm.invalidate_descendants()
             }
         }
     }
@@ -543,8 +1219,8 @@ impl SceneNodeData3d {
                 if let Some(dp) = p.upgrade() {
                     let mut dp = dp.borrow_mut();
                     dp.update();
-                    self.world_transform = self.local_transform * dp.world_transform;
-                    self.world_scale = self.local_scale * dp.local_scale;
+                    self.world_transform = dp.world_transform * self.local_transform;
+                    self.world_scale = dp.world_scale * self.local_scale;
                     self.up_to_date = true;
                     return;
                 }
@@ -558,6 +1234,17 @@ impl SceneNodeData3d {
     }
 }
 
+impl Drop for SceneNodeData3d {
+    fn drop(&mut self) {
+        // `try_with`: the registry may already be gone during thread teardown.
+        let _ = NODE_REGISTRY.try_with(|registry| {
+            if let Ok(mut registry) = registry.try_borrow_mut() {
+                let _ = registry.remove(&self.id);
+            }
+        });
+    }
+}
+
 impl Default for SceneNode3d {
     fn default() -> SceneNode3d {
         SceneNode3d::empty()
@@ -575,22 +1262,38 @@ impl SceneNode3d {
     /// # Returns
     /// A new `SceneNode` without a parent
     pub fn new(local_scale: Vec3, local_transform: Pose3, object: Option<Object3d>) -> SceneNode3d {
+        let id = SceneNodeId(NEXT_NODE_ID.fetch_add(1, Ordering::Relaxed));
         let data = SceneNodeData3d {
+            id,
+            name: None,
+            user_data: None,
+            axes: None,
             local_scale,
             local_transform,
             world_transform: local_transform,
             world_scale: local_scale,
             visible: true,
             up_to_date: false,
+            subtree: Rc::new(SubtreeFlag::new()),
             children: Vec::new(),
             object,
+            primitive: None,
+            precise_position: None,
             light: None,
             parent: None,
+            auto_instancing: false,
+            auto_instances: Vec::new(),
+            auto_instances_edits: None,
+            draw_queue: Vec::new(),
+            auto_instanced: false,
         };
 
-        SceneNode3d {
-            data: Rc::new(RefCell::new(data)),
-        }
+        let data = Rc::new(RefCell::new(data));
+        NODE_REGISTRY.with(|registry| {
+            let _ = registry.borrow_mut().insert(id, Rc::downgrade(&data));
+        });
+
+        SceneNode3d { data, id }
     }
 
     /// Creates a new empty scene node with identity transformations.
@@ -616,8 +1319,9 @@ impl SceneNode3d {
     /// * `wy` - the cube extent along the y axis
     /// * `wz` - the cube extent along the z axis
     pub fn cube(wx: f32, wy: f32, wz: f32) -> SceneNode3d {
-        Self::geom_with_name("cube", Vec3::new(wx, wy, wz))
-            .expect("Unable to load the default cube geometry.")
+        Self::primitive_node(Primitive3d::Cube {
+            extents: Vec3::new(wx, wy, wz),
+        })
     }
 
     /// Creates a new scene node with a sphere mesh.
@@ -627,8 +1331,10 @@ impl SceneNode3d {
     /// # Arguments
     /// * `r` - the sphere radius
     pub fn sphere(r: f32) -> SceneNode3d {
-        Self::geom_with_name("sphere", Vec3::new(r * 2.0, r * 2.0, r * 2.0))
-            .expect("Unable to load the default sphere geometry.")
+        Self::primitive_node(Primitive3d::Sphere {
+            radius: r,
+            subdivs: None,
+        })
     }
 
     /// Creates a new scene node with a sphere mesh with custom subdivisions.
@@ -640,10 +1346,10 @@ impl SceneNode3d {
     /// * `ntheta_subdiv` - number of subdivisions around the sphere (longitude)
     /// * `nphi_subdiv` - number of subdivisions from top to bottom (latitude)
     pub fn sphere_with_subdiv(r: f32, ntheta_subdiv: u32, nphi_subdiv: u32) -> SceneNode3d {
-        Self::render_mesh(
-            procedural::sphere(r * 2.0, ntheta_subdiv, nphi_subdiv, true),
-            Vec3::ONE,
-        )
+        Self::primitive_node(Primitive3d::Sphere {
+            radius: r,
+            subdivs: Some((ntheta_subdiv, nphi_subdiv)),
+        })
     }
 
     /// Creates a new scene node with a cone mesh.
@@ -654,8 +1360,11 @@ impl SceneNode3d {
     /// * `r` - the cone base radius
     /// * `h` - the cone height
     pub fn cone(r: f32, h: f32) -> SceneNode3d {
-        Self::geom_with_name("cone", Vec3::new(r * 2.0, h, r * 2.0))
-            .expect("Unable to load the default cone geometry.")
+        Self::primitive_node(Primitive3d::Cone {
+            radius: r,
+            height: h,
+            subdivs: None,
+        })
     }
 
     /// Creates a new scene node with a cone mesh with custom subdivisions.
@@ -667,7 +1376,11 @@ impl SceneNode3d {
     /// * `h` - the cone height
     /// * `nsubdiv` - number of subdivisions around the base circle
     pub fn cone_with_subdiv(r: f32, h: f32, nsubdiv: u32) -> SceneNode3d {
-        Self::render_mesh(procedural::cone(r * 2.0, h, nsubdiv), Vec3::ONE)
+        Self::primitive_node(Primitive3d::Cone {
+            radius: r,
+            height: h,
+            subdivs: Some(nsubdiv),
+        })
     }
 
     /// Creates a new scene node with a cylinder mesh.
@@ -679,8 +1392,11 @@ impl SceneNode3d {
     /// * `r` - the cylinder base radius
     /// * `h` - the cylinder height
     pub fn cylinder(r: f32, h: f32) -> SceneNode3d {
-        Self::geom_with_name("cylinder", Vec3::new(r * 2.0, h, r * 2.0))
-            .expect("Unable to load the default cylinder geometry.")
+        Self::primitive_node(Primitive3d::Cylinder {
+            radius: r,
+            height: h,
+            subdivs: None,
+        })
     }
 
     /// Creates a new scene node with a cylinder mesh with custom subdivisions.
@@ -693,7 +1409,11 @@ impl SceneNode3d {
     /// * `h` - the cylinder height
     /// * `nsubdiv` - number of subdivisions around the circumference
     pub fn cylinder_with_subdiv(r: f32, h: f32, nsubdiv: u32) -> SceneNode3d {
-        Self::render_mesh(procedural::cylinder(r * 2.0, h, nsubdiv), Vec3::ONE)
+        Self::primitive_node(Primitive3d::Cylinder {
+            radius: r,
+            height: h,
+            subdivs: Some(nsubdiv),
+        })
     }
 
     /// Creates a new scene node with a capsule mesh.
@@ -705,7 +1425,7 @@ impl SceneNode3d {
     /// * `r` - the capsule caps radius
     /// * `h` - the capsule height
     pub fn capsule(r: f32, h: f32) -> SceneNode3d {
-        Self::render_mesh(procedural::capsule(r * 2.0, h, 50, 50), Vec3::ONE)
+        Self::capsule_with_subdiv(r, h, 50, 50)
     }
 
     /// Creates a new scene node with a capsule mesh with custom subdivisions.
@@ -724,10 +1444,23 @@ impl SceneNode3d {
         ntheta_subdiv: u32,
         nphi_subdiv: u32,
     ) -> SceneNode3d {
-        Self::render_mesh(
-            procedural::capsule(r * 2.0, h, ntheta_subdiv, nphi_subdiv),
-            Vec3::ONE,
-        )
+        Self::primitive_node(Primitive3d::Capsule {
+            radius: r,
+            height: h,
+            subdivs: (ntheta_subdiv, nphi_subdiv),
+        })
+    }
+
+    /// Creates a node with the mesh and scale of `primitive`, remembering it for
+    /// [`SceneNode3d::set_primitive`].
+    fn primitive_node(primitive: Primitive3d) -> SceneNode3d {
+        let node = match primitive.shared_geometry() {
+            Some((name, scale)) => Self::geom_with_name(name, scale)
+                .unwrap_or_else(|| panic!("Unable to load the default {} geometry.", name)),
+            None => Self::render_mesh(primitive.render_mesh().unwrap(), Vec3::ONE),
+        };
+        node.data.borrow_mut().primitive = Some(primitive);
+        node
     }
 
     /// Creates a new scene node with a double-sided quad mesh.
@@ -777,18 +1510,19 @@ impl SceneNode3d {
     }
 
     /// Convenience function to add a new scene node using a mesh defined by its vertex and index buffers.
+    ///
+    /// `normals` selects how the vertex normals are generated (see [`NormalMode`]);
+    /// a `bool` is also accepted, `true` meaning flat normals and `false` fully
+    /// smooth ones.
     pub fn trimesh(
         vertices: Vec<Vec3>,
         indices: Vec<[u32; 3]>,
         scale: Vec3,
-        flat_normals: bool,
+        normals: impl Into<NormalMode>,
     ) -> SceneNode3d {
         let mut render_mesh =
             RenderMesh::new(vertices, None, None, Some(IndexBuffer::Unified(indices)));
-        if flat_normals {
-            render_mesh.replicate_vertices();
-            render_mesh.recompute_normals();
-        }
+        render_mesh.apply_normal_mode(normals.into());
 
         Self::mesh(
             Rc::new(RefCell::new(GpuMesh3d::from_render_mesh(
@@ -864,8 +1598,12 @@ impl SceneNode3d {
     /// as part of the scene hierarchy.
     pub fn remove(&mut self) {
         let self_self = self.clone();
-        self.data_mut().remove_from_parent(&self_self);
-        self.data_mut().parent = None
+        let mut data = self.data_mut();
+        data.remove_from_parent(&self_self);
+        data.parent = None;
+        *data.subtree.parent.borrow_mut() = None;
+        data.invalidate();
+        SCENE_GRAPH_EDITS.fetch_add(1, Ordering::Relaxed);
     }
 
     /// Returns an immutable reference to this node's internal data.
@@ -923,6 +1661,20 @@ impl SceneNode3d {
         Rc::as_ptr(&self.data) as *const () as u64
     }
 
+    /// The copyable identifier of this node. Never borrows the node, so it can be
+    /// called at any time.
+    #[inline]
+    pub fn id(&self) -> SceneNodeId {
+        self.id
+    }
+
+    /// The live node identified by `id`, or `None` if it has been dropped (or was
+    /// created on another thread).
+    pub fn from_id(id: SceneNodeId) -> Option<SceneNode3d> {
+        let data = NODE_REGISTRY.with(|registry| registry.borrow().get(&id)?.upgrade())?;
+        Some(SceneNode3d { data, id })
+    }
+
     /// Whether `self` and `other` are handles to the same underlying node.
     #[inline]
     pub fn same_node(&self, other: &SceneNode3d) -> bool {
@@ -963,8 +1715,15 @@ impl SceneNode3d {
 
         let mut node = node;
         let self_weak_ptr = Rc::downgrade(&self.data);
-        node.data_mut().set_parent(self_weak_ptr);
-        self.data_mut().children.push(node)
+        let flag = self.data().subtree.clone();
+        {
+            let mut data = node.data_mut();
+            data.set_parent(self_weak_ptr, flag);
+            // Its world transform now depends on this node.
+            data.invalidate();
+        }
+        self.data_mut().children.push(node);
+        SCENE_GRAPH_EDITS.fetch_add(1, Ordering::Relaxed);
     }
 
     /// Adds a new node with a renderable object as a child of this node.
@@ -1136,6 +1895,83 @@ impl SceneNode3d {
         node
     }
 
+    /// Adds a noise-based terrain of `width × depth` (on the XZ plane, heights
+    /// along Y) to this node children, colored by height and slope with the
+    /// default [`TerrainColoring`](procedural::TerrainColoring).
+    ///
+    /// See [`procedural::terrain`] for the meaning of the arguments. Use
+    /// [`procedural::terrain_texture`] directly for a custom coloring.
+    pub fn add_terrain(
+        &mut self,
+        width: f32,
+        depth: f32,
+        subdivisions: usize,
+        noise: &procedural::NoiseConfig,
+    ) -> SceneNode3d {
+        let mesh = procedural::terrain(width, depth, subdivisions, noise);
+        let image = procedural::terrain_texture(
+            &mesh,
+            subdivisions,
+            &procedural::TerrainColoring::default(),
+        );
+        let texture = Texture::new(
+            image.width(),
+            image.height(),
+            image.as_raw(),
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            wgpu::AddressMode::ClampToEdge,
+            wgpu::FilterMode::Linear,
+            false,
+        );
+        let mut node = self.add_render_mesh(mesh, Vec3::ONE);
+        node.set_texture(texture);
+        node
+    }
+
+    /// Adds a vector field glyph renderer as a child of this node: one arrow per
+    /// sample, drawn with instancing.
+    ///
+    /// Each arrow starts at `points[i]`, points along `vectors[i]` and is
+    /// `scale` times as long as the vector. Arrows are colored by the vector
+    /// magnitude through `colormap`, from the smallest to the largest magnitude
+    /// of the field. Use [`set_vector_field`](Self::set_vector_field) on the
+    /// returned node to update the field, e.g. every frame of a simulation.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let mut window = Window::new("Example").await;
+    /// # let mut camera = OrbitCamera3d::default();
+    /// let mut scene = SceneNode3d::empty();
+    /// let mut points = Vec::new();
+    /// let mut vectors = Vec::new();
+    /// for i in -5..=5 {
+    ///     for k in -5..=5 {
+    ///         let p = Vec3::new(i as f32, 0.0, k as f32);
+    ///         points.push(p);
+    ///         // A vortex around the Y axis.
+    ///         vectors.push(Vec3::new(-p.z, 0.0, p.x) * 0.2);
+    ///     }
+    /// }
+    /// scene.add_vector_field(&points, &vectors, 1.0, &Colormap::Turbo);
+    ///
+    /// while window.render_3d(&mut scene, &mut camera).await {}
+    /// # }
+    /// ```
+    pub fn add_vector_field(
+        &mut self,
+        points: &[Vec3],
+        vectors: &[Vec3],
+        scale: f32,
+        colormap: &Colormap,
+    ) -> SceneNode3d {
+        let mut node = self.add_render_mesh(procedural::arrow(0.03, 0.08, 0.25, 12), Vec3::ONE);
+        node.set_vector_field(points, vectors, scale, colormap);
+        node
+    }
+
     /// Adds a planar **reflector** (mirror) of size `width` × `height` as a child,
     /// returning its node.
     ///
@@ -1238,9 +2074,9 @@ impl SceneNode3d {
         vertices: Vec<Vec3>,
         indices: Vec<[u32; 3]>,
         scale: Vec3,
-        flat_normals: bool,
+        normals: impl Into<NormalMode>,
     ) -> SceneNode3d {
-        let node = Self::trimesh(vertices, indices, scale, flat_normals);
+        let node = Self::trimesh(vertices, indices, scale, normals);
         self.add_child(node.clone());
         node
     }
@@ -1249,12 +2085,26 @@ impl SceneNode3d {
     ///
     /// This will create a new node serving as a root of the scene described by the obj file. This
     /// newly created node is added to this node's children.
+    ///
+    /// # Panics
+    /// Panics if the obj file cannot be read or parsed.
     pub fn add_obj(&mut self, path: &Path, mtl_dir: &Path, scale: Vec3) -> SceneNode3d {
+        self.try_add_obj(path, mtl_dir, scale).unwrap()
+    }
+
+    /// Same as [`add_obj`](Self::add_obj), but returns the error of reading or
+    /// parsing the obj file instead of panicking.
+    pub(crate) fn try_add_obj(
+        &mut self,
+        path: &Path,
+        mtl_dir: &Path,
+        scale: Vec3,
+    ) -> std::io::Result<SceneNode3d> {
         let tex = TextureManager::get_global_manager(|tm| tm.get_default());
         let mat = MaterialManager3d::get_global_manager(|mm| mm.get_default());
 
-        // TODO: is there some error-handling stuff to do here instead of the `let _`.
-        let result = MeshManager3d::load_obj(path, mtl_dir, path.to_str().unwrap()).map(|objs| {
+        let path_name = path.to_string_lossy();
+        MeshManager3d::load_obj(path, mtl_dir, &path_name).map(|objs| {
             let mut root;
 
             let self_root = objs.len() == 1;
@@ -1269,36 +2119,19 @@ impl SceneNode3d {
                 child_scale = Vec3::ONE;
             }
 
-            for (_, mesh, mtl) in objs.into_iter() {
+            let prefix = format!("{}/", path_name);
+            for (name, mesh, mtl) in objs.into_iter() {
                 let mut object = Object3d::new(mesh, crate::color::WHITE, tex.clone(), mat.clone());
 
-                match mtl {
-                    None => {}
-                    Some(mtl) => {
-                        object.set_color(Color::new(
-                            mtl.diffuse[0],
-                            mtl.diffuse[1],
-                            mtl.diffuse[2],
-                            1.0,
-                        ));
-
-                        for t in mtl.diffuse_texture.iter() {
-                            let mut tpath = PathBuf::new();
-                            tpath.push(mtl_dir);
-                            tpath.push(&t[..]);
-                            object.set_texture_from_file(&tpath, tpath.to_str().unwrap())
-                        }
-
-                        for t in mtl.ambient_texture.iter() {
-                            let mut tpath = PathBuf::new();
-                            tpath.push(mtl_dir);
-                            tpath.push(&t[..]);
-                            object.set_texture_from_file(&tpath, tpath.to_str().unwrap())
-                        }
-                    }
+                if let Some(mtl) = mtl {
+                    Self::apply_mtl(&mut object, &mtl, mtl_dir);
                 }
 
-                let _ = root.add_object(child_scale, Pose3::IDENTITY, object);
+                // Groups are named `<path>/<group>`; the default group is just `<path>`.
+                let name = name.strip_prefix(&prefix).unwrap_or(&name);
+                let _ = root
+                    .add_object(child_scale, Pose3::IDENTITY, object)
+                    .set_name(name);
             }
 
             if self_root {
@@ -1310,9 +2143,76 @@ impl SceneNode3d {
             } else {
                 root
             }
-        });
+        })
+    }
+
+    /// Applies the colors and texture maps of an MTL material to `object`.
+    ///
+    /// Missing or undecodable texture files are skipped with a warning instead of
+    /// aborting the whole load. The specular map has no direct equivalent in the
+    /// metallic-roughness model, so it is converted to a roughness map (bright =
+    /// glossy).
+    fn apply_mtl(object: &mut Object3d, mtl: &MtlMaterial, mtl_dir: &Path) {
+        object.set_color(Color::new(
+            mtl.diffuse[0],
+            mtl.diffuse[1],
+            mtl.diffuse[2],
+            mtl.alpha,
+        ));
+        if mtl.emissive != [0.0; 3] {
+            object.set_emissive(Color::new(
+                mtl.emissive[0],
+                mtl.emissive[1],
+                mtl.emissive[2],
+                1.0,
+            ));
+        }
 
-        result.unwrap()
+        let load = |file: &str| {
+            let path = mtl_dir.join(file);
+            let res =
+                TextureManager::get_global_manager(|tm| tm.try_add(&path, &path.to_string_lossy()));
+            res.map_err(|e| log::warn!("{}", e)).ok()
+        };
+
+        // The ambient map is only a fallback: most exporters write the same
+        // image to both, and the diffuse one is the base color.
+        if let Some(t) = mtl
+            .diffuse_texture
+            .as_ref()
+            .or(mtl.ambient_texture.as_ref())
+            .and_then(|t| load(t))
+        {
+            object.set_texture(t);
+        }
+        if let Some(t) = mtl.normal_map.as_ref().and_then(|t| load(t)) {
+            object.set_normal_map(t);
+        }
+        if let Some(t) = mtl.emissive_texture.as_ref().and_then(|t| load(t)) {
+            object.set_emissive_map(t);
+            if mtl.emissive == [0.0; 3] {
+                object.set_emissive(crate::color::WHITE);
+            }
+        }
+        if let Some(file) = &mtl.specular_texture {
+            let path = mtl_dir.join(file);
+            // glTF convention: G = roughness, B = metallic.
+            let to_roughness = |img: image::DynamicImage| {
+                let spec = img.to_luma8();
+                let mr = image::RgbaImage::from_fn(spec.width(), spec.height(), |x, y| {
+                    image::Rgba([0, 255 - spec.get_pixel(x, y).0[0], 0, 255])
+                });
+                image::DynamicImage::ImageRgba8(mr)
+            };
+            let name = format!("{}#roughness", path.to_string_lossy());
+            let res = TextureManager::get_global_manager(|tm| {
+                tm.try_add_converted(&path, &name, to_roughness)
+            });
+            match res {
+                Ok(t) => object.set_metallic_roughness_map(t),
+                Err(e) => log::warn!("{}", e),
+            }
+        }
     }
 
     /// Loads a glTF / GLB file and adds it as a child of this node.
@@ -1446,7 +2346,11 @@ impl SceneNode3d {
     }
 
     /// Sets the morph-target weights on this node's object and every descendant
-    /// object whose target count matches `weights.len()`.
+    /// object whose mesh target count matches `weights.len()`.
+    ///
+    /// Each weight scales the deltas of one target added with
+    /// [`GpuMesh3d::add_morph_target`](crate::resource::GpuMesh3d::add_morph_target)
+    /// (or loaded from glTF), blended in the vertex shader.
     ///
     /// glTF attaches each mesh primitive as a child object node and shares one weight
     /// vector across them, so an animation channel targeting the mesh node fans the
@@ -1455,7 +2359,8 @@ impl SceneNode3d {
         let children = {
             let mut data = self.data.borrow_mut();
             if let Some(obj) = data.object.as_mut() {
-                if obj.data().morph_target_count() == weights.len() && !weights.is_empty() {
+                let num_targets = obj.mesh().borrow().morph_target_count();
+                if num_targets == weights.len() && !weights.is_empty() {
                     obj.data_mut().set_morph_weights(weights);
                 }
             }
@@ -1539,7 +2444,9 @@ impl SceneNode3d {
 
     /// Sets the material by name for this node's object only.
     ///
-    /// The material must have been previously registered with the global material manager.
+    /// The material must have been previously registered with the global material
+    /// manager, e.g. with [`MaterialManager3d::register`]. All the objects given
+    /// the same name share the same material instance.
     ///
     /// # Arguments
     /// * `name` - The name of the registered material
@@ -1547,6 +2454,23 @@ impl SceneNode3d {
     /// # Panics
     /// Panics if the material with the given name doesn't exist
     ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # use kiss3d::builtin::NormalsMaterial;
+    /// # use kiss3d::resource::MaterialManager3d;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let _window = Window::new("Example").await;
+    /// let mut scene = SceneNode3d::empty();
+    /// let _ = MaterialManager3d::get_global_manager(|mm| {
+    ///     mm.register("my_normals", NormalsMaterial::new())
+    /// });
+    /// scene.add_cube(1.0, 1.0, 1.0).set_material_with_name("my_normals");
+    /// scene.add_sphere(0.5).set_material_with_name("my_normals");
+    /// # }
+    /// ```
+    ///
     /// # See also
     /// * [`Self::set_material_with_name_recursive`] - to also modify all descendants.
     #[inline]
@@ -1639,6 +2563,88 @@ impl SceneNode3d {
         self.clone()
     }
 
+    /// Restricts the wireframe of this node's object to feature edges.
+    ///
+    /// # Arguments
+    /// * `angle` - Only edges whose adjacent faces' normals differ by more than this
+    ///   angle (in radians), plus boundary edges, are drawn; `None` draws every edge
+    ///
+    /// # See also
+    /// * [`Self::set_lines_feature_angle_recursive`] - to also modify all descendants.
+    #[inline]
+    pub fn set_lines_feature_angle(&mut self, angle: Option<f32>) -> Self {
+        self.apply_to_object_mut(&mut |o| o.set_lines_feature_angle(angle));
+        self.clone()
+    }
+
+    /// Restricts the wireframe of this node's object and all its descendants to
+    /// feature edges.
+    ///
+    /// # Arguments
+    /// * `angle` - Only edges whose adjacent faces' normals differ by more than this
+    ///   angle (in radians), plus boundary edges, are drawn; `None` draws every edge
+    ///
+    /// # See also
+    /// * [`Self::set_lines_feature_angle`] - to only modify this node.
+    #[inline]
+    pub fn set_lines_feature_angle_recursive(&mut self, angle: Option<f32>) -> Self {
+        self.apply_to_objects_mut_recursive(&mut |o| o.set_lines_feature_angle(angle));
+        self.clone()
+    }
+
+    /// Sets the depth bias of the wireframe lines of this node's object only.
+    ///
+    /// # Arguments
+    /// * `constant` - Constant bias, in units of the depth buffer precision
+    /// * `slope_scale` - Bias proportional to the line's depth slope
+    ///
+    /// Negative values pull the lines toward the camera.
+    ///
+    /// # See also
+    /// * [`Self::set_lines_depth_bias_recursive`] - to also modify all descendants.
+    #[inline]
+    pub fn set_lines_depth_bias(&mut self, constant: i32, slope_scale: f32) -> Self {
+        self.apply_to_object_mut(&mut |o| o.set_lines_depth_bias(constant, slope_scale));
+        self.clone()
+    }
+
+    /// Sets the depth bias of the wireframe lines of this node's object and all its
+    /// descendants.
+    ///
+    /// # Arguments
+    /// * `constant` - Constant bias, in units of the depth buffer precision
+    /// * `slope_scale` - Bias proportional to the line's depth slope
+    ///
+    /// # See also
+    /// * [`Self::set_lines_depth_bias`] - to only modify this node.
+    #[inline]
+    pub fn set_lines_depth_bias_recursive(&mut self, constant: i32, slope_scale: f32) -> Self {
+        self.apply_to_objects_mut_recursive(&mut |o| o.set_lines_depth_bias(constant, slope_scale));
+        self.clone()
+    }
+
+    /// Sets whether the wireframe lines of this node's object are drawn on top of
+    /// all geometry (without depth testing).
+    ///
+    /// # See also
+    /// * [`Self::set_lines_on_top_recursive`] - to also modify all descendants.
+    #[inline]
+    pub fn set_lines_on_top(&mut self, on_top: bool) -> Self {
+        self.apply_to_object_mut(&mut |o| o.set_lines_on_top(on_top));
+        self.clone()
+    }
+
+    /// Sets whether the wireframe lines of this node's object and all its
+    /// descendants are drawn on top of all geometry (without depth testing).
+    ///
+    /// # See also
+    /// * [`Self::set_lines_on_top`] - to only modify this node.
+    #[inline]
+    pub fn set_lines_on_top_recursive(&mut self, on_top: bool) -> Self {
+        self.apply_to_objects_mut_recursive(&mut |o| o.set_lines_on_top(on_top));
+        self.clone()
+    }
+
     /// Sets the point size for point cloud rendering of this node's object only.
     ///
     /// # Arguments
@@ -1755,6 +2761,28 @@ impl SceneNode3d {
         self.clone()
     }
 
+    /// Sets the winding of the front faces of this node's object (see
+    /// [`Object3d::set_front_face`]).
+    ///
+    /// # See also
+    /// * [`Self::set_front_face_recursive`] - to also modify all descendants.
+    #[inline]
+    pub fn set_front_face(&mut self, front_face: Option<wgpu::FrontFace>) -> Self {
+        self.apply_to_object_mut(&mut |o| o.set_front_face(front_face));
+        self.clone()
+    }
+
+    /// Sets the winding of the front faces of this node's object and all its
+    /// descendants, e.g. to fix a whole model loaded with clockwise triangles.
+    ///
+    /// # See also
+    /// * [`Self::set_front_face`] - to only modify this node.
+    #[inline]
+    pub fn set_front_face_recursive(&mut self, front_face: Option<wgpu::FrontFace>) -> Self {
+        self.apply_to_objects_mut_recursive(&mut |o| o.set_front_face(front_face));
+        self.clone()
+    }
+
     /// Mutably accesses the vertices of this node's object only.
     ///
     /// # See also
@@ -1920,15 +2948,221 @@ impl SceneNode3d {
         self.apply_to_object(&mut |o| o.read_uvs(f))
     }
 
-    /// Accesses the texture coordinates of this node's object and all its descendants.
-    ///
-    /// The provided closure is called once per object.
-    ///
-    /// # See also
-    /// * [`Self::read_uvs`] - to only access this node.
-    #[inline(always)]
-    pub fn read_uvs_recursive<F: FnMut(&[Vec2])>(&self, f: &mut F) {
-        self.apply_to_objects_recursive(&mut |o| o.read_uvs(f))
+    /// Accesses the texture coordinates of this node's object and all its descendants.
+    ///
+    /// The provided closure is called once per object.
+    ///
+    /// # See also
+    /// * [`Self::read_uvs`] - to only access this node.
+    #[inline(always)]
+    pub fn read_uvs_recursive<F: FnMut(&[Vec2])>(&self, f: &mut F) {
+        self.apply_to_objects_recursive(&mut |o| o.read_uvs(f))
+    }
+
+    /// The name of this node, if one was set.
+    ///
+    /// Loaders name the nodes they create after the source file's groups or
+    /// meshes (e.g. the OBJ `g`/`o`/`usemtl` groups).
+    #[inline]
+    pub fn name(&self) -> Option<String> {
+        self.data().name.clone()
+    }
+
+    /// Sets the name of this node.
+    #[inline]
+    pub fn set_name(&mut self, name: &str) -> Self {
+        self.data_mut().name = Some(name.to_string());
+        self.clone()
+    }
+
+    /// Attaches arbitrary application data to this node (e.g. the simulation
+    /// entity it displays), replacing any previous user data.
+    ///
+    /// # Example
+    /// ```
+    /// # use kiss3d::scene::SceneNode3d;
+    /// struct Entity(u32);
+    ///
+    /// let mut node = SceneNode3d::empty();
+    /// node.set_user_data(Box::new(Entity(42)));
+    /// assert_eq!(node.user_data::<Entity>().unwrap().0, 42);
+    /// assert!(node.user_data::<String>().is_none());
+    /// ```
+    #[inline]
+    pub fn set_user_data(&mut self, data: Box<dyn Any>) -> Self {
+        self.data_mut().user_data = Some(data);
+        self.clone()
+    }
+
+    /// The user data attached to this node, if there is some of type `T`.
+    ///
+    /// The returned guard borrows the node: drop it before modifying the node.
+    pub fn user_data<T: Any>(&self) -> Option<Ref<'_, T>> {
+        Ref::filter_map(self.data(), |d| d.user_data.as_ref()?.downcast_ref::<T>()).ok()
+    }
+
+    /// Mutable access to the user data attached to this node, if there is some of
+    /// type `T`.
+    pub fn user_data_mut<T: Any>(&mut self) -> Option<RefMut<'_, T>> {
+        RefMut::filter_map(self.data_mut(), |d| {
+            d.user_data.as_mut()?.downcast_mut::<T>()
+        })
+        .ok()
+    }
+
+    /// Detaches and returns the user data of this node.
+    pub fn take_user_data(&mut self) -> Option<Box<dyn Any>> {
+        self.data_mut().user_data.take()
+    }
+
+    /// Displays this node's coordinate frame as red, green and blue arrows along
+    /// its local X, Y and Z axes, replacing any axes shown previously.
+    ///
+    /// The arrows are `length` long and `thickness` wide, and follow the node's
+    /// world pose (and scale). They are a helper drawn with the node rather than
+    /// a child: [`SceneNodeData3d::children`], [`Self::find_by_name`], [`Self::world_aabb`],
+    /// the exports and automatic instancing don't see them. They don't cast
+    /// shadows. Remove them with [`Self::hide_axes`].
+    pub fn show_axes(&mut self, length: f32, thickness: f32) -> Self {
+        self.hide_axes();
+
+        let mut axes = SceneNode3d::empty();
+        // Arrows are built along +Y, then rotated onto each axis.
+        let frames = [
+            (
+                Quat::from_rotation_z(-std::f32::consts::FRAC_PI_2),
+                crate::color::RED,
+            ),
+            (Quat::IDENTITY, crate::color::LIME),
+            (
+                Quat::from_rotation_x(std::f32::consts::FRAC_PI_2),
+                crate::color::BLUE,
+            ),
+        ];
+        let head = (length * 0.25).min(thickness * 4.0);
+        let shaft = length - head;
+        for (rotation, color) in frames {
+            let mut arrow = axes.add_group();
+            arrow.set_rotation(rotation);
+            arrow
+                .add_cylinder(thickness * 0.5, shaft)
+                .set_position(Vec3::new(0.0, shaft * 0.5, 0.0));
+            arrow
+                .add_cone(thickness * 1.5, head)
+                .set_position(Vec3::new(0.0, shaft + head * 0.5, 0.0));
+            arrow.set_color_recursive(color);
+            arrow.set_casts_shadows_recursive(false);
+        }
+
+        // Linked to this node like a child, so it follows its world transform,
+        // without being one of its children.
+        let flag = self.data().subtree.clone();
+        {
+            let mut data = axes.data_mut();
+            data.set_parent(Rc::downgrade(&self.data), flag);
+            data.invalidate();
+        }
+        self.data_mut().axes = Some(axes);
+        self.clone()
+    }
+
+    /// Removes the coordinate-frame arrows added by [`Self::show_axes`].
+    pub fn hide_axes(&mut self) -> Self {
+        let axes = self.data_mut().axes.take();
+        if let Some(mut axes) = axes {
+            axes.remove();
+        }
+        self.clone()
+    }
+
+    /// Finds the first node named `name` among this node and its descendants
+    /// (depth-first, this node first).
+    pub fn find_by_name(&self, name: &str) -> Option<SceneNode3d> {
+        if self.data().name.as_deref() == Some(name) {
+            return Some(self.clone());
+        }
+
+        self.data()
+            .children
+            .iter()
+            .find_map(|c| c.find_by_name(name))
+    }
+
+    /// Merges the static geometry below this node into a few combined meshes.
+    ///
+    /// The objects of all visible descendants are grouped by appearance (same
+    /// material, textures, color and surface properties), and each group is merged
+    /// into a single mesh, with the node transforms and scales baked into its
+    /// vertices, drawn by one object. These objects are attached to a new child
+    /// node named `"baked_static"`, which is returned. A large static scene then
+    /// costs one draw per appearance instead of one per node.
+    ///
+    /// The original nodes are left in place, so their names, transforms and user
+    /// data remain available, but each merged object is hidden from every camera
+    /// (its render layers are cleared) and no longer casts shadows. Objects that
+    /// cannot be merged (skinned or morphed meshes, mirrors, instanced objects, or
+    /// meshes whose data is no longer on the CPU) are left untouched.
+    ///
+    /// The baked vertices are expressed in this node's frame, which is exact when
+    /// this node's world scale is one. Later changes to the original nodes are not
+    /// reflected in the baked meshes.
+    pub fn bake_static(&mut self) -> SceneNode3d {
+        let mut batches = Vec::new();
+        for c in self.data_mut().children.iter_mut() {
+            c.data_mut()
+                .collect_static_batches(Pose3::IDENTITY, Vec3::ONE, &mut batches);
+        }
+
+        let mut baked = self.add_group();
+        let _ = baked.set_name("baked_static");
+
+        for batch in batches {
+            let mesh = RenderMesh::new(
+                batch.coords,
+                Some(batch.normals),
+                Some(batch.uvs),
+                Some(IndexBuffer::Unified(batch.faces)),
+            );
+            let mesh = Rc::new(RefCell::new(GpuMesh3d::from_render_mesh(mesh, false)));
+            let object = Object3d::from_data(mesh, batch.data);
+            baked.add_child(SceneNode3d::new(Vec3::ONE, Pose3::IDENTITY, Some(object)));
+        }
+
+        baked
+    }
+
+    /// Enables or disables the automatic instancing of the scene rooted by this node.
+    ///
+    /// When enabled (it is disabled by default), the visible descendants whose
+    /// objects share the same mesh and appearance (material, textures, surface
+    /// properties and color alpha) are drawn with a single instanced draw, their
+    /// world transforms and colors becoming the instances; the shadows of the
+    /// opaque ones are drawn the same way. Creating thousands of nodes with, e.g.,
+    /// [`add_sphere`](Self::add_sphere) then costs about as much as one node with
+    /// as many [instances](Self::set_instances).
+    ///
+    /// The objects are grouped again when nodes are added, removed, shown or
+    /// hidden, or when an object can't be drawn by its group anymore (e.g. its
+    /// material changed). Otherwise, each frame only uploads the instances of the
+    /// objects that moved or changed color. An object whose appearance changes to
+    /// match a group is drawn on its own until the next regrouping.
+    ///
+    /// Only the objects drawn by the default material, and without custom
+    /// instances, wireframe, points, skinning, morphing, reflection or
+    /// transmission are merged. Objects with a uniform world scale are merged
+    /// whatever their scale, while objects with a non-uniform world scale are
+    /// only merged with objects of exactly the same world scale. This only
+    /// applies to the node the scene is rendered from (typically the window's
+    /// root scene node).
+    pub fn set_auto_instancing(&mut self, enabled: bool) -> Self {
+        self.data_mut().auto_instancing = enabled;
+        self.clone()
+    }
+
+    /// Whether the automatic instancing of the scene rooted by this node is enabled
+    /// (see [`set_auto_instancing`](Self::set_auto_instancing)).
+    pub fn auto_instancing(&self) -> bool {
+        self.data().auto_instancing
     }
 
     /// Get the visibility status of node.
@@ -1944,6 +3178,7 @@ impl SceneNode3d {
     #[inline]
     pub fn set_visible(&mut self, visible: bool) -> Self {
         self.data_mut().visible = visible;
+        SCENE_GRAPH_EDITS.fetch_add(1, Ordering::Relaxed);
         self.clone()
     }
 
@@ -2084,6 +3319,81 @@ impl SceneNode3d {
         self.clone()
     }
 
+    /// Shades this node's object only from the `matcap` texture, a picture of a
+    /// lit sphere, whatever the lights of the scene.
+    ///
+    /// This sets the texture of the object and its material to the `matcap`
+    /// material (a [`MatcapMaterial`](crate::builtin::MatcapMaterial)); set
+    /// another material to go back to regular shading.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # use kiss3d::resource::TextureManager;
+    /// # use std::path::Path;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let _window = Window::new("Example").await;
+    /// let clay = TextureManager::get_global_manager(|tm| {
+    ///     tm.add(Path::new("assets/matcap_clay.png"), "clay")
+    /// });
+    /// let mut scene = SceneNode3d::empty();
+    /// scene.add_sphere(0.5).set_matcap(clay);
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// * [`Self::set_matcap_recursive`] - to also modify all descendants.
+    pub fn set_matcap(&mut self, texture: Arc<Texture>) -> Self {
+        let _ = self.set_material_with_name("matcap");
+        self.set_texture(texture)
+    }
+
+    /// Shades this node's object and all its descendants only from the
+    /// `matcap` texture.
+    ///
+    /// # See also
+    /// * [`Self::set_matcap`] - to only modify this node.
+    pub fn set_matcap_recursive(&mut self, texture: Arc<Texture>) -> Self {
+        let _ = self.set_material_with_name_recursive("matcap");
+        self.set_texture_recursive(texture)
+    }
+
+    /// Sets the texture of this node's object to the texture loading in the
+    /// background with `load` once it is ready, keeping the current texture as
+    /// a placeholder until then.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # use kiss3d::resource::TextureManager;
+    /// # use std::path::Path;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let mut window = Window::new("Example").await;
+    /// # let mut camera = OrbitCamera3d::default();
+    /// let mut scene = SceneNode3d::empty();
+    /// let mut globe = scene.add_sphere(1.0);
+    /// let load = TextureManager::get_global_manager(|tm| {
+    ///     tm.add_async(Path::new("earth_8k.jpg"), "earth")
+    /// });
+    /// globe.set_texture_async(&load);
+    ///
+    /// while window.render_3d(&mut scene, &mut camera).await {
+    ///     if !load.is_ready() {
+    ///         println!("Loading: {:.0}%", load.progress() * 100.0);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn set_texture_async(&mut self, load: &TextureLoad) -> Self {
+        let mut node = self.clone();
+        load.on_ready(move |texture| {
+            let _ = node.set_texture(texture.clone());
+        });
+        self.clone()
+    }
+
     /// Sets the texture of this node's object and all its descendants.
     ///
     /// # See also
@@ -2157,6 +3467,28 @@ impl SceneNode3d {
         self.clone()
     }
 
+    /// Makes this node's object reflect its environment, from `0.0` (matte) to
+    /// `1.0` (mirror-like). See
+    /// [`Object3d::set_reflectivity`](crate::scene::Object3d::set_reflectivity).
+    ///
+    /// # See also
+    /// * [`Self::set_reflectivity_recursive`] - to also modify all descendants.
+    #[inline]
+    pub fn set_reflectivity(&mut self, reflectivity: f32) -> Self {
+        self.apply_to_object_mut(&mut |o| o.set_reflectivity(reflectivity));
+        self.clone()
+    }
+
+    /// Makes this node's object and all its descendants reflect their environment.
+    ///
+    /// # See also
+    /// * [`Self::set_reflectivity`] - to only modify this node.
+    #[inline]
+    pub fn set_reflectivity_recursive(&mut self, reflectivity: f32) -> Self {
+        self.apply_to_objects_mut_recursive(&mut |o| o.set_reflectivity(reflectivity));
+        self.clone()
+    }
+
     /// Sets the emissive color for this node's object only.
     ///
     /// # Arguments
@@ -2271,6 +3603,113 @@ impl SceneNode3d {
         self.clone()
     }
 
+    /// Sets which shading the default material applies to this node's object (see
+    /// [`ShadingMode`]).
+    #[inline]
+    pub fn set_shading_mode(&mut self, shading_mode: ShadingMode) -> Self {
+        self.apply_to_object_mut(&mut |o| o.set_shading_mode(shading_mode));
+        self.clone()
+    }
+
+    /// Sets the per-vertex colors of this node's mesh, one per vertex. They are
+    /// displayed with [`ShadingMode::VertexColor`].
+    ///
+    /// The other nodes sharing the mesh keep their colors (see
+    /// [`Object3d::set_vertex_colors`]).
+    pub fn set_vertex_colors(&mut self, colors: &[Color]) -> Self {
+        self.apply_to_object_mut(&mut |o| o.set_vertex_colors(colors.to_vec()));
+        self.clone()
+    }
+
+    /// Colors this node's mesh by a per-vertex scalar attribute (e.g. a stress or
+    /// temperature field), one value per vertex, mapped through `colormap`.
+    ///
+    /// `range` gives the values mapped to both ends of the colormap; `None` uses
+    /// the minimum and maximum of `scalars`. The object is switched to
+    /// [`ShadingMode::VertexColor`]. Calling this again with the same number of
+    /// values only rewrites the colors in place, so the field can be animated
+    /// every frame. The other nodes sharing the mesh keep their colors (see
+    /// [`Object3d::set_vertex_colors`]).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let mut window = Window::new("Example").await;
+    /// # let mut camera = OrbitCamera3d::default();
+    /// # fn simulate_temperatures() -> Vec<f32> { vec![] }
+    /// let mut scene = SceneNode3d::empty();
+    /// let mut plate = scene.add_quad(10.0, 10.0, 50, 50);
+    ///
+    /// while window.render_3d(&mut scene, &mut camera).await {
+    ///     // One value per vertex of the plate.
+    ///     let temperatures = simulate_temperatures();
+    ///     plate.set_scalar_field(&temperatures, &Colormap::Inferno, Some((0.0, 100.0)));
+    /// }
+    /// # }
+    /// ```
+    pub fn set_scalar_field(
+        &mut self,
+        scalars: &[f32],
+        colormap: &Colormap,
+        range: Option<(f32, f32)>,
+    ) -> Self {
+        let range = range.unwrap_or_else(|| {
+            scalars
+                .iter()
+                .filter(|s| s.is_finite())
+                .fold((f32::MAX, f32::MIN), |(lo, hi), s| (lo.min(*s), hi.max(*s)))
+        });
+        let colors: Vec<Color> = scalars.iter().map(|s| colormap.map(*s, range)).collect();
+        self.apply_to_object_mut(&mut |o| {
+            o.set_vertex_colors(colors.clone());
+            o.set_shading_mode(ShadingMode::VertexColor);
+        });
+        self.clone()
+    }
+
+    /// Replaces this node's instances by one copy of its mesh per sample of a
+    /// vector field, as done by [`add_vector_field`](Self::add_vector_field).
+    ///
+    /// The mesh is expected to point along `+Y` with unit length (see
+    /// [`procedural::arrow`]); each copy is placed at `points[i]`, rotated toward
+    /// `vectors[i]`, scaled by its magnitude times `scale`, and colored by that
+    /// magnitude through `colormap`. Samples beyond the shorter of the two slices
+    /// are ignored.
+    pub fn set_vector_field(
+        &mut self,
+        points: &[Vec3],
+        vectors: &[Vec3],
+        scale: f32,
+        colormap: &Colormap,
+    ) -> Self {
+        let magnitudes: Vec<f32> = vectors.iter().map(|v| v.length()).collect();
+        let range = magnitudes
+            .iter()
+            .filter(|m| m.is_finite())
+            .fold((f32::MAX, f32::MIN), |(lo, hi), m| (lo.min(*m), hi.max(*m)));
+        let instances: Vec<InstanceData3d> = points
+            .iter()
+            .zip(vectors)
+            .zip(&magnitudes)
+            .map(|((point, vector), magnitude)| {
+                let rotation = if *magnitude > 0.0 {
+                    Quat::from_rotation_arc(Vec3::Y, *vector / *magnitude)
+                } else {
+                    Quat::IDENTITY
+                };
+                InstanceData3d {
+                    position: *point,
+                    deformation: Mat3::from_quat(rotation) * (*magnitude * scale),
+                    color: colormap.map(*magnitude, range),
+                    ..Default::default()
+                }
+            })
+            .collect();
+        self.set_instances(&instances)
+    }
+
     /// Sets this node's object render-layer bitmask (see
     /// [`Object3d::set_render_layers`](crate::scene::Object3d::set_render_layers)).
     #[inline]
@@ -2279,6 +3718,18 @@ impl SceneNode3d {
         self.clone()
     }
 
+    /// Sets the render-layer bitmask of this node's object and of all its
+    /// descendants' objects, e.g. to hide a whole debug-geometry subtree from a
+    /// recording camera.
+    ///
+    /// # See also
+    /// * [`Self::set_render_layers`] - to only modify this node.
+    #[inline]
+    pub fn set_render_layers_recursive(&mut self, layers: u32) -> Self {
+        self.apply_to_objects_mut_recursive(&mut |o| o.set_render_layers(layers));
+        self.clone()
+    }
+
     /// Sets this node's object light-layer bitmask, i.e. which lights affect it
     /// (see [`Object3d::set_light_layers`](crate::scene::Object3d::set_light_layers)).
     #[inline]
@@ -2297,6 +3748,17 @@ impl SceneNode3d {
         self.clone()
     }
 
+    /// Sets whether this node's object and all its descendants occlude lights in
+    /// the shadow pass.
+    ///
+    /// # See also
+    /// * [`Self::set_casts_shadows`] - to only modify this node.
+    #[inline]
+    pub fn set_casts_shadows_recursive(&mut self, casts_shadows: bool) -> Self {
+        self.apply_to_objects_mut_recursive(&mut |o| o.set_casts_shadows(casts_shadows));
+        self.clone()
+    }
+
     // === PBR Texture Maps ===
 
     /// Sets the normal map for this node's object only.
@@ -2564,6 +4026,62 @@ impl SceneNode3d {
         self.clone()
     }
 
+    /// Sets the texture array sampled per instance, each instance reading the
+    /// layer given by its [`InstanceData3d::texture_layer`] (this node only).
+    ///
+    /// Build the array with
+    /// [`TextureManager::add_image_array`](crate::resource::TextureManager::add_image_array).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # use kiss3d::resource::TextureManager;
+    /// # use kiss3d::scene::InstanceData3d;
+    /// # fn example(mut scene: SceneNode3d, images: Vec<image::DynamicImage>) {
+    /// let mut images = Some(images);
+    /// let array = TextureManager::get_global_manager(|tm| {
+    ///     tm.add_image_array(images.take().unwrap(), "tiles")
+    /// });
+    /// let mut cubes = scene.add_cube(1.0, 1.0, 1.0);
+    /// cubes.set_texture_array(array);
+    /// cubes.set_instances(
+    ///     &(0..10)
+    ///         .map(|i| InstanceData3d {
+    ///             position: Vec3::new(i as f32 * 1.5, 0.0, 0.0),
+    ///             texture_layer: i % 4,
+    ///             ..Default::default()
+    ///         })
+    ///         .collect::<Vec<_>>(),
+    /// );
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_texture_array(&mut self, texture: Arc<Texture>) -> Self {
+        self.apply_to_object_mut(&mut |o| o.set_texture_array(texture.clone()));
+        self.clone()
+    }
+
+    /// Sets the texture array sampled per instance (this node only).
+    ///
+    /// The texture array must already have been registered as `name`.
+    #[inline]
+    pub fn set_texture_array_with_name(&mut self, name: &str) -> Self {
+        let texture = TextureManager::get_global_manager(|tm| {
+            tm.get(name).unwrap_or_else(|| {
+                panic!("Invalid attempt to use the unregistered texture: {}", name)
+            })
+        });
+
+        self.set_texture_array(texture)
+    }
+
+    /// Clears the texture array (this node only).
+    #[inline]
+    pub fn clear_texture_array(&mut self) -> Self {
+        self.apply_to_object_mut(&mut |o| o.clear_texture_array());
+        self.clone()
+    }
+
     /// Sets the parallax displacement scale (this node only); `0` disables it.
     #[inline]
     pub fn set_parallax_scale(&mut self, scale: f32) -> Self {
@@ -2638,16 +4156,30 @@ impl SceneNode3d {
         &mut self,
         f: &mut F,
     ) {
+        self.apply_to_identified_objects_with_world_mut_recursive(&mut |_, transform, scale, o| {
+            f(transform, scale, o)
+        })
+    }
+
+    /// Like [`Self::apply_to_objects_with_world_mut_recursive`], also passing the
+    /// id of the node containing each object.
+    pub(crate) fn apply_to_identified_objects_with_world_mut_recursive<
+        F: FnMut(SceneNodeId, Pose3, Vec3, &mut Object3d),
+    >(
+        &mut self,
+        f: &mut F,
+    ) {
+        let id = self.id;
         let mut data = self.data_mut();
         let world_transform = data.world_transform;
         let world_scale = data.world_scale;
         if let Some(ref mut o) = data.object {
-            f(world_transform, world_scale, o)
+            f(id, world_transform, world_scale, o)
         }
 
         for c in data.children.iter_mut() {
             if c.data().visible {
-                c.apply_to_objects_with_world_mut_recursive(f)
+                c.apply_to_identified_objects_with_world_mut_recursive(f)
             }
         }
     }
@@ -2711,6 +4243,137 @@ impl SceneNode3d {
         self.clone()
     }
 
+    /// The primitive shape and dimensions this node was created with, if it was
+    /// created by one of the primitive constructors (e.g. [`SceneNode3d::cube`] or
+    /// [`SceneNode3d::add_sphere`]).
+    pub fn primitive(&self) -> Option<Primitive3d> {
+        self.data().primitive
+    }
+
+    /// Changes the shape or dimensions of a primitive node.
+    ///
+    /// Primitives sharing the built-in geometry are resized through this node's
+    /// local scale (which, as when they were created, also scales its children);
+    /// the others get a new mesh of their own, so other nodes sharing their
+    /// previous mesh are not affected. Any scale set on top of the previous
+    /// dimensions (e.g. with [`set_local_scale`](Self::set_local_scale)) is kept.
+    /// Does nothing (and logs a warning) if this node has no object.
+    pub fn set_primitive(&mut self, primitive: Primitive3d) -> Self {
+        if self.data().object.is_none() {
+            log::warn!("set_primitive: this scene node has no object.");
+            return self.clone();
+        }
+
+        let scale = self.scale_over_extents() * primitive.extents_scale();
+        match primitive.shared_geometry() {
+            Some((name, _)) => {
+                if let Some(mesh) = MeshManager3d::get_global_manager(|mm| mm.get(name)) {
+                    self.data_mut().get_object_mut().set_mesh(mesh);
+                }
+            }
+            None => {
+                let mesh = primitive.render_mesh().unwrap();
+                let mesh = Rc::new(RefCell::new(GpuMesh3d::from_render_mesh(mesh, false)));
+                self.data_mut().get_object_mut().set_mesh(mesh);
+            }
+        }
+        let _ = self.set_local_scale(scale.x, scale.y, scale.z);
+        self.data_mut().primitive = Some(primitive);
+        self.clone()
+    }
+
+    /// The local scale of this node without the dimensions of its primitive,
+    /// i.e. the scale set on top of them.
+    fn scale_over_extents(&self) -> Vec3 {
+        let data = self.data();
+        let extents = data.primitive.map_or(Vec3::ONE, |p| p.extents_scale());
+        // Nothing can be recovered along a null dimension.
+        let ratio = |scale: f32, extent: f32| if extent == 0.0 { 1.0 } else { scale / extent };
+        Vec3::new(
+            ratio(data.local_scale.x, extents.x),
+            ratio(data.local_scale.y, extents.y),
+            ratio(data.local_scale.z, extents.z),
+        )
+    }
+
+    /// Changes the extents of a cube node (see [`SceneNode3d::add_cube`]).
+    ///
+    /// Logs a warning and does nothing if this node is not a cube.
+    pub fn set_cube_extents(&mut self, wx: f32, wy: f32, wz: f32) -> Self {
+        match self.primitive() {
+            Some(Primitive3d::Cube { .. }) => self.set_primitive(Primitive3d::Cube {
+                extents: Vec3::new(wx, wy, wz),
+            }),
+            _ => self.not_a_primitive("set_cube_extents", "cube"),
+        }
+    }
+
+    /// Changes the radius of a sphere node (see [`SceneNode3d::add_sphere`]).
+    ///
+    /// Logs a warning and does nothing if this node is not a sphere.
+    pub fn set_sphere_radius(&mut self, r: f32) -> Self {
+        match self.primitive() {
+            Some(Primitive3d::Sphere { subdivs, .. }) => {
+                self.set_primitive(Primitive3d::Sphere { radius: r, subdivs })
+            }
+            _ => self.not_a_primitive("set_sphere_radius", "sphere"),
+        }
+    }
+
+    /// Changes the base radius and height of a cone node (see
+    /// [`SceneNode3d::add_cone`]).
+    ///
+    /// Logs a warning and does nothing if this node is not a cone.
+    pub fn set_cone_dimensions(&mut self, r: f32, h: f32) -> Self {
+        match self.primitive() {
+            Some(Primitive3d::Cone { subdivs, .. }) => self.set_primitive(Primitive3d::Cone {
+                radius: r,
+                height: h,
+                subdivs,
+            }),
+            _ => self.not_a_primitive("set_cone_dimensions", "cone"),
+        }
+    }
+
+    /// Changes the base radius and height of a cylinder node (see
+    /// [`SceneNode3d::add_cylinder`]).
+    ///
+    /// Logs a warning and does nothing if this node is not a cylinder.
+    pub fn set_cylinder_dimensions(&mut self, r: f32, h: f32) -> Self {
+        match self.primitive() {
+            Some(Primitive3d::Cylinder { subdivs, .. }) => {
+                self.set_primitive(Primitive3d::Cylinder {
+                    radius: r,
+                    height: h,
+                    subdivs,
+                })
+            }
+            _ => self.not_a_primitive("set_cylinder_dimensions", "cylinder"),
+        }
+    }
+
+    /// Changes the caps radius and height of a capsule node (see
+    /// [`SceneNode3d::add_capsule`]).
+    ///
+    /// Logs a warning and does nothing if this node is not a capsule.
+    pub fn set_capsule_dimensions(&mut self, r: f32, h: f32) -> Self {
+        match self.primitive() {
+            Some(Primitive3d::Capsule { subdivs, .. }) => {
+                self.set_primitive(Primitive3d::Capsule {
+                    radius: r,
+                    height: h,
+                    subdivs,
+                })
+            }
+            _ => self.not_a_primitive("set_capsule_dimensions", "capsule"),
+        }
+    }
+
+    fn not_a_primitive(&self, setter: &str, shape: &str) -> Self {
+        log::warn!("{}: this scene node is not a {}.", setter, shape);
+        self.clone()
+    }
+
     /// Returns the scaling factors of the object.
     #[inline]
     pub fn local_scale(&self) -> Vec3 {
@@ -2754,6 +4417,26 @@ impl SceneNode3d {
         data.world_transform
     }
 
+    /// The world-space axis-aligned bounding box of the visible geometry of this
+    /// node and its descendants, as `(min, max)`, or `None` if there is none.
+    ///
+    /// Instances and skinning are taken into account. The world transforms of the
+    /// whole scene graph are updated first, so this is exact even right after
+    /// nodes were moved. See [`Camera3d::fit_scene`] to frame the result.
+    pub fn world_aabb(&self) -> Option<(Vec3, Vec3)> {
+        let mut root = self.data.clone();
+        loop {
+            let parent = root.borrow().parent.as_ref().and_then(|p| p.upgrade());
+            match parent {
+                Some(parent) => root = parent,
+                None => break,
+            }
+        }
+        root.borrow_mut()
+            .do_propagate_transforms(Pose3::IDENTITY, Vec3::ONE);
+        self.data().world_aabb()
+    }
+
     /// This node world scale.
     ///
     /// This will force an update of the world transformation of its parents if they have been
@@ -2891,6 +4574,128 @@ impl SceneNode3d {
         self.clone()
     }
 
+    /// Sets this node's position in double precision, for worlds too large for
+    /// `f32` coordinates.
+    ///
+    /// The position is relative to the world origin of
+    /// [`Window::world_origin`](crate::window::Window::world_origin): the node's
+    /// local translation is set to `position - origin`, in `f32`, every time the
+    /// scene is rebased (see [`rebase`](Self::rebase)), which the window does each
+    /// frame. The precision thus depends on the distance to the origin, not on the
+    /// magnitude of `position`. This is meant for nodes placed directly in world
+    /// space, i.e. children of the root or of unmoved groups; their descendants
+    /// keep `f32` positions relative to them.
+    ///
+    /// World-space nodes positioned in `f32` are in render space: with
+    /// camera-relative rendering they are translated whenever the origin
+    /// follows the camera, so they stay put (with `f32` precision), but they are
+    /// not moved by [`Window::set_world_origin`](crate::window::Window::set_world_origin).
+    ///
+    /// Calling [`set_position`](Self::set_position) afterward does not clear the
+    /// double-precision position, which wins on the next rebase; use
+    /// [`clear_position_f64`](Self::clear_position_f64) for that.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::scene::SceneNode3d;
+    /// # use kiss3d::glamx::glam::DVec3;
+    /// let mut scene = SceneNode3d::empty();
+    /// let mut probe = scene.add_cube(1.0, 1.0, 1.0);
+    /// probe.set_position_f64(DVec3::new(1.496e11, 0.0, 0.0));
+    /// ```
+    pub fn set_position_f64(&mut self, position: DVec3) -> Self {
+        let mut data = self.data_mut();
+        data.invalidate();
+        data.precise_position = Some(position);
+        drop(data);
+        PRECISE_POSITION_EDITS.fetch_add(1, Ordering::Relaxed);
+        self.clone()
+    }
+
+    /// This node's double-precision world position, if set with
+    /// [`set_position_f64`](Self::set_position_f64).
+    pub fn position_f64(&self) -> Option<DVec3> {
+        self.data().precise_position
+    }
+
+    /// Removes the double-precision position of this node, which keeps its current
+    /// local translation.
+    pub fn clear_position_f64(&mut self) -> Self {
+        self.data_mut().precise_position = None;
+        PRECISE_POSITION_EDITS.fetch_add(1, Ordering::Relaxed);
+        self.clone()
+    }
+
+    /// A counter bumped by every call to [`set_position_f64`](Self::set_position_f64)
+    /// or [`clear_position_f64`](Self::clear_position_f64), and every edit of the
+    /// transform of a node with a double-precision position.
+    pub(crate) fn precise_position_edits() -> u64 {
+        PRECISE_POSITION_EDITS.load(Ordering::Relaxed)
+    }
+
+    /// Sets the local translation of the nodes of this subtree with a
+    /// double-precision position (see [`set_position_f64`](Self::set_position_f64))
+    /// to their position relative to `origin`.
+    ///
+    /// The window does this before each frame with its
+    /// [world origin](crate::window::Window::world_origin), whenever the origin,
+    /// the rendered scene or a double-precision position changed since the last
+    /// rebase, so this is only needed to read up-to-date `f32` positions between
+    /// frames. The descendants of a node with a double-precision position are
+    /// left untouched.
+    pub fn rebase(&mut self, origin: DVec3) {
+        let mut data = self.data_mut();
+        if let Some(position) = data.precise_position {
+            let translation = (position - origin).as_vec3();
+            if data.local_transform.translation != translation {
+                data.invalidate();
+                data.local_transform.translation = translation;
+            }
+            return;
+        }
+        for child in data.children.iter_mut() {
+            child.rebase(origin);
+        }
+    }
+
+    /// Translates by `offset` the largest subtrees of this node without any
+    /// double-precision position, so that the `f32` nodes keep their world
+    /// position when the origin of render space moves by `-offset`.
+    ///
+    /// Like [`rebase`](Self::rebase), this assumes that the ancestors of the
+    /// nodes with a double-precision position are neither rotated nor scaled.
+    pub(crate) fn shift_f32_nodes(&mut self, offset: Vec3) {
+        if !self.shift_f32_children(offset) {
+            let mut data = self.data_mut();
+            data.invalidate();
+            data.local_transform.translation += offset;
+        }
+    }
+
+    /// Translates by `offset` the children of this subtree without any
+    /// double-precision position below a node with one, and returns whether
+    /// this subtree has any.
+    fn shift_f32_children(&mut self, offset: Vec3) -> bool {
+        let mut data = self.data_mut();
+        if data.precise_position.is_some() {
+            return true;
+        }
+        let precise: Vec<bool> = data
+            .children
+            .iter_mut()
+            .map(|child| child.shift_f32_children(offset))
+            .collect();
+        if !precise.contains(&true) {
+            return false;
+        }
+        for (child, _) in data.children.iter_mut().zip(precise).filter(|(_, p)| !p) {
+            let mut child = child.data_mut();
+            child.invalidate();
+            child.local_transform.translation += offset;
+        }
+        true
+    }
+
     /// Returns this node's local rotation component.
     ///
     /// # Returns
@@ -3144,3 +4949,420 @@ fn node_global_matrix(node: &Rc<RefCell<SceneNodeData3d>>) -> Mat4 {
         None => local,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Where the default shader puts the vertex `p` of an object drawn on its own
+    /// with the world transform `world` and world scale `scale`.
+    fn drawn_alone(world: Pose3, scale: Vec3, p: Vec3) -> Vec3 {
+        world * (scale * p)
+    }
+
+    /// Where the default shader puts the vertex `p` of the instance
+    /// `(position, deformation)` of a batch drawn with `batch_scale`.
+    fn drawn_instanced(position: Vec3, deformation: Mat3, batch_scale: Vec3, p: Vec3) -> Vec3 {
+        position + deformation * (batch_scale * p)
+    }
+
+    #[test]
+    fn auto_instances_match_individual_draws() {
+        let rotation = Quat::from_axis_angle(Vec3::new(1.0, 2.0, 3.0).normalize(), 0.7);
+        let world = Pose3::from_parts(Vec3::new(1.0, -2.0, 0.5), rotation);
+        let p = Vec3::new(0.3, -0.4, 1.2);
+
+        for scale in [
+            Vec3::ONE,
+            Vec3::splat(2.5),
+            Vec3::new(1.0, 2.0, 3.0),
+            Vec3::new(0.5, 0.5, 4.0),
+        ] {
+            let batch_scale = auto_instance_scale(scale);
+            let (position, deformation) = auto_instance_transform(world, scale, batch_scale);
+            let alone = drawn_alone(world, scale, p);
+            let instanced = drawn_instanced(position, deformation, batch_scale, p);
+            assert!(
+                alone.abs_diff_eq(instanced, 1.0e-5),
+                "scale {:?}: {:?} != {:?}",
+                scale,
+                alone,
+                instanced
+            );
+        }
+    }
+
+    #[test]
+    fn uniform_scales_share_a_batch_scale() {
+        assert_eq!(auto_instance_scale(Vec3::splat(3.0)), Vec3::ONE);
+        assert_eq!(auto_instance_scale(Vec3::ONE), Vec3::ONE);
+        let s = Vec3::new(1.0, 2.0, 1.0);
+        assert_eq!(auto_instance_scale(s), s);
+    }
+
+    #[test]
+    fn origin_shifts_move_f32_nodes_only() {
+        let mut root = SceneNode3d::empty();
+        let mut group = root.add_group();
+        let mut precise = group.add_group();
+        let mut precise_child = precise.add_group();
+        let mut f32_sibling = group.add_group();
+        let mut f32_child = f32_sibling.add_group();
+        let mut f32_top = root.add_group();
+        precise.set_position_f64(DVec3::new(1.0e9, 0.0, 0.0));
+        precise_child.set_position(Vec3::X);
+        f32_child.set_position(Vec3::Y);
+        f32_top.set_position(Vec3::Z);
+        root.rebase(DVec3::ZERO);
+
+        let offset = Vec3::new(-1.0, 2.0, -3.0);
+        root.shift_f32_nodes(offset);
+        assert_eq!(root.position(), Vec3::ZERO);
+        assert_eq!(group.position(), Vec3::ZERO);
+        assert_eq!(precise.position(), Vec3::new(1.0e9, 0.0, 0.0));
+        assert_eq!(precise_child.position(), Vec3::X);
+        assert_eq!(f32_sibling.position(), offset);
+        assert_eq!(f32_child.position(), Vec3::Y);
+        assert_eq!(f32_top.position(), Vec3::Z + offset);
+
+        // Without any double-precision node, the whole scene moves.
+        let mut scene = SceneNode3d::empty();
+        let child = scene.add_group();
+        scene.shift_f32_nodes(offset);
+        assert_eq!(scene.position(), offset);
+        assert_eq!(child.position(), Vec3::ZERO);
+    }
+
+    #[test]
+    fn editing_a_precise_node_requests_a_rebase() {
+        let mut node = SceneNode3d::empty();
+        node.set_position_f64(DVec3::new(10.0, 0.0, 0.0));
+        let before = SceneNode3d::precise_position_edits();
+        node.set_position(Vec3::ZERO);
+        assert_ne!(SceneNode3d::precise_position_edits(), before);
+        node.rebase(DVec3::new(4.0, 0.0, 0.0));
+        assert_eq!(node.position(), Vec3::new(6.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn axes_follow_the_node_without_being_a_child() {
+        crate::pollster::block_on(async {
+            if !crate::shader_validity::tests::adapter_available().await {
+                eprintln!("axes test: no GPU adapter found, skipping");
+                return;
+            }
+            // The arrows' meshes need a GPU context.
+            let _surface = crate::window::OffscreenSurface::new(8, 8).await;
+
+            let mut root = SceneNode3d::empty();
+            let mut node = root.add_group();
+            node.show_axes(1.0, 0.05);
+            assert!(node.data().children().is_empty());
+            assert!(root.find_by_name("axes").is_none());
+            assert_eq!(root.world_aabb(), None);
+
+            // The arrows still follow the node, even when it moves after a
+            // transform propagation.
+            let axes = node.data().axes.clone().unwrap();
+            node.set_position(Vec3::X);
+            assert_eq!(axes.world_pose().translation, Vec3::X);
+            root.world_aabb();
+            node.set_position(Vec3::Y);
+            root.world_aabb();
+            assert_eq!(axes.data().world_transform.translation, Vec3::Y);
+
+            node.hide_axes();
+            assert!(node.data().axes.is_none());
+            assert!(axes.data().is_root());
+        });
+    }
+
+    #[test]
+    fn auto_instancing_draws_like_individual_objects() {
+        crate::pollster::block_on(async {
+            if !crate::shader_validity::tests::adapter_available().await {
+                eprintln!("auto-instancing test: no GPU adapter found, skipping");
+                return;
+            }
+            let mut surface = crate::window::OffscreenSurface::new(96, 64).await;
+            surface.window_mut().set_shadows_enabled(true);
+            let mut camera =
+                crate::camera::OrbitCamera3d::new(Vec3::new(0.0, 4.0, 8.0), Vec3::ZERO);
+
+            let mut scene = SceneNode3d::empty();
+            scene
+                .add_light(Light::point(60.0).with_casts_shadows(true))
+                .set_position(Vec3::new(2.0, 5.0, 3.0));
+            scene.add_cube(10.0, 0.1, 10.0).set_position(Vec3::NEG_Y);
+            let mut spheres: Vec<_> = (0..5)
+                .map(|i| {
+                    let mut sphere = scene.add_sphere(0.4 + 0.1 * i as f32);
+                    sphere
+                        .set_position(Vec3::new(i as f32 - 2.0, 0.0, 0.0))
+                        .set_color(Color::new(0.2 * i as f32, 0.5, 1.0, 1.0));
+                    sphere
+                })
+                .collect();
+
+            let mut render = async |scene: &mut SceneNode3d, auto_instancing: bool| {
+                scene.set_auto_instancing(auto_instancing);
+                surface.render_3d(scene, &mut camera).await;
+                surface.snap_image()
+            };
+            let assert_same = |a: &image::RgbImage, b: &image::RgbImage| {
+                let differing = a
+                    .pixels()
+                    .zip(b.pixels())
+                    .filter(|(a, b)| a.0.iter().zip(b.0).any(|(a, b)| a.abs_diff(b) > 2))
+                    .count();
+                assert!(differing < 4, "{} pixels differ", differing);
+            };
+            let batch_sizes = |scene: &SceneNode3d| -> Vec<usize> {
+                let data = scene.data();
+                data.auto_instances
+                    .iter()
+                    .map(|b| b.members.len())
+                    .collect()
+            };
+
+            let alone = render(&mut scene, false).await;
+            assert!(batch_sizes(&scene).is_empty());
+            let instanced = render(&mut scene, true).await;
+            assert_eq!(batch_sizes(&scene), [5]);
+            assert_same(&alone, &instanced);
+
+            // Moving and recoloring a member updates its instance.
+            spheres[1]
+                .set_position(Vec3::new(-1.0, 1.0, 0.5))
+                .set_color(Color::new(1.0, 0.0, 0.0, 1.0));
+            let instanced = render(&mut scene, true).await;
+            assert_eq!(batch_sizes(&scene), [5]);
+            let alone = render(&mut scene, false).await;
+            assert_same(&alone, &instanced);
+
+            // A member that can't share the draw anymore leaves its batch, and
+            // new nodes join it.
+            spheres[2].set_metallic(1.0);
+            let _ = render(&mut scene, true).await;
+            assert_eq!(batch_sizes(&scene), [4]);
+            let mut extra = scene.add_sphere(0.3);
+            extra.set_position(Vec3::new(0.0, 0.0, 2.0));
+            spheres.push(extra);
+            let instanced = render(&mut scene, true).await;
+            assert_eq!(batch_sizes(&scene), [5]);
+            let alone = render(&mut scene, false).await;
+            assert_same(&alone, &instanced);
+        });
+    }
+
+    #[test]
+    fn instances_sample_their_layer_of_the_texture_array() {
+        crate::pollster::block_on(async {
+            if !crate::shader_validity::tests::adapter_available().await {
+                eprintln!("texture array test: no GPU adapter found, skipping");
+                return;
+            }
+            let mut surface = crate::window::OffscreenSurface::new(64, 32).await;
+            let mut camera =
+                crate::camera::OrbitCamera3d::new(Vec3::new(0.0, 0.0, 6.0), Vec3::ZERO);
+
+            let layer = |rgb: [u8; 3]| {
+                image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(2, 2, image::Rgb(rgb)))
+            };
+            let mut images = Some(vec![layer([255, 0, 0]), layer([0, 0, 255])]);
+            let array = TextureManager::get_global_manager(|tm| {
+                tm.add_image_array(images.take().unwrap(), "instance_layers_test")
+            });
+
+            let mut scene = SceneNode3d::empty();
+            let mut cubes = scene.add_cube(1.0, 1.0, 1.0);
+            cubes
+                .set_shading_mode(ShadingMode::TexturedUnlit)
+                .set_texture_array(array)
+                .set_instances(&[
+                    InstanceData3d::new(Vec3::new(-1.5, 0.0, 0.0)).with_texture_layer(0),
+                    InstanceData3d::new(Vec3::new(1.5, 0.0, 0.0)).with_texture_layer(1),
+                ]);
+            surface.render_3d(&mut scene, &mut camera).await;
+            let image = surface.snap_image();
+            let count = |x_range: std::ops::Range<u32>, channel: usize| {
+                image
+                    .enumerate_pixels()
+                    .filter(|(x, _, p)| x_range.contains(x) && p.0[channel] > 200)
+                    .count()
+            };
+            // Red on the left (layer 0), blue on the right (layer 1).
+            assert!(count(0..32, 0) > 0 && count(0..32, 2) == 0);
+            assert!(count(32..64, 2) > 0 && count(32..64, 0) == 0);
+
+            // The compute-writable buffers hold a layer per instance too.
+            let buffers = cubes.instance_compute_buffers(3);
+            assert!(buffers.texture_layers.size() >= 3 * 4);
+        })
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn obj_materials_load_from_non_utf8_paths() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        crate::pollster::block_on(async {
+            if !crate::shader_validity::tests::adapter_available().await {
+                eprintln!("OBJ material test: no GPU adapter found, skipping");
+                return;
+            }
+            let _surface = crate::window::OffscreenSurface::new(8, 8).await;
+
+            let dir = std::env::temp_dir()
+                .join(format!("kiss3d_mtl_{}", std::process::id()))
+                .join(OsStr::from_bytes(b"caf\xe9"));
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(
+                dir.join("quad.obj"),
+                "mtllib quad.mtl\nusemtl shiny\nv 0 0 0\nv 1 0 0\nv 1 1 0\nf 1 2 3\n",
+            )
+            .unwrap();
+            std::fs::write(dir.join("quad.mtl"), "newmtl shiny\nmap_Ks spec.png\n").unwrap();
+            image::GrayImage::from_pixel(2, 2, image::Luma([200]))
+                .save(dir.join("spec.png"))
+                .unwrap();
+
+            let mut scene = SceneNode3d::empty();
+            let quad = scene
+                .try_add_obj(&dir.join("quad.obj"), &dir, Vec3::ONE)
+                .unwrap();
+            let map = quad
+                .data()
+                .object()
+                .unwrap()
+                .data()
+                .metallic_roughness_map()
+                .cloned();
+            let name = format!("{}#roughness", dir.join("spec.png").to_string_lossy());
+            let registered = TextureManager::get_global_manager(|tm| tm.get(&name));
+            assert!(Arc::ptr_eq(&map.unwrap(), &registered.unwrap()));
+            let _ = std::fs::remove_dir_all(dir.parent().unwrap());
+        })
+    }
+
+    /// Propagates the world transforms of the scene rooted at `root`, as
+    /// `prepare` does.
+    fn propagate(root: &SceneNode3d) {
+        root.data
+            .borrow_mut()
+            .do_propagate_transforms(Pose3::IDENTITY, Vec3::ONE);
+    }
+
+    /// The world translation of `node` computed by the last propagation.
+    fn propagated_translation(node: &SceneNode3d) -> Vec3 {
+        node.data().world_transform.translation
+    }
+
+    #[test]
+    fn moving_a_grandchild_after_a_propagation_updates_it() {
+        let mut root = SceneNode3d::empty();
+        let mut a = root.add_group();
+        a.set_position(Vec3::X);
+        let mut b = a.add_group();
+        b.set_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2));
+        let mut c = b.add_group();
+        c.set_position(Vec3::X);
+        propagate(&root);
+        assert!(propagated_translation(&c).abs_diff_eq(Vec3::new(1.0, 1.0, 0.0), 1.0e-6));
+
+        c.set_position(Vec3::new(2.0, 0.0, 0.0));
+        propagate(&root);
+        assert!(propagated_translation(&c).abs_diff_eq(Vec3::new(1.0, 2.0, 0.0), 1.0e-6));
+
+        // Moving an ancestor moves its untouched descendants too.
+        a.set_position(Vec3::Z);
+        propagate(&root);
+        assert!(propagated_translation(&c).abs_diff_eq(Vec3::new(0.0, 2.0, 1.0), 1.0e-6));
+    }
+
+    #[test]
+    fn reparented_nodes_follow_their_new_parent() {
+        let mut root = SceneNode3d::empty();
+        let mut a = root.add_group();
+        a.set_position(Vec3::X);
+        let mut b = root.add_group();
+        b.set_position(Vec3::Y);
+        let mut c = a.add_group();
+        c.set_position(Vec3::Z);
+        propagate(&root);
+        assert_eq!(propagated_translation(&c), Vec3::X + Vec3::Z);
+
+        c.remove();
+        b.add_child(c.clone());
+        propagate(&root);
+        assert_eq!(propagated_translation(&c), Vec3::Y + Vec3::Z);
+
+        // Its old parent doesn't move it anymore, its new one does.
+        a.set_position(Vec3::NEG_X);
+        c.set_position(Vec3::new(0.0, 0.0, 2.0));
+        propagate(&root);
+        assert_eq!(propagated_translation(&c), Vec3::new(0.0, 1.0, 2.0));
+        b.set_position(Vec3::ZERO);
+        propagate(&root);
+        assert_eq!(propagated_translation(&c), Vec3::new(0.0, 0.0, 2.0));
+    }
+
+    #[test]
+    fn world_pose_follows_a_moved_ancestor() {
+        let mut root = SceneNode3d::empty();
+        let mut a = root.add_group();
+        let mut b = a.add_group();
+        b.set_position(Vec3::X);
+        b.set_local_scale(1.0, 2.0, 1.0);
+        assert_eq!(b.world_pose().translation, Vec3::X);
+
+        // The parent transform applies after the local one.
+        a.set_position(Vec3::X);
+        a.set_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2));
+        a.set_local_scale(3.0, 3.0, 3.0);
+        assert!(b
+            .world_pose()
+            .translation
+            .abs_diff_eq(Vec3::new(1.0, 1.0, 0.0), 1.0e-6));
+        assert_eq!(b.world_scale(), Vec3::new(3.0, 6.0, 3.0));
+    }
+
+    #[test]
+    fn resizing_a_primitive_keeps_its_user_scale() {
+        crate::pollster::block_on(async {
+            if !crate::shader_validity::tests::adapter_available().await {
+                eprintln!("primitive scale test: no GPU adapter found, skipping");
+                return;
+            }
+            let _surface = crate::window::OffscreenSurface::new(8, 8).await;
+
+            let mut scene = SceneNode3d::empty();
+            let mut cube = scene.add_cube(1.0, 2.0, 3.0);
+            let _ = cube.set_local_scale(2.0, 4.0, 6.0);
+            let _ = cube.set_cube_extents(4.0, 1.0, 1.0);
+            assert_eq!(cube.local_scale(), Vec3::new(8.0, 2.0, 2.0));
+
+            // Primitives with their own mesh only keep the user scale.
+            let mut capsule = scene.add_capsule(1.0, 2.0);
+            let _ = capsule.set_local_scale(3.0, 3.0, 3.0);
+            let _ = capsule.set_capsule_dimensions(0.5, 1.0);
+            assert_eq!(capsule.local_scale(), Vec3::splat(3.0));
+
+            let mut sphere = scene.add_sphere(0.5);
+            let _ = sphere.set_local_scale(2.0, 2.0, 2.0);
+            let _ = sphere.set_primitive(Primitive3d::Capsule {
+                radius: 0.5,
+                height: 1.0,
+                subdivs: (8, 8),
+            });
+            assert_eq!(sphere.local_scale(), Vec3::splat(2.0));
+
+            let built = scene
+                .build_cube(1.0, 2.0, 3.0)
+                .scale(Vec3::splat(2.0))
+                .spawn();
+            assert_eq!(built.local_scale(), Vec3::new(2.0, 4.0, 6.0));
+        })
+    }
+}
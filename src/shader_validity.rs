@@ -20,7 +20,7 @@
 //! dev box without a usable GPU doesn't fail the suite) rather than failing.
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use crate::builtin::{Bone2d, LitParams, ObjectMaterial, SkinVertex2d, SkinnedMesh2d};
     use crate::camera::{CoordinateSystem2d, FixedView2d, OrbitCamera3d};
     use crate::context::Context;
@@ -31,7 +31,8 @@ mod tests {
         PostProcessingEffect, SobelEdgeHighlight, Waves,
     };
     use crate::renderer::RayTracer;
-    use crate::scene::{AlphaMode, SceneNode2d, SceneNode3d, SpriteSheet, Tilemap};
+    use crate::scene::{AlphaMode, SceneNode2d, SceneNode3d, ShadingMode, SpriteSheet, Tilemap};
+    use crate::text::Font;
     use crate::window::OffscreenSurface;
     use glamx::{Pose2, Vec2, Vec3};
 
@@ -39,7 +40,7 @@ mod tests {
 
     /// Is a usable GPU adapter present? (CI installs lavapipe.) When none is found we
     /// skip the test instead of failing.
-    async fn adapter_available() -> bool {
+    pub(crate) async fn adapter_available() -> bool {
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
             backends: wgpu::Backends::all(),
             ..wgpu::InstanceDescriptor::new_without_display_handle()
@@ -93,6 +94,43 @@ mod tests {
         tr.set_color(Color::new(0.2, 0.9, 0.3, 0.6));
         tr.set_alpha_mode(AlphaMode::Blend);
         tr.set_position(Vec3::new(2.5, 0.0, 0.0));
+        // The user-shader material, with its default shader.
+        let _ = crate::resource::MaterialManager3d::get_global_manager(|mm| {
+            mm.register(
+                "shader_material",
+                crate::builtin::ShaderMaterial::new(crate::builtin::SHADER_MATERIAL_SRC),
+            )
+        });
+        scene
+            .add_sphere(0.5)
+            .set_material_with_name("shader_material")
+            .set_position(Vec3::new(0.0, 2.0, 0.0));
+        // Toon shading with both outline modes.
+        let toon: Box<dyn crate::resource::Material3d> = Box::new(
+            crate::builtin::ToonMaterial::new()
+                .with_outline(crate::builtin::ToonOutline::ScreenSpace { width: 2.0 }),
+        );
+        scene
+            .add_sphere(0.5)
+            .set_material(std::rc::Rc::new(std::cell::RefCell::new(toon)))
+            .set_position(Vec3::new(2.0, 2.0, 0.0));
+        scene
+            .add_cube(0.5, 0.5, 0.5)
+            .set_material_with_name("toon")
+            .set_position(Vec3::new(-2.0, 2.0, 0.0));
+        // Matcap shading.
+        let matcap = crate::resource::TextureManager::get_global_manager(|tm| tm.get_default());
+        scene
+            .add_sphere(0.5)
+            .set_matcap(matcap)
+            .set_position(Vec3::new(0.0, 2.0, -2.0));
+        // Unlit shading from per-vertex colors.
+        let mut vc = scene.add_quad(1.0, 1.0, 1, 1);
+        let mut num_vertices = 0;
+        vc.read_vertices(&mut |v| num_vertices = v.len());
+        vc.set_vertex_colors(&vec![Color::new(1.0, 0.2, 0.2, 1.0); num_vertices])
+            .set_shading_mode(ShadingMode::VertexColor)
+            .set_position(Vec3::new(0.0, 2.0, 2.0));
         scene
     }
 
@@ -215,17 +253,54 @@ mod tests {
             eprintln!("instantiated {built} object-material variants");
 
             // 2) Render real scenes that instantiate the rest of the shaders, with the
-            // screen-space effects enabled (shadows, SSAO, SSR, DoF, bloom, skybox).
+            // screen-space effects enabled (shadows, SSAO, SSR, DoF, bloom, skybox)
+            // the reference grid, a decal and an inset view.
             surface.window_mut().set_shadows_enabled(true);
             surface.window_mut().set_ssao_enabled(true);
             surface.window_mut().set_ssr_enabled(true);
             surface.window_mut().set_dof_enabled(true);
+            surface.window_mut().set_motion_blur_enabled(true);
             surface.set_bloom_enabled(true);
+            surface
+                .window_mut()
+                .enable_reference_grid(crate::renderer::GridConfig::default());
+            surface.window_mut().add_decal(
+                crate::resource::Texture::new_default(),
+                glamx::Pose3::IDENTITY,
+                Vec3::splat(2.0),
+            );
+            let inset = surface.window_mut().add_inset_view(
+                (0.7, 0.0, 0.3, 0.3),
+                OrbitCamera3d::new(Vec3::new(0.0, 12.0, 0.01), Vec3::ZERO),
+            );
+            surface
+                .window_mut()
+                .inset_view_mut(inset)
+                .unwrap()
+                .set_border(Some((Color::new(1.0, 1.0, 1.0, 1.0), 2.0)));
             let mut cam = OrbitCamera3d::new(Vec3::new(0.0, 2.0, 9.0), Vec3::ZERO);
             let mut scene = demo_scene_3d();
             for _ in 0..2 {
+                // Distance-field text, both screen-space and as a 3D label.
+                let font = Font::default();
+                surface.window_mut().draw_text_sdf(
+                    "SDF",
+                    Vec2::new(4.0, 4.0),
+                    24.0,
+                    &font,
+                    Color::new(1.0, 1.0, 1.0, 1.0),
+                );
+                surface.window_mut().draw_text_3d(
+                    "label",
+                    Vec3::new(0.0, 1.0, 0.0),
+                    0.5,
+                    &font,
+                    Color::new(1.0, 1.0, 1.0, 1.0),
+                );
                 surface.render_3d(&mut scene, &mut cam).await;
             }
+            // Depth read-back (builds both the single-sample and MSAA variants).
+            let _ = surface.snap_depth_image();
 
             // 3) 2D scene (object2d / points2d / polyline2d / wireframe / sdf2d / lit2d).
             Light2dManager::get_global_manager(|m| {
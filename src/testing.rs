@@ -0,0 +1,316 @@
+//! Golden-image testing of rendered scenes.
+//!
+//! Renders scenes headlessly (no window nor display server, see
+//! [`OffscreenSurface`]) and compares the result against reference images with a
+//! perceptual tolerance, so the visual output of an application can be checked
+//! in CI.
+//!
+//! # Example
+//! ```no_run
+//! use kiss3d::prelude::*;
+//! use kiss3d::testing::{assert_image_matches, render_scene_to_image, Tolerance};
+//!
+//! // In a `#[test]` function:
+//! kiss3d::pollster::block_on(async {
+//!     let mut scene = SceneNode3d::empty();
+//!     scene.add_cube(1.0, 1.0, 1.0).set_color(RED);
+//!     let mut camera = OrbitCamera3d::default();
+//!
+//!     let image = render_scene_to_image(&mut scene, &mut camera, (256, 256)).await;
+//!     assert_image_matches(&image, "tests/golden/red_cube.png", Tolerance::default());
+//! });
+//! ```
+//!
+//! Set the `KISS3D_UPDATE_GOLDEN` environment variable to create the reference
+//! images, or to overwrite them after an intended visual change; without it, a
+//! missing reference image fails the test.
+
+use std::path::{Path, PathBuf};
+
+use image::{Rgb, RgbImage};
+
+use crate::camera::Camera3d;
+use crate::scene::SceneNode3d;
+use crate::window::OffscreenSurface;
+
+/// Environment variable which, when set, makes [`assert_image_matches`]
+/// write the reference images instead of comparing against them.
+pub const UPDATE_GOLDEN_ENV: &str = "KISS3D_UPDATE_GOLDEN";
+
+/// Renders one frame of `scene` seen by `camera` into an image of `size`
+/// pixels, without any window.
+///
+/// # Panics
+/// Panics if no GPU adapter is available.
+pub async fn render_scene_to_image(
+    scene: &mut SceneNode3d,
+    camera: &mut impl Camera3d,
+    size: (u32, u32),
+) -> RgbImage {
+    let mut surface = OffscreenSurface::new(size.0, size.1).await;
+    surface.render_image_3d(scene, camera).await
+}
+
+/// How much two images may differ and still be considered matching.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Tolerance {
+    /// Perceptual difference, in `[0, 1]`, above which two pixels are considered
+    /// different. `0.0` requires exact equality; the default `0.1` ignores the
+    /// small color shifts of rasterization and filtering differences between GPUs.
+    pub pixel_threshold: f32,
+    /// Fraction of the pixels, in `[0, 1]`, allowed to differ.
+    pub max_differing_fraction: f32,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance {
+            pixel_threshold: 0.1,
+            max_differing_fraction: 0.001,
+        }
+    }
+}
+
+impl Tolerance {
+    /// A tolerance requiring both images to be identical.
+    pub fn exact() -> Self {
+        Tolerance {
+            pixel_threshold: 0.0,
+            max_differing_fraction: 0.0,
+        }
+    }
+}
+
+/// The differences between two images, computed by [`compare_images`].
+#[derive(Clone, Debug)]
+pub struct ImageDiff {
+    /// Number of pixels differing more than the tolerance's `pixel_threshold`.
+    pub differing_pixels: usize,
+    /// Total number of pixels compared.
+    pub total_pixels: usize,
+    /// Largest perceptual difference between two pixels, in `[0, 1]`.
+    pub max_difference: f32,
+    /// The differing pixels in red over a faded copy of the expected image.
+    pub diff_image: RgbImage,
+}
+
+impl ImageDiff {
+    /// Fraction of the pixels differing, in `[0, 1]`.
+    pub fn differing_fraction(&self) -> f32 {
+        if self.total_pixels == 0 {
+            0.0
+        } else {
+            self.differing_pixels as f32 / self.total_pixels as f32
+        }
+    }
+}
+
+/// Perceptual difference between two colors, in `[0, 1]`.
+///
+/// This is the YIQ color distance of Kotsarenko and Ramos ("Measuring perceived
+/// color difference using YIQ NTSC transmission color space", 2010), which
+/// weights brightness changes more than hue changes like the human eye does.
+// The coefficients are kept as published rather than truncated to `f32`.
+#[allow(clippy::excessive_precision)]
+pub fn pixel_difference(a: Rgb<u8>, b: Rgb<u8>) -> f32 {
+    // Largest possible value of the weighted distance below, for normalization.
+    const MAX_DELTA: f32 = 35215.0;
+
+    let [r1, g1, b1] = a.0.map(f32::from);
+    let [r2, g2, b2] = b.0.map(f32::from);
+    let (dr, dg, db) = (r1 - r2, g1 - g2, b1 - b2);
+
+    let y = dr * 0.29889531 + dg * 0.58662247 + db * 0.11448223;
+    let i = dr * 0.59597799 - dg * 0.2741761 - db * 0.32180189;
+    let q = dr * 0.21147017 - dg * 0.52261711 + db * 0.31114694;
+    ((0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q) / MAX_DELTA).min(1.0)
+}
+
+/// Compares `actual` to `expected` pixel by pixel.
+///
+/// # Panics
+/// Panics if the images don't have the same size.
+pub fn compare_images(actual: &RgbImage, expected: &RgbImage, tolerance: Tolerance) -> ImageDiff {
+    assert_eq!(
+        actual.dimensions(),
+        expected.dimensions(),
+        "The compared images have different sizes"
+    );
+
+    let mut diff_image = RgbImage::new(expected.width(), expected.height());
+    let mut differing_pixels = 0;
+    let mut max_difference = 0.0f32;
+
+    for ((a, e), d) in actual
+        .pixels()
+        .zip(expected.pixels())
+        .zip(diff_image.pixels_mut())
+    {
+        let difference = pixel_difference(*a, *e);
+        max_difference = max_difference.max(difference);
+
+        if difference > tolerance.pixel_threshold {
+            differing_pixels += 1;
+            *d = Rgb([255, 0, 0]);
+        } else {
+            // Faded grayscale of the expected image, for context.
+            let luma = (e[0] as u32 * 299 + e[1] as u32 * 587 + e[2] as u32 * 114) / 1000;
+            let faded = (255 - (255 - luma) / 4) as u8;
+            *d = Rgb([faded, faded, faded]);
+        }
+    }
+
+    ImageDiff {
+        differing_pixels,
+        total_pixels: (expected.width() * expected.height()) as usize,
+        max_difference,
+        diff_image,
+    }
+}
+
+/// Asserts that `actual` matches the reference image stored at `golden_path`.
+///
+/// If the [`UPDATE_GOLDEN_ENV`] environment variable is set, `actual` is saved
+/// as the new reference instead. On mismatch, `actual` and the
+/// [`ImageDiff::diff_image`] are saved next to the reference (as
+/// `<name>.actual.png` and `<name>.diff.png`) for inspection.
+///
+/// # Panics
+/// Panics if the reference image doesn't exist (unless [`UPDATE_GOLDEN_ENV`]
+/// is set, `actual` is then saved as `<name>.actual.png`), if the images differ
+/// by more than `tolerance`, if their sizes differ, or if the images can't be
+/// read or written.
+pub fn assert_image_matches<P: AsRef<Path>>(
+    actual: &RgbImage,
+    golden_path: P,
+    tolerance: Tolerance,
+) {
+    let golden_path = golden_path.as_ref();
+
+    if std::env::var_os(UPDATE_GOLDEN_ENV).is_some() {
+        if let Some(dir) = golden_path.parent() {
+            std::fs::create_dir_all(dir).expect("Failed to create the golden image directory");
+        }
+        actual
+            .save(golden_path)
+            .unwrap_or_else(|e| panic!("Failed to save {}: {}", golden_path.display(), e));
+        log::info!("Saved the golden image {}", golden_path.display());
+        return;
+    }
+
+    if !golden_path.exists() {
+        // A missing reference must not make the check pass silently (e.g. when
+        // it was not committed).
+        let actual_path = sibling_path(golden_path, "actual");
+        if let Some(dir) = golden_path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let _ = actual.save(&actual_path);
+        panic!(
+            "The golden image {} doesn't exist. Run with {} set to create it from \
+             the rendered image (saved to {}).",
+            golden_path.display(),
+            UPDATE_GOLDEN_ENV,
+            actual_path.display()
+        );
+    }
+
+    let expected = image::open(golden_path)
+        .unwrap_or_else(|e| panic!("Failed to load {}: {}", golden_path.display(), e))
+        .to_rgb8();
+    assert_eq!(
+        actual.dimensions(),
+        expected.dimensions(),
+        "The image size doesn't match the golden image {}",
+        golden_path.display()
+    );
+
+    let diff = compare_images(actual, &expected, tolerance);
+    if diff.differing_fraction() > tolerance.max_differing_fraction {
+        let actual_path = sibling_path(golden_path, "actual");
+        let diff_path = sibling_path(golden_path, "diff");
+        let _ = actual.save(&actual_path);
+        let _ = diff.diff_image.save(&diff_path);
+        panic!(
+            "The image doesn't match the golden image {}: {} of {} pixels ({:.3}%) differ \
+             (max difference {:.3}). See {} and {}.",
+            golden_path.display(),
+            diff.differing_pixels,
+            diff.total_pixels,
+            diff.differing_fraction() * 100.0,
+            diff.max_difference,
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+/// `dir/name.png` -> `dir/name.<suffix>.png`.
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!("{}.{}.png", stem, suffix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pixel_difference_bounds() {
+        let black = Rgb([0, 0, 0]);
+        let white = Rgb([255, 255, 255]);
+        assert_eq!(pixel_difference(black, black), 0.0);
+        assert_eq!(
+            pixel_difference(white, black),
+            pixel_difference(black, white)
+        );
+        assert!(pixel_difference(white, black) > 0.9);
+        assert!(pixel_difference(Rgb([255, 0, 255]), Rgb([0, 255, 0])) <= 1.0);
+
+        // Brightness changes weigh more than hue changes of the same amount.
+        let gray = Rgb([128, 128, 128]);
+        let lighter = Rgb([148, 148, 148]);
+        let bluer = Rgb([128, 128, 148]);
+        assert!(pixel_difference(gray, lighter) > pixel_difference(gray, bluer));
+        assert!(pixel_difference(gray, lighter) < 0.1);
+    }
+
+    #[test]
+    fn compare_images_counts_differing_pixels() {
+        let expected = RgbImage::from_pixel(4, 2, Rgb([100, 100, 100]));
+        let mut actual = expected.clone();
+        actual.put_pixel(1, 0, Rgb([255, 0, 0]));
+        actual.put_pixel(2, 1, Rgb([101, 100, 100]));
+
+        let diff = compare_images(&actual, &expected, Tolerance::default());
+        assert_eq!(diff.differing_pixels, 1);
+        assert_eq!(diff.total_pixels, 8);
+        assert_eq!(diff.differing_fraction(), 0.125);
+        assert_eq!(
+            diff.max_difference,
+            pixel_difference(Rgb([255, 0, 0]), Rgb([100, 100, 100]))
+        );
+        assert_eq!(*diff.diff_image.get_pixel(1, 0), Rgb([255, 0, 0]));
+        assert_ne!(*diff.diff_image.get_pixel(2, 1), Rgb([255, 0, 0]));
+
+        let exact = compare_images(&actual, &expected, Tolerance::exact());
+        assert_eq!(exact.differing_pixels, 2);
+        let same = compare_images(&expected, &expected, Tolerance::exact());
+        assert_eq!(same.differing_pixels, 0);
+        assert_eq!(same.max_difference, 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't exist")]
+    fn missing_golden_image_fails() {
+        if std::env::var_os(UPDATE_GOLDEN_ENV).is_some() {
+            panic!("doesn't exist (skipped with {} set)", UPDATE_GOLDEN_ENV);
+        }
+        let dir = std::env::temp_dir().join("kiss3d_missing_golden_test");
+        let image = RgbImage::new(2, 2);
+        assert_image_matches(&image, dir.join("missing.png"), Tolerance::default());
+    }
+}
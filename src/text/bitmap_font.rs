@@ -0,0 +1,486 @@
+//! Pre-rendered fonts in the AngelCode BMFont format.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use glamx::Vec2;
+use image::DynamicImage;
+
+use crate::context::Context;
+use crate::error::Error;
+
+/// A font whose glyphs are pre-rendered into atlas images, loaded from the
+/// AngelCode BMFont text format (a `.fnt` file and its page images).
+///
+/// Bitmap fonts are drawn with
+/// [`Window::draw_text_bitmap`](crate::window::Window::draw_text_bitmap). They
+/// suit pixel art and stylized text (the colors of the atlas are kept and
+/// multiplied by the text color), and start faster than TrueType fonts on the
+/// web since nothing is rasterized at runtime. Drawn at their native
+/// [`size`](Self::size) on whole pixels, they are pixel-perfect.
+///
+/// Atlases of signed distance fields, single- or multi-channel, as generated
+/// by `msdf-bmfont-xml` or `msdf-atlas-gen`, stay sharp at any size. They are
+/// detected from the `distanceField` line of the file, or can be flagged with
+/// [`with_distance_field`](Self::with_distance_field). Pages whose glyphs all
+/// lie in the same single channel (their `chnl` attribute, e.g. alpha-only
+/// atlases) are read from that channel.
+///
+/// # Example
+/// ```no_run
+/// # use kiss3d::prelude::*;
+/// # use kiss3d::text::BitmapFont;
+/// # use std::path::Path;
+/// # use std::sync::Arc;
+/// # #[kiss3d::main]
+/// # async fn main() {
+/// # let mut window = Window::new("Example").await;
+/// # let mut camera = OrbitCamera3d::default();
+/// # let mut scene = SceneNode3d::empty();
+/// let font = Arc::new(BitmapFont::from_file(Path::new("assets/pixel.fnt")).unwrap());
+///
+/// while window.render_3d(&mut scene, &mut camera).await {
+///     let pos = Vec2::new(10.0, 10.0);
+///     window.draw_text_bitmap("Score: 42", pos, font.size(), &font, WHITE);
+/// }
+/// # }
+/// ```
+pub struct BitmapFont {
+    size: f32,
+    line_height: f32,
+    base: f32,
+    glyphs: HashMap<char, BitmapGlyph>,
+    kernings: HashMap<(char, char), f32>,
+    pages: Vec<wgpu::TextureView>,
+    distance_field: bool,
+}
+
+/// A glyph of a bitmap font, in pixels of its page.
+#[derive(Copy, Clone, Debug)]
+pub(super) struct BitmapGlyph {
+    /// The texture coordinates of the glyph's rectangle.
+    pub(super) uv_min: [f32; 2],
+    pub(super) uv_max: [f32; 2],
+    pub(super) size: Vec2,
+    /// The offset of the rectangle from the pen position, at the top of the line.
+    pub(super) offset: Vec2,
+    pub(super) advance: f32,
+    pub(super) page: usize,
+}
+
+impl BitmapFont {
+    /// Loads a BMFont text file and the page images it references, which are
+    /// looked up relative to the file.
+    pub fn from_file(path: &Path) -> Result<BitmapFont, Error> {
+        let font_error = |message: String| Error::FontLoad {
+            path: Some(path.to_path_buf()),
+            message,
+        };
+        let source = std::fs::read_to_string(path).map_err(|e| font_error(e.to_string()))?;
+        let fnt = parse_fnt(&source).map_err(font_error)?;
+
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        let pages = fnt
+            .page_files
+            .iter()
+            .map(|file| {
+                let path = dir.join(file);
+                image::open(&path).map_err(|source| Error::TextureLoad {
+                    path: Some(path),
+                    source,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::from_parts(fnt, pages))
+    }
+
+    /// Loads a BMFont from the content of its text file and the encoded page
+    /// images (e.g. PNG), in page order. Useful to embed the font in the
+    /// executable with `include_str!` and `include_bytes!`, e.g. on the web.
+    pub fn from_memory(fnt: &str, pages: &[&[u8]]) -> Result<BitmapFont, Error> {
+        let fnt = parse_fnt(fnt).map_err(|message| Error::FontLoad {
+            path: None,
+            message,
+        })?;
+        if pages.len() < fnt.page_files.len() {
+            return Err(Error::FontLoad {
+                path: None,
+                message: format!(
+                    "the font has {} pages but {} images were given",
+                    fnt.page_files.len(),
+                    pages.len()
+                ),
+            });
+        }
+        let pages = pages
+            .iter()
+            .map(|data| {
+                image::load_from_memory(data)
+                    .map_err(|source| Error::TextureLoad { path: None, source })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::from_parts(fnt, pages))
+    }
+
+    /// Marks the atlas as holding signed distance fields (single- or
+    /// multi-channel), with the glyph edge at the middle value, for fonts whose
+    /// file doesn't say so.
+    pub fn with_distance_field(mut self, distance_field: bool) -> Self {
+        self.distance_field = distance_field;
+        self
+    }
+
+    /// Whether the atlas holds signed distance fields.
+    pub fn is_distance_field(&self) -> bool {
+        self.distance_field
+    }
+
+    /// The size the glyphs were rendered at, in pixels: the text scale at which
+    /// the font is drawn pixel for pixel.
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+
+    /// The distance between two lines at the native size, in pixels.
+    pub fn line_height(&self) -> f32 {
+        self.line_height
+    }
+
+    /// The distance from the top of a line to its baseline at the native size,
+    /// in pixels.
+    pub fn base(&self) -> f32 {
+        self.base
+    }
+
+    /// Whether the font has a glyph for `c`.
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.glyphs.contains_key(&c)
+    }
+
+    pub(super) fn glyph(&self, c: char) -> Option<&BitmapGlyph> {
+        self.glyphs.get(&c)
+    }
+
+    pub(super) fn kerning(&self, first: char, second: char) -> f32 {
+        self.kernings.get(&(first, second)).copied().unwrap_or(0.0)
+    }
+
+    pub(super) fn page(&self, i: usize) -> &wgpu::TextureView {
+        &self.pages[i]
+    }
+
+    fn from_parts(fnt: FntFile, images: Vec<DynamicImage>) -> BitmapFont {
+        let ctxt = Context::get();
+        let pages = images
+            .into_iter()
+            .enumerate()
+            .map(|(i, image)| {
+                let has_alpha = image.color().has_alpha();
+                let mut rgba = image.to_rgba8();
+                if let Some(channel) = fnt.page_channel(i) {
+                    // Glyphs packed in a single channel: the shaders read the
+                    // coverage from the alpha and the distance from the colors.
+                    for pixel in rgba.pixels_mut() {
+                        let value = pixel[channel];
+                        pixel.0 = if fnt.distance_field {
+                            [value, value, value, 255]
+                        } else {
+                            [255, 255, 255, value]
+                        };
+                    }
+                } else if !has_alpha && !fnt.distance_field {
+                    // Atlases without alpha hold the coverage as brightness.
+                    for pixel in rgba.pixels_mut() {
+                        let coverage = pixel[0].max(pixel[1]).max(pixel[2]);
+                        pixel.0 = [255, 255, 255, coverage];
+                    }
+                }
+                let (width, height) = rgba.dimensions();
+                let texture = ctxt.create_texture(&wgpu::TextureDescriptor {
+                    label: Some(&format!("bitmap_font_page_{}", i)),
+                    size: wgpu::Extent3d {
+                        width,
+                        height,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: wgpu::TextureFormat::Rgba8Unorm,
+                    usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                    view_formats: &[],
+                });
+                ctxt.write_texture(
+                    wgpu::TexelCopyTextureInfo {
+                        texture: &texture,
+                        mip_level: 0,
+                        origin: wgpu::Origin3d::ZERO,
+                        aspect: wgpu::TextureAspect::All,
+                    },
+                    &rgba,
+                    wgpu::TexelCopyBufferLayout {
+                        offset: 0,
+                        bytes_per_row: Some(4 * width),
+                        rows_per_image: Some(height),
+                    },
+                    wgpu::Extent3d {
+                        width,
+                        height,
+                        depth_or_array_layers: 1,
+                    },
+                );
+                let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+                (view, Vec2::new(width as f32, height as f32))
+            })
+            .collect::<Vec<_>>();
+
+        let glyphs = fnt
+            .chars
+            .iter()
+            .filter(|c| c.page < pages.len())
+            .map(|c| {
+                let atlas = pages[c.page].1;
+                let min = c.pos / atlas;
+                let max = (c.pos + c.size) / atlas;
+                let glyph = BitmapGlyph {
+                    uv_min: min.to_array(),
+                    uv_max: max.to_array(),
+                    size: c.size,
+                    offset: c.offset,
+                    advance: c.advance,
+                    page: c.page,
+                };
+                (c.id, glyph)
+            })
+            .collect();
+
+        BitmapFont {
+            size: fnt.size,
+            line_height: fnt.line_height,
+            base: fnt.base,
+            glyphs,
+            kernings: fnt.kernings,
+            pages: pages.into_iter().map(|(view, _)| view).collect(),
+            distance_field: fnt.distance_field,
+        }
+    }
+}
+
+/// The content of a BMFont text file.
+struct FntFile {
+    size: f32,
+    line_height: f32,
+    base: f32,
+    page_files: Vec<String>,
+    chars: Vec<FntChar>,
+    kernings: HashMap<(char, char), f32>,
+    distance_field: bool,
+}
+
+impl FntFile {
+    /// The RGBA index of the channel holding all the glyphs of the page `page`,
+    /// if they all use the same single channel (their `chnl` attribute).
+    fn page_channel(&self, page: usize) -> Option<usize> {
+        let mut chars = self.chars.iter().filter(|c| c.page == page);
+        let channels = chars.next()?.channels;
+        if chars.any(|c| c.channels != channels) {
+            return None;
+        }
+        // `chnl` bits: 1 for blue, 2 for green, 4 for red and 8 for alpha.
+        match channels {
+            1 => Some(2),
+            2 => Some(1),
+            4 => Some(0),
+            8 => Some(3),
+            _ => None,
+        }
+    }
+}
+
+struct FntChar {
+    id: char,
+    pos: Vec2,
+    size: Vec2,
+    offset: Vec2,
+    advance: f32,
+    page: usize,
+    channels: u8,
+}
+
+/// Parses the BMFont text format: one tag per line followed by `key=value`
+/// attributes, string values being quoted.
+fn parse_fnt(source: &str) -> Result<FntFile, String> {
+    let mut fnt = FntFile {
+        size: 0.0,
+        line_height: 0.0,
+        base: 0.0,
+        page_files: Vec::new(),
+        chars: Vec::new(),
+        kernings: HashMap::new(),
+        distance_field: false,
+    };
+    let mut has_common = false;
+
+    for (line_number, line) in source.lines().enumerate() {
+        let (tag, attributes) = split_fnt_line(line);
+        let number = |key: &str| -> Result<f32, String> {
+            attributes
+                .iter()
+                .find(|(k, _)| *k == key)
+                .and_then(|(_, v)| v.parse().ok())
+                .ok_or_else(|| format!("line {}: missing or invalid `{}`", line_number + 1, key))
+        };
+        let string = |key: &str| attributes.iter().find(|(k, _)| *k == key).map(|(_, v)| v);
+        let char_id = |key: &str| -> Result<char, String> {
+            char::from_u32(number(key)? as u32)
+                .ok_or_else(|| format!("line {}: invalid character", line_number + 1))
+        };
+
+        match tag {
+            // Some generators write a negative size for "match char height".
+            "info" => fnt.size = number("size")?.abs(),
+            "common" => {
+                fnt.line_height = number("lineHeight")?;
+                fnt.base = number("base")?;
+                has_common = true;
+            }
+            "page" => {
+                let id = number("id")? as usize;
+                let file = string("file")
+                    .ok_or_else(|| format!("line {}: missing `file`", line_number + 1))?;
+                if fnt.page_files.len() <= id {
+                    fnt.page_files.resize(id + 1, String::new());
+                }
+                fnt.page_files[id] = file.to_string();
+            }
+            "char" => fnt.chars.push(FntChar {
+                id: char_id("id")?,
+                pos: Vec2::new(number("x")?, number("y")?),
+                size: Vec2::new(number("width")?, number("height")?),
+                offset: Vec2::new(number("xoffset")?, number("yoffset")?),
+                advance: number("xadvance")?,
+                page: number("page").unwrap_or(0.0) as usize,
+                channels: number("chnl").unwrap_or(15.0) as u8,
+            }),
+            "kerning" => {
+                let _ = fnt
+                    .kernings
+                    .insert((char_id("first")?, char_id("second")?), number("amount")?);
+            }
+            "distanceField" => fnt.distance_field = true,
+            _ => {}
+        }
+    }
+
+    if !has_common {
+        return Err("not a BMFont text file (no `common` line)".to_string());
+    }
+    if fnt.size == 0.0 {
+        fnt.size = fnt.line_height;
+    }
+    Ok(fnt)
+}
+
+/// Splits a line of a BMFont text file into its tag and attributes.
+fn split_fnt_line(line: &str) -> (&str, Vec<(&str, &str)>) {
+    let line = line.trim();
+    let (tag, mut rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let mut attributes = Vec::new();
+
+    while let Some((key, value)) = rest.trim_start().split_once('=') {
+        let (value, remainder) = match value.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+            None => value.split_once(char::is_whitespace).unwrap_or((value, "")),
+        };
+        attributes.push((key.trim(), value));
+        rest = remainder;
+    }
+
+    (tag, attributes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_fnt_line_keeps_quoted_values_whole() {
+        let (tag, attributes) =
+            split_fnt_line(r#"info face="Open Sans" size=-32 padding=1,2,3,4 charset="""#);
+        assert_eq!(tag, "info");
+        assert_eq!(
+            attributes,
+            vec![
+                ("face", "Open Sans"),
+                ("size", "-32"),
+                ("padding", "1,2,3,4"),
+                ("charset", ""),
+            ]
+        );
+        assert_eq!(split_fnt_line("  common  "), ("common", Vec::new()));
+    }
+
+    #[test]
+    fn parse_fnt_reads_multi_page_fonts_and_kernings() {
+        let fnt = parse_fnt(
+            r#"info face="Pixel" size=-16
+common lineHeight=18 base=14 scaleW=64 scaleH=64 pages=2
+page id=1 file="pixel 1.png"
+page id=0 file="pixel 0.png"
+chars count=2
+char id=65 x=1 y=2 width=8 height=10 xoffset=-1 yoffset=3 xadvance=9 page=0 chnl=15
+char id=86   x=3 y=4 width=7 height=10 xoffset=0 yoffset=3 xadvance=8 page=1 chnl=15
+kernings count=1
+kerning first=65 second=86 amount=-2
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(fnt.size, 16.0);
+        assert_eq!(fnt.line_height, 18.0);
+        assert_eq!(fnt.base, 14.0);
+        assert_eq!(fnt.page_files, vec!["pixel 0.png", "pixel 1.png"]);
+        assert!(!fnt.distance_field);
+
+        let v = &fnt.chars[1];
+        assert_eq!((v.id, v.page, v.channels), ('V', 1, 15));
+        assert_eq!(v.pos, Vec2::new(3.0, 4.0));
+        assert_eq!(v.size, Vec2::new(7.0, 10.0));
+        assert_eq!(v.offset, Vec2::new(0.0, 3.0));
+        assert_eq!(v.advance, 8.0);
+        assert_eq!(fnt.chars[0].offset, Vec2::new(-1.0, 3.0));
+
+        assert_eq!(fnt.kernings.len(), 1);
+        assert_eq!(fnt.kernings[&('A', 'V')], -2.0);
+    }
+
+    #[test]
+    fn parse_fnt_rejects_other_files() {
+        assert!(parse_fnt("hello world").is_err());
+        assert!(parse_fnt("common lineHeight=18\n").is_err());
+    }
+
+    #[test]
+    fn single_channel_pages_are_detected() {
+        let fnt = parse_fnt(
+            "common lineHeight=18 base=14
+char id=65 x=0 y=0 width=1 height=1 xoffset=0 yoffset=0 xadvance=1 page=0 chnl=8
+char id=66 x=0 y=0 width=1 height=1 xoffset=0 yoffset=0 xadvance=1 page=0 chnl=8
+char id=67 x=0 y=0 width=1 height=1 xoffset=0 yoffset=0 xadvance=1 page=1 chnl=4
+char id=68 x=0 y=0 width=1 height=1 xoffset=0 yoffset=0 xadvance=1 page=1 chnl=1
+char id=69 x=0 y=0 width=1 height=1 xoffset=0 yoffset=0 xadvance=1 page=2
+distanceField fieldType=sdf distanceRange=4
+",
+        )
+        .unwrap();
+
+        assert!(fnt.distance_field);
+        assert_eq!(fnt.page_channel(0), Some(3));
+        // Glyphs packed in different channels of the same page.
+        assert_eq!(fnt.page_channel(1), None);
+        assert_eq!(fnt.page_channel(2), None);
+        assert_eq!(fnt.page_channel(3), None);
+    }
+}
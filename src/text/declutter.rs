@@ -0,0 +1,154 @@
+//! Placement of the 3D labels so they don't overlap.
+
+use glamx::Vec2;
+
+use crate::color::Color;
+
+/// How the labels drawn with
+/// [`Window::draw_text_3d`](crate::window::Window::draw_text_3d) are moved
+/// apart when they overlap on screen, enabled with
+/// [`Window::set_label_declutter`](crate::window::Window::set_label_declutter).
+///
+/// The labels are placed greedily, nearest to the camera first: a label that
+/// overlaps an already placed one is moved to the closest free spot around its
+/// anchor (above first, then below, then to the sides), and a leader line
+/// joins it to its anchor. Labels without a free spot within
+/// [`max_displacement`](Self::max_displacement) are hidden for the frame.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LabelDeclutter {
+    /// The minimal gap between two labels, in pixels (default: `2.0`).
+    pub padding: f32,
+    /// The farthest a label is moved from its position, in pixels (default:
+    /// `120.0`).
+    pub max_displacement: f32,
+    /// The color of the leader lines of the moved labels, or `None` to not
+    /// draw them (default: translucent white).
+    pub leader_color: Option<Color>,
+    /// The width of the leader lines, in pixels (default: `1.0`).
+    pub leader_width: f32,
+}
+
+impl Default for LabelDeclutter {
+    fn default() -> Self {
+        LabelDeclutter {
+            padding: 2.0,
+            max_displacement: 120.0,
+            leader_color: Some(Color::new(1.0, 1.0, 1.0, 0.6)),
+            leader_width: 1.0,
+        }
+    }
+}
+
+impl LabelDeclutter {
+    /// Places the labels of pixel-space bounds `rects` (`(min, size)`) in the
+    /// given priority `order`, returning the offset of each label, or `None` for
+    /// the labels that are hidden.
+    pub(super) fn place(&self, rects: &[(Vec2, Vec2)], order: &[usize]) -> Vec<Option<Vec2>> {
+        let mut offsets = vec![None; rects.len()];
+        let mut placed: Vec<(Vec2, Vec2)> = Vec::with_capacity(rects.len());
+
+        for &i in order {
+            let (min, size) = rects[i];
+            let is_free = |offset: Vec2| {
+                let a_min = min + offset;
+                let a_max = a_min + size;
+                placed.iter().all(|(b_min, b_max)| {
+                    a_max.x + self.padding <= b_min.x
+                        || b_max.x + self.padding <= a_min.x
+                        || a_max.y + self.padding <= b_min.y
+                        || b_max.y + self.padding <= a_min.y
+                })
+            };
+
+            let step = Vec2::new(size.x / 2.0, size.y) + Vec2::splat(self.padding);
+            let offset = std::iter::once(Vec2::ZERO)
+                .chain(candidate_offsets(step, self.max_displacement))
+                .find(|offset| is_free(*offset));
+
+            if let Some(offset) = offset {
+                placed.push((min + offset, min + offset + size));
+                offsets[i] = Some(offset);
+            }
+        }
+
+        offsets
+    }
+}
+
+/// The offsets tried, ring after ring of `step`s around the original position,
+/// within `max_displacement`.
+fn candidate_offsets(step: Vec2, max_displacement: f32) -> impl Iterator<Item = Vec2> {
+    // Up, down, then the sides (screen space has y pointing down).
+    const DIRECTIONS: [(f32, f32); 8] = [
+        (0.0, -1.0),
+        (0.0, 1.0),
+        (1.0, -1.0),
+        (-1.0, -1.0),
+        (1.0, 0.0),
+        (-1.0, 0.0),
+        (1.0, 1.0),
+        (-1.0, 1.0),
+    ];
+    let num_rings = if step.min_element() > 0.0 {
+        (max_displacement / step.min_element()).floor() as usize
+    } else {
+        0
+    };
+
+    (1..=num_rings)
+        .flat_map(move |ring| {
+            DIRECTIONS
+                .iter()
+                .map(move |(x, y)| Vec2::new(*x, *y) * step * ring as f32)
+        })
+        .filter(move |offset| offset.length() <= max_displacement)
+}
+
+/// The leader line from `anchor` to the closest point of the label bounds
+/// `(min, max)`.
+pub(super) fn leader_line(anchor: Vec2, min: Vec2, max: Vec2) -> (Vec2, Vec2) {
+    (anchor, anchor.clamp(min, max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: Vec2 = Vec2::new(40.0, 10.0);
+
+    #[test]
+    fn separate_labels_stay_in_place() {
+        let declutter = LabelDeclutter::default();
+        let rects = [(Vec2::ZERO, SIZE), (Vec2::new(100.0, 0.0), SIZE)];
+        assert_eq!(
+            declutter.place(&rects, &[0, 1]),
+            vec![Some(Vec2::ZERO), Some(Vec2::ZERO)]
+        );
+    }
+
+    #[test]
+    fn overlapping_label_moves_up() {
+        let declutter = LabelDeclutter::default();
+        let rects = [(Vec2::ZERO, SIZE), (Vec2::new(5.0, 3.0), SIZE)];
+        // The first label in priority order keeps its position.
+        let offsets = declutter.place(&rects, &[1, 0]);
+        assert_eq!(offsets[1], Some(Vec2::ZERO));
+        assert_eq!(
+            offsets[0],
+            Some(Vec2::new(0.0, -(SIZE.y + declutter.padding)))
+        );
+    }
+
+    #[test]
+    fn label_without_free_spot_is_hidden() {
+        let declutter = LabelDeclutter {
+            max_displacement: 5.0,
+            ..LabelDeclutter::default()
+        };
+        let rects = [(Vec2::ZERO, SIZE), (Vec2::ZERO, SIZE)];
+        assert_eq!(
+            declutter.place(&rects, &[0, 1]),
+            vec![Some(Vec2::ZERO), None]
+        );
+    }
+}
@@ -0,0 +1,154 @@
+//! Text labels whose layout stays on the GPU between frames.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use glamx::Vec2;
+
+use crate::color::{Color, WHITE};
+use crate::text::renderer::Spacing;
+use crate::text::Font;
+
+/// The font, size and color of a piece of text.
+#[derive(Clone)]
+pub struct TextStyle {
+    /// The text scale, i.e., its line height in pixels.
+    pub scale: f32,
+    /// The font of the text.
+    pub font: Arc<Font>,
+    /// The color of the text.
+    pub color: Color,
+}
+
+impl TextStyle {
+    /// A style with the given scale, font and color.
+    pub fn new(scale: f32, font: &Arc<Font>, color: Color) -> Self {
+        TextStyle {
+            scale,
+            font: font.clone(),
+            color,
+        }
+    }
+}
+
+impl Default for TextStyle {
+    /// White text with the default font, at scale `40.0`.
+    fn default() -> Self {
+        TextStyle::new(40.0, &Font::default(), WHITE)
+    }
+}
+
+/// A text label created by
+/// [`TextRenderer::create_label`](crate::text::TextRenderer::create_label),
+/// drawn every frame until all its handles are dropped.
+///
+/// The label is laid out once and its glyph quads kept in a GPU buffer, so
+/// moving it only updates a few bytes. It is laid out again only when its text
+/// or style changes. Cloning the handle gives another handle to the same label.
+#[derive(Clone)]
+pub struct LabelHandle {
+    pub(super) state: Rc<RefCell<CachedLabel>>,
+}
+
+pub(super) struct CachedLabel {
+    pub(super) text: String,
+    pub(super) style: TextStyle,
+    pub(super) position: Vec2,
+    pub(super) visible: bool,
+    /// Whether the text or style changed since the label was last laid out.
+    pub(super) dirty: bool,
+    pub(super) gpu: Option<LabelBuffers>,
+}
+
+/// The GPU side of a laid out label.
+pub(super) struct LabelBuffers {
+    pub(super) vertex_buffer: wgpu::Buffer,
+    pub(super) vertex_count: u32,
+    /// The text size multiplier the label was laid out with.
+    pub(super) size_scale: f32,
+    /// The spacing the label was laid out with.
+    pub(super) spacing: Spacing,
+    /// The size of the label's layout box, in pixels.
+    pub(super) size: Vec2,
+    pub(super) uniform_buffer: wgpu::Buffer,
+    pub(super) uniform_bind_group: wgpu::BindGroup,
+    /// The uniforms last written to `uniform_buffer`.
+    pub(super) uniforms: [f32; 4],
+}
+
+impl LabelHandle {
+    pub(super) fn new(text: &str, style: &TextStyle) -> Self {
+        LabelHandle {
+            state: Rc::new(RefCell::new(CachedLabel {
+                text: text.to_string(),
+                style: style.clone(),
+                position: Vec2::ZERO,
+                visible: true,
+                dirty: true,
+                gpu: None,
+            })),
+        }
+    }
+
+    /// The position of the top-left corner of the label, in pixels from the
+    /// top-left corner of the window.
+    pub fn position(&self) -> Vec2 {
+        self.state.borrow().position
+    }
+
+    /// Moves the top-left corner of the label to `pos`, in pixels from the
+    /// top-left corner of the window. This does not lay the label out again.
+    pub fn set_position(&self, pos: Vec2) {
+        self.state.borrow_mut().position = pos;
+    }
+
+    /// The text of the label.
+    pub fn text(&self) -> String {
+        self.state.borrow().text.clone()
+    }
+
+    /// Changes the text of the label, laying it out again if it differs.
+    ///
+    /// For text changing every frame, like a frame counter, drawing it with
+    /// [`TextRenderer::draw_text_sdf`](crate::text::TextRenderer::draw_text_sdf)
+    /// is just as fast.
+    pub fn set_text(&self, text: &str) {
+        let mut state = self.state.borrow_mut();
+        if state.text != text {
+            state.text = text.to_string();
+            state.dirty = true;
+        }
+    }
+
+    /// The style of the label.
+    pub fn style(&self) -> TextStyle {
+        self.state.borrow().style.clone()
+    }
+
+    /// Changes the style of the label, laying it out again.
+    pub fn set_style(&self, style: &TextStyle) {
+        let mut state = self.state.borrow_mut();
+        state.style = style.clone();
+        state.dirty = true;
+    }
+
+    /// Changes the color of the label.
+    pub fn set_color(&self, color: Color) {
+        let mut state = self.state.borrow_mut();
+        if state.style.color != color {
+            state.style.color = color;
+            state.dirty = true;
+        }
+    }
+
+    /// Whether the label is drawn.
+    pub fn is_visible(&self) -> bool {
+        self.state.borrow().visible
+    }
+
+    /// Shows or hides the label, keeping its layout.
+    pub fn set_visible(&self, visible: bool) {
+        self.state.borrow_mut().visible = visible;
+    }
+}
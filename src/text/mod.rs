@@ -1,9 +1,16 @@
 //! Text rendering.
 
+pub use crate::text::bitmap_font::BitmapFont;
+pub use crate::text::declutter::LabelDeclutter;
 pub use crate::text::font::Font;
 pub use crate::text::glyph::Glyph;
-pub use crate::text::renderer::TextRenderer;
+pub use crate::text::label::{LabelHandle, TextStyle};
+pub use crate::text::renderer::{TextBounds, TextRenderer};
 
+mod bitmap_font;
+mod declutter;
 mod font;
 mod glyph;
+mod label;
 mod renderer;
+mod sdf;
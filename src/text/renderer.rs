@@ -2,14 +2,21 @@
 // available under the BSD-3 licence.
 // It has been modified to work with wgpu, nalgebra, and rusttype
 
+use crate::camera::Camera3d;
 use crate::color::Color;
 use crate::context::Context;
 use crate::resource::RenderContext2dEncoder;
+use crate::text::bitmap_font::{BitmapFont, BitmapGlyph};
+use crate::text::declutter::{leader_line, LabelDeclutter};
+use crate::text::label::{LabelBuffers, LabelHandle, TextStyle};
+use crate::text::sdf::{SdfGlyphCache, SDF_BASE_SIZE};
 use crate::text::Font;
 use bytemuck::{Pod, Zeroable};
-use glamx::Vec2;
+use glamx::{Vec2, Vec3};
 use rusttype;
 use rusttype::gpu_cache::Cache;
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::sync::Arc;
 
 /// Vertex data for a text quad.
@@ -26,7 +33,8 @@ struct TextVertex {
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
 struct TextUniforms {
     inv_size: [f32; 2],
-    _padding: [f32; 2],
+    /// Translation, in pixels, of the drawn vertices.
+    offset: [f32; 2],
 }
 
 struct TextRenderContext {
@@ -37,6 +45,88 @@ struct TextRenderContext {
     font: Arc<Font>,
 }
 
+struct BitmapTextDraw {
+    text: String,
+    pos: Vec2,
+    scale: f32,
+    color: [f32; 4],
+    font: Arc<BitmapFont>,
+}
+
+/// Consecutive bitmap-font glyph quads sharing an atlas page.
+struct BitmapFontBatch {
+    font: Arc<BitmapFont>,
+    page: usize,
+    vertices: std::ops::Range<u32>,
+}
+
+struct TextLabel3d {
+    text: String,
+    pos: Vec3,
+    height: f32,
+    color: Color,
+    font: Arc<Font>,
+}
+
+/// A 3D label projected to the screen, in pixels from the top-left corner.
+struct ProjectedLabel {
+    label: TextLabel3d,
+    /// The projection of the anchor point.
+    anchor: Vec2,
+    /// The top-left corner of the layout.
+    pos: Vec2,
+    size: Vec2,
+    line_height: f32,
+    /// The distance to the camera along its view direction.
+    depth: f32,
+}
+
+/// The spacing settings of the text layout.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(super) struct Spacing {
+    /// Extra space after each glyph, as a fraction of the text scale.
+    letter: f32,
+    /// Multiplier of the distance between consecutive baselines.
+    line: f32,
+    /// Whether the kerning pairs of the font are applied.
+    kerning: bool,
+}
+
+impl Default for Spacing {
+    fn default() -> Self {
+        Spacing {
+            letter: 0.0,
+            line: 1.0,
+            kerning: true,
+        }
+    }
+}
+
+/// Pixel-space rectangle covered by a piece of drawn text, from the top-left
+/// corner of the window.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TextBounds {
+    /// The top-left corner of the text's layout box.
+    pub min: Vec2,
+    /// The bottom-right corner of the text's layout box.
+    pub max: Vec2,
+}
+
+impl TextBounds {
+    /// The size of the rectangle.
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    /// Whether `point` lies inside the rectangle.
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.x >= self.min.x
+            && point.y >= self.min.y
+            && point.x <= self.max.x
+            && point.y <= self.max.y
+    }
+}
+
 /// A ttf text renderer.
 pub struct TextRenderer {
     text: String,
@@ -52,6 +142,35 @@ pub struct TextRenderer {
     vertex_capacity: usize,
     contexts: Vec<TextRenderContext>,
     vertices: Vec<TextVertex>,
+    sdf_text: String,
+    sdf_cache: SdfGlyphCache,
+    sdf_texture: wgpu::Texture,
+    sdf_texture_view: wgpu::TextureView,
+    sdf_pipeline: wgpu::RenderPipeline,
+    sdf_vertex_buffer: wgpu::Buffer,
+    sdf_vertex_capacity: usize,
+    sdf_contexts: Vec<TextRenderContext>,
+    sdf_vertices: Vec<TextVertex>,
+    bmfont_pipeline: wgpu::RenderPipeline,
+    bmfont_distance_field_pipeline: wgpu::RenderPipeline,
+    /// Samples plain bitmap-font atlases, drawn pixel for pixel at their size.
+    nearest_sampler: wgpu::Sampler,
+    bmfont_vertex_buffer: wgpu::Buffer,
+    bmfont_vertex_capacity: usize,
+    bmfont_draws: Vec<BitmapTextDraw>,
+    bmfont_vertices: Vec<TextVertex>,
+    bmfont_batches: Vec<BitmapFontBatch>,
+    labels_3d: Vec<TextLabel3d>,
+    label_declutter: Option<LabelDeclutter>,
+    /// The labels created by `create_label`, with their cached layout.
+    labels: Vec<Rc<RefCell<crate::text::label::CachedLabel>>>,
+    /// Bounds of the screen-space text drawn for the upcoming frame, in call order.
+    bounds: Vec<TextBounds>,
+    /// Bounds of the text drawn during the last rendered frame.
+    last_bounds: Vec<TextBounds>,
+    /// Multiplier applied to the scale of screen-space text (the window's UI scale).
+    size_scale: f32,
+    spacing: Spacing,
     #[allow(dead_code)]
     atlas_width: u32,
     #[allow(dead_code)]
@@ -98,6 +217,26 @@ impl TextRenderer {
 
         let glyph_texture_view = glyph_texture.create_view(&wgpu::TextureViewDescriptor::default());
 
+        //
+        // Create the signed-distance-field atlas (single channel R8).
+        //
+        let sdf_cache = SdfGlyphCache::new(atlas_width, atlas_height);
+        let sdf_texture = ctxt.create_texture(&wgpu::TextureDescriptor {
+            label: Some("text_renderer_sdf_texture"),
+            size: wgpu::Extent3d {
+                width: atlas_width,
+                height: atlas_height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::R8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let sdf_texture_view = sdf_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
         let glyph_sampler = ctxt.create_sampler(&wgpu::SamplerDescriptor {
             label: Some("text_renderer_glyph_sampler"),
             address_mode_u: wgpu::AddressMode::ClampToEdge,
@@ -108,6 +247,16 @@ impl TextRenderer {
             mipmap_filter: wgpu::MipmapFilterMode::Nearest,
             ..Default::default()
         });
+        let nearest_sampler = ctxt.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("text_renderer_nearest_sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::MipmapFilterMode::Nearest,
+            ..Default::default()
+        });
 
         //
         // Create bind group layouts.
@@ -190,43 +339,65 @@ impl TextRenderer {
             ],
         };
 
-        let pipeline = ctxt.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("text_renderer_pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: Some("vs_main"),
-                buffers: &[vertex_buffer_layout],
-                compilation_options: Default::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: ctxt.surface_format,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: Default::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: None,
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: None, // Text rendering doesn't use depth
-            multisample: wgpu::MultisampleState {
-                count: 1,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
-            multiview_mask: None,
-            cache: None,
-        });
+        let create_pipeline = |label: &str, shader: &wgpu::ShaderModule, fs_entry: &str| {
+            ctxt.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: shader,
+                    entry_point: Some("vs_main"),
+                    buffers: std::slice::from_ref(&vertex_buffer_layout),
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: shader,
+                    entry_point: Some(fs_entry),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: ctxt.surface_format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: None,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                depth_stencil: None, // Text rendering doesn't use depth
+                multisample: wgpu::MultisampleState {
+                    count: 1,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview_mask: None,
+                cache: None,
+            })
+        };
+
+        let pipeline = create_pipeline("text_renderer_pipeline", &shader, "fs_main");
+
+        let sdf_shader = ctxt.create_shader_module(
+            Some("text_renderer_sdf_shader"),
+            include_str!("../builtin/text_sdf.wgsl"),
+        );
+        let sdf_pipeline = create_pipeline("text_renderer_sdf_pipeline", &sdf_shader, "fs_main");
+
+        let bmfont_shader = ctxt.create_shader_module(
+            Some("text_renderer_bmfont_shader"),
+            include_str!("../builtin/text_bitmap_font.wgsl"),
+        );
+        let bmfont_pipeline =
+            create_pipeline("text_renderer_bmfont_pipeline", &bmfont_shader, "fs_main");
+        let bmfont_distance_field_pipeline = create_pipeline(
+            "text_renderer_bmfont_distance_field_pipeline",
+            &bmfont_shader,
+            "fs_distance_field",
+        );
 
         // Create uniform buffer
         let uniform_buffer = ctxt.create_buffer(&wgpu::BufferDescriptor {
@@ -244,6 +415,19 @@ impl TextRenderer {
             usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
+        let sdf_vertex_buffer = ctxt.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("text_renderer_sdf_vertex_buffer"),
+            size: (std::mem::size_of::<TextVertex>() * vertex_capacity) as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bmfont_vertex_buffer = ctxt.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("text_renderer_bmfont_vertex_buffer"),
+            size: (std::mem::size_of::<TextVertex>() * vertex_capacity) as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
 
         TextRenderer {
             text: String::new(),
@@ -259,15 +443,90 @@ impl TextRenderer {
             vertex_capacity,
             contexts: Vec::new(),
             vertices: Vec::new(),
+            sdf_text: String::new(),
+            sdf_cache,
+            sdf_texture,
+            sdf_texture_view,
+            sdf_pipeline,
+            sdf_vertex_buffer,
+            sdf_vertex_capacity: vertex_capacity,
+            sdf_contexts: Vec::new(),
+            size_scale: 1.0,
+            spacing: Spacing::default(),
+            sdf_vertices: Vec::new(),
+            bmfont_pipeline,
+            bmfont_distance_field_pipeline,
+            nearest_sampler,
+            bmfont_vertex_buffer,
+            bmfont_vertex_capacity: vertex_capacity,
+            bmfont_draws: Vec::new(),
+            bmfont_vertices: Vec::new(),
+            bmfont_batches: Vec::new(),
+            labels_3d: Vec::new(),
+            label_declutter: None,
+            labels: Vec::new(),
+            bounds: Vec::new(),
+            last_bounds: Vec::new(),
             atlas_width,
             atlas_height,
         }
     }
 
+    /// Multiplier applied to the scale of screen-space text (3D labels,
+    /// sized in world units, are unaffected) of everything drawn from now on
+    /// (`1.0` by default). The window sets it to its UI scale (see
+    /// [`Window::set_ui_scale`](crate::window::Window::set_ui_scale)).
+    pub fn set_size_scale(&mut self, scale: f32) {
+        self.size_scale = scale;
+    }
+
+    /// The multiplier applied to the scale of screen-space text (3D labels,
+    /// sized in world units, are unaffected) of drawn items.
+    pub fn size_scale(&self) -> f32 {
+        self.size_scale
+    }
+
+    /// Sets the extra space added after each character, as a fraction of the
+    /// text scale (`0.0` by default). Negative values tighten the text.
+    pub fn set_letter_spacing(&mut self, spacing: f32) {
+        self.spacing.letter = spacing;
+    }
+
+    /// The extra space added after each character, as a fraction of the text
+    /// scale.
+    pub fn letter_spacing(&self) -> f32 {
+        self.spacing.letter
+    }
+
+    /// Sets the distance between the lines of multi-line text, as a multiple of
+    /// the font's line height (`1.0` by default).
+    pub fn set_line_spacing(&mut self, spacing: f32) {
+        self.spacing.line = spacing;
+    }
+
+    /// The distance between the lines of multi-line text, as a multiple of the
+    /// font's line height.
+    pub fn line_spacing(&self) -> f32 {
+        self.spacing.line
+    }
+
+    /// Enables or disables the kerning pairs of the fonts, which adjust the
+    /// space between specific characters like in "AV" (enabled by default).
+    pub fn set_kerning(&mut self, enabled: bool) {
+        self.spacing.kerning = enabled;
+    }
+
+    /// Whether the kerning pairs of the fonts are applied.
+    pub fn kerning(&self) -> bool {
+        self.spacing.kerning
+    }
+
     /// Adds a piece of text to be drawn during the next frame. The text is not persistent between
     /// frames. This method must be called for each text to draw, and at each update loop
     /// iteration.
     pub fn draw_text(&mut self, text: &str, pos: Vec2, scale: f32, font: &Arc<Font>, color: Color) {
+        let scale = scale * self.size_scale;
+        self.push_bounds(text, pos, scale, font);
         self.text.push_str(text);
         self.contexts.push(TextRenderContext {
             len: text.len(),
@@ -278,14 +537,500 @@ impl TextRenderer {
         })
     }
 
+    /// Adds a piece of text to be drawn during the next frame using the
+    /// signed-distance-field glyph atlas.
+    ///
+    /// Unlike [`Self::draw_text`], glyphs are not rasterized at `scale`: a single
+    /// distance field per glyph is reused at every size, so the text stays sharp
+    /// when it is large, animated in size, or very small. Like `draw_text`, the
+    /// text is not persistent between frames.
+    pub fn draw_text_sdf(
+        &mut self,
+        text: &str,
+        pos: Vec2,
+        scale: f32,
+        font: &Arc<Font>,
+        color: Color,
+    ) {
+        self.push_sdf_text(text, pos, scale * self.size_scale, font, color)
+    }
+
+    /// Same as [`Self::draw_text_sdf`] with a `scale` already in pixels, i.e.
+    /// not multiplied by the [size scale](Self::set_size_scale).
+    fn push_sdf_text(&mut self, text: &str, pos: Vec2, scale: f32, font: &Arc<Font>, color: Color) {
+        self.push_bounds(text, pos, scale, font);
+        self.sdf_text.push_str(text);
+        self.sdf_contexts.push(TextRenderContext {
+            len: text.len(),
+            scale,
+            color: [color.r, color.g, color.b, color.a],
+            pos,
+            font: font.clone(),
+        })
+    }
+
+    /// Adds a piece of text to be drawn during the next frame with a
+    /// pre-rendered [`BitmapFont`].
+    ///
+    /// `scale` is relative to the [size](BitmapFont::size) the font was rendered
+    /// at: drawing at that size, at a whole-pixel position, maps each atlas
+    /// pixel to a screen pixel. Characters missing from the font are skipped.
+    /// Like [`Self::draw_text`], the text is not persistent between frames.
+    pub fn draw_text_bitmap(
+        &mut self,
+        text: &str,
+        pos: Vec2,
+        scale: f32,
+        font: &Arc<BitmapFont>,
+        color: Color,
+    ) {
+        let size = self.measure_text_bitmap(text, scale, font);
+        self.bounds.push(TextBounds {
+            min: pos,
+            max: pos + size,
+        });
+        self.bmfont_draws.push(BitmapTextDraw {
+            text: text.to_string(),
+            pos,
+            scale: scale * self.size_scale,
+            color: [color.r, color.g, color.b, color.a],
+            font: font.clone(),
+        })
+    }
+
+    /// The size in pixels of the layout box of `text` drawn at `scale` with the
+    /// bitmap font `font` by [`Self::draw_text_bitmap`].
+    pub fn measure_text_bitmap(&self, text: &str, scale: f32, font: &BitmapFont) -> Vec2 {
+        let ratio = scale * self.size_scale / font.size();
+        let line_height = font.line_height() * ratio;
+        let mut size = Vec2::ZERO;
+        for (i, line) in text.lines().enumerate() {
+            let width = layout_bitmap_line(font, line, 0.0, ratio, self.spacing, |_, _| {});
+            size.x = size.x.max(width);
+            size.y += if i == 0 {
+                line_height
+            } else {
+                line_height * self.spacing.line
+            };
+        }
+        size
+    }
+
+    /// The size in pixels of the layout box of `text` drawn at `scale` with `font`
+    /// (the widest line by the total height of its lines), as covered by
+    /// [`Self::draw_text`] or [`Self::draw_text_sdf`].
+    pub fn measure_text(&self, text: &str, scale: f32, font: &Arc<Font>) -> Vec2 {
+        self.measure_text_px(text, scale * self.size_scale, font)
+    }
+
+    /// Same as [`Self::measure_text`] with a `scale` already in pixels.
+    fn measure_text_px(&self, text: &str, scale: f32, font: &Arc<Font>) -> Vec2 {
+        let scale = rusttype::Scale::uniform(scale);
+        let vmetrics = font.font().v_metrics(scale);
+        let line_height = vmetrics.ascent - vmetrics.descent;
+        let mut size = Vec2::ZERO;
+        for (i, line) in text.lines().enumerate() {
+            let width = line_width(font, line, scale, self.spacing);
+            size.x = size.x.max(width);
+            size.y += if i == 0 {
+                line_height
+            } else {
+                line_height * self.spacing.line
+            };
+        }
+        size
+    }
+
+    /// The pixel-space bounds of every piece of screen-space text drawn during the
+    /// last rendered frame, in the order of the [`Self::draw_text`],
+    /// [`Self::draw_text_sdf`] and [`Self::draw_text_bitmap`] calls, followed by
+    /// the projected 3D labels, then
+    /// the visible [labels](Self::create_label).
+    ///
+    /// Useful to make text respond to clicks: test the cursor position against
+    /// them with [`TextBounds::contains`].
+    pub fn bounds_of_last_draws(&self) -> &[TextBounds] {
+        &self.last_bounds
+    }
+
+    /// Records the bounds of `text` drawn at `pos` with `scale` in pixels.
+    fn push_bounds(&mut self, text: &str, pos: Vec2, scale: f32, font: &Arc<Font>) {
+        let size = self.measure_text_px(text, scale, font);
+        self.bounds.push(TextBounds {
+            min: pos,
+            max: pos + size,
+        });
+    }
+
+    /// Adds a camera-facing text label anchored at the world-space point `pos`.
+    ///
+    /// `height` is the label's line height in world units, so the label shrinks
+    /// with distance like any other object. The label is horizontally centered
+    /// on `pos`, with its baseline at `pos`, and is drawn with the SDF atlas on
+    /// top of the 3D scene (it is not depth-tested). Labels behind the camera are
+    /// skipped. The label is not persistent between frames.
+    pub fn draw_text_3d(
+        &mut self,
+        text: &str,
+        pos: Vec3,
+        height: f32,
+        font: &Arc<Font>,
+        color: Color,
+    ) {
+        self.labels_3d.push(TextLabel3d {
+            text: text.to_string(),
+            pos,
+            height,
+            color,
+            font: font.clone(),
+        })
+    }
+
+    /// Creates a persistent screen-space label, drawn every frame at
+    /// [`LabelHandle::set_position`] (the origin by default) until all the
+    /// handles to it are dropped.
+    ///
+    /// Unlike [`Self::draw_text_sdf`], which lays out and uploads its text every
+    /// frame, the label is laid out once into its own vertex buffer, so hundreds
+    /// of static labels cost little more than their draw calls. Labels use the
+    /// signed-distance-field atlas, and are drawn on top of the immediate text.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let mut window = Window::new("Example").await;
+    /// # let mut camera = OrbitCamera3d::default();
+    /// # let mut scene = SceneNode3d::empty();
+    /// let style = TextStyle::new(24.0, &Font::default(), YELLOW);
+    /// let labels: Vec<_> = (0..500)
+    ///     .map(|i| {
+    ///         let label = window.create_label(&format!("Sensor #{}", i), &style);
+    ///         label.set_position(Vec2::new((i % 20) as f32 * 60.0, (i / 20) as f32 * 30.0));
+    ///         label
+    ///     })
+    ///     .collect();
+    ///
+    /// while window.render_3d(&mut scene, &mut camera).await {}
+    /// # }
+    /// ```
+    pub fn create_label(&mut self, text: &str, style: &TextStyle) -> LabelHandle {
+        let label = LabelHandle::new(text, style);
+        self.labels.push(label.state.clone());
+        label
+    }
+
+    /// Moves the [`Self::draw_text_3d`] labels apart when they overlap on
+    /// screen, or lets them overlap if `None` (the default).
+    pub fn set_label_declutter(&mut self, declutter: Option<LabelDeclutter>) {
+        self.label_declutter = declutter;
+    }
+
+    /// How the [`Self::draw_text_3d`] labels are moved apart when they overlap.
+    pub fn label_declutter(&self) -> Option<LabelDeclutter> {
+        self.label_declutter
+    }
+
+    /// Projects the pending 3D labels with `camera` into screen-space SDF text.
+    ///
+    /// The camera renders a `width × height` viewport whose top-left corner is
+    /// at `offset` in the text's pixel coordinates. Returns the leader lines of
+    /// the labels moved by the [declutter](Self::set_label_declutter), in pixels
+    /// from the top-left corner of that viewport. Called by the window once per
+    /// frame, before [`Self::render`].
+    pub fn project_labels(
+        &mut self,
+        camera: &dyn Camera3d,
+        offset: Vec2,
+        width: f32,
+        height: f32,
+    ) -> Vec<(Vec2, Vec2)> {
+        if self.labels_3d.is_empty() {
+            return Vec::new();
+        }
+
+        let size = Vec2::new(width, height);
+        let (view, _) = camera.view_transform_pair(0);
+        let view_mat = view.to_mat4();
+        let up = view.rotation.inverse() * Vec3::Y;
+
+        // Lay the labels out first: the declutter needs all their bounds.
+        let mut projected = Vec::with_capacity(self.labels_3d.len());
+        for label in std::mem::take(&mut self.labels_3d) {
+            // The camera looks down its local -Z axis.
+            let depth = -view_mat.transform_point3(label.pos).z;
+            if depth <= 0.0 {
+                continue;
+            }
+
+            let base = camera.project(label.pos, size);
+            let top = camera.project(label.pos + up * label.height, size);
+            let line_height = (top - base).length();
+            if line_height < 1.0e-3 {
+                continue;
+            }
+
+            // `project` has its origin at the bottom-left; text is laid out from
+            // the top-left.
+            let scale = rusttype::Scale::uniform(line_height);
+            let vmetrics = label.font.font().v_metrics(scale);
+            let text_width = line_width(&label.font, &label.text, scale, self.spacing);
+            let anchor = Vec2::new(base.x, height - base.y);
+            let pos = Vec2::new(anchor.x - text_width / 2.0, anchor.y - vmetrics.ascent);
+            let text_size = Vec2::new(text_width, vmetrics.ascent - vmetrics.descent);
+
+            projected.push(ProjectedLabel {
+                label,
+                anchor,
+                pos,
+                size: text_size,
+                line_height,
+                depth,
+            });
+        }
+
+        let Some(declutter) = self.label_declutter else {
+            for p in projected {
+                let label = &p.label;
+                let pos = p.pos + offset;
+                self.push_sdf_text(&label.text, pos, p.line_height, &label.font, label.color);
+            }
+            return Vec::new();
+        };
+
+        // The labels closest to the camera keep their place.
+        let mut order: Vec<usize> = (0..projected.len()).collect();
+        order.sort_by(|a, b| projected[*a].depth.total_cmp(&projected[*b].depth));
+        let rects: Vec<_> = projected.iter().map(|p| (p.pos, p.size)).collect();
+        let offsets = declutter.place(&rects, &order);
+
+        let mut leader_lines = Vec::new();
+        for (p, shift) in projected.into_iter().zip(offsets) {
+            let Some(shift) = shift else {
+                continue;
+            };
+            let pos = p.pos + shift;
+            if shift != Vec2::ZERO {
+                leader_lines.push(leader_line(p.anchor, pos, pos + p.size));
+            }
+            let label = &p.label;
+            // The projected line height is already in pixels.
+            let pos = pos + offset;
+            self.push_sdf_text(&label.text, pos, p.line_height, &label.font, label.color);
+        }
+        leader_lines
+    }
+
     /// Actually draws the text.
     pub fn render(&mut self, width: f32, height: f32, context: &mut RenderContext2dEncoder) {
-        if self.contexts.is_empty() {
+        // Labels whose handles were all dropped.
+        self.labels.retain(|label| Rc::strong_count(label) > 1);
+
+        if self.contexts.is_empty()
+            && self.sdf_contexts.is_empty()
+            && self.bmfont_draws.is_empty()
+            && self.labels.is_empty()
+        {
+            self.clear();
             return;
         }
 
         let ctxt = Context::get();
 
+        self.build_bitmap_vertices(&ctxt);
+        self.build_sdf_vertices();
+        self.build_bmfont_vertices();
+        let inv_size = [2.0 / width, -2.0 / height];
+        let num_labels = self.prepare_labels(&ctxt, inv_size);
+        self.upload_sdf_glyphs(&ctxt);
+
+        if self.vertices.is_empty()
+            && self.sdf_vertices.is_empty()
+            && self.bmfont_vertices.is_empty()
+            && num_labels == 0
+        {
+            self.clear();
+            return;
+        }
+
+        // Update uniforms
+        let uniforms = TextUniforms {
+            inv_size,
+            offset: [0.0, 0.0],
+        };
+        ctxt.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+
+        // Upload vertex data, growing the buffers if needed.
+        upload_vertices(
+            &ctxt,
+            "text_renderer_vertex_buffer",
+            &mut self.vertex_buffer,
+            &mut self.vertex_capacity,
+            &self.vertices,
+        );
+        upload_vertices(
+            &ctxt,
+            "text_renderer_sdf_vertex_buffer",
+            &mut self.sdf_vertex_buffer,
+            &mut self.sdf_vertex_capacity,
+            &self.sdf_vertices,
+        );
+        upload_vertices(
+            &ctxt,
+            "text_renderer_bmfont_vertex_buffer",
+            &mut self.bmfont_vertex_buffer,
+            &mut self.bmfont_vertex_capacity,
+            &self.bmfont_vertices,
+        );
+
+        // Create bind groups
+        let uniform_bind_group = ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("text_renderer_uniform_bind_group"),
+            layout: &self.uniform_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: self.uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        let create_texture_bind_group =
+            |label: &str, view: &wgpu::TextureView, sampler: &wgpu::Sampler| {
+                ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some(label),
+                    layout: &self.texture_bind_group_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::TextureView(view),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::Sampler(sampler),
+                        },
+                    ],
+                })
+            };
+        let texture_bind_group = create_texture_bind_group(
+            "text_renderer_texture_bind_group",
+            &self.glyph_texture_view,
+            &self.glyph_sampler,
+        );
+        let sdf_texture_bind_group = create_texture_bind_group(
+            "text_renderer_sdf_texture_bind_group",
+            &self.sdf_texture_view,
+            &self.glyph_sampler,
+        );
+
+        // Create render pass and draw all text
+        {
+            let mut render_pass = context
+                .encoder
+                .begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("text_renderer_render_pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: context.color_view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Load,
+                            store: wgpu::StoreOp::Store,
+                        },
+                        depth_slice: None,
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                    multiview_mask: None,
+                });
+
+            render_pass.set_bind_group(0, &uniform_bind_group, &[]);
+
+            if !self.vertices.is_empty() {
+                render_pass.set_pipeline(&self.pipeline);
+                render_pass.set_bind_group(1, &texture_bind_group, &[]);
+                render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+                render_pass.draw(0..self.vertices.len() as u32, 0..1);
+            }
+
+            if !self.sdf_vertices.is_empty() {
+                render_pass.set_pipeline(&self.sdf_pipeline);
+                render_pass.set_bind_group(1, &sdf_texture_bind_group, &[]);
+                render_pass.set_vertex_buffer(0, self.sdf_vertex_buffer.slice(..));
+                render_pass.draw(0..self.sdf_vertices.len() as u32, 0..1);
+            }
+
+            if !self.bmfont_batches.is_empty() {
+                render_pass.set_vertex_buffer(0, self.bmfont_vertex_buffer.slice(..));
+                for batch in &self.bmfont_batches {
+                    let (pipeline, sampler) = if batch.font.is_distance_field() {
+                        (&self.bmfont_distance_field_pipeline, &self.glyph_sampler)
+                    } else {
+                        (&self.bmfont_pipeline, &self.nearest_sampler)
+                    };
+                    let page_bind_group = create_texture_bind_group(
+                        "text_renderer_bmfont_page_bind_group",
+                        batch.font.page(batch.page),
+                        sampler,
+                    );
+                    render_pass.set_pipeline(pipeline);
+                    render_pass.set_bind_group(1, &page_bind_group, &[]);
+                    render_pass.draw(batch.vertices.clone(), 0..1);
+                }
+            }
+
+            if num_labels > 0 {
+                render_pass.set_pipeline(&self.sdf_pipeline);
+                render_pass.set_bind_group(1, &sdf_texture_bind_group, &[]);
+                for label in &self.labels {
+                    let label = label.borrow();
+                    let Some(gpu) = label.gpu.as_ref().filter(|_| label.visible) else {
+                        continue;
+                    };
+                    if gpu.vertex_count > 0 {
+                        render_pass.set_bind_group(0, &gpu.uniform_bind_group, &[]);
+                        render_pass.set_vertex_buffer(0, gpu.vertex_buffer.slice(..));
+                        render_pass.draw(0..gpu.vertex_count, 0..1);
+                    }
+                }
+            }
+        }
+
+        self.clear();
+    }
+
+    /// Drops the pending text without rendering it, e.g. when the frame is
+    /// skipped. The bounds of the last rendered frame are kept.
+    pub fn discard(&mut self) {
+        self.labels_3d.clear();
+        self.bounds.clear();
+        self.discard_batches();
+    }
+
+    fn clear(&mut self) {
+        std::mem::swap(&mut self.bounds, &mut self.last_bounds);
+        self.bounds.clear();
+        self.discard_batches();
+    }
+
+    fn discard_batches(&mut self) {
+        self.vertices.clear();
+        self.contexts.clear();
+        self.text.clear();
+        self.sdf_vertices.clear();
+        self.sdf_contexts.clear();
+        self.sdf_text.clear();
+        self.bmfont_draws.clear();
+        self.bmfont_vertices.clear();
+        self.bmfont_batches.clear();
+    }
+
+    /// Rasterizes the glyphs of the `draw_text` calls into the coverage glyph
+    /// cache and generates their quads.
+    fn build_bitmap_vertices(&mut self, ctxt: &Context) {
+        if self.contexts.is_empty() {
+            return;
+        }
+
         // Collect all glyphs with their metadata first, then process them
         // This avoids re-creating glyph objects which might not match cache entries
         struct GlyphData {
@@ -312,8 +1057,8 @@ impl TextRenderer {
                     y: text_context.pos.y + vmetrics.ascent + vshift,
                 };
 
-                vshift += line_height;
-                let layout = text_context.font.font().layout(line, scale, orig);
+                vshift += line_height * self.spacing.line;
+                let layout = layout_line(&text_context.font, line, scale, orig, self.spacing);
 
                 for glyph in layout {
                     self.cache.queue_glyph(font_uid, glyph.clone());
@@ -330,28 +1075,14 @@ impl TextRenderer {
         // Update glyph cache texture with all queued glyphs
         let glyph_texture = &self.glyph_texture;
         let _ = self.cache.cache_queued(|rect, data| {
-            ctxt.write_texture(
-                wgpu::TexelCopyTextureInfo {
-                    texture: glyph_texture,
-                    mip_level: 0,
-                    origin: wgpu::Origin3d {
-                        x: rect.min.x,
-                        y: rect.min.y,
-                        z: 0,
-                    },
-                    aspect: wgpu::TextureAspect::All,
-                },
+            write_atlas(
+                ctxt,
+                glyph_texture,
+                rect.min.x,
+                rect.min.y,
+                rect.width(),
+                rect.height(),
                 data,
-                wgpu::TexelCopyBufferLayout {
-                    offset: 0,
-                    bytes_per_row: Some(rect.width()),
-                    rows_per_image: Some(rect.height()),
-                },
-                wgpu::Extent3d {
-                    width: rect.width(),
-                    height: rect.height(),
-                    depth_or_array_layers: 1,
-                },
             );
         });
 
@@ -360,132 +1091,213 @@ impl TextRenderer {
             if let Ok(Some((tex, px_rect))) =
                 self.cache.rect_for(glyph_data.font_uid, &glyph_data.glyph)
             {
-                let min_px = px_rect.min.x as f32;
-                let min_py = px_rect.min.y as f32;
-                let max_px = px_rect.max.x as f32;
-                let max_py = px_rect.max.y as f32;
-                let color = glyph_data.color;
-
-                // Two triangles per glyph quad
-                self.vertices.push(TextVertex {
-                    position: [min_px, min_py],
-                    tex_coord: [tex.min.x, tex.min.y],
-                    color,
-                });
-                self.vertices.push(TextVertex {
-                    position: [min_px, max_py],
-                    tex_coord: [tex.min.x, tex.max.y],
-                    color,
-                });
-                self.vertices.push(TextVertex {
-                    position: [max_px, min_py],
-                    tex_coord: [tex.max.x, tex.min.y],
-                    color,
-                });
+                push_quad(
+                    &mut self.vertices,
+                    [px_rect.min.x as f32, px_rect.min.y as f32],
+                    [px_rect.max.x as f32, px_rect.max.y as f32],
+                    [tex.min.x, tex.min.y],
+                    [tex.max.x, tex.max.y],
+                    glyph_data.color,
+                );
+            }
+        }
+    }
 
-                self.vertices.push(TextVertex {
-                    position: [max_px, min_py],
-                    tex_coord: [tex.max.x, tex.min.y],
-                    color,
-                });
-                self.vertices.push(TextVertex {
-                    position: [min_px, max_py],
-                    tex_coord: [tex.min.x, tex.max.y],
-                    color,
-                });
-                self.vertices.push(TextVertex {
-                    position: [max_px, max_py],
-                    tex_coord: [tex.max.x, tex.max.y],
-                    color,
+    /// Lays out the `draw_text_sdf` calls and generates their quads from the
+    /// distance-field atlas.
+    fn build_sdf_vertices(&mut self) {
+        let mut pos = 0;
+        for text_context in self.sdf_contexts.iter() {
+            let text = &self.sdf_text[pos..pos + text_context.len];
+            layout_sdf(
+                &mut self.sdf_cache,
+                &mut self.sdf_vertices,
+                text,
+                text_context.pos,
+                text_context.scale,
+                &text_context.font,
+                text_context.color,
+                self.spacing,
+            );
+            pos += text_context.len;
+        }
+    }
+
+    /// Lays out the `draw_text_bitmap` calls and generates their quads, batched
+    /// by atlas page.
+    fn build_bmfont_vertices(&mut self) {
+        let vertices = &mut self.bmfont_vertices;
+        let batches = &mut self.bmfont_batches;
+        let spacing = self.spacing;
+
+        for draw in &self.bmfont_draws {
+            let font = &draw.font;
+            let ratio = draw.scale / font.size();
+            let line_height = font.line_height() * ratio;
+            let mut top = draw.pos.y;
+
+            for line in draw.text.lines() {
+                let _ = layout_bitmap_line(font, line, draw.pos.x, ratio, spacing, |glyph, x| {
+                    let min = Vec2::new(x, top) + glyph.offset * ratio;
+                    let max = min + glyph.size * ratio;
+                    let start = vertices.len() as u32;
+                    push_quad(
+                        vertices,
+                        min.to_array(),
+                        max.to_array(),
+                        glyph.uv_min,
+                        glyph.uv_max,
+                        draw.color,
+                    );
+                    let end = vertices.len() as u32;
+
+                    let same_batch = batches.last().is_some_and(|batch| {
+                        Arc::ptr_eq(&batch.font, font) && batch.page == glyph.page
+                    });
+                    if same_batch {
+                        batches.last_mut().unwrap().vertices.end = end;
+                    } else {
+                        batches.push(BitmapFontBatch {
+                            font: font.clone(),
+                            page: glyph.page,
+                            vertices: start..end,
+                        });
+                    }
                 });
+                top += line_height * spacing.line;
             }
         }
+    }
 
-        if self.vertices.is_empty() {
-            self.contexts.clear();
-            self.text.clear();
-            return;
-        }
+    /// Lays out the labels created or modified since the last frame and updates
+    /// the uniforms of the moved ones. Returns the number of visible labels.
+    fn prepare_labels(&mut self, ctxt: &Context, inv_size: [f32; 2]) -> usize {
+        let mut num_visible = 0;
 
-        // Update uniforms
-        let uniforms = TextUniforms {
-            inv_size: [2.0 / width, -2.0 / height],
-            _padding: [0.0, 0.0],
-        };
-        ctxt.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+        for label in &self.labels {
+            let mut label = label.borrow_mut();
+            if !label.visible {
+                continue;
+            }
+            num_visible += 1;
 
-        // Ensure vertex buffer is large enough
-        let needed = self.vertices.len();
-        if needed > self.vertex_capacity {
-            let new_capacity = needed.next_power_of_two();
-            self.vertex_buffer = ctxt.create_buffer(&wgpu::BufferDescriptor {
-                label: Some("text_renderer_vertex_buffer"),
-                size: (std::mem::size_of::<TextVertex>() * new_capacity) as u64,
-                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-                mapped_at_creation: false,
+            let outdated = label.dirty
+                || label.gpu.as_ref().is_none_or(|gpu| {
+                    gpu.size_scale != self.size_scale || gpu.spacing != self.spacing
+                });
+            if outdated {
+                label.dirty = false;
+                let style = label.style.clone();
+                let mut vertices = Vec::new();
+                layout_sdf(
+                    &mut self.sdf_cache,
+                    &mut vertices,
+                    &label.text,
+                    Vec2::ZERO,
+                    style.scale * self.size_scale,
+                    &style.font,
+                    [style.color.r, style.color.g, style.color.b, style.color.a],
+                    self.spacing,
+                );
+                let size = self.measure_text(&label.text, style.scale, &style.font);
+                let gpu = label.gpu.take();
+                label.gpu = Some(self.label_buffers(ctxt, gpu, &vertices, size));
+            }
+
+            let position = label.position;
+            let gpu = label.gpu.as_mut().unwrap();
+            let uniforms = [inv_size[0], inv_size[1], position.x, position.y];
+            if gpu.uniforms != uniforms {
+                gpu.uniforms = uniforms;
+                let uniforms = TextUniforms {
+                    inv_size,
+                    offset: [position.x, position.y],
+                };
+                ctxt.write_buffer(&gpu.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+            }
+            self.bounds.push(TextBounds {
+                min: position,
+                max: position + gpu.size,
             });
-            self.vertex_capacity = new_capacity;
         }
 
-        // Upload vertex data
-        ctxt.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&self.vertices));
-
-        // Create bind groups
-        let uniform_bind_group = ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("text_renderer_uniform_bind_group"),
-            layout: &self.uniform_bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: self.uniform_buffer.as_entire_binding(),
-            }],
-        });
+        num_visible
+    }
 
-        let texture_bind_group = ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("text_renderer_texture_bind_group"),
-            layout: &self.texture_bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
+    /// Writes the quads of a label into its buffers, reusing `gpu` if its vertex
+    /// buffer is large enough.
+    fn label_buffers(
+        &self,
+        ctxt: &Context,
+        gpu: Option<LabelBuffers>,
+        vertices: &[TextVertex],
+        size: Vec2,
+    ) -> LabelBuffers {
+        let needed = (std::mem::size_of::<TextVertex>() * vertices.len().max(1)) as u64;
+        let mut gpu = gpu.unwrap_or_else(|| {
+            let uniform_buffer = ctxt.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("text_label_uniform_buffer"),
+                size: std::mem::size_of::<TextUniforms>() as u64,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+            let uniform_bind_group = ctxt.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("text_label_uniform_bind_group"),
+                layout: &self.uniform_bind_group_layout,
+                entries: &[wgpu::BindGroupEntry {
                     binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&self.glyph_texture_view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&self.glyph_sampler),
-                },
-            ],
+                    resource: uniform_buffer.as_entire_binding(),
+                }],
+            });
+            LabelBuffers {
+                vertex_buffer: ctxt.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("text_label_vertex_buffer"),
+                    size: needed,
+                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                }),
+                vertex_count: 0,
+                size_scale: self.size_scale,
+                spacing: self.spacing,
+                size,
+                uniform_buffer,
+                uniform_bind_group,
+                // Forces the first uniform upload.
+                uniforms: [f32::NAN; 4],
+            }
         });
 
-        // Create render pass and draw all text
-        {
-            let mut render_pass = context
-                .encoder
-                .begin_render_pass(&wgpu::RenderPassDescriptor {
-                    label: Some("text_renderer_render_pass"),
-                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                        view: context.color_view,
-                        resolve_target: None,
-                        ops: wgpu::Operations {
-                            load: wgpu::LoadOp::Load,
-                            store: wgpu::StoreOp::Store,
-                        },
-                        depth_slice: None,
-                    })],
-                    depth_stencil_attachment: None,
-                    timestamp_writes: None,
-                    occlusion_query_set: None,
-                    multiview_mask: None,
-                });
-
-            render_pass.set_pipeline(&self.pipeline);
-            render_pass.set_bind_group(0, &uniform_bind_group, &[]);
-            render_pass.set_bind_group(1, &texture_bind_group, &[]);
-            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-            render_pass.draw(0..self.vertices.len() as u32, 0..1);
+        if gpu.vertex_buffer.size() < needed {
+            gpu.vertex_buffer = ctxt.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("text_label_vertex_buffer"),
+                size: needed,
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
         }
+        if !vertices.is_empty() {
+            ctxt.write_buffer(&gpu.vertex_buffer, 0, bytemuck::cast_slice(vertices));
+        }
+        gpu.vertex_count = vertices.len() as u32;
+        gpu.size_scale = self.size_scale;
+        gpu.spacing = self.spacing;
+        gpu.size = size;
+        gpu
+    }
 
-        self.vertices.clear();
-        self.contexts.clear();
-        self.text.clear();
+    /// Uploads the distance fields of the glyphs used for the first time.
+    fn upload_sdf_glyphs(&mut self, ctxt: &Context) {
+        for upload in self.sdf_cache.take_uploads() {
+            write_atlas(
+                ctxt,
+                &self.sdf_texture,
+                upload.x,
+                upload.y,
+                upload.width,
+                upload.height,
+                &upload.data,
+            );
+        }
     }
 
     #[allow(dead_code)]
@@ -494,6 +1306,205 @@ impl TextRenderer {
     }
 }
 
+/// Positions the glyphs of a single line of text, starting at `origin` on its
+/// baseline. Positions are fractional: they are not rounded to whole pixels.
+fn layout_line(
+    font: &Font,
+    line: &str,
+    scale: rusttype::Scale,
+    origin: rusttype::Point<f32>,
+    spacing: Spacing,
+) -> Vec<rusttype::PositionedGlyph<'static>> {
+    let font = font.font();
+    let tracking = spacing.letter * scale.y;
+    let mut caret = origin.x;
+    let mut previous = None;
+
+    line.chars()
+        .map(|c| {
+            let glyph = font.glyph(c).scaled(scale);
+            if let (Some(previous), true) = (previous, spacing.kerning) {
+                caret += font.pair_kerning(scale, previous, glyph.id());
+            }
+            previous = Some(glyph.id());
+            let advance = glyph.h_metrics().advance_width;
+            let glyph = glyph.positioned(rusttype::point(caret, origin.y));
+            caret += advance + tracking;
+            glyph
+        })
+        .collect()
+}
+
+/// The width of a single line of text: the end of the advance of its last
+/// glyph.
+fn line_width(font: &Font, line: &str, scale: rusttype::Scale, spacing: Spacing) -> f32 {
+    layout_line(font, line, scale, rusttype::point(0.0, 0.0), spacing)
+        .last()
+        .map(|g| g.position().x + g.unpositioned().h_metrics().advance_width)
+        .unwrap_or(0.0)
+}
+
+/// Positions the glyphs of a single line of bitmap-font text, the pen starting
+/// at `x`, and calls `f` with each glyph and its pen position. Returns the width
+/// of the line.
+fn layout_bitmap_line(
+    font: &BitmapFont,
+    line: &str,
+    x: f32,
+    ratio: f32,
+    spacing: Spacing,
+    mut f: impl FnMut(&BitmapGlyph, f32),
+) -> f32 {
+    let tracking = spacing.letter * font.size() * ratio;
+    let mut caret = x;
+    let mut end = x;
+    let mut previous = None;
+
+    for c in line.chars() {
+        let Some(glyph) = font.glyph(c) else {
+            continue;
+        };
+        if let (Some(previous), true) = (previous, spacing.kerning) {
+            caret += font.kerning(previous, c) * ratio;
+        }
+        previous = Some(c);
+        f(glyph, caret);
+        caret += glyph.advance * ratio;
+        end = caret;
+        caret += tracking;
+    }
+
+    end - x
+}
+
+/// Lays out `text` with the top-left corner of its layout box at `pos` and
+/// pushes the quads of its glyphs from the distance-field atlas.
+fn layout_sdf(
+    sdf_cache: &mut SdfGlyphCache,
+    vertices: &mut Vec<TextVertex>,
+    text: &str,
+    pos: Vec2,
+    scale: f32,
+    font: &Arc<Font>,
+    color: [f32; 4],
+    spacing: Spacing,
+) {
+    let ratio = scale / SDF_BASE_SIZE;
+    let scale = rusttype::Scale::uniform(scale);
+    let vmetrics = font.font().v_metrics(scale);
+    let line_height = vmetrics.ascent - vmetrics.descent;
+    let mut vshift = 0.0;
+
+    for line in text.lines() {
+        let orig = rusttype::Point {
+            x: pos.x,
+            y: pos.y + vmetrics.ascent + vshift,
+        };
+
+        vshift += line_height * spacing.line;
+        let layout = layout_line(font, line, scale, orig, spacing);
+
+        for glyph in layout {
+            if let Some(sdf) = sdf_cache.get_or_insert(font, glyph.id()) {
+                let origin = glyph.position();
+                push_quad(
+                    vertices,
+                    [origin.x + sdf.min[0] * ratio, origin.y + sdf.min[1] * ratio],
+                    [origin.x + sdf.max[0] * ratio, origin.y + sdf.max[1] * ratio],
+                    sdf.uv_min,
+                    sdf.uv_max,
+                    color,
+                );
+            }
+        }
+    }
+}
+
+/// Pushes the two triangles of a glyph quad.
+fn push_quad(
+    vertices: &mut Vec<TextVertex>,
+    min: [f32; 2],
+    max: [f32; 2],
+    uv_min: [f32; 2],
+    uv_max: [f32; 2],
+    color: [f32; 4],
+) {
+    let corners = [
+        ([min[0], min[1]], [uv_min[0], uv_min[1]]),
+        ([min[0], max[1]], [uv_min[0], uv_max[1]]),
+        ([max[0], min[1]], [uv_max[0], uv_min[1]]),
+        ([max[0], min[1]], [uv_max[0], uv_min[1]]),
+        ([min[0], max[1]], [uv_min[0], uv_max[1]]),
+        ([max[0], max[1]], [uv_max[0], uv_max[1]]),
+    ];
+
+    for (position, tex_coord) in corners {
+        vertices.push(TextVertex {
+            position,
+            tex_coord,
+            color,
+        });
+    }
+}
+
+/// Writes a tightly-packed single-channel rectangle into a glyph atlas.
+fn write_atlas(
+    ctxt: &Context,
+    texture: &wgpu::Texture,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    data: &[u8],
+) {
+    ctxt.write_texture(
+        wgpu::TexelCopyTextureInfo {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d { x, y, z: 0 },
+            aspect: wgpu::TextureAspect::All,
+        },
+        data,
+        wgpu::TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(width),
+            rows_per_image: Some(height),
+        },
+        wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+    );
+}
+
+/// Uploads `vertices` into `buffer`, reallocating it when it is too small.
+fn upload_vertices(
+    ctxt: &Context,
+    label: &str,
+    buffer: &mut wgpu::Buffer,
+    capacity: &mut usize,
+    vertices: &[TextVertex],
+) {
+    if vertices.is_empty() {
+        return;
+    }
+
+    let needed = vertices.len();
+    if needed > *capacity {
+        let new_capacity = needed.next_power_of_two();
+        *buffer = ctxt.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(label),
+            size: (std::mem::size_of::<TextVertex>() * new_capacity) as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        *capacity = new_capacity;
+    }
+
+    ctxt.write_buffer(buffer, 0, bytemuck::cast_slice(vertices));
+}
+
 /// Vertex shader used by the material to display text.
 #[allow(dead_code)]
 pub static TEXT_VERTEX_SRC: &str = include_str!("../builtin/text.wgsl");
@@ -0,0 +1,304 @@
+//! Signed-distance-field glyph atlas.
+//!
+//! Glyphs are rasterized once at a fixed base size, converted to a signed
+//! distance field with an exact Euclidean distance transform, and packed into
+//! a single-channel atlas. Because the atlas stores distances instead of
+//! coverage, the same texels can be sampled at any on-screen scale and still
+//! produce a sharp, antialiased edge (the edge is reconstructed in the fragment
+//! shader at the `0.5` iso-line).
+
+use crate::text::Font;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Pixel size glyphs are rasterized at before computing their distance field.
+pub(crate) const SDF_BASE_SIZE: f32 = 48.0;
+/// Distance (in base-size pixels) covered by the field on each side of an edge.
+pub(crate) const SDF_SPREAD: u32 = 6;
+
+/// Placement of one glyph inside the SDF atlas.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct SdfGlyph {
+    /// Atlas texture coordinates of the glyph cell (top-left).
+    pub uv_min: [f32; 2],
+    /// Atlas texture coordinates of the glyph cell (bottom-right).
+    pub uv_max: [f32; 2],
+    /// Cell bounds relative to the glyph origin, in base-size pixels (the
+    /// spread padding included).
+    pub min: [f32; 2],
+    /// See [`Self::min`].
+    pub max: [f32; 2],
+}
+
+/// A rectangle of freshly generated distance values waiting to be uploaded.
+pub(crate) struct SdfUpload {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+/// Cache of SDF glyphs packed into a fixed-size atlas with a shelf allocator.
+pub(crate) struct SdfGlyphCache {
+    glyphs: HashMap<(usize, u16), Option<SdfGlyph>>,
+    uploads: Vec<SdfUpload>,
+    width: u32,
+    height: u32,
+    shelf_x: u32,
+    shelf_y: u32,
+    shelf_height: u32,
+}
+
+impl SdfGlyphCache {
+    pub fn new(width: u32, height: u32) -> Self {
+        SdfGlyphCache {
+            glyphs: HashMap::new(),
+            uploads: Vec::new(),
+            width,
+            height,
+            shelf_x: 0,
+            shelf_y: 0,
+            shelf_height: 0,
+        }
+    }
+
+    /// Returns the atlas entry for `glyph_id` of `font`, generating it on first
+    /// use. Returns `None` for glyphs without an outline (e.g. spaces) or when
+    /// the atlas is full.
+    pub fn get_or_insert(
+        &mut self,
+        font: &Arc<Font>,
+        glyph_id: rusttype::GlyphId,
+    ) -> Option<SdfGlyph> {
+        let key = (Font::uid(font), glyph_id.0);
+        if let Some(entry) = self.glyphs.get(&key) {
+            return *entry;
+        }
+
+        let entry = self.generate(font, glyph_id);
+        let _ = self.glyphs.insert(key, entry);
+        entry
+    }
+
+    /// Drains the rectangles generated since the last call.
+    pub fn take_uploads(&mut self) -> Vec<SdfUpload> {
+        std::mem::take(&mut self.uploads)
+    }
+
+    fn generate(&mut self, font: &Arc<Font>, glyph_id: rusttype::GlyphId) -> Option<SdfGlyph> {
+        let glyph = font
+            .font()
+            .glyph(glyph_id)
+            .scaled(rusttype::Scale::uniform(SDF_BASE_SIZE))
+            .positioned(rusttype::point(0.0, 0.0));
+        let bb = glyph.pixel_bounding_box()?;
+
+        let pad = SDF_SPREAD as i32;
+        let w = (bb.width() + 2 * pad) as usize;
+        let h = (bb.height() + 2 * pad) as usize;
+
+        let mut coverage = vec![0.0f32; w * h];
+        glyph.draw(|x, y, v| {
+            let px = x as usize + pad as usize;
+            let py = y as usize + pad as usize;
+            coverage[py * w + px] = v;
+        });
+
+        let (x, y) = self.allocate(w as u32, h as u32)?;
+        let data = signed_distance_field(&coverage, w, h, SDF_SPREAD as f32);
+
+        self.uploads.push(SdfUpload {
+            x,
+            y,
+            width: w as u32,
+            height: h as u32,
+            data,
+        });
+
+        let inv_w = 1.0 / self.width as f32;
+        let inv_h = 1.0 / self.height as f32;
+        Some(SdfGlyph {
+            uv_min: [x as f32 * inv_w, y as f32 * inv_h],
+            uv_max: [(x + w as u32) as f32 * inv_w, (y + h as u32) as f32 * inv_h],
+            min: [(bb.min.x - pad) as f32, (bb.min.y - pad) as f32],
+            max: [(bb.max.x + pad) as f32, (bb.max.y + pad) as f32],
+        })
+    }
+
+    fn allocate(&mut self, w: u32, h: u32) -> Option<(u32, u32)> {
+        if w > self.width {
+            return None;
+        }
+
+        if self.shelf_x + w > self.width {
+            self.shelf_y += self.shelf_height;
+            self.shelf_x = 0;
+            self.shelf_height = 0;
+        }
+
+        if self.shelf_y + h > self.height {
+            return None;
+        }
+
+        let pos = (self.shelf_x, self.shelf_y);
+        self.shelf_x += w;
+        self.shelf_height = self.shelf_height.max(h);
+        Some(pos)
+    }
+}
+
+/// Converts a coverage bitmap into an 8-bit signed distance field.
+///
+/// Texels inside the glyph map above `0.5`, texels outside below; `spread`
+/// pixels of distance map to the full `[0, 1]` range.
+fn signed_distance_field(coverage: &[f32], w: usize, h: usize, spread: f32) -> Vec<u8> {
+    const INF: f32 = 1.0e20;
+
+    let mut outside: Vec<f32> = coverage
+        .iter()
+        .map(|c| if *c >= 0.5 { 0.0 } else { INF })
+        .collect();
+    let mut inside: Vec<f32> = coverage
+        .iter()
+        .map(|c| if *c >= 0.5 { INF } else { 0.0 })
+        .collect();
+
+    edt_2d(&mut outside, w, h);
+    edt_2d(&mut inside, w, h);
+
+    outside
+        .iter()
+        .zip(inside.iter())
+        .map(|(o, i)| {
+            let dist = o.sqrt() - i.sqrt();
+            let v = 0.5 - dist / (2.0 * spread);
+            (v.clamp(0.0, 1.0) * 255.0).round() as u8
+        })
+        .collect()
+}
+
+/// In-place squared Euclidean distance transform (Felzenszwalb & Huttenlocher).
+fn edt_2d(grid: &mut [f32], w: usize, h: usize) {
+    let n = w.max(h);
+    let mut f = vec![0.0; n];
+    let mut d = vec![0.0; n];
+    let mut v = vec![0usize; n];
+    let mut z = vec![0.0; n + 1];
+
+    for x in 0..w {
+        for (y, fy) in f[..h].iter_mut().enumerate() {
+            *fy = grid[y * w + x];
+        }
+        edt_1d(&f[..h], &mut d[..h], &mut v, &mut z);
+        for (y, dy) in d[..h].iter().enumerate() {
+            grid[y * w + x] = *dy;
+        }
+    }
+
+    for y in 0..h {
+        f[..w].copy_from_slice(&grid[y * w..(y + 1) * w]);
+        edt_1d(&f[..w], &mut d[..w], &mut v, &mut z);
+        grid[y * w..(y + 1) * w].copy_from_slice(&d[..w]);
+    }
+}
+
+fn edt_1d(f: &[f32], d: &mut [f32], v: &mut [usize], z: &mut [f32]) {
+    let n = f.len();
+    let mut k = 0;
+    v[0] = 0;
+    z[0] = f32::NEG_INFINITY;
+    z[1] = f32::INFINITY;
+
+    for q in 1..n {
+        loop {
+            let p = v[k];
+            let s = ((f[q] + (q * q) as f32) - (f[p] + (p * p) as f32)) / (2.0 * (q - p) as f32);
+            if s <= z[k] && k > 0 {
+                k -= 1;
+            } else {
+                k += 1;
+                v[k] = q;
+                z[k] = s;
+                z[k + 1] = f32::INFINITY;
+                break;
+            }
+        }
+    }
+
+    k = 0;
+    for (q, dq) in d.iter_mut().enumerate() {
+        while z[k + 1] < q as f32 {
+            k += 1;
+        }
+        let p = v[k];
+        let dx = q as f32 - p as f32;
+        *dq = dx * dx + f[p];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edt_matches_brute_force() {
+        let (w, h) = (7, 5);
+        let seeds = [(1, 1), (5, 3), (6, 0)];
+        let mut grid = vec![1.0e20; w * h];
+        for (x, y) in seeds {
+            grid[y * w + x] = 0.0;
+        }
+
+        edt_2d(&mut grid, w, h);
+
+        for y in 0..h {
+            for x in 0..w {
+                let expected = seeds
+                    .iter()
+                    .map(|(sx, sy)| {
+                        let dx = x as f32 - *sx as f32;
+                        let dy = y as f32 - *sy as f32;
+                        dx * dx + dy * dy
+                    })
+                    .fold(f32::INFINITY, f32::min);
+                assert_eq!(grid[y * w + x], expected, "at ({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn sdf_is_above_half_inside_and_below_outside() {
+        // A 3x3 filled square centered in a 9x9 bitmap.
+        let (w, h) = (9, 9);
+        let coverage: Vec<f32> = (0..w * h)
+            .map(|i| {
+                let (x, y) = (i % w, i / w);
+                if (3..6).contains(&x) && (3..6).contains(&y) {
+                    1.0
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+
+        let sdf = signed_distance_field(&coverage, w, h, 2.0);
+
+        assert!(sdf[4 * w + 4] > 128);
+        assert!(sdf[4 * w + 2] < 128);
+        // Farther than the spread from the square.
+        assert_eq!(sdf[0], 0);
+    }
+
+    #[test]
+    fn shelf_allocator_packs_rows() {
+        let mut cache = SdfGlyphCache::new(10, 10);
+        assert_eq!(cache.allocate(4, 3), Some((0, 0)));
+        assert_eq!(cache.allocate(4, 5), Some((4, 0)));
+        // Doesn't fit on the first shelf, whose height is its tallest entry.
+        assert_eq!(cache.allocate(4, 2), Some((0, 5)));
+        assert_eq!(cache.allocate(11, 1), None);
+        assert_eq!(cache.allocate(6, 6), None);
+        assert_eq!(cache.allocate(6, 3), Some((4, 5)));
+    }
+}
@@ -0,0 +1,276 @@
+//! Scientific visualization helpers: streamlines of vector fields and
+//! ball-and-stick molecules.
+
+use glamx::{Mat3, Quat, Vec3};
+
+use crate::color::{Color, Colormap};
+use crate::procedural;
+use crate::renderer::Polyline3d;
+use crate::scene::{InstanceData3d, SceneNode3d, ShadingMode};
+
+/// A streamline traced by [`streamlines`]: the path of a massless particle
+/// carried by a vector field.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Streamline {
+    /// The points of the streamline, from its seed onward.
+    pub points: Vec<Vec3>,
+    /// The magnitude of the field at each point.
+    pub speeds: Vec<f32>,
+}
+
+impl Streamline {
+    /// The length of the streamline.
+    pub fn length(&self) -> f32 {
+        self.points.windows(2).map(|w| w[0].distance(w[1])).sum()
+    }
+
+    /// A polyline following the streamline, colored by speed through `colormap`,
+    /// `range` giving the speeds mapped to both ends of the colormap.
+    ///
+    /// Draw it with [`Window::draw_polyline`](crate::window::Window::draw_polyline).
+    pub fn to_polyline(&self, colormap: &Colormap, range: (f32, f32)) -> Polyline3d {
+        Polyline3d::new(self.points.clone()).with_colors(self.colors(colormap, range))
+    }
+
+    /// The speed color of each point.
+    fn colors(&self, colormap: &Colormap, range: (f32, f32)) -> Vec<Color> {
+        self.speeds
+            .iter()
+            .map(|s| colormap.map(*s, range))
+            .collect()
+    }
+}
+
+/// Traces the streamlines of the vector field `field` from each of the `seeds`.
+///
+/// Each streamline is integrated with the fourth-order Runge-Kutta method,
+/// following the field direction in steps of length `step`, until it is
+/// `max_len` long, leaves the region where the field is finite, or reaches a
+/// point where the field vanishes. Negate the field to trace streamlines
+/// upstream.
+///
+/// Render them as polylines with [`Streamline::to_polyline`] or as tubes with
+/// [`add_streamline_tubes`].
+///
+/// # Example
+/// ```no_run
+/// # use kiss3d::prelude::*;
+/// # use kiss3d::viz;
+/// # #[kiss3d::main]
+/// # async fn main() {
+/// # let mut window = Window::new("Example").await;
+/// # let mut camera = OrbitCamera3d::default();
+/// let mut scene = SceneNode3d::empty();
+/// // A swirling flow rising along the Y axis.
+/// let field = |p: Vec3| Vec3::new(-p.z, 0.3, p.x);
+/// let seeds: Vec<Vec3> = (1..=5).map(|i| Vec3::new(i as f32 * 0.4, 0.0, 0.0)).collect();
+/// let lines = viz::streamlines(field, &seeds, 0.05, 20.0);
+/// viz::add_streamline_tubes(&mut scene, &lines, 0.03, &Colormap::Viridis);
+///
+/// while window.render_3d(&mut scene, &mut camera).await {}
+/// # }
+/// ```
+pub fn streamlines(
+    field: impl Fn(Vec3) -> Vec3,
+    seeds: &[Vec3],
+    step: f32,
+    max_len: f32,
+) -> Vec<Streamline> {
+    let max_steps = if step > 0.0 {
+        (max_len / step).ceil().max(0.0) as usize
+    } else {
+        0
+    };
+    // The normalized field direction, so each step covers `step` units.
+    let direction = |p: Vec3| {
+        let v = field(p);
+        let speed = v.length();
+        (speed.is_finite() && speed > 1.0e-12).then(|| (v / speed, speed))
+    };
+
+    seeds
+        .iter()
+        .map(|seed| {
+            let mut line = Streamline::default();
+            let Some((_, speed)) = direction(*seed) else {
+                return line;
+            };
+            line.points.push(*seed);
+            line.speeds.push(speed);
+
+            let mut p = *seed;
+            for _ in 0..max_steps {
+                let Some((k1, _)) = direction(p) else { break };
+                let Some((k2, _)) = direction(p + k1 * (step * 0.5)) else {
+                    break;
+                };
+                let Some((k3, _)) = direction(p + k2 * (step * 0.5)) else {
+                    break;
+                };
+                let Some((k4, _)) = direction(p + k3 * step) else {
+                    break;
+                };
+                let next = p + (k1 + k2 * 2.0 + k3 * 2.0 + k4) * (step / 6.0);
+                let Some((_, speed)) = direction(next) else {
+                    break;
+                };
+                line.points.push(next);
+                line.speeds.push(speed);
+                p = next;
+            }
+            line
+        })
+        .collect()
+}
+
+/// The smallest and largest speeds of `lines`, e.g. to color several sets of
+/// streamlines consistently.
+pub fn speed_range(lines: &[Streamline]) -> (f32, f32) {
+    lines
+        .iter()
+        .flat_map(|l| l.speeds.iter())
+        .fold((f32::MAX, f32::MIN), |(lo, hi), s| (lo.min(*s), hi.max(*s)))
+}
+
+/// Adds the streamlines `lines` to `parent` as tubes of the given `radius`,
+/// colored by speed through `colormap` over the [`speed_range`] of `lines`.
+///
+/// Returns a group node holding one tube per streamline with at least two
+/// points.
+pub fn add_streamline_tubes(
+    parent: &mut SceneNode3d,
+    lines: &[Streamline],
+    radius: f32,
+    colormap: &Colormap,
+) -> SceneNode3d {
+    const NSUBDIV: u32 = 8;
+    let range = speed_range(lines);
+    let mut group = parent.add_group();
+
+    for line in lines.iter().filter(|l| l.points.len() >= 2) {
+        let mesh = procedural::tube(&line.points, radius, NSUBDIV);
+        // Vertex layout of `procedural::tube`: one ring per point, then the caps.
+        let colors = line.colors(colormap, range);
+        let mut vertex_colors: Vec<Color> = colors
+            .iter()
+            .flat_map(|c| std::iter::repeat_n(*c, NSUBDIV as usize))
+            .collect();
+        vertex_colors.extend(std::iter::repeat_n(colors[0], NSUBDIV as usize + 1));
+        vertex_colors.extend(std::iter::repeat_n(
+            colors[colors.len() - 1],
+            NSUBDIV as usize + 1,
+        ));
+
+        let mut tube = group.add_render_mesh(mesh, Vec3::ONE);
+        tube.apply_to_object_mut(&mut |o| {
+            o.mesh().borrow_mut().set_colors(vertex_colors.clone());
+            o.set_shading_mode(ShadingMode::VertexColor);
+        });
+    }
+
+    group
+}
+
+/// An atom of a molecule drawn by [`add_ball_and_stick`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Atom {
+    /// The position of the atom center.
+    pub position: Vec3,
+    /// The radius of the atom sphere.
+    pub radius: f32,
+    /// The color of the atom and of its half of each bond.
+    pub color: Color,
+}
+
+impl Atom {
+    /// An atom at `position` with the given sphere `radius` and `color`.
+    pub fn new(position: Vec3, radius: f32, color: Color) -> Self {
+        Atom {
+            position,
+            radius,
+            color,
+        }
+    }
+}
+
+/// Adds a ball-and-stick model of a molecule to `parent`: a sphere per atom and
+/// a cylinder of radius `bond_radius` per bond, each bond being a pair of
+/// indices into `atoms`.
+///
+/// Each half of a bond takes the color of the atom it touches. All the atoms
+/// are drawn by a single instanced sphere and all the bonds by a single
+/// instanced cylinder, so large molecules stay cheap to render. Bonds with an
+/// out-of-range index are ignored.
+///
+/// Returns a group node with two children: the atoms, then the bonds.
+///
+/// # Example
+/// ```no_run
+/// # use kiss3d::prelude::*;
+/// # use kiss3d::viz::{self, Atom};
+/// # #[kiss3d::main]
+/// # async fn main() {
+/// # let mut window = Window::new("Example").await;
+/// # let mut camera = OrbitCamera3d::default();
+/// let mut scene = SceneNode3d::empty();
+/// // A water molecule.
+/// let atoms = [
+///     Atom::new(Vec3::ZERO, 0.3, RED),
+///     Atom::new(Vec3::new(0.76, 0.59, 0.0), 0.2, WHITE),
+///     Atom::new(Vec3::new(-0.76, 0.59, 0.0), 0.2, WHITE),
+/// ];
+/// viz::add_ball_and_stick(&mut scene, &atoms, &[(0, 1), (0, 2)], 0.08);
+///
+/// while window.render_3d(&mut scene, &mut camera).await {}
+/// # }
+/// ```
+pub fn add_ball_and_stick(
+    parent: &mut SceneNode3d,
+    atoms: &[Atom],
+    bonds: &[(usize, usize)],
+    bond_radius: f32,
+) -> SceneNode3d {
+    let mut group = parent.add_group();
+
+    let balls: Vec<InstanceData3d> = atoms
+        .iter()
+        .map(|atom| InstanceData3d {
+            position: atom.position,
+            deformation: Mat3::from_diagonal(Vec3::splat(atom.radius * 2.0)),
+            color: atom.color,
+            ..Default::default()
+        })
+        .collect();
+    // A unit-diameter sphere and a unit cylinder along `Y`, sized per instance.
+    group.add_sphere(0.5).set_instances(&balls);
+
+    let mut sticks = Vec::with_capacity(bonds.len() * 2);
+    for (a, b) in bonds {
+        let (Some(a), Some(b)) = (atoms.get(*a), atoms.get(*b)) else {
+            continue;
+        };
+        let axis = b.position - a.position;
+        let length = axis.length();
+        if length <= f32::EPSILON {
+            continue;
+        }
+        let rotation = Mat3::from_quat(Quat::from_rotation_arc(Vec3::Y, axis / length));
+        let deformation = rotation
+            * Mat3::from_diagonal(Vec3::new(
+                bond_radius * 2.0,
+                length * 0.5,
+                bond_radius * 2.0,
+            ));
+        for (atom, center) in [(a, 0.25), (b, 0.75)] {
+            sticks.push(InstanceData3d {
+                position: a.position + axis * center,
+                deformation,
+                color: atom.color,
+                ..Default::default()
+            });
+        }
+    }
+    group.add_cylinder(0.5, 1.0).set_instances(&sticks);
+
+    group
+}
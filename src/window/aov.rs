@@ -1,4 +1,5 @@
-//! Auxiliary render outputs (AOVs): depth, surface normals and segmentation.
+//! Auxiliary render outputs (AOVs): depth, surface normals, segmentation and
+//! velocity.
 //!
 //! These methods render the current 3D scene a second time, with a dedicated
 //! material that writes a geometric quantity instead of shaded color, then read
@@ -12,7 +13,6 @@
 use crate::builtin::{AovKind, AovRenderer};
 use crate::camera::Camera3d;
 use crate::context::Context;
-use crate::light::LightCollection;
 use crate::scene::SceneNode3d;
 use image::{ImageBuffer, Luma, Rgb};
 
@@ -54,6 +54,90 @@ impl Window {
         Self::depth_to_luma8(&depth, w, h)
     }
 
+    /// Returns the depth buffer of the last rendered frame as **linear, eye-space
+    /// depth** in world units.
+    ///
+    /// Unlike [`snap_depth_raw`](Self::snap_depth_raw), this does not render the
+    /// scene again: it reads back the depth buffer the frame was actually drawn
+    /// with (like [`snap_image`](Self::snap_image) does for color) and linearizes
+    /// it with that frame's camera projection, so it lines up exactly with the
+    /// color image. The image has a top-left origin; background pixels are
+    /// `0.0`. Under MSAA the first sample of each pixel is used. With a
+    /// [fixed aspect ratio](Self::set_fixed_aspect), it only covers the
+    /// letterboxed viewport.
+    ///
+    /// The conversion runs in a compute pass, so this returns `None` on devices
+    /// without compute shaders (WebGL2, see [`Context::supports_compute`]). It
+    /// also waits for the GPU, which the browser doesn't allow, so it always
+    /// returns `None` on the web.
+    pub fn snap_depth_image(&mut self) -> Option<ImageBuffer<Luma<f32>, Vec<f32>>> {
+        let (_, _, (w, h)) = self.scene_depth();
+        let data = self.read_linear_depth([0, 0, w, h])?;
+        Some(ImageBuffer::from_vec(w, h, data).expect("depth readback has the frame size"))
+    }
+
+    /// The linear depth of the last rendered frame at the window pixel `(x, y)`,
+    /// read back alone rather than with the whole depth buffer (see
+    /// [`snap_depth_image`](Self::snap_depth_image)). `None` outside of the
+    /// scene viewport and where [`snap_depth_image`](Self::snap_depth_image) is
+    /// unavailable.
+    pub(crate) fn depth_at(&mut self, x: u32, y: u32) -> Option<f32> {
+        let (x0, y0, w, h) = self.scene_viewport();
+        let (x, y) = (x.checked_sub(x0)?, y.checked_sub(y0)?);
+        if x >= w || y >= h {
+            return None;
+        }
+        self.read_linear_depth([x, y, 1, 1])?.first().copied()
+    }
+
+    /// The depth buffer the last frame was drawn with, as a view to sample it,
+    /// its sample count and its size.
+    fn scene_depth(&self) -> (wgpu::TextureView, u32, (u32, u32)) {
+        if let Some((texture, _)) = &self.letterbox_depth {
+            let size = (texture.width(), texture.height());
+            return (
+                Context::depth_sample_view(texture),
+                texture.sample_count(),
+                size,
+            );
+        }
+        let (w, h) = self.canvas.size();
+        let size = (w.max(1), h.max(1));
+        match self
+            .offscreen_output_target
+            .as_ref()
+            .filter(|_| self.hidden)
+            .and_then(|t| t.depth_sample_view())
+        {
+            Some(view) => (view, 1, size),
+            None => (
+                self.canvas.depth_sample_view(),
+                self.canvas.sample_count(),
+                size,
+            ),
+        }
+    }
+
+    /// Linearizes the `region` (`[x, y, width, height]`) of the last frame's
+    /// depth buffer.
+    fn read_linear_depth(&mut self, region: [u32; 4]) -> Option<Vec<f32>> {
+        if cfg!(target_arch = "wasm32") || !Context::get().supports_compute() {
+            return None;
+        }
+
+        let (depth_view, sample_count, (w, h)) = self.scene_depth();
+        let linearizer = self
+            .depth_linearizer
+            .get_or_insert_with(crate::builtin::DepthLinearizer::new);
+        Some(linearizer.read(
+            &depth_view,
+            sample_count,
+            (w, h),
+            region,
+            self.last_projection,
+        ))
+    }
+
     /// Renders the scene and returns its **world-space surface normals**.
     ///
     /// Each pixel stores the unit normal encoded from `[-1, 1]` into `[0, 255]`
@@ -113,6 +197,22 @@ impl Window {
         self.render_aov::<u32>(AovKind::Segmentation, scene, camera, 1)
     }
 
+    /// Renders the scene and returns the per-pixel **screen-space velocity**:
+    /// two values per pixel, the motion along `x` and `y` since the previous
+    /// call, in fractions of the image size (top-left origin, `y` down).
+    ///
+    /// The motion comes from the previous transform of each object and of the
+    /// camera, so call this once per frame, e.g. to export motion vectors along
+    /// with recorded frames. Objects seen for the first time, and the
+    /// background, read back as `0.0`. The buffer is row-major, top-left origin.
+    pub fn snap_velocity(
+        &mut self,
+        scene: &mut SceneNode3d,
+        camera: &mut dyn Camera3d,
+    ) -> Vec<f32> {
+        self.render_aov::<f32>(AovKind::Velocity, scene, camera, 2)
+    }
+
     /// Renders the scene and returns a **colorized segmentation** image.
     ///
     /// Each distinct object id is mapped to a deterministic, well-spread RGB
@@ -132,7 +232,7 @@ impl Window {
         img
     }
 
-    /// Renders an auxiliary output (depth, normals or segmentation) of the
+    /// Renders an auxiliary output (depth, normals, segmentation or velocity) of the
     /// scene as a **display-ready image** into the window's offscreen output
     /// texture, entirely on the GPU — no CPU read-back, so unlike the `snap_*`
     /// methods this also works on the web.
@@ -162,7 +262,7 @@ impl Window {
         // Make sure the camera matrices match the target size, then propagate
         // world transforms so the AOV renderer can read them per object.
         camera.update(&self.canvas);
-        let mut lights = LightCollection::with_ambient(self.ambient_intensity);
+        let mut lights = self.scene_lights();
         scene.data_mut().prepare(0, camera, &mut lights, w, h);
 
         // Raw AOV target (sampled by the visualize pass below) + depth.
@@ -285,7 +385,7 @@ impl Window {
         // Make sure the camera matrices match the target size, then propagate
         // world transforms so the AOV renderer can read them per object.
         camera.update(&self.canvas);
-        let mut lights = LightCollection::with_ambient(self.ambient_intensity);
+        let mut lights = self.scene_lights();
         scene.data_mut().prepare(0, camera, &mut lights, w, h);
 
         // Create the single-sampled color and depth targets for this AOV.
@@ -346,7 +446,7 @@ impl Window {
 /// must have `COPY_SRC` usage and store `channels` elements of type `T` per
 /// pixel (matching the AOV format). The result is row-major with a top-left
 /// origin.
-fn read_texture<T: bytemuck::Pod + Default>(
+pub(super) fn read_texture<T: bytemuck::Pod + Default>(
     texture: &wgpu::Texture,
     width: u32,
     height: u32,
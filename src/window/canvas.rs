@@ -1,5 +1,7 @@
 use std::sync::mpsc::Sender;
+use std::time::Duration;
 
+use crate::error::Error;
 use crate::event::{Action, Key, MouseButton, WindowEvent};
 use crate::window::WgpuCanvas;
 use image::{GenericImage, Pixel};
@@ -53,6 +55,39 @@ pub struct CanvasSetup {
     /// the ones kiss3d enables by default.
     #[cfg_attr(feature = "serde", serde(skip))]
     pub required_features: wgpu::Features,
+    /// The graphics backends the adapter may be picked from (e.g.
+    /// `wgpu::Backends::VULKAN` or `wgpu::Backends::GL`). Defaults to all of them.
+    ///
+    /// Like the other adapter options, this only applies when the wgpu context is
+    /// created, i.e. for the first window: later windows share its device.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub backends: wgpu::Backends,
+    /// Whether to prefer a low-power (integrated) or high-performance (discrete)
+    /// GPU. Defaults to `wgpu::PowerPreference::None`, letting wgpu decide.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub power_preference: wgpu::PowerPreference,
+    /// Forces wgpu to pick its fallback (software) adapter, e.g. on machines
+    /// without a usable GPU driver. Defaults to `false`.
+    pub force_fallback_adapter: bool,
+    /// The format of the depth buffers (see [`Context::set_depth_format`]):
+    /// `wgpu::TextureFormat::Depth32Float` (the default) for the most precise
+    /// depth, or `wgpu::TextureFormat::Depth24PlusStencil8` for a stencil
+    /// buffer, or for WebGL targets lacking float depth.
+    ///
+    /// Like the adapter options, this only applies when the wgpu context is
+    /// created, i.e. for the first window.
+    ///
+    /// [`Context::set_depth_format`]: crate::context::Context::set_depth_format
+    #[cfg_attr(feature = "serde", serde(skip, default = "default_depth_format"))]
+    pub depth_format: wgpu::TextureFormat,
+    /// Creates a window with a transparent background, so that kiss3d can draw
+    /// overlays on top of the desktop. The window background then defaults to a
+    /// fully transparent color. Support depends on the platform and compositor.
+    /// Defaults to `false`.
+    pub transparent: bool,
+    /// Keeps the window above all other (non always-on-top) windows. Ignored on the
+    /// web. Defaults to `false`.
+    pub always_on_top: bool,
 }
 
 impl Default for CanvasSetup {
@@ -62,10 +97,40 @@ impl Default for CanvasSetup {
             samples: NumSamples::Four,
             canvas_id: "canvas".to_string(),
             required_features: wgpu::Features::empty(),
+            backends: wgpu::Backends::all(),
+            power_preference: wgpu::PowerPreference::default(),
+            force_fallback_adapter: false,
+            depth_format: default_depth_format(),
+            transparent: false,
+            always_on_top: false,
         }
     }
 }
 
+fn default_depth_format() -> wgpu::TextureFormat {
+    wgpu::TextureFormat::Depth32Float
+}
+
+/// A display connected to the system, as returned by
+/// [`Window::monitors`](crate::window::Window::monitors).
+///
+/// Positions and sizes are in physical pixels, in the virtual desktop
+/// coordinates shared by all monitors.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MonitorInfo {
+    /// The human-readable name of the monitor, if the platform provides one.
+    pub name: Option<String>,
+    /// The position of the top-left corner of the monitor on the desktop.
+    pub position: (i32, i32),
+    /// The resolution of the monitor.
+    pub size: (u32, u32),
+    /// The HiDPI scale factor of the monitor.
+    pub scale_factor: f64,
+    /// Whether this is the primary monitor.
+    pub primary: bool,
+}
+
 /// An abstract structure representing a window for native applications, and a canvas for web applications.
 pub struct Canvas {
     canvas: WgpuCanvas,
@@ -76,7 +141,7 @@ impl Canvas {
     pub async fn open(
         window_attrs: WindowAttributes,
         canvas_setup: Option<CanvasSetup>,
-        out_events: Sender<WindowEvent>,
+        out_events: Sender<(f64, WindowEvent)>,
     ) -> Self {
         Canvas {
             canvas: WgpuCanvas::open(window_attrs, canvas_setup, out_events).await,
@@ -88,25 +153,107 @@ impl Canvas {
         width: u32,
         height: u32,
         canvas_setup: Option<CanvasSetup>,
-        out_events: Sender<WindowEvent>,
+        out_events: Sender<(f64, WindowEvent)>,
     ) -> Self {
         Canvas {
             canvas: WgpuCanvas::open_headless(width, height, canvas_setup, out_events).await,
         }
     }
 
+    /// Open a new window, and initialize the wgpu context, returning an error
+    /// instead of panicking on failure.
+    pub async fn try_open(
+        window_attrs: WindowAttributes,
+        canvas_setup: Option<CanvasSetup>,
+        out_events: Sender<(f64, WindowEvent)>,
+    ) -> Result<Self, Error> {
+        Ok(Canvas {
+            canvas: WgpuCanvas::try_open(window_attrs, canvas_setup, out_events).await?,
+        })
+    }
+
+    /// Open a headless canvas (no window) for off-screen rendering, returning an
+    /// error instead of panicking on failure.
+    pub async fn try_open_headless(
+        width: u32,
+        height: u32,
+        canvas_setup: Option<CanvasSetup>,
+        out_events: Sender<(f64, WindowEvent)>,
+    ) -> Result<Self, Error> {
+        Ok(Canvas {
+            canvas: WgpuCanvas::try_open_headless(width, height, canvas_setup, out_events).await?,
+        })
+    }
+
     /// Poll all events that occurred since the last call to this method.
     pub fn poll_events(&mut self) {
         self.canvas.poll_events()
     }
 
+    /// Waits for at least one event (or until `timeout` elapses), then polls the
+    /// events. Only polls on the web.
+    pub fn wait_events(&mut self, timeout: Option<std::time::Duration>) {
+        self.canvas.wait_events(timeout)
+    }
+
+    /// The instant event timestamps are measured from.
+    pub fn clock_start(&self) -> web_time::Instant {
+        self.canvas.clock_start()
+    }
+
+    /// The longest time between two presses of a mouse button for them to make a
+    /// double click.
+    pub fn double_click_threshold(&self) -> Duration {
+        self.canvas.double_click_threshold()
+    }
+
+    /// Sets the longest time between two presses of a mouse button for them to
+    /// make a double click.
+    pub fn set_double_click_threshold(&mut self, threshold: Duration) {
+        self.canvas.set_double_click_threshold(threshold)
+    }
+
+    /// Starts or stops ignoring live input in favor of [`Self::replay_event`].
+    #[cfg(feature = "replay")]
+    pub(crate) fn set_input_replay(&mut self, replay: bool) {
+        self.canvas.set_input_replay(replay)
+    }
+
+    /// Forwards a replayed input event as if it came from the platform.
+    #[cfg(feature = "replay")]
+    pub(crate) fn replay_event(&mut self, event: WindowEvent) {
+        self.canvas.replay_event(event)
+    }
+
+    /// Starts or stops logging the input events forwarded to the window.
+    #[cfg(feature = "replay")]
+    pub(crate) fn set_input_log(&mut self, enabled: bool) {
+        self.canvas.set_input_log(enabled)
+    }
+
+    /// The input events forwarded since the last call, while logging.
+    #[cfg(feature = "replay")]
+    pub(crate) fn take_input_log(&mut self) -> Vec<WindowEvent> {
+        self.canvas.take_input_log()
+    }
+
+    /// Whether the window is minimized or fully occluded.
+    pub fn is_occluded(&self) -> bool {
+        self.canvas.is_occluded()
+    }
+
+    /// Whether the window was created with a transparent background.
+    pub fn is_transparent(&self) -> bool {
+        self.canvas.is_transparent()
+    }
+
     /// Resizes the canvas render targets.
     pub fn resize(&mut self, width: u32, height: u32) {
         self.canvas.resize(width, height)
     }
 
     /// Gets the current surface texture for rendering.
-    pub fn get_current_texture(&self) -> Option<wgpu::SurfaceTexture> {
+    pub fn get_current_texture(&mut self) -> Option<wgpu::SurfaceTexture> {
         self.canvas.get_current_texture()
     }
 
@@ -120,6 +267,11 @@ impl Canvas {
         self.canvas.depth_view()
     }
 
+    /// Creates a view of the depth texture to sample it in a shader.
+    pub fn depth_sample_view(&self) -> wgpu::TextureView {
+        self.canvas.depth_sample_view()
+    }
+
     /// Gets the MSAA texture view if MSAA is enabled.
     pub fn msaa_view(&self) -> Option<&wgpu::TextureView> {
         self.canvas.msaa_view()
@@ -199,6 +351,36 @@ impl Canvas {
         self.canvas.hide()
     }
 
+    /// The position of the top-left corner of the window, decorations included.
+    pub fn outer_position(&self) -> Option<(i32, i32)> {
+        self.canvas.outer_position()
+    }
+
+    /// Moves the window. See `Window::set_outer_position` for details.
+    pub fn set_outer_position(&mut self, x: i32, y: i32) {
+        self.canvas.set_outer_position(x, y)
+    }
+
+    /// The monitors connected to the system.
+    pub fn monitors(&self) -> Vec<MonitorInfo> {
+        self.canvas.monitors()
+    }
+
+    /// Centers the window on the `i`-th monitor of `monitors`.
+    pub fn move_to_monitor(&mut self, i: usize) -> bool {
+        self.canvas.move_to_monitor(i)
+    }
+
+    /// Whether the window is fullscreen.
+    pub fn is_fullscreen(&self) -> bool {
+        self.canvas.is_fullscreen()
+    }
+
+    /// Enters or leaves fullscreen. See `Window::set_fullscreen` for details.
+    pub fn set_fullscreen(&mut self, fullscreen: bool) {
+        self.canvas.set_fullscreen(fullscreen)
+    }
+
     /// Show the window.
     pub fn show(&mut self) {
         self.canvas.show()
@@ -0,0 +1,72 @@
+//! Detection of the loss of the GPU device. Recovering from it is left to the
+//! application, which re-creates the window.
+
+use crate::context::Context;
+use crate::resource::{MaterialManager2d, MaterialManager3d};
+
+use super::Window;
+
+impl Window {
+    /// Whether the GPU device was lost, e.g. after a driver reset or a switch
+    /// of GPU on a laptop.
+    ///
+    /// Once the device is lost, the `render*` methods stop drawing and return
+    /// `false`. Every GPU resource (meshes, textures, materials) is then
+    /// unusable: to recover, drop the scenes and the window, and create them
+    /// again. Creating the window sets up a new GPU context, and a lost window
+    /// surface is re-created automatically while the device is alive.
+    ///
+    /// The window does not re-create a lost device by itself, as the GPU
+    /// resources of the scene nodes can't be re-uploaded behind the
+    /// application's back; [`on_device_lost`](Self::on_device_lost) is the
+    /// place to save what the application needs to rebuild its scenes.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// // A kiosk that survives driver resets.
+    /// loop {
+    ///     let mut window = Window::new("Kiosk").await;
+    ///     let mut camera = OrbitCamera3d::default();
+    ///     let mut scene = SceneNode3d::empty();
+    ///     scene.add_cube(1.0, 1.0, 1.0);
+    ///
+    ///     while window.render_3d(&mut scene, &mut camera).await {}
+    ///     if !window.is_device_lost() {
+    ///         break;
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn is_device_lost(&self) -> bool {
+        Context::is_initialized() && Context::get().is_device_lost()
+    }
+
+    /// Registers a callback run once when the GPU device is lost, e.g. to save
+    /// the application state before the window is re-created (see
+    /// [`is_device_lost`](Self::is_device_lost)).
+    pub fn on_device_lost(&mut self, callback: impl FnMut() + 'static) {
+        self.device_lost_callbacks.push(Box::new(callback));
+    }
+
+    /// Checks for the loss of the device, notifying the materials (see
+    /// `Material3d::on_device_lost`) and the callbacks the first time it is
+    /// detected. Returns `true` if the device is lost.
+    pub(super) fn handle_device_lost(&mut self) -> bool {
+        if !self.is_device_lost() {
+            return false;
+        }
+        if !self.device_lost_handled {
+            self.device_lost_handled = true;
+            log::error!("the GPU device was lost: the window stops rendering");
+            MaterialManager3d::get_global_manager(|mm| mm.device_lost());
+            MaterialManager2d::get_global_manager(|mm| mm.device_lost());
+            for callback in &mut self.device_lost_callbacks {
+                callback();
+            }
+        }
+        true
+    }
+}
@@ -1,15 +1,39 @@
 //! Drawing methods for 2D and 3D primitives.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use glamx::{Vec2, Vec3};
+use web_time::Instant;
 
+use crate::camera::{Camera2d, Camera3d};
 use crate::color::Color;
 use crate::renderer::{Polyline2d, Polyline3d};
-use crate::text::Font;
+use crate::text::{BitmapFont, Font, LabelDeclutter, LabelHandle, TextBounds, TextStyle};
 
 use super::Window;
 
+/// An immediate-mode primitive found under a point by [`Window::pick_planar`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PlanarPick {
+    /// The text at this index of [`Window::text_bounds_of_last_draws`].
+    Text(usize),
+    /// The 2D line or polyline drawn by the `i`-th call to
+    /// [`Window::draw_line_2d`] or [`Window::draw_polyline_2d`] of the last frame.
+    Polyline2d(usize),
+}
+
+/// A primitive drawn with one of the `draw_*_for` methods, re-submitted every
+/// frame until it expires.
+pub(super) enum TimedDraw {
+    Line(Vec3, Vec3, Color, f32, bool),
+    Line2d(Vec2, Vec2, Color, f32),
+    Point(Vec3, Color, f32),
+    Point2d(Vec2, Color, f32),
+    Text(String, Vec2, f32, Arc<Font>, Color),
+    Text3d(String, Vec3, f32, Arc<Font>, Color),
+}
+
 impl Window {
     /// Draws a 3D line for the current frame.
     ///
@@ -165,4 +189,357 @@ impl Window {
     pub fn draw_text(&mut self, text: &str, pos: Vec2, scale: f32, font: &Arc<Font>, color: Color) {
         self.text_renderer.draw_text(text, pos, scale, font, color);
     }
+
+    /// Draws text for the current frame using signed-distance-field glyphs.
+    ///
+    /// Same as [`Self::draw_text`], but the glyphs are reconstructed from a
+    /// distance field instead of being rasterized at `scale`, so the text stays
+    /// crisp when it is very large or when `scale` changes every frame.
+    ///
+    /// # Arguments
+    /// * `text` - The string to display
+    /// * `pos` - The position in 2D screen coordinates
+    /// * `scale` - The text scale factor
+    /// * `font` - A reference to the font to use
+    /// * `color` - RGBA color (each component from 0.0 to 1.0)
+    #[inline]
+    pub fn draw_text_sdf(
+        &mut self,
+        text: &str,
+        pos: Vec2,
+        scale: f32,
+        font: &Arc<Font>,
+        color: Color,
+    ) {
+        self.text_renderer
+            .draw_text_sdf(text, pos, scale, font, color);
+    }
+
+    /// Draws text for the current frame with a pre-rendered [`BitmapFont`].
+    ///
+    /// `scale` is relative to the [size](BitmapFont::size) the font was rendered
+    /// at: drawing at that size, at a whole-pixel position, is pixel-perfect.
+    ///
+    /// # Arguments
+    /// * `text` - The string to display
+    /// * `pos` - The position in 2D screen coordinates
+    /// * `scale` - The text scale factor
+    /// * `font` - A reference to the bitmap font to use
+    /// * `color` - RGBA color (each component from 0.0 to 1.0), multiplying the
+    ///   colors of the font's atlas
+    #[inline]
+    pub fn draw_text_bitmap(
+        &mut self,
+        text: &str,
+        pos: Vec2,
+        scale: f32,
+        font: &Arc<BitmapFont>,
+        color: Color,
+    ) {
+        self.text_renderer
+            .draw_text_bitmap(text, pos, scale, font, color);
+    }
+
+    /// Draws a camera-facing text label anchored at a 3D point for the current frame.
+    ///
+    /// The label is horizontally centered on `pos` with its baseline at `pos`,
+    /// and its line height is `height` world units, so it scales with distance
+    /// like the rest of the scene. It is rendered with signed-distance-field
+    /// glyphs on top of the scene (without depth testing).
+    ///
+    /// # Arguments
+    /// * `text` - The string to display
+    /// * `pos` - The anchor point in world coordinates
+    /// * `height` - The line height in world units
+    /// * `font` - A reference to the font to use
+    /// * `color` - RGBA color (each component from 0.0 to 1.0)
+    #[inline]
+    pub fn draw_text_3d(
+        &mut self,
+        text: &str,
+        pos: Vec3,
+        height: f32,
+        font: &Arc<Font>,
+        color: Color,
+    ) {
+        self.text_renderer
+            .draw_text_3d(text, pos, height, font, color);
+    }
+
+    /// Moves the [`Self::draw_text_3d`] labels apart when they overlap on
+    /// screen, with leader lines to their anchors, or lets them overlap if
+    /// `None` (the default).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let mut window = Window::new("Example").await;
+    /// # let mut camera = OrbitCamera3d::default();
+    /// # let mut scene = SceneNode3d::empty();
+    /// # let font = Font::default();
+    /// window.set_label_declutter(Some(LabelDeclutter::default()));
+    /// while window.render_3d(&mut scene, &mut camera).await {
+    ///     for i in 0..50 {
+    ///         let pos = Vec3::new((i % 10) as f32 * 0.2, (i / 10) as f32 * 0.1, 0.0);
+    ///         window.draw_text_3d(&format!("node {}", i), pos, 0.1, &font, WHITE);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn set_label_declutter(&mut self, declutter: Option<LabelDeclutter>) {
+        self.text_renderer.set_label_declutter(declutter)
+    }
+
+    /// How the [`Self::draw_text_3d`] labels are moved apart when they overlap.
+    pub fn label_declutter(&self) -> Option<LabelDeclutter> {
+        self.text_renderer.label_declutter()
+    }
+
+    /// Creates a persistent text label, drawn every frame until all the handles
+    /// to it are dropped.
+    ///
+    /// The label is laid out once and kept on the GPU, so it is much cheaper
+    /// than calling [`Self::draw_text_sdf`] every frame for text that rarely
+    /// changes. Move it with [`LabelHandle::set_position`]. See
+    /// [`TextRenderer::create_label`](crate::text::TextRenderer::create_label).
+    ///
+    /// # Arguments
+    /// * `text` - The string to display
+    /// * `style` - The scale, font and color of the text
+    pub fn create_label(&mut self, text: &str, style: &TextStyle) -> LabelHandle {
+        self.text_renderer.create_label(text, style)
+    }
+
+    /// Sets the extra space added after each character of the text drawn by
+    /// this window, as a fraction of the text scale (`0.0` by default).
+    /// Negative values tighten the text.
+    pub fn set_letter_spacing(&mut self, spacing: f32) {
+        self.text_renderer.set_letter_spacing(spacing)
+    }
+
+    /// Sets the distance between the lines of the multi-line text drawn by this
+    /// window, as a multiple of the font's line height (`1.0` by default).
+    pub fn set_line_spacing(&mut self, spacing: f32) {
+        self.text_renderer.set_line_spacing(spacing)
+    }
+
+    /// Enables or disables the kerning pairs of the fonts, which adjust the
+    /// space between specific characters like in "AV" (enabled by default).
+    pub fn set_kerning(&mut self, enabled: bool) {
+        self.text_renderer.set_kerning(enabled)
+    }
+
+    /// The size in pixels of `text` drawn at `scale` with `font` by
+    /// [`Self::draw_text`] or [`Self::draw_text_sdf`], e.g. to center or
+    /// right-align it.
+    pub fn measure_text(&self, text: &str, scale: f32, font: &Arc<Font>) -> Vec2 {
+        self.text_renderer.measure_text(text, scale, font)
+    }
+
+    /// The size in pixels of `text` drawn at `scale` with the bitmap font `font`
+    /// by [`Self::draw_text_bitmap`].
+    pub fn measure_text_bitmap(&self, text: &str, scale: f32, font: &BitmapFont) -> Vec2 {
+        self.text_renderer.measure_text_bitmap(text, scale, font)
+    }
+
+    /// The pixel-space bounds of the text drawn during the last rendered frame:
+    /// the [`Self::draw_text`], [`Self::draw_text_sdf`] and
+    /// [`Self::draw_text_bitmap`] calls in order, followed by the
+    /// [`Self::draw_text_3d`] labels in front of the camera, then the visible
+    /// [persistent labels](Self::create_label).
+    pub fn text_bounds_of_last_draws(&self) -> &[TextBounds] {
+        self.text_renderer.bounds_of_last_draws()
+    }
+
+    /// Finds the topmost immediate-mode text or 2D line under the pixel `(x, y)`
+    /// (from the top-left corner of the window, like [`Self::cursor_pos`]) in the
+    /// last rendered frame.
+    ///
+    /// Text is tested against its layout box and lines within 2 pixels of their
+    /// edge, so HUD elements drawn every frame can react to clicks without an UI
+    /// library. Text is drawn over lines and is therefore tested first.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let mut window = Window::new("Example").await;
+    /// # let mut camera = OrbitCamera3d::default();
+    /// # let mut scene = SceneNode3d::empty();
+    /// # let font = Font::default();
+    /// while window.render_3d(&mut scene, &mut camera).await {
+    ///     window.draw_text("Restart", Vec2::new(20.0, 20.0), 40.0, &font, WHITE);
+    ///     for event in window.events().iter() {
+    ///         if let WindowEvent::MouseButton(MouseButton::Button1, Action::Press, _) = event.value {
+    ///             if let Some((x, y)) = window.cursor_pos() {
+    ///                 if window.pick_planar(x as f32, y as f32) == Some(PlanarPick::Text(0)) {
+    ///                     // Restart clicked.
+    ///                 }
+    ///             }
+    ///         }
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn pick_planar(&self, x: f32, y: f32) -> Option<PlanarPick> {
+        let point = Vec2::new(x, y);
+        if let Some(i) = self
+            .text_renderer
+            .bounds_of_last_draws()
+            .iter()
+            .rposition(|b| b.contains(point))
+        {
+            return Some(PlanarPick::Text(i));
+        }
+        self.polyline_renderer_2d
+            .pick(point, 2.0)
+            .map(PlanarPick::Polyline2d)
+    }
+
+    /// Draws a 3D line during `duration`, without calling this every frame.
+    ///
+    /// Useful for transient events (collisions, ray casts) that should stay
+    /// visible for a while. See [`Self::draw_line`] for the other arguments.
+    pub fn draw_line_for(
+        &mut self,
+        duration: Duration,
+        a: Vec3,
+        b: Vec3,
+        color: Color,
+        width: f32,
+        perspective: bool,
+    ) {
+        self.push_timed_draw(duration, TimedDraw::Line(a, b, color, width, perspective));
+    }
+
+    /// Draws a 2D line during `duration`, without calling this every frame.
+    ///
+    /// See [`Self::draw_line_2d`] for the other arguments.
+    pub fn draw_line_2d_for(
+        &mut self,
+        duration: Duration,
+        a: Vec2,
+        b: Vec2,
+        color: Color,
+        width: f32,
+    ) {
+        self.push_timed_draw(duration, TimedDraw::Line2d(a, b, color, width));
+    }
+
+    /// Draws a 3D point during `duration`, without calling this every frame.
+    ///
+    /// See [`Self::draw_point`] for the other arguments.
+    pub fn draw_point_for(&mut self, duration: Duration, pt: Vec3, color: Color, size: f32) {
+        self.push_timed_draw(duration, TimedDraw::Point(pt, color, size));
+    }
+
+    /// Draws a 2D point during `duration`, without calling this every frame.
+    ///
+    /// See [`Self::draw_point_2d`] for the other arguments.
+    pub fn draw_point_2d_for(&mut self, duration: Duration, pt: Vec2, color: Color, size: f32) {
+        self.push_timed_draw(duration, TimedDraw::Point2d(pt, color, size));
+    }
+
+    /// Draws screen-space text during `duration`, without calling this every
+    /// frame.
+    ///
+    /// See [`Self::draw_text`] for the other arguments.
+    pub fn draw_text_for(
+        &mut self,
+        duration: Duration,
+        text: &str,
+        pos: Vec2,
+        scale: f32,
+        font: &Arc<Font>,
+        color: Color,
+    ) {
+        let draw = TimedDraw::Text(text.to_string(), pos, scale, font.clone(), color);
+        self.push_timed_draw(duration, draw);
+    }
+
+    /// Draws a 3D text label during `duration`, without calling this every
+    /// frame.
+    ///
+    /// See [`Self::draw_text_3d`] for the other arguments.
+    pub fn draw_text_3d_for(
+        &mut self,
+        duration: Duration,
+        text: &str,
+        pos: Vec3,
+        height: f32,
+        font: &Arc<Font>,
+        color: Color,
+    ) {
+        let draw = TimedDraw::Text3d(text.to_string(), pos, height, font.clone(), color);
+        self.push_timed_draw(duration, draw);
+    }
+
+    /// Removes every primitive drawn with one of the `draw_*_for` methods before
+    /// it expires.
+    pub fn clear_timed_draws(&mut self) {
+        self.timed_draws.clear();
+    }
+
+    fn push_timed_draw(&mut self, duration: Duration, draw: TimedDraw) {
+        self.timed_draws.push((Instant::now() + duration, draw));
+    }
+
+    /// Projects the [`Self::draw_text_3d`] labels of the frame being rendered
+    /// and draws the leader lines of the labels moved by the declutter.
+    /// `camera_2d` maps their pixel coordinates to the 2D line renderer.
+    pub(super) fn project_text_labels(
+        &mut self,
+        camera: &dyn Camera3d,
+        camera_2d: &dyn Camera2d,
+        (x, y, width, height): (u32, u32, u32, u32),
+    ) {
+        let size = Vec2::new(width as f32, height as f32);
+        let offset = Vec2::new(x as f32, y as f32);
+        let leader_lines = self
+            .text_renderer
+            .project_labels(camera, offset, size.x, size.y);
+        let Some(declutter) = self.text_renderer.label_declutter() else {
+            return;
+        };
+        let Some(color) = declutter.leader_color else {
+            return;
+        };
+        for (a, b) in leader_lines {
+            let a = camera_2d.unproject(a, size);
+            let b = camera_2d.unproject(b, size);
+            self.draw_line_2d(a, b, color, declutter.leader_width);
+        }
+    }
+
+    /// Drops the expired timed primitives and submits the others for the frame
+    /// being rendered.
+    pub(super) fn submit_timed_draws(&mut self) {
+        if self.timed_draws.is_empty() {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut draws = std::mem::take(&mut self.timed_draws);
+        draws.retain(|(expiry, _)| *expiry > now);
+        for (_, draw) in &draws {
+            match draw {
+                TimedDraw::Line(a, b, color, width, perspective) => {
+                    self.draw_line(*a, *b, *color, *width, *perspective)
+                }
+                TimedDraw::Line2d(a, b, color, width) => self.draw_line_2d(*a, *b, *color, *width),
+                TimedDraw::Point(pt, color, size) => self.draw_point(*pt, *color, *size),
+                TimedDraw::Point2d(pt, color, size) => self.draw_point_2d(*pt, *color, *size),
+                TimedDraw::Text(text, pos, scale, font, color) => {
+                    self.draw_text(text, *pos, *scale, font, *color)
+                }
+                TimedDraw::Text3d(text, pos, height, font, color) => {
+                    self.draw_text_3d(text, *pos, *height, font, *color)
+                }
+            }
+        }
+        self.timed_draws = draws;
+    }
 }
@@ -121,6 +121,13 @@ impl Window {
         let scale_factor = self.scale_factor() as f32;
 
         match *event {
+            WindowEvent::ScaleFactorChanged(factor) => {
+                // Re-layout at the new DPI right away instead of next frame.
+                self.egui_context
+                    .renderer
+                    .context()
+                    .set_pixels_per_point(factor as f32);
+            }
             WindowEvent::CursorPos(x, y, _) => {
                 // Convert physical pixels to logical coordinates
                 let pos = egui::Pos2::new((x as f32) / scale_factor, (y as f32) / scale_factor);
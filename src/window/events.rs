@@ -2,7 +2,10 @@
 
 use crate::camera::Camera2d;
 use crate::camera::Camera3d;
+use crate::camera::Ray;
 use crate::event::{Action, EventManager, Key, MouseButton, WindowEvent};
+use glamx::{Vec2, Vec3};
+use std::time::Duration;
 
 use super::Window;
 
@@ -42,6 +45,39 @@ impl Window {
         EventManager::new(self.events.clone(), self.unhandled_events.clone())
     }
 
+    /// The longest time between two presses of a mouse button for them to make a
+    /// [`WindowEvent::DoubleClick`] (default: 500 ms).
+    pub fn double_click_threshold(&self) -> Duration {
+        self.canvas.double_click_threshold()
+    }
+
+    /// Sets the longest time between two presses of a mouse button for them to
+    /// make a [`WindowEvent::DoubleClick`]. The cursor must also stay within a
+    /// few pixels between both presses.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # use std::time::Duration;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let mut window = Window::new("Example").await;
+    /// # let mut camera = OrbitCamera3d::default();
+    /// # let mut scene = SceneNode3d::empty();
+    /// window.set_double_click_threshold(Duration::from_millis(300));
+    /// while window.render_3d(&mut scene, &mut camera).await {
+    ///     for event in window.events().iter() {
+    ///         if let WindowEvent::DoubleClick(MouseButton::Button1, _) = event.value {
+    ///             println!("Double click at {:.3} s", event.timestamp);
+    ///         }
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn set_double_click_threshold(&mut self, threshold: Duration) {
+        self.canvas.set_double_click_threshold(threshold)
+    }
+
     /// Gets the current state of a keyboard key.
     ///
     /// # Arguments
@@ -75,25 +111,111 @@ impl Window {
         self.canvas.cursor_pos()
     }
 
+    /// The world-space ray through the mouse cursor, as seen by `camera`, for
+    /// picking or dragging objects (see [`DragPlane`](crate::camera::DragPlane)).
+    ///
+    /// Returns `None` if the cursor position is unknown.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let mut window = Window::new("Example").await;
+    /// # let mut camera = OrbitCamera3d::default();
+    /// # let mut scene = SceneNode3d::empty();
+    /// while window.render_3d(&mut scene, &mut camera).await {
+    ///     if let Some(ray) = window.cursor_ray(&camera) {
+    ///         // The point of the ground plane under the cursor.
+    ///         if let Some(t) = ray.intersect_plane(Vec3::ZERO, Vec3::Y) {
+    ///             window.draw_point(ray.point_at(t), RED, 8.0);
+    ///         }
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn cursor_ray(&self, camera: &dyn Camera3d) -> Option<Ray> {
+        let (x, y) = self.cursor_pos()?;
+        Some(self.screen_ray(camera, Vec2::new(x as f32, y as f32)))
+    }
+
+    /// The world-space ray through the window pixel `pos` (physical pixels from
+    /// the top-left corner, like [`cursor_pos`](Self::cursor_pos)), as seen by
+    /// `camera`.
+    ///
+    /// Unlike calling [`Camera3d::unproject`] with the window size, this accounts
+    /// for the letterboxed viewport of a fixed aspect ratio (see
+    /// [`set_fixed_aspect`](Self::set_fixed_aspect)).
+    pub fn screen_ray(&self, camera: &dyn Camera3d, pos: Vec2) -> Ray {
+        let (x0, y0, vw, vh) = self.scene_viewport();
+        let (origin, dir) = camera.unproject(
+            pos - Vec2::new(x0 as f32, y0 as f32),
+            Vec2::new(vw as f32, vh as f32),
+        );
+        Ray::new(origin, dir)
+    }
+
+    /// The world-space point of the last rendered frame's geometry under the
+    /// window pixel `pos` (physical pixels from the top-left corner), as seen by
+    /// `camera`, or `None` if there is only background there.
+    ///
+    /// The depth of that single pixel is read back from the GPU and waited for,
+    /// so call this on clicks rather than every frame. Like
+    /// [`snap_depth_image`](Self::snap_depth_image), always `None` on devices
+    /// without compute shaders (WebGL2) and on the web.
+    pub fn scene_point_at(&mut self, camera: &dyn Camera3d, pos: Vec2) -> Option<Vec3> {
+        if pos.x < 0.0 || pos.y < 0.0 {
+            return None;
+        }
+        let depth = self.depth_at(pos.x as u32, pos.y as u32)?;
+        if !(depth > 0.0 && depth.is_finite()) {
+            return None;
+        }
+
+        // The point of the ray at the given eye-space depth along the view direction.
+        let ray = self.screen_ray(camera, pos);
+        let forward = camera.view_transform().rotation.inverse() * -Vec3::Z;
+        let along = ray.dir.dot(forward);
+        if along <= 1.0e-6 {
+            return None;
+        }
+        let t = (depth - (ray.origin - camera.eye()).dot(forward)) / along;
+        Some(ray.point_at(t))
+    }
+
     #[inline]
+    /// Handles the events received since the last frame, then polls the new
+    /// ones. Returns whether any event was handled.
     pub(crate) fn handle_events(
         &mut self,
         camera: &mut dyn Camera3d,
         camera_2d: &mut dyn Camera2d,
-    ) {
+    ) -> bool {
         let unhandled_events = self.unhandled_events.clone(); // TODO: could we avoid the clone?
         let events = self.events.clone(); // TODO: could we avoid the clone?
+        let mut handled_any = false;
 
-        for event in unhandled_events.borrow().iter() {
-            self.handle_event(camera, camera_2d, event)
+        for (_, event) in unhandled_events.borrow().iter() {
+            self.handle_event(camera, camera_2d, event);
+            handled_any = true;
         }
 
-        for event in events.try_iter() {
-            self.handle_event(camera, camera_2d, &event)
+        for (_, event) in events.try_iter() {
+            self.handle_event(camera, camera_2d, &event);
+            handled_any = true;
         }
 
         unhandled_events.borrow_mut().clear();
+        if let Some(pos) = camera.take_pick_request() {
+            // Cameras see cursor positions relative to the scene viewport.
+            let (x0, y0, _, _) = self.scene_viewport();
+            let point = self.scene_point_at(camera, pos + Vec2::new(x0 as f32, y0 as f32));
+            camera.set_picked_point(point);
+        }
         self.canvas.poll_events();
+        #[cfg(feature = "replay")]
+        self.update_replay();
+        handled_any
     }
 
     pub(crate) fn handle_event(
@@ -102,6 +224,8 @@ impl Window {
         camera_2d: &mut dyn Camera2d,
         event: &WindowEvent,
     ) {
+        self.pause_turntable_on(event);
+
         if let Some(binding_key) = self.close_key {
             if let WindowEvent::Key(key, Action::Release, modifiers) = event {
                 if binding_key == *key
@@ -112,6 +236,14 @@ impl Window {
             }
         }
 
+        if let Some((screenshot_key, _)) = &self.screenshot_key {
+            if let WindowEvent::Key(key, Action::Release, _) = event {
+                if key == screenshot_key {
+                    self.screenshot_requested = true;
+                }
+            }
+        }
+
         #[cfg(feature = "rt_switcher")]
         match event {
             WindowEvent::Key(Key::F4, Action::Release, _) => {
@@ -136,6 +268,11 @@ impl Window {
             self.close();
         }
 
+        if let WindowEvent::ScaleFactorChanged(_) = event {
+            // `UiScale::Auto` follows the new scale factor.
+            self.apply_ui_scale();
+        }
+
         // Feed events to egui and check if it wants to capture input
         #[cfg(feature = "egui")]
         {
@@ -150,7 +287,25 @@ impl Window {
             }
         }
 
-        camera.handle_event(&self.canvas, event);
-        camera_2d.handle_event(&self.canvas, event);
+        let event = self.to_scene_viewport(event);
+        camera.handle_event(&self.canvas, &event);
+        camera_2d.handle_event(&self.canvas, &event);
+    }
+
+    /// Converts the window coordinates of `event` to the coordinates of the
+    /// [scene viewport](Self::scene_viewport) seen by the cameras, which differ
+    /// with a fixed aspect ratio.
+    fn to_scene_viewport(&self, event: &WindowEvent) -> WindowEvent {
+        if self.letterbox.is_none() {
+            return *event;
+        }
+        let (x0, y0, w, h) = self.scene_viewport();
+        match *event {
+            WindowEvent::CursorPos(x, y, modifiers) => {
+                WindowEvent::CursorPos(x - x0 as f64, y - y0 as f64, modifiers)
+            }
+            WindowEvent::FramebufferSize(..) => WindowEvent::FramebufferSize(w, h),
+            event => event,
+        }
     }
 }
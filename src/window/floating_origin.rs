@@ -0,0 +1,135 @@
+//! Camera-relative rendering of large worlds with double-precision positions.
+
+use glamx::glam::DVec3;
+use glamx::Vec3;
+
+use crate::camera::Camera3d;
+use crate::scene::SceneNode3d;
+
+use super::Window;
+
+impl Window {
+    /// Enables or disables camera-relative rendering, for worlds spanning
+    /// distances at which `f32` coordinates jitter (beyond about `1.0e5` units
+    /// from the origin, e.g. planets or spacecraft).
+    ///
+    /// Nodes placed with [`SceneNode3d::set_position_f64`] keep their position in
+    /// double precision. Every frame, the world origin is moved to the camera,
+    /// whose position is brought back near zero with
+    /// [`Camera3d::shift_origin`], and the scene is rebased around it (see
+    /// [`SceneNode3d::rebase`]), so everything is rendered with `f32`
+    /// coordinates relative to the eye. The camera and the `f32` positions of
+    /// the scene are thus in *render space*; use
+    /// [`to_render_space`](Self::to_render_space) and
+    /// [`from_render_space`](Self::from_render_space) to convert, e.g. for the
+    /// immediate-mode drawing functions.
+    ///
+    /// When the origin moves with the camera, the nodes without any
+    /// double-precision position in their subtree, the decals and the
+    /// reflection probes are translated the other way, so they keep their world
+    /// position (with `f32` precision).
+    ///
+    /// Cameras that cannot be moved (see [`Camera3d::shift_origin`]) keep the
+    /// world origin where it is; the scene is still rebased around it.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # use kiss3d::glamx::glam::DVec3;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let mut window = Window::new("Example").await;
+    /// let mut scene = SceneNode3d::empty();
+    /// let earth_orbit = DVec3::new(1.496e11, 0.0, 0.0);
+    /// let mut probe = scene.add_cube(1.0, 1.0, 1.0);
+    /// probe.set_position_f64(earth_orbit);
+    ///
+    /// window.set_camera_relative(true);
+    /// window.set_world_origin(earth_orbit);
+    /// let mut camera = OrbitCamera3d::new(Vec3::new(0.0, 2.0, 5.0), Vec3::ZERO);
+    /// while window.render_3d(&mut scene, &mut camera).await {}
+    /// # }
+    /// ```
+    pub fn set_camera_relative(&mut self, enabled: bool) {
+        self.camera_relative = enabled;
+    }
+
+    /// Whether camera-relative rendering is enabled (see
+    /// [`set_camera_relative`](Self::set_camera_relative)).
+    pub fn is_camera_relative(&self) -> bool {
+        self.camera_relative
+    }
+
+    /// The world position, in double precision, of the origin of render space.
+    pub fn world_origin(&self) -> DVec3 {
+        self.world_origin
+    }
+
+    /// Moves the origin of render space to the world position `origin`.
+    ///
+    /// The nodes with a double-precision position are rebased on the next frame;
+    /// the camera is not moved, as it lives in render space.
+    pub fn set_world_origin(&mut self, origin: DVec3) {
+        self.world_origin = origin;
+    }
+
+    /// Converts a double-precision world position to render space.
+    pub fn to_render_space(&self, position: DVec3) -> Vec3 {
+        (position - self.world_origin).as_vec3()
+    }
+
+    /// Converts a render-space position to a double-precision world position.
+    pub fn from_render_space(&self, position: Vec3) -> DVec3 {
+        self.world_origin + position.as_dvec3()
+    }
+
+    /// Moves the world origin to the camera if camera-relative rendering is
+    /// enabled, translating the `f32` nodes of `scene`, the decals and the
+    /// reflection probes so they stay put, then rebases `scene` around the world
+    /// origin if the origin, the scene or a double-precision position changed
+    /// since the last rebase.
+    pub(super) fn rebase_world(
+        &mut self,
+        scene: Option<&mut SceneNode3d>,
+        camera: &mut dyn Camera3d,
+    ) {
+        let mut shift = None;
+        if self.camera_relative {
+            let eye = camera.eye();
+            if eye != Vec3::ZERO && eye.is_finite() && camera.shift_origin(eye) {
+                self.world_origin += eye.as_dvec3();
+                shift = Some(-eye);
+            }
+        }
+        if let Some(offset) = shift {
+            // What lives in render space with `f32` coordinates must move the
+            // other way to stay put in the world.
+            if let Some(decals) = &mut self.decals {
+                decals.translate(offset);
+            }
+            if let Some(probes) = &mut self.reflection_probes {
+                for i in 0..probes.len() {
+                    if let Some(probe) = probes.probe_mut(i) {
+                        probe.center += offset;
+                    }
+                }
+            }
+        }
+        if let Some(scene) = scene {
+            if let Some(offset) = shift {
+                scene.shift_f32_nodes(offset);
+            }
+            // Walking the whole tree every frame is wasted when nothing moved.
+            let key = (
+                self.world_origin,
+                scene.id(),
+                SceneNode3d::precise_position_edits(),
+            );
+            if self.last_rebase != Some(key) {
+                scene.rebase(self.world_origin);
+                // Rebasing edits the double-precision nodes it moves.
+                self.last_rebase = Some((key.0, key.1, SceneNode3d::precise_position_edits()));
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Callbacks run by the window at the start of each frame.
+
+use crate::context::FrameTime;
+use crate::scene::{SceneNode2d, SceneNode3d};
+
+use super::Window;
+
+/// A callback registered with [`Window::on_frame`].
+pub(super) type FrameCallback = Box<dyn FnMut(&mut FrameContext<'_>)>;
+
+/// The frame being rendered, given to the callbacks registered with
+/// [`Window::on_frame`].
+pub struct FrameContext<'a> {
+    /// Seconds elapsed since the window was created.
+    pub time: f32,
+    /// Seconds elapsed since the previous frame (`0` on the first frame).
+    pub delta: f32,
+    /// Number of frames rendered by the window, including this one.
+    pub frame: u64,
+    /// The 3D scene being rendered, if any.
+    pub scene: Option<&'a mut SceneNode3d>,
+    /// The 2D scene being rendered, if any.
+    pub scene_2d: Option<&'a mut SceneNode2d>,
+}
+
+impl Window {
+    /// Registers a callback run at the start of every frame, before the cameras
+    /// are updated and the scenes drawn.
+    ///
+    /// This gives integrations a single place to update the scene from external
+    /// data, e.g. scaling objects with the spectrum of the audio being played,
+    /// or moving them as network updates come in. The callbacks run in
+    /// registration order, by every `render*` method of this window.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # use std::sync::mpsc;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let mut window = Window::new("Example").await;
+    /// # let mut camera = OrbitCamera3d::default();
+    /// let mut scene = SceneNode3d::empty();
+    /// let mut bar = scene.add_cube(0.2, 1.0, 0.2);
+    /// // Levels sent by an audio analysis thread.
+    /// let (_sender, levels) = mpsc::channel::<f32>();
+    /// let mut level = 0.0;
+    ///
+    /// window.on_frame(move |frame| {
+    ///     if let Some(new_level) = levels.try_iter().last() {
+    ///         level = new_level;
+    ///     }
+    ///     // Ease towards the latest level.
+    ///     let height = bar.local_scale().y;
+    ///     let height = height + (level - height) * (10.0 * frame.delta).min(1.0);
+    ///     bar.set_local_scale(1.0, height, 1.0);
+    /// });
+    ///
+    /// while window.render_3d(&mut scene, &mut camera).await {}
+    /// # }
+    /// ```
+    pub fn on_frame(&mut self, callback: impl FnMut(&mut FrameContext<'_>) + 'static) {
+        self.frame_callbacks.push(Box::new(callback));
+    }
+
+    /// Removes all the callbacks registered with [`on_frame`](Self::on_frame).
+    pub fn clear_frame_callbacks(&mut self) {
+        self.frame_callbacks.clear();
+    }
+
+    /// Runs the callbacks registered with [`on_frame`](Self::on_frame).
+    pub(super) fn run_frame_callbacks(
+        &mut self,
+        frame_time: FrameTime,
+        scene: Option<&mut SceneNode3d>,
+        scene_2d: Option<&mut SceneNode2d>,
+    ) {
+        if self.frame_callbacks.is_empty() {
+            return;
+        }
+
+        let mut context = FrameContext {
+            time: frame_time.time,
+            delta: frame_time.delta,
+            frame: frame_time.frame,
+            scene,
+            scene_2d,
+        };
+        for callback in &mut self.frame_callbacks {
+            callback(&mut context);
+        }
+    }
+}
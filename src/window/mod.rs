@@ -2,27 +2,41 @@
 
 mod aov;
 mod canvas;
+mod device_lost;
 mod drawing;
 #[cfg(feature = "egui")]
 mod egui_integration;
 mod events;
+mod floating_origin;
+mod frame_callback;
 #[cfg(feature = "egui")]
 mod inspector;
 mod offscreen;
+#[cfg(feature = "rapier2d")]
+mod rapier2d_debug;
 #[cfg(feature = "recording")]
 mod recording;
 mod rendering;
+#[cfg(feature = "replay")]
+mod replay;
+mod rubber_band;
 mod screenshot;
+mod stats_overlay;
+mod turntable;
 mod wgpu_canvas;
 mod window;
 mod window_cache;
 
-pub use canvas::{Canvas, CanvasSetup, NumSamples};
+pub use canvas::{Canvas, CanvasSetup, MonitorInfo, NumSamples};
+pub use drawing::PlanarPick;
+pub use frame_callback::FrameContext;
 #[cfg(feature = "egui")]
 pub use inspector::{Inspector, InspectorTab};
 pub use offscreen::OffscreenSurface;
 #[cfg(feature = "recording")]
 pub use recording::RecordingConfig;
+#[cfg(feature = "replay")]
+pub use replay::ReplayFrame;
 pub use wgpu_canvas::WgpuCanvas;
-pub use window::Window;
+pub use window::{Aa, ClearMode, RedrawMode, TransparencyMode, UiScale, Window};
 pub(crate) use window_cache::WINDOW_CACHE;
@@ -238,6 +238,14 @@ impl OffscreenSurface {
         self.window.snap_depth_raw(scene, camera)
     }
 
+    /// Returns the depth buffer of the last `render_3d` as linear, eye-space
+    /// depth (in world units), with a top-left origin. See
+    /// [`Window::snap_depth_image`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn snap_depth_image(&mut self) -> Option<ImageBuffer<Luma<f32>, Vec<f32>>> {
+        self.window.snap_depth_image()
+    }
+
     /// Renders the scene and returns its depth as a normalized 8-bit grayscale
     /// image (nearest surface brightest, background black). See
     /// [`Window::snap_depth`].
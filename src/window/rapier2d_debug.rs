@@ -0,0 +1,83 @@
+//! Debug rendering of rapier2d physics scenes.
+
+use glamx::Vec2;
+use rapier2d::math::Vector;
+use rapier2d::pipeline::{DebugColor, DebugRenderBackend, DebugRenderObject, DebugRenderPipeline};
+use rapier2d::prelude::{
+    ColliderSet, ImpulseJointSet, MultibodyJointSet, NarrowPhase, RigidBodySet,
+};
+
+use crate::color::Color;
+
+use super::Window;
+
+/// Forwards the lines emitted by rapier's debug-render pipeline to the planar
+/// polyline renderer.
+struct Rapier2dBackend<'a> {
+    window: &'a mut Window,
+    width: f32,
+}
+
+impl DebugRenderBackend for Rapier2dBackend<'_> {
+    fn draw_line(&mut self, _object: DebugRenderObject, a: Vector, b: Vector, color: DebugColor) {
+        self.window.draw_line_2d(
+            Vec2::new(a.x, a.y),
+            Vec2::new(b.x, b.y),
+            hsla_to_color(color),
+            self.width,
+        );
+    }
+}
+
+/// Converts rapier's HSLA debug colors (hue in degrees) to an RGBA [`Color`].
+fn hsla_to_color([h, s, l, a]: DebugColor) -> Color {
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let hp = (h / 60.0).rem_euclid(6.0);
+    let x = c * (1.0 - (hp % 2.0 - 1.0).abs());
+    let (r, g, b) = match hp as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = l - c * 0.5;
+    Color::new(r + m, g + m, b + m, a)
+}
+
+impl Window {
+    /// Draws a rapier2d physics world for the current frame: colliders, joints,
+    /// contacts, AABBs, … as selected by the [`DebugRenderPipeline`]'s mode and
+    /// styled by its style.
+    ///
+    /// Lines are drawn with the planar (2D) renderer with a width of `line_width`
+    /// pixels, so they show up with the 2D camera passed to the render call. Call
+    /// this every frame, after stepping the simulation.
+    ///
+    /// **Note:** This requires the `rapier2d` feature.
+    #[allow(clippy::too_many_arguments)]
+    pub fn debug_render_rapier2d(
+        &mut self,
+        pipeline: &mut DebugRenderPipeline,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        impulse_joints: &ImpulseJointSet,
+        multibody_joints: &MultibodyJointSet,
+        narrow_phase: &NarrowPhase,
+        line_width: f32,
+    ) {
+        let mut backend = Rapier2dBackend {
+            window: self,
+            width: line_width,
+        };
+        pipeline.render(
+            &mut backend,
+            bodies,
+            colliders,
+            impulse_joints,
+            multibody_joints,
+            narrow_phase,
+        );
+    }
+}
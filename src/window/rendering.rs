@@ -3,19 +3,20 @@
 #![allow(clippy::await_holding_refcell_ref)]
 
 use crate::camera::{Camera2d, Camera3d, FixedView3d};
-use crate::context::Context;
+use crate::context::{Context, FrameTime};
 use crate::event::WindowEvent;
 use crate::light::LightCollection;
 use crate::post_processing::{PostProcessingContext, PostProcessingEffect};
 use crate::prelude::FixedView2d;
-use crate::renderer::timings::{CpuTimer, RenderTimings};
+use crate::renderer::timings::{self, CpuTimer, RenderTimings};
 use crate::renderer::{RayTracer, Renderer3d};
 use crate::resource::{
     MaterialManager2d, MaterialManager3d, RenderContext, RenderContext2d, RenderContext2dEncoder,
-    RenderPhase, RenderTarget,
+    RenderPhase, RenderTarget, TextureManager,
 };
 use crate::scene::{SceneNode2d, SceneNode3d};
 
+use super::window::{ClearMode, RedrawMode, TransparencyMode};
 use super::Window;
 
 /// Grace period during which the first frame keeps retrying surface acquisition
@@ -28,6 +29,34 @@ const STARTUP_SURFACE_TIMEOUT: std::time::Duration = std::time::Duration::from_s
 #[cfg(not(target_arch = "wasm32"))]
 const SURFACE_RETRY_INTERVAL: std::time::Duration = std::time::Duration::from_millis(16);
 
+/// How long a minimized or occluded window sleeps between event polls.
+const OCCLUDED_WAIT_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// The longest an idle window in [`RedrawMode::OnDemand`] waits for an event
+/// before returning from its `render_*` call, so the application gets to poll
+/// its own sources of change (data feeds, timers) and call
+/// [`Window::request_redraw`](super::Window::request_redraw).
+const ON_DEMAND_WAIT_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// Yields to the browser until its next animation frame.
+#[cfg(target_arch = "wasm32")]
+async fn wait_animation_frame() {
+    use wasm_bindgen::JsCast;
+    use web_sys::wasm_bindgen::closure::Closure;
+
+    if let Some(window) = web_sys::window() {
+        let (s, r) = oneshot::channel();
+
+        let closure = Closure::once(move || s.send(()).unwrap());
+
+        window
+            .request_animation_frame(closure.as_ref().unchecked_ref())
+            .unwrap();
+
+        r.await.unwrap();
+    }
+}
+
 impl Window {
     /// Renders one frame of a 3D scene.
     ///
@@ -174,7 +203,10 @@ impl Window {
 
         let camera = camera.unwrap_or(&mut default_cam);
         let camera_2d = camera_2d.unwrap_or(&mut default_cam2);
-        self.handle_events(camera, camera_2d);
+        let handled_events = self.handle_events(camera, camera_2d);
+        if !self.wait_for_redraw(handled_events).await {
+            return !self.should_close();
+        }
         self.render_single_frame(
             scene,
             scene_2d,
@@ -195,6 +227,11 @@ impl Window {
         mut renderer: Option<&mut dyn Renderer3d>,
         post_processing: &mut [&mut dyn PostProcessingEffect],
     ) -> bool {
+        if self.handle_device_lost() {
+            self.discard_frame_draws();
+            return false;
+        }
+
         // Frame timing: CPU wall-clock for the whole frame (and submit/present
         // below) plus per-pass GPU timestamps recorded into the GPU timer. The
         // frame-to-frame wall-clock period (true FPS) is the delta between
@@ -206,7 +243,17 @@ impl Window {
             .map(|prev| frame_start.duration_since(prev))
             .unwrap_or_default();
         self.last_frame_instant = Some(frame_start);
+        self.frame_count += 1;
+        let frame_time = FrameTime {
+            time: frame_start.duration_since(self.clock_start).as_secs_f32(),
+            delta: frame_wall.as_secs_f32(),
+            frame: self.frame_count,
+        };
+        #[cfg(feature = "replay")]
+        let frame_time = self.replay_frame_time(frame_time);
+        Context::set_frame_time(frame_time);
         let cpu = CpuTimer::start();
+        let _ = timings::take_draw_stats();
         self.gpu_timer.begin_frame();
 
         // A visible window renders into its surface; a hidden window has no
@@ -222,19 +269,39 @@ impl Window {
         } else {
             match self.acquire_next_frame() {
                 Some(frame) => Some(frame),
-                None => return !self.should_close(),
+                None => {
+                    self.discard_frame_draws();
+                    return !self.should_close();
+                }
             }
         };
 
         // Read the size only now: while retrying, a pending resize event may
         // have been processed and the surface reconfigured.
-        let w = self.width();
-        let h = self.height();
+        let (win_w, win_h) = (self.width(), self.height());
 
+        // With a fixed aspect ratio the scenes are rendered at the size of the
+        // letterboxed viewport, and copied into it at the end of the
+        // post-processing chain; `w × h` is the size of the scene buffers.
+        let (view_x, view_y, w, h) = self.scene_viewport();
+        self.run_frame_callbacks(frame_time, scene.as_deref_mut(), scene_2d.as_deref_mut());
         camera_2d.handle_event(&self.canvas, &WindowEvent::FramebufferSize(w, h));
         camera.handle_event(&self.canvas, &WindowEvent::FramebufferSize(w, h));
         camera_2d.update(&self.canvas);
+        self.advance_turntable(camera, frame_time.delta);
         camera.update(&self.canvas);
+        #[cfg(feature = "replay")]
+        self.replay_camera(&*camera, frame_time);
+        self.rebase_world(scene.as_deref_mut(), camera);
+        TextureManager::pump_uploads();
+        // Track scale-factor changes for `UiScale::Auto` (affects the items drawn
+        // for the next frame).
+        self.apply_ui_scale();
+        self.submit_timed_draws();
+        self.draw_stats_overlay();
+        self.draw_box_zoom_rect(&*camera, &*camera_2d);
+        // Projected now so the leader lines are drawn with the 2D lines.
+        self.project_text_labels(&*camera, &*camera_2d, (view_x, view_y, w, h));
 
         // No need to update the light position here - it's computed per-frame
         // in the material's prepare() based on the camera position
@@ -250,6 +317,23 @@ impl Window {
         let ctxt = Context::get();
         let mut encoder = ctxt.create_command_encoder(Some("kiss3d_frame_encoder"));
 
+        // User compute work queued with `dispatch_compute` runs before the scene.
+        if !self.compute_dispatches.is_empty() {
+            let compute_ts = self.gpu_timer.compute_scope("compute");
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("user_compute_pass"),
+                timestamp_writes: compute_ts,
+            });
+            for dispatch in self.compute_dispatches.drain(..) {
+                pass.set_pipeline(&dispatch.pipeline);
+                for (i, group) in dispatch.bind_groups.iter().enumerate() {
+                    pass.set_bind_group(i as u32, group, &[]);
+                }
+                let [x, y, z] = dispatch.workgroups;
+                pass.dispatch_workgroups(x, y, z);
+            }
+        }
+
         // Resize the HDR film + the offscreen render targets if needed.
         //
         // The rasterizer's material/renderer pipelines are built per sample count (a
@@ -264,15 +348,18 @@ impl Window {
             .resize(w, h, self.canvas.surface_format());
         if offscreen {
             if self.offscreen_output_target.is_none() {
-                self.offscreen_output_target =
-                    Some(self.framebuffer_manager.new_render_target(w, h, true));
+                self.offscreen_output_target = Some(
+                    self.framebuffer_manager
+                        .new_render_target(win_w, win_h, true),
+                );
             }
             self.offscreen_output_target.as_mut().unwrap().resize(
-                w,
-                h,
+                win_w,
+                win_h,
                 self.canvas.surface_format(),
             );
         }
+        self.resize_letterbox_depth(w, h, sample_count);
 
         // The view that receives the final composited image: the surface
         // texture for a visible window, the offscreen color texture otherwise.
@@ -298,8 +385,11 @@ impl Window {
 
         // The depth attachment must match the scene target's sample count. The
         // canvas depth texture is built MSAA-aware; offscreen rendering is always
-        // single-sampled and uses the offscreen target's depth.
-        let depth_view = if offscreen {
+        // single-sampled and uses the offscreen target's depth. A letterboxed
+        // scene has its own depth buffer of the viewport's size.
+        let depth_view = if let Some((_, view)) = &self.letterbox_depth {
+            view.clone()
+        } else if offscreen {
             self.offscreen_output_target
                 .as_ref()
                 .expect("offscreen render target was just created")
@@ -310,9 +400,35 @@ impl Window {
             self.canvas.depth_view().clone()
         };
 
-        // Clear the render target at the start of the frame
+        // Clear the render target at the start of the frame (or keep the previous
+        // frame's contents, see `set_clear`).
         {
             let bg = self.background;
+            let color_load = match self.clear_mode {
+                ClearMode::Color => wgpu::LoadOp::Clear(wgpu::Color {
+                    r: bg.r as f64,
+                    g: bg.g as f64,
+                    b: bg.b as f64,
+                    a: bg.a as f64,
+                }),
+                ClearMode::DepthOnly | ClearMode::None => wgpu::LoadOp::Load,
+            };
+            let depth_load = match self.clear_mode {
+                ClearMode::Color | ClearMode::DepthOnly => wgpu::LoadOp::Clear(1.0),
+                ClearMode::None => wgpu::LoadOp::Load,
+            };
+            // Depth formats with a stencil aspect get their stencil cleared too,
+            // for the custom pipelines using it.
+            let stencil_ops =
+                Context::depth_format()
+                    .has_stencil_aspect()
+                    .then_some(wgpu::Operations {
+                        load: match depth_load {
+                            wgpu::LoadOp::Load => wgpu::LoadOp::Load,
+                            _ => wgpu::LoadOp::Clear(0),
+                        },
+                        store: wgpu::StoreOp::Store,
+                    });
             let clear_ts = self.gpu_timer.render_scope("clear");
             let _clear_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("clear_pass"),
@@ -320,12 +436,7 @@ impl Window {
                     view: &color_view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: bg.r as f64,
-                            g: bg.g as f64,
-                            b: bg.b as f64,
-                            a: bg.a as f64,
-                        }),
+                        load: color_load,
                         store: wgpu::StoreOp::Store,
                     },
                     depth_slice: None,
@@ -333,10 +444,10 @@ impl Window {
                 depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
                     view: &depth_view,
                     depth_ops: Some(wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(1.0),
+                        load: depth_load,
                         store: wgpu::StoreOp::Store,
                     }),
-                    stencil_ops: None,
+                    stencil_ops,
                 }),
                 timestamp_writes: clear_ts,
                 occlusion_query_set: None,
@@ -426,12 +537,11 @@ impl Window {
             } else {
                 mat.set_ssao(None);
             }
+            mat.set_oit_enabled(self.transparency_mode == TransparencyMode::WeightedBlended);
         }
 
         // Create a light collection for this frame
-        let mut lights = LightCollection::with_ambient(self.ambient_intensity);
-        lights.ambient_color = self.ambient_color;
-        lights.fog = self.fog;
+        let mut lights = self.scene_lights();
 
         // Reflection-probe runtime capture (queued via `capture_reflection_probe`).
         // For each queued probe, render the scene into six cube faces from the probe
@@ -443,13 +553,12 @@ impl Window {
             let captures = std::mem::take(&mut self.pending_probe_captures);
             let (znear, zfar) = camera.clip_planes();
             const FACE: u32 = 256;
-            if self.probe_capture.is_none() {
-                self.probe_capture = Some(crate::renderer::ProbeCapture::new(FACE));
-            }
+            let cap = self
+                .probe_capture
+                .take()
+                .unwrap_or_else(|| crate::renderer::ProbeCapture::new(FACE));
             // Force the non-clustered shading path for the capture frame uniforms.
-            MaterialManager3d::get_global_manager(|mm| mm.get_default())
-                .borrow_mut()
-                .set_capture_mode(true);
+            set_capture_mode(true);
 
             // Each cube face must be its own queue submission. The frame uniform
             // (and the object-uniform buffer) are shared and uploaded with
@@ -460,7 +569,6 @@ impl Window {
             // instead of six probe-less cube faces (a feedback loop). Submitting
             // per face makes each face's uniforms take effect.
             let ctxt = Context::get();
-            let sky_set = self.skybox.is_set();
             for idx in captures {
                 let center = match self.reflection_probes.as_ref() {
                     Some(p) if idx < p.len() => p.probes()[idx].center,
@@ -468,77 +576,19 @@ impl Window {
                 };
                 for face in 0..6usize {
                     let mut cam = crate::renderer::CubeFaceCamera::new(center, face, znear, zfar);
-                    // Bump the frame counter so prepare writes this face's uniforms.
-                    MaterialManager3d::get_global_manager(|mm| mm.begin_frame());
-                    let mut cap_lights = LightCollection::with_ambient(self.ambient_intensity);
-                    cap_lights.ambient_color = self.ambient_color;
-                    cap_lights.fog = self.fog;
-                    if let Some(scene) = scene.as_deref_mut() {
-                        scene
-                            .data_mut()
-                            .prepare(0, &mut cam, &mut cap_lights, FACE, FACE);
-                        scene.update_deformations();
-                    }
-                    MaterialManager3d::get_global_manager(|mm| mm.flush());
-
-                    let mut fenc = ctxt.create_command_encoder(Some("probe_capture_face_encoder"));
-                    let cap = self.probe_capture.as_ref().unwrap();
-                    if sky_set {
-                        self.skybox.render(
-                            &mut fenc,
-                            cap.face_color_view(face),
-                            1,
-                            cam.inverse_transformation(),
-                            None,
-                        );
-                    }
-                    let ctx = RenderContext {
-                        surface_format: crate::post_processing::HDR_FORMAT,
-                        sample_count: 1,
-                        viewport_width: FACE,
-                        viewport_height: FACE,
+                    let target = CaptureTarget {
+                        color_view: cap.face_color_view(face),
+                        depth_view: cap.depth_view(),
+                        width: FACE,
+                        height: FACE,
+                        clear: wgpu::Color::BLACK,
                         render_layers: self.reflection_capture_layers,
                         force_no_cull: false,
-                        shadow: Some(self.shadow_mapper.resources()),
-                        phase: RenderPhase::Opaque,
+                        label: "probe",
                     };
-                    {
-                        let load = if sky_set {
-                            wgpu::LoadOp::Load
-                        } else {
-                            wgpu::LoadOp::Clear(wgpu::Color::BLACK)
-                        };
-                        let probe_ts = self.gpu_timer.render_scope("probe");
-                        let mut pass = fenc.begin_render_pass(&wgpu::RenderPassDescriptor {
-                            label: Some("probe_capture_face"),
-                            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                                view: cap.face_color_view(face),
-                                resolve_target: None,
-                                ops: wgpu::Operations {
-                                    load,
-                                    store: wgpu::StoreOp::Store,
-                                },
-                                depth_slice: None,
-                            })],
-                            depth_stencil_attachment: Some(
-                                wgpu::RenderPassDepthStencilAttachment {
-                                    view: cap.depth_view(),
-                                    depth_ops: Some(wgpu::Operations {
-                                        load: wgpu::LoadOp::Clear(1.0),
-                                        store: wgpu::StoreOp::Store,
-                                    }),
-                                    stencil_ops: None,
-                                },
-                            ),
-                            timestamp_writes: probe_ts,
-                            occlusion_query_set: None,
-                            multiview_mask: None,
-                        });
-                        if let Some(scene) = scene.as_deref_mut() {
-                            scene
-                                .data_mut()
-                                .render(0, &mut cam, &cap_lights, &mut pass, &ctx);
-                        }
+                    let mut fenc = ctxt.create_command_encoder(Some("probe_capture_face_encoder"));
+                    if let Some(scene) = scene.as_deref_mut() {
+                        self.record_capture(&mut fenc, scene, &mut cam, &target, None);
                     }
                     ctxt.submit(std::iter::once(fenc.finish()));
                 }
@@ -548,22 +598,16 @@ impl Window {
                 if let Some(probes) = self.reflection_probes.as_ref() {
                     let dst = probes.layer_mip0_view(idx);
                     let mut renc = ctxt.create_command_encoder(Some("probe_reproject_encoder"));
-                    self.probe_capture.as_ref().unwrap().reproject(
-                        &mut renc,
-                        &dst,
-                        &mut self.gpu_timer,
-                    );
+                    cap.reproject(&mut renc, &dst, &mut self.gpu_timer);
                     probes.generate_layer_mips(&mut renc, idx, Some(&mut self.gpu_timer));
                     ctxt.submit(std::iter::once(renc.finish()));
                 }
             }
+            self.probe_capture = Some(cap);
 
-            // Restore clustered shading and bump the counter so the main passes
-            // re-write the real camera's frame uniforms below.
-            MaterialManager3d::get_global_manager(|mm| mm.get_default())
-                .borrow_mut()
-                .set_capture_mode(false);
-            MaterialManager3d::get_global_manager(|mm| mm.begin_frame());
+            // Restore clustered shading; the main passes re-write the real
+            // camera's frame uniforms below.
+            set_capture_mode(false);
         }
 
         // === Planar reflectors (mirrors) ===
@@ -574,6 +618,17 @@ impl Window {
         // queue submission per reflector (the `write_buffer` coalescing rule).
         self.render_reflectors(scene.as_deref_mut(), camera, w, h);
 
+        // === Cube-map captures ===
+        // Fill the cube maps queued by `capture_cubemap`, one submission per face
+        // like the probe capture above.
+        self.render_cubemap_captures(scene.as_deref_mut(), camera.clip_planes());
+
+        // === Inset views ===
+        // Render the scene from each inset's camera into its own HDR target (same
+        // separate-submission scheme as the reflectors); they are composited over
+        // the final image after the post-processing chain.
+        self.render_inset_views(scene.as_deref_mut(), win_w, win_h);
+
         // Skybox: drawn full-screen into the HDR film right after the clear, so the
         // opaque pass overwrites it wherever geometry is visible. Uses the primary
         // (pass 0) inverse view-projection; since the sky is at infinity this is
@@ -819,6 +874,48 @@ impl Window {
                     renderer.render(pass, camera, &mut custom_render_pass, &render_context);
                 }
             }
+
+            // Decals: projected onto the opaque geometry of this pass through its
+            // depth buffer, so after the opaque pass and before the grid and
+            // transparent surfaces.
+            if let Some(decals) = &mut self.decals {
+                // Sampled, so only the depth aspect (see `Context::depth_sample_view`).
+                let depth_sample_view = match (&self.letterbox_depth, &self.offscreen_output_target)
+                {
+                    (Some((texture, _)), _) => Context::depth_sample_view(texture),
+                    (None, Some(target)) if offscreen => target
+                        .depth_sample_view()
+                        .expect("offscreen render target is never the screen"),
+                    _ => self.canvas.depth_sample_view(),
+                };
+                decals.render(
+                    &mut encoder,
+                    &color_view,
+                    &depth_sample_view,
+                    sample_count,
+                    (w, h),
+                    &*camera,
+                    pass,
+                    Some(&mut self.gpu_timer),
+                );
+            }
+        }
+
+        // Remembered for `snap_depth_image`, which linearizes this frame's depth.
+        self.last_projection = camera.view_transform_pair(0).1;
+
+        // Reference grid: after the opaque geometry (whose depth occludes it) and
+        // before the transparent surfaces, which blend over it. Done once (not per
+        // stereo pass).
+        if let Some(grid) = &self.reference_grid {
+            grid.render(
+                &mut encoder,
+                &color_view,
+                &depth_view,
+                sample_count,
+                &*camera,
+                Some(&mut self.gpu_timer),
+            );
         }
 
         // === Order-independent transparency ===
@@ -833,9 +930,10 @@ impl Window {
         // and the composite blends them back, all for zero draws otherwise. The
         // `has_transparent_surfaces` check uses the same per-object classification the
         // material applies, so a real transparent surface is never dropped.
+        let oit_enabled = self.transparency_mode == TransparencyMode::WeightedBlended;
         if let Some(scene) = scene
             .as_deref_mut()
-            .filter(|s| s.has_transparent_surfaces())
+            .filter(|s| oit_enabled && s.has_transparent_surfaces())
         {
             let oit_context = RenderContext {
                 surface_format: Context::render_format(),
@@ -900,6 +998,23 @@ impl Window {
             self.hdr.composite_oit(&mut encoder, &mut self.gpu_timer);
         }
 
+        // Raw wgpu work queued with `with_frame_encoder`, into the finished 3D film.
+        if !self.frame_encoder_hooks.is_empty() {
+            let hook_context = RenderContext {
+                surface_format: Context::render_format(),
+                sample_count,
+                viewport_width: w,
+                viewport_height: h,
+                render_layers: camera.render_layers(),
+                force_no_cull: false,
+                shadow: Some(self.shadow_mapper.resources()),
+                phase: RenderPhase::Opaque,
+            };
+            for hook in std::mem::take(&mut self.frame_encoder_hooks) {
+                hook(&mut encoder, &color_view, &depth_view, &hook_context);
+            }
+        }
+
         camera.render_complete(&self.canvas);
 
         // Render the 2D planar scene (into the HDR film, like the 3D scene).
@@ -962,15 +1077,19 @@ impl Window {
                     viewport_height: h,
                 };
 
-                if self.polyline_renderer_2d.needs_rendering() {
-                    self.polyline_renderer_2d
-                        .render(camera_2d, &mut context_2d_encoder);
-                }
+                // Called even without segments so `pick_planar` forgets the
+                // previous frame's lines.
+                self.polyline_renderer_2d
+                    .render(camera_2d, &mut context_2d_encoder);
 
                 if self.point_renderer_2d.needs_rendering() {
                     self.point_renderer_2d
                         .render(camera_2d, &mut context_2d_encoder);
                 }
+
+                if let Some(overlay) = &mut self.stats_overlay {
+                    overlay.render_lines(&mut context_2d_encoder);
+                }
             }
         }
 
@@ -1147,6 +1266,24 @@ impl Window {
             }
         }
 
+        // Motion blur: render the velocity G-buffer, then blur the resolved HDR
+        // scene along it before DoF, so out-of-focus regions blur their trails too.
+        if self.motion_blur_enabled {
+            if let Some(scene) = scene {
+                let motion_blur = self
+                    .motion_blur
+                    .get_or_insert_with(|| crate::renderer::MotionBlur::new(w, h));
+                motion_blur.resize(w, h);
+                motion_blur.compute(
+                    &mut encoder,
+                    scene,
+                    camera,
+                    self.hdr.scene_resolved_view(),
+                    &mut self.gpu_timer,
+                );
+            }
+        }
+
         // Depth of field: blur the resolved HDR scene by per-pixel circle of
         // confusion (focal distance + aperture), reading the prepass view-position
         // for depth and writing the composite back into the scene before tonemap.
@@ -1180,8 +1317,26 @@ impl Window {
         // the next (A→B→A→…), with the last effect writing the final `frame_view`.
         // A visible window presents to a surface a browser composites against the
         // page, so force an opaque alpha there; a hidden/offscreen target keeps the
-        // scene alpha for snapshots and host-app embedding.
-        let force_opaque = !offscreen;
+        // scene alpha for snapshots and host-app embedding, and so does a window
+        // created with `CanvasSetup::transparent`.
+        let force_opaque = !offscreen && !self.canvas.is_transparent();
+        // The built-in FXAA pass (`Window::set_antialiasing`) runs first, on the
+        // freshly tonemapped image.
+        let mut chain: Vec<&mut dyn PostProcessingEffect> =
+            Vec::with_capacity(post_processing.len() + 2);
+        if let Some(fxaa) = self.fxaa.as_mut() {
+            chain.push(fxaa);
+        }
+        for pp in post_processing.iter_mut() {
+            chain.push(&mut **pp);
+        }
+        // The letterbox (`Window::set_fixed_aspect`) comes last so user effects see
+        // the full image.
+        let letterboxed = self.letterbox.is_some();
+        if let Some(letterbox) = self.letterbox.as_mut() {
+            chain.push(letterbox);
+        }
+        let mut post_processing = chain;
         if post_processing.is_empty() {
             self.hdr
                 .resolve(&mut encoder, &frame_view, force_opaque, &mut self.gpu_timer);
@@ -1218,16 +1373,35 @@ impl Window {
                     }
                 };
 
-                // TODO: use the real time value instead of 0.016!
-                pp.update(0.016, w as f32, h as f32, znear, zfar);
+                let time = Context::frame_time();
+                // The letterbox, last, writes the whole window.
+                let (pp_w, pp_h) = if i == n - 1 && letterboxed {
+                    (win_w, win_h)
+                } else {
+                    (w, h)
+                };
+                pp.update(time.delta, pp_w as f32, pp_h as f32, znear, zfar);
                 let mut pp_context = PostProcessingContext {
                     encoder: &mut encoder,
                     output_view,
+                    time,
                 };
                 pp.draw(input, &mut pp_context);
             }
         }
 
+        // Inset views go over the post-processed image, under text and UI.
+        if let Some(insets) = &self.inset_views {
+            insets.composite(
+                &mut encoder,
+                &frame_view,
+                win_w,
+                win_h,
+                self.hdr.settings(),
+                &mut self.gpu_timer,
+            );
+        }
+
         // Render text
         {
             let mut context_2d_encoder = RenderContext2dEncoder {
@@ -1235,11 +1409,14 @@ impl Window {
                 color_view: &frame_view,
                 surface_format: self.canvas.surface_format(),
                 sample_count,
-                viewport_width: w,
-                viewport_height: h,
+                viewport_width: win_w,
+                viewport_height: win_h,
             };
             self.text_renderer
-                .render(w as f32, h as f32, &mut context_2d_encoder);
+                .render(win_w as f32, win_h as f32, &mut context_2d_encoder);
+            if let Some(overlay) = &mut self.stats_overlay {
+                overlay.render_text(win_w as f32, win_h as f32, &mut context_2d_encoder);
+            }
         }
 
         // Resolve the GPU timestamp queries into a readback buffer before submit.
@@ -1259,8 +1436,8 @@ impl Window {
             self.egui_context.renderer.render(
                 &frame_view,
                 &depth_view,
-                w,
-                h,
+                win_w,
+                win_h,
                 self.canvas.scale_factor() as f32,
             );
         }
@@ -1284,6 +1461,7 @@ impl Window {
         // Capture frame for video recording if enabled
         #[cfg(feature = "recording")]
         self.capture_frame_if_recording();
+        self.process_screenshot_key();
 
         // Present the frame (visible windows only; a hidden window has no
         // presentable surface).
@@ -1295,6 +1473,7 @@ impl Window {
 
         // Stored before the wasm frame-pacing wait below, so `total` reflects the
         // render work and not the idle wait for the next animation frame.
+        let (draw_calls, triangles) = timings::take_draw_stats();
         self.last_timings = Some(RenderTimings {
             renderer: "Rasterizer",
             frame_wall,
@@ -1302,25 +1481,12 @@ impl Window {
             cpu_submit,
             cpu_present,
             gpu_steps: self.gpu_timer.last(),
+            draw_calls,
+            triangles,
         });
 
         #[cfg(target_arch = "wasm32")]
-        {
-            use wasm_bindgen::JsCast;
-            use web_sys::wasm_bindgen::closure::Closure;
-
-            if let Some(window) = web_sys::window() {
-                let (s, r) = oneshot::channel();
-
-                let closure = Closure::once(move || s.send(()).unwrap());
-
-                window
-                    .request_animation_frame(closure.as_ref().unchecked_ref())
-                    .unwrap();
-
-                r.await.unwrap();
-            }
-        }
+        wait_animation_frame().await;
 
         !self.should_close()
     }
@@ -1338,6 +1504,11 @@ impl Window {
         camera: &mut dyn Camera3d,
         raytracer: &mut RayTracer,
     ) -> bool {
+        if self.handle_device_lost() {
+            self.discard_frame_draws();
+            return false;
+        }
+
         // Wall-clock frame-to-frame period (true FPS), the metric the per-pass GPU
         // timestamps don't capture. See `render_single_frame`.
         let frame_start = web_time::Instant::now();
@@ -1346,7 +1517,17 @@ impl Window {
             .map(|prev| frame_start.duration_since(prev))
             .unwrap_or_default();
         self.last_frame_instant = Some(frame_start);
+        self.frame_count += 1;
+        let frame_time = FrameTime {
+            time: frame_start.duration_since(self.clock_start).as_secs_f32(),
+            delta: frame_wall.as_secs_f32(),
+            frame: self.frame_count,
+        };
+        #[cfg(feature = "replay")]
+        let frame_time = self.replay_frame_time(frame_time);
+        Context::set_frame_time(frame_time);
         let cpu = CpuTimer::start();
+        let _ = timings::take_draw_stats();
         self.gpu_timer.begin_frame();
         let offscreen = self.hidden;
 
@@ -1355,15 +1536,24 @@ impl Window {
         } else {
             match self.acquire_next_frame() {
                 Some(frame) => Some(frame),
-                None => return !self.should_close(),
+                None => {
+                    self.discard_frame_draws();
+                    return !self.should_close();
+                }
             }
         };
 
         let w = self.width();
         let h = self.height();
 
+        self.run_frame_callbacks(frame_time, Some(&mut *scene), None);
         camera.handle_event(&self.canvas, &WindowEvent::FramebufferSize(w, h));
+        self.advance_turntable(camera, frame_time.delta);
         camera.update(&self.canvas);
+        #[cfg(feature = "replay")]
+        self.replay_camera(&*camera, frame_time);
+        self.rebase_world(Some(&mut *scene), camera);
+        TextureManager::pump_uploads();
 
         let sample_count = if offscreen {
             1
@@ -1403,9 +1593,7 @@ impl Window {
 
         // Collect lights and propagate world transforms for the path tracer.
         // (`prepare` does both; the path tracer reads geometry off the CPU side.)
-        let mut lights = LightCollection::with_ambient(self.ambient_intensity);
-        lights.ambient_color = self.ambient_color;
-        lights.fog = self.fog;
+        let mut lights = self.scene_lights();
         scene.data_mut().prepare(0, camera, &mut lights, w, h);
         // Refresh skinned-mesh joint palettes so the path tracer gathers the
         // animated (CPU-skinned) geometry, not the bind pose.
@@ -1473,6 +1661,8 @@ impl Window {
 
         // Render text on top of the path-traced image.
         {
+            self.text_renderer
+                .project_labels(&*camera, glamx::Vec2::ZERO, w as f32, h as f32);
             let mut context_2d_encoder = RenderContext2dEncoder {
                 encoder: &mut encoder,
                 color_view: &frame_view,
@@ -1523,6 +1713,7 @@ impl Window {
 
         #[cfg(feature = "recording")]
         self.capture_frame_if_recording();
+        self.process_screenshot_key();
 
         let (_, cpu_present) = CpuTimer::time(|| {
             if let Some(frame) = frame {
@@ -1530,6 +1721,7 @@ impl Window {
             }
         });
 
+        let (draw_calls, triangles) = timings::take_draw_stats();
         self.last_timings = Some(RenderTimings {
             renderer: "Path tracer",
             frame_wall,
@@ -1537,22 +1729,12 @@ impl Window {
             cpu_submit,
             cpu_present,
             gpu_steps: self.gpu_timer.last(),
+            draw_calls,
+            triangles,
         });
 
         #[cfg(target_arch = "wasm32")]
-        {
-            use wasm_bindgen::JsCast;
-            use web_sys::wasm_bindgen::closure::Closure;
-
-            if let Some(window) = web_sys::window() {
-                let (s, r) = oneshot::channel();
-                let closure = Closure::once(move || s.send(()).unwrap());
-                window
-                    .request_animation_frame(closure.as_ref().unchecked_ref())
-                    .unwrap();
-                r.await.unwrap();
-            }
-        }
+        wait_animation_frame().await;
 
         !self.should_close()
     }
@@ -1604,6 +1786,79 @@ impl Window {
         }
     }
 
+    /// Decides whether the current `render_*` call draws a frame.
+    ///
+    /// Returns `false` (skipping the frame) when the window is minimized or
+    /// occluded, or in [`RedrawMode::OnDemand`] when no event was handled and no
+    /// redraw was requested. Before returning `false` this waits for new events
+    /// (native, for at most [`ON_DEMAND_WAIT_INTERVAL`]) or the next animation
+    /// frame (web), so a skipped frame doesn't spin the CPU; the events it
+    /// collects are handled — and drawn — by the next call, while the draws
+    /// queued for the skipped frame are dropped.
+    pub(super) async fn wait_for_redraw(&mut self, handled_events: bool) -> bool {
+        // Hidden/headless windows are driven explicitly (snapshots, recording),
+        // and the first frame must always be drawn.
+        if self.hidden || self.first_frame {
+            self.redraw_requested = false;
+            return true;
+        }
+
+        if self.canvas.is_occluded() {
+            self.discard_frame_draws();
+            self.canvas.wait_events(Some(OCCLUDED_WAIT_INTERVAL));
+            #[cfg(target_arch = "wasm32")]
+            wait_animation_frame().await;
+            return false;
+        }
+
+        if self.redraw_mode == RedrawMode::Continuous || handled_events || self.redraw_requested {
+            self.redraw_requested = false;
+            return true;
+        }
+
+        self.discard_frame_draws();
+        self.canvas.wait_events(Some(ON_DEMAND_WAIT_INTERVAL));
+        #[cfg(target_arch = "wasm32")]
+        wait_animation_frame().await;
+        false
+    }
+
+    /// (Re-)creates the depth buffer of a letterboxed scene of size `w × h`
+    /// (see [`Window::set_fixed_aspect`]) if needed.
+    fn resize_letterbox_depth(&mut self, w: u32, h: u32, sample_count: u32) {
+        if self.letterbox.is_none() {
+            self.letterbox_depth = None;
+            return;
+        }
+        let up_to_date = self.letterbox_depth.as_ref().is_some_and(|(texture, _)| {
+            texture.width() == w.max(1)
+                && texture.height() == h.max(1)
+                && texture.sample_count() == sample_count.max(1)
+        });
+        if !up_to_date {
+            let ctxt = Context::get();
+            self.letterbox_depth = Some(super::WgpuCanvas::create_depth_texture(
+                &ctxt.device,
+                w,
+                h,
+                sample_count,
+            ));
+        }
+    }
+
+    /// Drops everything queued for the current frame (immediate-mode draws,
+    /// compute dispatches and encoder hooks) when it is skipped, so it doesn't
+    /// pile up until the next rendered frame.
+    fn discard_frame_draws(&mut self) {
+        self.polyline_renderer.discard();
+        self.polyline_renderer_2d.discard();
+        self.point_renderer.discard();
+        self.point_renderer_2d.discard();
+        self.text_renderer.discard();
+        self.compute_dispatches.clear();
+        self.frame_encoder_hooks.clear();
+    }
+
     fn render_scene(
         &mut self,
         scene: &mut SceneNode3d,
@@ -1627,6 +1882,298 @@ impl Window {
             .render(pass, camera, lights, render_pass, context);
     }
 
+    /// Renders the scene from the camera of every inset view into the inset's own
+    /// HDR target, sized to its rectangle of the `w × h` window. See
+    /// [`crate::renderer::InsetView`].
+    fn render_inset_views(&mut self, scene: Option<&mut SceneNode3d>, w: u32, h: u32) {
+        let scene = match scene {
+            Some(scene) if self.inset_views.as_ref().is_some_and(|i| !i.is_empty()) => scene,
+            _ => return,
+        };
+        let Some(mut insets) = self.inset_views.take() else {
+            return;
+        };
+
+        let ctxt = Context::get();
+        let has_transparent = scene.has_transparent_surfaces();
+
+        // Fixed-light path, as for the reflector captures: the inset cameras have
+        // no clustered cull data.
+        set_capture_mode(true);
+
+        for inset in insets.iter_mut() {
+            let (_, _, iw, ih) = inset.pixel_rect(w, h);
+            let (camera, targets) = inset.render_parts(iw, ih);
+            camera.handle_event(&self.canvas, &WindowEvent::FramebufferSize(iw, ih));
+
+            let target = CaptureTarget {
+                color_view: &targets.color_view,
+                depth_view: &targets.depth_view,
+                width: iw,
+                height: ih,
+                clear: self.background_clear_color(),
+                render_layers: camera.render_layers(),
+                force_no_cull: false,
+                label: "inset_view",
+            };
+            // Transparent surfaces: weighted-blended OIT into the inset's own
+            // single-sample targets, composited over the opaque capture.
+            let oit = if has_transparent {
+                let oit = targets
+                    .oit
+                    .get_or_insert_with(|| crate::renderer::ReflectorOit::new(iw, ih));
+                Some(oit)
+            } else {
+                None
+            };
+
+            let mut ienc = ctxt.create_command_encoder(Some("inset_view_encoder"));
+            self.record_capture(&mut ienc, scene, camera, &target, oit);
+            ctxt.submit(std::iter::once(ienc.finish()));
+        }
+
+        self.inset_views = Some(insets);
+        set_capture_mode(false);
+    }
+
+    /// Renders the scene into the six faces of each cube map queued by
+    /// `capture_cubemap`, from its capture point.
+    fn render_cubemap_captures(
+        &mut self,
+        scene: Option<&mut SceneNode3d>,
+        (znear, zfar): (f32, f32),
+    ) {
+        let scene = match scene {
+            Some(scene) if !self.pending_cubemap_captures.is_empty() => scene,
+            _ => return,
+        };
+        let captures = std::mem::take(&mut self.pending_cubemap_captures);
+        let ctxt = Context::get();
+
+        // Fixed-light path: the face cameras have no clustered cull data.
+        set_capture_mode(true);
+
+        for (position, texture) in captures {
+            let size = texture.size.0;
+            let depth = ctxt.create_texture(&wgpu::TextureDescriptor {
+                label: Some("cubemap_capture_depth"),
+                size: wgpu::Extent3d {
+                    width: size,
+                    height: size,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: Context::depth_format(),
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            });
+            let depth_view = depth.create_view(&wgpu::TextureViewDescriptor::default());
+
+            for face in 0..6usize {
+                let face_view = texture.texture.create_view(&wgpu::TextureViewDescriptor {
+                    label: Some("cubemap_capture_face"),
+                    dimension: Some(wgpu::TextureViewDimension::D2),
+                    base_array_layer: face as u32,
+                    array_layer_count: Some(1),
+                    ..Default::default()
+                });
+                let mut cam =
+                    crate::renderer::CubeFaceCamera::new_cubemap_face(position, face, znear, zfar);
+                // The mirrored face projection flips the winding.
+                self.render_cube_face(scene, &mut cam, &face_view, &depth_view, size, true);
+            }
+        }
+
+        set_capture_mode(false);
+    }
+
+    /// Renders the opaque objects of `scene` on the reflection capture layers,
+    /// over the skybox (or the background color), into one `size × size` cube
+    /// face, in its own queue submission.
+    ///
+    /// The caller enables the capture mode (the fixed-light path, see
+    /// [`set_capture_mode`]) around its faces.
+    pub(super) fn render_cube_face(
+        &mut self,
+        scene: &mut SceneNode3d,
+        cam: &mut crate::renderer::CubeFaceCamera,
+        color_view: &wgpu::TextureView,
+        depth_view: &wgpu::TextureView,
+        size: u32,
+        force_no_cull: bool,
+    ) {
+        let target = CaptureTarget {
+            color_view,
+            depth_view,
+            width: size,
+            height: size,
+            clear: self.background_clear_color(),
+            render_layers: self.reflection_capture_layers,
+            force_no_cull,
+            label: "cube_face",
+        };
+        let ctxt = Context::get();
+        let mut fenc = ctxt.create_command_encoder(Some("cube_face_encoder"));
+        self.record_capture(&mut fenc, scene, cam, &target, None);
+        ctxt.submit(std::iter::once(fenc.finish()));
+    }
+
+    /// The lights of the window's lighting setup (ambient and fog), to which
+    /// `prepare` adds the lights of the scene.
+    pub(super) fn scene_lights(&self) -> LightCollection {
+        let mut lights = LightCollection::with_ambient(self.ambient_intensity);
+        lights.ambient_color = self.ambient_color;
+        lights.ambient_ground_color = self.ambient_ground_color;
+        lights.fog = self.fog;
+        lights
+    }
+
+    /// The background color, as the clear color of an HDR capture target.
+    fn background_clear_color(&self) -> wgpu::Color {
+        wgpu::Color {
+            r: self.background.r as f64,
+            g: self.background.g as f64,
+            b: self.background.b as f64,
+            a: 1.0,
+        }
+    }
+
+    /// Prepares `scene` for `camera` and records its capture into `target`: the
+    /// skybox (or the clear color), the opaque objects and, given an `oit`
+    /// target, the transparent ones composited over them. Returns the lights
+    /// the scene was prepared with.
+    ///
+    /// The caller enables the capture mode around its captures (see
+    /// [`set_capture_mode`]) and submits `encoder` before the next capture: the
+    /// frame uniforms are written with `queue.write_buffer`, so captures sharing
+    /// a submission would all see the uniforms of the last one.
+    fn record_capture(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        scene: &mut SceneNode3d,
+        camera: &mut dyn Camera3d,
+        target: &CaptureTarget,
+        oit: Option<&mut crate::renderer::ReflectorOit>,
+    ) -> LightCollection {
+        // Bump the frame counter so prepare writes this camera's uniforms.
+        MaterialManager3d::get_global_manager(|mm| mm.begin_frame());
+        let mut lights = self.scene_lights();
+        scene
+            .data_mut()
+            .prepare(0, camera, &mut lights, target.width, target.height);
+        scene.update_deformations();
+        MaterialManager3d::get_global_manager(|mm| mm.flush());
+
+        let sky_set = self.skybox.is_set();
+        if sky_set {
+            self.skybox.render(
+                encoder,
+                target.color_view,
+                1,
+                camera.inverse_transformation(),
+                None,
+            );
+        }
+        let ctx = RenderContext {
+            surface_format: crate::post_processing::HDR_FORMAT,
+            sample_count: 1,
+            viewport_width: target.width,
+            viewport_height: target.height,
+            render_layers: target.render_layers,
+            force_no_cull: target.force_no_cull,
+            shadow: Some(self.shadow_mapper.resources()),
+            phase: RenderPhase::Opaque,
+        };
+        {
+            let load = if sky_set {
+                wgpu::LoadOp::Load
+            } else {
+                wgpu::LoadOp::Clear(target.clear)
+            };
+            let timestamps = self.gpu_timer.render_scope(target.label);
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some(target.label),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target.color_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                    depth_slice: None,
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: target.depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: timestamps,
+                occlusion_query_set: None,
+                multiview_mask: None,
+            });
+            scene.data_mut().render(0, camera, &lights, &mut pass, &ctx);
+        }
+
+        // Same weighted-blended OIT as the main pass, but into the single-sample
+        // `oit` targets, tested against the capture's opaque depth.
+        if let Some(oit) = oit {
+            let oit_ctx = RenderContext {
+                phase: RenderPhase::Transparent,
+                ..ctx
+            };
+            {
+                let timestamps = self.gpu_timer.render_scope("capture_oit");
+                let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("capture_oit"),
+                    color_attachments: &[
+                        // accum: cleared to 0 (additive).
+                        Some(wgpu::RenderPassColorAttachment {
+                            view: oit.accum_view(),
+                            resolve_target: None,
+                            ops: wgpu::Operations {
+                                load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                                store: wgpu::StoreOp::Store,
+                            },
+                            depth_slice: None,
+                        }),
+                        // revealage: cleared to 1 (nothing occluded yet).
+                        Some(wgpu::RenderPassColorAttachment {
+                            view: oit.reveal_view(),
+                            resolve_target: None,
+                            ops: wgpu::Operations {
+                                load: wgpu::LoadOp::Clear(wgpu::Color::WHITE),
+                                store: wgpu::StoreOp::Store,
+                            },
+                            depth_slice: None,
+                        }),
+                    ],
+                    depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                        view: target.depth_view,
+                        depth_ops: Some(wgpu::Operations {
+                            load: wgpu::LoadOp::Load,
+                            store: wgpu::StoreOp::Store,
+                        }),
+                        stencil_ops: None,
+                    }),
+                    timestamp_writes: timestamps,
+                    occlusion_query_set: None,
+                    multiview_mask: None,
+                });
+                scene
+                    .data_mut()
+                    .render(0, camera, &lights, &mut pass, &oit_ctx);
+            }
+            oit.composite(encoder, target.color_view);
+        }
+
+        lights
+    }
+
     /// Renders every planar reflector (mirror) in the scene: for each reflector
     /// surface, render the scene from a mirror camera into the reflector's own
     /// texture and store the reflected view-projection so the surface samples it
@@ -1658,13 +2205,10 @@ impl Window {
         let (znear, zfar) = camera.clip_planes();
         let (mview, mproj) = camera.view_transform_pair(0);
         let eye = camera.eye();
-        let sky_set = self.skybox.is_set();
 
         // Propagate world transforms so we can read each reflector's world plane.
         MaterialManager3d::get_global_manager(|mm| mm.begin_frame());
-        let mut lights = LightCollection::with_ambient(self.ambient_intensity);
-        lights.ambient_color = self.ambient_color;
-        lights.fog = self.fog;
+        let mut lights = self.scene_lights();
         scene.data_mut().prepare(0, camera, &mut lights, w, h);
         scene.update_deformations();
 
@@ -1712,9 +2256,14 @@ impl Window {
         // Fixed-light path for the capture frames (the mirror camera has no clustered
         // cull data). Reflector surfaces skip themselves during capture (handled in
         // the material via `capture_mode`).
-        MaterialManager3d::get_global_manager(|mm| mm.get_default())
-            .borrow_mut()
-            .set_capture_mode(true);
+        set_capture_mode(true);
+        // The transparent surfaces of the mirrors are drawn into dedicated
+        // single-sample OIT targets, shared by all the reflectors.
+        let mut oit = self.reflector_oit.take();
+        if has_transparent {
+            oit.get_or_insert_with(|| crate::renderer::ReflectorOit::new(w, h))
+                .resize(w, h);
+        }
 
         for (mut mcam, color_view, depth_view, clip) in jobs {
             // Clip geometry behind this mirror's plane.
@@ -1722,135 +2271,26 @@ impl Window {
                 .borrow_mut()
                 .set_clip_plane(Some(clip));
 
-            // Prepare + flush the scene for the mirror camera.
-            MaterialManager3d::get_global_manager(|mm| mm.begin_frame());
-            let mut mlights = LightCollection::with_ambient(self.ambient_intensity);
-            mlights.ambient_color = self.ambient_color;
-            mlights.fog = self.fog;
-            scene.data_mut().prepare(0, &mut mcam, &mut mlights, w, h);
-            scene.update_deformations();
-            MaterialManager3d::get_global_manager(|mm| mm.flush());
-
             // Render into this reflector's target (its own queue submission).
-            let mut menc = ctxt.create_command_encoder(Some("reflector_encoder"));
-            if sky_set {
-                self.skybox.render(
-                    &mut menc,
-                    &color_view,
-                    1,
-                    mcam.inverse_transformation(),
-                    None,
-                );
-            }
-            let ctx = RenderContext {
-                surface_format: crate::post_processing::HDR_FORMAT,
-                sample_count: 1,
-                viewport_width: w,
-                viewport_height: h,
+            let target = CaptureTarget {
+                color_view: &color_view,
+                depth_view: &depth_view,
+                width: w,
+                height: h,
+                clear: wgpu::Color::BLACK,
                 render_layers: camera.render_layers(),
                 // The reflected projection flips winding, so disable back-face cull.
                 force_no_cull: true,
-                shadow: Some(self.shadow_mapper.resources()),
-                phase: RenderPhase::Opaque,
+                label: "reflector",
             };
-            {
-                let load = if sky_set {
-                    wgpu::LoadOp::Load
-                } else {
-                    wgpu::LoadOp::Clear(wgpu::Color::BLACK)
-                };
-                let reflector_ts = self.gpu_timer.render_scope("reflector");
-                let mut pass = menc.begin_render_pass(&wgpu::RenderPassDescriptor {
-                    label: Some("reflector_pass"),
-                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                        view: &color_view,
-                        resolve_target: None,
-                        ops: wgpu::Operations {
-                            load,
-                            store: wgpu::StoreOp::Store,
-                        },
-                        depth_slice: None,
-                    })],
-                    depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                        view: &depth_view,
-                        depth_ops: Some(wgpu::Operations {
-                            load: wgpu::LoadOp::Clear(1.0),
-                            store: wgpu::StoreOp::Store,
-                        }),
-                        stencil_ops: None,
-                    }),
-                    timestamp_writes: reflector_ts,
-                    occlusion_query_set: None,
-                    multiview_mask: None,
-                });
-                scene
-                    .data_mut()
-                    .render(0, &mut mcam, &mlights, &mut pass, &ctx);
-            }
-
-            // === Transparent (OIT) surfaces in the mirror ===
-            // Same weighted-blended OIT as the main pass, but into dedicated
-            // single-sample targets, composited over the capture. Tests against
-            // the capture's opaque depth.
-            if has_transparent {
-                let oit = self
-                    .reflector_oit
-                    .get_or_insert_with(|| crate::renderer::ReflectorOit::new(w, h));
-                oit.resize(w, h);
-                let oit_ctx = RenderContext {
-                    surface_format: crate::post_processing::HDR_FORMAT,
-                    sample_count: 1,
-                    viewport_width: w,
-                    viewport_height: h,
-                    render_layers: camera.render_layers(),
-                    force_no_cull: true,
-                    shadow: Some(self.shadow_mapper.resources()),
-                    phase: RenderPhase::Transparent,
-                };
-                {
-                    let oit_ts = self.gpu_timer.render_scope("reflector_oit");
-                    let mut oit_pass = menc.begin_render_pass(&wgpu::RenderPassDescriptor {
-                        label: Some("reflector_oit_pass"),
-                        color_attachments: &[
-                            // accum: cleared to 0 (additive).
-                            Some(wgpu::RenderPassColorAttachment {
-                                view: oit.accum_view(),
-                                resolve_target: None,
-                                ops: wgpu::Operations {
-                                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
-                                    store: wgpu::StoreOp::Store,
-                                },
-                                depth_slice: None,
-                            }),
-                            // revealage: cleared to 1 (nothing occluded yet).
-                            Some(wgpu::RenderPassColorAttachment {
-                                view: oit.reveal_view(),
-                                resolve_target: None,
-                                ops: wgpu::Operations {
-                                    load: wgpu::LoadOp::Clear(wgpu::Color::WHITE),
-                                    store: wgpu::StoreOp::Store,
-                                },
-                                depth_slice: None,
-                            }),
-                        ],
-                        depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                            view: &depth_view,
-                            depth_ops: Some(wgpu::Operations {
-                                load: wgpu::LoadOp::Load,
-                                store: wgpu::StoreOp::Store,
-                            }),
-                            stencil_ops: None,
-                        }),
-                        timestamp_writes: oit_ts,
-                        occlusion_query_set: None,
-                        multiview_mask: None,
-                    });
-                    scene
-                        .data_mut()
-                        .render(0, &mut mcam, &mlights, &mut oit_pass, &oit_ctx);
-                }
-                oit.composite(&mut menc, &color_view);
-            }
+            let mut menc = ctxt.create_command_encoder(Some("reflector_encoder"));
+            let mlights = self.record_capture(
+                &mut menc,
+                scene,
+                &mut mcam,
+                &target,
+                oit.as_mut().filter(|_| has_transparent),
+            );
 
             // === Refractive (glass) surfaces in the mirror ===
             // Snapshot the capture (opaque + transparent) into the shared blurred
@@ -1931,17 +2371,15 @@ impl Window {
             ctxt.submit(std::iter::once(menc.finish()));
         }
 
-        // Restore the default material's state.
-        {
-            let default_mat = MaterialManager3d::get_global_manager(|mm| mm.get_default());
-            let mut m = default_mat.borrow_mut();
-            m.set_capture_mode(false);
-            m.set_clip_plane(None);
-        }
+        self.reflector_oit = oit;
 
-        // Bump the frame counter so the per-pass loop re-prepares the main camera
-        // (reflector objects then pick up the view-proj set on their Reflector above).
-        MaterialManager3d::get_global_manager(|mm| mm.begin_frame());
+        // Restore the default material's state. Leaving the capture mode makes the
+        // per-pass loop re-prepare the main camera (reflector objects then pick up
+        // the view-proj set on their Reflector above).
+        MaterialManager3d::get_global_manager(|mm| mm.get_default())
+            .borrow_mut()
+            .set_clip_plane(None);
+        set_capture_mode(false);
     }
 
     /// The texture view holding the final (LDR, post-tonemap) image of a hidden
@@ -1965,3 +2403,33 @@ impl Window {
             .clone()
     }
 }
+
+/// The targets and settings of an off-screen capture of the scene (reflection
+/// probe or cube-map face, mirror, inset view), recorded by
+/// [`Window::record_capture`].
+struct CaptureTarget<'a> {
+    color_view: &'a wgpu::TextureView,
+    depth_view: &'a wgpu::TextureView,
+    width: u32,
+    height: u32,
+    /// The color the target is cleared to when no skybox is set.
+    clear: wgpu::Color,
+    render_layers: u32,
+    /// Disables back-face culling, for projections flipping the winding.
+    force_no_cull: bool,
+    /// Names the opaque pass and its GPU timing scope.
+    label: &'static str,
+}
+
+/// Switches the default material to (or back from) the fixed-light path of the
+/// off-screen captures, whose cameras have no clustered cull data. Leaving it
+/// bumps the frame counter so the next `prepare` re-writes the uniforms of the
+/// main camera.
+pub(super) fn set_capture_mode(enabled: bool) {
+    MaterialManager3d::get_global_manager(|mm| mm.get_default())
+        .borrow_mut()
+        .set_capture_mode(enabled);
+    if !enabled {
+        MaterialManager3d::get_global_manager(|mm| mm.begin_frame());
+    }
+}
@@ -0,0 +1,279 @@
+//! Recording and replaying of the input and camera state, for deterministic
+//! replays.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::camera::Camera3d;
+use crate::context::FrameTime;
+use crate::event::WindowEvent;
+
+use super::Window;
+
+/// Distance between the recorded and the replayed camera positions above which a
+/// replay is considered diverged.
+const DIVERGENCE_TOLERANCE: f32 = 1.0e-3;
+
+/// The state of one frame of a replay file.
+///
+/// A replay file holds one frame per line, as JSON.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ReplayFrame {
+    /// Seconds elapsed since the window was created (see
+    /// [`FrameTime::time`](crate::context::FrameTime::time)).
+    pub time: f32,
+    /// Seconds elapsed since the previous frame.
+    pub delta: f32,
+    /// The size of the window, in pixels.
+    pub size: (u32, u32),
+    /// The keyboard, mouse and touch events received before this frame, in order.
+    pub events: Vec<WindowEvent>,
+    /// The position of the 3D camera once it handled the events up to this frame.
+    pub camera_eye: [f32; 3],
+}
+
+/// The replay being recorded or played by a window.
+#[derive(Default)]
+pub(crate) struct ReplayState {
+    mode: Option<ReplayMode>,
+    /// Index of the first diverged frame of the last replay played back.
+    divergence: Option<u64>,
+}
+
+enum ReplayMode {
+    Recording {
+        file: BufWriter<File>,
+        /// The input events received since the last rendered frame.
+        events: Vec<WindowEvent>,
+    },
+    Playing {
+        frames: VecDeque<ReplayFrame>,
+        /// The recorded frame being rendered, whose events were already fed.
+        /// It stays current until a frame is actually rendered, so skipped
+        /// frames (e.g. while the window is occluded) don't drop recorded ones.
+        current: Option<ReplayFrame>,
+        /// Index of the frame being rendered.
+        index: u64,
+    },
+}
+
+impl Window {
+    /// Starts recording the input events and camera state of every frame to the
+    /// replay file `path`, to be played back by [`replay`](Self::replay).
+    ///
+    /// Each frame stores its keyboard, mouse and touch events, its
+    /// [`FrameTime`](crate::context::FrameTime), the window size and the 3D camera
+    /// position. Stop with [`end_replay_recording`](Self::end_replay_recording).
+    ///
+    /// **Note:** This feature requires the `replay` feature to be enabled.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let mut window = Window::new("Example").await;
+    /// # let mut camera = OrbitCamera3d::default();
+    /// # let mut scene = SceneNode3d::empty();
+    /// window.begin_replay_recording("session.replay").unwrap();
+    /// while window.render_3d(&mut scene, &mut camera).await {}
+    /// window.end_replay_recording().unwrap();
+    /// # }
+    /// ```
+    pub fn begin_replay_recording<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        self.stop_replay();
+        self.canvas.set_input_log(true);
+        self.replay.mode = Some(ReplayMode::Recording {
+            file,
+            events: Vec::new(),
+        });
+        Ok(())
+    }
+
+    /// Stops recording a replay, flushing the replay file.
+    ///
+    /// Does nothing if no replay is being recorded.
+    pub fn end_replay_recording(&mut self) -> io::Result<()> {
+        match self.replay.mode.take() {
+            Some(ReplayMode::Recording { mut file, .. }) => {
+                self.canvas.set_input_log(false);
+                file.flush()
+            }
+            other => {
+                self.replay.mode = other;
+                Ok(())
+            }
+        }
+    }
+
+    /// Plays back the replay file `path` recorded by
+    /// [`begin_replay_recording`](Self::begin_replay_recording).
+    ///
+    /// From the next frame on, the live keyboard, mouse and touch input is
+    /// ignored: the window, the cameras and [`events`](Self::events) receive the
+    /// recorded events instead, and [`Context::frame_time`] returns the recorded
+    /// times, so an application driven by them renders the same frames again. The
+    /// position of the 3D camera is compared to the recorded one every frame (see
+    /// [`replay_divergence`](Self::replay_divergence)). Live input resumes once
+    /// every frame has been played, or with [`stop_replay`](Self::stop_replay).
+    ///
+    /// Replays are only reproducible if the window has the same size as when it
+    /// was recorded.
+    ///
+    /// **Note:** This feature requires the `replay` feature to be enabled.
+    ///
+    /// [`Context::frame_time`]: crate::context::Context::frame_time
+    pub fn replay<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let mut frames = VecDeque::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let frame = serde_json::from_str(&line)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            frames.push_back(frame);
+        }
+
+        self.end_replay_recording()?;
+        self.canvas.set_input_replay(true);
+        self.replay.mode = Some(ReplayMode::Playing {
+            frames,
+            current: None,
+            index: 0,
+        });
+        self.replay.divergence = None;
+        Ok(())
+    }
+
+    /// Stops playing back a replay (or recording one, without flushing it), and
+    /// resumes handling the live input.
+    pub fn stop_replay(&mut self) {
+        match self.replay.mode.take() {
+            Some(ReplayMode::Playing { .. }) => self.canvas.set_input_replay(false),
+            Some(ReplayMode::Recording { .. }) => self.canvas.set_input_log(false),
+            None => {}
+        }
+    }
+
+    /// Whether a replay is being played back.
+    pub fn is_replaying(&self) -> bool {
+        matches!(self.replay.mode, Some(ReplayMode::Playing { .. }))
+    }
+
+    /// Whether a replay is being recorded.
+    pub fn is_recording_replay(&self) -> bool {
+        matches!(self.replay.mode, Some(ReplayMode::Recording { .. }))
+    }
+
+    /// The index of the first frame of the replay being (or last) played back
+    /// whose 3D camera position didn't match the recording, if any.
+    ///
+    /// A divergence means the application didn't react to the replayed events and
+    /// times as it did when recording, e.g. because it depends on randomness or on
+    /// the wall-clock time.
+    pub fn replay_divergence(&self) -> Option<u64> {
+        self.replay.divergence
+    }
+
+    /// Called right after polling the events: logs the input events of the next
+    /// rendered frame when recording, or feeds the recorded ones when playing
+    /// back.
+    ///
+    /// Events are polled more often than frames are rendered (e.g. while waiting
+    /// for a redraw), so the logged events accumulate until
+    /// [`replay_camera`](Self::replay_camera) writes them, and playback only
+    /// moves to the next recorded frame once the current one was rendered.
+    pub(crate) fn update_replay(&mut self) {
+        match &mut self.replay.mode {
+            Some(ReplayMode::Recording { events, .. }) => {
+                events.extend(self.canvas.take_input_log());
+            }
+            Some(ReplayMode::Playing {
+                current: Some(_), ..
+            }) => {}
+            Some(ReplayMode::Playing {
+                frames, current, ..
+            }) => match frames.pop_front() {
+                Some(frame) => {
+                    for event in &frame.events {
+                        self.canvas.replay_event(*event);
+                    }
+                    *current = Some(frame);
+                }
+                None => {
+                    log::info!("Replay finished.");
+                    self.stop_replay();
+                }
+            },
+            None => {}
+        }
+    }
+
+    /// The frame time to use for the frame being rendered: the recorded one while
+    /// playing back a replay, else `live`.
+    pub(crate) fn replay_frame_time(&self, live: FrameTime) -> FrameTime {
+        match &self.replay.mode {
+            Some(ReplayMode::Playing {
+                current: Some(frame),
+                ..
+            }) => FrameTime {
+                time: frame.time,
+                delta: frame.delta,
+                frame: live.frame,
+            },
+            _ => live,
+        }
+    }
+
+    /// Called once the camera handled the frame's events: writes the frame to the
+    /// replay file when recording, or checks the camera against the recording when
+    /// playing back.
+    pub(crate) fn replay_camera(&mut self, camera: &dyn Camera3d, time: FrameTime) {
+        let eye = camera.eye();
+        let size = self.canvas.size();
+        match &mut self.replay.mode {
+            Some(ReplayMode::Recording { file, events }) => {
+                let frame = ReplayFrame {
+                    time: time.time,
+                    delta: time.delta,
+                    size,
+                    events: std::mem::take(events),
+                    camera_eye: eye.to_array(),
+                };
+                let written = serde_json::to_string(&frame)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+                    .and_then(|line| writeln!(file, "{}", line));
+                if let Err(e) = written {
+                    log::warn!("Failed to record the replay frame: {}", e);
+                    self.stop_replay();
+                }
+            }
+            Some(ReplayMode::Playing { current, index, .. }) => {
+                let Some(frame) = current.take() else {
+                    return;
+                };
+                if frame.size != size && *index == 0 {
+                    log::warn!(
+                        "Replaying a {}x{} recording in a {}x{} window: it may diverge.",
+                        frame.size.0,
+                        frame.size.1,
+                        size.0,
+                        size.1
+                    );
+                }
+                let recorded = glamx::Vec3::from_array(frame.camera_eye);
+                if self.replay.divergence.is_none() && recorded.distance(eye) > DIVERGENCE_TOLERANCE
+                {
+                    log::warn!("The replay diverged from the recording at frame {}.", index);
+                    self.replay.divergence = Some(*index);
+                }
+                *index += 1;
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//! The rubber band drawn while dragging a box zoom.
+
+use glamx::Vec2;
+
+use crate::camera::{Camera2d, Camera3d};
+use crate::color::Color;
+
+use super::Window;
+
+impl Window {
+    /// Draws the outline of the zoom rectangle dragged on `camera` or
+    /// `camera_2d`, if any (see [`Camera3d::box_zoom_rect`]). `camera_2d` maps
+    /// the rectangle's pixel coordinates to the 2D line renderer.
+    pub(super) fn draw_box_zoom_rect(&mut self, camera: &dyn Camera3d, camera_2d: &dyn Camera2d) {
+        let Some((a, b)) = camera.box_zoom_rect().or_else(|| camera_2d.box_zoom_rect()) else {
+            return;
+        };
+
+        // The cameras and the 2D lines live in the scene viewport.
+        let (_, _, w, h) = self.scene_viewport();
+        let size = Vec2::new(w as f32, h as f32);
+        let corners = [a, Vec2::new(b.x, a.y), b, Vec2::new(a.x, b.y)];
+        let color = Color::new(1.0, 1.0, 1.0, 0.8);
+        let width = self.ui_scale_factor();
+        for i in 0..4 {
+            let p = camera_2d.unproject(corners[i], size);
+            let q = camera_2d.unproject(corners[(i + 1) % 4], size);
+            self.draw_line_2d(p, q, color, width);
+        }
+    }
+}
@@ -1,8 +1,17 @@
 //! Screenshot functionality.
 
+use std::path::{Path, PathBuf};
+
+use glamx::{Quat, Vec3};
 use image::{imageops, ImageBuffer, Rgb};
 
+use super::rendering::set_capture_mode;
 use super::Window;
+use crate::camera::Camera3d;
+use crate::context::Context;
+use crate::event::Key;
+use crate::renderer::{CubeFaceCamera, ProbeCapture};
+use crate::scene::SceneNode3d;
 
 impl Window {
     /// Captures the current framebuffer as raw RGB pixel data.
@@ -97,4 +106,189 @@ impl Window {
             .expect("readback buffer was not big enough for image");
         Some(imageops::flip_vertical(&img))
     }
+
+    /// Captures the current framebuffer and saves it as an image file.
+    ///
+    /// The format is deduced from the extension of `path` (e.g. `.png`).
+    pub fn snap_to_file<P: AsRef<Path>>(&self, path: P) -> image::ImageResult<()> {
+        self.snap_image().save(path)
+    }
+
+    /// Renders a 360° equirectangular panorama of `scene` as seen from the
+    /// position of `camera`, `width` pixels wide and `width / 2` pixels high.
+    ///
+    /// The scene is rendered into six cube faces (like
+    /// [`capture_cubemap`](Self::capture_cubemap)) which are then reprojected
+    /// into a latitude/longitude image, the format 360° image viewers expect:
+    /// the top row looks straight up along the
+    /// [world up axis](crate::context::Context::world_up), the bottom row
+    /// straight down, and the center of the image looks along `+X` (for the
+    /// default `+Y` up axis). The orientation of `camera` is
+    /// ignored. The colors are the linear scene colors clamped to `[0, 1]`,
+    /// without the post-processing of the window.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let mut window = Window::new("Example").await;
+    /// # let mut camera = OrbitCamera3d::default();
+    /// # let mut scene = SceneNode3d::empty();
+    /// window.snap_panorama(&mut scene, &camera, 4096).save("panorama.png").unwrap();
+    /// # }
+    /// ```
+    pub fn snap_panorama(
+        &mut self,
+        scene: &mut SceneNode3d,
+        camera: &dyn Camera3d,
+        width: u32,
+    ) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+        let width = width.max(4);
+        let height = width / 2;
+        // A face spans 90°, a quarter of the panorama's width.
+        let face_size = (width / 4).max(16);
+        let (znear, zfar) = camera.clip_planes();
+        let eye = camera.eye();
+        let capture = ProbeCapture::new(face_size);
+        // Turn the `+Y`-up face layout so the panorama's poles follow the world up axis.
+        let orientation = Quat::from_rotation_arc(Vec3::Y, Context::world_up());
+
+        // Fixed-light path: the face cameras have no clustered cull data.
+        set_capture_mode(true);
+        for face in 0..6 {
+            let mut cam = CubeFaceCamera::new_oriented(eye, face, orientation, znear, zfar);
+            self.render_cube_face(
+                scene,
+                &mut cam,
+                capture.face_color_view(face),
+                capture.depth_view(),
+                face_size,
+                false,
+            );
+        }
+        set_capture_mode(false);
+
+        let ctxt = Context::get();
+        let target = ctxt.create_texture(&wgpu::TextureDescriptor {
+            label: Some("panorama_texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba16Float,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let target_view = target.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = ctxt.create_command_encoder(Some("panorama_encoder"));
+        capture.reproject(&mut encoder, &target_view, &mut self.gpu_timer);
+        ctxt.submit(std::iter::once(encoder.finish()));
+
+        let texels = super::aov::read_texture::<u16>(&target, width, height, 4);
+        let mut rgb = Vec::with_capacity(width as usize * height as usize * 3);
+        for texel in texels.chunks_exact(4) {
+            rgb.extend(texel[..3].iter().map(|c| linear_to_srgb(f16_to_f32(*c))));
+        }
+        ImageBuffer::from_vec(width, height, rgb).expect("panorama buffer has the image size")
+    }
+
+    /// Makes `key` save a screenshot of the window into `directory`.
+    ///
+    /// Each press writes a PNG named after the current time
+    /// (`kiss3d-<seconds>-<millis>.png`), creating `directory` if needed. The
+    /// frame is read back without stalling the GPU ([`Self::snap_begin`]) and
+    /// written to disk one frame later, on a background thread. Saving files is
+    /// not supported on the web.
+    ///
+    /// Avoid mixing this with manual [`Self::snap_begin`] calls: both share the
+    /// single in-flight readback.
+    pub fn enable_screenshot_key<P: Into<PathBuf>>(&mut self, key: Key, directory: P) {
+        self.screenshot_key = Some((key, directory.into()));
+    }
+
+    /// Disables the screenshot key set with [`Self::enable_screenshot_key`].
+    pub fn disable_screenshot_key(&mut self) {
+        self.screenshot_key = None;
+        self.screenshot_requested = false;
+    }
+
+    /// The key and directory set with [`Self::enable_screenshot_key`], if any.
+    pub fn screenshot_key(&self) -> Option<(Key, &Path)> {
+        self.screenshot_key
+            .as_ref()
+            .map(|(key, dir)| (*key, dir.as_path()))
+    }
+
+    /// Saves the screenshot read back during the previous frame, and starts the
+    /// readback of the frame just rendered if the screenshot key was pressed.
+    pub(super) fn process_screenshot_key(&mut self) {
+        if self.screenshot_in_flight {
+            self.screenshot_in_flight = false;
+            if let (Some(image), Some((_, dir))) = (self.snap_finish(), &self.screenshot_key) {
+                save_screenshot(image, dir.clone());
+            }
+        }
+
+        if self.screenshot_requested {
+            self.screenshot_requested = false;
+            self.snap_begin();
+            self.screenshot_in_flight = true;
+        }
+    }
+}
+
+fn save_screenshot(image: ImageBuffer<Rgb<u8>, Vec<u8>>, dir: PathBuf) {
+    let now = web_time::SystemTime::now()
+        .duration_since(web_time::SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    let path = dir.join(format!(
+        "kiss3d-{}-{:03}.png",
+        now.as_secs(),
+        now.subsec_millis()
+    ));
+
+    let save = move || {
+        let result = std::fs::create_dir_all(&dir)
+            .map_err(image::ImageError::IoError)
+            .and_then(|_| image.save(&path));
+        match result {
+            Ok(()) => log::info!("Screenshot saved to {}", path.display()),
+            Err(e) => log::warn!("Failed to save screenshot {}: {}", path.display(), e),
+        }
+    };
+
+    // PNG encoding is slow enough to cause a visible hitch on large windows.
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = std::thread::spawn(save);
+    #[cfg(target_arch = "wasm32")]
+    save();
+}
+
+/// Decodes IEEE-754 half-precision bits.
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exp = ((bits >> 10) & 0x1f) as i32;
+    let mant = (bits & 0x3ff) as f32;
+    match exp {
+        0 => sign * mant * 2.0f32.powi(-24),
+        0x1f if mant == 0.0 => sign * f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => sign * (1.0 + mant / 1024.0) * 2.0f32.powi(exp - 15),
+    }
+}
+
+/// Encodes a linear color channel, clamped to `[0, 1]`, as an 8-bit sRGB value.
+fn linear_to_srgb(c: f32) -> u8 {
+    let c = if c.is_nan() { 0.0 } else { c.clamp(0.0, 1.0) };
+    let s = if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (s * 255.0 + 0.5) as u8
 }
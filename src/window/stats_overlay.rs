@@ -0,0 +1,222 @@
+//! The built-in FPS and render statistics overlay.
+
+use std::collections::VecDeque;
+
+use glamx::Vec2;
+
+use crate::camera::{Camera2d, CoordinateSystem2d, FixedView2d};
+use crate::color::Color;
+use crate::event::WindowEvent;
+use crate::renderer::PolylineRenderer2d;
+use crate::resource::RenderContext2dEncoder;
+use crate::text::{Font, TextRenderer};
+
+use super::Window;
+
+/// Number of frames shown by the frame-time graph.
+const HISTORY: usize = 120;
+/// Frame time, in milliseconds, of the graph's reference line (60 FPS).
+const REFERENCE_MS: f32 = 1000.0 / 60.0;
+
+/// The frame times shown by the stats overlay, and the renderers drawing it.
+///
+/// The overlay has its own renderers so it doesn't show up in the user's
+/// immediate-mode draws ([`Window::text_bounds_of_last_draws`],
+/// [`Window::pick_planar`]).
+pub(crate) struct StatsOverlay {
+    /// Wall-clock frame times of the last frames, in milliseconds, oldest first.
+    frame_times: VecDeque<f32>,
+    text: TextRenderer,
+    lines: PolylineRenderer2d,
+    /// Maps the pixels of the scene viewport to the lines, from its top-left corner.
+    camera: FixedView2d,
+}
+
+impl StatsOverlay {
+    fn new() -> Self {
+        StatsOverlay {
+            frame_times: VecDeque::with_capacity(HISTORY),
+            text: TextRenderer::new(),
+            lines: PolylineRenderer2d::new(),
+            camera: FixedView2d::new(CoordinateSystem2d::TopLeftDown, false),
+        }
+    }
+
+    /// Renders the frame-time graph into the scene's HDR film, over the 2D lines.
+    pub(super) fn render_lines(&mut self, context: &mut RenderContext2dEncoder) {
+        self.lines.render(&mut self.camera, context);
+    }
+
+    /// Renders the text into the final image of size `width × height`, over the
+    /// text of the window.
+    pub(super) fn render_text(
+        &mut self,
+        width: f32,
+        height: f32,
+        context: &mut RenderContext2dEncoder,
+    ) {
+        self.text.render(width, height, context);
+    }
+}
+
+impl Window {
+    /// Shows or hides an overlay with the FPS, a graph of the last frame times,
+    /// and the number of draw calls and triangles of the last frame, in the
+    /// top-left corner of the window.
+    ///
+    /// It is drawn in the top-left corner of the scene viewport with its own text
+    /// and 2D line renderers, so it doesn't need the `egui` feature and isn't part
+    /// of [`text_bounds_of_last_draws`](Self::text_bounds_of_last_draws) or
+    /// [`pick_planar`](Self::pick_planar). The numbers are those of
+    /// [`render_timings`](Self::render_timings).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let mut window = Window::new("Example").await;
+    /// # let mut camera = OrbitCamera3d::default();
+    /// # let mut scene = SceneNode3d::empty();
+    /// window.show_stats_overlay(true);
+    /// while window.render_3d(&mut scene, &mut camera).await {}
+    /// # }
+    /// ```
+    pub fn show_stats_overlay(&mut self, show: bool) {
+        if show != self.stats_overlay.is_some() {
+            self.stats_overlay = show.then(StatsOverlay::new);
+        }
+    }
+
+    /// Whether the stats overlay is shown (see
+    /// [`show_stats_overlay`](Self::show_stats_overlay)).
+    pub fn is_stats_overlay_shown(&self) -> bool {
+        self.stats_overlay.is_some()
+    }
+
+    /// Queues the stats overlay, if shown, for the frame being rendered.
+    pub(super) fn draw_stats_overlay(&mut self) {
+        let ui_scale = self.ui_scale_factor();
+        let (view_x, view_y, w, h) = self.scene_viewport();
+        let Some(overlay) = &mut self.stats_overlay else {
+            return;
+        };
+        let Some(timings) = &self.last_timings else {
+            return;
+        };
+
+        let frame_ms = timings.frame_wall.as_secs_f32() * 1000.0;
+        if overlay.frame_times.len() == HISTORY {
+            let _ = overlay.frame_times.pop_front();
+        }
+        overlay.frame_times.push_back(frame_ms);
+
+        let fps = if frame_ms > 0.0 {
+            1000.0 / frame_ms
+        } else {
+            0.0
+        };
+        let text = format!(
+            "{:.0} FPS ({:.2} ms)\n{} draw calls\n{} triangles",
+            fps, frame_ms, timings.draw_calls, timings.triangles
+        );
+        let frame_times: Vec<f32> = overlay.frame_times.iter().copied().collect();
+
+        overlay.text.set_size_scale(ui_scale);
+        overlay.lines.set_size_scale(ui_scale);
+        overlay
+            .camera
+            .handle_event(&self.canvas, &WindowEvent::FramebufferSize(w, h));
+
+        // The text is drawn in window pixels, the lines in scene viewport pixels.
+        let margin = 8.0 * ui_scale;
+        let font = Font::default();
+        let text_color = Color::new(1.0, 1.0, 1.0, 0.9);
+        let text_size = overlay.text.measure_text(&text, 24.0, &font);
+        let text_pos = Vec2::new(view_x as f32, view_y as f32) + margin;
+        overlay
+            .text
+            .draw_text(&text, text_pos, 24.0, &font, text_color);
+
+        // Frame-time graph below the text, scaled so the 60 FPS reference line sits
+        // at most halfway up.
+        let graph_min = Vec2::new(margin, margin * 2.0 + text_size.y);
+        let graph_size = Vec2::new(HISTORY as f32 * 2.0, 48.0) * ui_scale;
+        let graph_max = graph_min + graph_size;
+        let max_ms = frame_times
+            .iter()
+            .copied()
+            .fold(REFERENCE_MS * 2.0, f32::max);
+        let y_of = |ms: f32| graph_max.y - graph_size.y * (ms / max_ms).min(1.0);
+
+        let frame_color = Color::new(1.0, 1.0, 1.0, 0.4);
+        let corners = [
+            graph_min,
+            Vec2::new(graph_max.x, graph_min.y),
+            graph_max,
+            Vec2::new(graph_min.x, graph_max.y),
+        ];
+        for i in 0..4 {
+            let (a, b) = (corners[i], corners[(i + 1) % 4]);
+            overlay.lines.draw_line(a, b, frame_color, 1.0);
+        }
+        let reference_y = y_of(REFERENCE_MS);
+        overlay.lines.draw_line(
+            Vec2::new(graph_min.x, reference_y),
+            Vec2::new(graph_max.x, reference_y),
+            Color::new(0.3, 0.9, 0.3, 0.5),
+            1.0,
+        );
+
+        let step = graph_size.x / (HISTORY - 1) as f32;
+        let offset = HISTORY - frame_times.len();
+        for (i, pair) in frame_times.windows(2).enumerate() {
+            let x = graph_min.x + (offset + i) as f32 * step;
+            let color = if pair[1] > REFERENCE_MS * 1.5 {
+                Color::new(1.0, 0.4, 0.3, 1.0)
+            } else {
+                Color::new(1.0, 0.9, 0.3, 1.0)
+            };
+            overlay.lines.draw_line(
+                Vec2::new(x, y_of(pair[0])),
+                Vec2::new(x + step, y_of(pair[1])),
+                color,
+                1.5,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::camera::OrbitCamera3d;
+    use crate::scene::SceneNode3d;
+    use crate::window::OffscreenSurface;
+
+    #[test]
+    fn overlay_is_not_part_of_the_user_draws() {
+        crate::pollster::block_on(async {
+            if !crate::shader_validity::tests::adapter_available().await {
+                eprintln!("stats overlay test: no GPU adapter found, skipping");
+                return;
+            }
+            let mut surface = OffscreenSurface::new(320, 200).await;
+            let mut camera = OrbitCamera3d::default();
+            let mut scene = SceneNode3d::empty();
+            surface.window_mut().show_stats_overlay(true);
+            // The first frame has no timings to show yet.
+            surface.render_3d(&mut scene, &mut camera).await;
+            surface.render_3d(&mut scene, &mut camera).await;
+
+            let window = surface.window();
+            assert!(window.text_bounds_of_last_draws().is_empty());
+            assert_eq!(window.pick_planar(12.0, 12.0), None);
+            let image = surface.snap_image();
+            let overlay_pixels = image
+                .enumerate_pixels()
+                .filter(|(x, y, p)| *x < 160 && *y < 100 && p.0.iter().all(|c| *c > 128))
+                .count();
+            assert!(overlay_pixels > 0);
+        })
+    }
+}
@@ -0,0 +1,94 @@
+//! Automatic rotation of the camera around the scene, for showcase rendering.
+
+use crate::camera::Camera3d;
+use crate::event::{Action, MouseButton, WindowEvent};
+
+use super::Window;
+
+/// Seconds without user input after which the turntable resumes.
+const TURNTABLE_RESUME_DELAY: f32 = 3.0;
+
+/// The state of the turntable mode enabled by [`Window::set_turntable`].
+pub(super) struct Turntable {
+    /// Rotation speed, in degrees per second.
+    speed: f32,
+    /// Seconds elapsed since the last user input.
+    idle: f32,
+}
+
+impl Window {
+    /// Slowly orbits the camera around the point it looks at, at `speed`
+    /// degrees per second (negative to turn the other way), for product-style
+    /// showcases. `0.0` disables it.
+    ///
+    /// The user can still take over with the mouse, touch or keyboard: the
+    /// rotation pauses while they interact, and resumes after a few seconds
+    /// without input. Only orbiting cameras turn (see
+    /// [`Camera3d::orbit_around_up`]). Combined with
+    /// `begin_recording` (feature `recording`), this makes turntable
+    /// videos: a full turn at 30°/s takes 12 seconds.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let mut window = Window::new("Example").await;
+    /// let mut camera = OrbitCamera3d::default();
+    /// let mut scene = SceneNode3d::empty();
+    /// scene.add_cube(1.0, 1.0, 1.0);
+    /// window.set_turntable(30.0);
+    ///
+    /// while window.render_3d(&mut scene, &mut camera).await {}
+    /// # }
+    /// ```
+    pub fn set_turntable(&mut self, speed: f32) {
+        self.turntable = (speed != 0.0).then_some(Turntable {
+            speed,
+            idle: TURNTABLE_RESUME_DELAY,
+        });
+    }
+
+    /// The turntable speed in degrees per second, `0.0` if disabled (see
+    /// [`set_turntable`](Self::set_turntable)).
+    pub fn turntable(&self) -> f32 {
+        self.turntable.as_ref().map_or(0.0, |t| t.speed)
+    }
+
+    /// Pauses the turntable on the user input `event`.
+    pub(super) fn pause_turntable_on(&mut self, event: &WindowEvent) {
+        if self.turntable.is_none() {
+            return;
+        }
+        let input = match event {
+            WindowEvent::MouseButton(..)
+            | WindowEvent::Scroll(..)
+            | WindowEvent::Touch(..)
+            | WindowEvent::Key(..) => true,
+            // Dragging the camera.
+            WindowEvent::CursorPos(..) => [
+                MouseButton::Button1,
+                MouseButton::Button2,
+                MouseButton::Button3,
+            ]
+            .iter()
+            .any(|b| self.canvas.get_mouse_button(*b) == Action::Press),
+            _ => false,
+        };
+        if let (true, Some(turntable)) = (input, &mut self.turntable) {
+            turntable.idle = 0.0;
+        }
+    }
+
+    /// Turns `camera` by the rotation of the turntable over `delta` seconds,
+    /// unless the user interacted recently.
+    pub(super) fn advance_turntable(&mut self, camera: &mut dyn Camera3d, delta: f32) {
+        let Some(turntable) = &mut self.turntable else {
+            return;
+        };
+        if turntable.idle >= TURNTABLE_RESUME_DELAY {
+            camera.orbit_around_up((turntable.speed * delta).to_radians());
+        }
+        turntable.idle += delta;
+    }
+}
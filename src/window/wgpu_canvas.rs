@@ -3,10 +3,12 @@
 use std::cell::RefCell;
 use std::sync::mpsc::Sender;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::context::Context;
+use crate::error::Error;
 use crate::event::{Action, Key, Modifiers, MouseButton, TouchAction, WindowEvent};
-use crate::window::canvas::CanvasSetup;
+use crate::window::canvas::{CanvasSetup, MonitorInfo};
 use image::{GenericImage, Pixel};
 #[cfg(not(target_arch = "wasm32"))]
 use winit::application::ApplicationHandler;
@@ -18,7 +20,9 @@ use winit::event_loop::EventLoop;
 use winit::keyboard::ModifiersState;
 #[cfg(not(target_arch = "wasm32"))]
 use winit::keyboard::{KeyCode, PhysicalKey};
-use winit::window::{Icon, Window, WindowAttributes};
+#[cfg(not(target_arch = "wasm32"))]
+use winit::window::Icon;
+use winit::window::{Window, WindowAttributes};
 
 #[cfg(target_arch = "wasm32")]
 use std::rc::Rc;
@@ -28,6 +32,12 @@ use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use wgpu::ExperimentalFeatures;
 
+/// The default longest time between two presses making a double click.
+const DOUBLE_CLICK_THRESHOLD: Duration = Duration::from_millis(500);
+/// The largest cursor motion, in pixels along each axis, between two presses
+/// making a double click.
+const DOUBLE_CLICK_DISTANCE: f64 = 4.0;
+
 /// Computes the device features to request.
 ///
 /// Opts into wgpu's experimental ray query + acceleration-structure features
@@ -85,7 +95,14 @@ thread_local! {
     static EVENT_LOOP: RefCell<Option<EventLoop<()>>> = const { RefCell::new(None) };
     // Shared event storage for multi-window support. Events are stored per window_id
     // so each window can retrieve only its own events after pump_app_events runs.
-    static PENDING_WINDOW_EVENTS: RefCell<std::collections::HashMap<winit::window::WindowId, Vec<PendingEvent>>> = RefCell::new(std::collections::HashMap::new());
+    // Each event is stored with the instant it was received, which its timestamp is
+    // computed from.
+    static PENDING_WINDOW_EVENTS: RefCell<std::collections::HashMap<winit::window::WindowId, Vec<(web_time::Instant, PendingEvent)>>> = RefCell::new(std::collections::HashMap::new());
+    // Raw mouse motion, which is not tied to a window: it goes to the focused one.
+    static PENDING_MOUSE_MOTION: RefCell<Vec<(web_time::Instant, (f64, f64))>> = const { RefCell::new(Vec::new()) };
+    // Whether the application is suspended by the OS (e.g. in the background on
+    // mobile), which applies to all the windows.
+    static APP_SUSPENDED: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
 }
 
 /// Internal event type that stores both the event data and state updates needed.
@@ -98,6 +115,7 @@ enum PendingEvent {
     CursorPos(f64, f64),
     #[allow(dead_code)]
     Modifiers(ModifiersState),
+    Occluded(bool),
     Resize {
         width: u32,
         height: u32,
@@ -125,8 +143,31 @@ pub struct WgpuCanvas {
     cursor_pos: Option<(f64, f64)>,
     key_states: [Action; Key::Unknown as usize + 1],
     button_states: [Action; MouseButton::Button8 as usize + 1],
-    out_events: Sender<WindowEvent>,
+    out_events: Sender<(f64, WindowEvent)>,
+    /// The instant event timestamps are measured from.
+    clock_start: web_time::Instant,
+    /// The longest time between two presses making a double click.
+    double_click_threshold: Duration,
+    /// The button, timestamp and cursor position of the last press that may
+    /// start a double click.
+    last_click: Option<(MouseButton, f64, (f64, f64))>,
+    /// While set, live input is ignored: input events are dropped, and the key,
+    /// button and cursor states only follow the events of `replay_event`.
+    input_replay: bool,
+    /// The input events forwarded since the last `take_input_log`, while logging.
+    input_log: Option<Vec<WindowEvent>>,
     modifiers_state: ModifiersState,
+    /// Whether the window is fully hidden by other windows (as reported by the
+    /// platform; always `false` where occlusion isn't reported).
+    occluded: bool,
+    /// Whether the application is suspended, in which case the window has no
+    /// surface.
+    #[cfg(not(target_arch = "wasm32"))]
+    suspended: bool,
+    /// The scale factor last reported by `ScaleFactorChanged`, to detect changes.
+    scale_factor: f64,
+    /// Whether the surface composites with what is behind the window.
+    transparent: bool,
     depth_texture: wgpu::Texture,
     depth_view: wgpu::TextureView,
     /// Multisampling texture for MSAA (if enabled)
@@ -143,7 +184,11 @@ pub struct WgpuCanvas {
     snap_pending: RefCell<Option<PendingSnap>>,
     /// Pending events from web callbacks (WASM only)
     #[cfg(target_arch = "wasm32")]
-    pending_events: Rc<RefCell<Vec<WindowEvent>>>,
+    pending_events: Rc<RefCell<Vec<(web_time::Instant, WindowEvent)>>>,
+    /// Whether the cursor is grabbed, locking the pointer on the next click
+    /// (WASM only)
+    #[cfg(target_arch = "wasm32")]
+    pointer_lock: Rc<std::cell::Cell<bool>>,
     /// Keep closures alive (WASM only)
     #[cfg(target_arch = "wasm32")]
     _event_closures: Vec<wasm_bindgen::JsValue>,
@@ -151,28 +196,52 @@ pub struct WgpuCanvas {
 
 impl WgpuCanvas {
     /// Opens a new window and initializes the wgpu context.
+    ///
+    /// Panics if the window or the GPU context cannot be created; see
+    /// [`Self::try_open`] for the fallible version.
     pub async fn open(
         window_attrs: WindowAttributes,
         canvas_setup: Option<CanvasSetup>,
-        out_events: Sender<WindowEvent>,
+        out_events: Sender<(f64, WindowEvent)>,
     ) -> Self {
+        Self::try_open(window_attrs, canvas_setup, out_events)
+            .await
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Opens a new window and initializes the wgpu context, returning an error
+    /// instead of panicking if the window, surface, adapter or device cannot be
+    /// created.
+    pub async fn try_open(
+        window_attrs: WindowAttributes,
+        canvas_setup: Option<CanvasSetup>,
+        out_events: Sender<(f64, WindowEvent)>,
+    ) -> Result<Self, Error> {
         let canvas_setup = canvas_setup.unwrap_or_default();
+        let window_attrs = window_attrs.with_transparent(canvas_setup.transparent);
 
         // Create the window
         #[cfg(not(target_arch = "wasm32"))]
         let window = {
+            let window_attrs = if canvas_setup.always_on_top {
+                window_attrs.with_window_level(winit::window::WindowLevel::AlwaysOnTop)
+            } else {
+                window_attrs
+            };
+
             // Get or create the thread-local EventLoop (winit only allows one per program)
             EVENT_LOOP.with(|event_loop_cell| {
                 let mut event_loop_opt = event_loop_cell.borrow_mut();
                 if event_loop_opt.is_none() {
-                    *event_loop_opt = Some(EventLoop::new().expect("Failed to create event loop"));
+                    *event_loop_opt =
+                        Some(EventLoop::new().map_err(|e| Error::WindowCreation(e.to_string()))?);
                 }
                 let event_loop = event_loop_opt.as_ref().unwrap();
                 #[allow(deprecated)]
                 event_loop
                     .create_window(window_attrs)
-                    .expect("Failed to create window")
-            })
+                    .map_err(|e| Error::WindowCreation(e.to_string()))
+            })?
         };
 
         #[cfg(target_arch = "wasm32")]
@@ -181,7 +250,7 @@ impl WgpuCanvas {
             use winit::platform::web::WindowAttributesExtWebSys;
 
             // For WASM, we create a local EventLoop (single-threaded environment)
-            let events = EventLoop::new().expect("Failed to create event loop");
+            let events = EventLoop::new().map_err(|e| Error::WindowCreation(e.to_string()))?;
 
             let web_window = web_sys::window().expect("Failed to get web_sys window");
             let document = web_window.document().expect("Failed to get document");
@@ -227,12 +296,17 @@ impl WgpuCanvas {
                 let _ = style.set_property("overflow", "hidden");
             }
 
+            // Name the page after the window, unless it already has a title.
+            if document.title().is_empty() {
+                document.set_title(&window_attrs.title);
+            }
+
             let window_attrs = window_attrs.with_canvas(Some(canvas));
 
             #[allow(deprecated)]
             let window = events
                 .create_window(window_attrs)
-                .expect("Failed to create window");
+                .map_err(|e| Error::WindowCreation(e.to_string()))?;
 
             // Style the canvas AFTER winit creates the window (winit may overwrite styles)
             use winit::platform::web::WindowExtWebSys;
@@ -256,7 +330,7 @@ impl WgpuCanvas {
             let surface = ctxt
                 .instance
                 .create_surface(window.clone())
-                .expect("Failed to create surface");
+                .map_err(Error::SurfaceCreation)?;
 
             // Configure surface with existing device
             let surface_caps = surface.get_capabilities(&ctxt.adapter);
@@ -272,24 +346,24 @@ impl WgpuCanvas {
         } else {
             // First window - create the full wgpu context
             let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-                backends: wgpu::Backends::all(),
+                backends: canvas_setup.backends,
                 ..wgpu::InstanceDescriptor::new_without_display_handle()
             });
 
             // Create surface
             let surface = instance
                 .create_surface(window.clone())
-                .expect("Failed to create surface");
+                .map_err(Error::SurfaceCreation)?;
 
             // Request adapter (async on all platforms)
             let adapter = instance
                 .request_adapter(&wgpu::RequestAdapterOptions {
-                    power_preference: wgpu::PowerPreference::default(),
+                    power_preference: canvas_setup.power_preference,
                     compatible_surface: Some(&surface),
-                    force_fallback_adapter: false,
+                    force_fallback_adapter: canvas_setup.force_fallback_adapter,
                 })
                 .await
-                .expect("Failed to find an appropriate adapter");
+                .map_err(Error::AdapterRequest)?;
 
             // Request the adapter's full limits on every platform. The path tracer,
             // the shadow-mapped material, and the storage-backed point/wireframe
@@ -313,7 +387,7 @@ impl WgpuCanvas {
                     experimental_features: experimental_features(required_features),
                 })
                 .await
-                .expect("Failed to create device");
+                .map_err(Error::DeviceRequest)?;
 
             // Get surface capabilities
             // We explicitly prefer non-sRGB formats for consistent behavior across platforms.
@@ -330,6 +404,7 @@ impl WgpuCanvas {
 
             // Initialize the global context (only for first window)
             Context::init(instance, device, queue, adapter, surface_format);
+            Context::set_depth_format(canvas_setup.depth_format);
 
             (surface, surface_format)
         };
@@ -351,13 +426,29 @@ impl WgpuCanvas {
             wgpu::PresentMode::AutoNoVsync
         };
 
+        // A transparent window needs a compositing mode that honors the alpha
+        // channel; the default (usually `Opaque`) ignores it.
+        let alpha_mode = if canvas_setup.transparent {
+            [
+                wgpu::CompositeAlphaMode::PreMultiplied,
+                wgpu::CompositeAlphaMode::PostMultiplied,
+                wgpu::CompositeAlphaMode::Inherit,
+            ]
+            .iter()
+            .copied()
+            .find(|mode| surface_caps.alpha_modes.contains(mode))
+            .unwrap_or(surface_caps.alpha_modes[0])
+        } else {
+            surface_caps.alpha_modes[0]
+        };
+
         let surface_config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
             format: surface_format,
             width,
             height,
             present_mode,
-            alpha_mode: surface_caps.alpha_modes[0],
+            alpha_mode,
             view_formats: vec![],
             desired_maximum_frame_latency: 2,
         };
@@ -388,10 +479,11 @@ impl WgpuCanvas {
 
         // Set up WASM event listeners
         #[cfg(target_arch = "wasm32")]
-        let (pending_events, _event_closures) = {
+        let (pending_events, pointer_lock, _event_closures) = {
             use winit::platform::web::WindowExtWebSys;
 
             let pending_events = Rc::new(RefCell::new(Vec::new()));
+            let pointer_lock = Rc::new(std::cell::Cell::new(false));
             let mut closures: Vec<wasm_bindgen::JsValue> = Vec::new();
 
             if let Some(canvas) = window.canvas() {
@@ -412,11 +504,18 @@ impl WgpuCanvas {
                             let x = css_x * scale_x;
                             let y = css_y * scale_y;
 
-                            pending.borrow_mut().push(WindowEvent::CursorPos(
-                                x,
-                                y,
-                                Modifiers::empty(),
-                            ));
+                            push_web_event(
+                                &pending,
+                                WindowEvent::CursorPos(x, y, Modifiers::empty()),
+                            );
+                            // `movement_x/y` keep reporting motion under pointer lock.
+                            let (dx, dy) = (event.movement_x(), event.movement_y());
+                            if dx != 0 || dy != 0 {
+                                push_web_event(
+                                    &pending,
+                                    WindowEvent::MouseMotionDelta(dx as f64, dy as f64),
+                                );
+                            }
                         });
                     let _ = canvas.add_event_listener_with_callback(
                         "pointermove",
@@ -428,16 +527,26 @@ impl WgpuCanvas {
                 // Pointer down
                 {
                     let pending = pending_events.clone();
+                    let pointer_lock = pointer_lock.clone();
+                    let canvas_clone = canvas.clone();
                     let closure =
                         Closure::<dyn FnMut(_)>::new(move |event: web_sys::PointerEvent| {
+                            // Browsers only lock the pointer during a user gesture, so
+                            // a grab requested by `set_cursor_grab` takes effect on click.
+                            if pointer_lock.get() && !is_pointer_locked(&canvas_clone) {
+                                canvas_clone.request_pointer_lock();
+                            }
                             // Only handle mouse pointer type (not touch - that's handled separately)
                             if event.pointer_type() == "mouse" {
                                 let button = translate_web_mouse_button(event.button());
-                                pending.borrow_mut().push(WindowEvent::MouseButton(
-                                    button,
-                                    Action::Press,
-                                    Modifiers::empty(),
-                                ));
+                                push_web_event(
+                                    &pending,
+                                    WindowEvent::MouseButton(
+                                        button,
+                                        Action::Press,
+                                        Modifiers::empty(),
+                                    ),
+                                );
                             }
                         });
                     let _ = canvas.add_event_listener_with_callback(
@@ -455,11 +564,14 @@ impl WgpuCanvas {
                             // Only handle mouse pointer type (not touch - that's handled separately)
                             if event.pointer_type() == "mouse" {
                                 let button = translate_web_mouse_button(event.button());
-                                pending.borrow_mut().push(WindowEvent::MouseButton(
-                                    button,
-                                    Action::Release,
-                                    Modifiers::empty(),
-                                ));
+                                push_web_event(
+                                    &pending,
+                                    WindowEvent::MouseButton(
+                                        button,
+                                        Action::Release,
+                                        Modifiers::empty(),
+                                    ),
+                                );
                             }
                         });
                     let _ = canvas.add_event_listener_with_callback(
@@ -487,11 +599,10 @@ impl WgpuCanvas {
                             };
                             let dx = event.delta_x() * scale;
                             let dy = -event.delta_y() * scale; // Invert for natural scrolling
-                            pending.borrow_mut().push(WindowEvent::Scroll(
-                                dx,
-                                dy,
-                                Modifiers::empty(),
-                            ));
+                            push_web_event(
+                                &pending,
+                                WindowEvent::Scroll(dx, dy, Modifiers::empty()),
+                            );
                         });
                     let _ = canvas.add_event_listener_with_callback(
                         "wheel",
@@ -522,13 +633,16 @@ impl WgpuCanvas {
                             let touches = event.changed_touches();
                             for i in 0..touches.length() {
                                 if let Some(touch) = touches.get(i) {
-                                    pending.borrow_mut().push(WindowEvent::Touch(
-                                        touch.identifier() as u64,
-                                        touch.client_x() as f64,
-                                        touch.client_y() as f64,
-                                        TouchAction::Start,
-                                        Modifiers::empty(),
-                                    ));
+                                    push_web_event(
+                                        &pending,
+                                        WindowEvent::Touch(
+                                            touch.identifier() as u64,
+                                            touch.client_x() as f64,
+                                            touch.client_y() as f64,
+                                            TouchAction::Start,
+                                            Modifiers::empty(),
+                                        ),
+                                    );
                                 }
                             }
                         });
@@ -547,13 +661,16 @@ impl WgpuCanvas {
                             let touches = event.changed_touches();
                             for i in 0..touches.length() {
                                 if let Some(touch) = touches.get(i) {
-                                    pending.borrow_mut().push(WindowEvent::Touch(
-                                        touch.identifier() as u64,
-                                        touch.client_x() as f64,
-                                        touch.client_y() as f64,
-                                        TouchAction::Move,
-                                        Modifiers::empty(),
-                                    ));
+                                    push_web_event(
+                                        &pending,
+                                        WindowEvent::Touch(
+                                            touch.identifier() as u64,
+                                            touch.client_x() as f64,
+                                            touch.client_y() as f64,
+                                            TouchAction::Move,
+                                            Modifiers::empty(),
+                                        ),
+                                    );
                                 }
                             }
                         });
@@ -572,13 +689,16 @@ impl WgpuCanvas {
                             let touches = event.changed_touches();
                             for i in 0..touches.length() {
                                 if let Some(touch) = touches.get(i) {
-                                    pending.borrow_mut().push(WindowEvent::Touch(
-                                        touch.identifier() as u64,
-                                        touch.client_x() as f64,
-                                        touch.client_y() as f64,
-                                        TouchAction::End,
-                                        Modifiers::empty(),
-                                    ));
+                                    push_web_event(
+                                        &pending,
+                                        WindowEvent::Touch(
+                                            touch.identifier() as u64,
+                                            touch.client_x() as f64,
+                                            touch.client_y() as f64,
+                                            TouchAction::End,
+                                            Modifiers::empty(),
+                                        ),
+                                    );
                                 }
                             }
                         });
@@ -597,13 +717,16 @@ impl WgpuCanvas {
                             let touches = event.changed_touches();
                             for i in 0..touches.length() {
                                 if let Some(touch) = touches.get(i) {
-                                    pending.borrow_mut().push(WindowEvent::Touch(
-                                        touch.identifier() as u64,
-                                        touch.client_x() as f64,
-                                        touch.client_y() as f64,
-                                        TouchAction::Cancel,
-                                        Modifiers::empty(),
-                                    ));
+                                    push_web_event(
+                                        &pending,
+                                        WindowEvent::Touch(
+                                            touch.identifier() as u64,
+                                            touch.client_x() as f64,
+                                            touch.client_y() as f64,
+                                            TouchAction::Cancel,
+                                            Modifiers::empty(),
+                                        ),
+                                    );
                                 }
                             }
                         });
@@ -621,11 +744,10 @@ impl WgpuCanvas {
                 let pending = pending_events.clone();
                 let closure = Closure::<dyn FnMut(_)>::new(move |event: web_sys::KeyboardEvent| {
                     let key = translate_web_key(&event.code());
-                    pending.borrow_mut().push(WindowEvent::Key(
-                        key,
-                        Action::Press,
-                        Modifiers::empty(),
-                    ));
+                    push_web_event(
+                        &pending,
+                        WindowEvent::Key(key, Action::Press, Modifiers::empty()),
+                    );
                     // Emit a Char event for single-character (printable) keys so
                     // egui text fields receive text input. Skip when a command
                     // modifier is held so shortcuts (e.g. Ctrl+A) don't insert text,
@@ -633,7 +755,7 @@ impl WgpuCanvas {
                     let key_string = event.key();
                     if !event.ctrl_key() && !event.meta_key() && key_string.chars().count() == 1 {
                         if let Some(ch) = key_string.chars().next() {
-                            pending.borrow_mut().push(WindowEvent::Char(ch));
+                            push_web_event(&pending, WindowEvent::Char(ch));
                         }
                     }
                 });
@@ -646,24 +768,50 @@ impl WgpuCanvas {
                 let pending = pending_events.clone();
                 let closure = Closure::<dyn FnMut(_)>::new(move |event: web_sys::KeyboardEvent| {
                     let key = translate_web_key(&event.code());
-                    pending.borrow_mut().push(WindowEvent::Key(
-                        key,
-                        Action::Release,
-                        Modifiers::empty(),
-                    ));
+                    push_web_event(
+                        &pending,
+                        WindowEvent::Key(key, Action::Release, Modifiers::empty()),
+                    );
                 });
                 let _ = web_window
                     .add_event_listener_with_callback("keyup", closure.as_ref().unchecked_ref());
                 closures.push(closure.into_js_value());
             }
 
-            (pending_events, closures)
+            // Focus of the page, and its visibility (Page Visibility API).
+            for (name, focused) in [("focus", true), ("blur", false)] {
+                let pending = pending_events.clone();
+                let closure = Closure::<dyn FnMut(_)>::new(move |_: web_sys::Event| {
+                    push_web_event(&pending, WindowEvent::Focus(focused));
+                });
+                let _ = web_window
+                    .add_event_listener_with_callback(name, closure.as_ref().unchecked_ref());
+                closures.push(closure.into_js_value());
+            }
+            if let Some(document) = web_window.document() {
+                let pending = pending_events.clone();
+                let closure = Closure::<dyn FnMut(_)>::new(move |_: web_sys::Event| {
+                    let hidden = web_sys::window()
+                        .and_then(|w| w.document())
+                        .is_some_and(|d| d.hidden());
+                    push_web_event(&pending, WindowEvent::Visible(!hidden));
+                });
+                let _ = document.add_event_listener_with_callback(
+                    "visibilitychange",
+                    closure.as_ref().unchecked_ref(),
+                );
+                closures.push(closure.into_js_value());
+            }
+
+            (pending_events, pointer_lock, closures)
         };
 
         #[cfg(not(target_arch = "wasm32"))]
         let window_id = window.id();
 
-        WgpuCanvas {
+        let scale_factor = window.scale_factor();
+
+        Ok(WgpuCanvas {
             window: Some(window),
             #[cfg(not(target_arch = "wasm32"))]
             window_id: Some(window_id),
@@ -673,7 +821,17 @@ impl WgpuCanvas {
             key_states: [Action::Release; Key::Unknown as usize + 1],
             button_states: [Action::Release; MouseButton::Button8 as usize + 1],
             out_events,
+            clock_start: web_time::Instant::now(),
+            double_click_threshold: DOUBLE_CLICK_THRESHOLD,
+            last_click: None,
+            input_replay: false,
+            input_log: None,
             modifiers_state: ModifiersState::default(),
+            occluded: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            suspended: false,
+            scale_factor,
+            transparent: canvas_setup.transparent,
             depth_texture,
             depth_view,
             msaa_texture,
@@ -685,18 +843,36 @@ impl WgpuCanvas {
             #[cfg(target_arch = "wasm32")]
             pending_events,
             #[cfg(target_arch = "wasm32")]
+            pointer_lock,
+            #[cfg(target_arch = "wasm32")]
             _event_closures,
-        }
+        })
     }
 
     /// Opens a headless canvas: a wgpu context with no window and no surface,
     /// for off-screen rendering. Works without a display server.
+    ///
+    /// Panics if the GPU context cannot be created; see
+    /// [`Self::try_open_headless`] for the fallible version.
     pub async fn open_headless(
         width: u32,
         height: u32,
         canvas_setup: Option<CanvasSetup>,
-        out_events: Sender<WindowEvent>,
+        out_events: Sender<(f64, WindowEvent)>,
     ) -> Self {
+        Self::try_open_headless(width, height, canvas_setup, out_events)
+            .await
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Opens a headless canvas, returning an error instead of panicking if no
+    /// adapter or device can be created.
+    pub async fn try_open_headless(
+        width: u32,
+        height: u32,
+        canvas_setup: Option<CanvasSetup>,
+        out_events: Sender<(f64, WindowEvent)>,
+    ) -> Result<Self, Error> {
         let canvas_setup = canvas_setup.unwrap_or_default();
         let width = width.max(1);
         let height = height.max(1);
@@ -707,18 +883,18 @@ impl WgpuCanvas {
             Context::get().surface_format
         } else {
             let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-                backends: wgpu::Backends::all(),
+                backends: canvas_setup.backends,
                 ..wgpu::InstanceDescriptor::new_without_display_handle()
             });
 
             let adapter = instance
                 .request_adapter(&wgpu::RequestAdapterOptions {
-                    power_preference: wgpu::PowerPreference::default(),
+                    power_preference: canvas_setup.power_preference,
                     compatible_surface: None,
-                    force_fallback_adapter: false,
+                    force_fallback_adapter: canvas_setup.force_fallback_adapter,
                 })
                 .await
-                .expect("Failed to find an appropriate adapter");
+                .map_err(Error::AdapterRequest)?;
 
             let required_features = device_features(&adapter, canvas_setup.required_features);
             let (device, queue) = adapter
@@ -734,12 +910,13 @@ impl WgpuCanvas {
                     experimental_features: experimental_features(required_features),
                 })
                 .await
-                .expect("Failed to create device");
+                .map_err(Error::DeviceRequest)?;
 
             // No surface to query for a preferred format; pick a widely
             // supported non-sRGB format (gamma is handled in shaders).
             let surface_format = wgpu::TextureFormat::Rgba8Unorm;
             Context::init(instance, device, queue, adapter, surface_format);
+            Context::set_depth_format(canvas_setup.depth_format);
             surface_format
         };
 
@@ -775,7 +952,7 @@ impl WgpuCanvas {
         let readback_texture =
             Self::create_readback_texture(&ctxt.device, width, height, surface_format);
 
-        WgpuCanvas {
+        Ok(WgpuCanvas {
             window: None,
             #[cfg(not(target_arch = "wasm32"))]
             window_id: None,
@@ -785,7 +962,17 @@ impl WgpuCanvas {
             key_states: [Action::Release; Key::Unknown as usize + 1],
             button_states: [Action::Release; MouseButton::Button8 as usize + 1],
             out_events,
+            clock_start: web_time::Instant::now(),
+            double_click_threshold: DOUBLE_CLICK_THRESHOLD,
+            last_click: None,
+            input_replay: false,
+            input_log: None,
             modifiers_state: ModifiersState::default(),
+            occluded: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            suspended: false,
+            scale_factor: 1.0,
+            transparent: false,
             depth_texture,
             depth_view,
             msaa_texture,
@@ -797,8 +984,10 @@ impl WgpuCanvas {
             #[cfg(target_arch = "wasm32")]
             pending_events: Rc::new(RefCell::new(Vec::new())),
             #[cfg(target_arch = "wasm32")]
+            pointer_lock: Rc::new(std::cell::Cell::new(false)),
+            #[cfg(target_arch = "wasm32")]
             _event_closures: Vec::new(),
-        }
+        })
     }
 
     /// Resizes the canvas render targets.
@@ -906,7 +1095,7 @@ impl WgpuCanvas {
         }
     }
 
-    fn create_depth_texture(
+    pub(crate) fn create_depth_texture(
         device: &wgpu::Device,
         width: u32,
         height: u32,
@@ -989,6 +1178,21 @@ impl WgpuCanvas {
 
     /// Polls events from the window system.
     pub fn poll_events(&mut self) {
+        self.pump_events(Some(std::time::Duration::ZERO))
+    }
+
+    /// Waits until at least one window event arrives, or `timeout` elapses
+    /// (`None` waits indefinitely), then polls the events like
+    /// [`Self::poll_events`].
+    ///
+    /// On the web the browser owns the event loop and this cannot block: it only
+    /// polls.
+    pub fn wait_events(&mut self, timeout: Option<std::time::Duration>) {
+        self.pump_events(timeout)
+    }
+
+    #[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+    fn pump_events(&mut self, timeout: Option<std::time::Duration>) {
         // A headless canvas has no window and no event loop; nothing to poll.
         if self.window.is_none() {
             return;
@@ -1002,7 +1206,13 @@ impl WgpuCanvas {
             struct EventCollector;
 
             impl ApplicationHandler for EventCollector {
-                fn resumed(&mut self, _event_loop: &ActiveEventLoop) {}
+                fn resumed(&mut self, _event_loop: &ActiveEventLoop) {
+                    APP_SUSPENDED.with(|suspended| suspended.set(false));
+                }
+
+                fn suspended(&mut self, _event_loop: &ActiveEventLoop) {
+                    APP_SUSPENDED.with(|suspended| suspended.set(true));
+                }
 
                 fn window_event(
                     &mut self,
@@ -1093,22 +1303,48 @@ impl WgpuCanvas {
                         WinitWindowEvent::ModifiersChanged(new_modifiers) => {
                             vec![PendingEvent::Modifiers(new_modifiers.state())]
                         }
+                        WinitWindowEvent::Occluded(occluded) => {
+                            vec![
+                                PendingEvent::Occluded(occluded),
+                                PendingEvent::WindowEvent(WindowEvent::Visible(!occluded)),
+                            ]
+                        }
+                        WinitWindowEvent::Focused(focused) => {
+                            vec![PendingEvent::WindowEvent(WindowEvent::Focus(focused))]
+                        }
+                        WinitWindowEvent::Moved(position) => {
+                            vec![PendingEvent::WindowEvent(WindowEvent::Pos(
+                                position.x, position.y,
+                            ))]
+                        }
                         _ => vec![],
                     };
 
                     if !pending_events.is_empty() {
+                        let now = web_time::Instant::now();
                         PENDING_WINDOW_EVENTS.with(|storage| {
                             storage
                                 .borrow_mut()
                                 .entry(window_id)
                                 .or_default()
-                                .extend(pending_events);
+                                .extend(pending_events.into_iter().map(|event| (now, event)));
                         });
                     }
                 }
+
+                fn device_event(
+                    &mut self,
+                    _event_loop: &ActiveEventLoop,
+                    _device_id: winit::event::DeviceId,
+                    event: winit::event::DeviceEvent,
+                ) {
+                    if let winit::event::DeviceEvent::MouseMotion { delta } = event {
+                        let now = web_time::Instant::now();
+                        PENDING_MOUSE_MOTION.with(|motion| motion.borrow_mut().push((now, delta)));
+                    }
+                }
             }
 
-            let timeout = Some(std::time::Duration::ZERO);
             EVENT_LOOP.with(|event_loop_cell| {
                 if let Some(ref mut event_loop) = *event_loop_cell.borrow_mut() {
                     let mut collector = EventCollector;
@@ -1124,65 +1360,61 @@ impl WgpuCanvas {
                     .unwrap_or_default()
             });
 
-            for event in events {
+            // Device events are only listened to while a window of the
+            // application has focus, so the motion is left for that window.
+            let motion = if self.window.as_ref().is_some_and(|w| w.has_focus()) {
+                PENDING_MOUSE_MOTION.with(|motion| std::mem::take(&mut *motion.borrow_mut()))
+            } else {
+                Vec::new()
+            };
+
+            for (received, event) in events {
                 match event {
                     PendingEvent::WindowEvent(we) => {
-                        let _ = self.out_events.send(we);
+                        self.emit_event(we, received);
                     }
                     PendingEvent::ButtonState(button, action) => {
-                        self.button_states[button as usize] = action;
+                        if !self.input_replay {
+                            self.button_states[button as usize] = action;
+                        }
                     }
                     PendingEvent::KeyState(key, action) => {
-                        self.key_states[key as usize] = action;
+                        if !self.input_replay {
+                            self.key_states[key as usize] = action;
+                        }
                     }
                     PendingEvent::CursorPos(x, y) => {
-                        self.cursor_pos = Some((x, y));
+                        if !self.input_replay {
+                            self.cursor_pos = Some((x, y));
+                        }
                     }
                     PendingEvent::Modifiers(m) => {
                         self.modifiers_state = m;
                     }
+                    PendingEvent::Occluded(occluded) => {
+                        self.occluded = occluded;
+                    }
                     PendingEvent::Resize { width, height } => {
-                        let ctxt = Context::get();
-
-                        // Resize surface
-                        self.surface_config.width = width;
-                        self.surface_config.height = height;
-                        if let Some(surface) = &self.surface {
-                            surface.configure(&ctxt.device, &self.surface_config);
-                        }
+                        self.resize_surface(width, height);
+                    }
+                }
+            }
 
-                        // Recreate depth texture
-                        let (new_depth, new_depth_view) = Self::create_depth_texture(
-                            &ctxt.device,
-                            width,
-                            height,
-                            self.sample_count,
-                        );
-                        self.depth_texture = new_depth;
-                        self.depth_view = new_depth_view;
-
-                        // Recreate MSAA texture if needed
-                        if self.sample_count > 1 {
-                            let (new_msaa, new_msaa_view) = Self::create_msaa_texture(
-                                &ctxt.device,
-                                width,
-                                height,
-                                self.surface_config.format,
-                                self.sample_count,
-                            );
-                            self.msaa_texture = Some(new_msaa);
-                            self.msaa_view = Some(new_msaa_view);
-                        }
+            for (received, (dx, dy)) in motion {
+                self.emit_event(WindowEvent::MouseMotionDelta(dx, dy), received);
+            }
 
-                        // Recreate readback texture
-                        self.readback_texture = Self::create_readback_texture(
-                            &ctxt.device,
-                            width,
-                            height,
-                            self.surface_config.format,
-                        );
-                    }
+            // The surface of a suspended application is destroyed by the OS
+            // (on Android), so it is dropped and re-created on resume.
+            let suspended = APP_SUSPENDED.with(std::cell::Cell::get);
+            if suspended != self.suspended {
+                self.suspended = suspended;
+                if suspended {
+                    self.surface = None;
+                } else {
+                    let _ = self.recreate_surface();
                 }
+                self.emit_event(WindowEvent::Visible(!suspended), web_time::Instant::now());
             }
         }
 
@@ -1195,89 +1427,287 @@ impl WgpuCanvas {
                 && (current_size.width != self.surface_config.width
                     || current_size.height != self.surface_config.height)
             {
-                let ctxt = Context::get();
+                self.resize_surface(current_size.width, current_size.height);
+                self.send_event(
+                    WindowEvent::FramebufferSize(current_size.width, current_size.height),
+                    web_time::Instant::now(),
+                );
+            }
 
-                // Resize surface
-                self.surface_config.width = current_size.width;
-                self.surface_config.height = current_size.height;
-                if let Some(surface) = &self.surface {
-                    surface.configure(&ctxt.device, &self.surface_config);
+            // Process pending events from web callbacks
+            let events: Vec<_> = self.pending_events.borrow_mut().drain(..).collect();
+            for (received, event) in events {
+                if !self.input_replay {
+                    self.track_input_state(&event);
                 }
+                self.emit_event(event, received);
+            }
+        }
 
-                // Recreate depth texture
-                let (new_depth, new_depth_view) = Self::create_depth_texture(
-                    &ctxt.device,
-                    current_size.width,
-                    current_size.height,
-                    self.sample_count,
-                );
-                self.depth_texture = new_depth;
-                self.depth_view = new_depth_view;
-
-                // Recreate MSAA texture if needed
-                if self.sample_count > 1 {
-                    let (new_msaa, new_msaa_view) = Self::create_msaa_texture(
-                        &ctxt.device,
-                        current_size.width,
-                        current_size.height,
-                        self.surface_config.format,
-                        self.sample_count,
-                    );
-                    self.msaa_texture = Some(new_msaa);
-                    self.msaa_view = Some(new_msaa_view);
-                }
+        // Picks up winit's `ScaleFactorChanged`, which is not always followed by
+        // a `Resized` event.
+        self.check_scale_factor();
+    }
 
-                // Recreate readback texture
-                self.readback_texture = Self::create_readback_texture(
-                    &ctxt.device,
-                    current_size.width,
-                    current_size.height,
-                    self.surface_config.format,
-                );
+    /// Resizes the surface and the depth, MSAA and readback textures.
+    fn resize_surface(&mut self, width: u32, height: u32) {
+        let ctxt = Context::get();
+
+        // Resize surface
+        self.surface_config.width = width;
+        self.surface_config.height = height;
+        if let Some(surface) = &self.surface {
+            surface.configure(&ctxt.device, &self.surface_config);
+        }
+
+        // Recreate depth texture
+        let (new_depth, new_depth_view) =
+            Self::create_depth_texture(&ctxt.device, width, height, self.sample_count);
+        self.depth_texture = new_depth;
+        self.depth_view = new_depth_view;
+
+        // Recreate MSAA texture if needed
+        if self.sample_count > 1 {
+            let (new_msaa, new_msaa_view) = Self::create_msaa_texture(
+                &ctxt.device,
+                width,
+                height,
+                self.surface_config.format,
+                self.sample_count,
+            );
+            self.msaa_texture = Some(new_msaa);
+            self.msaa_view = Some(new_msaa_view);
+        }
+
+        // Recreate readback texture
+        self.readback_texture =
+            Self::create_readback_texture(&ctxt.device, width, height, self.surface_config.format);
+    }
+
+    /// Emits [`WindowEvent::ScaleFactorChanged`] when the scale factor changed
+    /// (e.g. the window moved to a monitor with another DPI, or the browser zoom
+    /// changed), and resizes the surface to the new physical size if the platform
+    /// didn't report a resize along with it.
+    fn check_scale_factor(&mut self) {
+        let Some(window) = &self.window else {
+            return;
+        };
+        let scale_factor = window.scale_factor();
+        if scale_factor == self.scale_factor {
+            return;
+        }
+        self.scale_factor = scale_factor;
+        let size = window.inner_size();
 
-                let _ = self.out_events.send(WindowEvent::FramebufferSize(
-                    current_size.width,
-                    current_size.height,
-                ));
+        let now = web_time::Instant::now();
+        self.send_event(WindowEvent::ScaleFactorChanged(scale_factor), now);
+        if size.width > 0
+            && size.height > 0
+            && (size.width != self.surface_config.width
+                || size.height != self.surface_config.height)
+        {
+            self.resize_surface(size.width, size.height);
+            self.send_event(WindowEvent::FramebufferSize(size.width, size.height), now);
+        }
+    }
+
+    /// Updates the key, button and cursor states from an input event.
+    #[cfg(any(feature = "replay", target_arch = "wasm32"))]
+    fn track_input_state(&mut self, event: &WindowEvent) {
+        match event {
+            WindowEvent::CursorPos(x, y, _) => {
+                self.cursor_pos = Some((*x, *y));
+            }
+            WindowEvent::MouseButton(button, action, _) => {
+                self.button_states[*button as usize] = *action;
             }
+            WindowEvent::Key(key, action, _) => {
+                self.key_states[*key as usize] = *action;
+            }
+            _ => {}
+        }
+    }
 
-            // Process pending events from web callbacks
-            let events: Vec<WindowEvent> = self.pending_events.borrow_mut().drain(..).collect();
-            for event in events {
-                match &event {
-                    WindowEvent::CursorPos(x, y, _) => {
-                        self.cursor_pos = Some((*x, *y));
-                    }
-                    WindowEvent::MouseButton(button, action, _) => {
-                        self.button_states[*button as usize] = *action;
-                    }
-                    WindowEvent::Key(key, action, _) => {
-                        self.key_states[*key as usize] = *action;
-                    }
-                    _ => {}
-                }
-                let _ = self.out_events.send(event);
+    /// Forwards `event`, received at `received`, to the window, unless it is live
+    /// input while replaying. Input events are logged while the input log is
+    /// enabled.
+    fn emit_event(&mut self, event: WindowEvent, received: web_time::Instant) {
+        if event.is_keyboard_event() || event.is_mouse_event() || event.is_touch_event() {
+            if self.input_replay {
+                return;
+            }
+            if let Some(log) = &mut self.input_log {
+                log.push(event);
+            }
+        }
+        self.send_event(event, received);
+    }
+
+    /// Sends `event` to the window, stamped with the time it was `received`,
+    /// followed by a `DoubleClick` if it is the second of two close presses of a
+    /// button.
+    fn send_event(&mut self, event: WindowEvent, received: web_time::Instant) {
+        let timestamp = received
+            .saturating_duration_since(self.clock_start)
+            .as_secs_f64();
+        let _ = self.out_events.send((timestamp, event));
+
+        if let WindowEvent::MouseButton(button, Action::Press, modifiers) = event {
+            let pos = self.cursor_pos.unwrap_or_default();
+            let threshold = self.double_click_threshold.as_secs_f64();
+            let is_double = matches!(
+                self.last_click,
+                Some((last_button, last_time, last_pos))
+                    if last_button == button
+                        && timestamp - last_time <= threshold
+                        && (pos.0 - last_pos.0).abs() <= DOUBLE_CLICK_DISTANCE
+                        && (pos.1 - last_pos.1).abs() <= DOUBLE_CLICK_DISTANCE
+            );
+            if is_double {
+                self.last_click = None;
+                let _ = self
+                    .out_events
+                    .send((timestamp, WindowEvent::DoubleClick(button, modifiers)));
+            } else {
+                self.last_click = Some((button, timestamp, pos));
             }
         }
     }
 
+    /// The instant event timestamps are measured from.
+    pub fn clock_start(&self) -> web_time::Instant {
+        self.clock_start
+    }
+
+    /// The longest time between two presses making a double click.
+    pub fn double_click_threshold(&self) -> Duration {
+        self.double_click_threshold
+    }
+
+    /// Sets the longest time between two presses making a double click.
+    pub fn set_double_click_threshold(&mut self, threshold: Duration) {
+        self.double_click_threshold = threshold;
+    }
+
+    /// Starts or stops ignoring live input in favor of [`Self::replay_event`].
+    /// The key and button states are reset either way.
+    #[cfg(feature = "replay")]
+    pub(crate) fn set_input_replay(&mut self, replay: bool) {
+        self.input_replay = replay;
+        self.key_states = [Action::Release; Key::Unknown as usize + 1];
+        self.button_states = [Action::Release; MouseButton::Button8 as usize + 1];
+    }
+
+    /// Forwards a replayed input event to the window as if it came from the
+    /// platform, updating the key, button and cursor states accordingly.
+    #[cfg(feature = "replay")]
+    pub(crate) fn replay_event(&mut self, event: WindowEvent) {
+        self.track_input_state(&event);
+        self.send_event(event, web_time::Instant::now());
+    }
+
+    /// Starts or stops logging the input events forwarded to the window.
+    #[cfg(feature = "replay")]
+    pub(crate) fn set_input_log(&mut self, enabled: bool) {
+        self.input_log = if enabled { Some(Vec::new()) } else { None };
+    }
+
+    /// The input events forwarded since the last call, while logging.
+    #[cfg(feature = "replay")]
+    pub(crate) fn take_input_log(&mut self) -> Vec<WindowEvent> {
+        self.input_log
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Whether the window was created with a transparent background.
+    pub fn is_transparent(&self) -> bool {
+        self.transparent
+    }
+
+    /// Whether the window is minimized, fully occluded, or suspended, in which
+    /// case nothing it renders can be seen.
+    ///
+    /// On the web, whether the page is hidden (e.g. in a background tab).
+    /// Always `false` for a headless canvas.
+    pub fn is_occluded(&self) -> bool {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.occluded
+                || self.suspended
+                || self
+                    .window
+                    .as_ref()
+                    .and_then(|w| w.is_minimized())
+                    .unwrap_or(false)
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.window.is_some()
+                && web_sys::window()
+                    .and_then(|w| w.document())
+                    .is_some_and(|d| d.hidden())
+        }
+    }
+
     /// Gets the current surface texture for rendering.
-    pub fn get_current_texture(&self) -> Option<wgpu::SurfaceTexture> {
+    ///
+    /// An outdated or lost surface is reconfigured, and a surface that stays
+    /// lost (e.g. after the window moved to another GPU) is re-created from the
+    /// window. Returns `None` if the GPU device itself is lost.
+    pub fn get_current_texture(&mut self) -> Option<wgpu::SurfaceTexture> {
+        let ctxt = Context::get();
+        if ctxt.is_device_lost() {
+            return None;
+        }
         let surface = self.surface.as_ref()?;
+        let lost = match surface.get_current_texture() {
+            wgpu::CurrentSurfaceTexture::Success(texture)
+            | wgpu::CurrentSurfaceTexture::Suboptimal(texture) => return Some(texture),
+            wgpu::CurrentSurfaceTexture::Outdated => false,
+            wgpu::CurrentSurfaceTexture::Lost => true,
+            _ => return None,
+        };
+
+        // Reconfigure and retry once
+        surface.configure(&ctxt.device, &self.surface_config);
         match surface.get_current_texture() {
+            wgpu::CurrentSurfaceTexture::Success(texture)
+            | wgpu::CurrentSurfaceTexture::Suboptimal(texture) => return Some(texture),
+            _ if !lost => return None,
+            _ => {}
+        }
+
+        // Still lost: replace the surface by a new one for the same window.
+        if !self.recreate_surface() {
+            return None;
+        }
+        log::warn!("the window surface was lost and has been re-created");
+        match self.surface.as_ref()?.get_current_texture() {
             wgpu::CurrentSurfaceTexture::Success(texture)
             | wgpu::CurrentSurfaceTexture::Suboptimal(texture) => Some(texture),
-            wgpu::CurrentSurfaceTexture::Outdated | wgpu::CurrentSurfaceTexture::Lost => {
-                // Reconfigure and retry once
-                let ctxt = Context::get();
+            _ => None,
+        }
+    }
+
+    /// Replaces the surface by a new one for the same window, configured like
+    /// the previous one. Returns `false` if it could not be created.
+    fn recreate_surface(&mut self) -> bool {
+        let Some(window) = self.window.clone() else {
+            return false;
+        };
+        let ctxt = Context::get();
+        match ctxt.instance.create_surface(window) {
+            Ok(surface) => {
                 surface.configure(&ctxt.device, &self.surface_config);
-                match surface.get_current_texture() {
-                    wgpu::CurrentSurfaceTexture::Success(texture)
-                    | wgpu::CurrentSurfaceTexture::Suboptimal(texture) => Some(texture),
-                    _ => None,
-                }
+                self.surface = Some(surface);
+                true
+            }
+            Err(e) => {
+                log::error!("could not re-create the window surface: {}", e);
+                false
             }
-            _ => None,
         }
     }
 
@@ -1495,6 +1925,12 @@ impl WgpuCanvas {
         &self.depth_view
     }
 
+    /// Creates a view of the depth texture to sample it in a shader (see
+    /// [`Context::depth_sample_view`]).
+    pub fn depth_sample_view(&self) -> wgpu::TextureView {
+        Context::depth_sample_view(&self.depth_texture)
+    }
+
     /// Gets the MSAA texture view if MSAA is enabled.
     pub fn msaa_view(&self) -> Option<&wgpu::TextureView> {
         self.msaa_view.as_ref()
@@ -1530,36 +1966,71 @@ impl WgpuCanvas {
             .map_or(1.0, |window| window.scale_factor())
     }
 
-    /// Set the window title.
+    /// Set the window title, and the page title on wasm.
     pub fn set_title(&mut self, title: &str) {
         if let Some(window) = &self.window {
             window.set_title(title);
         }
+
+        #[cfg(target_arch = "wasm32")]
+        if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+            document.set_title(title);
+        }
     }
 
-    /// Set the window icon.
+    /// Set the window icon, or the page favicon on wasm.
     pub fn set_icon(&mut self, icon: impl GenericImage<Pixel = impl Pixel<Subpixel = u8>>) {
         let (width, height) = icon.dimensions();
         let mut rgba = Vec::with_capacity((width * height) as usize * 4);
         for (_, _, pixel) in icon.pixels() {
             rgba.extend_from_slice(&pixel.to_rgba().0);
         }
-        let icon = Icon::from_rgba(rgba, width, height).unwrap();
-        if let Some(window) = &self.window {
-            window.set_window_icon(Some(icon));
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let icon = Icon::from_rgba(rgba, width, height).unwrap();
+            if let Some(window) = &self.window {
+                window.set_window_icon(Some(icon));
+            }
         }
+
+        #[cfg(target_arch = "wasm32")]
+        set_favicon(rgba, width, height);
     }
 
-    /// Set the cursor grabbing behaviour.
+    /// Set the cursor grabbing behaviour. On wasm, this locks the pointer (see
+    /// `Window::set_cursor_grab`).
     pub fn set_cursor_grab(&self, grab: bool) {
-        use winit::window::CursorGrabMode;
-        let mode = if grab {
-            CursorGrabMode::Confined
-        } else {
-            CursorGrabMode::None
-        };
-        if let Some(window) = &self.window {
-            let _ = window.set_cursor_grab(mode);
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            use winit::window::CursorGrabMode;
+            let mode = if grab {
+                CursorGrabMode::Confined
+            } else {
+                CursorGrabMode::None
+            };
+            if let Some(window) = &self.window {
+                let _ = window.set_cursor_grab(mode);
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            use winit::platform::web::WindowExtWebSys;
+
+            self.pointer_lock.set(grab);
+            let Some(canvas) = self.window.as_ref().and_then(|w| w.canvas()) else {
+                return;
+            };
+            if grab {
+                if !is_pointer_locked(&canvas) {
+                    canvas.request_pointer_lock();
+                }
+            } else if is_pointer_locked(&canvas) {
+                if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+                    document.exit_pointer_lock();
+                }
+            }
         }
     }
 
@@ -1574,6 +2045,15 @@ impl WgpuCanvas {
     pub fn hide_cursor(&self, hide: bool) {
         if let Some(window) = &self.window {
             window.set_cursor_visible(!hide);
+
+            #[cfg(target_arch = "wasm32")]
+            {
+                use winit::platform::web::WindowExtWebSys;
+                if let Some(canvas) = window.canvas() {
+                    let cursor = if hide { "none" } else { "auto" };
+                    let _ = canvas.style().set_property("cursor", cursor);
+                }
+            }
         }
     }
 
@@ -1591,6 +2071,105 @@ impl WgpuCanvas {
         }
     }
 
+    /// The position of the top-left corner of the window, decorations included.
+    pub fn outer_position(&self) -> Option<(i32, i32)> {
+        let position = self.window.as_ref()?.outer_position().ok()?;
+        Some((position.x, position.y))
+    }
+
+    /// Moves the top-left corner of the window, decorations included.
+    pub fn set_outer_position(&mut self, x: i32, y: i32) {
+        if let Some(window) = &self.window {
+            window.set_outer_position(winit::dpi::PhysicalPosition::new(x, y));
+        }
+    }
+
+    /// The monitors connected to the system.
+    pub fn monitors(&self) -> Vec<MonitorInfo> {
+        let Some(window) = &self.window else {
+            return Vec::new();
+        };
+        let primary = window.primary_monitor();
+        window
+            .available_monitors()
+            .map(|monitor| {
+                let position = monitor.position();
+                let size = monitor.size();
+                MonitorInfo {
+                    name: monitor.name(),
+                    position: (position.x, position.y),
+                    size: (size.width, size.height),
+                    scale_factor: monitor.scale_factor(),
+                    primary: primary.as_ref() == Some(&monitor),
+                }
+            })
+            .collect()
+    }
+
+    /// Centers the window on the `i`-th monitor of `monitors`. Returns `false` if
+    /// there is no such monitor.
+    pub fn move_to_monitor(&mut self, i: usize) -> bool {
+        let Some(window) = &self.window else {
+            return false;
+        };
+        let Some(monitor) = window.available_monitors().nth(i) else {
+            return false;
+        };
+        let origin = monitor.position();
+        let free = |monitor: u32, window: u32| (monitor.saturating_sub(window) / 2) as i32;
+        let (monitor_size, window_size) = (monitor.size(), window.outer_size());
+        window.set_outer_position(winit::dpi::PhysicalPosition::new(
+            origin.x + free(monitor_size.width, window_size.width),
+            origin.y + free(monitor_size.height, window_size.height),
+        ));
+        true
+    }
+
+    /// Whether the window, or the canvas on wasm, is fullscreen.
+    pub fn is_fullscreen(&self) -> bool {
+        #[cfg(not(target_arch = "wasm32"))]
+        return self
+            .window
+            .as_ref()
+            .is_some_and(|w| w.fullscreen().is_some());
+
+        #[cfg(target_arch = "wasm32")]
+        return web_sys::window()
+            .and_then(|w| w.document())
+            .and_then(|d| d.fullscreen_element())
+            .is_some();
+    }
+
+    /// Makes the window borderless fullscreen on its current monitor, or the
+    /// canvas fullscreen with the Fullscreen API on wasm.
+    pub fn set_fullscreen(&mut self, fullscreen: bool) {
+        let Some(window) = &self.window else {
+            return;
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        window.set_fullscreen(fullscreen.then_some(winit::window::Fullscreen::Borderless(None)));
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            use winit::platform::web::WindowExtWebSys;
+
+            if fullscreen == self.is_fullscreen() {
+                return;
+            }
+            if fullscreen {
+                let Some(canvas) = window.canvas() else {
+                    return;
+                };
+                if let Err(e) = canvas.request_fullscreen() {
+                    log::warn!("The browser refused to go fullscreen: {:?}", e);
+                }
+            } else if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+                document.exit_fullscreen();
+            }
+        }
+    }
+
     /// The state of a mouse button.
     pub fn get_mouse_button(&self, button: MouseButton) -> Action {
         self.button_states[button as usize]
@@ -1827,6 +2406,12 @@ fn translate_key(physical_key: PhysicalKey) -> Key {
     }
 }
 
+/// Queues an event received by a web callback, with the instant it arrived.
+#[cfg(target_arch = "wasm32")]
+fn push_web_event(pending: &RefCell<Vec<(web_time::Instant, WindowEvent)>>, event: WindowEvent) {
+    pending.borrow_mut().push((web_time::Instant::now(), event));
+}
+
 #[cfg(target_arch = "wasm32")]
 fn translate_web_mouse_button(button: i16) -> MouseButton {
     match button {
@@ -2023,3 +2608,69 @@ mod tests {
         assert_eq!(char_events(&press), vec!['z']);
     }
 }
+
+/// Whether the pointer is locked to `canvas` by the Pointer Lock API.
+#[cfg(target_arch = "wasm32")]
+fn is_pointer_locked(canvas: &web_sys::HtmlCanvasElement) -> bool {
+    let canvas: &web_sys::Element = canvas;
+    web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.pointer_lock_element())
+        .is_some_and(|e| e == *canvas)
+}
+
+/// Replaces the favicon of the page with an RGBA image, as a PNG data URL.
+#[cfg(target_arch = "wasm32")]
+fn set_favicon(rgba: Vec<u8>, width: u32, height: u32) {
+    let Some(image) = image::RgbaImage::from_raw(width, height, rgba) else {
+        return;
+    };
+    let mut png = std::io::Cursor::new(Vec::new());
+    if let Err(e) = image.write_to(&mut png, image::ImageFormat::Png) {
+        log::error!("Failed to encode the favicon: {}", e);
+        return;
+    }
+    let href = format!("data:image/png;base64,{}", base64(png.get_ref()));
+
+    let Some(document) = web_sys::window().and_then(|w| w.document()) else {
+        return;
+    };
+    let link = match document.query_selector("link[rel~='icon']") {
+        Ok(Some(link)) => link,
+        _ => {
+            let Ok(link) = document.create_element("link") else {
+                return;
+            };
+            let _ = link.set_attribute("rel", "icon");
+            if let Some(head) = document.head() {
+                let _ = head.append_child(&link);
+            }
+            link
+        }
+    };
+    let _ = link.set_attribute("type", "image/png");
+    let _ = link.set_attribute("href", &href);
+}
+
+/// Standard base64 encoding, with padding.
+#[cfg(target_arch = "wasm32")]
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[((n >> (18 - 6 * i)) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
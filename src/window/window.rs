@@ -1,7 +1,7 @@
 //! The kiss3d window.
 
 use std::cell::RefCell;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::mpsc::{self, Receiver};
 use std::sync::Arc;
@@ -10,12 +10,13 @@ use crate::builtin::ShadowMapper;
 use crate::camera::{Camera3d, FixedView2d};
 use crate::color::{Color, BLACK};
 use crate::context::Context;
+use crate::error::Error;
 use crate::event::{Key, Modifiers, WindowEvent};
 use crate::post_processing::{HdrPipeline, HdrSettings, Tonemap};
 use crate::renderer::timings::GpuTimer;
 use crate::renderer::{
-    PointRenderer2d, PointRenderer3d, PolylineRenderer2d, PolylineRenderer3d, RayTracer,
-    RenderTimings,
+    GridConfig, PointRenderer2d, PointRenderer3d, PolylineRenderer2d, PolylineRenderer3d,
+    RayTracer, ReferenceGrid, RenderTimings,
 };
 use crate::resource::{
     FramebufferManager, MaterialManager2d, MeshManager2d, RenderTarget, Texture, TextureManager,
@@ -23,8 +24,8 @@ use crate::resource::{
 use crate::scene::SceneNode3d;
 use crate::text::TextRenderer;
 use crate::window::canvas::CanvasSetup;
-use crate::window::{Canvas, NumSamples};
-use glamx::UVec2;
+use crate::window::{Canvas, MonitorInfo, NumSamples};
+use glamx::{UVec2, Vec3};
 use image::{GenericImage, Pixel};
 use winit::dpi::LogicalSize;
 use winit::window::WindowAttributes;
@@ -33,6 +34,9 @@ use winit::window::WindowAttributes;
 pub(super) use super::egui_integration::EguiContext;
 #[cfg(feature = "recording")]
 pub(super) use super::recording::RecordingState;
+#[cfg(feature = "replay")]
+pub(super) use super::replay::ReplayState;
+use super::turntable::Turntable;
 use super::window_cache::WindowCache;
 
 pub(super) static DEFAULT_WIDTH: u32 = 800u32;
@@ -45,14 +49,118 @@ pub(super) static DEFAULT_HEIGHT: u32 = 600u32;
 /// and fill (the atlas is `resolution² × MAX_SHADOW_VIEWS`).
 pub(super) static DEFAULT_SHADOW_RESOLUTION: u32 = 2048u32;
 
+/// Controls when a [`Window`] renders a new frame.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RedrawMode {
+    /// Every call to a `render_*` method draws a frame (the default, suited to
+    /// animations and games).
+    #[default]
+    Continuous,
+    /// A frame is only drawn when a window event arrived since the last frame,
+    /// or after [`Window::request_redraw`]. Otherwise the `render_*` call waits
+    /// for the next event without touching the GPU, so an idle tool doesn't
+    /// burn power re-drawing the same image. The wait lasts at most a few tens
+    /// of milliseconds: the call then returns without drawing, letting the
+    /// render loop check for changes the window can't see (new data, timers)
+    /// and call [`Window::request_redraw`].
+    OnDemand,
+}
+
+/// Anti-aliasing method of a [`Window`], chosen with [`Window::set_antialiasing`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Aa {
+    /// No anti-aliasing.
+    None,
+    /// Multisample anti-aliasing of the rasterized geometry, with the given
+    /// number of samples.
+    Msaa(NumSamples),
+    /// A [`Fxaa`](crate::post_processing::Fxaa) pass over the tonemapped image.
+    /// Much cheaper than MSAA (notably on WebGL) and smooths every edge, including
+    /// shading and texture aliasing, at the cost of slightly softer detail.
+    Fxaa,
+}
+
+/// How a [`Window`] draws translucent surfaces, chosen with
+/// [`Window::set_transparency_mode`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TransparencyMode {
+    /// Weighted-blended order-independent transparency (McGuire & Bavoil):
+    /// translucent surfaces are accumulated into separate targets and composited
+    /// over the opaque scene. Needs no sorting and stays stable with many
+    /// overlapping or interpenetrating surfaces (e.g. molecular surfaces), at the
+    /// cost of an approximate blending order.
+    #[default]
+    WeightedBlended,
+    /// Classic alpha blending: translucent surfaces are drawn with the opaque ones,
+    /// in the scene's draw order, and write depth. That order groups the objects by
+    /// material, pipeline and texture, and only keeps the scene-graph order among
+    /// objects sharing them. Exact for a single translucent layer but
+    /// order-dependent with overlapping surfaces. Skips the OIT passes.
+    AlphaBlended,
+}
+
+/// Multiplier applied by a [`Window`] to text scales, point sizes and line widths,
+/// chosen with [`Window::set_ui_scale`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UiScale {
+    /// Follow the display's scale factor (`2.0` on a typical high-DPI display), so
+    /// sizes are in logical rather than physical pixels.
+    Auto,
+    /// A fixed multiplier. `Factor(1.0)`, the default, keeps sizes in physical
+    /// pixels.
+    Factor(f32),
+}
+
+impl Default for UiScale {
+    fn default() -> Self {
+        UiScale::Factor(1.0)
+    }
+}
+
+/// What a [`Window`] clears at the start of each frame, chosen with
+/// [`Window::set_clear`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ClearMode {
+    /// Clear both the color (to the background color) and the depth buffer
+    /// (the default).
+    #[default]
+    Color,
+    /// Clear the depth buffer only: new geometry is drawn over the previous
+    /// frame's image, e.g. to accumulate motion trails.
+    DepthOnly,
+    /// Clear nothing: the previous frame's image and depth are kept.
+    None,
+}
+
+/// A compute dispatch queued with [`Window::dispatch_compute`].
+pub(super) struct ComputeDispatch {
+    pub(super) pipeline: wgpu::ComputePipeline,
+    pub(super) bind_groups: Vec<wgpu::BindGroup>,
+    pub(super) workgroups: [u32; 3],
+}
+
+/// A closure queued with [`Window::with_frame_encoder`].
+pub(super) type FrameEncoderHook = dyn FnOnce(
+    &mut wgpu::CommandEncoder,
+    &wgpu::TextureView,
+    &wgpu::TextureView,
+    &crate::resource::RenderContext,
+);
+
 /// Structure representing a window and a 3D scene.
 ///
 /// This is the main interface with the 3d engine.
 pub struct Window {
-    pub(super) events: Rc<Receiver<WindowEvent>>,
-    pub(super) unhandled_events: Rc<RefCell<Vec<WindowEvent>>>,
+    pub(super) events: Rc<Receiver<(f64, WindowEvent)>>,
+    pub(super) unhandled_events: Rc<RefCell<Vec<(f64, WindowEvent)>>>,
     pub(super) ambient_intensity: f32,
     pub(super) ambient_color: Color,
+    pub(super) ambient_ground_color: Option<Color>,
     pub(super) fog: crate::light::Fog,
     pub(super) background: Color,
     pub(super) polyline_renderer_2d: PolylineRenderer2d,
@@ -83,6 +191,9 @@ pub struct Window {
     pub(super) probe_capture: Option<crate::renderer::ProbeCapture>,
     /// Probe indices queued for a runtime scene capture next frame.
     pub(super) pending_probe_captures: Vec<usize>,
+    /// Cube maps queued by `capture_cubemap`, filled next frame from their
+    /// capture point.
+    pub(super) pending_cubemap_captures: Vec<(glamx::Vec3, Arc<crate::resource::Texture>)>,
     /// Render-layer mask used when capturing reflection probes. Defaults to all
     /// layers; set it to exclude dynamic objects (which SSR reflects more
     /// accurately) so a single-point probe doesn't distort nearby geometry.
@@ -113,6 +224,11 @@ pub struct Window {
     /// Renderer for auxiliary outputs (depth, normals, segmentation). Created
     /// on first use of an AOV-producing method.
     pub(super) aov_renderer: Option<crate::builtin::AovRenderer>,
+    /// Converts the last frame's depth buffer for `snap_depth_image` (created on
+    /// first use).
+    pub(super) depth_linearizer: Option<crate::builtin::DepthLinearizer>,
+    /// Projection of the primary pass of the last rendered frame.
+    pub(super) last_projection: glamx::Mat4,
     /// Whether the window is hidden. Hidden windows render offscreen.
     pub(super) hidden: bool,
     pub(super) should_close: bool,
@@ -132,6 +248,68 @@ pub struct Window {
     pub(super) gpu_timer: GpuTimer,
     #[cfg(feature = "egui")]
     pub(super) egui_context: EguiContext,
+    /// When frames are rendered; see [`RedrawMode`].
+    pub(super) redraw_mode: RedrawMode,
+    /// Set by [`Window::request_redraw`]; consumed by the next rendered frame.
+    pub(super) redraw_requested: bool,
+    /// Infinite ground grid drawn after the opaque pass, if enabled.
+    pub(super) reference_grid: Option<crate::renderer::ReferenceGrid>,
+    /// Projected decals drawn after the opaque pass, created on the first decal.
+    pub(super) decals: Option<crate::renderer::Decals>,
+    /// Secondary camera views drawn over the final image, created on the first
+    /// inset.
+    pub(super) inset_views: Option<crate::renderer::InsetViews>,
+    /// Built-in FXAA pass selected by [`Window::set_antialiasing`].
+    pub(super) fxaa: Option<crate::post_processing::Fxaa>,
+    /// Multiplier applied to immediate-mode text, point and line sizes.
+    pub(super) ui_scale: UiScale,
+    /// Fixed-aspect letterboxing applied after the post-processing effects.
+    pub(super) letterbox: Option<crate::post_processing::Letterbox>,
+    /// The depth buffer of the scene rendered at the size of the letterboxed
+    /// viewport, and its attachment view.
+    pub(super) letterbox_depth: Option<(wgpu::Texture, wgpu::TextureView)>,
+    /// Compute dispatches queued for the next frame (see `dispatch_compute`).
+    pub(super) compute_dispatches: Vec<ComputeDispatch>,
+    /// Key and output directory of the built-in screenshot shortcut.
+    pub(super) screenshot_key: Option<(Key, PathBuf)>,
+    /// The screenshot key was pressed since the last rendered frame.
+    pub(super) screenshot_requested: bool,
+    /// A screenshot readback started last frame awaits saving.
+    pub(super) screenshot_in_flight: bool,
+    /// What is cleared at the start of each frame.
+    pub(super) clear_mode: ClearMode,
+    /// Primitives drawn with the `draw_*_for` methods, with their expiry time.
+    pub(super) timed_draws: Vec<(web_time::Instant, super::drawing::TimedDraw)>,
+    /// Raw wgpu work queued with `with_frame_encoder` for the next frame.
+    pub(super) frame_encoder_hooks: Vec<Box<FrameEncoderHook>>,
+    /// When this window was created, the origin of `FrameTime::time`.
+    pub(super) clock_start: web_time::Instant,
+    /// Number of frames rendered so far.
+    pub(super) frame_count: u64,
+    /// The replay being recorded or played back.
+    #[cfg(feature = "replay")]
+    pub(super) replay: ReplayState,
+    /// The FPS and render statistics overlay, when shown.
+    pub(super) stats_overlay: Option<crate::window::stats_overlay::StatsOverlay>,
+    /// How translucent surfaces are drawn.
+    pub(super) transparency_mode: TransparencyMode,
+    /// The world position, in double precision, of the render-space origin.
+    pub(super) world_origin: glamx::glam::DVec3,
+    /// The world origin, scene and double-precision position edit count of the
+    /// last rebase, which is skipped while they stay the same.
+    pub(super) last_rebase: Option<(glamx::glam::DVec3, crate::scene::SceneNodeId, u64)>,
+    /// Whether the world origin follows the camera every frame.
+    pub(super) camera_relative: bool,
+    /// Callbacks registered with `on_frame`, run at the start of each frame.
+    pub(super) frame_callbacks: Vec<super::frame_callback::FrameCallback>,
+    /// Callbacks run once when the GPU device is lost.
+    pub(super) device_lost_callbacks: Vec<Box<dyn FnMut()>>,
+    /// Whether the loss of the device was already handled.
+    pub(super) device_lost_handled: bool,
+    /// The turntable mode, see `set_turntable`.
+    pub(super) turntable: Option<Turntable>,
+    pub(super) motion_blur: Option<crate::renderer::MotionBlur>,
+    pub(super) motion_blur_enabled: bool,
     pub(super) canvas: Canvas,
     #[cfg(feature = "recording")]
     pub(super) recording: Option<RecordingState>,
@@ -191,6 +369,111 @@ impl Window {
         self.canvas.set_samples(samples);
     }
 
+    /// Selects the anti-aliasing method. MSAA and FXAA are exclusive: choosing
+    /// one disables the other. The change takes effect on the next rendered frame.
+    ///
+    /// FXAA runs before any post-processing effect passed to the `render_*`
+    /// methods.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::window::{Aa, Window};
+    /// # async fn f(window: &mut Window) {
+    /// window.set_antialiasing(Aa::Fxaa);
+    /// # }
+    /// ```
+    pub fn set_antialiasing(&mut self, aa: Aa) {
+        match aa {
+            Aa::None => {
+                self.canvas.set_samples(NumSamples::One);
+                self.fxaa = None;
+            }
+            Aa::Msaa(samples) => {
+                self.canvas.set_samples(samples);
+                self.fxaa = None;
+            }
+            Aa::Fxaa => {
+                self.canvas.set_samples(NumSamples::One);
+                self.fxaa
+                    .get_or_insert_with(crate::post_processing::Fxaa::new);
+            }
+        }
+    }
+
+    /// The current anti-aliasing method.
+    pub fn antialiasing(&self) -> Aa {
+        if self.fxaa.is_some() {
+            Aa::Fxaa
+        } else {
+            match NumSamples::from_u32(self.canvas.sample_count()) {
+                Some(NumSamples::One) | None => Aa::None,
+                Some(samples) => Aa::Msaa(samples),
+            }
+        }
+    }
+
+    /// Sets how translucent surfaces (alpha < 1 with a blending
+    /// [`AlphaMode`](crate::scene::AlphaMode)) are drawn. Defaults to
+    /// [`TransparencyMode::WeightedBlended`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::window::{TransparencyMode, Window};
+    /// # async fn f(window: &mut Window) {
+    /// window.set_transparency_mode(TransparencyMode::AlphaBlended);
+    /// # }
+    /// ```
+    pub fn set_transparency_mode(&mut self, mode: TransparencyMode) {
+        self.transparency_mode = mode;
+    }
+
+    /// How translucent surfaces are drawn.
+    pub fn transparency_mode(&self) -> TransparencyMode {
+        self.transparency_mode
+    }
+
+    /// The live scene node identified by `id` (see [`SceneNode3d::id`]), or `None`
+    /// if it has been dropped. Equivalent to [`SceneNode3d::from_id`].
+    pub fn node(&self, id: crate::scene::SceneNodeId) -> Option<SceneNode3d> {
+        SceneNode3d::from_id(id)
+    }
+
+    /// Sets the multiplier applied to the sizes given to the immediate-mode drawing
+    /// methods: the `scale` of screen-space text, point sizes and (non-perspective)
+    /// line and polyline widths, in 2D and 3D. With [`UiScale::Auto`] the same code
+    /// looks the same size on standard and high-DPI displays.
+    ///
+    /// Applies to items drawn after the call. World-sized items (3D text labels,
+    /// perspective lines) and scene objects are unaffected.
+    pub fn set_ui_scale(&mut self, scale: UiScale) {
+        self.ui_scale = scale;
+        self.apply_ui_scale();
+    }
+
+    /// The UI scale mode set by [`set_ui_scale`](Self::set_ui_scale).
+    pub fn ui_scale(&self) -> UiScale {
+        self.ui_scale
+    }
+
+    /// The multiplier currently applied to text scales, point sizes and line widths.
+    pub fn ui_scale_factor(&self) -> f32 {
+        match self.ui_scale {
+            UiScale::Auto => self.canvas.scale_factor() as f32,
+            UiScale::Factor(factor) => factor,
+        }
+    }
+
+    /// Pushes the current UI scale factor to the immediate-mode renderers. Also
+    /// called every frame so [`UiScale::Auto`] follows scale-factor changes.
+    pub(super) fn apply_ui_scale(&mut self) {
+        let factor = self.ui_scale_factor();
+        self.point_renderer.set_size_scale(factor);
+        self.point_renderer_2d.set_size_scale(factor);
+        self.polyline_renderer.set_size_scale(factor);
+        self.polyline_renderer_2d.set_size_scale(factor);
+        self.text_renderer.set_size_scale(factor);
+    }
+
     /// Whether vsync is currently enabled (vsync is on by default).
     #[inline]
     pub fn vsync(&self) -> bool {
@@ -283,12 +566,42 @@ impl Window {
         raytracer: &mut RayTracer,
     ) -> bool {
         let mut default_cam2 = FixedView2d::default();
-        self.handle_events(camera, &mut default_cam2);
+        let handled_events = self.handle_events(camera, &mut default_cam2);
+        if !self.wait_for_redraw(handled_events).await {
+            return !self.should_close();
+        }
         self.raytrace_3d_frame(scene, camera, raytracer).await
     }
 
+    /// Sets when frames are rendered.
+    ///
+    /// With [`RedrawMode::OnDemand`], `render_*` calls only draw when an event
+    /// (input, resize, …) arrived or [`request_redraw`](Self::request_redraw) was
+    /// called; otherwise they wait for the next event and return `true` without
+    /// rendering. Regardless of the mode, a minimized or fully occluded window
+    /// skips rendering.
+    pub fn set_redraw_mode(&mut self, mode: RedrawMode) {
+        self.redraw_mode = mode;
+    }
+
+    /// The current redraw mode. See [`set_redraw_mode`](Self::set_redraw_mode).
+    pub fn redraw_mode(&self) -> RedrawMode {
+        self.redraw_mode
+    }
+
+    /// Forces the next `render_*` call to draw a frame, even in
+    /// [`RedrawMode::OnDemand`] with no pending event.
+    ///
+    /// Call this whenever the scene changed for a reason the window can't see
+    /// (an animation step, new data, …).
+    pub fn request_redraw(&mut self) {
+        self.redraw_requested = true;
+    }
+
     /// Sets the window title.
     ///
+    /// On wasm, this also sets the title of the page (`document.title`).
+    ///
     /// # Arguments
     /// * `title` - The new title for the window
     ///
@@ -305,7 +618,10 @@ impl Window {
         self.canvas.set_title(title)
     }
 
-    /// Set the window icon. On wasm this does nothing.
+    /// Set the window icon.
+    ///
+    /// On wasm, this replaces the favicon of the page (see
+    /// [`set_favicon`](Self::set_favicon)).
     ///
     /// ```no_run
     /// # use kiss3d::window::Window;
@@ -319,6 +635,25 @@ impl Window {
         self.canvas.set_icon(icon)
     }
 
+    /// Sets the favicon of the page on wasm, e.g. to brand a web deployment
+    /// without extra JavaScript. The image is embedded in the page as a PNG
+    /// data URL, replacing any existing icon link.
+    ///
+    /// On native platforms, this sets the window icon like
+    /// [`set_icon`](Self::set_icon).
+    ///
+    /// ```no_run
+    /// # use kiss3d::window::Window;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let mut window = Window::new("Example").await;
+    /// window.set_favicon(image::open("logo.png").unwrap());
+    /// # }
+    /// ```
+    pub fn set_favicon(&mut self, icon: impl GenericImage<Pixel = impl Pixel<Subpixel = u8>>) {
+        self.canvas.set_icon(icon)
+    }
+
     /// Sets the cursor grabbing behaviour.
     ///
     /// If cursor grabbing is enabled, the cursor is prevented from leaving the window.
@@ -327,7 +662,13 @@ impl Window {
     /// * `grab` - `true` to enable cursor grabbing, `false` to disable it
     ///
     /// # Platform-specific
-    /// Does nothing on web platforms.
+    /// On web platforms, grabbing locks the pointer with the Pointer Lock API,
+    /// hiding the cursor and keeping it in place. Browsers only allow this from
+    /// a user gesture, so the pointer is locked on the next click on the canvas
+    /// if it can't be right away. The mouse motion is then reported by
+    /// [`WindowEvent::MouseMotionDelta`](crate::event::WindowEvent::MouseMotionDelta)
+    /// events. Pressing Escape releases the lock; it is requested again on the
+    /// next click until grabbing is disabled.
     pub fn set_cursor_grab(&self, grab: bool) {
         self.canvas.set_cursor_grab(grab);
     }
@@ -379,6 +720,97 @@ impl Window {
         self.canvas.show()
     }
 
+    /// The position of the top-left corner of the window, decorations included,
+    /// in physical pixels on the desktop. `None` where the platform doesn't
+    /// report it (e.g. Wayland) and for headless windows.
+    pub fn outer_position(&self) -> Option<(i32, i32)> {
+        self.canvas.outer_position()
+    }
+
+    /// Moves the top-left corner of the window, decorations included, to `(x, y)`
+    /// in physical pixels on the desktop.
+    ///
+    /// The desktop spans all the monitors (see [`monitors()`](Self::monitors)),
+    /// so this can place the window on any of them. Some platforms (e.g. Wayland)
+    /// don't let applications position their windows.
+    pub fn set_outer_position(&mut self, x: i32, y: i32) {
+        self.canvas.set_outer_position(x, y)
+    }
+
+    /// The monitors connected to the system, with their position and size on the
+    /// desktop. Empty for headless windows.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::window::Window;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// let mut window = Window::new("Projector output").await;
+    /// // Show the window on the first secondary monitor, if any.
+    /// if let Some(i) = window.monitors().iter().position(|m| !m.primary) {
+    ///     window.move_to_monitor(i);
+    /// }
+    /// # }
+    /// ```
+    pub fn monitors(&self) -> Vec<MonitorInfo> {
+        self.canvas.monitors()
+    }
+
+    /// Centers the window on the `i`-th monitor of [`monitors()`](Self::monitors).
+    ///
+    /// Returns `false`, leaving the window in place, if there is no such
+    /// monitor.
+    pub fn move_to_monitor(&mut self, i: usize) -> bool {
+        self.canvas.move_to_monitor(i)
+    }
+
+    /// Whether the window is fullscreen (on the web, whether the canvas is).
+    pub fn is_fullscreen(&self) -> bool {
+        self.canvas.is_fullscreen()
+    }
+
+    /// Makes the window borderless fullscreen on its current monitor, or
+    /// restores it.
+    ///
+    /// The surface follows the new size, so the next frame reports a
+    /// [`WindowEvent::FramebufferSize`](crate::event::WindowEvent::FramebufferSize)
+    /// event, and a
+    /// [`WindowEvent::ScaleFactorChanged`](crate::event::WindowEvent::ScaleFactorChanged)
+    /// one if the monitor has another scale factor.
+    ///
+    /// # Platform-specific
+    /// On web platforms, the canvas goes fullscreen with the browser's Fullscreen
+    /// API. Browsers only allow it shortly after a user gesture, such as a key
+    /// press or a click, and leave fullscreen when Escape is pressed.
+    pub fn set_fullscreen(&mut self, fullscreen: bool) {
+        self.canvas.set_fullscreen(fullscreen)
+    }
+
+    /// Enters fullscreen if the window is windowed, and leaves it otherwise
+    /// (see [`set_fullscreen()`](Self::set_fullscreen)).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let mut window = Window::new("Example").await;
+    /// # let mut camera = OrbitCamera3d::default();
+    /// # let mut scene = SceneNode3d::empty();
+    /// while window.render_3d(&mut scene, &mut camera).await {
+    ///     for event in window.events().iter() {
+    ///         if let WindowEvent::Key(Key::F11, Action::Press, _) = event.value {
+    ///             window.toggle_fullscreen();
+    ///         }
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn toggle_fullscreen(&mut self) {
+        let fullscreen = self.is_fullscreen();
+        self.set_fullscreen(!fullscreen)
+    }
+
     /// Sets the background color for the window.
     ///
     /// # Arguments
@@ -401,6 +833,24 @@ impl Window {
         self.background = color;
     }
 
+    /// Sets what is cleared at the start of each frame.
+    ///
+    /// With [`ClearMode::DepthOnly`] or [`ClearMode::None`] the scene is drawn
+    /// over the previous frame, for accumulation or paint-over effects. The
+    /// previous image is the scene before post-processing, and is lost whenever
+    /// the window is resized. A skybox covers the whole image every frame, so it
+    /// defeats accumulation.
+    #[inline]
+    pub fn set_clear(&mut self, mode: ClearMode) {
+        self.clear_mode = mode;
+    }
+
+    /// What is cleared at the start of each frame (see [`Self::set_clear`]).
+    #[inline]
+    pub fn clear_mode(&self) -> ClearMode {
+        self.clear_mode
+    }
+
     /// Loads a texture from a file and returns a reference to it.
     ///
     /// The texture is managed by the global texture manager and will be reused
@@ -468,6 +918,36 @@ impl Window {
         self.ambient_color
     }
 
+    /// Turns the flat ambient term into a hemisphere light.
+    ///
+    /// With `Some(ground)`, surfaces facing straight up (along the
+    /// [world up axis](Self::set_world_up)) receive the
+    /// [ambient color](Self::set_ambient_color) (the "sky"), surfaces facing
+    /// straight down receive `ground`, and everything in between a blend of the
+    /// two — a cheap way to give unlit sides some shape. `None` (the default)
+    /// restores the flat ambient term. Ignored while image-based lighting is
+    /// active, which already provides directional ambient light.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::window::Window;
+    /// # use kiss3d::color::Color;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let mut window = Window::new("Example").await;
+    /// window.set_ambient_color(Color::new(0.6, 0.7, 1.0, 1.0));
+    /// window.set_ambient_ground_color(Some(Color::new(0.3, 0.25, 0.2, 1.0)));
+    /// # }
+    /// ```
+    pub fn set_ambient_ground_color(&mut self, ground: Option<Color>) {
+        self.ambient_ground_color = ground;
+    }
+
+    /// Returns the hemisphere ambient ground color, if set.
+    pub fn ambient_ground_color(&self) -> Option<Color> {
+        self.ambient_ground_color
+    }
+
     /// Sets the distance fog applied to the rasterized scene.
     ///
     /// Pass a [`Fog`](crate::light::Fog) describing the falloff curve and color,
@@ -537,6 +1017,14 @@ impl Window {
         self.skybox.set_image(image);
     }
 
+    /// Sets the rasterizer skybox from the six faces of a cubemap (`+X, -X, +Y,
+    /// -Y, +Z, -Z`, see [`Skybox::set_cubemap`](crate::renderer::Skybox::set_cubemap)).
+    /// Like the equirectangular variants, it also becomes the image-based
+    /// lighting and reflection source of reflective materials.
+    pub fn set_skybox_cubemap(&mut self, faces: &[image::DynamicImage; 6]) {
+        self.skybox.set_cubemap(faces);
+    }
+
     /// Sets the skybox Y-axis rotation (radians) and luminance multiplier.
     pub fn set_skybox_orientation(&mut self, rotation_radians: f32, intensity: f32) {
         self.skybox.set_orientation(rotation_radians, intensity);
@@ -552,6 +1040,298 @@ impl Window {
         self.skybox.is_set()
     }
 
+    /// Sets the up axis of the world, e.g. `Vec3::Z` for the Z-up convention of
+    /// robotics and CAD data, instead of rotating the whole scene.
+    ///
+    /// The orbit and first-person cameras created afterwards use it as their up
+    /// axis (existing ones can be updated with their `set_up_axis` method), the
+    /// [reference grid](Self::enable_reference_grid) lies perpendicular to it,
+    /// [`DragPlane::ground`](crate::camera::DragPlane::ground) drags along it, and
+    /// the hemisphere ambient light blends from its ground color along it.
+    /// The world up axis is shared by all the windows.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// let mut window = Window::new("Z-up").await;
+    /// window.set_world_up(Vec3::Z);
+    /// window.enable_reference_grid(GridConfig::default());
+    /// let mut camera = OrbitCamera3d::new(Vec3::new(5.0, 5.0, 3.0), Vec3::ZERO);
+    /// let mut scene = SceneNode3d::empty();
+    ///
+    /// while window.render_3d(&mut scene, &mut camera).await {}
+    /// # }
+    /// ```
+    pub fn set_world_up(&mut self, up: Vec3) {
+        let up = up.normalize_or_zero();
+        if up != Vec3::ZERO {
+            Context::set_world_up(up);
+        }
+    }
+
+    /// Sets the winding of the front faces of the objects that don't set their
+    /// own (see [`Object3d::set_front_face`](crate::scene::Object3d::set_front_face)):
+    /// counter-clockwise (`wgpu::FrontFace::Ccw`, the default) or clockwise.
+    ///
+    /// Backface culling removes the faces that aren't front faces, so meshes
+    /// from exporters using the other convention vanish or render inside-out.
+    /// Switch the convention here, or per object, to fix them without editing
+    /// the geometry.
+    pub fn set_default_winding(&mut self, winding: wgpu::FrontFace) {
+        Context::set_default_winding(winding);
+    }
+
+    /// The default winding of front faces (see
+    /// [`set_default_winding`](Self::set_default_winding)).
+    pub fn default_winding(&self) -> wgpu::FrontFace {
+        Context::default_winding()
+    }
+
+    /// The up axis of the world (see [`set_world_up`](Self::set_world_up)).
+    pub fn world_up(&self) -> Vec3 {
+        Context::world_up()
+    }
+
+    /// Enables an infinite reference grid on the ground plane.
+    ///
+    /// The grid is drawn by a dedicated full-screen shader after the opaque scene
+    /// (so objects occlude it) with minor/major lines, colored X/Z axes and a
+    /// distance fade, all configured by `config`. Calling this again replaces the
+    /// configuration.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::window::Window;
+    /// # use kiss3d::renderer::GridConfig;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let mut window = Window::new("Example").await;
+    /// window.enable_reference_grid(GridConfig {
+    ///     cell_size: 0.5,
+    ///     ..Default::default()
+    /// });
+    /// # }
+    /// ```
+    pub fn enable_reference_grid(&mut self, config: GridConfig) {
+        match &mut self.reference_grid {
+            Some(grid) => grid.set_config(config),
+            None => self.reference_grid = Some(ReferenceGrid::new(config)),
+        }
+    }
+
+    /// Disables the reference grid.
+    pub fn disable_reference_grid(&mut self) {
+        self.reference_grid = None;
+    }
+
+    /// The current reference grid configuration, or `None` if the grid is disabled.
+    pub fn reference_grid(&self) -> Option<&GridConfig> {
+        self.reference_grid.as_ref().map(|grid| grid.config())
+    }
+
+    /// Projects `texture` onto the scene geometry inside the box of dimensions
+    /// `size` centered on `pose`, and returns the decal's index.
+    ///
+    /// The texture is projected along the box's local `-Y` axis (see
+    /// [`Decal`](crate::renderer::Decal)) onto whatever opaque surfaces the depth
+    /// buffer holds there, so markers or footprints can be laid over arbitrary
+    /// terrain without editing its mesh or UVs. Use [`decal_mut`](Self::decal_mut)
+    /// to move or tint it afterwards.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::window::Window;
+    /// # use kiss3d::prelude::*;
+    /// # use std::path::Path;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let mut window = Window::new("Example").await;
+    /// let marker = window.add_texture(Path::new("assets/marker.png"), "marker");
+    /// window.add_decal(marker, Pose3::IDENTITY, Vec3::new(1.0, 0.5, 1.0));
+    /// # }
+    /// ```
+    pub fn add_decal(
+        &mut self,
+        texture: Arc<Texture>,
+        pose: glamx::Pose3,
+        size: glamx::Vec3,
+    ) -> usize {
+        self.decals
+            .get_or_insert_with(crate::renderer::Decals::new)
+            .add(crate::renderer::Decal::new(texture, pose, size))
+    }
+
+    /// Mutable access to decal `idx` (its pose, size, texture and tint).
+    pub fn decal_mut(&mut self, idx: usize) -> Option<&mut crate::renderer::Decal> {
+        self.decals.as_mut().and_then(|d| d.get_mut(idx))
+    }
+
+    /// Removes decal `idx`, returning it if it existed. The indices of the other
+    /// decals are unaffected.
+    pub fn remove_decal(&mut self, idx: usize) -> Option<crate::renderer::Decal> {
+        self.decals.as_mut().and_then(|d| d.remove(idx))
+    }
+
+    /// Removes every decal.
+    pub fn clear_decals(&mut self) {
+        if let Some(decals) = &mut self.decals {
+            decals.clear();
+        }
+    }
+
+    /// Renders the scene from `camera` into the rectangle `rect` of the window
+    /// each frame, on top of the main image, and returns the inset's index.
+    ///
+    /// `rect` is `(x, y, width, height)` as fractions of the window size from its
+    /// top-left corner, e.g. `(0.75, 0.0, 0.25, 0.25)` for the top-right corner.
+    /// Typical uses are top-down minimaps and checking another camera's framing.
+    /// The inset camera gets no input events; update it through
+    /// [`inset_view_mut`](Self::inset_view_mut), which also sets a border. See
+    /// [`InsetView`](crate::renderer::InsetView) for what insets render.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let mut window = Window::new("Example").await;
+    /// let top_down = OrbitCamera3d::new(Vec3::new(0.0, 20.0, 0.01), Vec3::ZERO);
+    /// let minimap = window.add_inset_view((0.75, 0.0, 0.25, 0.25), top_down);
+    /// window
+    ///     .inset_view_mut(minimap)
+    ///     .unwrap()
+    ///     .set_border(Some((WHITE, 2.0)));
+    /// # }
+    /// ```
+    pub fn add_inset_view<C: Camera3d + 'static>(
+        &mut self,
+        rect: (f32, f32, f32, f32),
+        camera: C,
+    ) -> usize {
+        self.inset_views
+            .get_or_insert_with(crate::renderer::InsetViews::new)
+            .add(crate::renderer::InsetView::new(rect, camera))
+    }
+
+    /// Mutable access to inset view `idx` (its camera, rectangle and border).
+    pub fn inset_view_mut(&mut self, idx: usize) -> Option<&mut crate::renderer::InsetView> {
+        self.inset_views.as_mut().and_then(|v| v.get_mut(idx))
+    }
+
+    /// Removes inset view `idx`, returning it if it existed. The indices of the
+    /// other inset views are unaffected.
+    pub fn remove_inset_view(&mut self, idx: usize) -> Option<crate::renderer::InsetView> {
+        self.inset_views.as_mut().and_then(|v| v.remove(idx))
+    }
+
+    /// Removes every inset view.
+    pub fn clear_inset_views(&mut self) {
+        self.inset_views = None;
+    }
+
+    /// Locks the rendered image to the aspect ratio `aspect` (width / height, e.g.
+    /// `16.0 / 9.0`), whatever the window's shape.
+    ///
+    /// The 3D and 2D scenes are rendered at the size of the largest centered
+    /// rectangle of that aspect ratio, and copied into it as a final
+    /// post-processing step, with bars of `bar_color` filling the rest of the
+    /// window. The cameras receive the size of that rectangle and cursor
+    /// positions relative to its top-left corner. Useful to record videos or
+    /// screenshots at an exact aspect ratio while the window is resized. Text
+    /// and UI overlays still cover the whole window.
+    pub fn set_fixed_aspect(&mut self, aspect: f32, bar_color: Color) {
+        match &mut self.letterbox {
+            Some(letterbox) => {
+                letterbox.set_aspect(aspect);
+                letterbox.set_color(bar_color);
+            }
+            None => {
+                self.letterbox = Some(crate::post_processing::Letterbox::new(aspect, bar_color))
+            }
+        }
+    }
+
+    /// Removes the fixed aspect ratio set by [`set_fixed_aspect`](Self::set_fixed_aspect),
+    /// so the image fills the window again.
+    pub fn clear_fixed_aspect(&mut self) {
+        self.letterbox = None;
+        self.letterbox_depth = None;
+    }
+
+    /// The fixed aspect ratio, if one is set.
+    pub fn fixed_aspect(&self) -> Option<f32> {
+        self.letterbox.as_ref().map(|l| l.aspect())
+    }
+
+    /// The rectangle `(x, y, width, height)` of the window in which the scene is
+    /// rendered, in physical pixels from its top-left corner: the letterboxed
+    /// viewport with a [fixed aspect ratio](Self::set_fixed_aspect), the whole
+    /// window otherwise.
+    pub(crate) fn scene_viewport(&self) -> (u32, u32, u32, u32) {
+        let (w, h) = (self.width(), self.height());
+        match &self.letterbox {
+            Some(letterbox) => {
+                let (x, y, vw, vh) = crate::post_processing::Letterbox::viewport(
+                    letterbox.aspect(),
+                    w as f32,
+                    h as f32,
+                );
+                (x as u32, y as u32, vw as u32, vh as u32)
+            }
+            None => (0, 0, w, h),
+        }
+    }
+
+    /// Queues a compute dispatch, run at the start of the next rendered frame
+    /// before anything of the scene is drawn. Like the `draw_*` methods, it is
+    /// dropped if the frame is skipped (see [`RedrawMode`]).
+    ///
+    /// `bind_groups[i]` is bound at group `i`, then `workgroups` (x, y, z) work
+    /// groups of `pipeline` are dispatched. Dispatches run in the order they were
+    /// queued, on the same device and encoder as the frame, so a compute shader
+    /// can write into a [`GPUVec`](crate::resource::GPUVec) (see
+    /// [`GPUVec::add_usage`](crate::resource::GPUVec::add_usage)) that the scene
+    /// then draws. See [`Context::create_compute_pipeline`] to build the pipeline.
+    pub fn dispatch_compute(
+        &mut self,
+        pipeline: &wgpu::ComputePipeline,
+        bind_groups: &[&wgpu::BindGroup],
+        workgroups: [u32; 3],
+    ) {
+        self.compute_dispatches.push(ComputeDispatch {
+            pipeline: pipeline.clone(),
+            bind_groups: bind_groups.iter().map(|g| (*g).clone()).collect(),
+            workgroups,
+        });
+    }
+
+    /// Runs `f` during the next rendered frame to record raw wgpu work into the
+    /// frame's command encoder, after the 3D scene (including its transparent
+    /// surfaces) and before the 2D scene, post-processing and text.
+    ///
+    /// `f` receives the encoder, the scene color and depth attachments, and a
+    /// [`RenderContext`](crate::resource::RenderContext) describing them: the
+    /// color attachment is the linear HDR film (`surface_format`), possibly
+    /// multisampled (`sample_count`), and the depth attachment has the format
+    /// [`Context::depth_format`]. Begin a render pass that loads both to draw
+    /// depth-tested geometry into the scene without writing a full
+    /// [`Renderer3d`](crate::renderer::Renderer3d). Like the `draw_*` methods,
+    /// this only applies to the next frame, and is dropped if that frame is
+    /// skipped; call it every frame to keep drawing.
+    pub fn with_frame_encoder<F>(&mut self, f: F)
+    where
+        F: FnOnce(
+                &mut wgpu::CommandEncoder,
+                &wgpu::TextureView,
+                &wgpu::TextureView,
+                &crate::resource::RenderContext,
+            ) + 'static,
+    {
+        self.frame_encoder_hooks.push(Box::new(f));
+    }
+
     /// Enables or disables screen-space ambient occlusion (SSAO).
     ///
     /// When enabled, a depth/view-position prepass plus a hemisphere-sampling
@@ -637,6 +1417,43 @@ impl Window {
         }
     }
 
+    /// Captures the scene, as seen from `position`, into a new cube-map texture
+    /// with `resolution × resolution` faces.
+    ///
+    /// The texture is returned right away but filled during the next frame (the
+    /// next [`render_3d`](Self::render_3d) call), before the main view is drawn.
+    /// Its view is a `texture_cube` of linear HDR (`Rgba16Float`) colors with the
+    /// six faces in the usual `+X, -X, +Y, -Y, +Z, -Z` layer order, e.g. for
+    /// custom reflection shaders or to export the environment map of a generated
+    /// scene. Like [reflection probe captures](Self::capture_reflection_probe),
+    /// it only renders the objects on the
+    /// [reflection capture layers](Self::set_reflection_capture_layers), with the
+    /// fixed-light path and without transparent surfaces.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use kiss3d::prelude::*;
+    /// # #[kiss3d::main]
+    /// # async fn main() {
+    /// # let mut window = Window::new("Example").await;
+    /// # let mut camera = OrbitCamera3d::default();
+    /// # let mut scene = SceneNode3d::empty();
+    /// let cubemap = window.capture_cubemap(Vec3::new(0.0, 1.0, 0.0), 512);
+    /// window.render_3d(&mut scene, &mut camera).await;
+    /// // `cubemap` now holds the six views from (0, 1, 0).
+    /// # }
+    /// ```
+    pub fn capture_cubemap(
+        &mut self,
+        position: glamx::Vec3,
+        resolution: u32,
+    ) -> Arc<crate::resource::Texture> {
+        let texture = crate::resource::Texture::new_cubemap_target(resolution.max(1));
+        self.pending_cubemap_captures
+            .push((position, texture.clone()));
+        texture
+    }
+
     /// Enables or disables screen-space reflections (SSR).
     ///
     /// When enabled (and supported by the backend — native/WebGPU; not WebGL2), a
@@ -676,6 +1493,34 @@ impl Window {
         self.dof_enabled
     }
 
+    /// Enables or disables motion blur.
+    ///
+    /// When enabled, the scene is rendered a second time into a velocity
+    /// G-buffer, from the previous-frame transform of each object and of the
+    /// camera (see [`AovKind::Velocity`](crate::builtin::AovKind::Velocity)), and
+    /// each pixel is blurred along its motion as controlled by
+    /// [`MotionBlurSettings`](crate::renderer::MotionBlurSettings). Runs before
+    /// DoF and tonemapping. This makes recordings of fast simulations look
+    /// smooth even at 30 frames per second. Instancing, skinning and morph
+    /// targets don't contribute to the velocity. Disabled by default.
+    pub fn set_motion_blur_enabled(&mut self, enabled: bool) {
+        self.motion_blur_enabled = enabled;
+    }
+
+    /// Whether motion blur is enabled.
+    pub fn motion_blur_enabled(&self) -> bool {
+        self.motion_blur_enabled
+    }
+
+    /// Mutable access to the motion blur settings, creating the motion blur
+    /// state if needed.
+    pub fn motion_blur_settings_mut(&mut self) -> &mut crate::renderer::MotionBlurSettings {
+        let (w, h) = self.canvas.size();
+        self.motion_blur
+            .get_or_insert_with(|| crate::renderer::MotionBlur::new(w, h))
+            .settings_mut()
+    }
+
     /// Enables or disables refractive transmission (glass).
     ///
     /// When enabled (the default), objects with a non-zero
@@ -712,6 +1557,25 @@ impl Window {
             .settings_mut()
     }
 
+    /// Enables depth of field focused `focal_distance` units in front of the
+    /// camera, with the given aperture (in f-stops: smaller values blur more).
+    /// The other [`DofSettings`](crate::renderer::DofSettings) are left as is.
+    pub fn set_dof_focus(&mut self, focal_distance: f32, aperture_f_stops: f32) {
+        let settings = self.dof_settings_mut();
+        settings.focal_distance = focal_distance.max(1.0e-4);
+        settings.aperture_f_stops = aperture_f_stops.max(1.0e-4);
+        self.dof_enabled = true;
+    }
+
+    /// Moves the depth-of-field focal plane through `point` as seen by `camera`,
+    /// keeping the current aperture, e.g. to keep the subject of a product shot
+    /// sharp while the camera moves. Does not enable DoF by itself.
+    pub fn focus_dof_on(&mut self, camera: &dyn Camera3d, point: glamx::Vec3) {
+        // The focal distance is a view-space depth (view space looks down -Z).
+        let depth = -camera.view_transform().to_mat4().transform_point3(point).z;
+        self.dof_settings_mut().focal_distance = depth.max(1.0e-4);
+    }
+
     /// Enables or disables real-time shadow mapping for the rasterizer.
     ///
     /// Shadows are enabled by default. When disabled, no shadow pre-pass runs and
@@ -958,6 +1822,59 @@ impl Window {
         Window::do_new_with_window_attributes(window_attrs, None).await
     }
 
+    /// Creates a new visible window with default settings, returning an error
+    /// instead of panicking if the window or its GPU context cannot be created.
+    ///
+    /// This is the fallible version of [`Window::new`]. Use it to report a
+    /// friendly message when no suitable GPU is available, or to retry with a
+    /// different [`CanvasSetup`] through [`Window::try_new_with_setup`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// use kiss3d::prelude::*;
+    ///
+    /// #[kiss3d::main]
+    /// async fn main() {
+    ///     let mut window = match Window::try_new("My Application").await {
+    ///         Ok(window) => window,
+    ///         Err(e) => {
+    ///             eprintln!("Could not open a window: {}", e);
+    ///             return;
+    ///         }
+    ///     };
+    ///     let mut camera = OrbitCamera3d::default();
+    ///     let mut scene = SceneNode3d::empty();
+    ///
+    ///     while window.render_3d(&mut scene, &mut camera).await {}
+    /// }
+    /// ```
+    pub async fn try_new(title: &str) -> Result<Window, Error> {
+        Window::try_do_new(title, false, DEFAULT_WIDTH, DEFAULT_HEIGHT, None).await
+    }
+
+    /// Creates a new window with custom setup options, returning an error
+    /// instead of panicking on failure.
+    ///
+    /// This is the fallible version of [`Window::new_with_setup`].
+    pub async fn try_new_with_setup(
+        title: &str,
+        width: u32,
+        height: u32,
+        setup: CanvasSetup,
+    ) -> Result<Window, Error> {
+        Window::try_do_new(title, false, width, height, Some(setup)).await
+    }
+
+    /// Creates a new window with custom attributes, returning an error instead
+    /// of panicking on failure.
+    ///
+    /// This is the fallible version of [`Window::new_with_window_attributes`].
+    pub async fn try_new_with_window_attributes(
+        window_attrs: WindowAttributes,
+    ) -> Result<Window, Error> {
+        Window::try_do_new_with_window_attributes(window_attrs, None).await
+    }
+
     // TODO: make this pub?
     async fn do_new(
         title: &str,
@@ -966,19 +1883,42 @@ impl Window {
         height: u32,
         setup: Option<CanvasSetup>,
     ) -> Window {
+        Self::try_do_new(title, hide, width, height, setup)
+            .await
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    async fn try_do_new(
+        title: &str,
+        hide: bool,
+        width: u32,
+        height: u32,
+        setup: Option<CanvasSetup>,
+    ) -> Result<Window, Error> {
         let window_attrs = WindowAttributes::default()
             .with_title(title)
             .with_inner_size(LogicalSize::new(width as f64, height as f64))
             .with_visible(!hide);
-        Self::do_new_with_window_attributes(window_attrs, setup).await
+        Self::try_do_new_with_window_attributes(window_attrs, setup).await
     }
+
     async fn do_new_with_window_attributes(
         window_attrs: WindowAttributes,
         setup: Option<CanvasSetup>,
     ) -> Window {
+        Self::try_do_new_with_window_attributes(window_attrs, setup)
+            .await
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    async fn try_do_new_with_window_attributes(
+        window_attrs: WindowAttributes,
+        setup: Option<CanvasSetup>,
+    ) -> Result<Window, Error> {
         let (event_send, event_receive) = mpsc::channel();
         let hide = !window_attrs.visible;
-        let canvas = Canvas::open(window_attrs, setup, event_send).await;
+        let canvas = Canvas::try_open(window_attrs, setup, event_send).await?;
+        let transparent = canvas.is_transparent();
         let (width, height) = canvas.size();
         // The HDR resolve pass tonemaps into the LDR swapchain. The rasterizer's
         // material pipelines are single-sampled, so the HDR film is too (see the
@@ -999,13 +1939,49 @@ impl Window {
             last_timings: None,
             last_frame_instant: None,
             gpu_timer: GpuTimer::new(),
+            redraw_mode: RedrawMode::Continuous,
+            redraw_requested: false,
+            reference_grid: None,
+            ambient_ground_color: None,
+            decals: None,
+            inset_views: None,
+            fxaa: None,
+            ui_scale: UiScale::default(),
+            letterbox: None,
+            letterbox_depth: None,
+            compute_dispatches: Vec::new(),
+            screenshot_key: None,
+            screenshot_requested: false,
+            screenshot_in_flight: false,
+            clear_mode: ClearMode::default(),
+            timed_draws: Vec::new(),
+            frame_encoder_hooks: Vec::new(),
+            clock_start: canvas.clock_start(),
+            frame_count: 0,
+            #[cfg(feature = "replay")]
+            replay: ReplayState::default(),
+            stats_overlay: None,
+            transparency_mode: TransparencyMode::default(),
+            world_origin: glamx::glam::DVec3::ZERO,
+            last_rebase: None,
+            camera_relative: false,
+            frame_callbacks: Vec::new(),
+            device_lost_callbacks: Vec::new(),
+            device_lost_handled: false,
+            turntable: None,
+            motion_blur: None,
+            motion_blur_enabled: false,
             canvas,
             events: Rc::new(event_receive),
             unhandled_events: Rc::new(RefCell::new(Vec::new())),
             ambient_intensity: 0.2,
             ambient_color: crate::color::WHITE,
             fog: crate::light::Fog::default(),
-            background: BLACK,
+            background: if transparent {
+                Color::new(0.0, 0.0, 0.0, 0.0)
+            } else {
+                BLACK
+            },
             polyline_renderer_2d: PolylineRenderer2d::new(),
             point_renderer_2d: PointRenderer2d::new(),
             point_renderer: PointRenderer3d::new(),
@@ -1021,6 +1997,7 @@ impl Window {
             reflection_probes: None,
             probe_capture: None,
             pending_probe_captures: Vec::new(),
+            pending_cubemap_captures: Vec::new(),
             reflection_capture_layers: u32::MAX,
             ssr: None,
             ssr_enabled: false,
@@ -1034,6 +2011,8 @@ impl Window {
                 .new_render_target(width, height, false),
             offscreen_output_target: None,
             aov_renderer: None,
+            depth_linearizer: None,
+            last_projection: glamx::Mat4::IDENTITY,
             hidden: hide,
             shadow_mapper: ShadowMapper::new(DEFAULT_SHADOW_RESOLUTION),
             framebuffer_manager,
@@ -1047,7 +2026,7 @@ impl Window {
             usr_window.canvas.hide()
         }
 
-        usr_window
+        Ok(usr_window)
     }
 
     /// Creates a headless window with custom setup options: a full-featured
@@ -1060,6 +2039,18 @@ impl Window {
         Self::do_new_headless(width, height, Some(setup)).await
     }
 
+    /// Creates a headless window with custom setup options, returning an error
+    /// instead of panicking if no GPU adapter or device can be created.
+    ///
+    /// This is the fallible version of [`Window::new_headless_with_setup`].
+    pub async fn try_new_headless_with_setup(
+        width: u32,
+        height: u32,
+        setup: CanvasSetup,
+    ) -> Result<Window, Error> {
+        Self::try_do_new_headless(width, height, Some(setup)).await
+    }
+
     /// Creates a headless window: a render target backed by no actual window,
     /// for off-screen rendering. Powers [`OffscreenSurface`](crate::window::OffscreenSurface).
     pub(super) async fn do_new_headless(
@@ -1067,8 +2058,18 @@ impl Window {
         height: u32,
         setup: Option<CanvasSetup>,
     ) -> Window {
+        Self::try_do_new_headless(width, height, setup)
+            .await
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    async fn try_do_new_headless(
+        width: u32,
+        height: u32,
+        setup: Option<CanvasSetup>,
+    ) -> Result<Window, Error> {
         let (event_send, event_receive) = mpsc::channel();
-        let canvas = Canvas::open_headless(width, height, setup, event_send).await;
+        let canvas = Canvas::try_open_headless(width, height, setup, event_send).await?;
         let (width, height) = canvas.size();
         // A headless surface is never multisampled.
         let canvas_surface_format = canvas.surface_format();
@@ -1077,7 +2078,7 @@ impl Window {
         WindowCache::populate();
 
         let framebuffer_manager = FramebufferManager::new();
-        Window {
+        Ok(Window {
             should_close: false,
             first_frame: true,
             close_key: None,
@@ -1085,6 +2086,38 @@ impl Window {
             last_timings: None,
             last_frame_instant: None,
             gpu_timer: GpuTimer::new(),
+            redraw_mode: RedrawMode::Continuous,
+            redraw_requested: false,
+            reference_grid: None,
+            ambient_ground_color: None,
+            decals: None,
+            inset_views: None,
+            fxaa: None,
+            ui_scale: UiScale::default(),
+            letterbox: None,
+            letterbox_depth: None,
+            compute_dispatches: Vec::new(),
+            screenshot_key: None,
+            screenshot_requested: false,
+            screenshot_in_flight: false,
+            clear_mode: ClearMode::default(),
+            timed_draws: Vec::new(),
+            frame_encoder_hooks: Vec::new(),
+            clock_start: canvas.clock_start(),
+            frame_count: 0,
+            #[cfg(feature = "replay")]
+            replay: ReplayState::default(),
+            stats_overlay: None,
+            transparency_mode: TransparencyMode::default(),
+            world_origin: glamx::glam::DVec3::ZERO,
+            last_rebase: None,
+            camera_relative: false,
+            frame_callbacks: Vec::new(),
+            device_lost_callbacks: Vec::new(),
+            device_lost_handled: false,
+            turntable: None,
+            motion_blur: None,
+            motion_blur_enabled: false,
             canvas,
             events: Rc::new(event_receive),
             unhandled_events: Rc::new(RefCell::new(Vec::new())),
@@ -1108,6 +2141,7 @@ impl Window {
             reflection_probes: None,
             probe_capture: None,
             pending_probe_captures: Vec::new(),
+            pending_cubemap_captures: Vec::new(),
             reflection_capture_layers: u32::MAX,
             ssr: None,
             ssr_enabled: false,
@@ -1121,6 +2155,8 @@ impl Window {
                 .new_render_target(width, height, false),
             offscreen_output_target: None,
             aov_renderer: None,
+            depth_linearizer: None,
+            last_projection: glamx::Mat4::IDENTITY,
             // A headless window has no surface; always render off-screen.
             hidden: true,
             shadow_mapper: ShadowMapper::new(DEFAULT_SHADOW_RESOLUTION),
@@ -1129,7 +2165,7 @@ impl Window {
             recording: None,
             #[cfg(feature = "rt_switcher")]
             raytracer: (None, false),
-        }
+        })
     }
 }
 
@@ -1144,12 +2180,18 @@ impl Drop for Window {
             // The order matters: clear caches first (which hold references to GPU resources),
             // then clear the Context (which holds the wgpu Device/Queue/Instance).
 
+            // Clear 2D resource managers. First, since they are created lazily
+            // and creating them needs the texture manager of the window cache.
+            MeshManager2d::reset_global_manager();
+            MaterialManager2d::reset_global_manager();
+
             // Clear 3D resource managers
             WindowCache::reset();
 
-            // Clear 2D resource managers
-            MeshManager2d::reset_global_manager();
-            MaterialManager2d::reset_global_manager();
+            // Clear the other thread-local GPU caches, so they are re-created
+            // with the next context (e.g. after a device loss).
+            crate::builtin::deform::reset_globals();
+            crate::resource::gpu_vector::reset_staged_uploads();
 
             // Finally, clear the wgpu context itself
             Context::reset();